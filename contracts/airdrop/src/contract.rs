@@ -5,13 +5,13 @@ use crate::error::ContractError;
 
 use crate::state::{
     read_claimed, read_config, read_expiry_at_seconds, read_latest_stage, read_merkle_root,
-    store_claimed, store_config, store_expiry_at_seconds, store_latest_stage, store_merkle_root,
-    Config,
+    read_stage_info, store_claimed, store_config, store_expiry_at_seconds, store_latest_stage,
+    store_merkle_root, store_stage_info, Config, StageInfo,
 };
 
 use glow_protocol::airdrop::{
     ConfigResponse, ExecuteMsg, ExpiryAtSecondsResponse, InstantiateMsg, IsClaimedResponse,
-    LatestStageResponse, MerkleRootResponse, MigrateMsg, QueryMsg,
+    LatestStageResponse, MerkleRootResponse, MigrateMsg, QueryMsg, StageResponse,
 };
 
 use glow_protocol::querier::query_token_balance;
@@ -37,6 +37,7 @@ pub fn instantiate(
         &Config {
             owner: deps.api.addr_canonicalize(&msg.owner)?,
             glow_token: deps.api.addr_canonicalize(&msg.glow_token)?,
+            community_contract: Some(deps.api.addr_canonicalize(&msg.community_contract)?),
         },
     )?;
 
@@ -54,19 +55,31 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::UpdateConfig { owner } => update_config(deps, info, owner),
+        ExecuteMsg::UpdateConfig {
+            owner,
+            community_contract,
+        } => update_config(deps, info, owner, community_contract),
         ExecuteMsg::WithdrawExpiredTokens { recipient } => {
             execute_withdraw_expired_tokens(deps, env, info, recipient)
         }
         ExecuteMsg::RegisterMerkleRoot {
             merkle_root,
             expiry_at_seconds,
-        } => register_merkle_root(deps, env, info, merkle_root, expiry_at_seconds),
+            total_amount,
+        } => register_merkle_root(
+            deps,
+            env,
+            info,
+            merkle_root,
+            expiry_at_seconds,
+            total_amount,
+        ),
         ExecuteMsg::Claim {
             stage,
             amount,
             proof,
         } => claim(deps, env, info, stage, amount, proof),
+        ExecuteMsg::SweepStage { stage } => sweep_stage(deps, env, stage),
     }
 }
 
@@ -74,6 +87,7 @@ pub fn update_config(
     deps: DepsMut,
     info: MessageInfo,
     owner: Option<String>,
+    community_contract: Option<String>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = read_config(deps.as_ref().storage)?;
     if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
@@ -84,6 +98,10 @@ pub fn update_config(
         config.owner = deps.api.addr_canonicalize(&owner)?;
     }
 
+    if let Some(community_contract) = community_contract {
+        config.community_contract = Some(deps.api.addr_canonicalize(&community_contract)?);
+    }
+
     store_config(deps.storage, &config)?;
 
     Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
@@ -148,6 +166,7 @@ pub fn register_merkle_root(
     info: MessageInfo,
     merkle_root: String,
     expiry_at_seconds: u64,
+    total_amount: Uint128,
 ) -> Result<Response, ContractError> {
     let config: Config = read_config(deps.as_ref().storage)?;
     if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
@@ -171,12 +190,22 @@ pub fn register_merkle_root(
     store_merkle_root(deps.storage, stage, merkle_root.to_string())?;
     store_latest_stage(deps.storage, stage)?;
     store_expiry_at_seconds(deps.storage, stage, expiry_at_seconds)?;
+    store_stage_info(
+        deps.storage,
+        stage,
+        &StageInfo {
+            total_amount,
+            claimed_amount: Uint128::zero(),
+            swept_amount: None,
+        },
+    )?;
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "register_merkle_root"),
         attr("stage", stage.to_string()),
         attr("merkle_root", merkle_root),
         attr("expiry_at_seconds", expiry_at_seconds.to_string()),
+        attr("total_amount", total_amount.to_string()),
     ]))
 }
 
@@ -238,6 +267,15 @@ pub fn claim(
     // Update claim index to the current stage
     store_claimed(deps.storage, &user_raw, stage)?;
 
+    // Track the claimed amount so the rest of the stage can be swept once expired
+    if let Some(mut stage_info) = read_stage_info(deps.storage, stage)? {
+        stage_info.claimed_amount += amount;
+        if stage_info.claimed_amount > stage_info.total_amount {
+            return Err(ContractError::StageAmountExceeded {});
+        }
+        store_stage_info(deps.storage, stage, &stage_info)?;
+    }
+
     Ok(Response::new()
         .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: deps.api.addr_humanize(&config.glow_token)?.to_string(),
@@ -255,6 +293,51 @@ pub fn claim(
         ]))
 }
 
+pub fn sweep_stage(deps: DepsMut, env: Env, stage: u8) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+
+    // Claims are still open until the expiry at seconds time
+    if read_expiry_at_seconds(deps.storage, stage)? > env.block.time.seconds() {
+        return Err(ContractError::AirdropNotExpired {});
+    }
+
+    let mut stage_info =
+        read_stage_info(deps.storage, stage)?.ok_or(ContractError::StageNotSweepable { stage })?;
+    if stage_info.swept_amount.is_some() {
+        return Err(ContractError::StageAlreadySwept { stage });
+    }
+
+    let community_contract = deps.api.addr_humanize(
+        &config
+            .community_contract
+            .ok_or(ContractError::CommunityContractNotSet {})?,
+    )?;
+
+    let swept_amount = stage_info.total_amount - stage_info.claimed_amount;
+    stage_info.swept_amount = Some(swept_amount);
+    store_stage_info(deps.storage, stage, &stage_info)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !swept_amount.is_zero() {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(&config.glow_token)?.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: community_contract.to_string(),
+                amount: swept_amount,
+            })?,
+        }));
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "sweep_stage"),
+        attr("stage", stage.to_string()),
+        attr("to", community_contract.to_string()),
+        attr("claimed_amount", stage_info.claimed_amount.to_string()),
+        attr("swept_amount", swept_amount.to_string()),
+    ]))
+}
+
 fn bytes_cmp(a: [u8; 32], b: [u8; 32]) -> std::cmp::Ordering {
     let mut i = 0;
     while i < 32 {
@@ -278,6 +361,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_binary(&query_is_claimed(deps, stage, address)?)
         }
         QueryMsg::ExpiryAtSeconds { stage } => to_binary(&query_expiry_at_seconds(deps, stage)?),
+        QueryMsg::Stage { stage } => to_binary(&query_stage(deps, stage)?),
     }
 }
 
@@ -286,6 +370,11 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let resp = ConfigResponse {
         owner: deps.api.addr_humanize(&state.owner)?.to_string(),
         glow_token: deps.api.addr_humanize(&state.glow_token)?.to_string(),
+        community_contract: state
+            .community_contract
+            .map(|addr| deps.api.addr_humanize(&addr))
+            .transpose()?
+            .map(|addr| addr.to_string()),
     };
 
     Ok(resp)
@@ -321,6 +410,24 @@ pub fn query_expiry_at_seconds(deps: Deps, stage: u8) -> StdResult<ExpiryAtSecon
     Ok(resp)
 }
 
+pub fn query_stage(deps: Deps, stage: u8) -> StdResult<StageResponse> {
+    let expiry_at_seconds = read_expiry_at_seconds(deps.storage, stage)?;
+    let stage_info = read_stage_info(deps.storage, stage)?.unwrap_or(StageInfo {
+        total_amount: Uint128::zero(),
+        claimed_amount: Uint128::zero(),
+        swept_amount: None,
+    });
+    let resp = StageResponse {
+        stage,
+        expiry_at_seconds,
+        total_amount: stage_info.total_amount,
+        claimed_amount: stage_info.claimed_amount,
+        swept_amount: stage_info.swept_amount,
+    };
+
+    Ok(resp)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     Ok(Response::default())
@@ -32,4 +32,16 @@ pub enum ContractError {
 
     #[error("InvalidExpiryAtSeconds")]
     InvalidExpiryAtSeconds {},
+
+    #[error("Claims exceed the total amount of the stage")]
+    StageAmountExceeded {},
+
+    #[error("Stage {stage} has no claim accounting and can't be swept")]
+    StageNotSweepable { stage: u8 },
+
+    #[error("Stage {stage} already swept")]
+    StageAlreadySwept { stage: u8 },
+
+    #[error("Community contract not set")]
+    CommunityContractNotSet {},
 }
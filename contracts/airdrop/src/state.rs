@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{CanonicalAddr, StdResult, Storage};
+use cosmwasm_std::{CanonicalAddr, StdResult, Storage, Uint128};
 use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
 
 static KEY_CONFIG: &[u8] = b"config";
@@ -10,11 +10,25 @@ static KEY_LATEST_STAGE: &[u8] = b"latest_stage";
 static PREFIX_MERKLE_ROOT: &[u8] = b"merkle_root";
 static PREFIX_CLAIM_INDEX: &[u8] = b"claim_index";
 static PREFIX_EXPIRY_AT_SECONDS: &[u8] = b"expiry_at_seconds";
+static PREFIX_STAGE_INFO: &[u8] = b"stage_info";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: CanonicalAddr,
     pub glow_token: CanonicalAddr,
+    /// Recipient of the unclaimed tokens of expired stages,
+    /// unset on contracts instantiated before stage sweeps
+    #[serde(default)]
+    pub community_contract: Option<CanonicalAddr>,
+}
+
+/// Claim accounting of a stage. Stages registered before
+/// stage sweeps don't have any
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StageInfo {
+    pub total_amount: Uint128,
+    pub claimed_amount: Uint128,
+    pub swept_amount: Option<Uint128>,
 }
 
 pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
@@ -78,3 +92,18 @@ pub fn read_expiry_at_seconds(storage: &dyn Storage, stage: u8) -> StdResult<u64
         ReadonlyBucket::new(storage, PREFIX_EXPIRY_AT_SECONDS);
     expiry_at_seconds_bucket.load(&[stage])
 }
+
+pub fn store_stage_info(
+    storage: &mut dyn Storage,
+    stage: u8,
+    stage_info: &StageInfo,
+) -> StdResult<()> {
+    let mut stage_info_bucket: Bucket<StageInfo> = Bucket::new(storage, PREFIX_STAGE_INFO);
+    stage_info_bucket.save(&[stage], stage_info)
+}
+
+pub fn read_stage_info(storage: &dyn Storage, stage: u8) -> StdResult<Option<StageInfo>> {
+    let stage_info_bucket: ReadonlyBucket<StageInfo> =
+        ReadonlyBucket::new(storage, PREFIX_STAGE_INFO);
+    stage_info_bucket.may_load(&[stage])
+}
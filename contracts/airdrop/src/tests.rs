@@ -8,7 +8,7 @@ use cosmwasm_std::{
 use cw20::Cw20ExecuteMsg;
 use glow_protocol::airdrop::{
     ConfigResponse, ExecuteMsg, InstantiateMsg, IsClaimedResponse, LatestStageResponse,
-    MerkleRootResponse, QueryMsg,
+    MerkleRootResponse, QueryMsg, StageResponse,
 };
 
 #[test]
@@ -18,6 +18,7 @@ fn proper_instantiation() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         glow_token: "glow0000".to_string(),
+        community_contract: "community0000".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -30,6 +31,7 @@ fn proper_instantiation() {
     let config: ConfigResponse = from_binary(&res).unwrap();
     assert_eq!("owner0000", config.owner.as_str());
     assert_eq!("glow0000", config.glow_token.as_str());
+    assert_eq!(Some("community0000".to_string()), config.community_contract);
 
     let res = query(deps.as_ref(), mock_env(), QueryMsg::LatestStage {}).unwrap();
     let latest_stage: LatestStageResponse = from_binary(&res).unwrap();
@@ -43,6 +45,7 @@ fn update_config() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         glow_token: "glow0000".to_string(),
+        community_contract: "community0000".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -52,6 +55,7 @@ fn update_config() {
     let info = mock_info("owner0000", &[]);
     let msg = ExecuteMsg::UpdateConfig {
         owner: Some("owner0001".to_string()),
+        community_contract: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -64,7 +68,10 @@ fn update_config() {
 
     // Unauthorzied err
     let info = mock_info("owner0000", &[]);
-    let msg = ExecuteMsg::UpdateConfig { owner: None };
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        community_contract: None,
+    };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
     match res {
@@ -85,6 +92,7 @@ fn register_merkle_root() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         glow_token: "glow0000".to_string(),
+        community_contract: "community0000".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -95,6 +103,7 @@ fn register_merkle_root() {
     let msg = ExecuteMsg::RegisterMerkleRoot {
         merkle_root: "634de21cde1044f41d90373733b0f0fb1c1c71f9652b905cdf159e73c4cf0d37".to_string(),
         expiry_at_seconds: seconds2,
+        total_amount: Uint128::new(10000000u128),
     };
 
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
@@ -107,7 +116,8 @@ fn register_merkle_root() {
                 "merkle_root",
                 "634de21cde1044f41d90373733b0f0fb1c1c71f9652b905cdf159e73c4cf0d37"
             ),
-            attr("expiry_at_seconds", seconds2.to_string())
+            attr("expiry_at_seconds", seconds2.to_string()),
+            attr("total_amount", "10000000")
         ]
     );
 
@@ -134,6 +144,7 @@ fn register_merkle_root() {
     let msg = ExecuteMsg::RegisterMerkleRoot {
         merkle_root: "634de21cde1044f41d90373733b0f0fb1c1c71f9652b905cdf159e73c4cf0d37".to_string(),
         expiry_at_seconds: seconds2,
+        total_amount: Uint128::new(10000000u128),
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg);
     match res {
@@ -148,6 +159,7 @@ fn register_merkle_root() {
     let msg = ExecuteMsg::RegisterMerkleRoot {
         merkle_root: "634de21cde1044f41d90373733b0f0fb1c1c71f9652b905cdf159e73c4cf0d37".to_string(),
         expiry_at_seconds: seconds1,
+        total_amount: Uint128::new(10000000u128),
     };
     let res = execute(deps.as_mut(), env, info, msg);
     match res {
@@ -172,6 +184,7 @@ fn claim() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         glow_token: "glow0000".to_string(),
+        community_contract: "community0000".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -182,6 +195,7 @@ fn claim() {
     let msg = ExecuteMsg::RegisterMerkleRoot {
         merkle_root: "85e33930e7a8f015316cb4a53a4c45d26a69f299fc4c83f17357e1fd62e8fd95".to_string(),
         expiry_at_seconds: seconds2,
+        total_amount: Uint128::new(10000000u128),
     };
     let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
@@ -189,6 +203,7 @@ fn claim() {
     let msg = ExecuteMsg::RegisterMerkleRoot {
         merkle_root: "634de21cde1044f41d90373733b0f0fb1c1c71f9652b905cdf159e73c4cf0d37".to_string(),
         expiry_at_seconds: seconds3,
+        total_amount: Uint128::new(10000000u128),
     };
     let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
@@ -196,6 +211,7 @@ fn claim() {
     let msg = ExecuteMsg::RegisterMerkleRoot {
         merkle_root: "634de21cde1044f41d90373733b0f0fb1c1c71f9652b905cdf159e73c4cf0d37".to_string(),
         expiry_at_seconds: seconds1,
+        total_amount: Uint128::new(10000000u128),
     };
     let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
@@ -344,6 +360,7 @@ fn withdraw_expired_tokens() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         glow_token: "glow0000".to_string(),
+        community_contract: "community0000".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -371,6 +388,7 @@ fn withdraw_expired_tokens() {
     let msg = ExecuteMsg::RegisterMerkleRoot {
         merkle_root: "634de21cde1044f41d90373733b0f0fb1c1c71f9652b905cdf159e73c4cf0d37".to_string(),
         expiry_at_seconds: seconds1,
+        total_amount: Uint128::new(10000000u128),
     };
 
     execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
@@ -412,3 +430,99 @@ fn withdraw_expired_tokens() {
         _ => panic!("Must return airdrop not expired error"),
     }
 }
+
+#[test]
+fn sweep_stage() {
+    let seconds0 = 1635255900;
+    let seconds1 = 1635256000;
+
+    let mut deps = mock_dependencies(&[]);
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(seconds0);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        glow_token: "glow0000".to_string(),
+        community_contract: "community0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::RegisterMerkleRoot {
+        merkle_root: "85e33930e7a8f015316cb4a53a4c45d26a69f299fc4c83f17357e1fd62e8fd95".to_string(),
+        expiry_at_seconds: seconds1,
+        total_amount: Uint128::new(3000000u128),
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let claim_msg = ExecuteMsg::Claim {
+        amount: Uint128::new(1000001u128),
+        stage: 1u8,
+        proof: vec![
+            "b8ee25ffbee5ee215c4ad992fe582f20175868bc310ad9b2b7bdf440a224b2df".to_string(),
+            "98d73e0a035f23c490fef5e307f6e74652b9d3688c2aa5bff70eaa65956a24e1".to_string(),
+            "f328b89c766a62b8f1c768fefa1139c9562c6e05bab57a2af87f35e83f9e9dcf".to_string(),
+            "fe19ca2434f87cadb0431311ac9a484792525eb66a952e257f68bf02b4561950".to_string(),
+        ],
+    };
+    let claimer = mock_info("terra1qfqa2eu9wp272ha93lj4yhcenrc6ymng079nu8", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), claimer, claim_msg).unwrap();
+
+    // Anyone can sweep, but only once the stage expired
+    let info = mock_info("addr0001", &[]);
+    let msg = ExecuteMsg::SweepStage { stage: 1 };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    match res {
+        Err(ContractError::AirdropNotExpired {}) => {}
+        _ => panic!("Must return airdrop not expired error"),
+    }
+
+    env.block.time = Timestamp::from_seconds(seconds1);
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "glow0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "community0000".to_string(),
+                amount: Uint128::new(1999999u128),
+            })
+            .unwrap(),
+            funds: vec![]
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "sweep_stage"),
+            attr("stage", "1"),
+            attr("to", "community0000"),
+            attr("claimed_amount", "1000001"),
+            attr("swept_amount", "1999999"),
+        ]
+    );
+
+    let stage: StageResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Stage { stage: 1 }).unwrap())
+            .unwrap();
+    assert_eq!(
+        stage,
+        StageResponse {
+            stage: 1,
+            expiry_at_seconds: seconds1,
+            total_amount: Uint128::new(3000000u128),
+            claimed_amount: Uint128::new(1000001u128),
+            swept_amount: Some(Uint128::new(1999999u128)),
+        }
+    );
+
+    // A stage is only swept once
+    let res = execute(deps.as_mut(), env, info, msg);
+    match res {
+        Err(ContractError::StageAlreadySwept { stage: 1 }) => {}
+        _ => panic!("Must return stage already swept error"),
+    }
+}
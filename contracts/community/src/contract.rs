@@ -1,14 +1,18 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
-use crate::state::{read_config, read_old_config, store_config, Config};
+use crate::state::{
+    read_config, read_old_config, read_spend_whitelist, store_config, store_spend_whitelist, Config,
+};
 
 use cosmwasm_std::{
     attr, to_binary, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
     StdError, StdResult, Uint128, WasmMsg,
 };
 
-use glow_protocol::community::{ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use glow_protocol::community::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SpendWhitelistResponse,
+};
 
 use cosmwasm_bignumber::Decimal256;
 use cw20::Cw20ExecuteMsg;
@@ -58,6 +62,10 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
         ExecuteMsg::WithdrawSponsor {} => withdraw_sponsor(deps, info),
         ExecuteMsg::Swap { amount } => execute_swap(deps, info, env, amount),
         ExecuteMsg::Burn { amount } => execute_burn(deps, info, amount),
+        ExecuteMsg::UpdateSpendWhitelist { whitelist } => {
+            update_spend_whitelist(deps, info, whitelist)
+        }
+        ExecuteMsg::WhitelistedSpend { amount } => whitelisted_spend(deps, info, amount),
     }
 }
 
@@ -167,7 +175,7 @@ pub fn sponsor_lotto(
     info: MessageInfo,
     amount: Uint128,
     award: Option<bool>,
-    prize_distribution: Option<[Decimal256; 7]>,
+    prize_distribution: Option<Vec<Decimal256>>,
 ) -> StdResult<Response> {
     let config: Config = read_config(deps.storage)?;
     if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
@@ -186,6 +194,8 @@ pub fn sponsor_lotto(
             msg: to_binary(&LottoMsg::Sponsor {
                 award,
                 prize_distribution,
+                prize_template: None,
+                lock_duration: None,
             })?,
         })])
         .add_attributes(vec![
@@ -316,13 +326,73 @@ pub fn execute_burn(deps: DepsMut, info: MessageInfo, amount: Uint128) -> StdRes
         .add_attributes(vec![("action", "burn"), ("amount", &amount.to_string())]))
 }
 
+/// Update Spend Whitelist
+/// Owner (governance contract) can set the contracts allowed to request
+/// stable spends, like the lotto contract topping up its jackpot
+pub fn update_spend_whitelist(
+    deps: DepsMut,
+    info: MessageInfo,
+    whitelist: Vec<String>,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    let whitelist = whitelist
+        .iter()
+        .map(|addr| deps.api.addr_canonicalize(addr))
+        .collect::<StdResult<Vec<_>>>()?;
+    store_spend_whitelist(deps.storage, &whitelist)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_spend_whitelist")]))
+}
+
+/// Whitelisted Spend
+/// Whitelisted contracts can request `amount` of UST, sent to themselves
+pub fn whitelisted_spend(deps: DepsMut, info: MessageInfo, amount: Uint128) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if !read_spend_whitelist(deps.storage)?.contains(&sender) {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    if config.spend_limit < amount {
+        return Err(StdError::generic_err("Cannot spend more than spend_limit"));
+    }
+
+    Ok(Response::new()
+        .add_messages(vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                denom: config.stable_denom,
+                amount,
+            }],
+        })])
+        .add_attributes(vec![
+            ("action", "whitelisted_spend"),
+            ("recipient", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::SpendWhitelist {} => to_binary(&query_spend_whitelist(deps)?),
     }
 }
 
+pub fn query_spend_whitelist(deps: Deps) -> StdResult<SpendWhitelistResponse> {
+    let whitelist = read_spend_whitelist(deps.storage)?
+        .iter()
+        .map(|addr| Ok(deps.api.addr_humanize(addr)?.to_string()))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(SpendWhitelistResponse { whitelist })
+}
+
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config = read_config(deps.storage)?;
     let resp = ConfigResponse {
@@ -5,6 +5,7 @@ use cosmwasm_std::{CanonicalAddr, StdResult, Storage, Uint128};
 use cosmwasm_storage::{singleton, singleton_read};
 
 static KEY_CONFIG: &[u8] = b"config";
+static KEY_SPEND_WHITELIST: &[u8] = b"spend_whitelist";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
@@ -35,3 +36,16 @@ pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
 pub fn read_old_config(storage: &dyn Storage) -> StdResult<OldConfig> {
     singleton_read(storage, KEY_CONFIG).load()
 }
+
+pub fn store_spend_whitelist(
+    storage: &mut dyn Storage,
+    whitelist: &[CanonicalAddr],
+) -> StdResult<()> {
+    singleton(storage, KEY_SPEND_WHITELIST).save(&whitelist.to_vec())
+}
+
+pub fn read_spend_whitelist(storage: &dyn Storage) -> StdResult<Vec<CanonicalAddr>> {
+    Ok(singleton_read(storage, KEY_SPEND_WHITELIST)
+        .may_load()?
+        .unwrap_or_default())
+}
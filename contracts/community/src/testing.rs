@@ -7,7 +7,9 @@ use cosmwasm_std::{
     WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
-use glow_protocol::community::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use glow_protocol::community::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SpendWhitelistResponse,
+};
 use glow_protocol::lotto::ExecuteMsg as LottoMsg;
 use terraswap::asset::{Asset, AssetInfo};
 use terraswap::pair::ExecuteMsg as TerraswapExecuteMsg;
@@ -302,6 +304,99 @@ fn test_transfer_stable() {
     );
 }
 
+#[test]
+fn test_whitelisted_spend() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(10000000u128),
+    }]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        stable_denom: "uusd".to_string(),
+        glow_token: "glow".to_string(),
+        lotto_contract: "lotto".to_string(),
+        gov_contract: "gov".to_string(),
+        terraswap_factory: "terraswap".to_string(),
+        spend_limit: Uint128::from(1000000u128),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // permission failed
+    let msg = ExecuteMsg::UpdateSpendWhitelist {
+        whitelist: vec!["lotto".to_string()],
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Unauthorized"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // contracts which aren't whitelisted can't spend
+    let info = mock_info("lotto", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::WhitelistedSpend {
+            amount: Uint128::from(1000000u128),
+        },
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Unauthorized"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let info = mock_info("owner", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let res: SpendWhitelistResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::SpendWhitelist {}).unwrap())
+            .unwrap();
+    assert_eq!(res.whitelist, vec!["lotto".to_string()]);
+
+    // failed due to spend limit
+    let info = mock_info("lotto", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::WhitelistedSpend {
+            amount: Uint128::from(2000000u128),
+        },
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "Cannot spend more than spend_limit")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // the stable is sent to the whitelisted contract
+    let info = mock_info("lotto", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::WhitelistedSpend {
+            amount: Uint128::from(1000000u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "lotto".to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+        }))]
+    );
+}
+
 #[test]
 fn test_sponsor_lotto() {
     let mut deps = mock_dependencies(&[]);
@@ -347,7 +442,9 @@ fn test_sponsor_lotto() {
             }],
             msg: to_binary(&LottoMsg::Sponsor {
                 award: None,
-                prize_distribution: None
+                prize_distribution: None,
+                prize_template: None,
+                lock_duration: None
             })
             .unwrap(),
         }))]
@@ -2,7 +2,10 @@
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::entry_point;
 
-use crate::state::{read_config, store_config, Config};
+use crate::state::{
+    read_config, read_emission_curve, read_total_spent, store_config, store_emission_curve,
+    store_total_spent, Config,
+};
 
 use cosmwasm_std::{
     attr, to_binary, Binary, CanonicalAddr, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
@@ -10,7 +13,8 @@ use cosmwasm_std::{
 };
 
 use glow_protocol::distributor::{
-    ConfigResponse, ExecuteMsg, GlowEmissionRateResponse, InstantiateMsg, MigrateMsg, QueryMsg,
+    ConfigResponse, EmissionCurve, ExecuteMsg, GlowEmissionRateResponse, InstantiateMsg,
+    MigrateMsg, QueryMsg, RemainingEmissionResponse,
 };
 
 use cw20::Cw20ExecuteMsg;
@@ -64,12 +68,7 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(
-    deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    msg: ExecuteMsg,
-) -> StdResult<Response> {
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
     match msg {
         ExecuteMsg::UpdateConfig {
             owner,
@@ -88,11 +87,14 @@ pub fn execute(
             increment_multiplier,
             decrement_multiplier,
         ),
-        ExecuteMsg::Spend { recipient, amount } => spend(deps, info, recipient, amount),
+        ExecuteMsg::Spend { recipient, amount } => spend(deps, env, info, recipient, amount),
         ExecuteMsg::AddDistributor { distributor } => add_distributor(deps, info, distributor),
         ExecuteMsg::RemoveDistributor { distributor } => {
             remove_distributor(deps, info, distributor)
         }
+        ExecuteMsg::UpdateEmissionCurve { emission_curve } => {
+            update_emission_curve(deps, info, emission_curve)
+        }
     }
 }
 
@@ -217,11 +219,83 @@ pub fn remove_distributor(
     ]))
 }
 
+pub fn update_emission_curve(
+    deps: DepsMut,
+    info: MessageInfo,
+    emission_curve: EmissionCurve,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    if emission_curve.period == 0 {
+        return Err(StdError::generic_err(
+            "Emission period must be greater than 0",
+        ));
+    }
+
+    if emission_curve.end_time <= emission_curve.genesis_time {
+        return Err(StdError::generic_err(
+            "Emission end time must be greater than genesis time",
+        ));
+    }
+
+    if emission_curve.decay_rate > Decimal256::one() {
+        return Err(StdError::generic_err(
+            "Decay rate must be equal or smaller than 1",
+        ));
+    }
+
+    store_emission_curve(deps.storage, &emission_curve)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_emission_curve")]))
+}
+
+/// Cumulative GLOW unlocked by the emission curve at time, the whole amount
+/// of a period being unlocked when it starts
+fn emitted_until(curve: &EmissionCurve, time: u64) -> Uint256 {
+    if time < curve.genesis_time {
+        return Uint256::zero();
+    }
+
+    // A last partial period counts as a whole one
+    let duration = curve.end_time - curve.genesis_time;
+    let mut total_periods = duration / curve.period;
+    if total_periods * curve.period < duration {
+        total_periods += 1;
+    }
+    let periods = std::cmp::min(
+        (time - curve.genesis_time) / curve.period + 1,
+        total_periods,
+    );
+
+    let genesis_amount = Uint256::from(curve.genesis_amount);
+    if curve.decay_rate.is_zero() {
+        return genesis_amount * Uint256::from(periods);
+    }
+
+    // Sum of the geometric series genesis_amount * (1 - decay_rate)^k for k < periods
+    let mut retained = Decimal256::one() - curve.decay_rate;
+    let mut retained_pow = Decimal256::one();
+    let mut exp = periods;
+    while exp > 0 {
+        if exp % 2 == 1 {
+            retained_pow *= retained;
+        }
+        retained *= retained;
+        exp /= 2;
+    }
+
+    genesis_amount * ((Decimal256::one() - retained_pow) / curve.decay_rate)
+}
+
 /// Spend
 /// Owner can execute spend operation to send
 /// `amount` of GLOW token to `recipient` for community purposes
 pub fn spend(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     recipient: String,
     amount: Uint128,
@@ -237,6 +311,17 @@ pub fn spend(
         return Err(StdError::generic_err("Cannot spend more than spend_limit"));
     }
 
+    // Cumulative spends are bounded by the emission curve, if set
+    if let Some(emission_curve) = read_emission_curve(deps.storage)? {
+        let total_spent = read_total_spent(deps.storage)? + amount;
+        if Uint256::from(total_spent) > emitted_until(&emission_curve, env.block.time.seconds()) {
+            return Err(StdError::generic_err(
+                "Cannot spend more than the emission curve",
+            ));
+        }
+        store_total_spent(deps.storage, &total_spent)?;
+    }
+
     let glow_token = deps.api.addr_humanize(&config.glow_token)?.to_string();
     Ok(Response::new()
         .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
@@ -255,7 +340,7 @@ pub fn spend(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::GlowEmissionRate {
@@ -268,6 +353,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             target_award,
             current_emission_rate,
         )?),
+        QueryMsg::RemainingEmission {} => to_binary(&query_remaining_emission(deps, env)?),
     }
 }
 
@@ -294,6 +380,38 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     Ok(resp)
 }
 
+pub fn query_remaining_emission(deps: Deps, env: Env) -> StdResult<RemainingEmissionResponse> {
+    let emission_curve = read_emission_curve(deps.storage)?;
+    let total_spent = Uint256::from(read_total_spent(deps.storage)?);
+
+    let (emittable, remaining) = match &emission_curve {
+        Some(curve) => {
+            let emitted = emitted_until(curve, env.block.time.seconds());
+            let total_emission = emitted_until(curve, curve.end_time);
+            (
+                if emitted > total_spent {
+                    emitted - total_spent
+                } else {
+                    Uint256::zero()
+                },
+                if total_emission > total_spent {
+                    total_emission - total_spent
+                } else {
+                    Uint256::zero()
+                },
+            )
+        }
+        None => (Uint256::zero(), Uint256::zero()),
+    };
+
+    Ok(RemainingEmissionResponse {
+        emission_curve,
+        total_spent: total_spent.into(),
+        emittable: emittable.into(),
+        remaining: remaining.into(),
+    })
+}
+
 #[allow(clippy::comparison_chain)]
 fn query_glow_emission_rate(
     deps: Deps,
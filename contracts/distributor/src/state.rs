@@ -4,8 +4,11 @@ use serde::{Deserialize, Serialize};
 use cosmwasm_bignumber::Decimal256;
 use cosmwasm_std::{CanonicalAddr, StdResult, Storage, Uint128};
 use cosmwasm_storage::{singleton, singleton_read};
+use glow_protocol::distributor::EmissionCurve;
 
 static KEY_CONFIG: &[u8] = b"config";
+static KEY_EMISSION_CURVE: &[u8] = b"emission_curve";
+static KEY_TOTAL_SPENT: &[u8] = b"total_spent";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
@@ -26,3 +29,22 @@ pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()>
 pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
     singleton_read(storage, KEY_CONFIG).load()
 }
+
+pub fn store_emission_curve(storage: &mut dyn Storage, curve: &EmissionCurve) -> StdResult<()> {
+    singleton(storage, KEY_EMISSION_CURVE).save(curve)
+}
+
+pub fn read_emission_curve(storage: &dyn Storage) -> StdResult<Option<EmissionCurve>> {
+    singleton_read(storage, KEY_EMISSION_CURVE).may_load()
+}
+
+// GLOW spent since the emission curve was set
+pub fn store_total_spent(storage: &mut dyn Storage, total_spent: &Uint128) -> StdResult<()> {
+    singleton(storage, KEY_TOTAL_SPENT).save(total_spent)
+}
+
+pub fn read_total_spent(storage: &dyn Storage) -> StdResult<Uint128> {
+    Ok(singleton_read(storage, KEY_TOTAL_SPENT)
+        .may_load()?
+        .unwrap_or_default())
+}
@@ -4,7 +4,9 @@ use cosmwasm_bignumber::Decimal256;
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
 use cosmwasm_std::{from_binary, to_binary, CosmosMsg, StdError, SubMsg, Uint128, WasmMsg};
 use cw20::Cw20ExecuteMsg;
-use glow_protocol::distributor::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use glow_protocol::distributor::{
+    ConfigResponse, EmissionCurve, ExecuteMsg, InstantiateMsg, QueryMsg, RemainingEmissionResponse,
+};
 
 #[test]
 fn proper_initialization() {
@@ -425,3 +427,117 @@ fn test_spend() {
         }))]
     );
 }
+
+#[test]
+fn test_emission_curve() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        glow_token: "glow".to_string(),
+        whitelist: vec!["addr1".to_string()],
+        spend_limit: Uint128::from(1000000u128),
+        emission_cap: Decimal256::percent(3000u64),
+        emission_floor: Decimal256::percent(1000u64),
+        increment_multiplier: Decimal256::percent(150u64),
+        decrement_multiplier: Decimal256::percent(99u64),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // Without a curve, spends are only bounded by the spend limit
+    let res: RemainingEmissionResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::RemainingEmission {}).unwrap())
+            .unwrap();
+    assert_eq!(res.emission_curve, None);
+    assert_eq!(res.emittable, Uint128::zero());
+
+    // 1000 GLOW the first week, halving every week, for three weeks
+    let week = 7 * 24 * 60 * 60;
+    let genesis_time = mock_env().block.time.seconds();
+    let emission_curve = EmissionCurve {
+        genesis_time,
+        genesis_amount: Uint128::from(1000u128),
+        decay_rate: Decimal256::percent(50),
+        period: week,
+        end_time: genesis_time + 3 * week,
+    };
+
+    // Only the owner can set the curve
+    let msg = ExecuteMsg::UpdateEmissionCurve {
+        emission_curve: emission_curve.clone(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr1", &[]), msg);
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Unauthorized"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::UpdateEmissionCurve {
+        emission_curve: EmissionCurve {
+            period: 0,
+            ..emission_curve.clone()
+        },
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "Emission period must be greater than 0")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::UpdateEmissionCurve {
+        emission_curve: emission_curve.clone(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    // The first period is unlocked at genesis
+    let msg = ExecuteMsg::Spend {
+        recipient: "addr0000".to_string(),
+        amount: Uint128::from(600u128),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr1", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Spend {
+        recipient: "addr0000".to_string(),
+        amount: Uint128::from(401u128),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr1", &[]), msg);
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "Cannot spend more than the emission curve")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // A week later the second period adds 500 GLOW
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(week);
+    let res: RemainingEmissionResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::RemainingEmission {}).unwrap())
+            .unwrap();
+    assert_eq!(
+        res,
+        RemainingEmissionResponse {
+            emission_curve: Some(emission_curve),
+            total_spent: Uint128::from(600u128),
+            emittable: Uint128::from(900u128),
+            remaining: Uint128::from(1150u128),
+        }
+    );
+
+    let msg = ExecuteMsg::Spend {
+        recipient: "addr0000".to_string(),
+        amount: Uint128::from(900u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("addr1", &[]), msg).unwrap();
+
+    // Nothing is unlocked after the end of the curve
+    env.block.time = env.block.time.plus_seconds(10 * week);
+    let res: RemainingEmissionResponse =
+        from_binary(&query(deps.as_ref(), env, QueryMsg::RemainingEmission {}).unwrap()).unwrap();
+    assert_eq!(res.emittable, Uint128::from(250u128));
+    assert_eq!(res.remaining, Uint128::from(250u128));
+}
@@ -4,23 +4,26 @@ use cw_storage_plus::U64Key;
 use terraswap::asset::{Asset, AssetInfo, PairInfo};
 
 use crate::error::ContractError;
-use crate::helpers::compute_claimable;
+use crate::helpers::{compute_claimable, compute_claimable_epochs};
 use crate::querier::{
     query_address_voting_balance_at_timestamp, query_total_voting_balance_at_timestamp,
 };
 use crate::state::{
-    Config, State, CONFIG, STATE, USER_LAST_CLAIMED_FEE_TIMESTAMP, WEEKLY_TOKEN_DISTRIBUTION,
+    Config, State, UstEpoch, CONFIG, STATE, USER_CLAIMED_UST_EPOCHS,
+    USER_LAST_CLAIMED_FEE_TIMESTAMP, UST_EPOCHS, WEEKLY_TOKEN_DISTRIBUTION,
 };
 
 use cosmwasm_std::{
-    attr, to_binary, Addr, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult, Uint128, WasmMsg,
+    attr, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Response, StdResult, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 
 use glow_protocol::fee_distributor::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, StakerResponse, StateResponse,
+    ClaimableEpochsResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    StakerResponse, StateResponse, UstEpochResponse,
 };
+use glow_protocol::querier::deduct_tax;
 
 use terraswap::querier::{query_balance, query_pair_info, query_token_balance};
 
@@ -28,6 +31,10 @@ use terraswap::pair::ExecuteMsg as TerraswapExecuteMsg;
 
 pub const SECONDS_PER_WEEK: u64 = 7 * 24 * 60 * 60;
 pub const DEFAULT_CLAIM_LIMIT: u32 = 20;
+pub const STABLE_DENOM: &str = "uusd";
+// UST amounts below this aren't worth an epoch and roll over to the next one
+pub const MIN_UST_EPOCH_AMOUNT: u128 = 10_000;
+pub const MAX_CLAIM_EPOCHS: u64 = 50;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -46,6 +53,8 @@ pub fn instantiate(
     let state = State {
         contract_addr: env.contract.address,
         total_distributed_unclaimed_fees: Uint128::zero(),
+        total_unclaimed_ust: Uint128::zero(),
+        num_ust_epochs: 0,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -70,6 +79,8 @@ pub fn execute(
         ExecuteMsg::Sweep { denom } => sweep(deps, env, denom),
         ExecuteMsg::DistributeGlow {} => distribute_glow(deps, env),
         ExecuteMsg::Claim { limit } => claim(deps, env, info, limit),
+        ExecuteMsg::DistributeUst {} => distribute_ust(deps, env),
+        ExecuteMsg::ClaimEpochs { start, end } => claim_epochs(deps, info, start, end),
         ExecuteMsg::UpdateConfig { owner } => update_config(deps, info, owner),
     }
 }
@@ -185,6 +196,114 @@ pub fn claim(
         ]))
 }
 
+pub fn distribute_ust(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+
+    // The UST received since the last epoch, including the dust rolled over from it
+    let amount = query_balance(
+        &deps.querier,
+        env.contract.address.clone(),
+        STABLE_DENOM.to_string(),
+    )?
+    .checked_sub(state.total_unclaimed_ust)?;
+
+    // Snapshot the veGLOW balances at the last week boundary before the current block,
+    // so that locking in the same block as the distribution doesn't earn a share of it
+    let timestamp = (env.block.time.seconds() - 1) / SECONDS_PER_WEEK * SECONDS_PER_WEEK;
+    let total_voting_balance =
+        query_total_voting_balance_at_timestamp(&deps.querier, &config.ve_token, Some(timestamp))?;
+
+    // At most one epoch is opened per snapshot week, so that claims can't be spread
+    // over many small epochs
+    let week_has_epoch = match state.num_ust_epochs.checked_sub(1) {
+        Some(last_epoch) => {
+            UST_EPOCHS
+                .load(deps.storage, U64Key::from(last_epoch))?
+                .timestamp
+                == timestamp
+        }
+        None => false,
+    };
+
+    // Don't fail, so that the lotto epoch operations sending the UST go through
+    if total_voting_balance.is_zero()
+        || amount < Uint128::from(MIN_UST_EPOCH_AMOUNT)
+        || week_has_epoch
+    {
+        return Ok(Response::new().add_attributes(vec![
+            attr("action", "distribute_ust"),
+            attr("rolled_over", amount.to_string()),
+        ]));
+    }
+
+    let epoch = state.num_ust_epochs;
+    let ust_epoch = UstEpoch {
+        timestamp,
+        total_voting_balance,
+        ust_per_voting_balance: Decimal::from_ratio(amount, total_voting_balance),
+    };
+    let distributed_amount = ust_epoch.distributed_amount();
+
+    UST_EPOCHS.save(deps.storage, U64Key::from(epoch), &ust_epoch)?;
+    state.num_ust_epochs += 1;
+    state.total_unclaimed_ust += distributed_amount;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "distribute_ust"),
+        attr("epoch", epoch.to_string()),
+        attr("distributed_amount", distributed_amount.to_string()),
+        attr("rolled_over", (amount - distributed_amount).to_string()),
+    ]))
+}
+
+pub fn claim_epochs(
+    deps: DepsMut,
+    info: MessageInfo,
+    start: u64,
+    end: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+
+    let claimable_epochs =
+        compute_claimable_epochs(deps.as_ref(), &config, &state, &info.sender, start, end)?;
+
+    let mut claim_amount = Uint128::zero();
+    for (epoch, amount) in claimable_epochs {
+        USER_CLAIMED_UST_EPOCHS.save(deps.storage, (&info.sender, U64Key::from(epoch)), &amount)?;
+        claim_amount += amount;
+    }
+
+    if claim_amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    // The floored voter balances can add up to more than the snapshot total,
+    // the difference is paid from the UST rolled over
+    state.total_unclaimed_ust = state.total_unclaimed_ust.saturating_sub(claim_amount);
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: STABLE_DENOM.to_string(),
+                    amount: claim_amount,
+                },
+            )?],
+        }))
+        .add_attributes(vec![
+            attr("action", "claim_epochs"),
+            attr("start", start.to_string()),
+            attr("end", end.to_string()),
+            attr("claimed_amount", claim_amount.to_string()),
+        ]))
+}
+
 /// Register the addresses of the glow_token, ve_token, and terraswap_factory contracts
 pub fn register_contracts(
     deps: DepsMut,
@@ -229,8 +348,13 @@ pub fn sweep(deps: DepsMut, env: Env, denom: String) -> Result<Response, Contrac
         ],
     )?;
 
-    // Sweep the entire balance worth of the denom to glow
-    let amount = query_balance(&deps.querier, env.contract.address, denom.to_string())?;
+    // Sweep the entire balance worth of the denom to glow,
+    // except the UST stakers can still claim from past epochs
+    let mut amount = query_balance(&deps.querier, env.contract.address, denom.to_string())?;
+    if denom == STABLE_DENOM {
+        let state = STATE.load(deps.storage)?;
+        amount = amount.checked_sub(state.total_unclaimed_ust)?;
+    }
     let swap_asset = Asset {
         info: AssetInfo::NativeToken {
             denom: denom.to_string(),
@@ -305,6 +429,14 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
             fee_limit,
             fee_start_after,
         )?)?),
+        QueryMsg::UstEpoch { epoch } => Ok(to_binary(&query_ust_epoch(deps, epoch)?)?),
+        QueryMsg::ClaimableEpochs {
+            address,
+            start,
+            end,
+        } => Ok(to_binary(&query_claimable_epochs(
+            deps, address, start, end,
+        )?)?),
     }
 }
 
@@ -323,6 +455,42 @@ fn query_state(deps: Deps) -> Result<StateResponse, ContractError> {
     Ok(StateResponse {
         contract_addr: state.contract_addr.to_string(),
         total_distributed_unclaimed_fees: state.total_distributed_unclaimed_fees,
+        total_unclaimed_ust: state.total_unclaimed_ust,
+        num_ust_epochs: state.num_ust_epochs,
+    })
+}
+
+fn query_ust_epoch(deps: Deps, epoch: u64) -> Result<UstEpochResponse, ContractError> {
+    let ust_epoch = UST_EPOCHS.load(deps.storage, U64Key::from(epoch))?;
+    Ok(UstEpochResponse {
+        epoch,
+        timestamp: ust_epoch.timestamp,
+        total_voting_balance: ust_epoch.total_voting_balance,
+        ust_per_voting_balance: ust_epoch.ust_per_voting_balance,
+        distributed_amount: ust_epoch.distributed_amount(),
+    })
+}
+
+fn query_claimable_epochs(
+    deps: Deps,
+    address: String,
+    start: u64,
+    end: u64,
+) -> Result<ClaimableEpochsResponse, ContractError> {
+    let address = deps.api.addr_validate(&address)?;
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+
+    let claimable_epochs = compute_claimable_epochs(deps, &config, &state, &address, start, end)?;
+
+    Ok(ClaimableEpochsResponse {
+        claimable: claimable_epochs
+            .iter()
+            .fold(Uint128::zero(), |sum, (_, amount)| sum + *amount),
+        epochs: claimable_epochs
+            .into_iter()
+            .map(|(epoch, _)| epoch)
+            .collect(),
     })
 }
 
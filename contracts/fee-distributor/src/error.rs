@@ -17,4 +17,10 @@ pub enum ContractError {
 
     #[error("Nothing to distribute")]
     NothingToDistribute {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("Invalid epoch range, end must be above start by at most {max_epochs}")]
+    InvalidEpochRange { max_epochs: u64 },
 }
@@ -1,12 +1,16 @@
-use crate::contract::{DEFAULT_CLAIM_LIMIT, SECONDS_PER_WEEK};
+use crate::contract::{DEFAULT_CLAIM_LIMIT, MAX_CLAIM_EPOCHS, SECONDS_PER_WEEK};
+use crate::error::ContractError;
 use crate::querier::{
     query_address_voting_balance_at_timestamp, query_total_voting_balance_at_timestamp,
 };
-use crate::state::{Config, USER_LAST_CLAIMED_FEE_TIMESTAMP, WEEKLY_TOKEN_DISTRIBUTION};
+use crate::state::{
+    Config, State, USER_CLAIMED_UST_EPOCHS, USER_LAST_CLAIMED_FEE_TIMESTAMP, UST_EPOCHS,
+    WEEKLY_TOKEN_DISTRIBUTION,
+};
 use std::convert::TryInto;
 
 use cosmwasm_std::{Addr, Deps, Env, Order, StdResult, Uint128};
-use cw_storage_plus::Bound;
+use cw_storage_plus::{Bound, U64Key};
 
 pub fn compute_claimable(
     deps: Deps,
@@ -110,3 +114,44 @@ pub fn compute_claimable(
         claim_amount,
     ))
 }
+
+/// Returns the UST the user can claim in each epoch of [start, end) that it hasn't claimed yet.
+/// Epochs without a claimable amount are skipped
+pub fn compute_claimable_epochs(
+    deps: Deps,
+    config: &Config,
+    state: &State,
+    user: &Addr,
+    start: u64,
+    end: u64,
+) -> Result<Vec<(u64, Uint128)>, ContractError> {
+    if end <= start || end - start > MAX_CLAIM_EPOCHS {
+        return Err(ContractError::InvalidEpochRange {
+            max_epochs: MAX_CLAIM_EPOCHS,
+        });
+    }
+
+    let mut claimable_epochs = vec![];
+    for epoch in start..end.min(state.num_ust_epochs) {
+        if USER_CLAIMED_UST_EPOCHS.has(deps.storage, (user, U64Key::from(epoch))) {
+            continue;
+        }
+
+        let ust_epoch = UST_EPOCHS.load(deps.storage, U64Key::from(epoch))?;
+
+        // Get the user's voting balance at the epoch snapshot
+        let user_voting_balance = query_address_voting_balance_at_timestamp(
+            &deps.querier,
+            &config.ve_token,
+            Some(ust_epoch.timestamp),
+            user,
+        )?;
+
+        let amount = user_voting_balance * ust_epoch.ust_per_voting_balance;
+        if !amount.is_zero() {
+            claimable_epochs.push((epoch, amount));
+        }
+    }
+
+    Ok(claimable_epochs)
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal, Uint128};
 use cw_storage_plus::{Item, Map, U64Key};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -10,6 +10,12 @@ pub const WEEKLY_TOKEN_DISTRIBUTION: Map<U64Key, Uint128> = Map::new("distribute
 
 pub const USER_LAST_CLAIMED_FEE_TIMESTAMP: Map<Addr, u64> = Map::new("user_last_claimed_fee");
 
+pub const UST_EPOCHS: Map<U64Key, UstEpoch> = Map::new("ust_epochs");
+
+// UST claimed by a user in an epoch
+pub const USER_CLAIMED_UST_EPOCHS: Map<(&Addr, U64Key), Uint128> =
+    Map::new("user_claimed_ust_epochs");
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: Addr,
@@ -22,4 +28,25 @@ pub struct Config {
 pub struct State {
     pub contract_addr: Addr,
     pub total_distributed_unclaimed_fees: Uint128,
+    // UST distributed in epochs and not claimed yet
+    #[serde(default)]
+    pub total_unclaimed_ust: Uint128,
+    #[serde(default)]
+    pub num_ust_epochs: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UstEpoch {
+    // Timestamp at which the veGLOW balances are snapshotted
+    pub timestamp: u64,
+    pub total_voting_balance: Uint128,
+    // UST claimable per unit of veGLOW balance
+    pub ust_per_voting_balance: Decimal,
+}
+
+impl UstEpoch {
+    // UST reserved for the epoch. The remainder of the division rolls over to the next epoch
+    pub fn distributed_amount(&self) -> Uint128 {
+        self.total_voting_balance * self.ust_per_voting_balance
+    }
 }
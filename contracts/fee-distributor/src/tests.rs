@@ -1,15 +1,18 @@
-use crate::contract::{execute, instantiate, query, SECONDS_PER_WEEK};
+use crate::contract::{execute, instantiate, query, MAX_CLAIM_EPOCHS, SECONDS_PER_WEEK};
 use crate::error::ContractError;
 use crate::mock_querier::mock_dependencies;
 use crate::state::{Config, State, CONFIG, STATE};
 
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    coins, from_binary, to_binary, Addr, Api, CosmosMsg, DepsMut, Env, SubMsg, Timestamp, Uint128,
-    WasmMsg,
+    coins, from_binary, to_binary, Addr, Api, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env,
+    SubMsg, Timestamp, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
-use glow_protocol::fee_distributor::{ExecuteMsg, InstantiateMsg, QueryMsg, StakerResponse};
+use glow_protocol::fee_distributor::{
+    ClaimableEpochsResponse, ExecuteMsg, InstantiateMsg, QueryMsg, StakerResponse, StateResponse,
+    UstEpochResponse,
+};
 
 const VOTING_TOKEN: &str = "voting_token";
 const VE_TOKEN: &str = "ve_token";
@@ -92,6 +95,8 @@ fn proper_initialization() {
         State {
             contract_addr: deps.api.addr_validate(MOCK_CONTRACT_ADDR).unwrap(),
             total_distributed_unclaimed_fees: Uint128::zero(),
+            total_unclaimed_ust: Uint128::zero(),
+            num_ust_epochs: 0,
         }
     );
 }
@@ -280,3 +285,198 @@ fn distribute_glow_to_voter_2() {
         }))]
     )
 }
+
+#[test]
+fn distribute_ust_epochs() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    mock_register_contracts(deps.as_mut());
+    let mut env = mock_env_height(0, 1000000);
+    let info = mock_info("lotto", &coins(5_000, "uusd"));
+
+    // Nothing staked yet, so the UST rolls over
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, coins(50_000, "uusd"));
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::DistributeUst {},
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1].value, "50000".to_string());
+
+    deps.querier.with_token_balances(&[(
+        &VE_TOKEN.to_string(),
+        &[
+            (&TEST_VOTER.to_string(), &Uint128::from(100u128)),
+            (&"voter2".to_string(), &Uint128::from(200u128)),
+        ],
+    )]);
+
+    // Staked, but below the minimum epoch amount, so the UST rolls over
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, coins(5_000, "uusd"));
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::DistributeUst {},
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1].value, "5000".to_string());
+
+    // Open an epoch, the remainder of the division rolls over
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, coins(60_001, "uusd"));
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::DistributeUst {},
+    )
+    .unwrap();
+
+    let epoch: UstEpochResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::UstEpoch { epoch: 0 }).unwrap())
+            .unwrap();
+    assert_eq!(
+        epoch,
+        UstEpochResponse {
+            epoch: 0,
+            // The last week boundary before the distribution
+            timestamp: 1000000 / SECONDS_PER_WEEK * SECONDS_PER_WEEK,
+            total_voting_balance: Uint128::from(300u128),
+            ust_per_voting_balance: Decimal::from_ratio(60_001u128, 300u128),
+            distributed_amount: Uint128::from(60_000u128),
+        }
+    );
+
+    // Only one epoch is opened per week, the UST received meanwhile rolls over
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, coins(90_001, "uusd"));
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::DistributeUst {},
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1].value, "30001".to_string());
+
+    // A second epoch only gets the newly received UST
+    increase_env_time(&mut env, SECONDS_PER_WEEK);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::DistributeUst {},
+    )
+    .unwrap();
+
+    let state: StateResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap()).unwrap();
+    assert_eq!(state.num_ust_epochs, 2);
+    assert_eq!(state.total_unclaimed_ust, Uint128::from(90_000u128));
+
+    let claimable: ClaimableEpochsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ClaimableEpochs {
+                address: TEST_VOTER.to_string(),
+                start: 0,
+                end: 10,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        claimable,
+        ClaimableEpochsResponse {
+            claimable: Uint128::from(30_000u128),
+            epochs: vec![0, 1],
+        }
+    );
+
+    // Invalid ranges are rejected
+    let info = mock_info(TEST_VOTER, &[]);
+    for (start, end) in [(1, 1), (0, MAX_CLAIM_EPOCHS + 1)] {
+        match execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::ClaimEpochs { start, end },
+        ) {
+            Err(ContractError::InvalidEpochRange { max_epochs }) => {
+                assert_eq!(max_epochs, MAX_CLAIM_EPOCHS)
+            }
+            _ => panic!("DO NOT ENTER"),
+        }
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::ClaimEpochs { start: 0, end: 1 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: TEST_VOTER.to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(20_000u128),
+            }],
+        }))]
+    );
+
+    // Epochs can't be claimed twice
+    match execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::ClaimEpochs { start: 0, end: 1 },
+    ) {
+        Err(ContractError::NothingToClaim {}) => {}
+        _ => panic!("DO NOT ENTER"),
+    }
+
+    // Claiming the full range only pays out the remaining epoch
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::ClaimEpochs { start: 0, end: 10 },
+    )
+    .unwrap();
+    assert_eq!(res.attributes[3].value, "10000".to_string());
+
+    let state: StateResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap()).unwrap();
+    assert_eq!(state.total_unclaimed_ust, Uint128::from(60_000u128));
+
+    // Claims adding up to more than the snapshot totals don't underflow
+    deps.querier.with_token_balances(&[(
+        &VE_TOKEN.to_string(),
+        &[
+            (&TEST_VOTER.to_string(), &Uint128::from(100u128)),
+            (&"voter2".to_string(), &Uint128::from(400u128)),
+        ],
+    )]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("voter2", &[]),
+        ExecuteMsg::ClaimEpochs { start: 0, end: 10 },
+    )
+    .unwrap();
+    assert_eq!(res.attributes[3].value, "120002".to_string());
+
+    let state: StateResponse =
+        from_binary(&query(deps.as_ref(), env, QueryMsg::State {}).unwrap()).unwrap();
+    assert_eq!(state.total_unclaimed_ust, Uint128::zero());
+}
@@ -0,0 +1,214 @@
+use cosmwasm_std::{
+    attr, to_binary, Addr, CosmosMsg, Deps, DepsMut, MessageInfo, Order, Response, StdResult,
+    Storage, WasmMsg,
+};
+use cw_storage_plus::U64Key;
+use glow_protocol::common::Pagination;
+use glow_protocol::community::ExecuteMsg as CommunityExecuteMsg;
+use glow_protocol::gov::{ExecuteWhitelistResponse, MigrationWhitelistResponse, PollAction};
+use glow_protocol::lotto::ExecuteMsg as LottoExecuteMsg;
+
+use crate::error::ContractError;
+use crate::state::{config_read, Config, EXECUTE_WHITELIST, MIGRATION_WHITELIST};
+
+/// Validates the addresses of a poll action. Arbitrary messages
+/// can only be sent to whitelisted contracts
+pub fn validate_poll_action(deps: Deps, action: &PollAction) -> Result<(), ContractError> {
+    match action {
+        PollAction::CommunitySpend {
+            community_contract,
+            recipient,
+            ..
+        } => {
+            deps.api.addr_validate(community_contract)?;
+            deps.api.addr_validate(recipient)?;
+        }
+        PollAction::UpdateLottoConfig {
+            lotto_contract,
+            msg,
+        } => {
+            deps.api.addr_validate(lotto_contract)?;
+            match msg.as_ref() {
+                LottoExecuteMsg::UpdateConfig { .. }
+                | LottoExecuteMsg::UpdateLotteryConfig { .. }
+                | LottoExecuteMsg::ScheduleConfigUpdate { .. }
+                | LottoExecuteMsg::UpdateReserveDestination { .. }
+                | LottoExecuteMsg::UpdateBuybackConfig { .. }
+                | LottoExecuteMsg::UpdateEmissionDecay { .. }
+                | LottoExecuteMsg::UpdateInsurance { .. }
+                | LottoExecuteMsg::UpdateJackpotFloor { .. }
+                | LottoExecuteMsg::UpdateRateGuard { .. }
+                | LottoExecuteMsg::UpdatePrizeRebalancing { .. }
+                | LottoExecuteMsg::UpdatePrizeCaps { .. }
+                | LottoExecuteMsg::UpdateAutoClaimBounty { .. }
+                | LottoExecuteMsg::UpdateKeeperAllowlist { .. }
+                | LottoExecuteMsg::UpdateBadgeConfig { .. }
+                | LottoExecuteMsg::AcceptExchangeRate { .. }
+                | LottoExecuteMsg::UpdateIbcClaimConfig { .. } => {}
+                _ => return Err(ContractError::InvalidLottoConfigAction {}),
+            }
+        }
+        PollAction::WhitelistMigration { contract, .. } => {
+            deps.api.addr_validate(contract)?;
+        }
+        PollAction::MigrateContract {
+            contract,
+            new_code_id,
+            ..
+        } => {
+            let contract = deps.api.addr_validate(contract)?;
+            if !MIGRATION_WHITELIST.has(deps.storage, (&contract, U64Key::from(*new_code_id))) {
+                return Err(ContractError::MigrationNotWhitelisted {
+                    contract: contract.to_string(),
+                    code_id: *new_code_id,
+                });
+            }
+        }
+        PollAction::Execute { contract, .. } => {
+            assert_execute_whitelisted(deps, &deps.api.addr_validate(contract)?)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Arbitrary messages, from execute actions or legacy execute_msgs,
+/// can only be sent to the contracts whitelisted for execution
+pub fn assert_execute_whitelisted(deps: Deps, contract: &Addr) -> Result<(), ContractError> {
+    if !EXECUTE_WHITELIST.has(deps.storage, contract) {
+        return Err(ContractError::ContractNotWhitelisted(contract.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Applies a validated poll action. Actions updating the gov state
+/// are applied right away and have no message
+pub fn apply_poll_action(
+    storage: &mut dyn Storage,
+    action: PollAction,
+) -> StdResult<Option<CosmosMsg>> {
+    Ok(Some(match action {
+        PollAction::CommunitySpend {
+            community_contract,
+            recipient,
+            amount,
+        } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: community_contract,
+            msg: to_binary(&CommunityExecuteMsg::Spend { recipient, amount })?,
+            funds: vec![],
+        }),
+        PollAction::UpdateLottoConfig {
+            lotto_contract,
+            msg,
+        } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: lotto_contract,
+            msg: to_binary(&msg)?,
+            funds: vec![],
+        }),
+        PollAction::WhitelistMigration { contract, code_id } => {
+            MIGRATION_WHITELIST.save(
+                storage,
+                (&Addr::unchecked(contract), U64Key::from(code_id)),
+                &true,
+            )?;
+            return Ok(None);
+        }
+        PollAction::MigrateContract {
+            contract,
+            new_code_id,
+            msg,
+        } => {
+            MIGRATION_WHITELIST.remove(
+                storage,
+                (
+                    &Addr::unchecked(contract.clone()),
+                    U64Key::from(new_code_id),
+                ),
+            );
+            CosmosMsg::Wasm(WasmMsg::Migrate {
+                contract_addr: contract,
+                new_code_id,
+                msg,
+            })
+        }
+        PollAction::Execute { contract, msg } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract,
+            msg,
+            funds: vec![],
+        }),
+    }))
+}
+
+pub fn execute_update_execute_whitelist(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<String>>,
+    remove: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for contract in add.unwrap_or_default() {
+        let contract = deps.api.addr_validate(&contract)?;
+        EXECUTE_WHITELIST.save(deps.storage, &contract, &true)?;
+    }
+
+    for contract in remove.unwrap_or_default() {
+        let contract = deps.api.addr_validate(&contract)?;
+        EXECUTE_WHITELIST.remove(deps.storage, &contract);
+    }
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_execute_whitelist")]))
+}
+
+pub fn query_execute_whitelist(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ExecuteWhitelistResponse> {
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let pagination = Pagination::new(start_after, limit);
+
+    let contracts = EXECUTE_WHITELIST
+        .keys(
+            deps.storage,
+            pagination.start_bound(),
+            None,
+            Order::Ascending,
+        )
+        .take(pagination.limit)
+        .map(|key| String::from_utf8_lossy(&key).to_string())
+        .collect();
+
+    Ok(ExecuteWhitelistResponse {
+        contracts,
+        limit: pagination.limit as u32,
+    })
+}
+
+pub fn query_migration_whitelist(
+    deps: Deps,
+    contract: String,
+) -> StdResult<MigrationWhitelistResponse> {
+    let contract = deps.api.addr_validate(&contract)?;
+
+    let code_ids = MIGRATION_WHITELIST
+        .prefix(&contract)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|key| {
+            let mut code_id = [0u8; 8];
+            code_id.copy_from_slice(&key);
+            u64::from_be_bytes(code_id)
+        })
+        .collect();
+
+    Ok(MigrationWhitelistResponse {
+        contract: contract.to_string(),
+        code_ids,
+    })
+}
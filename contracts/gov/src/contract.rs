@@ -1,28 +1,43 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
+use crate::actions::{
+    apply_poll_action, assert_execute_whitelisted, execute_update_execute_whitelist,
+    query_execute_whitelist, query_migration_whitelist, validate_poll_action,
+};
+use crate::delegation::{
+    compute_voting_power, delegate, query_delegation, query_delegators, query_voting_power,
+    remove_delegator, undelegate,
+};
 use crate::error::ContractError;
-use crate::querier::{
-    query_address_voting_balance_at_timestamp, query_total_voting_balance_at_timestamp,
+use crate::offchain::{
+    execute_offchain_proposal, execute_register_offchain_proposal,
+    execute_update_offchain_committee, query_offchain_committee, query_offchain_proposal,
 };
+use crate::querier::query_total_voting_balance_at_timestamp;
 use crate::staking::{query_staker, stake_voting_tokens, withdraw_voting_tokens};
 use crate::state::{
     config_read, config_store, old_config_read, poll_indexer_store, poll_read, poll_store,
-    poll_voter_read, poll_voter_store, read_poll_voters, read_polls, state_read, state_store,
-    Config, ExecuteData, Poll, State,
+    poll_voter_read, poll_voter_store, read_delegate_at, read_poll_voters, read_polls, state_read,
+    state_store, Config, ExecuteData, Poll, State, UstDeposit, UstDepositConfig,
 };
 
+use cosmwasm_bignumber::Uint256;
 use cosmwasm_std::{
-    attr, from_binary, to_binary, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps, DepsMut,
-    Env, MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg,
+    attr, from_binary, to_binary, BankMsg, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps,
+    DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
-use glow_protocol::common::OrderBy;
+use glow_protocol::common::{OrderBy, Pagination};
 use glow_protocol::gov::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PollExecuteMsg,
-    PollResponse, PollStatus, PollsResponse, QueryMsg, StateResponse, VoteOption, VoterInfo,
-    VotersResponse, VotersResponseItem,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PollAction,
+    PollExecuteMsg, PollResponse, PollStatus, PollsResponse, QueryMsg, StateResponse,
+    UstDepositConfigResponse, UstDepositResponse, VoteOption, VoterInfo, VotersResponse,
+    VotersResponseItem,
+};
+use glow_protocol::querier::{
+    deduct_tax, deposit_stable_msg, query_exchange_rate, redeem_stable_msg,
 };
 
 use terraswap::asset::{Asset, AssetInfo, PairInfo};
@@ -35,6 +50,7 @@ const MIN_DESC_LENGTH: usize = 4;
 const MAX_DESC_LENGTH: usize = 1024;
 const MIN_LINK_LENGTH: usize = 12;
 const MAX_LINK_LENGTH: usize = 128;
+const STABLE_DENOM: &str = "uusd";
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -58,6 +74,7 @@ pub fn instantiate(
         expiration_period: msg.expiration_period,
         proposal_deposit: msg.proposal_deposit,
         snapshot_period: msg.snapshot_period,
+        ust_deposit_config: None,
     };
 
     let state = State {
@@ -109,11 +126,58 @@ pub fn execute(
             proposal_deposit,
             snapshot_period,
         ),
+        ExecuteMsg::UpdateUstDepositConfig {
+            anchor_contract,
+            a_terra_contract,
+            community_contract,
+            proposal_deposit,
+        } => update_ust_deposit_config(
+            deps,
+            info,
+            anchor_contract,
+            a_terra_contract,
+            community_contract,
+            proposal_deposit,
+        ),
+        ExecuteMsg::CreateUstPoll {
+            title,
+            description,
+            link,
+            execute_msgs,
+            actions,
+        } => create_ust_poll(
+            deps,
+            env,
+            info,
+            title,
+            description,
+            link,
+            execute_msgs,
+            actions,
+        ),
         ExecuteMsg::WithdrawVotingTokens { amount } => withdraw_voting_tokens(deps, info, amount),
         ExecuteMsg::CastVote { poll_id, vote } => cast_vote(deps, env, info, poll_id, vote),
         ExecuteMsg::EndPoll { poll_id } => end_poll(deps, env, poll_id),
         ExecuteMsg::ExecutePoll { poll_id } => execute_poll(deps, env, poll_id),
         ExecuteMsg::ExpirePoll { poll_id } => expire_poll(deps, env, poll_id),
+        ExecuteMsg::Delegate { to } => delegate(deps, env, info, to),
+        ExecuteMsg::Undelegate {} => undelegate(deps, env, info),
+        ExecuteMsg::RemoveDelegator { delegator } => remove_delegator(deps, env, info, delegator),
+        ExecuteMsg::UpdateExecuteWhitelist { add, remove } => {
+            execute_update_execute_whitelist(deps, info, add, remove)
+        }
+        ExecuteMsg::UpdateOffchainCommittee {
+            members,
+            min_signatures,
+        } => execute_update_offchain_committee(deps, info, members, min_signatures),
+        ExecuteMsg::RegisterOffchainProposal { proposal_hash } => {
+            execute_register_offchain_proposal(deps, env, info, proposal_hash)
+        }
+        ExecuteMsg::ExecuteOffchainProposal {
+            actions,
+            attestation,
+            signatures,
+        } => execute_offchain_proposal(deps, env, actions, attestation, signatures),
     }
 }
 
@@ -139,6 +203,7 @@ pub fn receive_cw20(
             description,
             link,
             execute_msgs,
+            actions,
         }) => create_poll(
             deps,
             env,
@@ -148,6 +213,8 @@ pub fn receive_cw20(
             description,
             link,
             execute_msgs,
+            actions,
+            None,
         ),
         _ => Err(ContractError::DataShouldBeGiven {}),
     }
@@ -286,6 +353,30 @@ pub fn update_config(
     Ok(Response::new().add_attributes(vec![("action", "update_config")]))
 }
 
+pub fn update_ust_deposit_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    anchor_contract: String,
+    a_terra_contract: String,
+    community_contract: String,
+    proposal_deposit: Uint128,
+) -> Result<Response, ContractError> {
+    let mut config: Config = config_read(deps.storage).load()?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.ust_deposit_config = Some(UstDepositConfig {
+        anchor_contract: deps.api.addr_canonicalize(&anchor_contract)?,
+        a_terra_contract: deps.api.addr_canonicalize(&a_terra_contract)?,
+        community_contract: deps.api.addr_canonicalize(&community_contract)?,
+        proposal_deposit,
+    });
+    config_store(deps.storage).save(&config)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "update_ust_deposit_config")]))
+}
+
 /// validate_title returns an error if the title is invalid
 fn validate_title(title: &str) -> StdResult<()> {
     if title.len() < MIN_TITLE_LENGTH {
@@ -354,13 +445,18 @@ pub fn create_poll(
     description: String,
     link: Option<String>,
     execute_msgs: Option<Vec<PollExecuteMsg>>,
+    actions: Option<Vec<PollAction>>,
+    ust_deposit: Option<UstDeposit>,
 ) -> Result<Response, ContractError> {
     validate_title(&title)?;
     validate_description(&description)?;
     validate_link(&link)?;
+    for action in actions.iter().flatten() {
+        validate_poll_action(deps.as_ref(), action)?;
+    }
 
     let config: Config = config_store(deps.storage).load()?;
-    if deposit_amount < config.proposal_deposit {
+    if ust_deposit.is_none() && deposit_amount < config.proposal_deposit {
         return Err(ContractError::InsufficientProposalDeposit(
             config.proposal_deposit.u128(),
         ));
@@ -376,6 +472,7 @@ pub fn create_poll(
     let mut data_list: Vec<ExecuteData> = vec![];
     let all_execute_data = if let Some(exe_msgs) = execute_msgs {
         for msgs in exe_msgs {
+            assert_execute_whitelisted(deps.as_ref(), &deps.api.addr_validate(&msgs.contract)?)?;
             let execute_data = ExecuteData {
                 order: msgs.order,
                 contract: deps.api.addr_canonicalize(&msgs.contract)?,
@@ -407,9 +504,11 @@ pub fn create_poll(
         description,
         link,
         execute_data: all_execute_data,
+        actions,
         deposit_amount,
         total_balance_at_end_poll: None,
         staked_amount: Some(staked_amount),
+        ust_deposit,
     };
 
     poll_store(deps.storage).save(&poll_id.to_be_bytes(), &new_poll)?;
@@ -432,6 +531,149 @@ pub fn create_poll(
     ]))
 }
 
+/// Create a poll paying the proposal deposit in UST.
+/// The deposit is held in Anchor until the poll ends
+#[allow(clippy::too_many_arguments)]
+pub fn create_ust_poll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    title: String,
+    description: String,
+    link: Option<String>,
+    execute_msgs: Option<Vec<PollExecuteMsg>>,
+    actions: Option<Vec<PollAction>>,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    let ust_deposit_config = config
+        .ust_deposit_config
+        .ok_or(ContractError::UstDepositsNotEnabled {})?;
+
+    let deposit_amount = info
+        .funds
+        .iter()
+        .find(|c| c.denom == STABLE_DENOM)
+        .map(|c| c.amount)
+        .unwrap_or_else(Uint128::zero);
+    if info.funds.len() != 1 || deposit_amount < ust_deposit_config.proposal_deposit {
+        return Err(ContractError::InsufficientUstProposalDeposit(
+            ust_deposit_config.proposal_deposit.u128(),
+        ));
+    }
+
+    // Anchor receives the deposit net of taxes
+    let net_amount = deduct_tax(
+        deps.as_ref(),
+        Coin {
+            denom: STABLE_DENOM.to_string(),
+            amount: deposit_amount,
+        },
+    )?
+    .amount;
+
+    let anchor_contract = deps
+        .api
+        .addr_humanize(&ust_deposit_config.anchor_contract)?;
+    let epoch_state =
+        query_exchange_rate(deps.as_ref(), anchor_contract.to_string(), env.block.height)?;
+    let aust_amount = Uint256::from(net_amount) / epoch_state.exchange_rate;
+
+    let ust_deposit = UstDeposit {
+        amount: net_amount,
+        aust_amount: aust_amount.into(),
+    };
+
+    let res = create_poll(
+        deps,
+        env,
+        info.sender.to_string(),
+        Uint128::zero(),
+        title,
+        description,
+        link,
+        execute_msgs,
+        actions,
+        Some(ust_deposit),
+    )?;
+
+    Ok(res
+        .add_message(deposit_stable_msg(
+            &anchor_contract,
+            STABLE_DENOM,
+            net_amount.into(),
+        )?)
+        .add_attribute("ust_deposit", net_amount.to_string()))
+}
+
+/// Redeems the UST deposit of a poll from Anchor. The principal is refunded to
+/// the proposer only when quorum is reached and the accrued yield (or the
+/// whole deposit otherwise) is sent to the community contract
+fn redeem_ust_deposit(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    a_poll: &Poll,
+    ust_deposit: &UstDeposit,
+    quorum_reached: bool,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let ust_deposit_config = config
+        .ust_deposit_config
+        .as_ref()
+        .ok_or(ContractError::UstDepositsNotEnabled {})?;
+
+    let anchor_contract = deps
+        .api
+        .addr_humanize(&ust_deposit_config.anchor_contract)?;
+    let epoch_state = query_exchange_rate(deps, anchor_contract.to_string(), env.block.height)?;
+
+    let mut messages = vec![redeem_stable_msg(
+        &deps
+            .api
+            .addr_humanize(&ust_deposit_config.a_terra_contract)?,
+        &anchor_contract,
+        ust_deposit.aust_amount.into(),
+    )?];
+
+    // Anchor deducts taxes when sending back the redeemed UST
+    let redeemed = deduct_tax(
+        deps,
+        Coin {
+            denom: STABLE_DENOM.to_string(),
+            amount: (Uint256::from(ust_deposit.aust_amount) * epoch_state.exchange_rate).into(),
+        },
+    )?
+    .amount;
+
+    let refund_amount = if quorum_reached {
+        std::cmp::min(redeemed, ust_deposit.amount)
+    } else {
+        Uint128::zero()
+    };
+    let community_amount = redeemed.checked_sub(refund_amount)?;
+
+    for (recipient, amount) in [
+        (&a_poll.creator, refund_amount),
+        (&ust_deposit_config.community_contract, community_amount),
+    ] {
+        if amount.is_zero() {
+            continue;
+        }
+
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: deps.api.addr_humanize(recipient)?.to_string(),
+            amount: vec![deduct_tax(
+                deps,
+                Coin {
+                    denom: STABLE_DENOM.to_string(),
+                    amount,
+                },
+            )?],
+        }));
+    }
+
+    Ok(messages)
+}
+
 /*
  * Ends a poll.
  */
@@ -454,6 +696,7 @@ pub fn end_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, Contr
     let mut poll_status = PollStatus::Rejected;
     let mut rejected_reason = "";
     let mut passed = false;
+    let mut quorum_reached = false;
 
     let mut messages: Vec<CosmosMsg> = vec![];
     let config: Config = config_read(deps.storage).load()?;
@@ -475,6 +718,7 @@ pub fn end_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, Contr
         // period need to have participated in the vote.
         rejected_reason = "Quorum not reached";
     } else {
+        quorum_reached = true;
         if Decimal::from_ratio(yes, tallied_weight) > config.threshold {
             //Threshold: More than 50% of the tokens that participated in the vote
             // (after excluding “Abstain” votes) need to have voted in favor of the proposal (“Yes”).
@@ -497,6 +741,17 @@ pub fn end_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, Contr
         }
     }
 
+    if let Some(ust_deposit) = &a_poll.ust_deposit {
+        messages.extend(redeem_ust_deposit(
+            deps.as_ref(),
+            &env,
+            &config,
+            &a_poll,
+            ust_deposit,
+            quorum_reached,
+        )?);
+    }
+
     // Decrease total deposit amount
     state.total_deposit = state.total_deposit.checked_sub(a_poll.deposit_amount)?;
     state_store(deps.storage).save(&state)?;
@@ -539,19 +794,31 @@ pub fn execute_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, C
     a_poll.status = PollStatus::Executed;
     poll_store(deps.storage).save(&poll_id.to_be_bytes(), &a_poll)?;
 
+    if a_poll.execute_data.is_none() && a_poll.actions.is_none() {
+        return Err(ContractError::NoExecuteData {});
+    }
+
     let mut messages: Vec<CosmosMsg> = vec![];
     if let Some(all_msgs) = a_poll.execute_data {
         let mut msgs = all_msgs;
         msgs.sort();
         for msg in msgs {
+            let contract = deps.api.addr_humanize(&msg.contract)?;
+            assert_execute_whitelisted(deps.as_ref(), &contract)?;
             messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: deps.api.addr_humanize(&msg.contract)?.to_string(),
+                contract_addr: contract.to_string(),
                 msg: msg.msg,
                 funds: vec![],
             }))
         }
-    } else {
-        return Err(ContractError::NoExecuteData {});
+    }
+
+    // The whitelists are checked again, entries may have been removed since the poll was created
+    for action in a_poll.actions.into_iter().flatten() {
+        validate_poll_action(deps.as_ref(), &action)?;
+        if let Some(msg) = apply_poll_action(deps.storage, action)? {
+            messages.push(msg);
+        }
     }
 
     Ok(Response::new().add_messages(messages).add_attributes(vec![
@@ -569,7 +836,7 @@ pub fn expire_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, Co
         return Err(ContractError::PollNotPassed {});
     }
 
-    if a_poll.execute_data.is_none() {
+    if a_poll.execute_data.is_none() && a_poll.actions.is_none() {
         return Err(ContractError::NoExecuteData {});
     }
 
@@ -616,12 +883,18 @@ pub fn cast_vote(
         return Err(ContractError::AlreadyVoted {});
     }
 
-    let amount = query_address_voting_balance_at_timestamp(
-        &deps.querier,
+    // Voting power delegated when the poll started is cast by the delegate
+    if read_delegate_at(deps.storage, &info.sender, Some(a_poll.start_time))?.is_some() {
+        return Err(ContractError::VotingPowerDelegated {});
+    }
+
+    let amount = compute_voting_power(
+        deps.as_ref(),
         &deps.api.addr_humanize(&config.ve_token)?,
-        Some(a_poll.start_time),
         &info.sender,
-    )?;
+        Some(a_poll.start_time),
+    )?
+    .voting_power;
 
     // update tally info
     if VoteOption::Yes == vote {
@@ -680,6 +953,21 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             limit,
             order_by,
         )?)?),
+        QueryMsg::Delegation { address } => Ok(to_binary(&query_delegation(deps, address)?)?),
+        QueryMsg::Delegators { address } => Ok(to_binary(&query_delegators(deps, address)?)?),
+        QueryMsg::VotingPower { address, timestamp } => {
+            Ok(to_binary(&query_voting_power(deps, address, timestamp)?)?)
+        }
+        QueryMsg::ExecuteWhitelist { start_after, limit } => Ok(to_binary(
+            &query_execute_whitelist(deps, start_after, limit)?,
+        )?),
+        QueryMsg::MigrationWhitelist { contract } => {
+            Ok(to_binary(&query_migration_whitelist(deps, contract)?)?)
+        }
+        QueryMsg::OffchainCommittee {} => Ok(to_binary(&query_offchain_committee(deps)?)?),
+        QueryMsg::OffchainProposal { proposal_hash } => {
+            Ok(to_binary(&query_offchain_proposal(deps, proposal_hash)?)?)
+        }
     }
 }
 
@@ -699,6 +987,17 @@ fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
         expiration_period: config.expiration_period,
         proposal_deposit: config.proposal_deposit,
         snapshot_period: config.snapshot_period,
+        ust_deposit_config: config
+            .ust_deposit_config
+            .map(|c| -> StdResult<UstDepositConfigResponse> {
+                Ok(UstDepositConfigResponse {
+                    anchor_contract: deps.api.addr_humanize(&c.anchor_contract)?.to_string(),
+                    a_terra_contract: deps.api.addr_humanize(&c.a_terra_contract)?.to_string(),
+                    community_contract: deps.api.addr_humanize(&c.community_contract)?.to_string(),
+                    proposal_deposit: c.proposal_deposit,
+                })
+            })
+            .transpose()?,
     })
 }
 
@@ -743,10 +1042,15 @@ fn query_poll(deps: Deps, poll_id: u64) -> Result<PollResponse, ContractError> {
         } else {
             None
         },
+        actions: poll.actions,
         yes_votes: poll.yes_votes,
         no_votes: poll.no_votes,
         staked_amount: poll.staked_amount,
         total_balance_at_end_poll: poll.total_balance_at_end_poll,
+        ust_deposit: poll.ust_deposit.map(|d| UstDepositResponse {
+            amount: d.amount,
+            aust_amount: d.aust_amount,
+        }),
     })
 }
 
@@ -757,7 +1061,8 @@ fn query_polls(
     limit: Option<u32>,
     order_by: Option<OrderBy>,
 ) -> Result<PollsResponse, ContractError> {
-    let polls = read_polls(deps.storage, filter, start_after, limit, order_by)?;
+    let pagination = Pagination::new(start_after, limit);
+    let polls = read_polls(deps.storage, filter, &pagination, order_by)?;
 
     let poll_responses: StdResult<Vec<PollResponse>> = polls
         .iter()
@@ -787,16 +1092,22 @@ fn query_polls(
                 } else {
                     None
                 },
+                actions: poll.actions.clone(),
                 yes_votes: poll.yes_votes,
                 no_votes: poll.no_votes,
                 staked_amount: poll.staked_amount,
                 total_balance_at_end_poll: poll.total_balance_at_end_poll,
+                ust_deposit: poll.ust_deposit.as_ref().map(|d| UstDepositResponse {
+                    amount: d.amount,
+                    aust_amount: d.aust_amount,
+                }),
             })
         })
         .collect();
 
     Ok(PollsResponse {
         polls: poll_responses?,
+        limit: pagination.limit as u32,
     })
 }
 
@@ -813,18 +1124,14 @@ fn query_voters(
     }
     .unwrap();
 
+    let start_after = start_after
+        .map(|addr| deps.api.addr_canonicalize(&addr))
+        .transpose()?;
+    let pagination = Pagination::new(start_after, limit);
     let voters = if poll.status != PollStatus::InProgress {
         vec![]
-    } else if let Some(start_after) = start_after {
-        read_poll_voters(
-            deps.storage,
-            poll_id,
-            Some(deps.api.addr_canonicalize(&start_after)?),
-            limit,
-            order_by,
-        )?
     } else {
-        read_poll_voters(deps.storage, poll_id, None, limit, order_by)?
+        read_poll_voters(deps.storage, poll_id, &pagination, order_by)?
     };
 
     let voters_response: StdResult<Vec<VotersResponseItem>> = voters
@@ -840,6 +1147,7 @@ fn query_voters(
 
     Ok(VotersResponse {
         voters: voters_response?,
+        limit: pagination.limit as u32,
     })
 }
 
@@ -859,6 +1167,7 @@ pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response>
         expiration_period: old_config.expiration_period,
         proposal_deposit: old_config.proposal_deposit,
         snapshot_period: old_config.snapshot_period,
+        ust_deposit_config: None,
     };
 
     config_store(deps.storage).save(&new_config)?;
@@ -0,0 +1,180 @@
+use crate::error::ContractError;
+use crate::querier::query_address_voting_balance_at_timestamp;
+use crate::state::{
+    config_read, read_delegate_at, read_delegators_at, Config, DELEGATION_CHECKPOINTS,
+    DELEGATORS_CHECKPOINTS,
+};
+
+use cosmwasm_std::{Addr, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128};
+use cw_storage_plus::U64Key;
+use glow_protocol::gov::{DelegationResponse, DelegatorsResponse, VotingPowerResponse};
+
+// Bounds the number of ve balance queries needed to tally a delegate's vote
+pub const MAX_DELEGATORS: usize = 30;
+
+pub fn delegate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    to: String,
+) -> Result<Response, ContractError> {
+    let delegate = deps.api.addr_validate(&to)?;
+    if delegate == info.sender {
+        return Err(ContractError::CannotDelegateToSelf {});
+    }
+
+    if read_delegate_at(deps.storage, &info.sender, None)?.is_some() {
+        return Err(ContractError::AlreadyDelegated {});
+    }
+
+    // Addresses without voting power could only take up delegator slots
+    let config: Config = config_read(deps.storage).load()?;
+    let balance = query_address_voting_balance_at_timestamp(
+        &deps.querier,
+        &deps.api.addr_humanize(&config.ve_token)?,
+        None,
+        &info.sender,
+    )?;
+    if balance.is_zero() {
+        return Err(ContractError::NoVotingPower {});
+    }
+
+    // Only one level of delegation, so a tally never has to follow a chain
+    if !read_delegators_at(deps.storage, &info.sender, None)?.is_empty()
+        || read_delegate_at(deps.storage, &delegate, None)?.is_some()
+    {
+        return Err(ContractError::DelegationChain {});
+    }
+
+    let mut delegators = read_delegators_at(deps.storage, &delegate, None)?;
+    if delegators.len() >= MAX_DELEGATORS {
+        return Err(ContractError::TooManyDelegators(MAX_DELEGATORS));
+    }
+    delegators.push(info.sender.clone());
+
+    let time_key = U64Key::from(env.block.time.seconds());
+    DELEGATION_CHECKPOINTS.save(
+        deps.storage,
+        (&info.sender, time_key.clone()),
+        &Some(delegate.clone()),
+    )?;
+    DELEGATORS_CHECKPOINTS.save(deps.storage, (&delegate, time_key), &delegators)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "delegate"),
+        ("delegator", info.sender.as_str()),
+        ("delegate", delegate.as_str()),
+    ]))
+}
+
+pub fn undelegate(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let delegate = match read_delegate_at(deps.storage, &info.sender, None)? {
+        Some(delegate) => delegate,
+        None => return Err(ContractError::NotDelegated {}),
+    };
+
+    end_delegation(deps, &env, &info.sender, &delegate)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "undelegate"),
+        ("delegator", info.sender.as_str()),
+        ("delegate", delegate.as_str()),
+    ]))
+}
+
+/// Lets a delegate refuse the voting power delegated by delegator
+pub fn remove_delegator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    delegator: String,
+) -> Result<Response, ContractError> {
+    let delegator = deps.api.addr_validate(&delegator)?;
+    if read_delegate_at(deps.storage, &delegator, None)?.as_ref() != Some(&info.sender) {
+        return Err(ContractError::NotDelegated {});
+    }
+
+    end_delegation(deps, &env, &delegator, &info.sender)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "remove_delegator"),
+        ("delegator", delegator.as_str()),
+        ("delegate", info.sender.as_str()),
+    ]))
+}
+
+fn end_delegation(deps: DepsMut, env: &Env, delegator: &Addr, delegate: &Addr) -> StdResult<()> {
+    let mut delegators = read_delegators_at(deps.storage, delegate, None)?;
+    delegators.retain(|address| address != delegator);
+
+    let time_key = U64Key::from(env.block.time.seconds());
+    DELEGATION_CHECKPOINTS.save(deps.storage, (delegator, time_key.clone()), &None)?;
+    DELEGATORS_CHECKPOINTS.save(deps.storage, (delegate, time_key), &delegators)
+}
+
+/// Computes the voting power of address at timestamp. Delegated checkpoints are
+/// read at the same timestamp as the ve balances, so delegations made after a
+/// poll started don't change its tally.
+pub fn compute_voting_power(
+    deps: Deps,
+    ve_token: &Addr,
+    address: &Addr,
+    timestamp: Option<u64>,
+) -> StdResult<VotingPowerResponse> {
+    let balance =
+        query_address_voting_balance_at_timestamp(&deps.querier, ve_token, timestamp, address)?;
+
+    let mut delegated_balance = Uint128::zero();
+    for delegator in read_delegators_at(deps.storage, address, timestamp)? {
+        delegated_balance += query_address_voting_balance_at_timestamp(
+            &deps.querier,
+            ve_token,
+            timestamp,
+            &delegator,
+        )?;
+    }
+
+    let voting_power = if read_delegate_at(deps.storage, address, timestamp)?.is_some() {
+        Uint128::zero()
+    } else {
+        balance + delegated_balance
+    };
+
+    Ok(VotingPowerResponse {
+        balance,
+        delegated_balance,
+        voting_power,
+    })
+}
+
+pub fn query_delegation(deps: Deps, address: String) -> StdResult<DelegationResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    Ok(DelegationResponse {
+        delegate: read_delegate_at(deps.storage, &address, None)?.map(|addr| addr.to_string()),
+    })
+}
+
+pub fn query_delegators(deps: Deps, address: String) -> StdResult<DelegatorsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    Ok(DelegatorsResponse {
+        delegators: read_delegators_at(deps.storage, &address, None)?
+            .into_iter()
+            .map(|addr| addr.to_string())
+            .collect(),
+    })
+}
+
+pub fn query_voting_power(
+    deps: Deps,
+    address: String,
+    timestamp: Option<u64>,
+) -> StdResult<VotingPowerResponse> {
+    let config: Config = config_read(deps.storage).load()?;
+    let address = deps.api.addr_validate(&address)?;
+    compute_voting_power(
+        deps,
+        &deps.api.addr_humanize(&config.ve_token)?,
+        &address,
+        timestamp,
+    )
+}
@@ -24,6 +24,12 @@ pub enum ContractError {
     #[error("Must deposit more than {0} token")]
     InsufficientProposalDeposit(u128),
 
+    #[error("Must deposit more than {0} uusd")]
+    InsufficientUstProposalDeposit(u128),
+
+    #[error("UST proposal deposits are not enabled")]
+    UstDepositsNotEnabled {},
+
     #[error("Reward deposited is too small")]
     InsufficientReward {},
 
@@ -68,4 +74,66 @@ pub enum ContractError {
 
     #[error("Voting period has not expired")]
     PollVotingPeriod {},
+
+    #[error("Cannot delegate voting power to yourself")]
+    CannotDelegateToSelf {},
+
+    #[error("Voting power is already delegated")]
+    AlreadyDelegated {},
+
+    #[error("Voting power is not delegated")]
+    NotDelegated {},
+
+    #[error("Delegates cannot delegate their voting power and delegators cannot be delegated to")]
+    DelegationChain {},
+
+    #[error("Delegate has reached the maximum of {0} delegators")]
+    TooManyDelegators(usize),
+
+    #[error("Only addresses holding voting power can delegate")]
+    NoVotingPower {},
+
+    #[error("Voting power was delegated when the poll started")]
+    VotingPowerDelegated {},
+
+    #[error("Contract {0} is not whitelisted for poll execution")]
+    ContractNotWhitelisted(String),
+
+    #[error("Migration of {contract} to code id {code_id} was not whitelisted by a poll")]
+    MigrationNotWhitelisted { contract: String, code_id: u64 },
+
+    #[error("Only lotto config updates can be executed with UpdateLottoConfig")]
+    InvalidLottoConfigAction {},
+
+    #[error("Committee members must be distinct secp256k1 public keys and min_signatures between one and their number")]
+    InvalidOffchainCommittee {},
+
+    #[error("No committee attests off-chain votes")]
+    OffchainCommitteeNotSet {},
+
+    #[error("Invalid off-chain proposal hash, expected a hex encoded keccak256 hash")]
+    InvalidOffchainProposalHash {},
+
+    #[error("Off-chain proposal {0} is already registered")]
+    OffchainProposalAlreadyRegistered(String),
+
+    #[error("Off-chain proposal {0} is not registered")]
+    OffchainProposalNotFound(String),
+
+    #[error("Off-chain proposal {0} was already executed")]
+    OffchainProposalAlreadyExecuted(String),
+
+    #[error(
+        "Off-chain proposals can only update the lotto config or execute on whitelisted contracts"
+    )]
+    OffchainActionNotAllowed {},
+
+    #[error("Signature is not a valid signature of a distinct committee member")]
+    InvalidCommitteeSignature {},
+
+    #[error("Attestation needs {required} committee signatures, got {received}")]
+    InsufficientCommitteeSignatures { required: u32, received: u32 },
+
+    #[error("Attested off-chain vote did not reach quorum and threshold")]
+    OffchainVoteNotPassed {},
 }
@@ -1,6 +1,9 @@
 pub mod contract;
 
+mod actions;
+mod delegation;
 mod error;
+mod offchain;
 mod querier;
 mod staking;
 mod state;
@@ -1,5 +1,7 @@
 #![allow(dead_code)]
+use cosmwasm_bignumber::{Decimal256, Uint256};
 use glow_protocol::ve_token::{StakerResponse, StateResponse};
+use moneymarket::market::EpochStateResponse;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -34,6 +36,7 @@ pub struct WasmMockQuerier {
     token_querier: TokenQuerier,
     tax_querier: TaxQuerier,
     terraswap_factory_querier: TerraswapFactoryQuerier,
+    exchange_rate: Decimal256,
 }
 
 #[derive(Clone, Default)]
@@ -141,6 +144,11 @@ pub enum QueryMsg {
         address: String,
         timestamp: Option<u64>,
     },
+
+    EpochState {
+        block_height: Option<u64>,
+        distributed_interest: Option<Uint256>,
+    },
 }
 
 impl WasmMockQuerier {
@@ -224,6 +232,14 @@ impl WasmMockQuerier {
                     ))
                 }
 
+                Ok(QueryMsg::EpochState { .. }) => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&EpochStateResponse {
+                        exchange_rate: self.exchange_rate,
+                        aterra_supply: Uint256::one(),
+                    })
+                    .unwrap(),
+                )),
+
                 Ok(QueryMsg::State { .. }) => {
                     let balances: &HashMap<String, Uint128> =
                         match self.token_querier.balances.get(contract_addr) {
@@ -295,6 +311,7 @@ impl WasmMockQuerier {
             token_querier: TokenQuerier::default(),
             tax_querier: TaxQuerier::default(),
             terraswap_factory_querier: TerraswapFactoryQuerier::default(),
+            exchange_rate: Decimal256::one(),
         }
     }
 
@@ -312,4 +329,9 @@ impl WasmMockQuerier {
     pub fn with_terraswap_pairs(&mut self, pairs: &[(&String, &String)]) {
         self.terraswap_factory_querier = TerraswapFactoryQuerier::new(pairs);
     }
+
+    // configure the anchor exchange rate
+    pub fn with_exchange_rate(&mut self, exchange_rate: Decimal256) {
+        self.exchange_rate = exchange_rate;
+    }
 }
@@ -0,0 +1,232 @@
+use cosmwasm_std::{
+    attr, to_vec, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+};
+use glow_protocol::gov::{
+    CommitteeSignature, OffchainCommitteeResponse, OffchainProposalResponse,
+    OffchainVoteAttestation, PollAction,
+};
+use serde::Serialize;
+use sha3::{Digest, Keccak256};
+
+use crate::actions::{apply_poll_action, validate_poll_action};
+use crate::error::ContractError;
+use crate::state::{
+    config_read, Config, OffchainCommittee, OffchainProposal, OFFCHAIN_COMMITTEE,
+    OFFCHAIN_PROPOSALS,
+};
+
+/// Payload signed by the committee, binding the attestation to this contract and chain
+#[derive(Serialize)]
+struct AttestationPayload<'a> {
+    chain_id: &'a str,
+    contract: &'a str,
+    proposal_hash: &'a str,
+    attestation: &'a OffchainVoteAttestation,
+}
+
+/// Json encoded attestation payload, whose keccak256 hash is what gets signed
+pub fn attestation_payload(
+    env: &Env,
+    proposal_hash: &str,
+    attestation: &OffchainVoteAttestation,
+) -> StdResult<Vec<u8>> {
+    to_vec(&AttestationPayload {
+        chain_id: &env.block.chain_id,
+        contract: env.contract.address.as_str(),
+        proposal_hash,
+        attestation,
+    })
+}
+
+/// Hex encoded keccak256 hash of the json encoded actions of an off-chain proposal
+pub fn offchain_proposal_hash(actions: &[PollAction]) -> StdResult<String> {
+    Ok(hex::encode(Keccak256::digest(&to_vec(actions)?)))
+}
+
+fn assert_owner(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+/// Replaces the committee. Attestations signed by the removed members can't be used anymore
+pub fn execute_update_offchain_committee(
+    deps: DepsMut,
+    info: MessageInfo,
+    members: Vec<Binary>,
+    min_signatures: u32,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.as_ref(), &info)?;
+
+    // compressed or uncompressed secp256k1 public keys
+    for (index, member) in members.iter().enumerate() {
+        if (member.len() != 33 && member.len() != 65) || members[..index].contains(member) {
+            return Err(ContractError::InvalidOffchainCommittee {});
+        }
+    }
+    if min_signatures == 0 || min_signatures as usize > members.len() {
+        return Err(ContractError::InvalidOffchainCommittee {});
+    }
+
+    OFFCHAIN_COMMITTEE.save(
+        deps.storage,
+        &OffchainCommittee {
+            members: members.clone(),
+            min_signatures,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_offchain_committee"),
+        attr("members", members.len().to_string()),
+        attr("min_signatures", min_signatures.to_string()),
+    ]))
+}
+
+pub fn execute_register_offchain_proposal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_hash: String,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.as_ref(), &info)?;
+
+    let key = match hex::decode(&proposal_hash) {
+        Ok(key) if key.len() == 32 => key,
+        _ => return Err(ContractError::InvalidOffchainProposalHash {}),
+    };
+    let proposal_hash = hex::encode(&key);
+
+    if OFFCHAIN_PROPOSALS.has(deps.storage, &key) {
+        return Err(ContractError::OffchainProposalAlreadyRegistered(
+            proposal_hash,
+        ));
+    }
+
+    OFFCHAIN_PROPOSALS.save(
+        deps.storage,
+        &key,
+        &OffchainProposal {
+            registered_at: env.block.time.seconds(),
+            executed: false,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_offchain_proposal"),
+        attr("proposal_hash", proposal_hash),
+    ]))
+}
+
+/// Executes the actions of a registered proposal whose off-chain vote passed, as
+/// attested by enough committee members. Only lotto config updates and messages to
+/// whitelisted contracts can be executed, anything else requires an on-chain poll
+pub fn execute_offchain_proposal(
+    deps: DepsMut,
+    env: Env,
+    actions: Vec<PollAction>,
+    attestation: OffchainVoteAttestation,
+    signatures: Vec<CommitteeSignature>,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    let committee = OFFCHAIN_COMMITTEE
+        .may_load(deps.storage)?
+        .ok_or(ContractError::OffchainCommitteeNotSet {})?;
+
+    let proposal_hash = offchain_proposal_hash(&actions)?;
+    let key = hex::decode(&proposal_hash).unwrap();
+    let mut proposal = OFFCHAIN_PROPOSALS
+        .may_load(deps.storage, &key)?
+        .ok_or_else(|| ContractError::OffchainProposalNotFound(proposal_hash.clone()))?;
+    if proposal.executed {
+        return Err(ContractError::OffchainProposalAlreadyExecuted(
+            proposal_hash,
+        ));
+    }
+
+    // Every signature must come from a distinct member
+    let digest = Keccak256::digest(&attestation_payload(&env, &proposal_hash, &attestation)?);
+    let mut signers: Vec<&Binary> = vec![];
+    for signature in signatures.iter() {
+        if !committee.members.contains(&signature.public_key)
+            || signers.contains(&&signature.public_key)
+            || !deps
+                .api
+                .secp256k1_verify(&digest, &signature.signature, &signature.public_key)
+                .unwrap_or(false)
+        {
+            return Err(ContractError::InvalidCommitteeSignature {});
+        }
+        signers.push(&signature.public_key);
+    }
+    if (signers.len() as u32) < committee.min_signatures {
+        return Err(ContractError::InsufficientCommitteeSignatures {
+            required: committee.min_signatures,
+            received: signers.len() as u32,
+        });
+    }
+
+    // Same quorum and threshold as on-chain polls
+    let tallied_weight = attestation.yes_votes + attestation.no_votes;
+    if tallied_weight.is_zero()
+        || attestation.total_voting_power.is_zero()
+        || Decimal::from_ratio(tallied_weight, attestation.total_voting_power) < config.quorum
+        || Decimal::from_ratio(attestation.yes_votes, tallied_weight) <= config.threshold
+    {
+        return Err(ContractError::OffchainVoteNotPassed {});
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for action in actions {
+        match action {
+            PollAction::UpdateLottoConfig { .. } | PollAction::Execute { .. } => {}
+            _ => return Err(ContractError::OffchainActionNotAllowed {}),
+        }
+        validate_poll_action(deps.as_ref(), &action)?;
+        if let Some(msg) = apply_poll_action(deps.storage, action)? {
+            messages.push(msg);
+        }
+    }
+
+    proposal.executed = true;
+    OFFCHAIN_PROPOSALS.save(deps.storage, &key, &proposal)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "execute_offchain_proposal"),
+        attr("proposal_hash", proposal_hash),
+        attr("signatures", signers.len().to_string()),
+    ]))
+}
+
+pub fn query_offchain_committee(deps: Deps) -> Result<OffchainCommitteeResponse, ContractError> {
+    let committee = OFFCHAIN_COMMITTEE
+        .may_load(deps.storage)?
+        .ok_or(ContractError::OffchainCommitteeNotSet {})?;
+
+    Ok(OffchainCommitteeResponse {
+        members: committee.members,
+        min_signatures: committee.min_signatures,
+    })
+}
+
+pub fn query_offchain_proposal(
+    deps: Deps,
+    proposal_hash: String,
+) -> Result<OffchainProposalResponse, ContractError> {
+    let key = match hex::decode(&proposal_hash) {
+        Ok(key) if key.len() == 32 => key,
+        _ => return Err(ContractError::InvalidOffchainProposalHash {}),
+    };
+    let proposal_hash = hex::encode(&key);
+    let proposal = OFFCHAIN_PROPOSALS
+        .may_load(deps.storage, &key)?
+        .ok_or_else(|| ContractError::OffchainProposalNotFound(proposal_hash.clone()))?;
+
+    Ok(OffchainProposalResponse {
+        proposal_hash,
+        registered_at: proposal.registered_at,
+        executed: proposal.executed,
+    })
+}
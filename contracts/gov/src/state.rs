@@ -1,13 +1,14 @@
-use cosmwasm_std::{Binary, CanonicalAddr, Decimal, StdResult, Storage, Uint128};
+use cosmwasm_std::{Addr, Binary, CanonicalAddr, Decimal, Order, StdResult, Storage, Uint128};
 use cosmwasm_storage::{
     bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
     Singleton,
 };
+use cw_storage_plus::{Bound, Item, Map, U64Key};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use glow_protocol::common::OrderBy;
-use glow_protocol::gov::{PollStatus, VoterInfo};
+use glow_protocol::common::{OrderBy, Pagination};
+use glow_protocol::gov::{PollAction, PollStatus, VoterInfo};
 use std::cmp::Ordering;
 
 static KEY_CONFIG: &[u8] = b"config";
@@ -18,6 +19,34 @@ static PREFIX_POLL_VOTER: &[u8] = b"poll_voter";
 static PREFIX_POLL: &[u8] = b"poll";
 static PREFIX_BANK: &[u8] = b"bank";
 
+// Delegate of each delegator, checkpointed by block time. None marks an undelegation
+pub const DELEGATION_CHECKPOINTS: Map<(&Addr, U64Key), Option<Addr>> =
+    Map::new("delegation_checkpoints");
+// Contracts polls can send arbitrary messages to
+pub const EXECUTE_WHITELIST: Map<&Addr, bool> = Map::new("execute_whitelist");
+// Code ids each contract can be migrated to, approved by a WhitelistMigration poll
+pub const MIGRATION_WHITELIST: Map<(&Addr, U64Key), bool> = Map::new("migration_whitelist");
+// Delegators of each delegate, checkpointed by block time
+pub const DELEGATORS_CHECKPOINTS: Map<(&Addr, U64Key), Vec<Addr>> =
+    Map::new("delegators_checkpoints");
+// Committee attesting the results of off-chain votes
+pub const OFFCHAIN_COMMITTEE: Item<OffchainCommittee> = Item::new("offchain_committee");
+// Off-chain proposals by hash of their actions
+pub const OFFCHAIN_PROPOSALS: Map<&[u8], OffchainProposal> = Map::new("offchain_proposals");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OffchainCommittee {
+    // secp256k1 public keys of the members
+    pub members: Vec<Binary>,
+    pub min_signatures: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OffchainProposal {
+    pub registered_at: u64,
+    pub executed: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct OldConfig {
     pub owner: CanonicalAddr,
@@ -45,6 +74,17 @@ pub struct Config {
     pub expiration_period: u64,
     pub proposal_deposit: Uint128,
     pub snapshot_period: u64,
+    /// Anchor integration used by polls created with a UST deposit
+    #[serde(default)]
+    pub ust_deposit_config: Option<UstDepositConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UstDepositConfig {
+    pub anchor_contract: CanonicalAddr,
+    pub a_terra_contract: CanonicalAddr,
+    pub community_contract: CanonicalAddr,
+    pub proposal_deposit: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -74,10 +114,24 @@ pub struct Poll {
     pub description: String,
     pub link: Option<String>,
     pub execute_data: Option<Vec<ExecuteData>>,
+    /// Typed actions executed after execute_data
+    #[serde(default)]
+    pub actions: Option<Vec<PollAction>>,
     pub deposit_amount: Uint128,
     /// Total balance at the end poll
     pub total_balance_at_end_poll: Option<Uint128>,
     pub staked_amount: Option<Uint128>,
+    /// UST deposit held in Anchor while the poll is in progress
+    #[serde(default)]
+    pub ust_deposit: Option<UstDeposit>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UstDeposit {
+    /// UST principal returned to the proposer when quorum is reached
+    pub amount: Uint128,
+    /// aUST minted by Anchor for the deposit
+    pub aust_amount: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -155,21 +209,17 @@ pub fn poll_voter_read(storage: &dyn Storage, poll_id: u64) -> ReadonlyBucket<Vo
 pub fn read_poll_voters<'a>(
     storage: &'a dyn Storage,
     poll_id: u64,
-    start_after: Option<CanonicalAddr>,
-    limit: Option<u32>,
+    pagination: &Pagination<CanonicalAddr>,
     order_by: Option<OrderBy>,
 ) -> StdResult<Vec<(CanonicalAddr, VoterInfo)>> {
-    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let (start, end, order_by) = match order_by {
-        Some(OrderBy::Asc) => (calc_range_start_addr(start_after), None, OrderBy::Asc),
-        _ => (None, calc_range_end_addr(start_after), OrderBy::Desc),
-    };
+    let order = order_by.unwrap_or(OrderBy::Desc).into();
+    let (start, end) = pagination.raw_range_bounds(order);
 
     let voters: ReadonlyBucket<'a, VoterInfo> =
         ReadonlyBucket::multilevel(storage, &[PREFIX_POLL_VOTER, &poll_id.to_be_bytes()]);
     voters
-        .range(start.as_deref(), end.as_deref(), order_by.into())
-        .take(limit)
+        .range(start.as_deref(), end.as_deref(), order)
+        .take(pagination.limit)
         .map(|item| {
             let (k, v) = item?;
             Ok((CanonicalAddr::from(k), v))
@@ -177,20 +227,14 @@ pub fn read_poll_voters<'a>(
         .collect()
 }
 
-const MAX_LIMIT: u32 = 30;
-const DEFAULT_LIMIT: u32 = 10;
 pub fn read_polls<'a>(
     storage: &'a dyn Storage,
     filter: Option<PollStatus>,
-    start_after: Option<u64>,
-    limit: Option<u32>,
+    pagination: &Pagination<u64>,
     order_by: Option<OrderBy>,
 ) -> StdResult<Vec<Poll>> {
-    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let (start, end, order_by) = match order_by {
-        Some(OrderBy::Asc) => (calc_range_start(start_after), None, OrderBy::Asc),
-        _ => (None, calc_range_end(start_after), OrderBy::Desc),
-    };
+    let order = order_by.unwrap_or(OrderBy::Desc).into();
+    let (start, end) = pagination.raw_range_bounds(order);
 
     if let Some(status) = filter {
         let poll_indexer: ReadonlyBucket<'a, bool> = ReadonlyBucket::multilevel(
@@ -198,8 +242,8 @@ pub fn read_polls<'a>(
             &[PREFIX_POLL_INDEXER, status.to_string().as_bytes()],
         );
         poll_indexer
-            .range(start.as_deref(), end.as_deref(), order_by.into())
-            .take(limit)
+            .range(start.as_deref(), end.as_deref(), order)
+            .take(pagination.limit)
             .map(|item| {
                 let (k, _) = item?;
                 poll_read(storage).load(&k)
@@ -209,8 +253,8 @@ pub fn read_polls<'a>(
         let polls: ReadonlyBucket<'a, Poll> = ReadonlyBucket::new(storage, PREFIX_POLL);
 
         polls
-            .range(start.as_deref(), end.as_deref(), order_by.into())
-            .take(limit)
+            .range(start.as_deref(), end.as_deref(), order)
+            .take(pagination.limit)
             .map(|item| {
                 let (_, v) = item?;
                 Ok(v)
@@ -227,30 +271,36 @@ pub fn bank_read(storage: &dyn Storage) -> ReadonlyBucket<TokenManager> {
     bucket_read(storage, PREFIX_BANK)
 }
 
-// this will set the first key after the provided key, by appending a 1 byte
-fn calc_range_start(start_after: Option<u64>) -> Option<Vec<u8>> {
-    start_after.map(|id| {
-        let mut v = id.to_be_bytes().to_vec();
-        v.push(1);
-        v
-    })
+/// Returns the delegate of delegator at timestamp, or the current one if timestamp is None
+pub fn read_delegate_at(
+    storage: &dyn Storage,
+    delegator: &Addr,
+    timestamp: Option<u64>,
+) -> StdResult<Option<Addr>> {
+    let end = timestamp.map(|t| Bound::Inclusive(t.to_be_bytes().to_vec()));
+    DELEGATION_CHECKPOINTS
+        .prefix(delegator)
+        .range(storage, None, end, Order::Descending)
+        .next()
+        .transpose()
+        .map(|checkpoint| checkpoint.and_then(|(_, delegate)| delegate))
 }
 
-// this will set the first key after the provided key, by appending a 1 byte
-fn calc_range_end(start_after: Option<u64>) -> Option<Vec<u8>> {
-    start_after.map(|id| id.to_be_bytes().to_vec())
-}
-
-// this will set the first key after the provided key, by appending a 1 byte
-fn calc_range_start_addr(start_after: Option<CanonicalAddr>) -> Option<Vec<u8>> {
-    start_after.map(|addr| {
-        let mut v = addr.as_slice().to_vec();
-        v.push(1);
-        v
-    })
-}
-
-// this will set the first key after the provided key, by appending a 1 byte
-fn calc_range_end_addr(start_after: Option<CanonicalAddr>) -> Option<Vec<u8>> {
-    start_after.map(|addr| addr.as_slice().to_vec())
+/// Returns the delegators of delegate at timestamp, or the current ones if timestamp is None
+pub fn read_delegators_at(
+    storage: &dyn Storage,
+    delegate: &Addr,
+    timestamp: Option<u64>,
+) -> StdResult<Vec<Addr>> {
+    let end = timestamp.map(|t| Bound::Inclusive(t.to_be_bytes().to_vec()));
+    DELEGATORS_CHECKPOINTS
+        .prefix(delegate)
+        .range(storage, None, end, Order::Descending)
+        .next()
+        .transpose()
+        .map(|checkpoint| {
+            checkpoint
+                .map(|(_, delegators)| delegators)
+                .unwrap_or_default()
+        })
 }
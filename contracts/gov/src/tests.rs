@@ -1,19 +1,30 @@
 use crate::contract::{execute, instantiate, query};
 use crate::error::ContractError;
 use crate::mock_querier::mock_dependencies;
-use crate::state::{config_read, poll_voter_read, state_read, Config, State};
+use crate::offchain::{attestation_payload, offchain_proposal_hash};
+use crate::state::{config_read, poll_voter_read, state_read, Config, State, MIGRATION_WHITELIST};
 
+use cosmwasm_bignumber::Decimal256;
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    attr, coins, from_binary, to_binary, Addr, Api, CanonicalAddr, CosmosMsg, Decimal, Deps,
-    DepsMut, Env, Response, StdError, SubMsg, Timestamp, Uint128, WasmMsg,
+    attr, coins, from_binary, to_binary, Addr, Api, BankMsg, Binary, CanonicalAddr, CosmosMsg,
+    Decimal, Deps, DepsMut, Env, Response, StdError, SubMsg, Timestamp, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::U64Key;
 use glow_protocol::common::OrderBy;
+use glow_protocol::community::ExecuteMsg as CommunityExecuteMsg;
 use glow_protocol::gov::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PollExecuteMsg, PollResponse,
-    PollStatus, PollsResponse, QueryMsg, VoteOption, VoterInfo, VotersResponse, VotersResponseItem,
+    CommitteeSignature, ConfigResponse, Cw20HookMsg, DelegationResponse, DelegatorsResponse,
+    ExecuteMsg, ExecuteWhitelistResponse, InstantiateMsg, MigrationWhitelistResponse,
+    OffchainCommitteeResponse, OffchainProposalResponse, OffchainVoteAttestation, PollAction,
+    PollExecuteMsg, PollResponse, PollStatus, PollsResponse, QueryMsg, UstDepositResponse,
+    VoteOption, VoterInfo, VotersResponse, VotersResponseItem, VotingPowerResponse,
 };
+use glow_protocol::lotto::ExecuteMsg as LottoExecuteMsg;
+use k256::ecdsa::{signature::DigestSigner, Signature, SigningKey};
+use moneymarket::market::{Cw20HookMsg as AnchorHookMsg, ExecuteMsg as AnchorMsg};
+use sha3::{Digest, Keccak256};
 
 const VOTING_TOKEN: &str = "voting_token";
 const VE_TOKEN: &str = "ve_token";
@@ -57,6 +68,16 @@ fn mock_register_contracts(deps: DepsMut) {
         .expect("contract successfully executes RegisterContracts");
 }
 
+fn mock_whitelist_execute(deps: DepsMut, contract: &str) {
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::UpdateExecuteWhitelist {
+        add: Some(vec![contract.to_string()]),
+        remove: None,
+    };
+    let _res = execute(deps, mock_env(), info, msg)
+        .expect("contract successfully executes UpdateExecuteWhitelist");
+}
+
 fn mock_env_height(height: u64, time: u64) -> Env {
     let mut env = mock_env();
     env.block.height = height;
@@ -99,7 +120,8 @@ fn proper_initialization() {
             timelock_period: DEFAULT_TIMELOCK_PERIOD,
             expiration_period: DEFAULT_EXPIRATION_PERIOD,
             proposal_deposit: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
-            snapshot_period: DEFAULT_FIX_PERIOD
+            snapshot_period: DEFAULT_FIX_PERIOD,
+            ust_deposit_config: None,
         }
     );
 
@@ -337,6 +359,7 @@ fn fails_create_poll_invalid_deposit() {
             description: "TESTTEST".to_string(),
             link: None,
             execute_msgs: None,
+            actions: None,
         })
         .unwrap(),
     });
@@ -363,6 +386,7 @@ fn create_poll_msg(
             description,
             link,
             execute_msgs: execute_msg,
+            actions: None,
         })
         .unwrap(),
     })
@@ -393,6 +417,7 @@ fn query_polls() {
     let mut deps = mock_dependencies(&[]);
     mock_instantiate(deps.as_mut());
     mock_register_contracts(deps.as_mut());
+    mock_whitelist_execute(deps.as_mut(), VOTING_TOKEN);
     let env = mock_env_height(0, 10000);
     let info = mock_info(VOTING_TOKEN, &[]);
 
@@ -452,6 +477,7 @@ fn query_polls() {
     )
     .unwrap();
     let response: PollsResponse = from_binary(&res).unwrap();
+    assert_eq!(response.limit, 10);
     assert_eq!(
         response.polls,
         vec![
@@ -466,10 +492,12 @@ fn query_polls() {
                 link: Some("http://google.com".to_string()),
                 deposit_amount: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
                 execute_data: Some(execute_msgs.clone()),
+                actions: None,
                 yes_votes: Uint128::zero(),
                 no_votes: Uint128::zero(),
                 staked_amount: Some(Uint128::zero()),
                 total_balance_at_end_poll: None,
+                ust_deposit: None,
             },
             PollResponse {
                 id: 2u64,
@@ -482,10 +510,12 @@ fn query_polls() {
                 link: None,
                 deposit_amount: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
                 execute_data: None,
+                actions: None,
                 yes_votes: Uint128::zero(),
                 no_votes: Uint128::zero(),
                 staked_amount: Some(Uint128::zero()),
                 total_balance_at_end_poll: None,
+                ust_deposit: None,
             },
         ]
     );
@@ -515,10 +545,12 @@ fn query_polls() {
             link: None,
             deposit_amount: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
             execute_data: None,
+            actions: None,
             yes_votes: Uint128::zero(),
             no_votes: Uint128::zero(),
             staked_amount: Some(Uint128::zero()),
             total_balance_at_end_poll: None,
+            ust_deposit: None,
         },]
     );
 
@@ -547,10 +579,12 @@ fn query_polls() {
             link: Some("http://google.com".to_string()),
             deposit_amount: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
             execute_data: Some(execute_msgs),
+            actions: None,
             yes_votes: Uint128::zero(),
             no_votes: Uint128::zero(),
             staked_amount: Some(Uint128::zero()),
             total_balance_at_end_poll: None,
+            ust_deposit: None,
         }]
     );
 
@@ -579,10 +613,12 @@ fn query_polls() {
             link: None,
             deposit_amount: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
             execute_data: None,
+            actions: None,
             yes_votes: Uint128::zero(),
             no_votes: Uint128::zero(),
             staked_amount: Some(Uint128::zero()),
             total_balance_at_end_poll: None,
+            ust_deposit: None,
         },]
     );
 
@@ -666,6 +702,7 @@ fn happy_days_end_poll() {
     let mut deps = mock_dependencies(&coins(1000, VOTING_TOKEN));
     mock_instantiate(deps.as_mut());
     mock_register_contracts(deps.as_mut());
+    mock_whitelist_execute(deps.as_mut(), VOTING_TOKEN);
 
     deps.querier.with_token_balances(&[
         (
@@ -951,6 +988,7 @@ fn expire_poll() {
     let mut deps = mock_dependencies(&coins(1000, VOTING_TOKEN));
     mock_instantiate(deps.as_mut());
     mock_register_contracts(deps.as_mut());
+    mock_whitelist_execute(deps.as_mut(), VOTING_TOKEN);
 
     deps.querier.with_token_balances(&[
         (
@@ -1117,6 +1155,7 @@ fn end_poll_zero_quorum() {
     let mut deps = mock_dependencies(&coins(1000, VOTING_TOKEN));
     mock_instantiate(deps.as_mut());
     mock_register_contracts(deps.as_mut());
+    mock_whitelist_execute(deps.as_mut(), VOTING_TOKEN);
     let mut creator_env = mock_env_height(1000, 10000);
     let mut creator_info = mock_info(VOTING_TOKEN, &[]);
 
@@ -1766,6 +1805,7 @@ fn add_several_execute_msgs() {
     let mut deps = mock_dependencies(&[]);
     mock_instantiate(deps.as_mut());
     mock_register_contracts(deps.as_mut());
+    mock_whitelist_execute(deps.as_mut(), VOTING_TOKEN);
     let info = mock_info(VOTING_TOKEN, &[]);
     let env = mock_env_height(0, 10000);
 
@@ -1836,6 +1876,7 @@ fn execute_poll_with_order() {
     let mut deps = mock_dependencies(&coins(1000, VOTING_TOKEN));
     mock_instantiate(deps.as_mut());
     mock_register_contracts(deps.as_mut());
+    mock_whitelist_execute(deps.as_mut(), VOTING_TOKEN);
 
     deps.querier.with_token_balances(&[
         (
@@ -2041,6 +2082,422 @@ fn execute_poll_with_order() {
     );
 }
 
+#[test]
+fn execute_poll_with_actions() {
+    const POLL_START_HEIGHT: u64 = 1000;
+    let stake_amount = 1000;
+
+    let mut deps = mock_dependencies(&coins(1000, VOTING_TOKEN));
+    mock_instantiate(deps.as_mut());
+    mock_register_contracts(deps.as_mut());
+
+    deps.querier.with_token_balances(&[
+        (
+            &VOTING_TOKEN.to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
+            )],
+        ),
+        (
+            &VE_TOKEN.to_string(),
+            &[(
+                &TEST_VOTER.to_string(),
+                &Uint128::from(stake_amount as u128),
+            )],
+        ),
+    ]);
+
+    let create_poll_with_actions = |actions: Vec<PollAction>| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: TEST_CREATOR.to_string(),
+            amount: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
+            msg: to_binary(&Cw20HookMsg::CreatePoll {
+                title: "test".to_string(),
+                description: "test".to_string(),
+                link: None,
+                execute_msgs: None,
+                actions: Some(actions),
+            })
+            .unwrap(),
+        })
+    };
+
+    let mut creator_env = mock_env_height(POLL_START_HEIGHT, 10000);
+    let creator_info = mock_info(VOTING_TOKEN, &[]);
+
+    let execute_action = PollAction::Execute {
+        contract: "staking".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Burn {
+            amount: Uint128::new(10),
+        })
+        .unwrap(),
+    };
+
+    // Arbitrary messages can only be sent to whitelisted contracts
+    let res = execute(
+        deps.as_mut(),
+        creator_env.clone(),
+        creator_info.clone(),
+        create_poll_with_actions(vec![execute_action.clone()]),
+    );
+    match res {
+        Err(ContractError::ContractNotWhitelisted(contract)) => assert_eq!(contract, "staking"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // Legacy execute_msgs are held to the same whitelist
+    let res = execute(
+        deps.as_mut(),
+        creator_env.clone(),
+        creator_info.clone(),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: TEST_CREATOR.to_string(),
+            amount: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
+            msg: to_binary(&Cw20HookMsg::CreatePoll {
+                title: "test".to_string(),
+                description: "test".to_string(),
+                link: None,
+                execute_msgs: Some(vec![PollExecuteMsg {
+                    order: 1,
+                    contract: "staking".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Burn {
+                        amount: Uint128::new(10),
+                    })
+                    .unwrap(),
+                }]),
+                actions: None,
+            })
+            .unwrap(),
+        }),
+    );
+    match res {
+        Err(ContractError::ContractNotWhitelisted(contract)) => assert_eq!(contract, "staking"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // Only lotto config messages can be sent with UpdateLottoConfig
+    let res = execute(
+        deps.as_mut(),
+        creator_env.clone(),
+        creator_info.clone(),
+        create_poll_with_actions(vec![PollAction::UpdateLottoConfig {
+            lotto_contract: "lotto".to_string(),
+            msg: Box::new(LottoExecuteMsg::Claim { recipient: None }),
+        }]),
+    );
+    match res {
+        Err(ContractError::InvalidLottoConfigAction {}) => {}
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // Only the owner can update the whitelist
+    let msg = ExecuteMsg::UpdateExecuteWhitelist {
+        add: Some(vec!["staking".to_string()]),
+        remove: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_VOTER, &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+    let res: ExecuteWhitelistResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ExecuteWhitelist {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.contracts, vec!["staking".to_string()]);
+
+    // Migration of the lotto to code id 42 was whitelisted by an earlier poll
+    MIGRATION_WHITELIST
+        .save(
+            deps.as_mut().storage,
+            (&Addr::unchecked("lotto"), U64Key::from(42u64)),
+            &true,
+        )
+        .unwrap();
+
+    let actions = vec![
+        PollAction::CommunitySpend {
+            community_contract: "community".to_string(),
+            recipient: "lotto".to_string(),
+            amount: Uint128::new(100),
+        },
+        PollAction::UpdateLottoConfig {
+            lotto_contract: "lotto".to_string(),
+            msg: Box::new(LottoExecuteMsg::UpdateBuybackConfig {
+                buyback_config: None,
+            }),
+        },
+        PollAction::MigrateContract {
+            contract: "lotto".to_string(),
+            new_code_id: 42,
+            msg: to_binary(&"{}").unwrap(),
+        },
+        execute_action,
+    ];
+    execute(
+        deps.as_mut(),
+        creator_env.clone(),
+        creator_info,
+        create_poll_with_actions(actions.clone()),
+    )
+    .unwrap();
+
+    let res: PollResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap())
+            .unwrap();
+    assert_eq!(res.actions, Some(actions));
+
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+    };
+    execute(
+        deps.as_mut(),
+        creator_env.clone(),
+        mock_info(TEST_VOTER, &[]),
+        msg,
+    )
+    .unwrap();
+
+    creator_env.block.height += DEFAULT_VOTING_PERIOD;
+    let creator_info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    execute(
+        deps.as_mut(),
+        creator_env.clone(),
+        creator_info.clone(),
+        msg,
+    )
+    .unwrap();
+
+    // All the actions are executed in order in the same response
+    creator_env.block.height += DEFAULT_TIMELOCK_PERIOD;
+    let msg = ExecuteMsg::ExecutePoll { poll_id: 1 };
+    let res = execute(deps.as_mut(), creator_env, creator_info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "community".to_string(),
+                msg: to_binary(&CommunityExecuteMsg::Spend {
+                    recipient: "lotto".to_string(),
+                    amount: Uint128::new(100),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "lotto".to_string(),
+                msg: to_binary(&LottoExecuteMsg::UpdateBuybackConfig {
+                    buyback_config: None,
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Migrate {
+                contract_addr: "lotto".to_string(),
+                new_code_id: 42,
+                msg: to_binary(&"{}").unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "staking".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Burn {
+                    amount: Uint128::new(10),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+}
+
+#[test]
+fn execute_poll_with_migration() {
+    const POLL_START_HEIGHT: u64 = 1000;
+    let stake_amount = 1000;
+
+    let mut deps = mock_dependencies(&coins(1000, VOTING_TOKEN));
+    mock_instantiate(deps.as_mut());
+    mock_register_contracts(deps.as_mut());
+
+    deps.querier.with_token_balances(&[
+        (
+            &VOTING_TOKEN.to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
+            )],
+        ),
+        (
+            &VE_TOKEN.to_string(),
+            &[(
+                &TEST_VOTER.to_string(),
+                &Uint128::from(stake_amount as u128),
+            )],
+        ),
+    ]);
+
+    let create_poll_with_actions = |actions: Vec<PollAction>| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: TEST_CREATOR.to_string(),
+            amount: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
+            msg: to_binary(&Cw20HookMsg::CreatePoll {
+                title: "test".to_string(),
+                description: "test".to_string(),
+                link: None,
+                execute_msgs: None,
+                actions: Some(actions),
+            })
+            .unwrap(),
+        })
+    };
+
+    let mut env = mock_env_height(POLL_START_HEIGHT, 10000);
+    let creator_info = mock_info(VOTING_TOKEN, &[]);
+
+    let migrate_action = PollAction::MigrateContract {
+        contract: "lotto".to_string(),
+        new_code_id: 42,
+        msg: to_binary(&"{}").unwrap(),
+    };
+
+    // The code id must be whitelisted by a poll before the migration is proposed
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        creator_info.clone(),
+        create_poll_with_actions(vec![
+            PollAction::WhitelistMigration {
+                contract: "lotto".to_string(),
+                code_id: 42,
+            },
+            migrate_action.clone(),
+        ]),
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::MigrationNotWhitelisted {
+            contract: "lotto".to_string(),
+            code_id: 42,
+        })
+    );
+
+    let pass_poll = |mut deps: DepsMut, env: &mut Env, actions: Vec<PollAction>, poll_id: u64| {
+        execute(
+            deps.branch(),
+            env.clone(),
+            creator_info.clone(),
+            create_poll_with_actions(actions),
+        )
+        .unwrap();
+        execute(
+            deps.branch(),
+            env.clone(),
+            mock_info(TEST_VOTER, &[]),
+            ExecuteMsg::CastVote {
+                poll_id,
+                vote: VoteOption::Yes,
+            },
+        )
+        .unwrap();
+        env.block.height += DEFAULT_VOTING_PERIOD;
+        execute(
+            deps.branch(),
+            env.clone(),
+            mock_info(TEST_CREATOR, &[]),
+            ExecuteMsg::EndPoll { poll_id },
+        )
+        .unwrap();
+        env.block.height += DEFAULT_TIMELOCK_PERIOD;
+        execute(
+            deps,
+            env.clone(),
+            mock_info(TEST_CREATOR, &[]),
+            ExecuteMsg::ExecutePoll { poll_id },
+        )
+        .unwrap()
+    };
+
+    // Whitelisting only updates the gov state
+    let res = pass_poll(
+        deps.as_mut(),
+        &mut env,
+        vec![PollAction::WhitelistMigration {
+            contract: "lotto".to_string(),
+            code_id: 42,
+        }],
+        1,
+    );
+    assert!(res.messages.is_empty());
+
+    let res: MigrationWhitelistResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::MigrationWhitelist {
+                contract: "lotto".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.code_ids, vec![42]);
+
+    let res = pass_poll(deps.as_mut(), &mut env, vec![migrate_action.clone()], 2);
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr: "lotto".to_string(),
+            new_code_id: 42,
+            msg: to_binary(&"{}").unwrap(),
+        }))]
+    );
+
+    // The approval is consumed by the migration
+    let res: MigrationWhitelistResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::MigrationWhitelist {
+                contract: "lotto".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(res.code_ids.is_empty());
+
+    let res = execute(
+        deps.as_mut(),
+        env,
+        creator_info,
+        create_poll_with_actions(vec![migrate_action]),
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::MigrationNotWhitelisted {
+            contract: "lotto".to_string(),
+            code_id: 42,
+        })
+    );
+}
+
 #[test]
 fn happy_days_end_poll_with_controlled_quorum() {
     const POLL_START_HEIGHT: u64 = 1000;
@@ -2050,6 +2507,7 @@ fn happy_days_end_poll_with_controlled_quorum() {
     let mut deps = mock_dependencies(&coins(1000, VOTING_TOKEN));
     mock_instantiate(deps.as_mut());
     mock_register_contracts(deps.as_mut());
+    mock_whitelist_execute(deps.as_mut(), VOTING_TOKEN);
 
     deps.querier.with_token_balances(&[
         (
@@ -2219,3 +2677,711 @@ fn happy_days_end_poll_with_controlled_quorum() {
 
     // assert_eq!(actual_staked_weight.u128(), (10 * stake_amount))
 }
+
+#[test]
+fn delegate_voting_power() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    mock_register_contracts(deps.as_mut());
+
+    deps.querier.with_token_balances(&[(
+        &VE_TOKEN.to_string(),
+        &[
+            (&TEST_VOTER.to_string(), &Uint128::from(1000u128)),
+            (&TEST_VOTER_2.to_string(), &Uint128::from(500u128)),
+            (&TEST_CREATOR.to_string(), &Uint128::from(200u128)),
+        ],
+    )]);
+
+    // Self delegation is rejected
+    let res = execute(
+        deps.as_mut(),
+        mock_env_height(0, 10000),
+        mock_info(TEST_VOTER_2, &[]),
+        ExecuteMsg::Delegate {
+            to: TEST_VOTER_2.to_string(),
+        },
+    );
+    match res {
+        Err(ContractError::CannotDelegateToSelf {}) => {}
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // Undelegating without a delegation is rejected
+    let res = execute(
+        deps.as_mut(),
+        mock_env_height(0, 10000),
+        mock_info(TEST_VOTER_2, &[]),
+        ExecuteMsg::Undelegate {},
+    );
+    match res {
+        Err(ContractError::NotDelegated {}) => {}
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // Addresses without voting power can't delegate
+    let res = execute(
+        deps.as_mut(),
+        mock_env_height(0, 10000),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::Delegate {
+            to: TEST_VOTER.to_string(),
+        },
+    );
+    match res {
+        Err(ContractError::NoVotingPower {}) => {}
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // voter2 delegates to voter1
+    let res = execute(
+        deps.as_mut(),
+        mock_env_height(0, 10000),
+        mock_info(TEST_VOTER_2, &[]),
+        ExecuteMsg::Delegate {
+            to: TEST_VOTER.to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "delegate"),
+            attr("delegator", TEST_VOTER_2),
+            attr("delegate", TEST_VOTER),
+        ]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_height(0, 10000),
+        mock_info(TEST_VOTER_2, &[]),
+        ExecuteMsg::Delegate {
+            to: TEST_CREATOR.to_string(),
+        },
+    );
+    match res {
+        Err(ContractError::AlreadyDelegated {}) => {}
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // voter1 can't delegate onwards while holding delegated power
+    let res = execute(
+        deps.as_mut(),
+        mock_env_height(0, 10000),
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::Delegate {
+            to: TEST_CREATOR.to_string(),
+        },
+    );
+    match res {
+        Err(ContractError::DelegationChain {}) => {}
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let res: DelegationResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Delegation {
+                address: TEST_VOTER_2.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.delegate, Some(TEST_VOTER.to_string()));
+
+    let res: DelegatorsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Delegators {
+                address: TEST_VOTER.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.delegators, vec![TEST_VOTER_2.to_string()]);
+
+    let res: VotingPowerResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::VotingPower {
+                address: TEST_VOTER.to_string(),
+                timestamp: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        VotingPowerResponse {
+            balance: Uint128::from(1000u128),
+            delegated_balance: Uint128::from(500u128),
+            voting_power: Uint128::from(1500u128),
+        }
+    );
+
+    // Create a poll after the delegation
+    let msg = create_poll_msg("test".to_string(), "test".to_string(), None, None);
+    execute(
+        deps.as_mut(),
+        mock_env_height(10, 10010),
+        mock_info(VOTING_TOKEN, &[]),
+        msg,
+    )
+    .unwrap();
+
+    // The delegator can't vote
+    let res = execute(
+        deps.as_mut(),
+        mock_env_height(11, 10011),
+        mock_info(TEST_VOTER_2, &[]),
+        ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote: VoteOption::Yes,
+        },
+    );
+    match res {
+        Err(ContractError::VotingPowerDelegated {}) => {}
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // Undelegating after the poll started doesn't restore the vote on that poll
+    execute(
+        deps.as_mut(),
+        mock_env_height(12, 10012),
+        mock_info(TEST_VOTER_2, &[]),
+        ExecuteMsg::Undelegate {},
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_height(13, 10013),
+        mock_info(TEST_VOTER_2, &[]),
+        ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote: VoteOption::Yes,
+        },
+    );
+    match res {
+        Err(ContractError::VotingPowerDelegated {}) => {}
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // The delegate still votes with the power held at poll start
+    execute(
+        deps.as_mut(),
+        mock_env_height(13, 10013),
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote: VoteOption::Yes,
+        },
+    )
+    .unwrap();
+
+    let res: PollResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap())
+            .unwrap();
+    assert_eq!(res.yes_votes, Uint128::from(1500u128));
+
+    // The current delegation state is cleared
+    let res: DelegatorsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Delegators {
+                address: TEST_VOTER.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(res.delegators.is_empty());
+
+    // A delegate can refuse a delegation
+    execute(
+        deps.as_mut(),
+        mock_env_height(14, 10014),
+        mock_info(TEST_VOTER_2, &[]),
+        ExecuteMsg::Delegate {
+            to: TEST_VOTER.to_string(),
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_height(15, 10015),
+        mock_info(TEST_CREATOR, &[]),
+        ExecuteMsg::RemoveDelegator {
+            delegator: TEST_VOTER_2.to_string(),
+        },
+    );
+    match res {
+        Err(ContractError::NotDelegated {}) => {}
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_height(15, 10015),
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::RemoveDelegator {
+            delegator: TEST_VOTER_2.to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "remove_delegator"),
+            attr("delegator", TEST_VOTER_2),
+            attr("delegate", TEST_VOTER),
+        ]
+    );
+
+    let res: DelegationResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Delegation {
+                address: TEST_VOTER_2.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.delegate, None);
+
+    let res: DelegatorsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Delegators {
+                address: TEST_VOTER.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(res.delegators.is_empty());
+}
+
+#[test]
+fn ust_deposit_poll() {
+    const ANCHOR: &str = "anchor";
+    const A_TERRA: &str = "aterra";
+    const COMMUNITY: &str = "community";
+    const UST_DEPOSIT: u128 = 1_000_000_000u128;
+
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    mock_register_contracts(deps.as_mut());
+
+    deps.querier.with_token_balances(&[(
+        &VE_TOKEN.to_string(),
+        &[(&TEST_VOTER.to_string(), &Uint128::from(1000u128))],
+    )]);
+
+    let create_msg = ExecuteMsg::CreateUstPoll {
+        title: "test".to_string(),
+        description: "test".to_string(),
+        link: None,
+        execute_msgs: None,
+        actions: None,
+    };
+
+    // UST deposits are disabled by default
+    let res = execute(
+        deps.as_mut(),
+        mock_env_height(1000, 10000),
+        mock_info(TEST_CREATOR, &coins(UST_DEPOSIT, "uusd")),
+        create_msg.clone(),
+    );
+    match res {
+        Err(ContractError::UstDepositsNotEnabled {}) => {}
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let update_msg = ExecuteMsg::UpdateUstDepositConfig {
+        anchor_contract: ANCHOR.to_string(),
+        a_terra_contract: A_TERRA.to_string(),
+        community_contract: COMMUNITY.to_string(),
+        proposal_deposit: Uint128::from(UST_DEPOSIT),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_VOTER, &[]),
+        update_msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_CREATOR, &[]),
+        update_msg,
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_height(1000, 10000),
+        mock_info(TEST_CREATOR, &coins(UST_DEPOSIT - 1, "uusd")),
+        create_msg.clone(),
+    );
+    match res {
+        Err(ContractError::InsufficientUstProposalDeposit(amount)) => {
+            assert_eq!(amount, UST_DEPOSIT)
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // Deposit is sent to anchor
+    deps.querier
+        .with_exchange_rate(Decimal256::from_ratio(125u64, 100u64));
+    let res = execute(
+        deps.as_mut(),
+        mock_env_height(1000, 10000),
+        mock_info(TEST_CREATOR, &coins(UST_DEPOSIT, "uusd")),
+        create_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ANCHOR.to_string(),
+            funds: coins(UST_DEPOSIT, "uusd"),
+            msg: to_binary(&AnchorMsg::DepositStable {}).unwrap(),
+        }))]
+    );
+
+    let res: PollResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap())
+            .unwrap();
+    assert_eq!(res.deposit_amount, Uint128::zero());
+    assert_eq!(
+        res.ust_deposit,
+        Some(UstDepositResponse {
+            amount: Uint128::from(UST_DEPOSIT),
+            aust_amount: Uint128::from(800_000_000u128),
+        })
+    );
+
+    // A second poll which will not reach quorum
+    execute(
+        deps.as_mut(),
+        mock_env_height(1000, 10000),
+        mock_info(TEST_CREATOR, &coins(UST_DEPOSIT, "uusd")),
+        create_msg,
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env_height(1000, 10000),
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote: VoteOption::No,
+        },
+    )
+    .unwrap();
+
+    // Principal is refunded and the yield goes to the community contract
+    deps.querier
+        .with_exchange_rate(Decimal256::from_ratio(150u64, 100u64));
+    let end_env = mock_env_height(1000 + DEFAULT_VOTING_PERIOD, 10000);
+    let res = execute(
+        deps.as_mut(),
+        end_env.clone(),
+        mock_info(TEST_CREATOR, &[]),
+        ExecuteMsg::EndPoll { poll_id: 1 },
+    )
+    .unwrap();
+
+    let redeem_msg = SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: A_TERRA.to_string(),
+        funds: vec![],
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: ANCHOR.to_string(),
+            amount: Uint128::from(800_000_000u128),
+            msg: to_binary(&AnchorHookMsg::RedeemStable {}).unwrap(),
+        })
+        .unwrap(),
+    }));
+    assert_eq!(
+        res.messages,
+        vec![
+            redeem_msg.clone(),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: TEST_CREATOR.to_string(),
+                amount: coins(UST_DEPOSIT, "uusd"),
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: COMMUNITY.to_string(),
+                amount: coins(200_000_000u128, "uusd"),
+            })),
+        ]
+    );
+
+    // Without quorum the whole deposit goes to the community contract
+    let res = execute(
+        deps.as_mut(),
+        end_env,
+        mock_info(TEST_CREATOR, &[]),
+        ExecuteMsg::EndPoll { poll_id: 2 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            redeem_msg,
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: COMMUNITY.to_string(),
+                amount: coins(1_200_000_000u128, "uusd"),
+            })),
+        ]
+    );
+}
+
+#[test]
+fn execute_offchain_proposal() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    mock_register_contracts(deps.as_mut());
+
+    let keys: Vec<SigningKey> = (1u8..=3)
+        .map(|i| SigningKey::from_bytes(&[i; 32]).unwrap())
+        .collect();
+    let members: Vec<Binary> = keys
+        .iter()
+        .map(|key| Binary::from(key.verifying_key().to_bytes().to_vec()))
+        .collect();
+    let sign = |key: &SigningKey, proposal_hash: &str, attestation: &OffchainVoteAttestation| {
+        let signature: Signature = key.sign_digest(
+            Keccak256::new()
+                .chain(attestation_payload(&mock_env(), proposal_hash, attestation).unwrap()),
+        );
+        CommitteeSignature {
+            public_key: Binary::from(key.verifying_key().to_bytes().to_vec()),
+            signature: Binary::from(signature.as_ref().to_vec()),
+        }
+    };
+
+    // Only the owner can appoint the committee, two of three members must sign
+    let msg = ExecuteMsg::UpdateOffchainCommittee {
+        members: members.clone(),
+        min_signatures: 2,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_VOTER, &[]),
+        msg.clone(),
+    );
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_CREATOR, &[]),
+        ExecuteMsg::UpdateOffchainCommittee {
+            members: members.clone(),
+            min_signatures: 4,
+        },
+    );
+    assert_eq!(res, Err(ContractError::InvalidOffchainCommittee {}));
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+    let res: OffchainCommitteeResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::OffchainCommittee {}).unwrap())
+            .unwrap();
+    assert_eq!(res.members, members);
+    assert_eq!(res.min_signatures, 2);
+
+    let lotto_msg = LottoExecuteMsg::UpdateBuybackConfig {
+        buyback_config: None,
+    };
+    let actions = vec![PollAction::UpdateLottoConfig {
+        lotto_contract: "lotto".to_string(),
+        msg: Box::new(lotto_msg.clone()),
+    }];
+    let proposal_hash = offchain_proposal_hash(&actions).unwrap();
+    let attestation = OffchainVoteAttestation {
+        yes_votes: Uint128::new(400),
+        no_votes: Uint128::new(100),
+        total_voting_power: Uint128::new(1000),
+    };
+    let execute_msg = |attestation: &OffchainVoteAttestation, signers: &[&SigningKey]| {
+        ExecuteMsg::ExecuteOffchainProposal {
+            actions: actions.clone(),
+            attestation: attestation.clone(),
+            signatures: signers
+                .iter()
+                .map(|key| sign(key, &proposal_hash, attestation))
+                .collect(),
+        }
+    };
+
+    // The proposal must be registered first
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_VOTER, &[]),
+        execute_msg(&attestation, &[&keys[0], &keys[1]]),
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::OffchainProposalNotFound(
+            proposal_hash.clone()
+        ))
+    );
+
+    let msg = ExecuteMsg::RegisterOffchainProposal {
+        proposal_hash: proposal_hash.clone(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_VOTER, &[]),
+        msg.clone(),
+    );
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+    // Not enough, duplicated or foreign signatures are rejected
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_VOTER, &[]),
+        execute_msg(&attestation, &[&keys[0]]),
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::InsufficientCommitteeSignatures {
+            required: 2,
+            received: 1
+        })
+    );
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_VOTER, &[]),
+        execute_msg(&attestation, &[&keys[0], &keys[0]]),
+    );
+    assert_eq!(res, Err(ContractError::InvalidCommitteeSignature {}));
+    let outsider = SigningKey::from_bytes(&[9u8; 32]).unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_VOTER, &[]),
+        execute_msg(&attestation, &[&keys[0], &outsider]),
+    );
+    assert_eq!(res, Err(ContractError::InvalidCommitteeSignature {}));
+
+    // A signature over another result doesn't verify
+    let mut msg = execute_msg(&attestation, &[&keys[0], &keys[1]]);
+    if let ExecuteMsg::ExecuteOffchainProposal { attestation, .. } = &mut msg {
+        attestation.yes_votes = Uint128::new(450);
+    }
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_VOTER, &[]), msg);
+    assert_eq!(res, Err(ContractError::InvalidCommitteeSignature {}));
+
+    // The attested vote must reach quorum
+    let low_turnout = OffchainVoteAttestation {
+        yes_votes: Uint128::new(200),
+        no_votes: Uint128::new(50),
+        total_voting_power: Uint128::new(1000),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_VOTER, &[]),
+        execute_msg(&low_turnout, &[&keys[0], &keys[1]]),
+    );
+    assert_eq!(res, Err(ContractError::OffchainVoteNotPassed {}));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_VOTER, &[]),
+        execute_msg(&attestation, &[&keys[1], &keys[2]]),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "lotto".to_string(),
+            msg: to_binary(&lotto_msg).unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // A proposal is executed only once
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_VOTER, &[]),
+        execute_msg(&attestation, &[&keys[1], &keys[2]]),
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::OffchainProposalAlreadyExecuted(
+            proposal_hash.clone()
+        ))
+    );
+    let res: OffchainProposalResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::OffchainProposal {
+                proposal_hash: proposal_hash.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(res.executed);
+
+    // Community spends still require an on-chain poll
+    let actions = vec![PollAction::CommunitySpend {
+        community_contract: "community".to_string(),
+        recipient: TEST_VOTER.to_string(),
+        amount: Uint128::new(100),
+    }];
+    let proposal_hash = offchain_proposal_hash(&actions).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_CREATOR, &[]),
+        ExecuteMsg::RegisterOffchainProposal {
+            proposal_hash: proposal_hash.clone(),
+        },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::ExecuteOffchainProposal {
+            actions,
+            attestation: attestation.clone(),
+            signatures: vec![
+                sign(&keys[0], &proposal_hash, &attestation),
+                sign(&keys[1], &proposal_hash, &attestation),
+            ],
+        },
+    );
+    assert_eq!(res, Err(ContractError::OffchainActionNotAllowed {}));
+}
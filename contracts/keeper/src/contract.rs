@@ -0,0 +1,321 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::error::ContractError;
+use crate::state::{Config, Task, CONFIG, TASKS};
+
+use cosmwasm_std::{
+    attr, to_binary, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    StdResult, Uint128, WasmMsg,
+};
+
+use glow_protocol::keeper::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, KeeperTask, MigrateMsg, QueryMsg, TaskInfo,
+    TasksResponse,
+};
+use glow_protocol::lotto::{
+    CanExecuteResponse, EpochOperation, EpochOperationsResponse, ExecuteMsg as LottoExecuteMsg,
+    QueryMsg as LottoQueryMsg,
+};
+use glow_protocol::querier::deduct_tax;
+
+// Order in which a poke dispatches the tasks which are due
+pub const KEEPER_TASKS: [KeeperTask; 4] = [
+    KeeperTask::ExecuteLottery,
+    KeeperTask::ExecutePrize,
+    KeeperTask::EpochOperations,
+    KeeperTask::SweepExpired,
+];
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        lotto_contract: deps.api.addr_validate(&msg.lotto_contract)?,
+        bounty_denom: msg.bounty_denom,
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig {
+            owner,
+            lotto_contract,
+        } => update_config(deps, info, owner, lotto_contract),
+        ExecuteMsg::RegisterTask {
+            task,
+            interval,
+            bounty,
+        } => register_task(deps, info, task, interval, bounty),
+        ExecuteMsg::RemoveTask { task } => remove_task(deps, info, task),
+        ExecuteMsg::Poke {} => poke(deps, env, info),
+    }
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    lotto_contract: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+
+    if let Some(lotto_contract) = lotto_contract {
+        config.lotto_contract = deps.api.addr_validate(&lotto_contract)?;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+pub fn register_task(
+    deps: DepsMut,
+    info: MessageInfo,
+    task: KeeperTask,
+    interval: u64,
+    bounty: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Re-registering a task keeps the time of its last run
+    let last_run = TASKS
+        .may_load(deps.storage, task.as_str())?
+        .and_then(|task| task.last_run);
+
+    TASKS.save(
+        deps.storage,
+        task.as_str(),
+        &Task {
+            interval,
+            bounty,
+            last_run,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_task"),
+        attr("task", task.as_str()),
+        attr("interval", interval.to_string()),
+        attr("bounty", bounty.to_string()),
+    ]))
+}
+
+pub fn remove_task(
+    deps: DepsMut,
+    info: MessageInfo,
+    task: KeeperTask,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !TASKS.has(deps.storage, task.as_str()) {
+        return Err(ContractError::TaskNotRegistered {});
+    }
+
+    TASKS.remove(deps.storage, task.as_str());
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_task"),
+        attr("task", task.as_str()),
+    ]))
+}
+
+pub fn poke(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    let mut executed_tasks: Vec<&str> = vec![];
+    let mut total_bounty = Uint128::zero();
+
+    for keeper_task in KEEPER_TASKS.iter() {
+        let mut task = match TASKS.may_load(deps.storage, keeper_task.as_str())? {
+            Some(task) if task.is_scheduled(now) => task,
+            _ => continue,
+        };
+
+        // Skip the tasks which would fail on the lotto contract
+        let msg = match ready_task_msg(deps.as_ref(), &config, *keeper_task)? {
+            Some(msg) => msg,
+            None => continue,
+        };
+
+        task.last_run = Some(now);
+        TASKS.save(deps.storage, keeper_task.as_str(), &task)?;
+
+        msgs.push(msg);
+        executed_tasks.push(keeper_task.as_str());
+        total_bounty += task.bounty;
+    }
+
+    if msgs.is_empty() {
+        return Err(ContractError::NoTasksDue {});
+    }
+
+    // Pay the bounties out of the keeper balance, as far as it goes
+    let balance = deps
+        .querier
+        .query_balance(env.contract.address, config.bounty_denom.clone())?;
+    let bounty = std::cmp::min(total_bounty, balance.amount);
+
+    if !bounty.is_zero() {
+        msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: config.bounty_denom,
+                    amount: bounty,
+                },
+            )?],
+        }));
+    }
+
+    Ok(Response::new().add_messages(msgs).add_attributes(vec![
+        attr("action", "poke"),
+        attr("keeper", info.sender.to_string()),
+        attr("tasks", executed_tasks.join(",")),
+        attr("bounty", bounty.to_string()),
+    ]))
+}
+
+/// Message running the task on the lotto contract, None if the lotto
+/// contract reports it is not ready at the current block
+fn ready_task_msg(deps: Deps, config: &Config, task: KeeperTask) -> StdResult<Option<CosmosMsg>> {
+    let msg = match task {
+        KeeperTask::ExecuteLottery | KeeperTask::ExecutePrize => {
+            let (query, msg) = if task == KeeperTask::ExecuteLottery {
+                (
+                    LottoQueryMsg::CanExecuteLottery {},
+                    LottoExecuteMsg::ExecuteLottery {},
+                )
+            } else {
+                (
+                    LottoQueryMsg::CanExecutePrize {},
+                    LottoExecuteMsg::ExecutePrize { limit: None },
+                )
+            };
+
+            let res: CanExecuteResponse = deps
+                .querier
+                .query_wasm_smart(config.lotto_contract.to_string(), &query)?;
+            if !res.ready {
+                return Ok(None);
+            }
+
+            msg
+        }
+        KeeperTask::EpochOperations | KeeperTask::SweepExpired => {
+            let res: EpochOperationsResponse = deps.querier.query_wasm_smart(
+                config.lotto_contract.to_string(),
+                &LottoQueryMsg::EpochOperations {},
+            )?;
+
+            let operations: Vec<EpochOperation> = res
+                .operations
+                .into_iter()
+                .filter(|status| {
+                    status.ready
+                        && (status.operation == EpochOperation::SweepExpired)
+                            == (task == KeeperTask::SweepExpired)
+                })
+                .map(|status| status.operation)
+                .collect();
+            if operations.is_empty() {
+                return Ok(None);
+            }
+
+            LottoExecuteMsg::ExecuteEpochOps {
+                operations: Some(operations),
+            }
+        }
+    };
+
+    Ok(Some(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: config.lotto_contract.to_string(),
+        msg: to_binary(&msg)?,
+        funds: vec![],
+    })))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Tasks {} => to_binary(&query_tasks(deps, env)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        owner: config.owner.to_string(),
+        lotto_contract: config.lotto_contract.to_string(),
+        bounty_denom: config.bounty_denom,
+    })
+}
+
+pub fn query_tasks(deps: Deps, env: Env) -> StdResult<TasksResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+
+    let mut tasks = vec![];
+    for keeper_task in KEEPER_TASKS.iter() {
+        let task = match TASKS.may_load(deps.storage, keeper_task.as_str())? {
+            Some(task) => task,
+            None => continue,
+        };
+
+        let due = task.is_scheduled(now) && ready_task_msg(deps, &config, *keeper_task)?.is_some();
+
+        tasks.push(TaskInfo {
+            task: *keeper_task,
+            interval: task.interval,
+            bounty: task.bounty,
+            last_run: task.last_run,
+            due,
+        });
+    }
+
+    Ok(TasksResponse { tasks })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    Ok(Response::default())
+}
@@ -0,0 +1,10 @@
+pub mod contract;
+
+mod error;
+mod state;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(test)]
+mod mock_querier;
@@ -0,0 +1,181 @@
+#![allow(dead_code)]
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Coin, ContractResult, Decimal, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+};
+use cw0::Expiration;
+use glow_protocol::lotto::{
+    CanExecuteResponse, EpochOperation, EpochOperationStatus, EpochOperationsResponse,
+    QueryMsg as LottoQueryMsg,
+};
+
+use std::collections::HashMap;
+use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute};
+
+/// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
+/// this uses our CustomQuerier.
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+
+    OwnedDeps {
+        api: MockApi::default(),
+        storage: MockStorage::default(),
+        querier: custom_querier,
+    }
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<TerraQueryWrapper>,
+    tax_querier: TaxQuerier,
+    lotto_querier: LottoQuerier,
+}
+
+#[derive(Clone, Default)]
+pub struct TaxQuerier {
+    rate: Decimal,
+    // this lets us iterate over all pairs that match the first string
+    caps: HashMap<String, Uint128>,
+}
+
+impl TaxQuerier {
+    pub fn new(rate: Decimal, caps: &[(&String, &Uint128)]) -> Self {
+        TaxQuerier {
+            rate,
+            caps: caps_to_map(caps),
+        }
+    }
+}
+
+pub(crate) fn caps_to_map(caps: &[(&String, &Uint128)]) -> HashMap<String, Uint128> {
+    let mut owner_map: HashMap<String, Uint128> = HashMap::new();
+    for (denom, cap) in caps.iter() {
+        owner_map.insert(denom.to_string(), **cap);
+    }
+    owner_map
+}
+
+#[derive(Clone, Default)]
+pub struct LottoQuerier {
+    lottery_ready: bool,
+    prize_ready: bool,
+    // Epoch operations reported as ready
+    ready_operations: Vec<EpochOperation>,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        // MockQuerier doesn't support Custom, so we ignore it completely here
+        let request: QueryRequest<TerraQueryWrapper> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<TerraQueryWrapper>) -> QuerierResult {
+        match &request {
+            QueryRequest::Custom(TerraQueryWrapper { route, query_data }) => {
+                if route == &TerraRoute::Treasury {
+                    match query_data {
+                        TerraQuery::TaxRate {} => {
+                            let res = TaxRateResponse {
+                                rate: self.tax_querier.rate,
+                            };
+                            SystemResult::Ok(ContractResult::from(to_binary(&res)))
+                        }
+                        TerraQuery::TaxCap { denom } => {
+                            let cap = self
+                                .tax_querier
+                                .caps
+                                .get(denom)
+                                .copied()
+                                .unwrap_or_default();
+                            let res = TaxCapResponse { cap };
+                            SystemResult::Ok(ContractResult::from(to_binary(&res)))
+                        }
+                        _ => panic!("DO NOT ENTER HERE"),
+                    }
+                } else {
+                    panic!("DO NOT ENTER HERE")
+                }
+            }
+            QueryRequest::Wasm(WasmQuery::Smart { msg, .. }) => match from_binary(msg).unwrap() {
+                LottoQueryMsg::CanExecuteLottery {} => SystemResult::Ok(ContractResult::from(
+                    to_binary(&can_execute_response(self.lotto_querier.lottery_ready)),
+                )),
+                LottoQueryMsg::CanExecutePrize {} => SystemResult::Ok(ContractResult::from(
+                    to_binary(&can_execute_response(self.lotto_querier.prize_ready)),
+                )),
+                LottoQueryMsg::EpochOperations {} => {
+                    let operations = [
+                        EpochOperation::SweepReserve,
+                        EpochOperation::UpdateEmissions,
+                        EpochOperation::SweepExpired,
+                    ]
+                    .iter()
+                    .map(|operation| EpochOperationStatus {
+                        operation: *operation,
+                        last_executed: None,
+                        next_execution: Expiration::Never {},
+                        ready: self.lotto_querier.ready_operations.contains(operation),
+                    })
+                    .collect();
+                    SystemResult::Ok(ContractResult::from(to_binary(&EpochOperationsResponse {
+                        operations,
+                    })))
+                }
+                _ => panic!("DO NOT ENTER HERE"),
+            },
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+fn can_execute_response(ready: bool) -> CanExecuteResponse {
+    CanExecuteResponse {
+        ready,
+        reason: None,
+        seconds_remaining: None,
+        missing_funds: None,
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<TerraQueryWrapper>) -> Self {
+        WasmMockQuerier {
+            base,
+            tax_querier: TaxQuerier::default(),
+            lotto_querier: LottoQuerier::default(),
+        }
+    }
+
+    // configure the tax mock querier
+    pub fn with_tax(&mut self, rate: Decimal, caps: &[(&String, &Uint128)]) {
+        self.tax_querier = TaxQuerier::new(rate, caps);
+    }
+
+    // configure which lotto operations are ready
+    pub fn with_lotto_ready(
+        &mut self,
+        lottery_ready: bool,
+        prize_ready: bool,
+        ready_operations: &[EpochOperation],
+    ) {
+        self.lotto_querier = LottoQuerier {
+            lottery_ready,
+            prize_ready,
+            ready_operations: ready_operations.to_vec(),
+        };
+    }
+}
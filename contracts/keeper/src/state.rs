@@ -0,0 +1,35 @@
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+// Registered tasks, keyed by KeeperTask::as_str
+pub const TASKS: Map<&str, Task> = Map::new("tasks");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub owner: Addr,
+    pub lotto_contract: Addr,
+    pub bounty_denom: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Task {
+    // Minimum number of seconds between two runs
+    pub interval: u64,
+    // Paid to the sender of the Poke which runs the task
+    pub bounty: Uint128,
+    // Block time in seconds of the last run, None if it never ran
+    pub last_run: Option<u64>,
+}
+
+impl Task {
+    pub fn is_scheduled(&self, now: u64) -> bool {
+        match self.last_run {
+            Some(last_run) => last_run + self.interval <= now,
+            None => true,
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::mock_querier::mock_dependencies;
+
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{
+    attr, coins, from_binary, to_binary, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, SubMsg,
+    Timestamp, Uint128, WasmMsg,
+};
+use glow_protocol::keeper::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, KeeperTask, QueryMsg, TaskInfo, TasksResponse,
+};
+use glow_protocol::lotto::{EpochOperation, ExecuteMsg as LottoExecuteMsg};
+
+const TEST_OWNER: &str = "owner";
+const TEST_KEEPER: &str = "keeper";
+const LOTTO: &str = "lotto";
+const DENOM: &str = "uusd";
+
+fn mock_instantiate(deps: DepsMut) {
+    let msg = InstantiateMsg {
+        owner: TEST_OWNER.to_string(),
+        lotto_contract: LOTTO.to_string(),
+        bounty_denom: DENOM.to_string(),
+    };
+
+    let info = mock_info(TEST_OWNER, &[]);
+    let _res = instantiate(deps, mock_env(), info, msg)
+        .expect("contract successfully executes InstantiateMsg");
+}
+
+fn mock_register_task(deps: DepsMut, task: KeeperTask, interval: u64, bounty: u128) {
+    let info = mock_info(TEST_OWNER, &[]);
+    let msg = ExecuteMsg::RegisterTask {
+        task,
+        interval,
+        bounty: Uint128::from(bounty),
+    };
+    let _res =
+        execute(deps, mock_env(), info, msg).expect("contract successfully executes RegisterTask");
+}
+
+fn increase_env_time(env: &mut Env, increase_time: u64) {
+    env.block.time = Timestamp::from_seconds(env.block.time.seconds() + increase_time);
+}
+
+fn lotto_msg(msg: &LottoExecuteMsg) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: LOTTO.to_string(),
+        msg: to_binary(msg).unwrap(),
+        funds: vec![],
+    }))
+}
+
+#[test]
+fn proper_initialization() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            owner: TEST_OWNER.to_string(),
+            lotto_contract: LOTTO.to_string(),
+            bounty_denom: DENOM.to_string(),
+        }
+    );
+
+    let tasks: TasksResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Tasks {}).unwrap()).unwrap();
+    assert!(tasks.tasks.is_empty());
+}
+
+#[test]
+fn register_and_remove_task() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    // Only the owner can register tasks
+    let msg = ExecuteMsg::RegisterTask {
+        task: KeeperTask::ExecuteLottery,
+        interval: 3600,
+        bounty: Uint128::from(1_000u128),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_KEEPER, &[]),
+        msg.clone(),
+    );
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_OWNER, &[]), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "register_task"),
+            attr("task", "execute_lottery"),
+            attr("interval", "3600"),
+            attr("bounty", "1000"),
+        ]
+    );
+
+    let tasks: TasksResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Tasks {}).unwrap()).unwrap();
+    assert_eq!(
+        tasks.tasks,
+        vec![TaskInfo {
+            task: KeeperTask::ExecuteLottery,
+            interval: 3600,
+            bounty: Uint128::from(1_000u128),
+            last_run: None,
+            due: false,
+        }]
+    );
+
+    // Only the owner can remove tasks
+    let msg = ExecuteMsg::RemoveTask {
+        task: KeeperTask::ExecuteLottery,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_KEEPER, &[]),
+        msg.clone(),
+    );
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_OWNER, &[]),
+        msg.clone(),
+    )
+    .unwrap();
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_OWNER, &[]), msg);
+    assert_eq!(res, Err(ContractError::TaskNotRegistered {}));
+}
+
+#[test]
+fn poke_dispatches_due_tasks() {
+    let mut deps = mock_dependencies(&coins(1_500, DENOM));
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&DENOM.to_string(), &Uint128::from(1_000_000u128))],
+    );
+    mock_instantiate(deps.as_mut());
+
+    mock_register_task(deps.as_mut(), KeeperTask::ExecuteLottery, 3600, 1_000);
+    mock_register_task(deps.as_mut(), KeeperTask::EpochOperations, 86400, 1_000);
+    mock_register_task(deps.as_mut(), KeeperTask::SweepExpired, 86400, 500);
+
+    // Nothing is ready on the lotto contract
+    let mut env = mock_env();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_KEEPER, &[]),
+        ExecuteMsg::Poke {},
+    );
+    assert_eq!(res, Err(ContractError::NoTasksDue {}));
+
+    deps.querier.with_lotto_ready(
+        true,
+        false,
+        &[
+            EpochOperation::SweepReserve,
+            EpochOperation::UpdateEmissions,
+        ],
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_KEEPER, &[]),
+        ExecuteMsg::Poke {},
+    )
+    .unwrap();
+
+    // The bounties add up to 2_000 but the keeper only holds 1_500
+    assert_eq!(
+        res.messages,
+        vec![
+            lotto_msg(&LottoExecuteMsg::ExecuteLottery {}),
+            lotto_msg(&LottoExecuteMsg::ExecuteEpochOps {
+                operations: Some(vec![
+                    EpochOperation::SweepReserve,
+                    EpochOperation::UpdateEmissions
+                ]),
+            }),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: TEST_KEEPER.to_string(),
+                amount: vec![Coin {
+                    denom: DENOM.to_string(),
+                    amount: Uint128::from(1_485u128),
+                }],
+            })),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "poke"),
+            attr("keeper", TEST_KEEPER),
+            attr("tasks", "execute_lottery,epoch_operations"),
+            attr("bounty", "1500"),
+        ]
+    );
+
+    // The tasks which ran are not due again until their interval passed
+    deps.querier
+        .with_lotto_ready(true, false, &[EpochOperation::SweepExpired]);
+    increase_env_time(&mut env, 1800);
+
+    let tasks: TasksResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Tasks {}).unwrap()).unwrap();
+    assert_eq!(
+        tasks.tasks.iter().map(|t| t.due).collect::<Vec<bool>>(),
+        vec![false, false, true]
+    );
+    assert_eq!(
+        tasks.tasks[0].last_run,
+        Some(mock_env().block.time.seconds())
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_KEEPER, &[]),
+        ExecuteMsg::Poke {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0],
+        lotto_msg(&LottoExecuteMsg::ExecuteEpochOps {
+            operations: Some(vec![EpochOperation::SweepExpired]),
+        })
+    );
+    assert_eq!(res.attributes[2], attr("tasks", "sweep_expired"));
+
+    increase_env_time(&mut env, 1800);
+
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info(TEST_KEEPER, &[]),
+        ExecuteMsg::Poke {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0],
+        lotto_msg(&LottoExecuteMsg::ExecuteLottery {})
+    );
+    assert_eq!(res.attributes[2], attr("tasks", "execute_lottery"));
+}
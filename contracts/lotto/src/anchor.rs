@@ -0,0 +1,112 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{
+    attr, ContractResult, CosmosMsg, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
+    Storage, SubMsg,
+};
+use cw_storage_plus::U64Key;
+use glow_protocol::querier::{
+    deposit_stable_msg as anchor_deposit_stable_msg, redeem_stable_msg as anchor_redeem_stable_msg,
+};
+
+use crate::error::ContractError;
+use crate::state::{
+    Config, PendingAnchorOperations, ANCHOR_IN_FLIGHT, CONFIG, NEXT_ANCHOR_OPERATION_ID,
+    PENDING_ANCHOR_OPERATIONS,
+};
+
+// Each redemption replies with its own id from ANCHOR_REDEEM_REPLY_ID_START on,
+// which keys its amount in ANCHOR_IN_FLIGHT
+pub const ANCHOR_REDEEM_REPLY_ID_START: u64 = 1 << 32;
+
+/// Message depositing amount of stable into anchor. Deposits are credited
+/// to the pool before the aust is minted, so a failure reverts the transaction
+pub fn deposit_stable_msg(config: &Config, amount: Uint256) -> StdResult<CosmosMsg> {
+    anchor_deposit_stable_msg(&config.anchor_contract, &config.stable_denom, amount)
+}
+
+/// Builds a submessage redeeming aust_amount of aust from anchor.
+/// If the redemption fails, the amount is recorded as pending instead of failing the transaction.
+/// Only use it when nothing else in the transaction depends on the redeemed stable.
+pub fn redeem_stable_submsg(
+    storage: &mut dyn Storage,
+    config: &Config,
+    aust_amount: Uint256,
+) -> StdResult<SubMsg> {
+    let operation_id = NEXT_ANCHOR_OPERATION_ID
+        .may_load(storage)?
+        .unwrap_or_default();
+    NEXT_ANCHOR_OPERATION_ID.save(storage, &(operation_id + 1))?;
+
+    let reply_id = ANCHOR_REDEEM_REPLY_ID_START + operation_id;
+    ANCHOR_IN_FLIGHT.save(storage, U64Key::from(reply_id), &aust_amount)?;
+
+    // Successful redemptions reply too, so their amount doesn't stay in flight
+    Ok(SubMsg::reply_always(
+        redeem_stable_msg(config, aust_amount)?,
+        reply_id,
+    ))
+}
+
+/// Message redeeming aust_amount of aust from anchor
+pub fn redeem_stable_msg(config: &Config, aust_amount: Uint256) -> StdResult<CosmosMsg> {
+    anchor_redeem_stable_msg(
+        &config.a_terra_contract,
+        &config.anchor_contract,
+        aust_amount,
+    )
+}
+
+pub fn read_pending_anchor_operations(storage: &dyn Storage) -> StdResult<PendingAnchorOperations> {
+    Ok(PENDING_ANCHOR_OPERATIONS
+        .may_load(storage)?
+        .unwrap_or_default())
+}
+
+/// Records failed anchor redemptions as pending operations
+pub fn handle_anchor_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let key = U64Key::from(msg.id);
+    let amount = match ANCHOR_IN_FLIGHT.may_load(deps.storage, key.clone())? {
+        Some(amount) if msg.id >= ANCHOR_REDEEM_REPLY_ID_START => amount,
+        _ => return Err(ContractError::InvalidReplyId(msg.id)),
+    };
+    ANCHOR_IN_FLIGHT.remove(deps.storage, key);
+
+    let error = match msg.result {
+        ContractResult::Err(error) => error,
+        ContractResult::Ok(_) => return Ok(Response::default()),
+    };
+
+    let mut pending = read_pending_anchor_operations(deps.storage)?;
+    pending.redeem_stable += amount;
+    PENDING_ANCHOR_OPERATIONS.save(deps.storage, &pending)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "anchor_redeem_failed"),
+        attr("pending_amount", amount.to_string()),
+        attr("error", error),
+    ]))
+}
+
+/// Re-sends the anchor redemptions that previously failed
+pub fn execute_retry_pending(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let pending = read_pending_anchor_operations(deps.storage)?;
+
+    if pending.redeem_stable.is_zero() {
+        return Err(ContractError::NoPendingAnchorOperations {});
+    }
+
+    let msg = redeem_stable_submsg(deps.storage, &config, pending.redeem_stable)?;
+
+    // Redemptions failing again are recorded back by their replies
+    PENDING_ANCHOR_OPERATIONS.save(deps.storage, &PendingAnchorOperations::default())?;
+
+    Ok(Response::new().add_submessage(msg).add_attributes(vec![
+        attr("action", "retry_pending"),
+        attr("redeem_stable", pending.redeem_stable.to_string()),
+    ]))
+}
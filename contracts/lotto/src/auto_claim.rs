@@ -0,0 +1,194 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    attr, coin, Addr, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdResult, Storage, Uint128,
+};
+use cw_storage_plus::Bound;
+use glow_protocol::lotto::AutoClaimResponse;
+use glow_protocol::querier::deduct_tax;
+
+use crate::compliance::assert_not_blacklisted;
+use crate::contract::available_claim_balance;
+use crate::error::ContractError;
+use crate::events::claim_event;
+use crate::helpers::claim_unbonded_withdrawals;
+use crate::state::{
+    read_depositor_info, read_lottery_info, store_depositor_info, AUTO_CLAIMS,
+    AUTO_CLAIM_BOUNTY_RATE, AUTO_CLAIM_CURSOR, CONFIG, STATE,
+};
+
+// settings for pagination
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+// Share of auto claims paid to keepers, in permille, until set by gov
+const DEFAULT_AUTO_CLAIM_BOUNTY_RATE: u64 = 1;
+// Largest share of auto claims gov can pay to keepers, in percent
+const MAX_AUTO_CLAIM_BOUNTY_RATE: u64 = 1;
+
+pub fn read_auto_claim_bounty_rate(storage: &dyn Storage) -> StdResult<Decimal256> {
+    Ok(AUTO_CLAIM_BOUNTY_RATE
+        .may_load(storage)?
+        .unwrap_or_else(|| Decimal256::permille(DEFAULT_AUTO_CLAIM_BOUNTY_RATE)))
+}
+
+pub fn execute_register_auto_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    AUTO_CLAIMS.save(deps.storage, &info.sender, &env.block.time.seconds())?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_auto_claim"),
+        attr("depositor", info.sender.to_string()),
+    ]))
+}
+
+pub fn execute_deregister_auto_claim(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    AUTO_CLAIMS.remove(deps.storage, &info.sender);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "deregister_auto_claim"),
+        attr("depositor", info.sender.to_string()),
+    ]))
+}
+
+pub fn execute_update_auto_claim_bounty(
+    deps: DepsMut,
+    info: MessageInfo,
+    bounty_rate: Decimal256,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let max = Decimal256::percent(MAX_AUTO_CLAIM_BOUNTY_RATE);
+    if bounty_rate > max {
+        return Err(ContractError::InvalidAutoClaimBounty { max });
+    }
+
+    AUTO_CLAIM_BOUNTY_RATE.save(deps.storage, &bounty_rate)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_auto_claim_bounty"),
+        attr("bounty_rate", bounty_rate.to_string()),
+    ]))
+}
+
+/// Pays the matured unbonding claims of up to limit registered depositors, resuming
+/// after the last depositor of the previous call and starting over once all were scanned.
+/// Claims the contract can't cover right now are left for the depositor to claim later
+pub fn execute_process_auto_claims(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+
+    // Validate that there isn't a lottery in progress already
+    let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
+    if current_lottery.rand_round != 0 {
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = AUTO_CLAIM_CURSOR
+        .may_load(deps.storage)?
+        .map(|addr| Bound::exclusive(addr.as_str()));
+    let depositors: Vec<Addr> = AUTO_CLAIMS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|key| Addr::unchecked(String::from_utf8_lossy(&key).to_string()))
+        .collect();
+
+    match depositors.last() {
+        Some(last) if depositors.len() == limit => AUTO_CLAIM_CURSOR.save(deps.storage, last)?,
+        _ => AUTO_CLAIM_CURSOR.remove(deps.storage),
+    }
+
+    let bounty_rate = read_auto_claim_bounty_rate(deps.storage)?;
+    let mut available_balance = available_claim_balance(deps.as_ref(), &env, &config, &state)?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    let mut events = vec![];
+    let mut total_bounty = Uint128::zero();
+    for depositor in depositors {
+        if assert_not_blacklisted(deps.storage, &depositor).is_err() {
+            continue;
+        }
+
+        let mut depositor_info = read_depositor_info(deps.storage, &depositor);
+        let to_send = claim_unbonded_withdrawals(&mut depositor_info, &env.block, None)?;
+        if to_send.is_zero() || Uint256::from(to_send) > available_balance {
+            continue;
+        }
+        available_balance -= Uint256::from(to_send);
+
+        let bounty = Uint128::from(Uint256::from(to_send) * bounty_rate);
+        total_bounty += bounty;
+
+        // Deduct taxes on the claim
+        let net_send = deduct_tax(
+            deps.as_ref(),
+            coin((to_send - bounty).into(), config.stable_denom.clone()),
+        )?
+        .amount;
+
+        store_depositor_info(deps.storage, &depositor, depositor_info, env.block.height)?;
+
+        msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: depositor.to_string(),
+            amount: vec![Coin {
+                denom: config.stable_denom.clone(),
+                amount: net_send,
+            }],
+        }));
+        events.push(claim_event(&depositor, &[], net_send, Uint128::zero()));
+    }
+
+    let processed_claims = msgs.len();
+    let net_bounty = deduct_tax(
+        deps.as_ref(),
+        coin(total_bounty.into(), config.stable_denom.clone()),
+    )?
+    .amount;
+    if !net_bounty.is_zero() {
+        msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                denom: config.stable_denom,
+                amount: net_bounty,
+            }],
+        }));
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_events(events)
+        .add_attributes(vec![
+            attr("action", "process_auto_claims"),
+            attr("keeper", info.sender.to_string()),
+            attr("processed_claims", processed_claims.to_string()),
+            attr("bounty", net_bounty),
+        ]))
+}
+
+pub fn query_auto_claim(deps: Deps, depositor: String) -> StdResult<AutoClaimResponse> {
+    let depositor = deps.api.addr_validate(&depositor)?;
+
+    Ok(AutoClaimResponse {
+        registered: AUTO_CLAIMS.has(deps.storage, &depositor),
+        bounty_rate: read_auto_claim_bounty_rate(deps.storage)?,
+    })
+}
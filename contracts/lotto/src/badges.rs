@@ -0,0 +1,250 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{
+    attr, to_binary, Addr, ContractResult, Deps, DepsMut, Empty, Env, MessageInfo, Order, Reply,
+    Response, StdResult, Storage, SubMsg, WasmMsg,
+};
+use cw_storage_plus::U32Key;
+use glow_protocol::lotto::{BadgeConfig as BadgeConfigMsg, BadgeEligibilityResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ContractError;
+use crate::state::{
+    BadgeConfig, BadgeMint, BADGE_CONFIG, BADGE_MINTS_IN_FLIGHT, CONFIG, MINTED_BADGES,
+    SPONSORSHIP_TOTALS,
+};
+
+pub const BADGE_REPLY_ID: u64 = 5;
+
+const MAX_BADGE_TIERS: u32 = 10;
+
+/// Mint message of the cw721-base badge contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum BadgeExecuteMsg {
+    Mint(MintMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct MintMsg {
+    token_id: String,
+    owner: String,
+    token_uri: Option<String>,
+    extension: Option<Empty>,
+}
+
+/// Set the badge contract and tiers, or stop minting badges if
+/// badge_config is None - restricted to gov
+pub fn execute_update_badge_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    badge_config: Option<BadgeConfigMsg>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let badge_config = match badge_config {
+        Some(badge_config) => badge_config,
+        None => {
+            BADGE_CONFIG.remove(deps.storage);
+            return Ok(Response::new().add_attributes(vec![
+                attr("action", "update_badge_config"),
+                attr("enabled", "false"),
+            ]));
+        }
+    };
+
+    let mut previous_threshold = Uint256::zero();
+    for tier in badge_config.tiers.iter() {
+        if tier.threshold <= previous_threshold {
+            return Err(ContractError::InvalidBadgeConfig {
+                max_tiers: MAX_BADGE_TIERS,
+            });
+        }
+        previous_threshold = tier.threshold;
+    }
+    if badge_config.tiers.is_empty() || badge_config.tiers.len() > MAX_BADGE_TIERS as usize {
+        return Err(ContractError::InvalidBadgeConfig {
+            max_tiers: MAX_BADGE_TIERS,
+        });
+    }
+
+    BADGE_CONFIG.save(
+        deps.storage,
+        &BadgeConfig {
+            badge_contract: deps.api.addr_validate(&badge_config.badge_contract)?,
+            tiers: badge_config.tiers,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_badge_config"),
+        attr("enabled", "true"),
+    ]))
+}
+
+/// Adds amount to the cumulative sponsorship of sponsor
+pub fn record_sponsorship(
+    storage: &mut dyn Storage,
+    sponsor: &Addr,
+    amount: Uint256,
+) -> StdResult<()> {
+    SPONSORSHIP_TOTALS.update(storage, sponsor, |total| -> StdResult<_> {
+        Ok(total.unwrap_or_default() + amount)
+    })?;
+
+    Ok(())
+}
+
+/// Tiers crossed by the cumulative sponsorship of sponsor which weren't minted yet
+fn eligible_tiers(
+    storage: &dyn Storage,
+    badge_config: &BadgeConfig,
+    sponsor: &Addr,
+) -> StdResult<Vec<u32>> {
+    let total_sponsored = SPONSORSHIP_TOTALS
+        .may_load(storage, sponsor)?
+        .unwrap_or_default();
+
+    let mut tiers = vec![];
+    for (index, tier) in badge_config.tiers.iter().enumerate() {
+        if tier.threshold > total_sponsored {
+            break;
+        }
+        let tier_number = index as u32 + 1;
+        if !MINTED_BADGES.has(storage, (sponsor, U32Key::from(tier_number))) {
+            tiers.push(tier_number);
+        }
+    }
+
+    Ok(tiers)
+}
+
+/// Mint submessages of the badges sponsor is eligible to. Badges are recorded
+/// as minted right away so they are only sent once, and released again if
+/// their mint fails
+pub fn badge_mint_submsgs(
+    storage: &mut dyn Storage,
+    env: &Env,
+    sponsor: &Addr,
+) -> StdResult<Vec<SubMsg>> {
+    let badge_config = match BADGE_CONFIG.may_load(storage)? {
+        Some(badge_config) => badge_config,
+        None => return Ok(vec![]),
+    };
+
+    let mut in_flight = BADGE_MINTS_IN_FLIGHT.may_load(storage)?.unwrap_or_default();
+    let mut submsgs = vec![];
+    for tier in eligible_tiers(storage, &badge_config, sponsor)? {
+        MINTED_BADGES.save(
+            storage,
+            (sponsor, U32Key::from(tier)),
+            &env.block.time.seconds(),
+        )?;
+        in_flight.push(BadgeMint {
+            sponsor: sponsor.clone(),
+            tier,
+        });
+
+        submsgs.push(SubMsg::reply_always(
+            WasmMsg::Execute {
+                contract_addr: badge_config.badge_contract.to_string(),
+                funds: vec![],
+                msg: to_binary(&BadgeExecuteMsg::Mint(MintMsg {
+                    token_id: format!("{}:{}", sponsor, tier),
+                    owner: sponsor.to_string(),
+                    token_uri: badge_config.tiers[tier as usize - 1].token_uri.clone(),
+                    extension: None,
+                }))?,
+            },
+            BADGE_REPLY_ID,
+        ));
+    }
+    BADGE_MINTS_IN_FLIGHT.save(storage, &in_flight)?;
+
+    Ok(submsgs)
+}
+
+pub fn execute_claim_badges(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let submsgs = badge_mint_submsgs(deps.storage, &env, &info.sender)?;
+    if submsgs.is_empty() {
+        return Err(ContractError::NoBadgeToMint {});
+    }
+
+    Ok(Response::new()
+        .add_submessages(submsgs)
+        .add_attributes(vec![
+            attr("action", "claim_badges"),
+            attr("sponsor", info.sender.to_string()),
+        ]))
+}
+
+/// Replies come in the order the mints were sent. A failed mint
+/// doesn't revert the sponsorship, the badge can be claimed again
+pub fn handle_badge_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let mut in_flight = BADGE_MINTS_IN_FLIGHT.load(deps.storage)?;
+    let mint = in_flight.remove(0);
+    BADGE_MINTS_IN_FLIGHT.save(deps.storage, &in_flight)?;
+
+    if let ContractResult::Err(error) = msg.result {
+        MINTED_BADGES.remove(deps.storage, (&mint.sponsor, U32Key::from(mint.tier)));
+
+        return Ok(Response::new().add_attributes(vec![
+            attr("action", "badge_mint_failed"),
+            attr("sponsor", mint.sponsor.to_string()),
+            attr("tier", mint.tier.to_string()),
+            attr("error", error),
+        ]));
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "badge_minted"),
+        attr("sponsor", mint.sponsor.to_string()),
+        attr("tier", mint.tier.to_string()),
+    ]))
+}
+
+pub fn query_badge_eligibility(deps: Deps, address: String) -> StdResult<BadgeEligibilityResponse> {
+    let sponsor = deps.api.addr_validate(&address)?;
+    let total_sponsored = SPONSORSHIP_TOTALS
+        .may_load(deps.storage, &sponsor)?
+        .unwrap_or_default();
+
+    let minted_tiers = MINTED_BADGES
+        .prefix(&sponsor)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|key| {
+            let mut tier = [0u8; 4];
+            tier.copy_from_slice(&key);
+            u32::from_be_bytes(tier)
+        })
+        .collect();
+
+    let (eligible_tiers, next_threshold) = match BADGE_CONFIG.may_load(deps.storage)? {
+        Some(badge_config) => (
+            eligible_tiers(deps.storage, &badge_config, &sponsor)?,
+            badge_config
+                .tiers
+                .iter()
+                .map(|tier| tier.threshold)
+                .find(|threshold| *threshold > total_sponsored),
+        ),
+        None => (vec![], None),
+    };
+
+    Ok(BadgeEligibilityResponse {
+        address: sponsor.to_string(),
+        total_sponsored,
+        minted_tiers,
+        eligible_tiers,
+        next_threshold,
+    })
+}
@@ -0,0 +1,223 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    attr, to_binary, Coin, ContractResult, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Reply, Response, StdResult, SubMsg, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use glow_protocol::lotto::{BuybackConfig, BuybackDestination, BuybackResponse};
+use glow_protocol::querier::{deduct_tax, query_token_balance};
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::pair::ExecuteMsg as TerraswapExecuteMsg;
+
+use crate::error::ContractError;
+use crate::state::{
+    Buyback, BuybackInFlight, BuybackState, Config, BUYBACK, BUYBACK_IN_FLIGHT, BUYBACK_STATE,
+    CONFIG, STATE,
+};
+
+pub const BUYBACK_REPLY_ID: u64 = 3;
+
+pub fn read_buyback_state(deps: Deps) -> StdResult<BuybackState> {
+    Ok(BUYBACK_STATE.may_load(deps.storage)?.unwrap_or_default())
+}
+
+/// Set how much of the reserve is swapped to GLOW at each epoch operation,
+/// or disable buybacks if buyback_config is None - restricted to gov
+pub fn execute_update_buyback_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    buyback_config: Option<BuybackConfig>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let buyback_config = match buyback_config {
+        Some(buyback_config) => buyback_config,
+        None => {
+            BUYBACK.remove(deps.storage);
+            return Ok(Response::new().add_attributes(vec![
+                attr("action", "update_buyback_config"),
+                attr("enabled", "false"),
+            ]));
+        }
+    };
+
+    if buyback_config.reserve_share > Decimal256::one()
+        || buyback_config.max_spread > Decimal::one()
+    {
+        return Err(ContractError::InvalidBuybackConfig {});
+    }
+
+    // Buybacks sent to the fee distributor require its address
+    if buyback_config.destination == BuybackDestination::FeeDistributor
+        && config.fee_distributor_contract.is_none()
+    {
+        return Err(ContractError::FeeDistributorNotRegistered {});
+    }
+
+    BUYBACK.save(
+        deps.storage,
+        &Buyback {
+            pair_contract: deps.api.addr_validate(&buyback_config.pair_contract)?,
+            glow_token: deps.api.addr_validate(&buyback_config.glow_token)?,
+            reserve_share: buyback_config.reserve_share,
+            max_spread: buyback_config.max_spread,
+            destination: buyback_config.destination,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_buyback_config"),
+        attr("enabled", "true"),
+    ]))
+}
+
+/// Part of total_reserves to swap to GLOW during epoch operations
+pub fn buyback_reserves(deps: Deps, total_reserves: Uint256) -> StdResult<Uint256> {
+    Ok(match BUYBACK.may_load(deps.storage)? {
+        Some(buyback) => total_reserves * buyback.reserve_share,
+        None => Uint256::zero(),
+    })
+}
+
+/// Submessage swapping amount of stable to GLOW through the buyback pair.
+/// The reply forwards the bought GLOW, or returns amount to the reserve if the swap fails.
+pub fn buyback_submsg(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    amount: Uint256,
+) -> StdResult<SubMsg> {
+    let buyback = BUYBACK.load(deps.storage)?;
+
+    let offer_coin = deduct_tax(
+        deps.as_ref(),
+        Coin {
+            denom: config.stable_denom.clone(),
+            amount: amount.into(),
+        },
+    )?;
+
+    // The bought amount is measured against the GLOW held before the swap
+    let glow_balance = query_token_balance(
+        deps.as_ref(),
+        buyback.glow_token.clone(),
+        env.contract.address.clone(),
+    )?;
+    BUYBACK_IN_FLIGHT.save(
+        deps.storage,
+        &BuybackInFlight {
+            stable_amount: amount,
+            glow_balance,
+        },
+    )?;
+
+    Ok(SubMsg::reply_always(
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: buyback.pair_contract.to_string(),
+            msg: to_binary(&TerraswapExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: config.stable_denom.clone(),
+                    },
+                    amount: offer_coin.amount,
+                },
+                belief_price: None,
+                max_spread: Some(buyback.max_spread),
+                to: None,
+            })?,
+            funds: vec![offer_coin],
+        }),
+        BUYBACK_REPLY_ID,
+    ))
+}
+
+/// Sends the GLOW bought back to its destination, or credits the stable
+/// back to the reserve when the swap failed, e.g. above the max spread
+pub fn handle_buyback_reply(
+    deps: DepsMut,
+    env: Env,
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    let in_flight = BUYBACK_IN_FLIGHT.load(deps.storage)?;
+    BUYBACK_IN_FLIGHT.remove(deps.storage);
+
+    if let ContractResult::Err(error) = msg.result {
+        let mut state = STATE.load(deps.storage)?;
+        state.total_reserve += in_flight.stable_amount;
+        STATE.save(deps.storage, &state, env.block.height)?;
+
+        return Ok(Response::new().add_attributes(vec![
+            attr("action", "buyback_failed"),
+            attr("returned_reserves", in_flight.stable_amount.to_string()),
+            attr("error", error),
+        ]));
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let buyback = BUYBACK.load(deps.storage)?;
+
+    let glow_balance = query_token_balance(
+        deps.as_ref(),
+        buyback.glow_token.clone(),
+        env.contract.address,
+    )?;
+    let glow_bought = if glow_balance > in_flight.glow_balance {
+        glow_balance - in_flight.glow_balance
+    } else {
+        Uint256::zero()
+    };
+
+    let mut buyback_state = read_buyback_state(deps.as_ref())?;
+    buyback_state.total_glow_bought += glow_bought;
+    buyback_state.total_stable_spent += in_flight.stable_amount;
+    BUYBACK_STATE.save(deps.storage, &buyback_state)?;
+
+    let recipient = match buyback.destination {
+        BuybackDestination::Community => config.community_contract,
+        BuybackDestination::FeeDistributor => config
+            .fee_distributor_contract
+            .ok_or(ContractError::FeeDistributorNotRegistered {})?,
+    };
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !glow_bought.is_zero() {
+        msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: buyback.glow_token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: glow_bought.into(),
+            })?,
+            funds: vec![],
+        }));
+    }
+
+    Ok(Response::new().add_messages(msgs).add_attributes(vec![
+        attr("action", "buyback"),
+        attr("stable_spent", in_flight.stable_amount.to_string()),
+        attr("glow_bought", glow_bought.to_string()),
+        attr("recipient", recipient.to_string()),
+    ]))
+}
+
+pub fn query_buyback(deps: Deps) -> StdResult<BuybackResponse> {
+    let buyback_state = read_buyback_state(deps)?;
+
+    Ok(BuybackResponse {
+        buyback_config: BUYBACK
+            .may_load(deps.storage)?
+            .map(|buyback| BuybackConfig {
+                pair_contract: buyback.pair_contract.to_string(),
+                glow_token: buyback.glow_token.to_string(),
+                reserve_share: buyback.reserve_share,
+                max_spread: buyback.max_spread,
+                destination: buyback.destination,
+            }),
+        total_glow_bought: buyback_state.total_glow_bought,
+        total_stable_spent: buyback_state.total_stable_spent,
+    })
+}
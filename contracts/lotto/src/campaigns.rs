@@ -0,0 +1,77 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{Addr, Deps, Order, StdResult, Storage};
+use glow_protocol::common::Pagination;
+use glow_protocol::lotto::{CampaignStatsResponse, OperatorCampaignStatsResponse};
+
+use crate::error::ContractError;
+use crate::state::OPERATOR_CAMPAIGNS;
+
+const MAX_CAMPAIGN_ID_LEN: usize = 32;
+
+pub fn validate_campaign_id(campaign_id: &str) -> Result<(), ContractError> {
+    if campaign_id.is_empty()
+        || campaign_id.len() > MAX_CAMPAIGN_ID_LEN
+        || !campaign_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(ContractError::InvalidCampaignId {});
+    }
+
+    Ok(())
+}
+
+/// Attributes a deposit of amount, after taxes, and its tickets to the campaign of operator
+pub fn record_campaign_deposit(
+    storage: &mut dyn Storage,
+    operator: &Addr,
+    campaign_id: &str,
+    amount: Uint256,
+    tickets: u64,
+) -> StdResult<()> {
+    OPERATOR_CAMPAIGNS.update(storage, (operator, campaign_id), |stats| -> StdResult<_> {
+        let mut stats = stats.unwrap_or_default();
+        stats.deposited += amount;
+        stats.tickets += tickets;
+        stats.deposits += 1;
+        Ok(stats)
+    })?;
+
+    Ok(())
+}
+
+pub fn query_operator_campaign_stats(
+    deps: Deps,
+    operator: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<OperatorCampaignStatsResponse> {
+    let operator = deps.api.addr_validate(&operator)?;
+    let pagination = Pagination::new(start_after, limit);
+
+    let campaigns = OPERATOR_CAMPAIGNS
+        .prefix(&operator)
+        .range(
+            deps.storage,
+            pagination.start_bound(),
+            None,
+            Order::Ascending,
+        )
+        .take(pagination.limit)
+        .map(|item| {
+            let (campaign_id, stats) = item?;
+            Ok(CampaignStatsResponse {
+                campaign_id: String::from_utf8_lossy(&campaign_id).to_string(),
+                deposited: stats.deposited,
+                tickets: stats.tickets,
+                deposits: stats.deposits,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(OperatorCampaignStatsResponse {
+        operator: operator.to_string(),
+        campaigns,
+        limit: pagination.limit as u32,
+    })
+}
@@ -0,0 +1,125 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    attr, Addr, Attribute, Deps, DepsMut, MessageInfo, Order, Response, StdResult, Storage,
+};
+use glow_protocol::common::Pagination;
+use glow_protocol::lotto::{CharitiesResponse, CharityDonation, CharityResponse};
+
+use crate::error::ContractError;
+use crate::state::{Charity, CHARITIES, CONFIG};
+
+/// Register and unregister charities. Unregistered charities keep
+/// their donation totals and can be registered again - restricted to gov
+pub fn execute_update_charities(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes: Vec<Attribute> = vec![attr("action", "update_charities")];
+
+    for address in add {
+        let address = deps.api.addr_validate(&address)?;
+        let mut charity = CHARITIES
+            .may_load(deps.storage, &address)?
+            .unwrap_or(Charity {
+                registered: false,
+                total_donated: Uint256::zero(),
+            });
+        if !charity.registered {
+            charity.registered = true;
+            CHARITIES.save(deps.storage, &address, &charity)?;
+            attributes.push(attr("registered", address.to_string()));
+        }
+    }
+
+    for address in remove {
+        let address = deps.api.addr_validate(&address)?;
+        if let Some(mut charity) = CHARITIES.may_load(deps.storage, &address)? {
+            if charity.registered {
+                charity.registered = false;
+                CHARITIES.save(deps.storage, &address, &charity)?;
+                attributes.push(attr("unregistered", address.to_string()));
+            }
+        }
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Validates that the donation goes to a registered charity and returns its address
+pub fn validate_charity_donation(
+    deps: Deps,
+    donation: &CharityDonation,
+) -> Result<Addr, ContractError> {
+    if donation.share.is_zero() || donation.share > Decimal256::one() {
+        return Err(ContractError::InvalidDonationShare {});
+    }
+
+    let charity = deps.api.addr_validate(&donation.charity)?;
+    match CHARITIES.may_load(deps.storage, &charity)? {
+        Some(Charity {
+            registered: true, ..
+        }) => Ok(charity),
+        _ => Err(ContractError::CharityNotRegistered(charity.to_string())),
+    }
+}
+
+/// Adds amount to the total donated to a charity
+pub fn record_donation(
+    storage: &mut dyn Storage,
+    charity: &Addr,
+    amount: Uint256,
+) -> StdResult<()> {
+    CHARITIES.update(storage, charity, |charity| -> StdResult<_> {
+        let mut charity = charity.unwrap_or(Charity {
+            registered: false,
+            total_donated: Uint256::zero(),
+        });
+        charity.total_donated += amount;
+        Ok(charity)
+    })?;
+
+    Ok(())
+}
+
+pub fn query_charities(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CharitiesResponse> {
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let pagination = Pagination::new(start_after, limit);
+
+    let charities = CHARITIES
+        .range(
+            deps.storage,
+            pagination.start_bound(),
+            None,
+            Order::Ascending,
+        )
+        .take(pagination.limit)
+        .map(|item| {
+            let (address, charity) = item?;
+            Ok(CharityResponse {
+                address: String::from_utf8_lossy(&address).to_string(),
+                registered: charity.registered,
+                total_donated: charity.total_donated,
+            })
+        })
+        .collect::<StdResult<Vec<CharityResponse>>>()?;
+
+    Ok(CharitiesResponse {
+        charities,
+        limit: pagination.limit as u32,
+    })
+}
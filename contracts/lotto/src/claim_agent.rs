@@ -0,0 +1,102 @@
+use cosmwasm_std::{
+    attr, Addr, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage,
+};
+use glow_protocol::common::Pagination;
+use glow_protocol::lotto::{ClaimAgentResponse, ClaimAgentsResponse};
+
+use crate::error::ContractError;
+use crate::state::CLAIM_AGENTS;
+
+/// Authorize agent to claim the sender's lottery prizes on their behalf.
+/// The prizes are always paid to the sender
+pub fn execute_authorize_claim_agent(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    agent: String,
+) -> Result<Response, ContractError> {
+    let agent = deps.api.addr_validate(&agent)?;
+    if agent == info.sender {
+        return Err(ContractError::InvalidClaimAgent {});
+    }
+
+    CLAIM_AGENTS.save(
+        deps.storage,
+        (&info.sender, &agent),
+        &env.block.time.seconds(),
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "authorize_claim_agent"),
+        attr("winner", info.sender.to_string()),
+        attr("agent", agent.to_string()),
+    ]))
+}
+
+pub fn execute_revoke_claim_agent(
+    deps: DepsMut,
+    info: MessageInfo,
+    agent: String,
+) -> Result<Response, ContractError> {
+    let agent = deps.api.addr_validate(&agent)?;
+    assert_claim_agent(deps.storage, &info.sender, &agent)?;
+
+    CLAIM_AGENTS.remove(deps.storage, (&info.sender, &agent));
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "revoke_claim_agent"),
+        attr("winner", info.sender.to_string()),
+        attr("agent", agent.to_string()),
+    ]))
+}
+
+pub fn assert_claim_agent(
+    storage: &dyn Storage,
+    winner: &Addr,
+    agent: &Addr,
+) -> Result<(), ContractError> {
+    if !CLAIM_AGENTS.has(storage, (winner, agent)) {
+        return Err(ContractError::ClaimAgentNotAuthorized {
+            agent: agent.to_string(),
+            winner: winner.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn query_claim_agents(
+    deps: Deps,
+    winner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ClaimAgentsResponse> {
+    let winner = deps.api.addr_validate(&winner)?;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let pagination = Pagination::new(start_after, limit);
+
+    let agents = CLAIM_AGENTS
+        .prefix(&winner)
+        .range(
+            deps.storage,
+            pagination.start_bound(),
+            None,
+            Order::Ascending,
+        )
+        .take(pagination.limit)
+        .map(|item| {
+            let (agent, authorized_at) = item?;
+            Ok(ClaimAgentResponse {
+                agent: String::from_utf8_lossy(&agent).to_string(),
+                authorized_at,
+            })
+        })
+        .collect::<StdResult<Vec<ClaimAgentResponse>>>()?;
+
+    Ok(ClaimAgentsResponse {
+        agents,
+        limit: pagination.limit as u32,
+    })
+}
@@ -0,0 +1,163 @@
+use cosmwasm_std::{
+    attr, ContractResult, CosmosMsg, Deps, DepsMut, MessageInfo, QuerierWrapper, Reply, Response,
+    StdResult, Storage, SubMsg, WasmMsg,
+};
+use cw_storage_plus::U64Key;
+use glow_protocol::lotto::{ClaimReceiptResponse, ClaimReceiptStatus};
+
+use crate::error::ContractError;
+use crate::glow_reserve::glow_prize_msg;
+use crate::state::{
+    ClaimReceipt, Config, CLAIM_RECEIPTS, CONFIG, GLOW_PRIZE_RESERVE, NEXT_CLAIM_RECEIPT_ID,
+    PENDING_CLAIM_RECEIPT,
+};
+
+pub const CLAIM_RECEIPT_REPLY_ID: u64 = 8;
+
+/// Prizes can't be claimed again while the GLOW payout of a claim is executing,
+/// e.g. by a contract receiving it
+pub fn assert_no_pending_claim(storage: &dyn Storage) -> Result<(), ContractError> {
+    if PENDING_CLAIM_RECEIPT.may_load(storage)?.is_some() {
+        return Err(ContractError::ClaimInProgress {});
+    }
+
+    Ok(())
+}
+
+/// Records the receipt of a claim and returns its id, along with the GLOW payout
+/// if the claim has a GLOW prize. The receipt is finalized by the payout reply
+pub fn record_claim_receipt(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    config: &Config,
+    mut receipt: ClaimReceipt,
+) -> StdResult<(u64, Option<SubMsg>)> {
+    let receipt_id = NEXT_CLAIM_RECEIPT_ID.may_load(storage)?.unwrap_or_default();
+    NEXT_CLAIM_RECEIPT_ID.save(storage, &(receipt_id + 1))?;
+
+    let msg = if receipt.glow_amount.is_zero() {
+        receipt.status = ClaimReceiptStatus::Finalized;
+        None
+    } else {
+        Some(glow_payout_submsg(
+            storage,
+            querier,
+            config,
+            receipt_id,
+            &mut receipt,
+        )?)
+    };
+    CLAIM_RECEIPTS.save(storage, U64Key::from(receipt_id), &receipt)?;
+
+    Ok((receipt_id, msg))
+}
+
+fn glow_payout_submsg(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    config: &Config,
+    receipt_id: u64,
+    receipt: &mut ClaimReceipt,
+) -> StdResult<SubMsg> {
+    let msg = glow_prize_msg(
+        storage,
+        querier,
+        config,
+        &receipt.recipient,
+        receipt.glow_amount,
+    )?;
+
+    receipt.glow_from_reserve = !matches!(
+        &msg,
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. })
+            if *contract_addr == config.distributor_contract
+    );
+    receipt.status = ClaimReceiptStatus::Pending;
+    PENDING_CLAIM_RECEIPT.save(storage, &receipt_id)?;
+
+    Ok(SubMsg::reply_always(msg, CLAIM_RECEIPT_REPLY_ID))
+}
+
+/// Finalizes the pending receipt, or marks it as failed if its GLOW payout failed.
+/// The UST prize stays paid, only the GLOW payout is owed to the winner
+pub fn handle_claim_receipt_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let receipt_id = PENDING_CLAIM_RECEIPT.load(deps.storage)?;
+    PENDING_CLAIM_RECEIPT.remove(deps.storage);
+
+    let mut receipt = CLAIM_RECEIPTS.load(deps.storage, U64Key::from(receipt_id))?;
+
+    let res = match msg.result {
+        ContractResult::Ok(_) => {
+            receipt.status = ClaimReceiptStatus::Finalized;
+            Response::new()
+        }
+        ContractResult::Err(error) => {
+            // Give back the glow taken out of the reserve for the payout
+            if receipt.glow_from_reserve {
+                let mut reserve = GLOW_PRIZE_RESERVE.load(deps.storage)?;
+                reserve.balance += receipt.glow_amount;
+                GLOW_PRIZE_RESERVE.save(deps.storage, &reserve)?;
+            }
+            receipt.status = ClaimReceiptStatus::Failed;
+
+            Response::new().add_attributes(vec![
+                attr("action", "claim_glow_payout_failed"),
+                attr("receipt_id", receipt_id.to_string()),
+                attr("glow_amount", receipt.glow_amount),
+                attr("error", error),
+            ])
+        }
+    };
+    CLAIM_RECEIPTS.save(deps.storage, U64Key::from(receipt_id), &receipt)?;
+
+    Ok(res)
+}
+
+pub fn execute_retry_claim(
+    deps: DepsMut,
+    info: MessageInfo,
+    receipt_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut receipt = CLAIM_RECEIPTS.load(deps.storage, U64Key::from(receipt_id))?;
+
+    // check permission
+    if info.sender != receipt.winner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if receipt.status != ClaimReceiptStatus::Failed {
+        return Err(ContractError::ClaimReceiptNotFailed(receipt_id));
+    }
+    assert_no_pending_claim(deps.storage)?;
+
+    let msg = glow_payout_submsg(
+        deps.storage,
+        &deps.querier,
+        &config,
+        receipt_id,
+        &mut receipt,
+    )?;
+    CLAIM_RECEIPTS.save(deps.storage, U64Key::from(receipt_id), &receipt)?;
+
+    Ok(Response::new().add_submessage(msg).add_attributes(vec![
+        attr("action", "retry_claim"),
+        attr("receipt_id", receipt_id.to_string()),
+        attr("recipient", receipt.recipient.to_string()),
+        attr("glow_amount", receipt.glow_amount),
+    ]))
+}
+
+pub fn query_claim_receipt(deps: Deps, receipt_id: u64) -> StdResult<ClaimReceiptResponse> {
+    let receipt = CLAIM_RECEIPTS.load(deps.storage, U64Key::from(receipt_id))?;
+
+    Ok(ClaimReceiptResponse {
+        receipt_id,
+        winner: receipt.winner.to_string(),
+        recipient: receipt.recipient.to_string(),
+        lottery_ids: receipt.lottery_ids,
+        ust_amount: receipt.ust_amount,
+        glow_amount: receipt.glow_amount,
+        status: receipt.status,
+    })
+}
@@ -0,0 +1,130 @@
+use cosmwasm_std::{
+    attr, coin, Addr, Attribute, BankMsg, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    StdResult, Storage, Uint128,
+};
+use glow_protocol::lotto::BlacklistResponse;
+use glow_protocol::querier::deduct_tax;
+
+use crate::error::ContractError;
+use crate::helpers::claim_unbonded_withdrawals;
+use crate::state::{read_depositor_info, store_depositor_info, BlacklistEntry, BLACKLIST, CONFIG};
+
+pub fn assert_not_blacklisted(storage: &dyn Storage, address: &Addr) -> Result<(), ContractError> {
+    if BLACKLIST.has(storage, address) {
+        return Err(ContractError::Blacklisted(address.to_string()));
+    }
+
+    Ok(())
+}
+
+pub fn execute_update_blacklist(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+    dispute_window: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(dispute_window) = dispute_window {
+        config.blacklist_dispute_window = dispute_window;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    let mut attributes: Vec<Attribute> = vec![attr("action", "update_blacklist")];
+
+    for address in add {
+        let address = deps.api.addr_validate(&address)?;
+        // Keep the original blacklisting time so the dispute window isn't extended
+        if !BLACKLIST.has(deps.storage, &address) {
+            BLACKLIST.save(
+                deps.storage,
+                &address,
+                &BlacklistEntry {
+                    blacklisted_at: env.block.time.seconds(),
+                },
+            )?;
+            attributes.push(attr("blacklisted", address.to_string()));
+        }
+    }
+
+    for address in remove {
+        let address = deps.api.addr_validate(&address)?;
+        if BLACKLIST.has(deps.storage, &address) {
+            BLACKLIST.remove(deps.storage, &address);
+            attributes.push(attr("unblacklisted", address.to_string()));
+        }
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+pub fn execute_redirect_blacklisted_claims(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let address = deps.api.addr_validate(&address)?;
+    let entry = BLACKLIST
+        .may_load(deps.storage, &address)?
+        .ok_or_else(|| ContractError::NotBlacklisted(address.to_string()))?;
+
+    let redirectable_at = entry.blacklisted_at + config.blacklist_dispute_window;
+    if env.block.time.seconds() < redirectable_at {
+        return Err(ContractError::DisputeWindowNotOver(redirectable_at));
+    }
+
+    let mut depositor = read_depositor_info(deps.storage, &address);
+    let to_send = claim_unbonded_withdrawals(&mut depositor, &env.block, None)?;
+
+    if to_send == Uint128::zero() {
+        return Err(ContractError::InsufficientClaimableFunds(
+            address.to_string(),
+        ));
+    }
+
+    store_depositor_info(deps.storage, &address, depositor, env.block.height)?;
+
+    // Deduct taxes on the redirected claims
+    let net_send = deduct_tax(
+        deps.as_ref(),
+        coin(to_send.into(), config.stable_denom.clone()),
+    )?;
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Bank(BankMsg::Send {
+            to_address: config.community_contract.to_string(),
+            amount: vec![net_send.clone()],
+        }))
+        .add_attributes(vec![
+            attr("action", "redirect_blacklisted_claims"),
+            attr("depositor", address.to_string()),
+            attr("redirected_amount", net_send.amount),
+        ]))
+}
+
+pub fn query_blacklist(deps: Deps, address: String) -> StdResult<BlacklistResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let entry = BLACKLIST.may_load(deps.storage, &deps.api.addr_validate(&address)?)?;
+
+    Ok(BlacklistResponse {
+        address,
+        blacklisted: entry.is_some(),
+        blacklisted_at: entry.as_ref().map(|entry| entry.blacklisted_at),
+        redirectable_at: entry.map(|entry| entry.blacklisted_at + config.blacklist_dispute_window),
+    })
+}
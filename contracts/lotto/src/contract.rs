@@ -1,46 +1,188 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
+use crate::anchor::{
+    deposit_stable_msg, execute_retry_pending, handle_anchor_reply, read_pending_anchor_operations,
+    redeem_stable_msg, redeem_stable_submsg,
+};
+use crate::auto_claim::{
+    execute_deregister_auto_claim, execute_process_auto_claims, execute_register_auto_claim,
+    execute_update_auto_claim_bounty, query_auto_claim,
+};
+use crate::badges::{
+    badge_mint_submsgs, execute_claim_badges, execute_update_badge_config, handle_badge_reply,
+    query_badge_eligibility, record_sponsorship, BADGE_REPLY_ID,
+};
+use crate::buyback::{
+    execute_update_buyback_config, handle_buyback_reply, query_buyback, BUYBACK_REPLY_ID,
+};
+use crate::campaigns::{
+    query_operator_campaign_stats, record_campaign_deposit, validate_campaign_id,
+};
+use crate::charity::{
+    execute_update_charities, query_charities, record_donation, validate_charity_donation,
+};
+use crate::claim_agent::{
+    assert_claim_agent, execute_authorize_claim_agent, execute_revoke_claim_agent,
+    query_claim_agents,
+};
+use crate::claim_receipts::{
+    assert_no_pending_claim, execute_retry_claim, handle_claim_receipt_reply, query_claim_receipt,
+    record_claim_receipt, CLAIM_RECEIPT_REPLY_ID,
+};
+use crate::compliance::{
+    assert_not_blacklisted, execute_redirect_blacklisted_claims, execute_update_blacklist,
+    query_blacklist,
+};
+use crate::emission_decay::{execute_update_emission_decay, query_emission_schedule};
+use crate::epoch_ops::{
+    execute_epoch_operations, handle_fee_distribution_reply, init_epoch_operations,
+    query_checkpoints, query_epoch_operations, query_yield_stats, EPOCH_OPERATIONS_ORDER,
+    FEE_DISTRIBUTION_REPLY_ID,
+};
 use crate::error::ContractError;
+use crate::events::{
+    agent_claim_event, claim_event, deposit_event, insurance_shortfall_event, sponsor_event,
+    withdraw_event,
+};
+use crate::exit::execute_exit;
+use crate::gift_cards::{
+    execute_commit_gift_card, execute_fund_gift_card, execute_redeem_gift_card,
+    execute_refund_gift_card, query_gift_card, read_total_gift_cards,
+};
+use crate::glow_reserve::{execute_top_up_glow, query_glow_prize_reserve};
 use crate::helpers::{
-    calculate_value_of_aust_to_be_redeemed_for_lottery, calculate_winner_prize,
-    claim_unbonded_withdrawals, compute_global_operator_reward, compute_global_sponsor_reward,
-    compute_operator_reward, compute_sponsor_reward, decimal_from_ratio_or_one,
-    handle_depositor_operator_updates, handle_depositor_ticket_updates,
+    add_depositor_tickets, add_unbonding_claim, base64_encoded_tickets_to_vec_string_tickets,
+    calculate_boost_multiplier, calculate_boosted_winner_prize,
+    calculate_convertible_yield_tickets, calculate_prize_cap_excess, calculate_share_price,
+    calculate_share_price_twap, calculate_value_of_aust_to_be_redeemed_for_lottery,
+    calculate_winner_boost_multiplier, calculate_winner_prize, claim_unbonded_withdrawals,
+    compute_global_operator_reward, compute_global_sponsor_reward, compute_operator_reward,
+    compute_sponsor_reward, decimal_from_ratio_or_one, decimal_from_ratio_or_zero,
+    generate_quick_pick_sequences, get_minimum_matches_for_winning_ticket,
+    handle_depositor_operator_updates, handle_depositor_ticket_updates, is_valid_sequence,
+    record_operator_share_checkpoint, snapshot_winner_boost, validate_min_matches_for_prize,
+    validate_prize_distribution, vec_string_tickets_to_vec_binary_tickets,
     ExecuteLotteryRedeemedAustInfo,
 };
-use crate::prize_strategy::{execute_lottery, execute_prize};
-use crate::querier::{query_balance, query_exchange_rate};
+use crate::hooks::{
+    execute_update_hooks, handle_hook_reply, hook_submsgs, query_hooks, HOOK_REPLY_ID,
+};
+use crate::ibc_claim::{
+    execute_refund_ibc_claim, execute_update_ibc_claim_config, ibc_claim_transfer_msg,
+    query_ibc_claim, validate_claim_destination,
+};
+use crate::inbox::{execute_mark_seen, query_inbox, record_prize_claimed};
+use crate::instant_withdrawal_cap::{
+    execute_update_instant_withdrawal_cap, query_instant_withdrawal_cap, record_instant_withdrawal,
+};
+use crate::insurance::{
+    draw_insurance, execute_top_up_insurance, execute_update_insurance, execute_withdraw_insurance,
+    query_insurance, read_insurance,
+};
+use crate::jackpot_floor::{
+    execute_update_jackpot_floor, handle_jackpot_top_up_reply, query_jackpot_floor,
+    JACKPOT_TOP_UP_REPLY_ID,
+};
+use crate::keeper_allowlist::{execute_update_keeper_allowlist, query_keeper_allowlist};
+use crate::migration::{
+    execute_migrate_step, has_pending_migration_steps, migrate_to, query_migration_status,
+    LATEST_CONTRACT_VERSION,
+};
+use crate::payout_privacy::{execute_register_payout_hash, query_payout_hash, take_payout_address};
+use crate::permit::{
+    execute_deposit_with_permit, execute_fund_escrow, execute_withdraw_escrow, query_escrow,
+    read_total_escrowed,
+};
+use crate::pol::{execute_pol_deposit, execute_pol_withdraw};
+use crate::prize_caps::{execute_update_prize_caps, query_prize_caps, roll_over_prize_excess};
+use crate::prize_rebalancing::{execute_update_prize_rebalancing, query_prize_rebalancing};
+use crate::prize_strategy::{
+    calculate_tracked_aust, execute_lottery, execute_prize, execute_skim,
+    query_can_execute_lottery, query_can_execute_prize, query_next_lottery_projection, query_odds,
+    query_prize_execution_progress,
+};
+use crate::prize_templates::{
+    execute_remove_prize_template, execute_save_prize_template, query_prize_templates,
+    read_prize_template,
+};
+use crate::querier::{
+    query_address_voting_power_at_timestamp, query_aust_balance_and_exchange_rate, query_balance,
+    query_exchange_rate, query_total_voting_balance_at_timestamp,
+};
+use crate::rate_guard::{
+    execute_accept_exchange_rate, execute_update_rate_guard, query_rate_guard,
+};
+use crate::receipt::{
+    execute_create_receipt_token, execute_receipt_deposit, execute_redeem_receipt,
+    handle_receipt_token_reply, query_receipt_token, read_total_receipt_aust,
+    RECEIPT_TOKEN_REPLY_ID,
+};
+use crate::savings::{
+    execute_convert_savings_to_tickets, execute_deposit_savings, execute_withdraw_savings,
+    query_savings,
+};
+use crate::share_donation::execute_donate_shares_to_sponsorship;
+use crate::sponsor_weight::execute_set_sponsor_weight;
 use crate::state::{
-    old_read_depositors, old_read_lottery_info, old_remove_depositor_info, old_remove_lottery_info,
-    parse_length, read_depositor_info, read_depositor_stats, read_depositor_stats_at_height,
+    read_current_epoch, read_depositor_info, read_depositor_ledger, read_depositor_stats,
+    read_depositor_stats_at_height, read_depositor_tickets, read_depositor_tickets_page,
     read_depositors_info, read_depositors_stats, read_lottery_info, read_lottery_prizes,
-    read_operator_info, read_sponsor_info, store_depositor_info, store_lottery_info,
-    store_operator_info, store_sponsor_info, Config, DepositorInfo, LotteryInfo, OperatorInfo,
-    Pool, PrizeInfo, SponsorInfo, State, CONFIG, OLDCONFIG, OLDPOOL, OLDSTATE, OLD_PRIZES, POOL,
-    PRIZES, STATE, TICKETS,
+    read_operator_depositors, read_operator_info, read_operator_reward_ledger,
+    read_operator_share_checkpoints, read_pending_tickets, read_pool_at_height,
+    read_share_price_checkpoints, read_sponsor_info, read_state_at_height,
+    read_total_pending_tickets, remove_depositor_ticket, remove_depositor_tickets,
+    remove_ticket_acquisition, store_depositor_info, store_operator_info, store_sponsor_info,
+    update_depositor_ledger, ClaimReceipt, Config, ContractVersion, DepositorInfo, LotteryInfo,
+    OperatorInfo, Pool, PrizeBoost, PrizeInfo, QueuedWithdrawal, SponsorInfo, State, CONFIG,
+    CONTRACT_VERSION, OPERATOR_REWARD_LEDGERS, POOL, PRIZES, PRIZE_BOOSTS, PRIZE_BUCKET_DONATIONS,
+    PRIZE_DONORS, QUICK_PICK_NONCES, STATE, TICKETS,
+};
+use crate::syndicate::{
+    execute_claim_syndicate_lottery, execute_create_syndicate, execute_join_syndicate,
+    execute_leave_syndicate, query_syndicate, query_syndicate_member,
+};
+use crate::timelock::{
+    effective_instant_withdrawal_fee, execute_cancel_scheduled_update,
+    execute_schedule_config_update, execute_scheduled_update, query_config_versions,
+    query_scheduled_updates,
+};
+use crate::winners_root::query_winner_leaves;
+use crate::withdrawal_queue::{
+    enqueue_withdrawal, execute_process_withdrawal_queue, query_queued_withdrawals,
+    query_withdrawal_queue, read_withdrawal_queue_state, should_queue_withdrawal,
 };
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{
-    attr, coin, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
-    Order, Response, StdError, StdResult, Timestamp, Uint128, WasmMsg,
+    attr, coin, from_binary, to_binary, Addr, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, Deps,
+    DepsMut, Env, MessageInfo, Order, Reply, Response, StdError, StdResult, SubMsg, Timestamp,
+    Uint128, WasmMsg,
 };
 use cw0::{Duration, Expiration};
-use cw20::Cw20ExecuteMsg;
-use cw_storage_plus::U64Key;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::{Bound, U64Key};
+use glow_protocol::common::Pagination;
 use glow_protocol::distributor::ExecuteMsg as FaucetExecuteMsg;
-use glow_protocol::lotto::NUM_PRIZE_BUCKETS;
 use glow_protocol::lotto::{
-    BoostConfig, Claim, ConfigResponse, DepositorInfoResponse, DepositorStatsResponse,
-    DepositorsInfoResponse, DepositorsStatsResponse, ExecuteMsg, InstantiateMsg,
-    LotteryBalanceResponse, LotteryInfoResponse, MigrateMsg, OperatorInfoResponse, PoolResponse,
-    PrizeInfoResponse, PrizeInfosResponse, QueryMsg, RewardEmissionsIndex, SponsorInfoResponse,
-    StateResponse, TicketInfoResponse,
+    AccountResponse, BoostConfig, CharityDonation, Claim, ClaimDestination, ClaimReceiptStatus,
+    ClaimableAtResponse, ConfigResponse, Cw20HookMsg, DepositReceipt, DepositorInfoResponse,
+    DepositorLedgerResponse, DepositorStatsResponse, DepositorTicketsResponse,
+    DepositorsInfoResponse, DepositorsStatsResponse, ExecuteMsg, GiftBatchItem, GlowHookMsg,
+    IbcClaimConfig, InstantiateMsg, LotteryBalanceResponse, LotteryInfoResponse,
+    LotteryWinnerResponse, LotteryWinnersResponse, LoyaltyConfig, MigrateMsg, MissedLotteryPolicy,
+    OperatorDepositorsResponse, OperatorInfoResponse, OperatorShareCheckpointResponse,
+    OperatorStatsResponse, PauseState, PayoutAsset, PayoutReveal, PendingAnchorOperationsResponse,
+    PoolResponse, PrizeBoostDetailResponse, PrizeDonationsResponse, PrizeInfoResponse,
+    PrizeInfosResponse, QueryMsg, RedepositConfig, ReserveDestination, RewardEmissionsIndex,
+    SequenceCoverageResponse, SequencePrefixCoverage, SharePriceResponse, SponsorInfoResponse,
+    SponsorLockConfig, StateResponse, TicketInfoResponse, UnbondingClaimResponse,
+};
+use glow_protocol::lotto::{
+    MAX_COVERAGE_PREFIX_LEN, MAX_TICKET_LENGTH, MIN_TICKET_LENGTH, SECONDS_PER_WEEK, TICKET_LENGTH,
 };
 use glow_protocol::querier::deduct_tax;
-use moneymarket::market::{Cw20HookMsg, EpochStateResponse, ExecuteMsg as AnchorMsg};
+use moneymarket::market::{EpochStateResponse, ExecuteMsg as AnchorMsg};
 use std::ops::{Add, Sub};
-use std::str::from_utf8;
 use terraswap::querier::query_token_balance;
 
 pub const INITIAL_DEPOSIT_AMOUNT: u128 = 10_000_000;
@@ -48,6 +190,8 @@ pub const MAX_CLAIMS: u8 = 15;
 pub const THIRTY_MINUTE_TIME: u64 = 60 * 30;
 pub const MAX_HOLDERS_FLOOR: u8 = 10;
 pub const MAX_HOLDERS_CAP: u8 = 100;
+pub const MAX_GIFT_BATCH_TICKETS: u64 = 1000;
+pub const MAX_PRIZE_INFOS_LOTTERIES: usize = 100;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -67,19 +211,28 @@ pub fn instantiate(
         return Err(ContractError::InvalidDepositInstantiation(initial_deposit));
     }
 
-    // Validate prize distribution
-    if msg.prize_distribution.len() != NUM_PRIZE_BUCKETS {
-        return Err(ContractError::InvalidPrizeDistribution {});
+    // Validate ticket length, prizes are split in a bucket per number of matches
+    let ticket_length = msg.ticket_length.unwrap_or(TICKET_LENGTH as u8);
+    if (ticket_length as usize) < MIN_TICKET_LENGTH || MAX_TICKET_LENGTH < ticket_length as usize {
+        return Err(ContractError::InvalidTicketLength {
+            min: MIN_TICKET_LENGTH,
+            max: MAX_TICKET_LENGTH,
+        });
     }
+    let num_prize_buckets = ticket_length as usize + 1;
 
-    let mut sum = Decimal256::zero();
-    for item in msg.prize_distribution.iter() {
-        sum += *item;
+    // Validate prize distribution and glow prize buckets
+    validate_prize_distribution(&msg.prize_distribution, num_prize_buckets)?;
+    if msg.glow_prize_buckets.len() != num_prize_buckets {
+        return Err(ContractError::InvalidGlowPrizeBuckets {});
     }
 
-    if sum != Decimal256::one() {
-        return Err(ContractError::InvalidPrizeDistribution {});
-    }
+    // Validate the minimum matches of a winning ticket, derived from the prize distribution if not set
+    let min_matches_for_prize = match msg.min_matches_for_prize {
+        Some(min_matches_for_prize) => min_matches_for_prize,
+        None => get_minimum_matches_for_winning_ticket(&msg.prize_distribution)? as u8,
+    };
+    validate_min_matches_for_prize(min_matches_for_prize, &msg.prize_distribution)?;
 
     // Validate factors
     if msg.reserve_factor > Decimal256::one() {
@@ -154,6 +307,23 @@ pub fn instantiate(
             glow_prize_buckets: msg.glow_prize_buckets,
             paused: false,
             lotto_winner_boost_config,
+            reserve_destination: ReserveDestination::Community,
+            fee_distributor_contract: None,
+            lp_staking_contract: None,
+            lp_staking_reserve_share: Decimal256::zero(),
+            min_deposit_amount: Uint256::zero(),
+            loyalty_config: LoyaltyConfig::default(),
+            sponsor_lock_config: SponsorLockConfig::default(),
+            ibc_claim_config: IbcClaimConfig::default(),
+            operator_reward_vesting_epochs: 0,
+            pause_state: PauseState::default(),
+            guardians: vec![],
+            ticket_eligibility_delay: 0,
+            missed_lottery_policy: MissedLotteryPolicy::SkipToNext,
+            blacklist_dispute_window: 0,
+            time_weighted_odds: false,
+            ticket_length,
+            min_matches_for_prize: Some(min_matches_for_prize),
         },
     )?;
 
@@ -162,6 +332,14 @@ pub fn instantiate(
         return Err(ContractError::InvalidFirstLotteryExec {});
     }
 
+    CONTRACT_VERSION.save(
+        deps.storage,
+        &ContractVersion {
+            version: LATEST_CONTRACT_VERSION,
+            target_version: LATEST_CONTRACT_VERSION,
+        },
+    )?;
+
     // Query exchange_rate from anchor money market
     let aust_exchange_rate: Decimal256 = query_exchange_rate(
         deps.as_ref(),
@@ -172,18 +350,21 @@ pub fn instantiate(
     )?
     .exchange_rate;
 
+    let next_epoch = Duration::Time(msg.epoch_interval).after(&env.block);
+    init_epoch_operations(deps.storage, next_epoch)?;
+
     STATE.save(
         deps.storage,
         &State {
             total_tickets: Uint256::zero(),
             total_reserve: Uint256::zero(),
-            prize_buckets: [Uint256::zero(); NUM_PRIZE_BUCKETS],
+            prize_buckets: vec![Uint256::zero(); num_prize_buckets],
             current_lottery: 0,
             next_lottery_time: Expiration::AtTime(Timestamp::from_seconds(
                 msg.initial_lottery_execution,
             )),
             next_lottery_exec_time: Expiration::Never {},
-            next_epoch: Duration::Time(msg.epoch_interval).after(&env.block),
+            next_epoch,
             operator_reward_emission_index: RewardEmissionsIndex {
                 last_reward_updated: env.block.height,
                 global_reward_index: Decimal256::zero(),
@@ -195,7 +376,11 @@ pub fn instantiate(
                 glow_emission_rate: msg.initial_sponsor_glow_emission_rate,
             },
             last_lottery_execution_aust_exchange_rate: aust_exchange_rate,
+            missed_lotteries: 0,
+            missed_lottery_prizes: Uint256::zero(),
+            yield_deficit: Uint256::zero(),
         },
+        env.block.height,
     )?;
 
     POOL.save(
@@ -205,7 +390,13 @@ pub fn instantiate(
             total_user_shares: Uint256::zero(),
             total_sponsor_lottery_deposits: Uint256::zero(),
             total_operator_shares: Uint256::zero(),
+            total_sponsor_lock_bonus: Uint256::zero(),
+            total_sponsor_weight_bonus: Uint256::zero(),
+            total_pol_aust: Uint256::zero(),
+            total_pol_principal: Uint256::zero(),
+            total_savings_aust: Uint256::zero(),
         },
+        env.block.height,
     )?;
 
     // Deduct taxes that will be payed when transferring to anchor
@@ -232,20 +423,32 @@ pub fn instantiate(
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let res = handle_execute(deps.branch(), env.clone(), info, msg)?;
+
+    #[cfg(feature = "debug-invariants")]
+    crate::invariants::assert_invariants(deps.as_ref(), &env)?;
+
+    Ok(res)
+}
+
+fn handle_execute(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    if let ExecuteMsg::MigrateOldDepositors { limit } = msg {
-        return migrate_old_depositors(deps, env, limit);
+    if let ExecuteMsg::MigrateStep { step_id, limit } = msg {
+        return execute_migrate_step(deps, env, step_id, limit);
     }
 
     if let ExecuteMsg::UpdateConfig {
         owner,
         oracle_addr,
-        reserve_factor,
-        instant_withdrawal_fee,
         unbonding_period,
         epoch_interval,
         max_holders,
@@ -254,15 +457,20 @@ pub fn execute(
         lotto_winner_boost_config,
         operator_glow_emission_rate,
         sponsor_glow_emission_rate,
+        min_deposit_amount,
+        operator_reward_vesting_epochs,
+        deposits_paused,
+        withdrawals_paused,
+        lottery_paused,
+        claims_paused,
     } = msg
     {
         return execute_update_config(
             deps,
+            env,
             info,
             owner,
             oracle_addr,
-            reserve_factor,
-            instant_withdrawal_fee,
             unbonding_period,
             epoch_interval,
             max_holders,
@@ -271,15 +479,116 @@ pub fn execute(
             lotto_winner_boost_config,
             operator_glow_emission_rate,
             sponsor_glow_emission_rate,
+            min_deposit_amount,
+            operator_reward_vesting_epochs,
+            deposits_paused,
+            withdrawals_paused,
+            lottery_paused,
+            claims_paused,
         );
     }
 
+    // Everything is stopped while the steps of a migration are pending
     let config = CONFIG.load(deps.storage)?;
     if config.paused {
         return Err(ContractError::ContractPaused {});
     }
 
+    // Check the pause flag of the requested execution path.
+    // The cw20 hooks are checked by receive_cw20
+    match msg {
+        ExecuteMsg::Deposit { .. }
+        | ExecuteMsg::QuickPickDeposit { .. }
+        | ExecuteMsg::ClaimTickets { .. }
+        | ExecuteMsg::ConvertYieldToTickets { .. }
+        | ExecuteMsg::Gift { .. }
+        | ExecuteMsg::GiftBatch { .. }
+        | ExecuteMsg::DepositFor { .. }
+        | ExecuteMsg::Sponsor { .. }
+        | ExecuteMsg::DonateToPrizeBucket { .. }
+        | ExecuteMsg::JoinSyndicate { .. }
+        | ExecuteMsg::FundEscrow { .. }
+        | ExecuteMsg::DepositWithPermit { .. }
+        | ExecuteMsg::FundGiftCard { .. }
+        | ExecuteMsg::RedeemGiftCard { .. }
+        | ExecuteMsg::ReceiptDeposit {}
+        | ExecuteMsg::PolDeposit {}
+        | ExecuteMsg::DepositSavings {}
+        | ExecuteMsg::ConvertSavingsToTickets { .. }
+            if config.pause_state.deposits_paused =>
+        {
+            return Err(ContractError::DepositsPaused {});
+        }
+        ExecuteMsg::Withdraw { .. }
+        | ExecuteMsg::Exit { .. }
+        | ExecuteMsg::ProcessWithdrawalQueue { .. }
+        | ExecuteMsg::SponsorWithdraw {}
+        | ExecuteMsg::PolWithdraw { .. }
+        | ExecuteMsg::WithdrawSavings { .. }
+        | ExecuteMsg::DonateSharesToSponsorship { .. }
+        | ExecuteMsg::LeaveSyndicate { .. }
+        | ExecuteMsg::WithdrawEscrow { .. }
+        | ExecuteMsg::RefundGiftCard { .. }
+        | ExecuteMsg::DustSweep { .. }
+            if config.pause_state.withdrawals_paused =>
+        {
+            return Err(ContractError::WithdrawalsPaused {});
+        }
+        ExecuteMsg::ExecuteLottery {} | ExecuteMsg::ExecutePrize { .. } | ExecuteMsg::Skim {}
+            if config.pause_state.lottery_paused =>
+        {
+            return Err(ContractError::LotteryPaused {});
+        }
+        ExecuteMsg::Claim { .. }
+        | ExecuteMsg::ProcessAutoClaims { .. }
+        | ExecuteMsg::ClaimLottery { .. }
+        | ExecuteMsg::ClaimLotteryFor { .. }
+        | ExecuteMsg::RetryClaim { .. }
+        | ExecuteMsg::RefundIbcClaim { .. }
+        | ExecuteMsg::ClaimRewards {}
+        | ExecuteMsg::ClaimBadges {}
+        | ExecuteMsg::ClaimSyndicateLottery { .. }
+        | ExecuteMsg::Exit { .. }
+            if config.pause_state.claims_paused =>
+        {
+            return Err(ContractError::ClaimsPaused {});
+        }
+        _ => {}
+    }
+
+    // Blacklisted addresses can't deposit, sponsor, gift or claim
     match msg {
+        ExecuteMsg::Deposit { .. }
+        | ExecuteMsg::QuickPickDeposit { .. }
+        | ExecuteMsg::ClaimTickets { .. }
+        | ExecuteMsg::ConvertYieldToTickets { .. }
+        | ExecuteMsg::Gift { .. }
+        | ExecuteMsg::GiftBatch { .. }
+        | ExecuteMsg::DepositFor { .. }
+        | ExecuteMsg::Sponsor { .. }
+        | ExecuteMsg::DonateToPrizeBucket { .. }
+        | ExecuteMsg::DonateSharesToSponsorship { .. }
+        | ExecuteMsg::JoinSyndicate { .. }
+        | ExecuteMsg::FundEscrow { .. }
+        | ExecuteMsg::FundGiftCard { .. }
+        | ExecuteMsg::RedeemGiftCard { .. }
+        | ExecuteMsg::ReceiptDeposit {}
+        | ExecuteMsg::DepositSavings {}
+        | ExecuteMsg::ConvertSavingsToTickets { .. }
+        | ExecuteMsg::Claim { .. }
+        | ExecuteMsg::RegisterAutoClaim {}
+        | ExecuteMsg::ClaimLottery { .. }
+        | ExecuteMsg::ClaimLotteryFor { .. }
+        | ExecuteMsg::RetryClaim { .. }
+        | ExecuteMsg::ClaimSyndicateLottery { .. }
+        | ExecuteMsg::Exit { .. } => {
+            assert_not_blacklisted(deps.storage, &info.sender)?;
+        }
+        _ => {}
+    }
+
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::RegisterContracts {
             gov_contract,
             community_contract,
@@ -296,36 +605,245 @@ pub fn execute(
         ExecuteMsg::Deposit {
             encoded_tickets,
             operator,
-        } => execute_deposit(deps, env, info, encoded_tickets, operator),
+            campaign_id,
+        } => execute_deposit(deps, env, info, encoded_tickets, operator, campaign_id),
+        ExecuteMsg::QuickPickDeposit {
+            quick_pick,
+            operator,
+        } => execute_quick_pick_deposit(deps, env, info, quick_pick, operator),
         ExecuteMsg::ClaimTickets { encoded_tickets } => {
             execute_claim_tickets(deps, env, info, encoded_tickets)
         }
+        ExecuteMsg::ConvertYieldToTickets { max_tickets } => {
+            execute_convert_yield_to_tickets(deps, env, info, max_tickets)
+        }
         ExecuteMsg::Gift {
             encoded_tickets,
             recipient,
             operator,
         } => execute_gift(deps, env, info, encoded_tickets, recipient, operator),
+        ExecuteMsg::GiftBatch { gifts } => execute_gift_batch(deps, env, info, gifts),
+        ExecuteMsg::DepositFor {
+            recipient,
+            encoded_tickets,
+            operator,
+        } => deposit(
+            deps,
+            env,
+            info,
+            Some(recipient),
+            operator,
+            None,
+            encoded_tickets,
+        ),
         ExecuteMsg::Sponsor {
             award,
             prize_distribution,
-        } => execute_sponsor(deps, env, info, award, prize_distribution),
+            prize_template,
+            lock_duration,
+        } => execute_sponsor(
+            deps,
+            env,
+            info,
+            award,
+            prize_distribution,
+            prize_template,
+            lock_duration,
+        ),
+        ExecuteMsg::DonateToPrizeBucket { bucket_index } => {
+            execute_donate_to_prize_bucket(deps, env, info, bucket_index)
+        }
+        ExecuteMsg::ProcessWithdrawalQueue { limit } => {
+            execute_process_withdrawal_queue(deps, env, limit)
+        }
+        ExecuteMsg::FundEscrow { pubkey } => execute_fund_escrow(deps, info, pubkey),
+        ExecuteMsg::WithdrawEscrow { amount } => execute_withdraw_escrow(deps, info, amount),
+        ExecuteMsg::DepositWithPermit { permit, signature } => {
+            execute_deposit_with_permit(deps, env, info, permit, signature)
+        }
+        ExecuteMsg::FundGiftCard {
+            code_hash,
+            num_tickets,
+            expires,
+        } => execute_fund_gift_card(deps, env, info, code_hash, num_tickets, expires),
+        ExecuteMsg::CommitGiftCard { commitment } => {
+            execute_commit_gift_card(deps, env, info, commitment)
+        }
+        ExecuteMsg::RedeemGiftCard {
+            code,
+            encoded_tickets,
+        } => execute_redeem_gift_card(deps, env, info, code, encoded_tickets),
+        ExecuteMsg::RefundGiftCard { code_hash } => {
+            execute_refund_gift_card(deps, env, info, code_hash)
+        }
         ExecuteMsg::SponsorWithdraw {} => execute_sponsor_withdraw(deps, env, info),
-        ExecuteMsg::Withdraw { amount, instant } => {
-            execute_withdraw(deps, env, info, amount, instant)
+        ExecuteMsg::DonateSharesToSponsorship { amount } => {
+            execute_donate_shares_to_sponsorship(deps, env, info, amount)
+        }
+        ExecuteMsg::PolDeposit {} => execute_pol_deposit(deps, env, info),
+        ExecuteMsg::PolWithdraw { amount } => execute_pol_withdraw(deps, env, info, amount),
+        ExecuteMsg::Withdraw {
+            amount,
+            instant,
+            tickets,
+            recipient,
+        } => execute_withdraw(deps, env, info, amount, instant, tickets, recipient),
+        ExecuteMsg::Claim { recipient } => execute_claim_unbonded(deps, env, info, recipient),
+        ExecuteMsg::RegisterAutoClaim {} => execute_register_auto_claim(deps, env, info),
+        ExecuteMsg::DeregisterAutoClaim {} => execute_deregister_auto_claim(deps, info),
+        ExecuteMsg::ProcessAutoClaims { limit } => {
+            execute_process_auto_claims(deps, env, info, limit)
+        }
+        ExecuteMsg::Exit { instant } => execute_exit(deps, env, info, instant),
+        ExecuteMsg::ClaimLottery {
+            lottery_ids,
+            redeposit,
+            destination,
+            donation,
+            payout,
+            payout_asset,
+        } => execute_claim_lottery(
+            deps,
+            env,
+            info,
+            lottery_ids,
+            redeposit,
+            destination,
+            donation,
+            payout,
+            payout_asset,
+        ),
+        ExecuteMsg::RegisterPayoutHash { payout_hash } => {
+            execute_register_payout_hash(deps, info, payout_hash)
+        }
+        ExecuteMsg::AuthorizeClaimAgent { agent } => {
+            execute_authorize_claim_agent(deps, env, info, agent)
         }
-        ExecuteMsg::Claim {} => execute_claim_unbonded(deps, env, info),
-        ExecuteMsg::ClaimLottery { lottery_ids } => {
-            execute_claim_lottery(deps, env, info, lottery_ids)
+        ExecuteMsg::RevokeClaimAgent { agent } => execute_revoke_claim_agent(deps, info, agent),
+        ExecuteMsg::ClaimLotteryFor {
+            winner,
+            lottery_ids,
+        } => execute_claim_lottery_for(deps, env, info, winner, lottery_ids),
+        ExecuteMsg::RetryClaim { receipt_id } => execute_retry_claim(deps, info, receipt_id),
+        ExecuteMsg::UpdateBadgeConfig { badge_config } => {
+            execute_update_badge_config(deps, info, badge_config)
+        }
+        ExecuteMsg::ClaimBadges {} => execute_claim_badges(deps, env, info),
+        ExecuteMsg::UpdateCharities { add, remove } => {
+            execute_update_charities(deps, info, add, remove)
         }
         ExecuteMsg::ClaimRewards {} => execute_claim_rewards(deps, env, info),
+        ExecuteMsg::MarkSeen {} => execute_mark_seen(deps, info),
+        ExecuteMsg::CreateSyndicate {} => execute_create_syndicate(deps, info),
+        ExecuteMsg::JoinSyndicate {
+            syndicate_id,
+            encoded_tickets,
+        } => execute_join_syndicate(deps, env, info, syndicate_id, encoded_tickets),
+        ExecuteMsg::LeaveSyndicate { syndicate_id } => {
+            execute_leave_syndicate(deps, env, info, syndicate_id)
+        }
+        ExecuteMsg::ClaimSyndicateLottery {
+            syndicate_id,
+            lottery_ids,
+        } => execute_claim_syndicate_lottery(deps, env, info, syndicate_id, lottery_ids),
         ExecuteMsg::ExecuteLottery {} => execute_lottery(deps, env, info),
         ExecuteMsg::ExecutePrize { limit } => execute_prize(deps, env, info, limit),
-        ExecuteMsg::ExecuteEpochOps {} => execute_epoch_ops(deps, env),
+        ExecuteMsg::ExecuteEpochOps { operations } => execute_epoch_operations(
+            deps,
+            env,
+            operations.unwrap_or_else(|| EPOCH_OPERATIONS_ORDER.to_vec()),
+        ),
+        ExecuteMsg::RetryPending {} => execute_retry_pending(deps, env, info),
+        ExecuteMsg::DustSweep { depositors } => execute_dust_sweep(deps, env, info, depositors),
+        ExecuteMsg::Skim {} => execute_skim(deps, env, info),
+        ExecuteMsg::AddGuardian { guardian } => execute_add_guardian(deps, info, guardian),
+        ExecuteMsg::RemoveGuardian { guardian } => execute_remove_guardian(deps, info, guardian),
+        ExecuteMsg::UpdateHooks { add, remove } => execute_update_hooks(deps, info, add, remove),
+        ExecuteMsg::ScheduleConfigUpdate { update, eta } => {
+            execute_schedule_config_update(deps, env, info, update, eta)
+        }
+        ExecuteMsg::ExecuteScheduledUpdate { id } => execute_scheduled_update(deps, env, id),
+        ExecuteMsg::CancelScheduledUpdate { id } => {
+            execute_cancel_scheduled_update(deps, env, info, id)
+        }
+        ExecuteMsg::UpdateIbcClaimConfig { ibc_claim_config } => {
+            execute_update_ibc_claim_config(deps, info, ibc_claim_config)
+        }
+        ExecuteMsg::RefundIbcClaim { id } => execute_refund_ibc_claim(deps, env, info, id),
+        ExecuteMsg::UpdateBlacklist {
+            add,
+            remove,
+            dispute_window,
+        } => execute_update_blacklist(deps, env, info, add, remove, dispute_window),
+        ExecuteMsg::RedirectBlacklistedClaims { address } => {
+            execute_redirect_blacklisted_claims(deps, env, info, address)
+        }
+        ExecuteMsg::UpdateBuybackConfig { buyback_config } => {
+            execute_update_buyback_config(deps, info, buyback_config)
+        }
+        ExecuteMsg::UpdateInsurance { reserve_share } => {
+            execute_update_insurance(deps, info, reserve_share)
+        }
+        ExecuteMsg::UpdateJackpotFloor { floor } => execute_update_jackpot_floor(deps, info, floor),
+        ExecuteMsg::UpdateRateGuard {
+            max_deviation_per_hour,
+        } => execute_update_rate_guard(deps, info, max_deviation_per_hour),
+        ExecuteMsg::UpdatePrizeRebalancing { rebalancing } => execute_update_prize_rebalancing(
+            deps,
+            info,
+            rebalancing.map(|rebalancing| *rebalancing),
+        ),
+        ExecuteMsg::UpdatePrizeCaps { prize_caps } => {
+            execute_update_prize_caps(deps, info, prize_caps)
+        }
+        ExecuteMsg::UpdateAutoClaimBounty { bounty_rate } => {
+            execute_update_auto_claim_bounty(deps, info, bounty_rate)
+        }
+        ExecuteMsg::UpdateKeeperAllowlist { allowlist } => {
+            execute_update_keeper_allowlist(deps, info, allowlist)
+        }
+        ExecuteMsg::AcceptExchangeRate { rate } => {
+            execute_accept_exchange_rate(deps, env, info, rate)
+        }
+        ExecuteMsg::UpdateInstantWithdrawalCap { cap } => {
+            execute_update_instant_withdrawal_cap(deps, info, cap)
+        }
+        ExecuteMsg::TopUpInsurance { amount } => execute_top_up_insurance(deps, env, info, amount),
+        ExecuteMsg::WithdrawInsurance { amount, recipient } => {
+            execute_withdraw_insurance(deps, info, amount, recipient)
+        }
+        ExecuteMsg::UpdateEmissionDecay {
+            operator_decay,
+            sponsor_decay,
+        } => execute_update_emission_decay(deps, env, info, operator_decay, sponsor_decay),
+        ExecuteMsg::SetSponsorWeight { sponsor, weight } => {
+            execute_set_sponsor_weight(deps, env, info, sponsor, weight)
+        }
+        ExecuteMsg::CreatePrizeTemplate {
+            name,
+            prize_distribution,
+        } => execute_save_prize_template(deps, info, name, prize_distribution, true),
+        ExecuteMsg::UpdatePrizeTemplate {
+            name,
+            prize_distribution,
+        } => execute_save_prize_template(deps, info, name, prize_distribution, false),
+        ExecuteMsg::RemovePrizeTemplate { name } => execute_remove_prize_template(deps, info, name),
+        ExecuteMsg::CreateReceiptToken {
+            code_id,
+            name,
+            symbol,
+        } => execute_create_receipt_token(deps, env, info, code_id, name, symbol),
+        ExecuteMsg::ReceiptDeposit {} => execute_receipt_deposit(deps, env, info),
+        ExecuteMsg::DepositSavings {} => execute_deposit_savings(deps, env, info),
+        ExecuteMsg::WithdrawSavings { amount } => execute_withdraw_savings(deps, env, info, amount),
+        ExecuteMsg::ConvertSavingsToTickets {
+            encoded_tickets,
+            amount,
+        } => execute_convert_savings_to_tickets(deps, env, info, encoded_tickets, amount),
+        ExecuteMsg::TopUpGlow { amount } => execute_top_up_glow(deps, env, info, amount),
         ExecuteMsg::UpdateConfig {
             owner,
             oracle_addr,
-            reserve_factor,
-            instant_withdrawal_fee,
             unbonding_period,
             epoch_interval,
             max_holders,
@@ -334,13 +852,18 @@ pub fn execute(
             lotto_winner_boost_config,
             operator_glow_emission_rate,
             sponsor_glow_emission_rate,
+            min_deposit_amount,
+            operator_reward_vesting_epochs,
+            deposits_paused,
+            withdrawals_paused,
+            lottery_paused,
+            claims_paused,
         } => execute_update_config(
             deps,
+            env,
             info,
             owner,
             oracle_addr,
-            reserve_factor,
-            instant_withdrawal_fee,
             unbonding_period,
             epoch_interval,
             max_holders,
@@ -349,28 +872,74 @@ pub fn execute(
             lotto_winner_boost_config,
             operator_glow_emission_rate,
             sponsor_glow_emission_rate,
+            min_deposit_amount,
+            operator_reward_vesting_epochs,
+            deposits_paused,
+            withdrawals_paused,
+            lottery_paused,
+            claims_paused,
         ),
         ExecuteMsg::UpdateLotteryConfig {
             lottery_interval,
             block_time,
             ticket_price,
-            prize_distribution,
             round_delta,
+            loyalty_config,
+            sponsor_lock_config,
+            ticket_eligibility_delay,
+            missed_lottery_policy,
+            time_weighted_odds,
         } => execute_update_lottery_config(
             deps,
             info,
             lottery_interval,
             block_time,
             ticket_price,
-            prize_distribution,
             round_delta,
+            loyalty_config,
+            sponsor_lock_config,
+            ticket_eligibility_delay,
+            missed_lottery_policy,
+            time_weighted_odds,
+        ),
+        ExecuteMsg::UpdateReserveDestination {
+            reserve_destination,
+            fee_distributor_contract,
+            lp_staking_contract,
+            lp_staking_reserve_share,
+        } => execute_update_reserve_destination(
+            deps,
+            info,
+            reserve_destination,
+            fee_distributor_contract,
+            lp_staking_contract,
+            lp_staking_reserve_share,
         ),
-        ExecuteMsg::MigrateOldDepositors { .. } => Err(ContractError::Std(StdError::generic_err(
-            "Cannot call MigrateLoop when unpaused.",
+        ExecuteMsg::MigrateStep { .. } => Err(ContractError::Std(StdError::generic_err(
+            "Cannot call MigrateStep when unpaused.",
         ))),
     }
 }
 
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::RedeemReceipt {} => {
+            // Redeeming receipt tokens withdraws their stable
+            if config.pause_state.withdrawals_paused {
+                return Err(ContractError::WithdrawalsPaused {});
+            }
+            execute_redeem_receipt(deps, env, info, cw20_msg.sender, cw20_msg.amount)
+        }
+    }
+}
+
 pub fn execute_register_contracts(
     deps: DepsMut,
     info: MessageInfo,
@@ -406,24 +975,22 @@ pub fn deposit(
     info: MessageInfo,
     recipient: Option<String>,
     new_operator_addr: Option<String>,
+    campaign_id: Option<String>,
     encoded_tickets: String,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let mut state = STATE.load(deps.storage)?;
-    let mut pool = POOL.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
 
-    // Get the aust exchange rate
-    let aust_exchange_rate = query_exchange_rate(
-        deps.as_ref(),
-        config.anchor_contract.to_string(),
-        env.block.height,
-    )?
-    .exchange_rate;
+    if let Some(campaign_id) = &campaign_id {
+        validate_campaign_id(campaign_id)?;
+    }
 
     // Validate that the lottery has not already started
     let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
     if current_lottery.rand_round != 0 {
-        return Err(ContractError::LotteryAlreadyStarted {});
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
     }
 
     // Get the amount of funds sent in the base stable denom
@@ -442,7 +1009,6 @@ pub fn deposit(
     } else {
         info.sender.clone()
     };
-    let mut depositor_info: DepositorInfo = read_depositor_info(deps.storage, &depositor);
 
     // Validate that the deposit amount is non zero
     if deposit_amount.is_zero() {
@@ -453,6 +1019,13 @@ pub fn deposit(
         };
     }
 
+    if deposit_amount < config.min_deposit_amount {
+        return Err(ContractError::DepositBelowMinimum {
+            amount: deposit_amount,
+            min_deposit_amount: config.min_deposit_amount,
+        });
+    }
+
     // Deduct tx taxes when calculating the net deposited amount in anchor
     let net_coin_amount = deduct_tax(
         deps.as_ref(),
@@ -461,6 +1034,103 @@ pub fn deposit(
 
     let post_tax_deposit_amount = Uint256::from(net_coin_amount.amount);
 
+    let depositor_info = read_depositor_info(deps.storage, &depositor);
+    // Campaigns belong to the operator the depositor's shares count for
+    if campaign_id.is_some() && !depositor_info.operator_registered() && new_operator_addr.is_none()
+    {
+        return Err(ContractError::CampaignWithoutOperator {});
+    }
+
+    let shares_before = depositor_info.shares;
+    let (number_of_new_tickets, minted_aust) = process_deposit(
+        deps.branch(),
+        &env,
+        &config,
+        &depositor,
+        post_tax_deposit_amount,
+        encoded_tickets,
+        new_operator_addr,
+    )?;
+    let depositor_info = read_depositor_info(deps.storage, &depositor);
+    let shares_minted = depositor_info.shares - shares_before;
+
+    let mut campaign_attrs = vec![];
+    if let Some(campaign_id) = campaign_id {
+        record_campaign_deposit(
+            deps.storage,
+            &depositor_info.operator_addr,
+            &campaign_id,
+            post_tax_deposit_amount,
+            number_of_new_tickets,
+        )?;
+        campaign_attrs.push(attr("operator", depositor_info.operator_addr.to_string()));
+        campaign_attrs.push(attr("campaign_id", campaign_id));
+    }
+
+    // save depositor and state information
+    Ok(Response::new()
+        .set_data(to_binary(&DepositReceipt {
+            recipient: depositor.to_string(),
+            shares_minted,
+            tickets: number_of_new_tickets,
+            aust_minted: minted_aust,
+        })?)
+        .add_message(deposit_stable_msg(&config, post_tax_deposit_amount)?)
+        .add_submessages(hook_submsgs(
+            deps.storage,
+            GlowHookMsg::OnDeposit {
+                depositor: info.sender.to_string(),
+                recipient: depositor.to_string(),
+                amount: deposit_amount,
+                tickets: number_of_new_tickets,
+            },
+        )?)
+        .add_event(deposit_event(
+            &info.sender,
+            &depositor,
+            deposit_amount,
+            post_tax_deposit_amount,
+            number_of_new_tickets,
+            minted_aust,
+        ))
+        .add_attributes(vec![
+            attr("action", "deposit"),
+            attr("depositor", info.sender.to_string()),
+            attr("recipient", depositor.to_string()),
+            attr("deposit_amount", deposit_amount.to_string()),
+            attr("tickets", number_of_new_tickets.to_string()),
+            attr("aust_minted", minted_aust.to_string()),
+        ])
+        .add_attributes(campaign_attrs))
+}
+
+/// Mints shares and tickets to the depositor for post_tax_deposit_amount of UST
+/// which the caller is responsible for depositing into anchor.
+/// Returns the number of new tickets and the amount of minted aust.
+pub fn process_deposit(
+    mut deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    depositor: &Addr,
+    post_tax_deposit_amount: Uint256,
+    encoded_tickets: String,
+    new_operator_addr: Option<String>,
+) -> Result<(u64, Uint256), ContractError> {
+    // Tickets can't be gifted to blacklisted addresses either
+    assert_not_blacklisted(deps.storage, depositor)?;
+
+    let mut state = STATE.load(deps.storage)?;
+    let mut pool = POOL.load(deps.storage)?;
+    let mut depositor_info: DepositorInfo = read_depositor_info(deps.storage, depositor);
+
+    // Get the aust exchange rate
+    let aust_exchange_rate = query_exchange_rate(
+        deps.as_ref(),
+        config.anchor_contract.to_string(),
+        env.block.height,
+    )?
+    .exchange_rate;
+
     // Get the number of minted aust
     let minted_aust = post_tax_deposit_amount / aust_exchange_rate;
 
@@ -470,10 +1140,10 @@ pub fn deposit(
 
     let number_of_new_tickets = handle_depositor_ticket_updates(
         deps.branch(),
-        &env,
-        &config,
+        env,
+        config,
         &pool,
-        &depositor,
+        depositor,
         &mut depositor_info,
         encoded_tickets,
         aust_exchange_rate,
@@ -489,11 +1159,19 @@ pub fn deposit(
         deps.branch(),
         &mut state,
         &mut pool,
-        &depositor,
+        depositor,
         &mut depositor_info,
         minted_shares,
         new_operator_addr,
     )?;
+    if depositor_info.operator_registered() {
+        record_operator_share_checkpoint(
+            deps.storage,
+            &depositor_info.operator_addr,
+            &pool,
+            env.block.time.seconds(),
+        )?;
+    }
 
     // Increase the depositor's shares by the number of minted shares
     depositor_info.shares = depositor_info.shares.add(minted_shares);
@@ -508,28 +1186,14 @@ pub fn deposit(
     state.total_tickets = state.total_tickets.add(number_of_new_tickets.into());
 
     // update depositor and state information
-    store_depositor_info(deps.storage, &depositor, depositor_info, env.block.height)?;
-    STATE.save(deps.storage, &state)?;
-    POOL.save(deps.storage, &pool)?;
-
-    // save depositor and state information
-    Ok(Response::new()
-        .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: config.anchor_contract.to_string(),
-            funds: vec![Coin {
-                denom: config.stable_denom,
-                amount: post_tax_deposit_amount.into(),
-            }],
-            msg: to_binary(&AnchorMsg::DepositStable {})?,
-        })])
-        .add_attributes(vec![
-            attr("action", "deposit"),
-            attr("depositor", info.sender.to_string()),
-            attr("recipient", depositor.to_string()),
-            attr("deposit_amount", deposit_amount.to_string()),
-            attr("tickets", number_of_new_tickets.to_string()),
-            attr("aust_minted", minted_aust.to_string()),
-        ]))
+    store_depositor_info(deps.storage, depositor, depositor_info, env.block.height)?;
+    update_depositor_ledger(deps.storage, depositor, |ledger| {
+        ledger.deposited += post_tax_deposit_amount
+    })?;
+    STATE.save(deps.storage, &state, env.block.height)?;
+    POOL.save(deps.storage, &pool, env.block.height)?;
+
+    Ok((number_of_new_tickets, minted_aust))
 }
 
 // Deposit UST and get savings aust and tickets in return
@@ -539,13 +1203,57 @@ pub fn execute_deposit(
     info: MessageInfo,
     encoded_tickets: String,
     operator_addr: Option<String>,
+    campaign_id: Option<String>,
+) -> Result<Response, ContractError> {
+    deposit(
+        deps.branch(),
+        env,
+        info,
+        None,
+        operator_addr,
+        campaign_id,
+        encoded_tickets,
+    )
+}
+
+// Deposit UST and get savings aust and quick_pick generated tickets in return
+pub fn execute_quick_pick_deposit(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    quick_pick: u32,
+    operator_addr: Option<String>,
 ) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if quick_pick == 0 {
+        return Err(ContractError::ZeroQuickPick {});
+    }
+
+    // Generate the sequences from the block and the depositor's nonce
+    let nonce = QUICK_PICK_NONCES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    let (sequences, next_nonce) = generate_quick_pick_sequences(
+        deps.storage,
+        &env,
+        &config,
+        &info.sender,
+        nonce,
+        quick_pick,
+    )?;
+    QUICK_PICK_NONCES.save(deps.storage, &info.sender, &next_nonce)?;
+
+    let encoded_tickets =
+        base64::encode(vec_string_tickets_to_vec_binary_tickets(sequences)?.concat());
+
     deposit(
         deps.branch(),
         env,
         info,
         None,
         operator_addr,
+        None,
         encoded_tickets,
     )
 }
@@ -575,7 +1283,9 @@ pub fn execute_claim_tickets(
     // Validate that the lottery has not already started
     let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
     if current_lottery.rand_round != 0 {
-        return Err(ContractError::LotteryAlreadyStarted {});
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
     }
 
     // Propogate depositor ticket updates
@@ -604,35 +1314,266 @@ pub fn execute_claim_tickets(
     ]))
 }
 
-// Gift several tickets at once to a given address
-pub fn execute_gift(
+// Get up to max_tickets quick_pick generated tickets backed by the savings appreciation.
+// Shares are unchanged, the tickets are backed by the grown value of the depositor's shares
+pub fn execute_convert_yield_to_tickets(
     mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    encoded_tickets: String,
-    to: String,
-    operator_addr: Option<String>,
+    max_tickets: u32,
 ) -> Result<Response, ContractError> {
-    if to == info.sender {
-        return Err(ContractError::GiftToSelf {});
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+    let pool = POOL.load(deps.storage)?;
+
+    if max_tickets == 0 {
+        return Err(ContractError::ZeroQuickPick {});
     }
-    deposit(
-        deps.branch(),
-        env,
-        info,
-        Some(to),
-        operator_addr,
-        encoded_tickets,
-    )
-}
 
-// Make a donation deposit to the lottery pool
-pub fn execute_sponsor(
+    // Validate that the lottery has not already started
+    let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
+    if current_lottery.rand_round != 0 {
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
+    }
+
+    let depositor = info.sender;
+    let mut depositor_info: DepositorInfo = read_depositor_info(deps.storage, &depositor);
+
+    // Get the aust exchange rate
+    let aust_exchange_rate = query_exchange_rate(
+        deps.as_ref(),
+        config.anchor_contract.to_string(),
+        env.block.height,
+    )?
+    .exchange_rate;
+
+    let convertible_tickets = calculate_convertible_yield_tickets(
+        &state,
+        &pool,
+        &config,
+        &depositor_info,
+        aust_exchange_rate,
+    );
+
+    // Converted tickets never take the depositor over max_tickets_per_depositor
+    let number_of_new_tickets = convertible_tickets.min(max_tickets as u64).min(
+        config
+            .max_tickets_per_depositor
+            .saturating_sub(depositor_info.num_tickets as u64),
+    );
+    if number_of_new_tickets == 0 {
+        return Err(ContractError::NoConvertibleYield(depositor.to_string()));
+    }
+
+    // Generate the sequences from the block and the depositor's nonce
+    let nonce = QUICK_PICK_NONCES
+        .may_load(deps.storage, &depositor)?
+        .unwrap_or_default();
+    let (sequences, next_nonce) = generate_quick_pick_sequences(
+        deps.storage,
+        &env,
+        &config,
+        &depositor,
+        nonce,
+        number_of_new_tickets as u32,
+    )?;
+    QUICK_PICK_NONCES.save(deps.storage, &depositor, &next_nonce)?;
+
+    add_depositor_tickets(
+        deps.branch(),
+        &env,
+        &config,
+        &depositor,
+        &mut depositor_info,
+        &sequences,
+    )?;
+
+    // Update the number of total_tickets
+    state.total_tickets += Uint256::from(number_of_new_tickets);
+
+    store_depositor_info(deps.storage, &depositor, depositor_info, env.block.height)?;
+    STATE.save(deps.storage, &state, env.block.height)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "convert_yield_to_tickets"),
+        attr("depositor", depositor.to_string()),
+        attr("tickets", number_of_new_tickets.to_string()),
+    ]))
+}
+
+// Gift several tickets at once to a given address
+pub fn execute_gift(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    encoded_tickets: String,
+    to: String,
+    operator_addr: Option<String>,
+) -> Result<Response, ContractError> {
+    if to == info.sender {
+        return Err(ContractError::GiftToSelf {});
+    }
+    deposit(
+        deps.branch(),
+        env,
+        info,
+        Some(to),
+        operator_addr,
+        None,
+        encoded_tickets,
+    )
+}
+
+// Gift tickets to many recipients at once, paying the ticket price of each gifted ticket
+pub fn execute_gift_batch(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    gifts: Vec<GiftBatchItem>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+
+    // Validate that the lottery has not already started
+    let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
+    if current_lottery.rand_round != 0 {
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
+    }
+
+    if gifts.is_empty() {
+        return Err(ContractError::EmptyGiftBatch {});
+    }
+
+    // Validate the recipients and count the tickets of each gift
+    let mut recipients: Vec<(Addr, u64)> = vec![];
+    for gift in gifts.iter() {
+        let recipient = deps.api.addr_validate(&gift.recipient)?;
+        if recipient == info.sender {
+            return Err(ContractError::GiftToSelf {});
+        }
+
+        let num_tickets = base64_encoded_tickets_to_vec_string_tickets(
+            gift.encoded_tickets.clone(),
+            config.ticket_length as usize,
+        )?
+        .len() as u64;
+        if num_tickets == 0 {
+            return Err(ContractError::ZeroGiftAmount {});
+        }
+
+        // Each gift is a separate deposit, so each must meet the minimum
+        let amount = config.ticket_price * Uint256::from(num_tickets);
+        if amount < config.min_deposit_amount {
+            return Err(ContractError::DepositBelowMinimum {
+                amount,
+                min_deposit_amount: config.min_deposit_amount,
+            });
+        }
+
+        recipients.push((recipient, num_tickets));
+    }
+
+    // Guard against batches too large to be processed in a single transaction
+    let total_tickets: u64 = recipients.iter().map(|(_, tickets)| tickets).sum();
+    if total_tickets > MAX_GIFT_BATCH_TICKETS {
+        return Err(ContractError::GiftBatchTooLarge {
+            num_tickets: total_tickets,
+            max_tickets: MAX_GIFT_BATCH_TICKETS,
+        });
+    }
+
+    // Validate that the funds sent cover exactly the price of all the gifted tickets
+    let gift_amount = info
+        .funds
+        .iter()
+        .find(|c| c.denom == config.stable_denom)
+        .map(|c| Uint256::from(c.amount))
+        .unwrap_or_else(Uint256::zero);
+
+    let expected_amount = config.ticket_price * Uint256::from(total_tickets);
+    if gift_amount != expected_amount {
+        return Err(ContractError::InvalidGiftBatchAmount {
+            expected: expected_amount,
+            received: gift_amount,
+            denom: config.stable_denom,
+        });
+    }
+
+    // Deduct tx taxes once for the whole batch and split them pro rata between the gifts
+    let post_tax_gift_amount = Uint256::from(
+        deduct_tax(
+            deps.as_ref(),
+            coin(gift_amount.into(), config.stable_denom.clone()),
+        )?
+        .amount,
+    );
+    let post_tax_ratio = Decimal256::from_ratio(post_tax_gift_amount, gift_amount);
+
+    let mut total_deposit_amount = Uint256::zero();
+    let mut events = vec![];
+    let mut hooks = vec![];
+    let mut attributes = vec![
+        attr("action", "gift_batch"),
+        attr("depositor", info.sender.to_string()),
+        attr("deposit_amount", gift_amount.to_string()),
+    ];
+
+    for ((recipient, num_tickets), gift) in recipients.into_iter().zip(gifts) {
+        let post_tax_deposit_amount =
+            config.ticket_price * Uint256::from(num_tickets) * post_tax_ratio;
+
+        let (number_of_new_tickets, minted_aust) = process_deposit(
+            deps.branch(),
+            &env,
+            &config,
+            &recipient,
+            post_tax_deposit_amount,
+            gift.encoded_tickets,
+            None,
+        )?;
+
+        total_deposit_amount += post_tax_deposit_amount;
+        hooks.extend(hook_submsgs(
+            deps.storage,
+            GlowHookMsg::OnDeposit {
+                depositor: info.sender.to_string(),
+                recipient: recipient.to_string(),
+                amount: config.ticket_price * Uint256::from(num_tickets),
+                tickets: number_of_new_tickets,
+            },
+        )?);
+        events.push(deposit_event(
+            &info.sender,
+            &recipient,
+            config.ticket_price * Uint256::from(num_tickets),
+            post_tax_deposit_amount,
+            number_of_new_tickets,
+            minted_aust,
+        ));
+        attributes.push(attr("recipient", recipient.to_string()));
+        attributes.push(attr("tickets", number_of_new_tickets.to_string()));
+    }
+
+    Ok(Response::new()
+        .add_message(deposit_stable_msg(&config, total_deposit_amount)?)
+        .add_submessages(hooks)
+        .add_events(events)
+        .add_attributes(attributes))
+}
+
+// Make a donation deposit to the lottery pool
+pub fn execute_sponsor(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     award: Option<bool>,
-    prize_distribution: Option<[Decimal256; NUM_PRIZE_BUCKETS]>,
+    prize_distribution: Option<Vec<Decimal256>>,
+    prize_template: Option<String>,
+    lock_duration: Option<u64>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let mut state = STATE.load(deps.storage)?;
@@ -651,16 +1592,33 @@ pub fn execute_sponsor(
         return Err(ContractError::ZeroSponsorshipAmount {});
     }
 
+    if sponsor_amount < config.min_deposit_amount {
+        return Err(ContractError::DepositBelowMinimum {
+            amount: sponsor_amount,
+            min_deposit_amount: config.min_deposit_amount,
+        });
+    }
+
     compute_global_sponsor_reward(&mut state, &pool, env.block.height);
 
-    let mut msgs: Vec<CosmosMsg> = vec![];
+    let mut msgs: Vec<SubMsg> = vec![];
 
     if let None | Some(false) = award {
         // Can't specify prize distribution in this case
-        if prize_distribution.is_some() {
+        if prize_distribution.is_some() || prize_template.is_some() {
             return Err(ContractError::InvalidPrizeDistribution {});
         }
 
+        // Validate the lock duration
+        let lock_config = &config.sponsor_lock_config;
+        if let Some(lock_duration) = lock_duration {
+            if lock_duration == 0 || lock_duration > lock_config.max_lock_weeks {
+                return Err(ContractError::InvalidLockDuration {
+                    max_lock_weeks: lock_config.max_lock_weeks,
+                });
+            }
+        }
+
         // Deduct taxes that will be payed when transferring to anchor
         let net_sponsor_amount = Uint256::from(
             deduct_tax(
@@ -689,43 +1647,63 @@ pub fn execute_sponsor(
         // update sponsor sponsor rewards
         compute_sponsor_reward(&state, &mut sponsor_info);
 
+        // remove the previous lock and weight bonuses from the pool, they are recomputed below
+        pool.total_sponsor_lock_bonus -= sponsor_info.lock_bonus();
+        pool.total_sponsor_weight_bonus -= sponsor_info.weight_bonus();
+
+        let current_time = env.block.time.seconds();
+        if let Some(lock_duration) = lock_duration {
+            // A lock applies to the whole sponsorship and can only be extended
+            let lock_end_time = current_time + lock_duration * SECONDS_PER_WEEK;
+            if lock_end_time < sponsor_info.lock_end_time {
+                return Err(ContractError::SponsorLockShortened {
+                    lock_end_time: sponsor_info.lock_end_time,
+                });
+            }
+            sponsor_info.lock_end_time = lock_end_time;
+            sponsor_info.lock_bonus_rate =
+                lock_config.bonus_per_week * Decimal256::from_uint256(Uint256::from(lock_duration));
+        } else if !sponsor_info.is_locked(current_time) {
+            // The lock bonus ends with the lock
+            sponsor_info.lock_end_time = 0;
+            sponsor_info.lock_bonus_rate = Decimal256::zero();
+        }
+
         // add sponsor_amount to depositor
         sponsor_info.lottery_deposit = sponsor_info.lottery_deposit.add(minted_aust_value);
-        store_sponsor_info(deps.storage, &info.sender, sponsor_info)?;
 
         // update pool
         pool.total_sponsor_lottery_deposits =
             pool.total_sponsor_lottery_deposits.add(minted_aust_value);
+        pool.total_sponsor_lock_bonus += sponsor_info.lock_bonus();
+        pool.total_sponsor_weight_bonus += sponsor_info.weight_bonus();
+
+        store_sponsor_info(deps.storage, &info.sender, sponsor_info)?;
 
         // Push message to deposit stable coins into anchor
-        msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: config.anchor_contract.to_string(),
-            funds: vec![Coin {
-                denom: config.stable_denom,
-                amount: net_sponsor_amount.into(),
-            }],
-            msg: to_binary(&AnchorMsg::DepositStable {})?,
-        }));
+        msgs.push(SubMsg::new(deposit_stable_msg(
+            &config,
+            net_sponsor_amount,
+        )?));
     } else {
         // Award is instant
 
-        // Get the prize_distribution or the prize_distribution in the config
-        let prize_distribution = prize_distribution.unwrap_or(config.prize_distribution);
-
-        // Validate that the prize_distribution is of length NUM_PRIZE_BUCKETS
-        if prize_distribution.len() != NUM_PRIZE_BUCKETS {
-            return Err(ContractError::InvalidPrizeDistribution {});
+        // Instant awards can't be locked
+        if lock_duration.is_some() {
+            return Err(ContractError::LockedAwardSponsorship {});
         }
 
-        // Validate that the prize_distributions sums to 1
-        let mut sum = Decimal256::zero();
-        for item in prize_distribution.iter() {
-            sum += *item;
-        }
+        // Get the prize_distribution, the one of the template or the prize_distribution in the config
+        let num_prize_buckets = config.num_prize_buckets();
+        let prize_distribution = match (prize_distribution, prize_template) {
+            (Some(_), Some(_)) => return Err(ContractError::InvalidPrizeDistribution {}),
+            (Some(prize_distribution), None) => prize_distribution,
+            (None, Some(prize_template)) => read_prize_template(deps.storage, &prize_template)?,
+            (None, None) => config.prize_distribution,
+        };
 
-        if sum != Decimal256::one() {
-            return Err(ContractError::InvalidPrizeDistribution {});
-        }
+        // Validate that the prize_distributions sums to 1
+        validate_prize_distribution(&prize_distribution, num_prize_buckets)?;
 
         // Distribute the sponsorship to the prize buckets according to the prize distribution
         for (index, fraction_of_prize) in prize_distribution.iter().enumerate() {
@@ -734,13 +1712,78 @@ pub fn execute_sponsor(
         }
     }
 
-    STATE.save(deps.storage, &state)?;
-    POOL.save(deps.storage, &pool)?;
+    STATE.save(deps.storage, &state, env.block.height)?;
+    POOL.save(deps.storage, &pool, env.block.height)?;
 
-    Ok(Response::new().add_messages(msgs).add_attributes(vec![
-        attr("action", "sponsorship"),
-        attr("sponsor", info.sender.to_string()),
-        attr("sponsorship_amount", sponsor_amount),
+    // Mint the badges of the sponsorship thresholds crossed
+    record_sponsorship(deps.storage, &info.sender, sponsor_amount)?;
+    msgs.extend(badge_mint_submsgs(deps.storage, &env, &info.sender)?);
+
+    Ok(Response::new()
+        .add_submessages(msgs)
+        .add_event(sponsor_event(
+            &info.sender,
+            sponsor_amount,
+            award.unwrap_or(false),
+        ))
+        .add_attributes(vec![
+            attr("action", "sponsorship"),
+            attr("sponsor", info.sender.to_string()),
+            attr("sponsorship_amount", sponsor_amount),
+        ]))
+}
+
+// Credit a donation directly to a prize bucket
+pub fn execute_donate_to_prize_bucket(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bucket_index: u8,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+
+    // validate the bucket index
+    let index = bucket_index as usize;
+    if index >= config.num_prize_buckets() {
+        return Err(ContractError::InvalidPrizeBucketIndex(bucket_index));
+    }
+
+    // get the amount of funds sent in the base stable denom
+    let donation_amount = info
+        .funds
+        .iter()
+        .find(|c| c.denom == config.stable_denom)
+        .map(|c| Uint256::from(c.amount))
+        .unwrap_or_else(Uint256::zero);
+
+    if donation_amount.is_zero() {
+        return Err(ContractError::ZeroDonationAmount {});
+    }
+
+    state.prize_buckets[index] += donation_amount;
+    STATE.save(deps.storage, &state, env.block.height)?;
+
+    let mut bucket_donations = PRIZE_BUCKET_DONATIONS
+        .may_load(deps.storage)?
+        .unwrap_or(vec![Uint256::zero(); config.num_prize_buckets()]);
+    bucket_donations[index] += donation_amount;
+    PRIZE_BUCKET_DONATIONS.save(deps.storage, &bucket_donations)?;
+
+    let donor_amount = PRIZE_DONORS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    PRIZE_DONORS.save(
+        deps.storage,
+        &info.sender,
+        &(donor_amount + donation_amount),
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "donate_to_prize_bucket"),
+        attr("donor", info.sender.to_string()),
+        attr("bucket_index", bucket_index.to_string()),
+        attr("donation_amount", donation_amount),
     ]))
 }
 
@@ -765,13 +1808,17 @@ pub fn execute_sponsor_withdraw(
 
     // Validate that the sponsor has a lottery deposit
     if sponsor_info.lottery_deposit.is_zero() {
-        return Err(ContractError::NoSponsorLotteryDeposit {});
+        return Err(ContractError::NoSponsorLotteryDeposit(
+            info.sender.to_string(),
+        ));
     }
 
     // Validate that there isn't a lottery in progress
     let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
     if current_lottery.rand_round != 0 {
-        return Err(ContractError::LotteryAlreadyStarted {});
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
     }
 
     // Compute Glow depositor rewards
@@ -781,28 +1828,33 @@ pub fn execute_sponsor_withdraw(
     let aust_to_redeem = sponsor_info.lottery_deposit / rate;
     let aust_to_redeem_value = aust_to_redeem * rate;
 
+    // Withdrawing before the lock expires forfeits part of the sponsorship
+    let early_exit_penalty = if sponsor_info.is_locked(env.block.time.seconds()) {
+        aust_to_redeem_value * config.sponsor_lock_config.early_exit_penalty
+    } else {
+        Uint256::zero()
+    };
+
     // Update global state
 
     pool.total_sponsor_lottery_deposits = pool
         .total_sponsor_lottery_deposits
         .sub(sponsor_info.lottery_deposit);
+    pool.total_sponsor_lock_bonus -= sponsor_info.lock_bonus();
+    pool.total_sponsor_weight_bonus -= sponsor_info.weight_bonus();
 
     // Update sponsor info
     sponsor_info.lottery_deposit = Uint256::zero();
+    sponsor_info.lock_end_time = 0;
+    sponsor_info.lock_bonus_rate = Decimal256::zero();
 
-    let mut msgs: Vec<CosmosMsg> = vec![];
+    // The penalty stays in the contract and is added to the prize buckets
+    for (index, fraction_of_prize) in config.prize_distribution.iter().enumerate() {
+        state.prize_buckets[index] += early_exit_penalty * *fraction_of_prize;
+    }
 
     // Message for redeem amount operation of aUST
-    let redeem_msg = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: config.a_terra_contract.to_string(),
-        funds: vec![],
-        msg: to_binary(&Cw20ExecuteMsg::Send {
-            contract: config.anchor_contract.to_string(),
-            amount: aust_to_redeem.into(),
-            msg: to_binary(&Cw20HookMsg::RedeemStable {}).unwrap(),
-        })?,
-    });
-    msgs.push(redeem_msg);
+    let mut msgs: Vec<CosmosMsg> = vec![redeem_stable_msg(&config, aust_to_redeem)?];
 
     // Discount tx taxes from Anchor to Glow
     let coin_amount = deduct_tax(
@@ -812,6 +1864,7 @@ pub fn execute_sponsor_withdraw(
     .amount;
 
     // Discount tx taxes from Glow to User
+    let coin_amount = Uint256::from(coin_amount) - early_exit_penalty;
     let net_coin_amount = deduct_tax(deps.as_ref(), coin(coin_amount.into(), config.stable_denom))?;
 
     msgs.push(CosmosMsg::Bank(BankMsg::Send {
@@ -820,14 +1873,15 @@ pub fn execute_sponsor_withdraw(
     }));
 
     store_sponsor_info(deps.storage, &info.sender, sponsor_info)?;
-    STATE.save(deps.storage, &state)?;
-    POOL.save(deps.storage, &pool)?;
+    STATE.save(deps.storage, &state, env.block.height)?;
+    POOL.save(deps.storage, &pool, env.block.height)?;
 
     Ok(Response::new().add_messages(msgs).add_attributes(vec![
         attr("action", "withdraw_sponsor"),
         attr("depositor", info.sender.to_string()),
         attr("redeem_amount_anchor", aust_to_redeem.to_string()),
         attr("redeem_stable_amount", aust_to_redeem_value),
+        attr("early_exit_penalty", early_exit_penalty),
     ]))
 }
 
@@ -837,11 +1891,24 @@ pub fn execute_withdraw(
     info: MessageInfo,
     amount: Option<Uint128>,
     instant: Option<bool>,
+    tickets: Option<Vec<String>>,
+    recipient: Option<String>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let mut state = STATE.load(deps.storage)?;
     let mut pool = POOL.load(deps.storage)?;
 
+    // Regular withdrawals become claims of the depositor, paid to the recipient of the claim
+    if recipient.is_some() && instant != Some(true) {
+        return Err(ContractError::RecipientRequiresInstantWithdrawal {});
+    }
+    let recipient = recipient
+        .map(|recipient| deps.api.addr_validate(&recipient))
+        .transpose()?;
+    if let Some(recipient) = &recipient {
+        assert_not_blacklisted(deps.storage, recipient)?;
+    }
+
     let mut depositor_info: DepositorInfo = read_depositor_info(deps.storage, &info.sender);
 
     // Get the aust exchange rate
@@ -854,7 +1921,9 @@ pub fn execute_withdraw(
 
     // Validate that the user has savings aust to withdraw
     if depositor_info.shares.is_zero() {
-        return Err(ContractError::NoDepositorSavingsAustToWithdraw {});
+        return Err(ContractError::NoDepositorSavingsAustToWithdraw(
+            info.sender.to_string(),
+        ));
     }
 
     // Validate that the user is withdrawing a non zero amount
@@ -865,7 +1934,9 @@ pub fn execute_withdraw(
     // Validate that there isn't a lottery in progress already
     let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
     if current_lottery.rand_round != 0 {
-        return Err(ContractError::LotteryAlreadyStarted {});
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
     }
 
     // Get the number of withdrawn shares
@@ -898,6 +1969,15 @@ pub fn execute_withdraw(
         });
     }
 
+    // Instant withdrawals past the cap of the epoch have to use the unbonding period
+    if let Some(true) = instant {
+        record_instant_withdrawal(
+            deps.storage,
+            pool.total_user_aust * aust_exchange_rate,
+            withdrawn_aust_value,
+        )?;
+    }
+
     // Get the depositor's balance post withdraw
     let post_transaction_depositor_balance = (pool.total_user_aust - withdrawn_aust)
         * decimal_from_ratio_or_one(
@@ -912,7 +1992,7 @@ pub fn execute_withdraw(
     .u128();
 
     // Calculate how many tickets to remove
-    let num_depositor_tickets = depositor_info.tickets.len() as u128;
+    let num_depositor_tickets = depositor_info.num_tickets as u128;
 
     // Get the number of tickets to withdraw
     let withdrawn_tickets: u128 = num_depositor_tickets
@@ -926,7 +2006,30 @@ pub fn execute_withdraw(
         });
     }
 
-    for seq in depositor_info.tickets.drain(..withdrawn_tickets as usize) {
+    let withdrawn_sequences = match tickets {
+        // Give up the tickets chosen by the depositor
+        Some(tickets) => {
+            if tickets.len() as u128 != withdrawn_tickets {
+                return Err(ContractError::WithdrawnTicketsMismatch {
+                    withdrawn_tickets,
+                    num_specified_tickets: tickets.len() as u128,
+                });
+            }
+
+            for sequence in tickets.iter() {
+                if !is_valid_sequence(sequence, config.ticket_length as usize) {
+                    return Err(ContractError::InvalidSequence(sequence.clone()));
+                }
+                if !remove_depositor_ticket(deps.storage, &info.sender, sequence)? {
+                    return Err(ContractError::TicketNotHeld(sequence.clone()));
+                }
+            }
+            tickets
+        }
+        None => remove_depositor_tickets(deps.storage, &info.sender, withdrawn_tickets as usize)?,
+    };
+    depositor_info.num_tickets -= withdrawn_tickets as usize;
+    for seq in withdrawn_sequences {
         TICKETS.update(deps.storage, seq.as_bytes(), |tickets| -> StdResult<_> {
             let mut new_tickets = tickets.unwrap();
             let index = new_tickets
@@ -936,6 +2039,7 @@ pub fn execute_withdraw(
             let _elem = new_tickets.remove(index);
             Ok(new_tickets)
         })?;
+        remove_ticket_acquisition(deps.storage, &seq, &info.sender)?;
     }
 
     // Update operator information
@@ -954,6 +2058,12 @@ pub fn execute_withdraw(
         store_operator_info(deps.storage, &depositor_info.operator_addr, operator)?;
 
         pool.total_operator_shares = pool.total_operator_shares.sub(withdrawn_shares);
+        record_operator_share_checkpoint(
+            deps.storage,
+            &depositor_info.operator_addr,
+            &pool,
+            env.block.time.seconds(),
+        )?;
     }
 
     // Update depositor info
@@ -968,35 +2078,45 @@ pub fn execute_withdraw(
     // Remove withdrawn_tickets from total_tickets
     state.total_tickets = state.total_tickets.sub(Uint256::from(withdrawn_tickets));
 
+    // Negative yield since the last lottery is covered by the insurance fund
+    let mut shortfall_events = vec![];
+    let expected_aust_value = withdrawn_aust * state.last_lottery_execution_aust_exchange_rate;
+    let mut insurance_cover = Uint256::zero();
+    if expected_aust_value > withdrawn_aust_value {
+        let shortfall = expected_aust_value - withdrawn_aust_value;
+        insurance_cover = draw_insurance(deps.storage, shortfall)?;
+        shortfall_events.push(insurance_shortfall_event(
+            "withdraw",
+            shortfall,
+            insurance_cover,
+        ));
+    }
+
     // Get the value of the returned amount after accounting for taxes.
     let mut return_amount = Uint256::from(
         deduct_tax(
             deps.as_ref(),
-            coin(withdrawn_aust_value.into(), config.clone().stable_denom),
+            coin(
+                (withdrawn_aust_value + insurance_cover).into(),
+                config.clone().stable_denom,
+            ),
         )?
         .amount,
     );
 
-    let mut msgs: Vec<CosmosMsg> = vec![];
+    let mut msgs: Vec<SubMsg> = vec![];
 
-    // Message for redeem amount operation of aUST
-    let redeem_msg = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: config.a_terra_contract.to_string(),
-        funds: vec![],
-        msg: to_binary(&Cw20ExecuteMsg::Send {
-            contract: config.anchor_contract.to_string(),
-            amount: withdrawn_aust.into(),
-            msg: to_binary(&Cw20HookMsg::RedeemStable {}).unwrap(),
-        })?,
-    });
-    msgs.push(redeem_msg);
+    // Withdrawals anchor can't redeem right now wait in the withdrawal queue
+    let queue_withdrawal = should_queue_withdrawal(deps.as_ref(), &config, withdrawn_aust_value)?;
+    let mut queued_withdrawal_id: Option<u64> = None;
 
     // Instant withdrawal. The user incurs a fee and receive the funds with this operation
     let mut withdrawal_fee = Uint256::zero();
 
     if let Some(true) = instant {
-        // Apply instant withdrawal fee
-        withdrawal_fee = return_amount * config.instant_withdrawal_fee;
+        // Apply instant withdrawal fee, the previous one during the grace window of an update
+        withdrawal_fee = return_amount
+            * effective_instant_withdrawal_fee(deps.storage, &config, env.block.time.seconds())?;
         return_amount = return_amount.sub(withdrawal_fee);
 
         // Add the withdrawal fee to the total_reserve
@@ -1005,121 +2125,511 @@ pub fn execute_withdraw(
         // Get the amount of ust to return after tax
         let net_coin_amount = deduct_tax(
             deps.as_ref(),
-            coin(return_amount.into(), config.stable_denom),
+            coin(return_amount.into(), config.stable_denom.clone()),
         )?;
 
-        msgs.push(CosmosMsg::Bank(BankMsg::Send {
-            to_address: info.sender.to_string(),
-            amount: vec![net_coin_amount],
-        }));
-    } else {
-        // Check max unbonding_info concurrent claims is not bypassed
-        if depositor_info.unbonding_info.len() as u8 >= MAX_CLAIMS {
-            return Err(ContractError::MaxUnbondingClaims {});
+        if queue_withdrawal {
+            queued_withdrawal_id = Some(enqueue_withdrawal(
+                deps.storage,
+                QueuedWithdrawal {
+                    id: 0,
+                    depositor: info.sender.clone(),
+                    aust_amount: withdrawn_aust,
+                    amount: return_amount,
+                    instant: true,
+                    release_at: Expiration::AtTime(env.block.time),
+                    recipient: recipient.clone(),
+                },
+            )?);
+        } else {
+            // The payout depends on the redeemed funds, so the redemption must succeed
+            msgs.push(SubMsg::new(redeem_stable_msg(&config, withdrawn_aust)?));
+            msgs.push(SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: recipient.as_ref().unwrap_or(&info.sender).to_string(),
+                amount: vec![net_coin_amount],
+            })));
         }
+    } else if queue_withdrawal {
+        // The claim is added once the withdrawal is fulfilled, keeping its release time
+        queued_withdrawal_id = Some(enqueue_withdrawal(
+            deps.storage,
+            QueuedWithdrawal {
+                id: 0,
+                depositor: info.sender.clone(),
+                aust_amount: withdrawn_aust,
+                amount: return_amount,
+                instant: false,
+                release_at: config.unbonding_period.after(&env.block),
+                recipient: None,
+            },
+        )?);
+    } else {
         // Place amount in unbonding state as a claim
-        depositor_info.unbonding_info.push(Claim {
-            amount: return_amount,
-            release_at: config.unbonding_period.after(&env.block),
-        });
+        add_unbonding_claim(
+            &mut depositor_info,
+            Claim {
+                amount: return_amount,
+                release_at: config.unbonding_period.after(&env.block),
+            },
+            config.epoch_interval,
+        )?;
+
+        // A failed redemption is recorded as pending and retried before the claim is released
+        msgs.push(redeem_stable_submsg(deps.storage, &config, withdrawn_aust)?);
     }
 
     store_depositor_info(deps.storage, &info.sender, depositor_info, env.block.height)?;
-    STATE.save(deps.storage, &state)?;
-    POOL.save(deps.storage, &pool)?;
+    update_depositor_ledger(deps.storage, &info.sender, |ledger| {
+        ledger.withdrawn += return_amount
+    })?;
+    STATE.save(deps.storage, &state, env.block.height)?;
+    POOL.save(deps.storage, &pool, env.block.height)?;
 
-    Ok(Response::new().add_messages(msgs).add_attributes(vec![
+    let mut attributes = vec![
         attr("action", "withdraw_ticket"),
         attr("depositor", info.sender.to_string()),
         attr("tickets_amount", withdrawn_tickets.to_string()),
         attr("redeem_amount_anchor", withdrawn_aust.to_string()),
         attr("redeem_stable_amount", return_amount.to_string()),
         attr("instant_withdrawal_fee", withdrawal_fee.to_string()),
-    ]))
+    ];
+    if let Some(id) = queued_withdrawal_id {
+        attributes.push(attr("queued_withdrawal_id", id.to_string()));
+    }
+    if let Some(recipient) = &recipient {
+        attributes.push(attr("recipient", recipient.to_string()));
+    }
+
+    msgs.extend(hook_submsgs(
+        deps.storage,
+        GlowHookMsg::OnWithdraw {
+            depositor: info.sender.to_string(),
+            amount: return_amount,
+            instant: instant.unwrap_or(false),
+        },
+    )?);
+
+    Ok(Response::new()
+        .add_submessages(msgs)
+        .add_event(withdraw_event(
+            &info.sender,
+            withdrawn_tickets,
+            withdrawn_aust,
+            return_amount,
+            instant.unwrap_or(false),
+            withdrawal_fee,
+            queued_withdrawal_id,
+        ))
+        .add_events(shortfall_events)
+        .add_attributes(attributes))
 }
 
-// Send available UST to user from unbonded withdrawals
-pub fn execute_claim_unbonded(
+/// Withdraws the whole balance of every listed depositor holding less than
+/// min_deposit_amount into an unbonding claim and removes their tickets.
+/// Depositors that are not dust or have no free claim slots are skipped.
+pub fn execute_dust_sweep(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    depositors: Vec<String>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let state = STATE.load(deps.storage)?;
-
-    let mut depositor = read_depositor_info(deps.storage, &info.sender);
+    let mut state = STATE.load(deps.storage)?;
+    let mut pool = POOL.load(deps.storage)?;
 
-    let to_send = claim_unbonded_withdrawals(&mut depositor, &env.block, None)?;
+    // check permission
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
 
+    // Validate that there isn't a lottery in progress already
     let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
     if current_lottery.rand_round != 0 {
-        return Err(ContractError::LotteryAlreadyStarted {});
-    }
-
-    if to_send == Uint128::zero() {
-        return Err(ContractError::InsufficientClaimableFunds {});
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
     }
 
-    // Deduct taxes on the claim
-    let net_send = deduct_tax(
-        deps.as_ref(),
-        coin(to_send.into(), config.stable_denom.clone()),
+    // Get the aust exchange rate
+    let aust_exchange_rate = query_exchange_rate(
+        deps.as_ref(),
+        config.anchor_contract.to_string(),
+        env.block.height,
     )?
-    .amount;
+    .exchange_rate;
 
-    // Double-check if there is enough balance to send in the contract
+    // update the glow operator reward index before any operator shares change
+    compute_global_operator_reward(&mut state, &pool, env.block.height);
+
+    let mut swept_depositors: u64 = 0;
+    let mut swept_aust = Uint256::zero();
+
+    for depositor in depositors {
+        let depositor = deps.api.addr_validate(&depositor)?;
+        let mut depositor_info: DepositorInfo = read_depositor_info(deps.storage, &depositor);
+
+        if depositor_info.shares.is_zero()
+            || depositor_info.unbonding_info.len() as u8 >= MAX_CLAIMS
+        {
+            continue;
+        }
+
+        let withdrawn_shares = depositor_info.shares;
+        let withdrawn_aust =
+            withdrawn_shares.multiply_ratio(pool.total_user_aust, pool.total_user_shares);
+        let withdrawn_aust_value = withdrawn_aust * aust_exchange_rate;
+
+        if withdrawn_aust_value >= config.min_deposit_amount {
+            continue;
+        }
+
+        // Remove all the depositor tickets
+        let withdrawn_tickets = depositor_info.num_tickets;
+        let withdrawn_sequences =
+            remove_depositor_tickets(deps.storage, &depositor, withdrawn_tickets)?;
+        depositor_info.num_tickets = 0;
+        for seq in withdrawn_sequences {
+            TICKETS.update(deps.storage, seq.as_bytes(), |tickets| -> StdResult<_> {
+                let mut new_tickets = tickets.unwrap();
+                let index = new_tickets.iter().position(|x| *x == depositor).unwrap();
+                let _elem = new_tickets.remove(index);
+                Ok(new_tickets)
+            })?;
+            remove_ticket_acquisition(deps.storage, &seq, &depositor)?;
+        }
+        state.total_tickets = state
+            .total_tickets
+            .sub(Uint256::from(withdrawn_tickets as u64));
+
+        // Update operator information
+        if depositor_info.operator_registered() {
+            let mut operator = read_operator_info(deps.storage, &depositor_info.operator_addr);
+            compute_operator_reward(&state, &mut operator);
+            operator.shares = operator.shares.sub(withdrawn_shares);
+            store_operator_info(deps.storage, &depositor_info.operator_addr, operator)?;
+
+            pool.total_operator_shares = pool.total_operator_shares.sub(withdrawn_shares);
+            record_operator_share_checkpoint(
+                deps.storage,
+                &depositor_info.operator_addr,
+                &pool,
+                env.block.time.seconds(),
+            )?;
+        }
+
+        depositor_info.shares = Uint256::zero();
+
+        pool.total_user_shares = pool.total_user_shares.sub(withdrawn_shares);
+        pool.total_user_aust = pool.total_user_aust.sub(withdrawn_aust);
+
+        // Get the value of the returned amount after accounting for taxes.
+        let return_amount = Uint256::from(
+            deduct_tax(
+                deps.as_ref(),
+                coin(withdrawn_aust_value.into(), config.stable_denom.clone()),
+            )?
+            .amount,
+        );
+
+        // The swept balance goes through the regular unbonding period
+        add_unbonding_claim(
+            &mut depositor_info,
+            Claim {
+                amount: return_amount,
+                release_at: config.unbonding_period.after(&env.block),
+            },
+            config.epoch_interval,
+        )?;
+
+        store_depositor_info(deps.storage, &depositor, depositor_info, env.block.height)?;
+        update_depositor_ledger(deps.storage, &depositor, |ledger| {
+            ledger.withdrawn += return_amount
+        })?;
+
+        swept_depositors += 1;
+        swept_aust += withdrawn_aust;
+    }
+
+    STATE.save(deps.storage, &state, env.block.height)?;
+    POOL.save(deps.storage, &pool, env.block.height)?;
+
+    let mut msgs: Vec<SubMsg> = vec![];
+    if !swept_aust.is_zero() {
+        msgs.push(redeem_stable_submsg(deps.storage, &config, swept_aust)?);
+    }
+
+    Ok(Response::new().add_submessages(msgs).add_attributes(vec![
+        attr("action", "dust_sweep"),
+        attr("swept_depositors", swept_depositors.to_string()),
+        attr("redeem_amount_anchor", swept_aust.to_string()),
+    ]))
+}
+
+/// Stable balance of the contract available for unbonded claims. Stable held for
+/// prizes, escrows, gift cards and the insurance fund isn't available
+pub fn available_claim_balance(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    state: &State,
+) -> StdResult<Uint256> {
     let balance = query_balance(
-        deps.as_ref(),
+        deps,
         env.contract.address.to_string(),
         config.stable_denom.clone(),
     )?;
 
-    let reserved_for_prizes = state
+    let reserved = state
         .prize_buckets
         .iter()
-        .fold(Uint256::zero(), |sum, val| sum + *val);
+        .fold(state.missed_lottery_prizes, |sum, val| sum + *val)
+        + read_total_escrowed(deps.storage)?
+        + read_total_gift_cards(deps.storage)?
+        + read_insurance(deps.storage)?.balance;
 
-    if to_send > (balance - reserved_for_prizes).into() {
-        return Err(ContractError::InsufficientFunds {
-            to_send,
-            available_balance: balance - reserved_for_prizes,
+    Ok(if balance > reserved {
+        balance - reserved
+    } else {
+        Uint256::zero()
+    })
+}
+
+// Send available UST to user from unbonded withdrawals
+pub fn execute_claim_unbonded(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+
+    // Claims stay owned by the depositor, only the payout goes to the recipient
+    let recipient = match recipient {
+        Some(recipient) => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            assert_not_blacklisted(deps.storage, &recipient)?;
+            recipient
+        }
+        None => info.sender.clone(),
+    };
+
+    let mut depositor = read_depositor_info(deps.storage, &info.sender);
+
+    let to_send = claim_unbonded_withdrawals(&mut depositor, &env.block, None)?;
+
+    let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
+    if current_lottery.rand_round != 0 {
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
         });
     }
 
+    if to_send == Uint128::zero() {
+        return Err(ContractError::InsufficientClaimableFunds(
+            info.sender.to_string(),
+        ));
+    }
+
+    // Deduct taxes on the claim
+    let net_send = deduct_tax(
+        deps.as_ref(),
+        coin(to_send.into(), config.stable_denom.clone()),
+    )?
+    .amount;
+
+    // Double-check if there is enough balance to send in the contract
+    let insurance = read_insurance(deps.storage)?;
+    let available_balance = available_claim_balance(deps.as_ref(), &env, &config, &state)?;
+
+    // Redemptions returning less than expected are covered by the insurance fund
+    let mut shortfall_events = vec![];
+    if Uint256::from(to_send) > available_balance {
+        let shortfall = Uint256::from(to_send) - available_balance;
+        if shortfall > insurance.balance {
+            return Err(ContractError::InsufficientFunds {
+                to_send,
+                available_balance,
+            });
+        }
+        let covered = draw_insurance(deps.storage, shortfall)?;
+        shortfall_events.push(insurance_shortfall_event("claim", shortfall, covered));
+    }
+
     store_depositor_info(deps.storage, &info.sender, depositor, env.block.height)?;
-    STATE.save(deps.storage, &state)?;
+    STATE.save(deps.storage, &state, env.block.height)?;
 
     Ok(Response::new()
         .add_message(CosmosMsg::Bank(BankMsg::Send {
-            to_address: info.sender.to_string(),
+            to_address: recipient.to_string(),
             amount: vec![Coin {
                 denom: config.stable_denom,
                 amount: net_send,
             }],
         }))
+        .add_event(claim_event(&info.sender, &[], net_send, Uint128::zero()))
+        .add_events(shortfall_events)
         .add_attributes(vec![
             attr("action", "claim_unbonded"),
             attr("depositor", info.sender.to_string()),
+            attr("recipient", recipient.to_string()),
             attr("redeemed_amount", net_send),
         ]))
 }
 
 // Send available UST to user from prizes won in the given lottery_id
+// or deposit it back into the pool on their behalf if redeposit is set
+#[allow(clippy::too_many_arguments)]
 pub fn execute_claim_lottery(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     lottery_ids: Vec<u64>,
+    redeposit: Option<RedepositConfig>,
+    destination: Option<ClaimDestination>,
+    donation: Option<CharityDonation>,
+    payout: Option<PayoutReveal>,
+    payout_asset: Option<PayoutAsset>,
+) -> Result<Response, ContractError> {
+    claim_lottery(
+        deps,
+        env,
+        info.sender,
+        lottery_ids,
+        redeposit,
+        destination,
+        donation,
+        payout,
+        payout_asset,
+    )
+}
+
+// Claim the prizes of winner in the given lottery_ids on their behalf. The
+// sender must be a claim agent of the winner, who always receives the prizes
+pub fn execute_claim_lottery_for(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    winner: String,
+    lottery_ids: Vec<u64>,
+) -> Result<Response, ContractError> {
+    let winner = deps.api.addr_validate(&winner)?;
+    assert_claim_agent(deps.storage, &winner, &info.sender)?;
+    assert_not_blacklisted(deps.storage, &winner)?;
+
+    let res = claim_lottery(
+        deps,
+        env,
+        winner.clone(),
+        lottery_ids.clone(),
+        None,
+        None,
+        None,
+        None,
+        None,
+    )?;
+
+    Ok(res
+        .add_event(agent_claim_event(&info.sender, &winner, &lottery_ids))
+        .add_attribute("agent", info.sender.to_string()))
+}
+
+/// Aust worth amount of UST, if the contract holds that much aust not owed to anyone
+fn available_aust_payout(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    state: &State,
+    amount: Uint128,
+) -> Result<Uint256, ContractError> {
+    let pool = POOL.load(deps.storage)?;
+    let (aust_balance, aust_exchange_rate) = query_aust_balance_and_exchange_rate(
+        &deps.querier,
+        &config.a_terra_contract,
+        &config.anchor_contract,
+        &env.contract.address,
+        env.block.height,
+    )?;
+
+    let tracked_aust = calculate_tracked_aust(deps.storage, state, &pool, aust_exchange_rate)?;
+    let available = if aust_balance > tracked_aust {
+        aust_balance - tracked_aust
+    } else {
+        Uint256::zero()
+    };
+
+    let aust_amount = Uint256::from(amount) / aust_exchange_rate;
+    if aust_amount.is_zero() || aust_amount > available {
+        return Err(ContractError::InsufficientAustPayout {
+            amount: aust_amount,
+            available,
+        });
+    }
+
+    Ok(aust_amount)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn claim_lottery(
+    mut deps: DepsMut,
+    env: Env,
+    winner: Addr,
+    lottery_ids: Vec<u64>,
+    redeposit: Option<RedepositConfig>,
+    destination: Option<ClaimDestination>,
+    donation: Option<CharityDonation>,
+    payout: Option<PayoutReveal>,
+    payout_asset: Option<PayoutAsset>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let state = STATE.load(deps.storage)?;
 
+    assert_no_pending_claim(deps.storage)?;
+
+    // Redepositing through the payout asset is the same as without an operator
+    let redeposit = match (payout_asset, redeposit) {
+        (Some(PayoutAsset::Redeposit), None) => Some(RedepositConfig { operator: None }),
+        (Some(PayoutAsset::Ust), Some(_)) | (Some(PayoutAsset::Aust), Some(_)) => {
+            return Err(ContractError::InvalidPayoutAsset {});
+        }
+        (_, redeposit) => redeposit,
+    };
+    let aust_payout = payout_asset == Some(PayoutAsset::Aust);
+    if aust_payout && destination.is_some() {
+        return Err(ContractError::InvalidPayoutAsset {});
+    }
+
+    if let Some(destination) = &destination {
+        if redeposit.is_some() {
+            return Err(ContractError::IbcClaimWithRedeposit {});
+        }
+        validate_claim_destination(&config, destination)?;
+    }
+
+    // Prizes are paid to the revealed address, everything else stays keyed to winner
+    let recipient = match &payout {
+        Some(payout) => {
+            if redeposit.is_some() || destination.is_some() {
+                return Err(ContractError::PayoutRevealWithRedeposit {});
+            }
+            take_payout_address(deps.branch(), &winner, payout)?
+        }
+        None => winner.clone(),
+    };
+
+    let charity = donation
+        .as_ref()
+        .map(|donation| validate_charity_donation(deps.as_ref(), donation))
+        .transpose()?;
+
     let mut ust_to_send = Uint128::zero();
     let mut glow_to_send = Uint128::zero();
 
     let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
     if current_lottery.rand_round != 0 {
-        return Err(ContractError::LotteryAlreadyStarted {});
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
     }
 
     for lottery_id in lottery_ids.clone() {
@@ -1130,57 +2640,89 @@ pub fn execute_claim_lottery(
         //Calculate and add to to_send
         let lottery_key: U64Key = U64Key::from(lottery_id);
         let prize = PRIZES
-            .may_load(deps.storage, (lottery_key.clone(), &info.sender))
+            .may_load(deps.storage, (lottery_key.clone(), &winner))
             .unwrap();
         if let Some(prize) = prize {
             if prize.claimed {
                 return Err(ContractError::InvalidClaimPrizeAlreadyClaimed(lottery_id));
             }
 
-            let snapshotted_depositor_stats_info = read_depositor_stats_at_height(
-                deps.storage,
-                &info.sender,
-                lottery_info.block_height,
-            );
-
+            let snapshotted_depositor_stats_info =
+                read_depositor_stats_at_height(deps.storage, &winner, lottery_info.block_height);
+
+            let boost = snapshot_winner_boost(
+                &deps.querier,
+                &config,
+                &lottery_info,
+                &snapshotted_depositor_stats_info,
+                &winner,
+            )?;
             let (local_ust_to_send, local_glow_to_send): (Uint128, Uint128) =
-                calculate_winner_prize(
-                    &deps.querier,
-                    &config,
-                    &prize,
-                    &lottery_info,
-                    &snapshotted_depositor_stats_info,
-                    &info.sender,
-                )?;
+                calculate_boosted_winner_prize(&prize, &lottery_info, boost.multiplier);
 
             ust_to_send += local_ust_to_send;
             glow_to_send += local_glow_to_send;
 
+            // The prize over the per winner cap goes back to the prize buckets
+            roll_over_prize_excess(
+                deps.storage,
+                env.block.height,
+                &calculate_prize_cap_excess(&prize, &lottery_info),
+            )?;
+
+            // Keep the boost used to compute the GLOW prize
+            PRIZE_BOOSTS.save(
+                deps.storage,
+                (lottery_key.clone(), &winner),
+                &PrizeBoost {
+                    boost,
+                    glow_prize: local_glow_to_send,
+                    claimed_at_height: env.block.height,
+                },
+            )?;
+
             PRIZES.save(
                 deps.storage,
-                (lottery_key, &info.sender),
+                (lottery_key, &winner),
                 &PrizeInfo {
                     claimed: true,
                     ..prize
                 },
             )?;
+            record_prize_claimed(deps.storage, &winner)?;
         }
     }
 
     // If ust_to_send is zero, don't send anything even if glow_to_send is positive.
     // It should never be the case that ust_to_send is 0 and glow_to_send is positive.
     if ust_to_send == Uint128::zero() {
-        return Err(ContractError::InsufficientClaimableFunds {});
+        return Err(ContractError::InsufficientClaimableFunds(
+            winner.to_string(),
+        ));
     }
 
     let mut msgs: Vec<CosmosMsg> = vec![];
 
     // ust_to_send calculations
 
+    // The donated share of the prize goes to the charity
+    let donated_ust = match &donation {
+        Some(donation) => Uint128::from(Uint256::from(ust_to_send) * donation.share),
+        None => Uint128::zero(),
+    };
+
     // Deduct taxes on the claim
     let net_send = deduct_tax(
         deps.as_ref(),
-        coin(ust_to_send.into(), config.stable_denom.clone()),
+        coin(
+            (ust_to_send - donated_ust).into(),
+            config.stable_denom.clone(),
+        ),
+    )?
+    .amount;
+    let net_donation = deduct_tax(
+        deps.as_ref(),
+        coin(donated_ust.into(), config.stable_denom.clone()),
     )?
     .amount;
 
@@ -1198,94 +2740,177 @@ pub fn execute_claim_lottery(
         });
     }
 
-    msgs.push(CosmosMsg::Bank(BankMsg::Send {
-        to_address: info.sender.to_string(),
-        amount: vec![Coin {
-            denom: config.stable_denom,
-            amount: net_send,
-        }],
-    }));
+    update_depositor_ledger(deps.storage, &winner, |ledger| {
+        ledger.ust_won += Uint256::from(net_send + net_donation);
+        ledger.glow_won += Uint256::from(glow_to_send);
+    })?;
+
+    let mut redeposit_msgs: Vec<SubMsg> = vec![];
+    let mut payout_attrs = vec![];
+    let mut events = vec![claim_event(&winner, &lottery_ids, net_send, glow_to_send)];
+
+    if let Some(charity) = charity {
+        if !net_donation.is_zero() {
+            msgs.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: charity.to_string(),
+                amount: vec![Coin {
+                    denom: config.stable_denom.clone(),
+                    amount: net_donation,
+                }],
+            }));
+        }
+        record_donation(deps.storage, &charity, Uint256::from(net_donation))?;
 
-    // glow_to_send calculations
+        payout_attrs.push(attr("charity", charity.to_string()));
+        payout_attrs.push(attr("donated_ust", net_donation));
+    }
 
-    if glow_to_send != Uint128::zero() {
+    // The aust paid instead of the UST prize, if requested. Claims asking for more aust
+    // than the contract holds unowed fail rather than falling back to UST
+    let aust_payout_amount = if aust_payout && !net_send.is_zero() {
+        Some(available_aust_payout(
+            deps.as_ref(),
+            &env,
+            &config,
+            &state,
+            net_send,
+        )?)
+    } else {
+        None
+    };
+
+    if net_send.is_zero() {
+        // The whole UST prize was donated
+    } else if let Some(redeposit) = redeposit {
+        // Mint shares and tickets for the winnings. No tickets are requested explicitly,
+        // so random sequences are generated up to what the new balance affords
+        let (number_of_new_tickets, minted_aust) = process_deposit(
+            deps.branch(),
+            &env,
+            &config,
+            &winner,
+            Uint256::from(net_send),
+            String::new(),
+            redeposit.operator,
+        )?;
+
+        redeposit_msgs.push(SubMsg::new(deposit_stable_msg(
+            &config,
+            Uint256::from(net_send),
+        )?));
+        redeposit_msgs.extend(hook_submsgs(
+            deps.storage,
+            GlowHookMsg::OnDeposit {
+                depositor: winner.to_string(),
+                recipient: winner.to_string(),
+                amount: Uint256::from(net_send),
+                tickets: number_of_new_tickets,
+            },
+        )?);
+        events.push(deposit_event(
+            &winner,
+            &winner,
+            Uint256::from(net_send),
+            Uint256::from(net_send),
+            number_of_new_tickets,
+            minted_aust,
+        ));
+
+        payout_attrs.extend(vec![
+            attr("redeposited", "true"),
+            attr("tickets", number_of_new_tickets.to_string()),
+            attr("aust_minted", minted_aust.to_string()),
+        ]);
+    } else if let Some(destination) = destination {
+        let ibc_channel = destination.ibc_channel.clone();
+        let (ibc_claim_id, transfer_msg) =
+            ibc_claim_transfer_msg(deps.storage, &env, &config, &winner, destination, net_send)?;
+        msgs.push(transfer_msg);
+
+        payout_attrs.extend(vec![
+            attr("ibc_claim_id", ibc_claim_id.to_string()),
+            attr("ibc_channel", ibc_channel),
+        ]);
+    } else if let Some(aust_amount) = aust_payout_amount {
+        // Pay the equivalent aust out of the contract balance, and deposit the UST
+        // prize into anchor in its place
         msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: config.distributor_contract.to_string(),
+            contract_addr: config.a_terra_contract.to_string(),
             funds: vec![],
-            msg: to_binary(&FaucetExecuteMsg::Spend {
-                recipient: info.sender.to_string(),
-                amount: glow_to_send,
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: aust_amount.into(),
             })?,
         }));
+        redeposit_msgs.push(SubMsg::new(deposit_stable_msg(
+            &config,
+            Uint256::from(net_send),
+        )?));
+
+        payout_attrs.extend(vec![
+            attr("payout_asset", "aust"),
+            attr("aust_amount", aust_amount.to_string()),
+        ]);
+    } else {
+        msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin {
+                denom: config.stable_denom.clone(),
+                amount: net_send,
+            }],
+        }));
     }
 
-    // Update storage
-    STATE.save(deps.storage, &state)?;
-
-    // Send response
-
-    Ok(Response::new().add_messages(msgs).add_attributes(vec![
-        attr("action", "claim_lottery"),
-        attr("lottery_ids", format!("{:?}", lottery_ids)),
-        attr("depositor", info.sender.to_string()),
-        attr("redeemed_ust", net_send),
-        attr("redeemed_glow", glow_to_send),
-    ]))
-}
-
-pub fn execute_epoch_ops(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    let pool = POOL.load(deps.storage)?;
-    let mut state = STATE.load(deps.storage)?;
-
-    // Validate distributor contract has already been registered
-    if !config.contracts_registered() {
-        return Err(ContractError::NotRegistered {});
-    }
+    // glow_to_send calculations
 
-    // Validate that executing epoch will follow rate limiting
-    if !state.next_epoch.is_expired(&env.block) {
-        return Err(ContractError::InvalidEpochExecution {});
-    }
+    // The receipt is recorded before any payout is sent. A failed GLOW payout
+    // is recorded on it and can be sent again with RetryClaim
+    let (receipt_id, glow_msg) = record_claim_receipt(
+        deps.storage,
+        &deps.querier,
+        &config,
+        ClaimReceipt {
+            winner: winner.clone(),
+            recipient: recipient.clone(),
+            lottery_ids: lottery_ids.clone(),
+            ust_amount: net_send,
+            glow_amount: glow_to_send,
+            status: ClaimReceiptStatus::Finalized,
+            glow_from_reserve: false,
+        },
+    )?;
 
-    // Validate that the lottery is not in the process of running
-    // This helps avoid delaying the computing of the reward following lottery execution.
-    let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
-    if current_lottery.rand_round != 0 {
-        return Err(ContractError::LotteryAlreadyStarted {});
+    if payout.is_some() {
+        payout_attrs.push(attr("recipient", recipient.to_string()));
     }
 
-    // Compute global Glow rewards
-    compute_global_operator_reward(&mut state, &pool, env.block.height);
-    compute_global_sponsor_reward(&mut state, &pool, env.block.height);
-
-    // Compute total_reserves to fund community contract
-    let total_reserves = state.total_reserve;
-    let messages: Vec<CosmosMsg> = if !total_reserves.is_zero() {
-        vec![CosmosMsg::Bank(BankMsg::Send {
-            to_address: config.community_contract.to_string(),
-            amount: vec![deduct_tax(
-                deps.as_ref(),
-                Coin {
-                    denom: config.stable_denom,
-                    amount: total_reserves.into(),
-                },
-            )?],
-        })]
-    } else {
-        vec![]
-    };
+    let hooks = hook_submsgs(
+        deps.storage,
+        GlowHookMsg::OnPrizeClaimed {
+            winner: winner.to_string(),
+            lottery_ids: lottery_ids.clone(),
+            amount: net_send,
+            glow_amount: glow_to_send,
+        },
+    )?;
 
-    // Update next_epoch based on epoch_interval
-    state.next_epoch = Expiration::AtTime(env.block.time).add(config.epoch_interval)?;
-    // Empty total reserve and store state
-    state.total_reserve = Uint256::zero();
-    STATE.save(deps.storage, &state)?;
+    // Send response
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "execute_epoch_operations"),
-        attr("total_reserves", total_reserves.to_string()),
-    ]))
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_submessages(glow_msg)
+        .add_submessages(redeposit_msgs)
+        .add_submessages(hooks)
+        .add_events(events)
+        .add_attributes(vec![
+            attr("action", "claim_lottery"),
+            attr("lottery_ids", format!("{:?}", lottery_ids)),
+            attr("depositor", winner.to_string()),
+            attr("redeemed_ust", net_send),
+            attr("redeemed_glow", glow_to_send),
+            attr("claim_receipt_id", receipt_id.to_string()),
+        ])
+        .add_attributes(payout_attrs))
 }
 
 pub fn execute_claim_rewards(
@@ -1312,12 +2937,24 @@ pub fn execute_claim_rewards(
     compute_operator_reward(&state, &mut operator);
     compute_sponsor_reward(&state, &mut sponsor);
 
-    let claim_amount = (operator.pending_rewards + sponsor.pending_rewards) * Uint256::one();
+    // Operator rewards vest before they can be claimed
+    let current_epoch = read_current_epoch(deps.storage)?;
+    let mut operator_reward_ledger = read_operator_reward_ledger(deps.storage, &info.sender)?;
+    operator_reward_ledger.settle(
+        operator.pending_rewards,
+        current_epoch,
+        config.operator_reward_vesting_epochs,
+    );
+    let operator_claim_amount =
+        operator_reward_ledger.claim(current_epoch, config.operator_reward_vesting_epochs);
+
+    let claim_amount = (operator_claim_amount + sponsor.pending_rewards) * Uint256::one();
     sponsor.pending_rewards = Decimal256::zero();
     operator.pending_rewards = Decimal256::zero();
-    STATE.save(deps.storage, &state)?;
+    STATE.save(deps.storage, &state, env.block.height)?;
     store_sponsor_info(deps.storage, &info.sender, sponsor)?;
     store_operator_info(deps.storage, &info.sender, operator)?;
+    OPERATOR_REWARD_LEDGERS.save(deps.storage, &info.sender, &operator_reward_ledger)?;
 
     let messages: Vec<CosmosMsg> = if !claim_amount.is_zero() {
         vec![CosmosMsg::Wasm(WasmMsg::Execute {
@@ -1341,11 +2978,10 @@ pub fn execute_claim_rewards(
 #[allow(clippy::too_many_arguments)]
 pub fn execute_update_config(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     owner: Option<String>,
     oracle_addr: Option<String>,
-    reserve_factor: Option<Decimal256>,
-    instant_withdrawal_fee: Option<Decimal256>,
     unbonding_period: Option<u64>,
     epoch_interval: Option<u64>,
     max_holders: Option<u8>,
@@ -1354,14 +2990,51 @@ pub fn execute_update_config(
     lotto_winner_boost_config: Option<BoostConfig>,
     operator_glow_emission_rate: Option<Decimal256>,
     sponsor_glow_emission_rate: Option<Decimal256>,
+    min_deposit_amount: Option<Uint256>,
+    operator_reward_vesting_epochs: Option<u64>,
+    deposits_paused: Option<bool>,
+    withdrawals_paused: Option<bool>,
+    lottery_paused: Option<bool>,
+    claims_paused: Option<bool>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // check permission
-    if info.sender != config.owner {
+    let is_guardian = info.sender != config.owner && config.guardians.contains(&info.sender);
+    if info.sender != config.owner && !is_guardian {
         return Err(ContractError::Unauthorized {});
     }
 
+    let pause_flags = [
+        ("paused", paused),
+        ("deposits_paused", deposits_paused),
+        ("withdrawals_paused", withdrawals_paused),
+        ("lottery_paused", lottery_paused),
+        ("claims_paused", claims_paused),
+    ];
+
+    // Guardians can only set pause flags, not unpause or change any other parameter
+    if is_guardian {
+        let other_updates = owner.is_some()
+            || oracle_addr.is_some()
+            || unbonding_period.is_some()
+            || epoch_interval.is_some()
+            || max_holders.is_some()
+            || max_tickets_per_depositor.is_some()
+            || lotto_winner_boost_config.is_some()
+            || operator_glow_emission_rate.is_some()
+            || sponsor_glow_emission_rate.is_some()
+            || min_deposit_amount.is_some()
+            || operator_reward_vesting_epochs.is_some();
+
+        if other_updates
+            || pause_flags.iter().all(|(_, flag)| flag.is_none())
+            || pause_flags.iter().any(|(_, flag)| *flag == Some(false))
+        {
+            return Err(ContractError::GuardianActionNotAllowed {});
+        }
+    }
+
     // change owner of Glow lotto contract
     if let Some(owner) = owner {
         config.owner = deps.api.addr_validate(owner.as_str())?;
@@ -1372,21 +3045,6 @@ pub fn execute_update_config(
         config.owner = deps.api.addr_validate(oracle_addr.as_str())?;
     }
 
-    if let Some(reserve_factor) = reserve_factor {
-        if reserve_factor > Decimal256::one() {
-            return Err(ContractError::InvalidReserveFactor {});
-        }
-
-        config.reserve_factor = reserve_factor;
-    }
-
-    if let Some(instant_withdrawal_fee) = instant_withdrawal_fee {
-        if instant_withdrawal_fee > Decimal256::one() {
-            return Err(ContractError::InvalidWithdrawalFee {});
-        }
-        config.instant_withdrawal_fee = instant_withdrawal_fee;
-    }
-
     if let Some(unbonding_period) = unbonding_period {
         config.unbonding_period = Duration::Time(unbonding_period);
     }
@@ -1418,18 +3076,37 @@ pub fn execute_update_config(
         config.max_tickets_per_depositor = max_tickets_per_depositor;
     }
 
+    // The global flag sets every pause flag, it only stops everything during migrations
     if let Some(paused) = paused {
         if !paused {
             // Make sure that there isn't any old data left if you are unpausing
-
-            let old_depositors = old_read_depositors(deps.as_ref(), None, Some(1))?;
-            if !old_depositors.is_empty() {
-                return Err(ContractError::Std(StdError::generic_err(
-                    "Cannot unpause contract with old depositors",
-                )));
+            if has_pending_migration_steps(deps.storage)? {
+                return Err(ContractError::MigrationPending {});
             }
+            config.paused = false;
         }
-        config.paused = paused;
+        config.pause_state = PauseState {
+            deposits_paused: paused,
+            withdrawals_paused: paused,
+            lottery_paused: paused,
+            claims_paused: paused,
+        };
+    }
+
+    if let Some(deposits_paused) = deposits_paused {
+        config.pause_state.deposits_paused = deposits_paused;
+    }
+
+    if let Some(withdrawals_paused) = withdrawals_paused {
+        config.pause_state.withdrawals_paused = withdrawals_paused;
+    }
+
+    if let Some(lottery_paused) = lottery_paused {
+        config.pause_state.lottery_paused = lottery_paused;
+    }
+
+    if let Some(claims_paused) = claims_paused {
+        config.pause_state.claims_paused = claims_paused;
     }
 
     if let Some(lotto_winner_boost_config) = lotto_winner_boost_config {
@@ -1439,6 +3116,14 @@ pub fn execute_update_config(
         config.lotto_winner_boost_config = lotto_winner_boost_config
     }
 
+    if let Some(min_deposit_amount) = min_deposit_amount {
+        config.min_deposit_amount = min_deposit_amount;
+    }
+
+    if let Some(operator_reward_vesting_epochs) = operator_reward_vesting_epochs {
+        config.operator_reward_vesting_epochs = operator_reward_vesting_epochs;
+    }
+
     CONFIG.save(deps.storage, &config)?;
 
     let mut state = STATE.load(deps.storage)?;
@@ -1451,19 +3136,146 @@ pub fn execute_update_config(
         state.sponsor_reward_emission_index.glow_emission_rate = sponsor_glow_emission_rate;
     }
 
-    STATE.save(deps.storage, &state)?;
+    STATE.save(deps.storage, &state, env.block.height)?;
+
+    if is_guardian {
+        // Let monitoring pick up every guardian action
+        return Ok(Response::new()
+            .add_attributes(vec![
+                attr("action", "guardian_pause"),
+                attr("guardian", info.sender.to_string()),
+            ])
+            .add_attributes(
+                pause_flags
+                    .iter()
+                    .filter(|(_, flag)| flag.is_some())
+                    .map(|(name, _)| attr(*name, "true")),
+            ));
+    }
 
     Ok(Response::new().add_attributes(vec![("action", "update_config")]))
 }
 
+pub fn execute_add_guardian(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardian: String,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let guardian = deps.api.addr_validate(&guardian)?;
+    if config.guardians.contains(&guardian) {
+        return Err(ContractError::GuardianAlreadyExists(guardian.to_string()));
+    }
+
+    config.guardians.push(guardian.clone());
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_guardian"),
+        attr("guardian", guardian.to_string()),
+    ]))
+}
+
+pub fn execute_remove_guardian(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardian: String,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let guardian = deps.api.addr_validate(&guardian)?;
+    if !config.guardians.contains(&guardian) {
+        return Err(ContractError::GuardianNotFound(guardian.to_string()));
+    }
+
+    config.guardians.retain(|g| *g != guardian);
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_guardian"),
+        attr("guardian", guardian.to_string()),
+    ]))
+}
+
+pub fn execute_update_reserve_destination(
+    deps: DepsMut,
+    info: MessageInfo,
+    reserve_destination: ReserveDestination,
+    fee_distributor_contract: Option<String>,
+    lp_staking_contract: Option<String>,
+    lp_staking_reserve_share: Option<Decimal256>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let ReserveDestination::Split { community_bps } = reserve_destination {
+        if community_bps > 10_000 {
+            return Err(ContractError::InvalidReserveDestination {});
+        }
+    }
+
+    if let Some(fee_distributor_contract) = fee_distributor_contract {
+        config.fee_distributor_contract =
+            Some(deps.api.addr_validate(fee_distributor_contract.as_str())?);
+    }
+
+    // Sweeping to the fee distributor requires its address
+    if reserve_destination != ReserveDestination::Community
+        && config.fee_distributor_contract.is_none()
+    {
+        return Err(ContractError::FeeDistributorNotRegistered {});
+    }
+
+    if let Some(lp_staking_contract) = lp_staking_contract {
+        config.lp_staking_contract = Some(deps.api.addr_validate(lp_staking_contract.as_str())?);
+    }
+
+    if let Some(lp_staking_reserve_share) = lp_staking_reserve_share {
+        if lp_staking_reserve_share > Decimal256::one() {
+            return Err(ContractError::InvalidLpStakingReserveShare {});
+        }
+        config.lp_staking_reserve_share = lp_staking_reserve_share;
+    }
+
+    // Sending reserves to LP stakers requires the staking contract address
+    if !config.lp_staking_reserve_share.is_zero() && config.lp_staking_contract.is_none() {
+        return Err(ContractError::LpStakingNotRegistered {});
+    }
+
+    config.reserve_destination = reserve_destination;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "update_reserve_destination")]))
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn execute_update_lottery_config(
     deps: DepsMut,
     info: MessageInfo,
     lottery_interval: Option<u64>,
     block_time: Option<u64>,
     ticket_price: Option<Uint256>,
-    prize_distribution: Option<[Decimal256; NUM_PRIZE_BUCKETS]>,
     round_delta: Option<u64>,
+    loyalty_config: Option<LoyaltyConfig>,
+    sponsor_lock_config: Option<SponsorLockConfig>,
+    ticket_eligibility_delay: Option<u64>,
+    missed_lottery_policy: Option<MissedLotteryPolicy>,
+    time_weighted_odds: Option<bool>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
@@ -1488,21 +3300,41 @@ pub fn execute_update_lottery_config(
         config.ticket_price = ticket_price;
     }
 
-    if let Some(prize_distribution) = prize_distribution {
-        if prize_distribution.len() != NUM_PRIZE_BUCKETS {
-            return Err(ContractError::InvalidPrizeDistribution {});
+    if let Some(loyalty_config) = loyalty_config {
+        if loyalty_config.bonus_per_round > Decimal256::one() {
+            return Err(ContractError::InvalidLoyaltyConfig {});
         }
+        config.loyalty_config = loyalty_config;
+    }
 
-        let mut sum = Decimal256::zero();
-        for item in prize_distribution.iter() {
-            sum += *item;
+    if let Some(sponsor_lock_config) = sponsor_lock_config {
+        if sponsor_lock_config.bonus_per_week > Decimal256::one()
+            || sponsor_lock_config.early_exit_penalty > Decimal256::one()
+        {
+            return Err(ContractError::InvalidSponsorLockConfig {});
         }
+        config.sponsor_lock_config = sponsor_lock_config;
+    }
 
-        if sum != Decimal256::one() {
-            return Err(ContractError::InvalidPrizeDistribution {});
+    if let Some(ticket_eligibility_delay) = ticket_eligibility_delay {
+        // The delay must leave part of each lottery interval open for eligible tickets
+        let lottery_interval = if let Duration::Time(time) = config.lottery_interval {
+            time
+        } else {
+            return Err(ContractError::InvalidLotteryInterval {});
+        };
+        if ticket_eligibility_delay >= lottery_interval {
+            return Err(ContractError::InvalidTicketEligibilityDelay {});
         }
+        config.ticket_eligibility_delay = ticket_eligibility_delay;
+    }
 
-        config.prize_distribution = prize_distribution;
+    if let Some(missed_lottery_policy) = missed_lottery_policy {
+        config.missed_lottery_policy = missed_lottery_policy;
+    }
+
+    if let Some(time_weighted_odds) = time_weighted_odds {
+        config.time_weighted_odds = time_weighted_odds;
     }
 
     CONFIG.save(deps.storage, &config)?;
@@ -1510,12 +3342,38 @@ pub fn execute_update_lottery_config(
     Ok(Response::new().add_attributes(vec![("action", "update_lottery_config")]))
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    if msg.id == BUYBACK_REPLY_ID {
+        return handle_buyback_reply(deps, env, msg);
+    }
+    if msg.id == RECEIPT_TOKEN_REPLY_ID {
+        return handle_receipt_token_reply(deps, msg);
+    }
+    if msg.id == BADGE_REPLY_ID {
+        return handle_badge_reply(deps, msg);
+    }
+    if msg.id == HOOK_REPLY_ID {
+        return handle_hook_reply(msg);
+    }
+    if msg.id == JACKPOT_TOP_UP_REPLY_ID {
+        return handle_jackpot_top_up_reply(deps, env, msg);
+    }
+    if msg.id == CLAIM_RECEIPT_REPLY_ID {
+        return handle_claim_receipt_reply(deps, msg);
+    }
+    if msg.id == FEE_DISTRIBUTION_REPLY_ID {
+        return handle_fee_distribution_reply(deps, env, msg);
+    }
+    handle_anchor_reply(deps, msg)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::State { block_height } => to_binary(&query_state(deps, env, block_height)?),
-        QueryMsg::Pool {} => to_binary(&query_pool(deps)?),
+        QueryMsg::Pool { block_height } => to_binary(&query_pool(deps, block_height)?),
         QueryMsg::LotteryInfo { lottery_id } => {
             to_binary(&query_lottery_info(deps, env, lottery_id)?)
         }
@@ -1524,17 +3382,49 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             address,
             lottery_id,
         } => to_binary(&query_prizes(deps, address, lottery_id)?),
+        QueryMsg::PrizeInfos {
+            address,
+            lottery_ids,
+        } => to_binary(&query_prize_infos(deps, address, lottery_ids)?),
+        QueryMsg::PrizeBoostDetail {
+            address,
+            lottery_id,
+        } => to_binary(&query_prize_boost_detail(deps, address, lottery_id)?),
         QueryMsg::LotteryPrizeInfos {
             lottery_id,
             start_after,
             limit,
         } => to_binary(&query_lottery_prizes(deps, lottery_id, start_after, limit)?),
-        QueryMsg::DepositorInfo { address } => {
-            to_binary(&query_depositor_info(deps, env, address)?)
-        }
+        QueryMsg::LotteryWinners {
+            lottery_id,
+            start_after,
+            limit,
+        } => to_binary(&query_lottery_winners(
+            deps,
+            lottery_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::DepositorInfo {
+            address,
+            include_tickets,
+        } => to_binary(&query_depositor_info(
+            deps,
+            env,
+            address,
+            include_tickets.unwrap_or(false),
+        )?),
+        QueryMsg::DepositorTickets {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&query_depositor_tickets(deps, address, start_after, limit)?),
         QueryMsg::DepositorStatsInfo { address } => {
             to_binary(&query_depositor_stats(deps, env, address)?)
         }
+        QueryMsg::DepositorLedger { address } => {
+            to_binary(&query_depositor_ledger(deps, env, address)?)
+        }
         QueryMsg::DepositorInfos { start_after, limit } => {
             to_binary(&query_depositors_info(deps, start_after, limit)?)
         }
@@ -1542,8 +3432,111 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_binary(&query_depositors_stats(deps, start_after, limit)?)
         }
         QueryMsg::Sponsor { address } => to_binary(&query_sponsor(deps, env, address)?),
+        QueryMsg::PrizeDonations { donor } => to_binary(&query_prize_donations(deps, donor)?),
+        QueryMsg::QueuedWithdrawals { address } => {
+            to_binary(&query_queued_withdrawals(deps, env, address)?)
+        }
+        QueryMsg::WithdrawalQueue {} => to_binary(&query_withdrawal_queue(deps)?),
         QueryMsg::Operator { address } => to_binary(&query_operator(deps, env, address)?),
+        QueryMsg::OperatorDepositors {
+            operator,
+            start_after,
+            limit,
+        } => to_binary(&query_operator_depositors(
+            deps,
+            operator,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::OperatorStats { operator } => to_binary(&query_operator_stats(deps, operator)?),
+        QueryMsg::OperatorCampaignStats {
+            operator,
+            start_after,
+            limit,
+        } => to_binary(&query_operator_campaign_stats(
+            deps,
+            operator,
+            start_after,
+            limit,
+        )?),
         QueryMsg::LotteryBalance {} => to_binary(&query_lottery_balance(deps, env)?),
+        QueryMsg::PendingAnchorOperations {} => to_binary(&query_pending_anchor_operations(deps)?),
+        QueryMsg::SharePrice {} => to_binary(&query_share_price(deps, env)?),
+        QueryMsg::Account { address } => to_binary(&query_account(deps, env, address)?),
+        QueryMsg::Inbox { address } => to_binary(&query_inbox(deps, env, address)?),
+        QueryMsg::ScheduledUpdates { start_after, limit } => {
+            to_binary(&query_scheduled_updates(deps, start_after, limit)?)
+        }
+        QueryMsg::ConfigVersions {} => to_binary(&query_config_versions(deps, env)?),
+        QueryMsg::SequenceCoverage { prefix_len } => {
+            to_binary(&query_sequence_coverage(deps, prefix_len)?)
+        }
+        QueryMsg::IbcClaim { id } => to_binary(&query_ibc_claim(deps, id)?),
+        QueryMsg::ClaimReceipt { receipt_id } => to_binary(&query_claim_receipt(deps, receipt_id)?),
+        QueryMsg::Syndicate { syndicate_id } => to_binary(&query_syndicate(deps, syndicate_id)?),
+        QueryMsg::SyndicateMember {
+            syndicate_id,
+            member,
+        } => to_binary(&query_syndicate_member(deps, syndicate_id, member)?),
+        QueryMsg::ClaimableAt { address, timestamp } => {
+            to_binary(&query_claimable_at(deps, env, address, timestamp)?)
+        }
+        QueryMsg::Blacklist { address } => to_binary(&query_blacklist(deps, address)?),
+        QueryMsg::GlowPrizeReserve {} => to_binary(&query_glow_prize_reserve(deps)?),
+        QueryMsg::Escrow { address } => to_binary(&query_escrow(deps, address)?),
+        QueryMsg::GiftCard { code_hash } => to_binary(&query_gift_card(deps, code_hash)?),
+        QueryMsg::ReceiptToken {} => to_binary(&query_receipt_token(deps)?),
+        QueryMsg::Savings { address } => to_binary(&query_savings(deps, env, address)?),
+        QueryMsg::Buyback {} => to_binary(&query_buyback(deps)?),
+        QueryMsg::Charities { start_after, limit } => {
+            to_binary(&query_charities(deps, start_after, limit)?)
+        }
+        QueryMsg::ClaimAgents {
+            winner,
+            start_after,
+            limit,
+        } => to_binary(&query_claim_agents(deps, winner, start_after, limit)?),
+        QueryMsg::PayoutHash { depositor } => to_binary(&query_payout_hash(deps, depositor)?),
+        QueryMsg::Insurance {} => to_binary(&query_insurance(deps)?),
+        QueryMsg::JackpotFloor {} => to_binary(&query_jackpot_floor(deps)?),
+        QueryMsg::RateGuard {} => to_binary(&query_rate_guard(deps)?),
+        QueryMsg::InstantWithdrawalCap {} => to_binary(&query_instant_withdrawal_cap(deps)?),
+        QueryMsg::PrizeRebalancing {} => to_binary(&query_prize_rebalancing(deps)?),
+        QueryMsg::PrizeCaps {} => to_binary(&query_prize_caps(deps)?),
+        QueryMsg::AutoClaim { depositor } => to_binary(&query_auto_claim(deps, depositor)?),
+        QueryMsg::KeeperAllowlist {} => to_binary(&query_keeper_allowlist(deps)?),
+        QueryMsg::EpochOperations {} => to_binary(&query_epoch_operations(deps, env)?),
+        QueryMsg::Checkpoints { start_after, limit } => {
+            to_binary(&query_checkpoints(deps, start_after, limit)?)
+        }
+        QueryMsg::YieldStats {} => to_binary(&query_yield_stats(deps)?),
+        QueryMsg::Hooks {} => to_binary(&query_hooks(deps)?),
+        QueryMsg::WinnerLeaves {
+            lottery_id,
+            start_after,
+            limit,
+        } => to_binary(&query_winner_leaves(deps, lottery_id, start_after, limit)?),
+        QueryMsg::BadgeEligibility { address } => {
+            to_binary(&query_badge_eligibility(deps, address)?)
+        }
+        QueryMsg::EmissionSchedule { epochs } => to_binary(&query_emission_schedule(deps, epochs)?),
+        QueryMsg::PrizeTemplates { start_after, limit } => {
+            to_binary(&query_prize_templates(deps, start_after, limit)?)
+        }
+        QueryMsg::PrizeExecutionProgress { lottery_id } => {
+            to_binary(&query_prize_execution_progress(deps, lottery_id)?)
+        }
+        QueryMsg::CanExecuteLottery {} => to_binary(
+            &query_can_execute_lottery(deps, env)
+                .map_err(|err| StdError::generic_err(err.to_string()))?,
+        ),
+        QueryMsg::CanExecutePrize {} => to_binary(&query_can_execute_prize(deps, env)?),
+        QueryMsg::MigrationStatus {} => to_binary(&query_migration_status(deps)?),
+        QueryMsg::Odds { num_tickets } => to_binary(&query_odds(deps, num_tickets)?),
+        QueryMsg::NextLotteryProjection {} => to_binary(
+            &query_next_lottery_projection(deps, env)
+                .map_err(|err| StdError::generic_err(err.to_string()))?,
+        ),
     }
 }
 
@@ -1554,6 +3547,60 @@ pub fn query_ticket_info(deps: Deps, ticket: String) -> StdResult<TicketInfoResp
     Ok(TicketInfoResponse { holders })
 }
 
+pub fn query_sequence_coverage(deps: Deps, prefix_len: u8) -> StdResult<SequenceCoverageResponse> {
+    if prefix_len == 0 || prefix_len > MAX_COVERAGE_PREFIX_LEN {
+        return Err(StdError::generic_err(format!(
+            "prefix_len must be between 1 and {}",
+            MAX_COVERAGE_PREFIX_LEN
+        )));
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut coverage = vec![];
+    for prefix_index in 0..16u32.pow(prefix_len as u32) {
+        let prefix = format!("{:0width$x}", prefix_index, width = prefix_len as usize);
+
+        // All sequences starting with the prefix are between prefix000.. and prefixfff..
+        let min_bound = format!(
+            "{:0<length$}",
+            prefix,
+            length = config.ticket_length as usize
+        );
+        let max_bound = format!(
+            "{:f<length$}",
+            prefix,
+            length = config.ticket_length as usize
+        );
+
+        let mut sequences = 0u32;
+        let mut tickets = 0u32;
+        for item in TICKETS.range(
+            deps.storage,
+            Some(Bound::Inclusive(min_bound.as_bytes().to_vec())),
+            Some(Bound::Inclusive(max_bound.as_bytes().to_vec())),
+            Order::Ascending,
+        ) {
+            let (_, holders) = item?;
+            sequences += 1;
+            tickets += holders.len() as u32;
+        }
+
+        if sequences > 0 {
+            coverage.push(SequencePrefixCoverage {
+                prefix,
+                sequences,
+                tickets,
+            });
+        }
+    }
+
+    Ok(SequenceCoverageResponse {
+        prefix_len,
+        coverage,
+    })
+}
+
 pub fn query_prizes(deps: Deps, address: String, lottery_id: u64) -> StdResult<PrizeInfoResponse> {
     // Get config
     let config = CONFIG.load(deps.storage)?;
@@ -1573,26 +3620,137 @@ pub fn query_prizes(deps: Deps, address: String, lottery_id: u64) -> StdResult<P
             ));
         };
 
-    // Get ust and glow to send
+    prize_info_response(deps, &config, addr, lottery_id, &lottery_info, prize_info)
+}
+
+/// Prizes of address on each of lottery_ids, leaving out the lotteries it didn't win
+pub fn query_prize_infos(
+    deps: Deps,
+    address: String,
+    lottery_ids: Vec<u64>,
+) -> StdResult<PrizeInfosResponse> {
+    if lottery_ids.len() > MAX_PRIZE_INFOS_LOTTERIES {
+        return Err(StdError::generic_err(format!(
+            "Prizes can be queried for at most {} lotteries",
+            MAX_PRIZE_INFOS_LOTTERIES
+        )));
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let addr = deps.api.addr_validate(&address)?;
+
+    let mut prize_infos = vec![];
+    for lottery_id in lottery_ids {
+        let prize_info = match PRIZES.may_load(deps.storage, (U64Key::from(lottery_id), &addr))? {
+            Some(prize_info) => prize_info,
+            None => continue,
+        };
+        let lottery_info = read_lottery_info(deps.storage, lottery_id);
+
+        prize_infos.push(prize_info_response(
+            deps,
+            &config,
+            addr.clone(),
+            lottery_id,
+            &lottery_info,
+            prize_info,
+        )?);
+    }
+
+    Ok(PrizeInfosResponse {
+        prize_infos,
+        limit: MAX_PRIZE_INFOS_LOTTERIES as u32,
+    })
+}
+
+fn prize_info_response(
+    deps: Deps,
+    config: &Config,
+    addr: Addr,
+    lottery_id: u64,
+    lottery_info: &LotteryInfo,
+    prize_info: PrizeInfo,
+) -> StdResult<PrizeInfoResponse> {
+    // Get ust and glow to send, with the depositor stats snapshotted at the lottery
     let snapshotted_depositor_stats_info =
         read_depositor_stats_at_height(deps.storage, &addr, lottery_info.block_height);
 
     let (local_ust_to_send, local_glow_to_send): (Uint128, Uint128) = calculate_winner_prize(
         &deps.querier,
-        &config,
+        config,
         &prize_info,
-        &lottery_info,
+        lottery_info,
         &snapshotted_depositor_stats_info,
         &addr,
     )?;
 
-    Ok(PrizeInfoResponse {
-        holder: addr,
+    Ok(PrizeInfoResponse {
+        holder: addr,
+        lottery_id,
+        claimed: prize_info.claimed,
+        matches: prize_info.matches,
+        won_ust: local_ust_to_send,
+        won_glow: local_glow_to_send,
+    })
+}
+
+pub fn query_prize_boost_detail(
+    deps: Deps,
+    address: String,
+    lottery_id: u64,
+) -> StdResult<PrizeBoostDetailResponse> {
+    let lottery_key = U64Key::from(lottery_id);
+    let addr = deps.api.addr_validate(&address)?;
+    let prize_info =
+        if let Some(prize_info) = PRIZES.may_load(deps.storage, (lottery_key.clone(), &addr))? {
+            prize_info
+        } else {
+            return Err(StdError::generic_err(
+                "No prize with the specified address and lottery id.",
+            ));
+        };
+
+    // Prizes claimed before boosts were recorded are computed like unclaimed ones
+    let prize_boost = match PRIZE_BOOSTS.may_load(deps.storage, (lottery_key, &addr))? {
+        Some(prize_boost) => prize_boost,
+        None => {
+            let config = CONFIG.load(deps.storage)?;
+            let lottery_info = read_lottery_info(deps.storage, lottery_id);
+            let snapshotted_depositor_stats_info =
+                read_depositor_stats_at_height(deps.storage, &addr, lottery_info.block_height);
+
+            let boost = snapshot_winner_boost(
+                &deps.querier,
+                &config,
+                &lottery_info,
+                &snapshotted_depositor_stats_info,
+                &addr,
+            )?;
+            let (_, glow_prize) =
+                calculate_boosted_winner_prize(&prize_info, &lottery_info, boost.multiplier);
+
+            PrizeBoost {
+                boost,
+                glow_prize,
+                claimed_at_height: 0,
+            }
+        }
+    };
+
+    Ok(PrizeBoostDetailResponse {
+        address: addr.to_string(),
         lottery_id,
-        claimed: prize_info.claimed,
-        matches: prize_info.matches,
-        won_ust: local_ust_to_send,
-        won_glow: local_glow_to_send,
+        glow_boost_multiplier: prize_boost.boost.multiplier,
+        user_shares: prize_boost.boost.user_shares,
+        total_user_shares: prize_boost.boost.total_user_shares,
+        user_voting_balance: prize_boost.boost.user_voting_balance,
+        total_voting_balance: prize_boost.boost.total_voting_balance,
+        glow_prize: prize_boost.glow_prize,
+        claimed_at_height: if prize_boost.claimed_at_height == 0 {
+            None
+        } else {
+            Some(prize_boost.claimed_at_height)
+        },
     })
 }
 
@@ -1612,7 +3770,8 @@ pub fn query_lottery_prizes(
 
     let lottery_info = read_lottery_info(deps.storage, lottery_id);
 
-    let prize_infos = read_lottery_prizes(deps, lottery_id, addr, limit)?;
+    let pagination = Pagination::uncapped(addr, limit);
+    let prize_infos = read_lottery_prizes(deps, lottery_id, &pagination)?;
 
     let prize_info_responses = prize_infos
         .into_iter()
@@ -1643,11 +3802,73 @@ pub fn query_lottery_prizes(
 
     Ok(PrizeInfosResponse {
         prize_infos: prize_info_responses,
+        limit: pagination.limit as u32,
+    })
+}
+
+pub fn query_lottery_winners(
+    deps: Deps,
+    lottery_id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<LotteryWinnersResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let addr = if let Some(s) = start_after {
+        Some(deps.api.addr_validate(&s)?)
+    } else {
+        None
+    };
+
+    let lottery_info = read_lottery_info(deps.storage, lottery_id);
+
+    let pagination = Pagination::uncapped(addr, limit);
+    let prize_infos = read_lottery_prizes(deps, lottery_id, &pagination)?;
+
+    let winners = prize_infos
+        .into_iter()
+        .map(|(addr, prize_info)| {
+            let snapshotted_depositor_stats_info =
+                read_depositor_stats_at_height(deps.storage, &addr, lottery_info.block_height);
+
+            let glow_boost_multiplier = calculate_winner_boost_multiplier(
+                &deps.querier,
+                &config,
+                &lottery_info,
+                &snapshotted_depositor_stats_info,
+                &addr,
+            )?;
+
+            let (ust_prize, glow_prize): (Uint128, Uint128) = calculate_winner_prize(
+                &deps.querier,
+                &config,
+                &prize_info,
+                &lottery_info,
+                &snapshotted_depositor_stats_info,
+                &addr,
+            )?;
+
+            Ok(LotteryWinnerResponse {
+                address: addr.to_string(),
+                matches: prize_info.matches,
+                ust_prize,
+                glow_prize,
+                glow_boost_multiplier,
+                claimed: prize_info.claimed,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(LotteryWinnersResponse {
+        lottery_id,
+        winners,
+        limit: pagination.limit as u32,
     })
 }
 
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config = CONFIG.load(deps.storage)?;
+    let min_matches_for_prize = config.min_matches_for_prize()? as u8;
 
     Ok(ConfigResponse {
         owner: config.owner.to_string(),
@@ -1672,19 +3893,46 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         unbonding_period: config.unbonding_period,
         max_tickets_per_depositor: config.max_tickets_per_depositor,
         paused: config.paused,
+        pause_state: config.pause_state,
+        reserve_destination: config.reserve_destination,
+        fee_distributor_contract: config.fee_distributor_contract.map(|addr| addr.to_string()),
+        lp_staking_contract: config.lp_staking_contract.map(|addr| addr.to_string()),
+        lp_staking_reserve_share: config.lp_staking_reserve_share,
+        min_deposit_amount: config.min_deposit_amount,
+        loyalty_config: config.loyalty_config,
+        sponsor_lock_config: config.sponsor_lock_config,
+        ibc_claim_config: config.ibc_claim_config,
+        operator_reward_vesting_epochs: config.operator_reward_vesting_epochs,
+        ticket_eligibility_delay: config.ticket_eligibility_delay,
+        guardians: config
+            .guardians
+            .iter()
+            .map(|guardian| guardian.to_string())
+            .collect(),
+        missed_lottery_policy: config.missed_lottery_policy,
+        time_weighted_odds: config.time_weighted_odds,
+        ticket_length: config.ticket_length,
+        min_matches_for_prize,
     })
 }
 
 pub fn query_state(deps: Deps, env: Env, block_height: Option<u64>) -> StdResult<StateResponse> {
-    let pool = POOL.load(deps.storage)?;
-    let mut state = STATE.load(deps.storage)?;
-
     let block_height = if let Some(block_height) = block_height {
         block_height
     } else {
         env.block.height
     };
 
+    // Load the snapshotted state and pool if querying a past height
+    let (mut state, pool) = if block_height < env.block.height {
+        (
+            read_state_at_height(deps.storage, block_height)?,
+            read_pool_at_height(deps.storage, block_height)?,
+        )
+    } else {
+        (STATE.load(deps.storage)?, POOL.load(deps.storage)?)
+    };
+
     if block_height < state.operator_reward_emission_index.last_reward_updated
         || block_height < state.sponsor_reward_emission_index.last_reward_updated
     {
@@ -1699,6 +3947,7 @@ pub fn query_state(deps: Deps, env: Env, block_height: Option<u64>) -> StdResult
 
     Ok(StateResponse {
         total_tickets: state.total_tickets,
+        pending_tickets: read_total_pending_tickets(deps.storage)?.pending(state.current_lottery),
         total_reserve: state.total_reserve,
         prize_buckets: state.prize_buckets,
         current_lottery: state.current_lottery,
@@ -1708,17 +3957,29 @@ pub fn query_state(deps: Deps, env: Env, block_height: Option<u64>) -> StdResult
         operator_reward_emission_index: state.operator_reward_emission_index,
         sponsor_reward_emission_index: state.sponsor_reward_emission_index,
         last_lottery_execution_aust_exchange_rate: state.last_lottery_execution_aust_exchange_rate,
+        missed_lotteries: state.missed_lotteries,
+        missed_lottery_prizes: state.missed_lottery_prizes,
+        yield_deficit: state.yield_deficit,
     })
 }
 
-pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
-    let pool = POOL.load(deps.storage)?;
+pub fn query_pool(deps: Deps, block_height: Option<u64>) -> StdResult<PoolResponse> {
+    let pool = if let Some(block_height) = block_height {
+        read_pool_at_height(deps.storage, block_height)?
+    } else {
+        POOL.load(deps.storage)?
+    };
 
     Ok(PoolResponse {
         total_user_shares: pool.total_user_shares,
         total_user_aust: pool.total_user_aust,
         total_sponsor_lottery_deposits: pool.total_sponsor_lottery_deposits,
         total_operator_shares: pool.total_operator_shares,
+        total_sponsor_lock_bonus: pool.total_sponsor_lock_bonus,
+        total_sponsor_weight_bonus: pool.total_sponsor_weight_bonus,
+        total_pol_aust: pool.total_pol_aust,
+        total_pol_principal: pool.total_pol_principal,
+        total_savings_aust: pool.total_savings_aust,
     })
 }
 
@@ -1743,8 +4004,14 @@ pub fn query_lottery_info(
         glow_prize_buckets: lottery.glow_prize_buckets,
         prize_buckets: lottery.prize_buckets,
         number_winners: lottery.number_winners,
-        page: lottery.page,
+        prize_cursor: lottery.prize_cursor,
         total_user_shares: lottery.total_user_shares,
+        draw_timeline: lottery.draw_timeline,
+        loyalty_weights: lottery.loyalty_weights,
+        time_weight_window: lottery.time_weight_window,
+        funded_glow_prize_buckets: lottery.funded_glow_prize_buckets,
+        glow_prize_shortfall: lottery.glow_prize_shortfall,
+        winners_root: lottery.winners_root,
     })
 }
 
@@ -1752,18 +4019,41 @@ pub fn query_depositor_info(
     deps: Deps,
     _env: Env,
     addr: String,
+    include_tickets: bool,
 ) -> StdResult<DepositorInfoResponse> {
     let address = deps.api.addr_validate(&addr)?;
     let depositor = read_depositor_info(deps.storage, &address);
 
+    // The ticket list can outgrow the response size limit, see query_depositor_tickets
+    let tickets = if include_tickets {
+        read_depositor_tickets(deps.storage, &address)?
+    } else {
+        vec![]
+    };
+
     Ok(DepositorInfoResponse {
         depositor: addr,
         shares: depositor.shares,
-        tickets: depositor.tickets,
+        tickets,
         unbonding_info: depositor.unbonding_info,
     })
 }
 
+pub fn query_depositor_tickets(
+    deps: Deps,
+    addr: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<DepositorTicketsResponse> {
+    let address = deps.api.addr_validate(&addr)?;
+
+    Ok(DepositorTicketsResponse {
+        depositor: addr,
+        tickets: read_depositor_tickets_page(deps.storage, &address, start_after, limit)?,
+        num_tickets: read_depositor_stats(deps.storage, &address).num_tickets,
+    })
+}
+
 pub fn query_depositor_stats(
     deps: Deps,
     _env: Env,
@@ -1771,11 +4061,81 @@ pub fn query_depositor_stats(
 ) -> StdResult<DepositorStatsResponse> {
     let address = deps.api.addr_validate(&addr)?;
     let depositor_stats_info = read_depositor_stats(deps.storage, &address);
+    let current_lottery = STATE.load(deps.storage)?.current_lottery;
 
     Ok(DepositorStatsResponse {
         depositor: addr,
         shares: depositor_stats_info.shares,
         num_tickets: depositor_stats_info.num_tickets,
+        pending_tickets: read_pending_tickets(deps.storage, &address)?.pending(current_lottery),
+    })
+}
+
+pub fn query_depositor_ledger(
+    deps: Deps,
+    env: Env,
+    addr: String,
+) -> StdResult<DepositorLedgerResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pool = POOL.load(deps.storage)?;
+    let address = deps.api.addr_validate(&addr)?;
+    let ledger = read_depositor_ledger(deps.storage, &address);
+    let depositor_stats_info = read_depositor_stats(deps.storage, &address);
+
+    let aust_exchange_rate =
+        query_exchange_rate(deps, config.anchor_contract.to_string(), env.block.height)?
+            .exchange_rate;
+
+    // Value of the depositor's shares
+    let current_balance = pool.total_user_aust
+        * decimal_from_ratio_or_one(depositor_stats_info.shares, pool.total_user_shares)
+        * aust_exchange_rate;
+
+    // Redeposited winnings count as deposits, so they are offset by ust_won
+    let total_returned = current_balance + ledger.withdrawn + ledger.ust_won;
+    let (net_gain, net_loss) = if total_returned >= ledger.deposited {
+        (total_returned - ledger.deposited, Uint256::zero())
+    } else {
+        (Uint256::zero(), ledger.deposited - total_returned)
+    };
+
+    Ok(DepositorLedgerResponse {
+        depositor: addr,
+        lifetime_deposited: ledger.deposited,
+        lifetime_withdrawn: ledger.withdrawn,
+        lifetime_ust_won: ledger.ust_won,
+        lifetime_glow_won: ledger.glow_won,
+        current_balance,
+        net_gain,
+        net_loss,
+    })
+}
+
+pub fn query_prize_donations(
+    deps: Deps,
+    donor: Option<String>,
+) -> StdResult<PrizeDonationsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let bucket_donations = PRIZE_BUCKET_DONATIONS
+        .may_load(deps.storage)?
+        .unwrap_or(vec![Uint256::zero(); config.num_prize_buckets()]);
+
+    let donor_amount = match &donor {
+        Some(donor) => {
+            let address = deps.api.addr_validate(donor)?;
+            Some(
+                PRIZE_DONORS
+                    .may_load(deps.storage, &address)?
+                    .unwrap_or_default(),
+            )
+        }
+        None => None,
+    };
+
+    Ok(PrizeDonationsResponse {
+        bucket_donations,
+        donor,
+        donor_amount,
     })
 }
 
@@ -1795,6 +4155,9 @@ pub fn query_sponsor(deps: Deps, env: Env, addr: String) -> StdResult<SponsorInf
         lottery_deposit: sponsor.lottery_deposit,
         reward_index: sponsor.reward_index,
         pending_rewards: sponsor.pending_rewards,
+        lock_end_time: sponsor.lock_end_time,
+        lock_bonus_rate: sponsor.lock_bonus_rate,
+        emission_weight: sponsor.emission_weight,
     })
 }
 
@@ -1809,11 +4172,176 @@ pub fn query_operator(deps: Deps, env: Env, addr: String) -> StdResult<OperatorI
     compute_global_operator_reward(&mut state, &pool, env.block.height);
     compute_operator_reward(&state, &mut operator);
 
+    // settle pending rewards as ClaimRewards would, without storing the result
+    let config = CONFIG.load(deps.storage)?;
+    let current_epoch = read_current_epoch(deps.storage)?;
+    let mut operator_reward_ledger = read_operator_reward_ledger(deps.storage, &address)?;
+    operator_reward_ledger.settle(
+        operator.pending_rewards,
+        current_epoch,
+        config.operator_reward_vesting_epochs,
+    );
+
     Ok(OperatorInfoResponse {
         operator: addr,
         shares: operator.shares,
         reward_index: operator.reward_index,
         pending_rewards: operator.pending_rewards,
+        locked_rewards: operator_reward_ledger
+            .locked(current_epoch, config.operator_reward_vesting_epochs),
+        unlocked_rewards: operator_reward_ledger
+            .claimable(current_epoch, config.operator_reward_vesting_epochs),
+    })
+}
+
+pub fn query_operator_depositors(
+    deps: Deps,
+    operator: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<OperatorDepositorsResponse> {
+    let operator = deps.api.addr_validate(&operator)?;
+    let start_after = if let Some(start_after) = start_after {
+        Some(deps.api.addr_validate(&start_after)?)
+    } else {
+        None
+    };
+
+    let pagination = Pagination::uncapped(start_after, limit);
+    let depositors = read_operator_depositors(deps, &operator, &pagination)?;
+    Ok(OperatorDepositorsResponse {
+        depositors,
+        limit: pagination.limit as u32,
+    })
+}
+
+pub fn query_operator_stats(deps: Deps, addr: String) -> StdResult<OperatorStatsResponse> {
+    let address = deps.api.addr_validate(&addr)?;
+    let operator = read_operator_info(deps.storage, &address);
+    let pool = POOL.load(deps.storage)?;
+
+    let history = read_operator_share_checkpoints(deps.storage, &address)?
+        .into_iter()
+        .map(|checkpoint| OperatorShareCheckpointResponse {
+            time: checkpoint.time,
+            shares: checkpoint.shares,
+            total_operator_shares: checkpoint.total_operator_shares,
+            share_ratio: decimal_from_ratio_or_zero(
+                checkpoint.shares,
+                checkpoint.total_operator_shares,
+            ),
+        })
+        .collect();
+
+    Ok(OperatorStatsResponse {
+        operator: addr,
+        shares: operator.shares,
+        total_operator_shares: pool.total_operator_shares,
+        share_ratio: decimal_from_ratio_or_zero(operator.shares, pool.total_operator_shares),
+        history,
+    })
+}
+
+pub fn query_account(deps: Deps, env: Env, addr: String) -> StdResult<AccountResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+    let pool = POOL.load(deps.storage)?;
+    let address = deps.api.addr_validate(&addr)?;
+    let depositor = read_depositor_info(deps.storage, &address);
+
+    let sponsor = query_sponsor(deps, env.clone(), addr.clone())?;
+    let operator = query_operator(deps, env.clone(), addr.clone())?;
+
+    let unbonding_info = depositor
+        .unbonding_info
+        .into_iter()
+        .map(|claim| UnbondingClaimResponse {
+            amount: claim.amount,
+            time_remaining: match claim.release_at {
+                Expiration::AtTime(time) => time.seconds().saturating_sub(env.block.time.seconds()),
+                Expiration::AtHeight(height) => height.saturating_sub(env.block.height),
+                Expiration::Never {} => u64::MAX,
+            },
+            release_at: claim.release_at,
+        })
+        .collect();
+
+    // Count the prizes won and not claimed yet across all lotteries
+    let mut unclaimed_prizes = 0u32;
+    for lottery_id in 0..=state.current_lottery {
+        if let Some(prize_info) =
+            PRIZES.may_load(deps.storage, (U64Key::from(lottery_id), &address))?
+        {
+            if !prize_info.claimed {
+                unclaimed_prizes += 1;
+            }
+        }
+    }
+
+    // Boost the depositor would get if a lottery was executed now
+    let user_voting_balance = query_address_voting_power_at_timestamp(
+        &deps.querier,
+        &config.gov_contract,
+        env.block.time.seconds(),
+        &address,
+    )?;
+    let total_voting_balance = query_total_voting_balance_at_timestamp(
+        &deps.querier,
+        &config.ve_contract,
+        env.block.time.seconds(),
+    )?;
+    let boost_multiplier = calculate_boost_multiplier(
+        config.lotto_winner_boost_config,
+        depositor.shares,
+        pool.total_user_shares,
+        user_voting_balance,
+        total_voting_balance,
+    );
+
+    Ok(AccountResponse {
+        address: addr,
+        shares: depositor.shares,
+        tickets: read_depositor_tickets(deps.storage, &address)?,
+        unbonding_info,
+        pending_glow_rewards: sponsor.pending_rewards + operator.pending_rewards,
+        sponsor,
+        operator,
+        unclaimed_prizes,
+        boost_multiplier,
+    })
+}
+
+pub fn query_claimable_at(
+    deps: Deps,
+    env: Env,
+    addr: String,
+    timestamp: u64,
+) -> StdResult<ClaimableAtResponse> {
+    let address = deps.api.addr_validate(&addr)?;
+    let depositor = read_depositor_info(deps.storage, &address);
+
+    // Height based claims are only counted if they are already released
+    let block = BlockInfo {
+        time: Timestamp::from_seconds(timestamp),
+        ..env.block
+    };
+
+    let (claimable, unbonding) = depositor.unbonding_info.iter().fold(
+        (Uint256::zero(), Uint256::zero()),
+        |(claimable, unbonding), claim| {
+            if claim.release_at.is_expired(&block) {
+                (claimable + claim.amount, unbonding)
+            } else {
+                (claimable, unbonding + claim.amount)
+            }
+        },
+    );
+
+    Ok(ClaimableAtResponse {
+        address: addr,
+        timestamp,
+        claimable,
+        unbonding,
     })
 }
 
@@ -1828,8 +4356,12 @@ pub fn query_depositors_info(
         None
     };
 
-    let depositors = read_depositors_info(deps, start_after, limit)?;
-    Ok(DepositorsInfoResponse { depositors })
+    let pagination = Pagination::uncapped(start_after, limit);
+    let depositors = read_depositors_info(deps, &pagination)?;
+    Ok(DepositorsInfoResponse {
+        depositors,
+        limit: pagination.limit as u32,
+    })
 }
 
 pub fn query_depositors_stats(
@@ -1843,8 +4375,12 @@ pub fn query_depositors_stats(
         None
     };
 
-    let depositors = read_depositors_stats(deps, start_after, limit)?;
-    Ok(DepositorsStatsResponse { depositors })
+    let pagination = Pagination::uncapped(start_after, limit);
+    let depositors = read_depositors_stats(deps, &pagination)?;
+    Ok(DepositorsStatsResponse {
+        depositors,
+        limit: pagination.limit as u32,
+    })
 }
 
 pub fn query_lottery_balance(deps: Deps, env: Env) -> StdResult<LotteryBalanceResponse> {
@@ -1869,13 +4405,18 @@ pub fn query_lottery_balance(deps: Deps, env: Env) -> StdResult<LotteryBalanceRe
         user_aust_to_redeem,
         value_of_sponsor_aust_to_be_redeemed_for_lottery,
         sponsor_aust_to_redeem,
+        value_of_pol_aust_to_be_redeemed_for_lottery,
+        pol_aust_to_redeem,
         aust_to_redeem,
         aust_to_redeem_value,
     } = calculate_value_of_aust_to_be_redeemed_for_lottery(
         &state,
         &pool,
         &config,
-        contract_a_balance,
+        // aust held for queued withdrawals and receipt tokens is not part of the pool
+        contract_a_balance
+            - read_withdrawal_queue_state(deps.storage)?.total_aust
+            - read_total_receipt_aust(deps.storage)?,
         aust_exchange_rate,
     );
 
@@ -1884,250 +4425,47 @@ pub fn query_lottery_balance(deps: Deps, env: Env) -> StdResult<LotteryBalanceRe
         user_aust_to_redeem,
         value_of_sponsor_aust_to_be_redeemed_for_lottery,
         sponsor_aust_to_redeem,
+        value_of_pol_aust_to_be_redeemed_for_lottery,
+        pol_aust_to_redeem,
         aust_to_redeem,
         aust_to_redeem_value,
         prize_buckets: state.prize_buckets,
     })
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> StdResult<Response> {
-    // Migration Notes
-    // The changes to storage:
-    // - CONFIG (reuses storage key)
-    // - LOTTERIES (new storage key)
-    // - PRIZES (new storage key)
-    // - DEPOSITORS (new storage key, paginated migration)
-    // - STATE (reuses storage key)
-    // - POOL (reuses storage key)
-
-    let default_lotto_winner_boost_config: BoostConfig = BoostConfig {
-        base_multiplier: Decimal256::from_ratio(40u64, 100u64),
-        max_multiplier: Decimal256::one(),
-        total_voting_power_weight: Decimal256::percent(150),
-    };
-
-    let lotto_winner_boost_config =
-        if let Some(msg_lotto_winner_boost_config) = msg.lotto_winner_boost_config {
-            if msg_lotto_winner_boost_config.base_multiplier
-                > msg_lotto_winner_boost_config.max_multiplier
-            {
-                return Err(StdError::generic_err(
-                    "boost config base multiplier must be less than max multiplier",
-                ));
-            }
-            msg_lotto_winner_boost_config
-        } else {
-            default_lotto_winner_boost_config
-        };
-
-    // migrate config
-    let old_config = OLDCONFIG.load(deps.as_ref().storage)?;
-    let new_config = Config {
-        owner: old_config.owner,
-        a_terra_contract: old_config.a_terra_contract,
-        gov_contract: old_config.gov_contract,
-        ve_contract: deps.api.addr_validate(msg.ve_contract.as_str())?,
-        community_contract: deps.api.addr_validate(msg.community_contract.as_str())?,
-        distributor_contract: old_config.distributor_contract,
-        oracle_contract: old_config.oracle_contract,
-        stable_denom: old_config.stable_denom,
-        anchor_contract: old_config.anchor_contract,
-        lottery_interval: old_config.lottery_interval,
-        epoch_interval: old_config.epoch_interval,
-        block_time: old_config.block_time,
-        round_delta: old_config.round_delta,
-        ticket_price: old_config.ticket_price,
-        max_holders: old_config.max_holders,
-        prize_distribution: old_config.prize_distribution,
-        target_award: old_config.target_award,
-        reserve_factor: old_config.reserve_factor,
-        split_factor: old_config.split_factor,
-        instant_withdrawal_fee: old_config.instant_withdrawal_fee,
-        unbonding_period: old_config.unbonding_period,
-        max_tickets_per_depositor: msg.max_tickets_per_depositor,
-        glow_prize_buckets: msg.glow_prize_buckets,
-        paused: true,
-        lotto_winner_boost_config,
-    };
-
-    CONFIG.save(deps.storage, &new_config)?;
-
-    // Query exchange_rate from anchor money market
-    let aust_exchange_rate: Decimal256 = query_exchange_rate(
-        deps.as_ref(),
-        deps.api
-            .addr_validate(new_config.anchor_contract.as_str())?
-            .to_string(),
-        env.block.height,
-    )?
-    .exchange_rate;
-
-    let old_state = OLDSTATE.load(deps.storage)?;
-
-    let state = State {
-        total_tickets: old_state.total_tickets,
-        total_reserve: old_state.total_reserve,
-        prize_buckets: old_state.prize_buckets,
-        current_lottery: old_state.current_lottery,
-        next_lottery_time: old_state.next_lottery_time,
-        next_lottery_exec_time: old_state.next_lottery_exec_time,
-        next_epoch: old_state.next_epoch,
-        operator_reward_emission_index: RewardEmissionsIndex {
-            global_reward_index: Decimal256::zero(),
-            glow_emission_rate: msg.operator_glow_emission_rate,
-            last_reward_updated: env.block.height,
-        },
-        sponsor_reward_emission_index: RewardEmissionsIndex {
-            global_reward_index: old_state.global_reward_index,
-            glow_emission_rate: msg.sponsor_glow_emission_rate,
-            last_reward_updated: old_state.last_reward_updated,
-        },
-        last_lottery_execution_aust_exchange_rate: aust_exchange_rate,
-    };
-
-    STATE.save(deps.storage, &state)?;
-
-    // Migrate pool
-    // Initially total_user_aust and total_user_shares are set to 0
-    // But they are updated in the migrate_old_depositors section of the loop
-    let old_pool = OLDPOOL.load(deps.as_ref().storage)?;
-    let new_pool = Pool {
-        total_user_aust: Uint256::zero(),
-        total_user_shares: Uint256::zero(),
-        total_sponsor_lottery_deposits: old_pool.total_sponsor_lottery_deposits,
-        total_operator_shares: Uint256::zero(),
-    };
-
-    POOL.save(deps.storage, &new_pool)?;
-
-    // Migrate prize info
-    let old_prizes = OLD_PRIZES
-        .range(deps.storage, None, None, Order::Ascending)
-        .map(|item| {
-            let (mut k, v) = item?;
-
-            // https://github.com/CosmWasm/cw-plus/issues/466
-
-            // Gets the length prefix from the composite key
-            let mut tu = k.split_off(2);
-
-            // Calculate the size of the first key in the composite key
-            // using the length prefix
-            let t_len = parse_length(&k)?;
-
-            // Split tu into the first and second key.
-            // u is the second key, and tu is the first key
-            let u = tu.split_off(t_len);
-
-            // Extract address from the first key
-            let addr = Addr::unchecked(from_utf8(&tu)?);
-
-            // Extract the lottery id from the second key
-            let lottery_id = U64Key::from(u);
+pub fn query_pending_anchor_operations(deps: Deps) -> StdResult<PendingAnchorOperationsResponse> {
+    let pending = read_pending_anchor_operations(deps.storage)?;
 
-            // Return
-            Ok((lottery_id, addr, v))
-        })
-        .collect::<StdResult<Vec<_>>>()?;
-
-    for old_prize in old_prizes {
-        let (lottery_id, addr, prize_info) = old_prize;
-        OLD_PRIZES.remove(deps.storage, (&addr, lottery_id.clone()));
-
-        PRIZES.save(deps.storage, (lottery_id, &addr), &prize_info)?;
-    }
-
-    Ok(Response::default())
+    Ok(PendingAnchorOperationsResponse {
+        redeem_stable: pending.redeem_stable,
+    })
 }
 
-pub fn migrate_old_depositors(
-    deps: DepsMut,
-    env: Env,
-    limit: Option<u32>,
-) -> Result<Response, ContractError> {
-    let mut config = CONFIG.load(deps.storage)?;
-
-    let aust_exchange_rate: Decimal256 = query_exchange_rate(
-        deps.as_ref(),
-        deps.api
-            .addr_validate(config.anchor_contract.as_str())?
-            .to_string(),
-        env.block.height,
-    )?
-    .exchange_rate;
-
-    let old_depositors = old_read_depositors(deps.as_ref(), None, limit)?;
-
-    let mut num_migrated_entries: u32 = 0;
-
-    let mut pool = POOL.load(deps.storage)?;
-
-    for (addr, old_depositor_info) in old_depositors {
-        // Delete old depositor
-        old_remove_depositor_info(deps.storage, &addr);
-
-        // Get the depositors balance, add the value of the savings aust with the lottery_deposit
-        // Then at the end there will be some left over aust.
-        // This will be captured by the sponsors.
-        let depositor_aust_balance = old_depositor_info.savings_aust
-            + old_depositor_info.lottery_deposit / aust_exchange_rate;
-
-        let new_depositor_info = DepositorInfo {
-            shares: depositor_aust_balance,
-            tickets: old_depositor_info.tickets,
-            unbonding_info: old_depositor_info.unbonding_info,
-            operator_addr: Addr::unchecked(""),
-        };
-
-        pool.total_user_shares += depositor_aust_balance;
-        pool.total_user_aust += depositor_aust_balance;
-
-        // Store new depositor
-        store_depositor_info(deps.storage, &addr, new_depositor_info, env.block.height)?;
-
-        // Increment num_migrates_entries
-        num_migrated_entries += 1;
-    }
-
-    let old_depositors = old_read_depositors(deps.as_ref(), None, Some(1))?;
-    if old_depositors.is_empty() {
-        // Migrate lottery info
-
-        let state = STATE.load(deps.storage)?;
-
-        // Don't need to include state.current_lottery
-        // because nothing has been saved with id state.current_lottery yet
-        for i in 0..state.current_lottery {
-            let old_lottery_info = old_read_lottery_info(deps.storage, i);
-
-            let new_lottery_info = LotteryInfo {
-                rand_round: old_lottery_info.rand_round,
-                sequence: old_lottery_info.sequence,
-                awarded: old_lottery_info.awarded,
-                timestamp: Timestamp::from_seconds(0),
-                prize_buckets: old_lottery_info.prize_buckets,
-                number_winners: old_lottery_info.number_winners,
-                page: old_lottery_info.page,
-                glow_prize_buckets: [Uint256::zero(); NUM_PRIZE_BUCKETS],
-                block_height: old_lottery_info.timestamp,
-                total_user_shares: pool.total_user_shares,
-            };
-
-            store_lottery_info(deps.storage, i, &new_lottery_info)?;
+pub fn query_share_price(deps: Deps, env: Env) -> StdResult<SharePriceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pool = POOL.load(deps.storage)?;
 
-            old_remove_lottery_info(deps.storage, i);
-        }
+    let aust_exchange_rate =
+        query_exchange_rate(deps, config.anchor_contract.to_string(), env.block.height)?
+            .exchange_rate;
+    let share_price = calculate_share_price(&pool, aust_exchange_rate);
 
-        // Set paused to false and save
-        config.paused = false;
-        CONFIG.save(deps.storage, &config)?;
-    }
+    let checkpoints = read_share_price_checkpoints(deps.storage)?;
+    let twap = calculate_share_price_twap(&checkpoints, env.block.time.seconds(), share_price);
 
-    POOL.save(deps.storage, &pool)?;
+    Ok(SharePriceResponse {
+        share_price,
+        aust_exchange_rate,
+        twap,
+        twap_start_time: checkpoints
+            .first()
+            .map(|checkpoint| checkpoint.time)
+            .unwrap_or_else(|| env.block.time.seconds()),
+        num_checkpoints: checkpoints.len() as u32,
+    })
+}
 
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "migrate_old_depositors"),
-        attr("num_migrated_entries", num_migrated_entries.to_string()),
-    ]))
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    migrate_to(deps, env, msg)
 }
@@ -0,0 +1,155 @@
+use cosmwasm_bignumber::Decimal256;
+use cosmwasm_std::{attr, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage};
+use glow_protocol::lotto::{
+    EmissionDecaySchedule, EmissionScheduleResponse, ProjectedEmissionRates,
+};
+
+use crate::error::ContractError;
+use crate::helpers::{compute_global_operator_reward, compute_global_sponsor_reward};
+use crate::state::{read_current_epoch, EmissionDecay, State, CONFIG, EMISSION_DECAY, POOL, STATE};
+
+// settings for the projected rates
+const MAX_PROJECTED_EPOCHS: u32 = 100;
+const DEFAULT_PROJECTED_EPOCHS: u32 = 10;
+
+/// Emission rate after one epoch of decay. Rates already at or
+/// below the floor, e.g. set through UpdateConfig, are left as is
+fn decayed_rate(rate: Decimal256, schedule: &Option<EmissionDecaySchedule>) -> Decimal256 {
+    match schedule {
+        Some(schedule) if rate > schedule.floor => {
+            let decayed = rate * schedule.decay_factor;
+            if decayed > schedule.floor {
+                decayed
+            } else {
+                schedule.floor
+            }
+        }
+        _ => rate,
+    }
+}
+
+/// Decays the operator and sponsor emission rates by one epoch. Global rewards
+/// must be computed up to the current block before calling this
+pub fn apply_emission_decay(storage: &dyn Storage, state: &mut State) -> StdResult<()> {
+    let emission_decay = EMISSION_DECAY.may_load(storage)?.unwrap_or_default();
+
+    state.operator_reward_emission_index.glow_emission_rate = decayed_rate(
+        state.operator_reward_emission_index.glow_emission_rate,
+        &emission_decay.operator_decay,
+    );
+    state.sponsor_reward_emission_index.glow_emission_rate = decayed_rate(
+        state.sponsor_reward_emission_index.glow_emission_rate,
+        &emission_decay.sponsor_decay,
+    );
+
+    Ok(())
+}
+
+fn validate_schedule(schedule: &Option<EmissionDecaySchedule>) -> Result<(), ContractError> {
+    if let Some(schedule) = schedule {
+        if schedule.decay_factor > Decimal256::one() || schedule.floor > schedule.initial_rate {
+            return Err(ContractError::InvalidEmissionDecay {});
+        }
+    }
+
+    Ok(())
+}
+
+/// Set the emission decay schedules. The emission rate of each schedule
+/// is reset to its initial_rate - restricted to gov
+pub fn execute_update_emission_decay(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator_decay: Option<EmissionDecaySchedule>,
+    sponsor_decay: Option<EmissionDecaySchedule>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    validate_schedule(&operator_decay)?;
+    validate_schedule(&sponsor_decay)?;
+
+    let pool = POOL.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+
+    // Rewards accrued so far are distributed at the previous rates
+    compute_global_operator_reward(&mut state, &pool, env.block.height);
+    compute_global_sponsor_reward(&mut state, &pool, env.block.height);
+
+    if let Some(operator_decay) = &operator_decay {
+        state.operator_reward_emission_index.glow_emission_rate = operator_decay.initial_rate;
+    }
+    if let Some(sponsor_decay) = &sponsor_decay {
+        state.sponsor_reward_emission_index.glow_emission_rate = sponsor_decay.initial_rate;
+    }
+
+    STATE.save(deps.storage, &state, env.block.height)?;
+    EMISSION_DECAY.save(
+        deps.storage,
+        &EmissionDecay {
+            operator_decay,
+            sponsor_decay,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_emission_decay"),
+        attr(
+            "operator_glow_emission_rate",
+            state
+                .operator_reward_emission_index
+                .glow_emission_rate
+                .to_string(),
+        ),
+        attr(
+            "sponsor_glow_emission_rate",
+            state
+                .sponsor_reward_emission_index
+                .glow_emission_rate
+                .to_string(),
+        ),
+    ]))
+}
+
+pub fn query_emission_schedule(
+    deps: Deps,
+    epochs: Option<u32>,
+) -> StdResult<EmissionScheduleResponse> {
+    let epochs = epochs
+        .unwrap_or(DEFAULT_PROJECTED_EPOCHS)
+        .min(MAX_PROJECTED_EPOCHS);
+    let emission_decay = EMISSION_DECAY.may_load(deps.storage)?.unwrap_or_default();
+    let state = STATE.load(deps.storage)?;
+    let current_epoch = read_current_epoch(deps.storage)?;
+
+    let operator_glow_emission_rate = state.operator_reward_emission_index.glow_emission_rate;
+    let sponsor_glow_emission_rate = state.sponsor_reward_emission_index.glow_emission_rate;
+
+    let mut operator_rate = operator_glow_emission_rate;
+    let mut sponsor_rate = sponsor_glow_emission_rate;
+    let projected_rates = (1..=epochs as u64)
+        .map(|offset| {
+            operator_rate = decayed_rate(operator_rate, &emission_decay.operator_decay);
+            sponsor_rate = decayed_rate(sponsor_rate, &emission_decay.sponsor_decay);
+            ProjectedEmissionRates {
+                epoch: current_epoch + offset,
+                operator_glow_emission_rate: operator_rate,
+                sponsor_glow_emission_rate: sponsor_rate,
+            }
+        })
+        .collect();
+
+    Ok(EmissionScheduleResponse {
+        current_epoch,
+        operator_decay: emission_decay.operator_decay,
+        sponsor_decay: emission_decay.sponsor_decay,
+        operator_glow_emission_rate,
+        sponsor_glow_emission_rate,
+        projected_rates,
+    })
+}
@@ -0,0 +1,525 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    attr, to_binary, BankMsg, Coin, ContractResult, CosmosMsg, Deps, DepsMut, Env, Order, Reply,
+    Response, StdResult, Storage, SubMsg, WasmMsg,
+};
+use cw0::Expiration;
+use cw_storage_plus::{Bound, U64Key};
+use glow_protocol::common::Pagination;
+use glow_protocol::fee_distributor::ExecuteMsg as FeeDistributorExecuteMsg;
+use glow_protocol::lotto::{
+    CheckpointsResponse, EpochOperation, EpochOperationStatus, EpochOperationsResponse,
+    ProtocolCheckpoint, ReserveDestination, YieldStatsResponse, SECONDS_PER_YEAR,
+};
+use glow_protocol::querier::deduct_tax;
+use glow_protocol::staking::ExecuteMsg as StakingExecuteMsg;
+use std::ops::Add;
+
+use crate::buyback::{buyback_reserves, buyback_submsg};
+use crate::emission_decay::apply_emission_decay;
+use crate::error::ContractError;
+use crate::helpers::{
+    calculate_share_price, compute_global_operator_reward, compute_global_sponsor_reward,
+};
+use crate::insurance::accrue_insurance;
+use crate::jackpot_floor::repay_jackpot_subsidy;
+use crate::prize_rebalancing::apply_prize_rebalancing;
+use crate::querier::query_aust_balance_and_exchange_rate;
+use crate::state::{
+    read_current_epoch, read_lottery_info, store_share_price_checkpoint, Config,
+    EpochOperationState, SharePriceCheckpoint, State, CONFIG, CURRENT_EPOCH, EPOCH_OPERATIONS,
+    FEE_DISTRIBUTION_IN_FLIGHT, IBC_CLAIMS, IBC_CLAIM_SWEEP_CURSOR, INSTANT_WITHDRAWALS,
+    MAX_PROTOCOL_CHECKPOINTS, POOL, PRIZE_TIER_STATS, PROTOCOL_CHECKPOINTS, STATE,
+};
+
+// Order the epoch operations run in when executed together
+pub const EPOCH_OPERATIONS_ORDER: [EpochOperation; 3] = [
+    EpochOperation::SweepReserve,
+    EpochOperation::UpdateEmissions,
+    EpochOperation::SweepExpired,
+];
+
+pub const FEE_DISTRIBUTION_REPLY_ID: u64 = 9;
+
+// Maximum number of ibc claims scanned by a single SweepExpired
+const MAX_SCANNED_IBC_CLAIMS: usize = 30;
+
+// Number of trailing epochs the yield stats are computed over
+pub const YIELD_STATS_EPOCHS: u64 = 4;
+
+fn operation_key(operation: EpochOperation) -> &'static str {
+    match operation {
+        EpochOperation::SweepReserve => "sweep_reserve",
+        EpochOperation::UpdateEmissions => "update_emissions",
+        EpochOperation::SweepExpired => "sweep_expired",
+    }
+}
+
+/// Schedules every epoch operation which isn't tracked yet at next_execution,
+/// so each of them keeps its own schedule from the start
+pub fn init_epoch_operations(
+    storage: &mut dyn Storage,
+    next_execution: Expiration,
+) -> StdResult<()> {
+    for operation in EPOCH_OPERATIONS_ORDER.iter() {
+        let key = operation_key(*operation);
+        if !EPOCH_OPERATIONS.has(storage, key) {
+            EPOCH_OPERATIONS.save(
+                storage,
+                key,
+                &EpochOperationState {
+                    last_executed: None,
+                    next_execution,
+                },
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn assert_ready(
+    storage: &dyn Storage,
+    env: &Env,
+    state: &State,
+    operation: EpochOperation,
+) -> Result<(), ContractError> {
+    // Validate that executing the operation will follow rate limiting
+    let next_execution = EPOCH_OPERATIONS
+        .load(storage, operation_key(operation))?
+        .next_execution;
+    if !next_execution.is_expired(&env.block) {
+        return Err(ContractError::InvalidEpochExecution {
+            next_epoch: next_execution,
+        });
+    }
+
+    // Validate that the lottery is not in the process of running
+    // This helps avoid delaying the computing of the reward following lottery execution.
+    if operation == EpochOperation::UpdateEmissions {
+        let current_lottery = read_lottery_info(storage, state.current_lottery);
+        if current_lottery.rand_round != 0 {
+            return Err(ContractError::LotteryAlreadyStarted {
+                lottery_id: state.current_lottery,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the given epoch operations in EPOCH_OPERATIONS_ORDER. Each operation
+/// is rate limited on its own, so one failing leg can be skipped by running
+/// the others individually
+pub fn execute_epoch_operations(
+    mut deps: DepsMut,
+    env: Env,
+    operations: Vec<EpochOperation>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+
+    // Validate distributor contract has already been registered
+    if !config.contracts_registered() {
+        return Err(ContractError::NotRegistered {});
+    }
+
+    if operations.is_empty() {
+        return Err(ContractError::NoEpochOperations {});
+    }
+
+    // Readiness is checked before any operation updates next_epoch
+    let operations: Vec<EpochOperation> = EPOCH_OPERATIONS_ORDER
+        .iter()
+        .copied()
+        .filter(|operation| operations.contains(operation))
+        .collect();
+    for operation in operations.iter() {
+        assert_ready(deps.storage, &env, &state, *operation)?;
+    }
+
+    let mut response = Response::new().add_attribute("action", "execute_epoch_operations");
+    let mut executed = vec![];
+    for operation in operations {
+        let operation_response = match operation {
+            EpochOperation::SweepReserve => {
+                sweep_reserve(deps.branch(), &env, &config, &mut state)?
+            }
+            EpochOperation::UpdateEmissions => {
+                update_emissions(deps.branch(), &env, &config, &mut state)?
+            }
+            EpochOperation::SweepExpired => sweep_expired(deps.storage)?,
+        };
+        response = response
+            .add_submessages(operation_response.messages)
+            .add_attributes(operation_response.attributes);
+
+        EPOCH_OPERATIONS.save(
+            deps.storage,
+            operation_key(operation),
+            &EpochOperationState {
+                last_executed: Some(env.block.time.seconds()),
+                next_execution: Expiration::AtTime(env.block.time).add(config.epoch_interval)?,
+            },
+        )?;
+        executed.push(operation_key(operation));
+    }
+
+    STATE.save(deps.storage, &state, env.block.height)?;
+
+    Ok(response.add_attribute("operations", executed.join(",")))
+}
+
+/// The insurance share of total_reserves stays in the contract for the insurance fund.
+/// The buyback share of the rest is swapped to GLOW, then LP stakers get their share
+/// of what is left, which is split between the community and fee distributor contracts
+fn sweep_reserve(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    state: &mut State,
+) -> Result<Response, ContractError> {
+    let total_reserves = state.total_reserve;
+    let uninsured_reserves = accrue_insurance(deps.storage, total_reserves)?;
+    let insurance_reserves = total_reserves - uninsured_reserves;
+    // The jackpot subsidy is repaid before anything is distributed
+    let subsidy_repayment = repay_jackpot_subsidy(deps.storage, uninsured_reserves)?;
+    let swept_reserves = uninsured_reserves - subsidy_repayment;
+    let buyback_reserves = buyback_reserves(deps.as_ref(), swept_reserves)?;
+    let distributed_reserves = swept_reserves - buyback_reserves;
+    let lp_staking_reserves = distributed_reserves * config.lp_staking_reserve_share;
+    let remaining_reserves = distributed_reserves - lp_staking_reserves;
+    let community_reserves = match config.reserve_destination {
+        ReserveDestination::Community => remaining_reserves,
+        ReserveDestination::FeeDistributor => Uint256::zero(),
+        ReserveDestination::Split { community_bps } => {
+            remaining_reserves * Decimal256::from_ratio(community_bps, 10_000u64)
+        }
+    };
+    let fee_distributor_reserves = remaining_reserves - community_reserves;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !subsidy_repayment.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: config.community_contract.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: config.stable_denom.clone(),
+                    amount: subsidy_repayment.into(),
+                },
+            )?],
+        }));
+    }
+    if !lp_staking_reserves.is_zero() {
+        let lp_staking_contract = config
+            .lp_staking_contract
+            .clone()
+            .ok_or(ContractError::LpStakingNotRegistered {})?;
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: lp_staking_contract.to_string(),
+            funds: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: config.stable_denom.clone(),
+                    amount: lp_staking_reserves.into(),
+                },
+            )?],
+            msg: to_binary(&StakingExecuteMsg::DepositReward {})?,
+        }));
+    }
+    if !community_reserves.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: config.community_contract.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: config.stable_denom.clone(),
+                    amount: community_reserves.into(),
+                },
+            )?],
+        }));
+    }
+    let mut response = Response::new().add_messages(messages);
+    if !fee_distributor_reserves.is_zero() {
+        let fee_distributor_contract = config
+            .fee_distributor_contract
+            .clone()
+            .ok_or(ContractError::FeeDistributorNotRegistered {})?;
+        // A failing fee distributor doesn't hold the rest of the sweep back
+        FEE_DISTRIBUTION_IN_FLIGHT.save(deps.storage, &fee_distributor_reserves)?;
+        response = response.add_submessage(SubMsg::reply_on_error(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: fee_distributor_contract.to_string(),
+                funds: vec![deduct_tax(
+                    deps.as_ref(),
+                    Coin {
+                        denom: config.stable_denom.clone(),
+                        amount: fee_distributor_reserves.into(),
+                    },
+                )?],
+                msg: to_binary(&FeeDistributorExecuteMsg::DistributeUst {})?,
+            }),
+            FEE_DISTRIBUTION_REPLY_ID,
+        ));
+    }
+    if !buyback_reserves.is_zero() {
+        response = response.add_submessage(buyback_submsg(deps, env, config, buyback_reserves)?);
+    }
+
+    // Empty total reserve
+    state.total_reserve = Uint256::zero();
+
+    Ok(response.add_attributes(vec![
+        attr("total_reserves", total_reserves.to_string()),
+        attr("buyback_reserves", buyback_reserves.to_string()),
+        attr("insurance_reserves", insurance_reserves.to_string()),
+        attr("jackpot_subsidy_repayment", subsidy_repayment.to_string()),
+        attr("lp_staking_reserves", lp_staking_reserves.to_string()),
+        attr("community_reserves", community_reserves.to_string()),
+        attr(
+            "fee_distributor_reserves",
+            fee_distributor_reserves.to_string(),
+        ),
+    ]))
+}
+
+/// The stable of a failed fee distribution stays in the contract,
+/// so it is added back to the reserve for the next sweep
+pub fn handle_fee_distribution_reply(
+    deps: DepsMut,
+    env: Env,
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    let error = match msg.result {
+        ContractResult::Err(error) => error,
+        ContractResult::Ok(_) => return Ok(Response::default()),
+    };
+
+    let amount = FEE_DISTRIBUTION_IN_FLIGHT.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+    state.total_reserve += amount;
+    STATE.save(deps.storage, &state, env.block.height)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "fee_distribution_failed"),
+        attr("returned_reserves", amount.to_string()),
+        attr("error", error),
+    ]))
+}
+
+fn update_emissions(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    state: &mut State,
+) -> Result<Response, ContractError> {
+    let pool = POOL.load(deps.storage)?;
+
+    // Compute global Glow rewards
+    compute_global_operator_reward(state, &pool, env.block.height);
+    compute_global_sponsor_reward(state, &pool, env.block.height);
+
+    // Rewards of the next epoch are emitted at the decayed rates
+    apply_emission_decay(deps.storage, state)?;
+
+    let current_epoch = read_current_epoch(deps.storage)?;
+    CURRENT_EPOCH.save(deps.storage, &(current_epoch + 1))?;
+
+    // The instant withdrawal cap applies afresh to the new epoch
+    INSTANT_WITHDRAWALS.remove(deps.storage);
+
+    // Move prize_distribution towards the realized win frequencies, within the gov bounds
+    let rebalancing_attributes = apply_prize_rebalancing(deps.storage, state.current_lottery)?;
+
+    let (aust_balance, aust_exchange_rate) = query_aust_balance_and_exchange_rate(
+        &deps.querier,
+        &config.a_terra_contract,
+        &config.anchor_contract,
+        &env.contract.address,
+        env.block.height,
+    )?;
+
+    let awarded_prizes = PRIZE_TIER_STATS
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .awarded_prizes
+        .iter()
+        .fold(Uint256::zero(), |sum, val| sum + *val);
+
+    // Record the statistics of the epoch, dropping the oldest beyond MAX_PROTOCOL_CHECKPOINTS
+    PROTOCOL_CHECKPOINTS.save(
+        deps.storage,
+        U64Key::from(current_epoch),
+        &ProtocolCheckpoint {
+            epoch: current_epoch,
+            time: env.block.time.seconds(),
+            total_user_shares: pool.total_user_shares,
+            aust_balance,
+            prize_buckets: state.prize_buckets.clone(),
+            total_tickets: state.total_tickets,
+            total_sponsor_lottery_deposits: pool.total_sponsor_lottery_deposits,
+            aust_exchange_rate,
+            awarded_prizes: Some(awarded_prizes),
+        },
+    )?;
+    if current_epoch >= MAX_PROTOCOL_CHECKPOINTS {
+        PROTOCOL_CHECKPOINTS.remove(
+            deps.storage,
+            U64Key::from(current_epoch - MAX_PROTOCOL_CHECKPOINTS),
+        );
+    }
+
+    // Record the share price for the twap
+    let share_price = calculate_share_price(&pool, aust_exchange_rate);
+    store_share_price_checkpoint(
+        deps.storage,
+        SharePriceCheckpoint {
+            time: env.block.time.seconds(),
+            share_price,
+        },
+    )?;
+
+    // Update next_epoch based on epoch_interval
+    state.next_epoch = Expiration::AtTime(env.block.time).add(config.epoch_interval)?;
+
+    Ok(Response::new()
+        .add_attribute("share_price", share_price.to_string())
+        .add_attributes(rebalancing_attributes))
+}
+
+/// Removes the records of ibc claims refunded after timing out, which can't be
+/// refunded again. Scanning resumes where the previous sweep stopped
+fn sweep_expired(storage: &mut dyn Storage) -> StdResult<Response> {
+    let start = IBC_CLAIM_SWEEP_CURSOR
+        .may_load(storage)?
+        .map(|id| Bound::exclusive(U64Key::from(id)));
+    let ibc_claims = IBC_CLAIMS
+        .range(storage, start, None, Order::Ascending)
+        .take(MAX_SCANNED_IBC_CLAIMS)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut last_scanned = None;
+    let mut swept_ibc_claims = 0u64;
+    for (key, ibc_claim) in ibc_claims.iter() {
+        let mut id = [0u8; 8];
+        id.copy_from_slice(key);
+        let id = u64::from_be_bytes(id);
+
+        if ibc_claim.refunded {
+            IBC_CLAIMS.remove(storage, U64Key::from(id));
+            swept_ibc_claims += 1;
+        }
+        last_scanned = Some(id);
+    }
+
+    // Start over from the first claim once the end is reached
+    match last_scanned {
+        Some(id) if ibc_claims.len() == MAX_SCANNED_IBC_CLAIMS => {
+            IBC_CLAIM_SWEEP_CURSOR.save(storage, &id)?
+        }
+        _ => IBC_CLAIM_SWEEP_CURSOR.remove(storage),
+    }
+
+    Ok(Response::new().add_attribute("swept_ibc_claims", swept_ibc_claims.to_string()))
+}
+
+pub fn query_epoch_operations(deps: Deps, env: Env) -> StdResult<EpochOperationsResponse> {
+    let state = STATE.load(deps.storage)?;
+
+    let operations = EPOCH_OPERATIONS_ORDER
+        .iter()
+        .map(|operation| {
+            let operation_state = EPOCH_OPERATIONS.load(deps.storage, operation_key(*operation))?;
+            Ok(EpochOperationStatus {
+                operation: *operation,
+                last_executed: operation_state.last_executed,
+                next_execution: operation_state.next_execution,
+                ready: assert_ready(deps.storage, &env, &state, *operation).is_ok(),
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(EpochOperationsResponse { operations })
+}
+
+pub fn query_checkpoints(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<CheckpointsResponse> {
+    let pagination = Pagination::new(start_after, limit);
+
+    let checkpoints = PROTOCOL_CHECKPOINTS
+        .range(
+            deps.storage,
+            pagination.start_bound(),
+            None,
+            Order::Ascending,
+        )
+        .take(pagination.limit)
+        .map(|item| item.map(|(_, checkpoint)| checkpoint))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CheckpointsResponse {
+        checkpoints,
+        limit: pagination.limit as u32,
+    })
+}
+
+pub fn query_yield_stats(deps: Deps) -> StdResult<YieldStatsResponse> {
+    // The last YIELD_STATS_EPOCHS epochs span the last YIELD_STATS_EPOCHS + 1 checkpoints
+    let mut checkpoints = PROTOCOL_CHECKPOINTS
+        .range(deps.storage, None, None, Order::Descending)
+        .take(YIELD_STATS_EPOCHS as usize + 1)
+        .map(|item| item.map(|(_, checkpoint)| checkpoint))
+        .collect::<StdResult<Vec<_>>>()?;
+    checkpoints.reverse();
+
+    // Only the checkpoints that recorded the awarded prizes can be compared
+    checkpoints.retain(|checkpoint| checkpoint.awarded_prizes.is_some());
+
+    let (first, last) = match (checkpoints.first(), checkpoints.last()) {
+        (Some(first), Some(last)) if last.time > first.time => (first, last),
+        _ => {
+            return Ok(YieldStatsResponse {
+                epochs: 0,
+                start_time: 0,
+                end_time: 0,
+                awarded_prizes: Uint256::zero(),
+                average_tvl: Uint256::zero(),
+                prize_yield: Decimal256::zero(),
+                sponsor_apy: Decimal256::zero(),
+            })
+        }
+    };
+
+    let awarded_prizes = last.awarded_prizes.unwrap_or_else(Uint256::zero)
+        - first.awarded_prizes.unwrap_or_else(Uint256::zero);
+    let average_tvl = checkpoints.iter().fold(Uint256::zero(), |sum, checkpoint| {
+        sum + checkpoint.aust_balance * checkpoint.aust_exchange_rate
+    }) / Uint256::from(checkpoints.len() as u64);
+
+    let periods_per_year = Decimal256::from_ratio(SECONDS_PER_YEAR, last.time - first.time);
+    let prize_yield = if average_tvl.is_zero() {
+        Decimal256::zero()
+    } else {
+        Decimal256::from_ratio(awarded_prizes, average_tvl) * periods_per_year
+    };
+    let sponsor_apy = if last.aust_exchange_rate > first.aust_exchange_rate {
+        (last.aust_exchange_rate - first.aust_exchange_rate) / first.aust_exchange_rate
+            * periods_per_year
+    } else {
+        Decimal256::zero()
+    };
+
+    Ok(YieldStatsResponse {
+        epochs: last.epoch - first.epoch,
+        start_time: first.time,
+        end_time: last.time,
+        awarded_prizes,
+        average_tvl,
+        prize_yield,
+        sponsor_apy,
+    })
+}
@@ -1,4 +1,4 @@
-use cosmwasm_bignumber::Uint256;
+use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{StdError, Uint128};
 use cw0::Expiration;
 use thiserror::Error;
@@ -14,6 +14,18 @@ pub enum ContractError {
     #[error("The contract is paused")]
     ContractPaused {},
 
+    #[error("Deposits are paused")]
+    DepositsPaused {},
+
+    #[error("Withdrawals are paused")]
+    WithdrawalsPaused {},
+
+    #[error("The lottery is paused")]
+    LotteryPaused {},
+
+    #[error("Claims are paused")]
+    ClaimsPaused {},
+
     #[error("Invalid boost config. Base multiplier must be less than or equal to max multiplier")]
     InvalidBoostConfig {},
 
@@ -38,6 +50,15 @@ pub enum ContractError {
     #[error("The ticket max holder limit has been reached for the following ticket: {0}")]
     InvalidHolderSequence(String),
 
+    #[error("Quick pick must request at least one ticket")]
+    ZeroQuickPick {},
+
+    #[error("Could not generate enough quick pick tickets below the ticket max holder limit")]
+    QuickPickExhausted {},
+
+    #[error("The savings of {0} don't back any additional ticket")]
+    NoConvertibleYield(String),
+
     #[error("Gift tickets to oneself is not allowed")]
     GiftToSelf {},
 
@@ -51,17 +72,42 @@ pub enum ContractError {
         post_transaction_max_depositor_tickets: u64,
     },
 
+    #[error("Gift batch must contain at least one gift")]
+    EmptyGiftBatch {},
+
+    #[error("Gift batch of {num_tickets} tickets exceeds the limit of {max_tickets} tickets")]
+    GiftBatchTooLarge { num_tickets: u64, max_tickets: u64 },
+
+    #[error("Gift batch requires exactly {expected}{denom} for its tickets but {received}{denom} was sent")]
+    InvalidGiftBatchAmount {
+        expected: Uint256,
+        received: Uint256,
+        denom: String,
+    },
+
     #[error("Sponsorship amount must be greater than zero")]
     ZeroSponsorshipAmount {},
 
-    #[error("Lottery already in progress, wait until the next one begins")]
-    LotteryAlreadyStarted {},
+    #[error("Donation amount must be greater than zero")]
+    ZeroDonationAmount {},
+
+    #[error("Invalid prize bucket index {0}")]
+    InvalidPrizeBucketIndex(u8),
+
+    #[error("Withdrawal queue is empty")]
+    WithdrawalQueueEmpty {},
+
+    #[error("Anchor has no redemption capacity")]
+    NoRedemptionCapacity {},
+
+    #[error("Lottery {lottery_id} already in progress, wait until the next one begins")]
+    LotteryAlreadyStarted { lottery_id: u64 },
 
     #[error("Lottery is not ready to undergo execution yet, please wait until next_lottery_time: {next_lottery_time:?}")]
     LotteryNotReady { next_lottery_time: Expiration },
 
-    #[error("The depositor doesn't have any savings aust so there is nothing to withdraw")]
-    NoDepositorSavingsAustToWithdraw {},
+    #[error("The depositor {0} doesn't have any savings aust so there is nothing to withdraw")]
+    NoDepositorSavingsAustToWithdraw(String),
 
     #[error("The depositor specified to withdraw zero funds which is too small")]
     SpecifiedWithdrawAmountIsZero {},
@@ -78,14 +124,23 @@ pub enum ContractError {
         num_depositor_tickets: u128,
     },
 
+    #[error("The withdrawal gives up {withdrawn_tickets} tickets but {num_specified_tickets} tickets were specified")]
+    WithdrawnTicketsMismatch {
+        withdrawn_tickets: u128,
+        num_specified_tickets: u128,
+    },
+
+    #[error("The depositor doesn't hold the following ticket: {0}")]
+    TicketNotHeld(String),
+
     #[error("There are no enough funds in the contract for that operation. Amount to send: {to_send}. Available balance: {available_balance}")]
     InsufficientFunds {
         to_send: Uint128,
         available_balance: Uint256,
     },
 
-    #[error("The sponsor doesn't have any lottery deposits so there is nothing to withdraw")]
-    NoSponsorLotteryDeposit {},
+    #[error("The sponsor {0} doesn't have any lottery deposits so there is nothing to withdraw")]
+    NoSponsorLotteryDeposit(String),
 
     #[error("The lottery pool ({pool_value}) is smaller than total lottery deposits ({total_lottery_deposits}), no redeem stable allowed")]
     InsufficientPoolFunds {
@@ -93,20 +148,22 @@ pub enum ContractError {
         total_lottery_deposits: Uint256,
     },
 
-    #[error("There are not enough funds to run the lottery")]
-    InsufficientLotteryFunds {},
+    #[error("There are not enough funds to run lottery {lottery_id}")]
+    InsufficientLotteryFunds { lottery_id: u64 },
 
-    #[error("Max number of concurrent unbonding claims for this users has been reached")]
-    MaxUnbondingClaims {},
+    #[error(
+        "Max number of {max_claims} concurrent unbonding claims has been reached, claim the unbonded withdrawals first"
+    )]
+    MaxUnbondingClaims { max_claims: u8 },
 
     #[error("Lottery claim is invalid, as lottery #{0} has not being awarded yet")]
     InvalidClaimLotteryNotAwarded(u64),
 
-    #[error("Lottery claim is invalid, as prize has already been claimed for lottery #")]
+    #[error("Lottery claim is invalid, as prize has already been claimed for lottery #{0}")]
     InvalidClaimPrizeAlreadyClaimed(u64),
 
-    #[error("There not enough claimable funds for the given user")]
-    InsufficientClaimableFunds {},
+    #[error("There are not enough claimable funds for {0}")]
+    InsufficientClaimableFunds(String),
 
     #[error("Invalid prize distribution config")]
     InvalidPrizeDistribution {},
@@ -144,20 +201,26 @@ pub enum ContractError {
     #[error("Invalid execution of the lottery. No sent funds allowed.")]
     InvalidLotteryExecutionFunds {},
 
-    #[error("Invalid execution of the lottery. No tickets in the lotto.")]
-    InvalidLotteryExecutionTickets {},
+    #[error("Invalid execution of lottery {lottery_id}. No tickets in the lotto.")]
+    InvalidLotteryExecutionTickets { lottery_id: u64 },
 
-    #[error("Invalid execution of the lottery prize. The lottery must be executed first.")]
-    InvalidLotteryPrizeExecution {},
+    #[error("Invalid execution of the prize of lottery {lottery_id}. The lottery must be executed first.")]
+    InvalidLotteryPrizeExecution { lottery_id: u64 },
 
-    #[error("Invalid execution of the lottery prize. Block time has not expired yet.")]
-    InvalidLotteryPrizeExecutionExpired {},
+    #[error("Invalid execution of the prize of lottery {lottery_id}. Wait until next_lottery_exec_time: {next_lottery_exec_time:?}")]
+    InvalidLotteryPrizeExecutionExpired {
+        lottery_id: u64,
+        next_lottery_exec_time: Expiration,
+    },
 
     #[error("Invalid execution of the lottery prize. Sent funds not allowed.")]
     InvalidLotteryPrizeExecutionFunds {},
 
-    #[error("Invalid execute epochs execution")]
-    InvalidEpochExecution {},
+    #[error("Invalid execute epochs execution, wait until next_epoch: {next_epoch:?}")]
+    InvalidEpochExecution { next_epoch: Expiration },
+
+    #[error("No epoch operation to execute")]
+    NoEpochOperations {},
 
     #[error("Max tickets per depositor exceeded. Max tickets per depositor: {max_tickets_per_depositor}. Post transaction num depositor tickets: {post_transaction_num_depositor_tickets}")]
     MaxTicketsPerDepositorExceeded {
@@ -165,6 +228,334 @@ pub enum ContractError {
         post_transaction_num_depositor_tickets: u64,
     },
 
+    #[error("There are no pending anchor operations to retry")]
+    NoPendingAnchorOperations {},
+
+    #[error("Invalid reply id: {0}")]
+    InvalidReplyId(u64),
+
+    #[error("Invalid reserve destination, community_bps must not exceed 10000")]
+    InvalidReserveDestination {},
+
+    #[error("Fee distributor contract has not been registered")]
+    FeeDistributorNotRegistered {},
+
+    #[error("LP staking contract has not been registered")]
+    LpStakingNotRegistered {},
+
+    #[error("Invalid LP staking reserve share, must not exceed 1")]
+    InvalidLpStakingReserveShare {},
+
+    #[error(
+        "Deposit amount ({amount}) is below the minimum deposit amount ({min_deposit_amount})"
+    )]
+    DepositBelowMinimum {
+        amount: Uint256,
+        min_deposit_amount: Uint256,
+    },
+
+    #[error("Config update must change at least one parameter")]
+    EmptyConfigUpdate {},
+
+    #[error("Timelock eta must be at least {min_eta}")]
+    InvalidTimelockEta { min_eta: u64 },
+
+    #[error("Scheduled update can't be executed before {eta}")]
+    TimelockNotExpired { eta: u64 },
+
+    #[error("Scheduled update can't be cancelled after {eta}")]
+    TimelockExpired { eta: u64 },
+
+    #[error("Skim can't be executed before {next_skim_time}")]
+    SkimNotReady { next_skim_time: u64 },
+
+    #[error("There is no untracked aust to skim")]
+    NothingToSkim {},
+
+    #[error("Invalid loyalty config, bonus per round must not exceed 1")]
+    InvalidLoyaltyConfig {},
+
+    #[error(
+        "Invalid sponsor lock config, bonus per week and early exit penalty must not exceed 1"
+    )]
+    InvalidSponsorLockConfig {},
+
+    #[error("Ticket eligibility delay must be shorter than the lottery interval")]
+    InvalidTicketEligibilityDelay {},
+
+    #[error("Invalid lock duration, must be between 1 and {max_lock_weeks} weeks")]
+    InvalidLockDuration { max_lock_weeks: u64 },
+
+    #[error("Locked sponsorships can't be awarded instantly")]
+    LockedAwardSponsorship {},
+
+    #[error("Sponsorship is already locked until {lock_end_time}")]
+    SponsorLockShortened { lock_end_time: u64 },
+
+    #[error("Charity {0} is not registered")]
+    CharityNotRegistered(String),
+
+    #[error("Donation share must be greater than zero and at most one")]
+    InvalidDonationShare {},
+
+    #[error("{agent} is not a claim agent of {winner}")]
+    ClaimAgentNotAuthorized { agent: String, winner: String },
+
+    #[error("Can't authorize yourself as a claim agent")]
+    InvalidClaimAgent {},
+
+    #[error("Invalid ibc claim config, timeout must be positive")]
+    InvalidIbcClaimConfig {},
+
+    #[error("Prizes can't be claimed over ibc channel {0}")]
+    IbcChannelNotAllowed(String),
+
+    #[error("Prizes claimed over ibc can't be redeposited")]
+    IbcClaimWithRedeposit {},
+
+    #[error("Invalid payout hash, expected a hex encoded keccak256 hash")]
+    InvalidPayoutHash {},
+
+    #[error("No payout hash registered by {0}")]
+    PayoutHashNotRegistered(String),
+
+    #[error("Revealed payout address and salt don't match the registered payout hash")]
+    InvalidPayoutReveal {},
+
+    #[error("Prizes paid to a revealed payout address can't be redeposited or claimed over ibc")]
+    PayoutRevealWithRedeposit {},
+
+    #[error("Payout asset conflicts with the redeposit or destination of the claim")]
+    InvalidPayoutAsset {},
+
+    #[error("Aust payout of {amount} exceeds the {available} aust that isn't owed to anyone")]
+    InsufficientAustPayout { amount: Uint256, available: Uint256 },
+
+    #[error("Ibc claim can't be refunded before its timeout at {timeout}")]
+    IbcClaimNotTimedOut { timeout: u64 },
+
+    #[error("Ibc claim {0} was already refunded")]
+    IbcClaimAlreadyRefunded(u64),
+
+    #[error("Sender is not a member of syndicate {0}")]
+    NotSyndicateMember(u64),
+
+    #[error("Syndicate prize of lottery {0} was already claimed by the sender")]
+    SyndicatePrizeAlreadyClaimed(u64),
+
+    #[cfg(feature = "debug-invariants")]
+    #[error("Invariant violated: {0}")]
+    InvariantViolated(String),
+
+    #[error("Guardians can only pause")]
+    GuardianActionNotAllowed {},
+
+    #[error("Guardian {0} already exists")]
+    GuardianAlreadyExists(String),
+
+    #[error("Guardian {0} not found")]
+    GuardianNotFound(String),
+
+    #[error("Address {0} is blacklisted")]
+    Blacklisted(String),
+
+    #[error("Address {0} is not blacklisted")]
+    NotBlacklisted(String),
+
+    #[error("Claims can't be redirected before the end of the dispute window at {0}")]
+    DisputeWindowNotOver(u64),
+
+    #[error("Glow top up amount must be greater than zero")]
+    InvalidGlowTopUpAmount {},
+
+    #[error("Glow token is unknown while the distributor is not registered")]
+    GlowTokenUnknown {},
+
+    #[error("Cannot migrate to version {0}")]
+    InvalidMigrationTarget(u64),
+
+    #[error("Migration step {0} is not pending")]
+    MigrationStepNotPending(u64),
+
+    #[error("Cannot unpause contract with pending migration steps")]
+    MigrationPending {},
+
+    #[error("A public key is required to open an escrow")]
+    EscrowPubkeyMissing {},
+
+    #[error("Escrow public key must be a compressed or uncompressed secp256k1 key")]
+    InvalidEscrowPubkey {},
+
+    #[error("Escrow of {0} not found")]
+    EscrowNotFound(String),
+
+    #[error("Escrow withdrawal amount must be greater than zero")]
+    ZeroEscrowWithdrawal {},
+
+    #[error("Amount {amount} exceeds the escrow balance of {escrow_balance}")]
+    InsufficientEscrowBalance {
+        amount: Uint256,
+        escrow_balance: Uint256,
+    },
+
+    #[error("Permit expired at {0}")]
+    PermitExpired(u64),
+
+    #[error("Invalid permit nonce {nonce}, expected {expected}")]
+    InvalidPermitNonce { nonce: u64, expected: u64 },
+
+    #[error("Invalid permit signature")]
+    InvalidPermitSignature {},
+
+    #[error("Buyback reserve share and max spread can't be greater than 1")]
+    InvalidBuybackConfig {},
+
+    #[error("Emission decay factor must be at most one and floor at most the initial rate")]
+    InvalidEmissionDecay {},
+
+    #[error("Prize template names must be between 1 and 32 characters")]
+    InvalidPrizeTemplateName {},
+
+    #[error("Prize template {0} already exists")]
+    PrizeTemplateAlreadyExists(String),
+
+    #[error("Prize template {0} not found")]
+    PrizeTemplateNotFound(String),
+
+    #[error("Receipt token has already been created")]
+    ReceiptTokenAlreadyCreated {},
+
+    #[error("Receipt token has not been created")]
+    ReceiptTokenNotCreated {},
+
+    #[error("Receipt token instantiation reply is missing the contract address")]
+    InvalidReceiptTokenReply {},
+
+    #[error("Cannot redeem zero receipt tokens")]
+    ZeroReceiptRedemption {},
+
+    #[error("Insurance reserve share can't be greater than 1")]
+    InvalidInsuranceShare {},
+
+    #[error("Exchange rate deviation per hour must be between 0 and 1")]
+    InvalidRateDeviation {},
+
+    #[error("Invalid prize re-balancing bounds")]
+    InvalidPrizeRebalancing {},
+
+    #[error("Prize caps must be positive and given for each prize bucket")]
+    InvalidPrizeCaps {},
+
+    #[error("Auto claim bounty rate can't exceed {max}")]
+    InvalidAutoClaimBounty { max: Decimal256 },
+
+    #[error("Keeper allowlist can't be empty")]
+    EmptyKeeperAllowlist {},
+
+    #[error(
+        "Only allowlisted keepers can execute the lottery for another {seconds_remaining} seconds"
+    )]
+    KeeperExclusivityWindow { seconds_remaining: u64 },
+
+    #[error("Badge tiers must have increasing non zero thresholds, at most {max_tiers} of them")]
+    InvalidBadgeConfig { max_tiers: u32 },
+
+    #[error("No badge to mint")]
+    NoBadgeToMint {},
+
+    #[error("Hook {0} is already registered")]
+    HookAlreadyRegistered(String),
+
+    #[error("Hook {0} is not registered")]
+    HookNotRegistered(String),
+
+    #[error("At most {max_hooks} hooks can be registered")]
+    TooManyHooks { max_hooks: u32 },
+
+    #[error("Instant withdrawals are capped, {available} left until {retry_after}. Use a regular withdrawal instead")]
+    InstantWithdrawalCapExceeded {
+        available: Uint256,
+        retry_after: Expiration,
+    },
+
+    #[error("Instant withdrawal pool ratio must be between 0 and 1")]
+    InvalidInstantWithdrawalCap {},
+
+    #[error("Only instant withdrawals can be paid to a recipient, claims are paid by Claim")]
+    RecipientRequiresInstantWithdrawal {},
+
+    #[error("Cannot move {amount} to the insurance fund, the reserve holds {total_reserve}")]
+    InsufficientReserve {
+        amount: Uint256,
+        total_reserve: Uint256,
+    },
+
+    #[error("Cannot withdraw {amount} from the insurance fund, it holds {balance}")]
+    InsufficientInsuranceBalance { amount: Uint256, balance: Uint256 },
+
+    #[error("Ticket length must be between {min} and {max}")]
+    InvalidTicketLength { min: usize, max: usize },
+
+    #[error("min_matches_for_prize {0} must be at most the ticket length, with a zero prize_distribution below it")]
+    InvalidMinMatchesForPrize(u8),
+
+    #[error("Glow prize buckets must have one amount per prize bucket")]
+    InvalidGlowPrizeBuckets {},
+
+    #[error("Sponsor emission weight must be between {min} and {max}")]
+    InvalidSponsorEmissionWeight { min: u64, max: u64 },
+
+    #[error("Gift card hashes must be hex encoded 32 byte hashes")]
+    InvalidGiftCardHash {},
+
+    #[error("A gift card with this code hash already exists")]
+    GiftCardAlreadyExists {},
+
+    #[error("Gift card not found")]
+    GiftCardNotFound {},
+
+    #[error("Gift card expired at {0}")]
+    GiftCardExpired(u64),
+
+    #[error("Gift card can't be refunded before it expires at {0}")]
+    GiftCardNotExpired(u64),
+
+    #[error("Gift card amount must be the price of its tickets. Expected {expected}, received {received}")]
+    InvalidGiftCardAmount {
+        expected: Uint256,
+        received: Uint256,
+    },
+
+    #[error("Gift card pays for exactly {expected} tickets")]
+    InvalidGiftCardTickets { expected: u64 },
+
+    #[error("Commit to the gift card redemption in an earlier block before revealing the code")]
+    GiftCardNotCommitted {},
+
+    #[error("Protocol owned liquidity withdrawal amount must be greater than zero")]
+    ZeroPolWithdrawal {},
+
+    #[error("Amount {amount} exceeds the protocol owned liquidity balance of {balance}")]
+    InsufficientPolBalance { amount: Uint256, balance: Uint256 },
+
+    #[error("Campaign ids must be between 1 and 32 alphanumeric, '-' or '_' characters")]
+    InvalidCampaignId {},
+
+    #[error("Deposits can only be attributed to a campaign of the depositor's operator")]
+    CampaignWithoutOperator {},
+
+    #[error("Savings amount must be greater than zero")]
+    ZeroSavingsAmount {},
+
+    #[error("Amount {amount} exceeds the savings balance of {balance}")]
+    InsufficientSavings { amount: Uint256, balance: Uint256 },
+
+    #[error("A prize claim is already being paid, wait for it to complete")]
+    ClaimInProgress {},
+
+    #[error("The GLOW payout of claim receipt #{0} has not failed")]
+    ClaimReceiptNotFailed(u64),
+
     #[error("Unauthorized")]
     Unauthorized {},
 }
@@ -0,0 +1,127 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{Addr, Event, Uint128};
+
+// Typed events for indexers, emitted as wasm-<type> events next to the legacy
+// response attributes, which are kept for one more release.
+// Amounts are in the stable denom unless the attribute says otherwise.
+
+/// Shares and tickets minted to recipient for a deposit paid by depositor
+pub fn deposit_event(
+    depositor: &Addr,
+    recipient: &Addr,
+    amount: Uint256,
+    net_amount: Uint256,
+    tickets: u64,
+    aust_minted: Uint256,
+) -> Event {
+    Event::new("deposit")
+        .add_attribute("depositor", depositor.to_string())
+        .add_attribute("recipient", recipient.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("net_amount", net_amount.to_string())
+        .add_attribute("tickets", tickets.to_string())
+        .add_attribute("aust_minted", aust_minted.to_string())
+}
+
+pub fn withdraw_event(
+    depositor: &Addr,
+    tickets: u128,
+    aust_amount: Uint256,
+    amount: Uint256,
+    instant: bool,
+    instant_withdrawal_fee: Uint256,
+    queued_withdrawal_id: Option<u64>,
+) -> Event {
+    let event = Event::new("withdraw")
+        .add_attribute("depositor", depositor.to_string())
+        .add_attribute("tickets", tickets.to_string())
+        .add_attribute("aust_amount", aust_amount.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("instant", instant.to_string())
+        .add_attribute("instant_withdrawal_fee", instant_withdrawal_fee.to_string());
+
+    match queued_withdrawal_id {
+        Some(id) => event.add_attribute("queued_withdrawal_id", id.to_string()),
+        None => event,
+    }
+}
+
+/// Yield added to the prize buckets when lottery_id is executed
+pub fn lottery_executed_event(
+    lottery_id: u64,
+    redeemed_aust: Uint256,
+    prize_amount: Uint256,
+    missed_lotteries: u64,
+) -> Event {
+    Event::new("lottery_executed")
+        .add_attribute("lottery_id", lottery_id.to_string())
+        .add_attribute("redeemed_aust", redeemed_aust.to_string())
+        .add_attribute("prize_amount", prize_amount.to_string())
+        .add_attribute("missed_lotteries", missed_lotteries.to_string())
+}
+
+/// Prize awarded to the winners of a tier once lottery_id is finalized
+pub fn prize_awarded_event(
+    lottery_id: u64,
+    bucket_index: usize,
+    num_winners: u32,
+    prize_amount: Uint256,
+    reserve_fee: Uint256,
+    glow_prize_amount: Uint256,
+) -> Event {
+    Event::new("prize_awarded")
+        .add_attribute("lottery_id", lottery_id.to_string())
+        .add_attribute("bucket_index", bucket_index.to_string())
+        .add_attribute("num_winners", num_winners.to_string())
+        .add_attribute("prize_amount", prize_amount.to_string())
+        .add_attribute("reserve_fee", reserve_fee.to_string())
+        .add_attribute("glow_prize_amount", glow_prize_amount.to_string())
+}
+
+/// Unbonded withdrawals or lottery prizes claimed by claimer.
+/// lottery_ids is empty for unbonded withdrawals
+pub fn claim_event(
+    claimer: &Addr,
+    lottery_ids: &[u64],
+    amount: Uint128,
+    glow_amount: Uint128,
+) -> Event {
+    let kind = if lottery_ids.is_empty() {
+        "unbonded"
+    } else {
+        "lottery"
+    };
+    let lottery_ids: Vec<String> = lottery_ids.iter().map(|id| id.to_string()).collect();
+
+    Event::new("claim")
+        .add_attribute("claimer", claimer.to_string())
+        .add_attribute("kind", kind)
+        .add_attribute("lottery_ids", lottery_ids.join(","))
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("glow_amount", glow_amount.to_string())
+}
+
+/// Lottery prizes of winner claimed by one of their claim agents
+pub fn agent_claim_event(agent: &Addr, winner: &Addr, lottery_ids: &[u64]) -> Event {
+    let lottery_ids: Vec<String> = lottery_ids.iter().map(|id| id.to_string()).collect();
+
+    Event::new("agent_claim")
+        .add_attribute("agent", agent.to_string())
+        .add_attribute("winner", winner.to_string())
+        .add_attribute("lottery_ids", lottery_ids.join(","))
+}
+
+pub fn sponsor_event(sponsor: &Addr, amount: Uint256, award: bool) -> Event {
+    Event::new("sponsor")
+        .add_attribute("sponsor", sponsor.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("award", award.to_string())
+}
+
+/// Shortfall of a withdrawal or claim, of which covered was drawn from the insurance fund
+pub fn insurance_shortfall_event(cause: &str, shortfall: Uint256, covered: Uint256) -> Event {
+    Event::new("insurance_shortfall")
+        .add_attribute("cause", cause)
+        .add_attribute("shortfall", shortfall.to_string())
+        .add_attribute("covered", covered.to_string())
+}
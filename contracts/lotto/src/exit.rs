@@ -0,0 +1,126 @@
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, StdResult, Storage};
+use cw_storage_plus::U64Key;
+
+use crate::contract::{
+    execute_claim_lottery, execute_claim_rewards, execute_claim_unbonded, execute_withdraw,
+};
+use crate::error::ContractError;
+use crate::state::{read_depositor_info, read_lottery_info, EXIT_CURSORS, PRIZES, STATE};
+
+// Maximum number of lotteries scanned for unclaimed prizes by a single Exit
+const MAX_EXIT_SCANNED_LOTTERIES: u64 = 100;
+
+fn merge_response(response: Response, other: Response) -> Response {
+    response
+        .add_submessages(other.messages)
+        .add_events(other.events)
+        .add_attributes(other.attributes)
+}
+
+/// Unclaimed prizes of the sender in the lotteries scanned from the exit cursor,
+/// and the lottery id the scan stopped at. Lotteries before the current one are
+/// all awarded, so later exits only scan the lotteries run since
+fn scan_unclaimed_prizes(
+    storage: &dyn Storage,
+    info: &MessageInfo,
+) -> StdResult<(Vec<u64>, u64, bool)> {
+    let state = STATE.load(storage)?;
+    let start = EXIT_CURSORS
+        .may_load(storage, &info.sender)?
+        .unwrap_or_default();
+    let end = std::cmp::min(start + MAX_EXIT_SCANNED_LOTTERIES, state.current_lottery);
+
+    let mut lottery_ids = vec![];
+    for lottery_id in start..end {
+        let prize = PRIZES.may_load(storage, (U64Key::from(lottery_id), &info.sender))?;
+        if let Some(prize) = prize {
+            if !prize.claimed && read_lottery_info(storage, lottery_id).awarded {
+                lottery_ids.push(lottery_id);
+            }
+        }
+    }
+
+    Ok((lottery_ids, end, end == state.current_lottery))
+}
+
+/// Claims the unclaimed prizes of the sender, then withdraws all their shares,
+/// claims their matured unbonding claims and their GLOW rewards. Lotteries are
+/// scanned for prizes in pages: while exit_complete is false, Exit has to be
+/// called again, and only the last call withdraws
+pub fn execute_exit(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    instant: bool,
+) -> Result<Response, ContractError> {
+    let mut response = Response::new();
+
+    let (lottery_ids, next_lottery_id, scan_complete) = scan_unclaimed_prizes(deps.storage, &info)?;
+    EXIT_CURSORS.save(deps.storage, &info.sender, &next_lottery_id)?;
+
+    if !lottery_ids.is_empty() {
+        response = merge_response(
+            response,
+            execute_claim_lottery(
+                deps.branch(),
+                env.clone(),
+                info.clone(),
+                lottery_ids,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )?,
+        );
+    }
+
+    if !scan_complete {
+        return Ok(response.add_attributes(vec![
+            attr("action", "exit"),
+            attr("depositor", info.sender.to_string()),
+            attr("exit_complete", "false"),
+            attr("next_lottery_id", next_lottery_id.to_string()),
+        ]));
+    }
+
+    let depositor = read_depositor_info(deps.storage, &info.sender);
+    if !depositor.shares.is_zero() {
+        response = merge_response(
+            response,
+            execute_withdraw(
+                deps.branch(),
+                env.clone(),
+                info.clone(),
+                None,
+                Some(instant),
+                None,
+                None,
+            )?,
+        );
+    }
+
+    // Claims released by now, the one of a regular withdrawal is still unbonding
+    let depositor = read_depositor_info(deps.storage, &info.sender);
+    if depositor
+        .unbonding_info
+        .iter()
+        .any(|claim| claim.release_at.is_expired(&env.block))
+    {
+        response = merge_response(
+            response,
+            execute_claim_unbonded(deps.branch(), env.clone(), info.clone(), None)?,
+        );
+    }
+
+    response = merge_response(
+        response,
+        execute_claim_rewards(deps.branch(), env, info.clone())?,
+    );
+
+    Ok(response.add_attributes(vec![
+        attr("action", "exit"),
+        attr("depositor", info.sender.to_string()),
+        attr("exit_complete", "true"),
+    ]))
+}
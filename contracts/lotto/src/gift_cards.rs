@@ -0,0 +1,292 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{
+    attr, coin, BankMsg, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage,
+};
+use glow_protocol::lotto::{GiftCardResponse, GlowHookMsg};
+use glow_protocol::querier::deduct_tax;
+use sha3::{Digest, Keccak256};
+
+use crate::anchor::deposit_stable_msg;
+use crate::contract::process_deposit;
+use crate::error::ContractError;
+use crate::events::deposit_event;
+use crate::helpers::base64_encoded_tickets_to_vec_string_tickets;
+use crate::hooks::hook_submsgs;
+use crate::state::{
+    read_lottery_info, GiftCard, CONFIG, GIFT_CARDS, GIFT_CARD_COMMITMENTS, STATE, TOTAL_GIFT_CARDS,
+};
+
+pub fn read_total_gift_cards(storage: &dyn Storage) -> StdResult<Uint256> {
+    Ok(TOTAL_GIFT_CARDS.may_load(storage)?.unwrap_or_default())
+}
+
+/// Hex encoded keccak256 hash of a gift card code, the key of the card
+pub fn gift_card_code_hash(code: &str) -> String {
+    hex::encode(Keccak256::digest(code.as_bytes()))
+}
+
+/// Hex encoded keccak256 hash of "{code}:{redeemer}", committed to before
+/// redeeming so a revealed code can't be redeemed by someone else
+pub fn gift_card_commitment(code: &str, redeemer: &str) -> String {
+    hex::encode(Keccak256::digest(
+        format!("{}:{}", code, redeemer).as_bytes(),
+    ))
+}
+
+fn decode_hash(hash: &str) -> Result<Vec<u8>, ContractError> {
+    match hex::decode(hash) {
+        Ok(hash) if hash.len() == 32 => Ok(hash),
+        _ => Err(ContractError::InvalidGiftCardHash {}),
+    }
+}
+
+/// Funds a gift card worth num_tickets tickets, redeemable by whoever knows the
+/// code hashing to code_hash until expires. The sent stable must pay for the tickets
+pub fn execute_fund_gift_card(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    code_hash: String,
+    num_tickets: u64,
+    expires: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let key = decode_hash(&code_hash)?;
+    if GIFT_CARDS.has(deps.storage, &key) {
+        return Err(ContractError::GiftCardAlreadyExists {});
+    }
+
+    if expires <= env.block.time.seconds() {
+        return Err(ContractError::GiftCardExpired(expires));
+    }
+
+    if num_tickets == 0 {
+        return Err(ContractError::ZeroGiftAmount {});
+    }
+
+    let amount = info
+        .funds
+        .iter()
+        .find(|c| c.denom == config.stable_denom)
+        .map(|c| Uint256::from(c.amount))
+        .unwrap_or_else(Uint256::zero);
+
+    let expected_amount = config.ticket_price * Uint256::from(num_tickets);
+    if amount != expected_amount {
+        return Err(ContractError::InvalidGiftCardAmount {
+            expected: expected_amount,
+            received: amount,
+        });
+    }
+    if amount < config.min_deposit_amount {
+        return Err(ContractError::DepositBelowMinimum {
+            amount,
+            min_deposit_amount: config.min_deposit_amount,
+        });
+    }
+
+    GIFT_CARDS.save(
+        deps.storage,
+        &key,
+        &GiftCard {
+            buyer: info.sender.clone(),
+            amount,
+            num_tickets,
+            expires,
+        },
+    )?;
+    let total_gift_cards = read_total_gift_cards(deps.storage)? + amount;
+    TOTAL_GIFT_CARDS.save(deps.storage, &total_gift_cards)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "fund_gift_card"),
+        attr("buyer", info.sender.to_string()),
+        attr("code_hash", hex::encode(key)),
+        attr("amount", amount.to_string()),
+        attr("num_tickets", num_tickets.to_string()),
+        attr("expires", expires.to_string()),
+    ]))
+}
+
+/// First step of a redemption: records the commitment of the sender to a code,
+/// see gift_card_commitment. The code can be revealed from the next block on
+pub fn execute_commit_gift_card(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    commitment: String,
+) -> Result<Response, ContractError> {
+    // The first commitment is kept, so replaying it can't push the reveal back
+    let key = decode_hash(&commitment)?;
+    if !GIFT_CARD_COMMITMENTS.has(deps.storage, &key) {
+        GIFT_CARD_COMMITMENTS.save(deps.storage, &key, &env.block.height)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "commit_gift_card"),
+        attr("redeemer", info.sender.to_string()),
+        attr("commitment", hex::encode(key)),
+    ]))
+}
+
+/// Reveals the code of a gift card committed to in an earlier block, depositing
+/// the card in the name of the sender with the given tickets
+pub fn execute_redeem_gift_card(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    code: String,
+    encoded_tickets: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+
+    // Validate that the lottery has not already started
+    let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
+    if current_lottery.rand_round != 0 {
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
+    }
+
+    // Only a commitment made before the code was revealed is accepted
+    let commitment = decode_hash(&gift_card_commitment(&code, info.sender.as_str()))?;
+    match GIFT_CARD_COMMITMENTS.may_load(deps.storage, &commitment)? {
+        Some(height) if height < env.block.height => {}
+        _ => return Err(ContractError::GiftCardNotCommitted {}),
+    }
+
+    let key = decode_hash(&gift_card_code_hash(&code))?;
+    let gift_card = GIFT_CARDS
+        .may_load(deps.storage, &key)?
+        .ok_or(ContractError::GiftCardNotFound {})?;
+
+    if gift_card.expires <= env.block.time.seconds() {
+        return Err(ContractError::GiftCardExpired(gift_card.expires));
+    }
+
+    let num_tickets = base64_encoded_tickets_to_vec_string_tickets(
+        encoded_tickets.clone(),
+        config.ticket_length as usize,
+    )?
+    .len() as u64;
+    if num_tickets != gift_card.num_tickets {
+        return Err(ContractError::InvalidGiftCardTickets {
+            expected: gift_card.num_tickets,
+        });
+    }
+
+    GIFT_CARDS.remove(deps.storage, &key);
+    GIFT_CARD_COMMITMENTS.remove(deps.storage, &commitment);
+    let total_gift_cards = read_total_gift_cards(deps.storage)? - gift_card.amount;
+    TOTAL_GIFT_CARDS.save(deps.storage, &total_gift_cards)?;
+
+    // Deduct tx taxes when calculating the net deposited amount in anchor
+    let post_tax_deposit_amount = Uint256::from(
+        deduct_tax(
+            deps.as_ref(),
+            coin(gift_card.amount.into(), config.stable_denom.clone()),
+        )?
+        .amount,
+    );
+
+    let (number_of_new_tickets, minted_aust) = process_deposit(
+        deps.branch(),
+        &env,
+        &config,
+        &info.sender,
+        post_tax_deposit_amount,
+        encoded_tickets,
+        None,
+    )?;
+
+    Ok(Response::new()
+        .add_message(deposit_stable_msg(&config, post_tax_deposit_amount)?)
+        .add_submessages(hook_submsgs(
+            deps.storage,
+            GlowHookMsg::OnDeposit {
+                depositor: gift_card.buyer.to_string(),
+                recipient: info.sender.to_string(),
+                amount: gift_card.amount,
+                tickets: number_of_new_tickets,
+            },
+        )?)
+        .add_event(deposit_event(
+            &gift_card.buyer,
+            &info.sender,
+            gift_card.amount,
+            post_tax_deposit_amount,
+            number_of_new_tickets,
+            minted_aust,
+        ))
+        .add_attributes(vec![
+            attr("action", "redeem_gift_card"),
+            attr("buyer", gift_card.buyer.to_string()),
+            attr("redeemer", info.sender.to_string()),
+            attr("code_hash", hex::encode(key)),
+            attr("deposit_amount", gift_card.amount.to_string()),
+            attr("tickets", number_of_new_tickets.to_string()),
+            attr("aust_minted", minted_aust.to_string()),
+        ]))
+}
+
+/// Sends the stable of an expired, unredeemed gift card back to its buyer
+pub fn execute_refund_gift_card(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    code_hash: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let key = decode_hash(&code_hash)?;
+    let gift_card = GIFT_CARDS
+        .may_load(deps.storage, &key)?
+        .ok_or(ContractError::GiftCardNotFound {})?;
+
+    if info.sender != gift_card.buyer {
+        return Err(ContractError::Unauthorized {});
+    }
+    if gift_card.expires > env.block.time.seconds() {
+        return Err(ContractError::GiftCardNotExpired(gift_card.expires));
+    }
+
+    GIFT_CARDS.remove(deps.storage, &key);
+    let total_gift_cards = read_total_gift_cards(deps.storage)? - gift_card.amount;
+    TOTAL_GIFT_CARDS.save(deps.storage, &total_gift_cards)?;
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Bank(BankMsg::Send {
+            to_address: gift_card.buyer.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                coin(gift_card.amount.into(), config.stable_denom),
+            )?],
+        }))
+        .add_attributes(vec![
+            attr("action", "refund_gift_card"),
+            attr("buyer", gift_card.buyer.to_string()),
+            attr("code_hash", hex::encode(key)),
+            attr("amount", gift_card.amount.to_string()),
+        ]))
+}
+
+pub fn query_gift_card(deps: Deps, code_hash: String) -> StdResult<GiftCardResponse> {
+    let key = hex::decode(&code_hash).unwrap_or_default();
+    let gift_card = GIFT_CARDS.may_load(deps.storage, &key)?;
+
+    Ok(GiftCardResponse {
+        code_hash,
+        buyer: gift_card.as_ref().map(|card| card.buyer.to_string()),
+        amount: gift_card
+            .as_ref()
+            .map(|card| card.amount)
+            .unwrap_or_default(),
+        num_tickets: gift_card
+            .as_ref()
+            .map(|card| card.num_tickets)
+            .unwrap_or_default(),
+        expires: gift_card.map(|card| card.expires).unwrap_or_default(),
+    })
+}
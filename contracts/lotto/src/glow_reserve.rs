@@ -0,0 +1,126 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{
+    attr, to_binary, Addr, CosmosMsg, Deps, DepsMut, Env, MessageInfo, QuerierWrapper, Response,
+    StdResult, Storage, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use glow_protocol::distributor::{
+    ConfigResponse as DistributorConfigResponse, ExecuteMsg as FaucetExecuteMsg,
+    QueryMsg as DistributorQueryMsg,
+};
+use glow_protocol::lotto::GlowPrizeReserveResponse;
+
+use crate::error::ContractError;
+use crate::querier::query_distributor_glow_balance;
+use crate::state::{Config, GlowPrizeReserve, CONFIG, GLOW_PRIZE_RESERVE};
+
+/// Pulls amount of GLOW from the owner into the local prize reserve, used to pay
+/// glow prizes when the distributor can't. Requires an allowance from the owner.
+pub fn execute_top_up_glow(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if amount.is_zero() {
+        return Err(ContractError::InvalidGlowTopUpAmount {});
+    }
+
+    let mut reserve = match GLOW_PRIZE_RESERVE.may_load(deps.storage)? {
+        Some(reserve) => reserve,
+        None => {
+            // The glow token is taken from the distributor on the first top up
+            if config.distributor_contract == Addr::unchecked("") {
+                return Err(ContractError::GlowTokenUnknown {});
+            }
+            let distributor_config: DistributorConfigResponse = deps.querier.query_wasm_smart(
+                config.distributor_contract.to_string(),
+                &DistributorQueryMsg::Config {},
+            )?;
+            GlowPrizeReserve {
+                glow_token: deps.api.addr_validate(&distributor_config.glow_token)?,
+                balance: Uint128::zero(),
+            }
+        }
+    };
+
+    reserve.balance += amount;
+    GLOW_PRIZE_RESERVE.save(deps.storage, &reserve)?;
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: reserve.glow_token.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: info.sender.to_string(),
+                recipient: env.contract.address.to_string(),
+                amount,
+            })?,
+        }))
+        .add_attributes(vec![
+            attr("action", "top_up_glow"),
+            attr("amount", amount.to_string()),
+            attr("reserve_balance", reserve.balance.to_string()),
+        ]))
+}
+
+/// Message paying a glow prize to recipient. The prize is spent from the distributor
+/// unless the distributor is unregistered or can't cover it, in which case it is paid
+/// from the local prize reserve if the reserve holds enough.
+pub fn glow_prize_msg(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    config: &Config,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    let distributor_funded = config.distributor_contract != Addr::unchecked("")
+        && query_distributor_glow_balance(querier, &config.distributor_contract)
+            .map(|balance| balance >= Uint256::from(amount))
+            .unwrap_or(false);
+
+    if !distributor_funded {
+        if let Some(mut reserve) = GLOW_PRIZE_RESERVE.may_load(storage)? {
+            if reserve.balance >= amount {
+                reserve.balance = reserve.balance.checked_sub(amount)?;
+                GLOW_PRIZE_RESERVE.save(storage, &reserve)?;
+
+                return Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: reserve.glow_token.to_string(),
+                    funds: vec![],
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: recipient.to_string(),
+                        amount,
+                    })?,
+                }));
+            }
+        }
+    }
+
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: config.distributor_contract.to_string(),
+        funds: vec![],
+        msg: to_binary(&FaucetExecuteMsg::Spend {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+    }))
+}
+
+pub fn query_glow_prize_reserve(deps: Deps) -> StdResult<GlowPrizeReserveResponse> {
+    let reserve = GLOW_PRIZE_RESERVE.may_load(deps.storage)?;
+
+    Ok(GlowPrizeReserveResponse {
+        glow_token: reserve
+            .as_ref()
+            .map(|reserve| reserve.glow_token.to_string()),
+        balance: reserve.map(|reserve| reserve.balance).unwrap_or_default(),
+    })
+}
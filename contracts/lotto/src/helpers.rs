@@ -1,21 +1,33 @@
-use std::convert::TryInto;
 use std::ops::Add;
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::{Addr, BlockInfo, DepsMut, Env, QuerierWrapper, StdError, StdResult, Uint128};
-use glow_protocol::lotto::{BoostConfig, RewardEmissionsIndex, NUM_PRIZE_BUCKETS, TICKET_LENGTH};
+use cosmwasm_std::{
+    Addr, BlockInfo, DepsMut, Env, QuerierWrapper, StdError, StdResult, Storage, Uint128,
+};
+use cw0::{Duration, Expiration};
+use glow_protocol::lotto::{
+    BoostConfig, Claim, LoyaltyConfig, RewardEmissionsIndex, TimeWeightWindow,
+};
 use sha3::{Digest, Keccak256};
 
+use crate::contract::MAX_CLAIMS;
 use crate::error::ContractError;
+use crate::prize_caps::winner_prize_cap;
 use crate::querier::{
-    query_address_voting_balance_at_timestamp, query_total_voting_balance_at_timestamp,
+    query_address_voting_power_at_timestamp, query_total_voting_balance_at_timestamp,
 };
 
 use crate::state::{
-    read_operator_info, store_operator_info, Config, DepositorInfo, DepositorStatsInfo,
-    LotteryInfo, OperatorInfo, Pool, PrizeInfo, SponsorInfo, State, TICKETS,
+    read_operator_info, store_depositor_tickets, store_operator_info,
+    store_operator_share_checkpoint, store_pending_tickets, store_ticket_acquisition,
+    BoostSnapshot, Config, DepositorInfo, DepositorStatsInfo, LotteryInfo, OperatorInfo,
+    OperatorShareCheckpoint, Pool, PrizeInfo, SharePriceCheckpoint, SponsorInfo, State, STATE,
+    TICKETS,
 };
 
+// Number of sequences tried per requested quick pick ticket before giving up
+const MAX_QUICK_PICK_ATTEMPTS: u64 = 10;
+
 /// Compute distributed reward and update global reward index for operators
 pub fn compute_global_operator_reward(state: &mut State, pool: &Pool, block_height: u64) {
     compute_global_reward(
@@ -29,7 +41,9 @@ pub fn compute_global_operator_reward(state: &mut State, pool: &Pool, block_heig
 pub fn compute_global_sponsor_reward(state: &mut State, pool: &Pool, block_height: u64) {
     compute_global_reward(
         &mut state.sponsor_reward_emission_index,
-        pool.total_sponsor_lottery_deposits,
+        pool.total_sponsor_lottery_deposits
+            + pool.total_sponsor_lock_bonus
+            + pool.total_sponsor_weight_bonus,
         block_height,
     );
 }
@@ -66,8 +80,10 @@ pub fn compute_operator_reward(state: &State, operator: &mut OperatorInfo) {
 
 /// Compute reward amount a sponsor received
 pub fn compute_sponsor_reward(state: &State, sponsor: &mut SponsorInfo) {
-    sponsor.pending_rewards += Decimal256::from_uint256(sponsor.lottery_deposit)
-        * (state.sponsor_reward_emission_index.global_reward_index - sponsor.reward_index);
+    sponsor.pending_rewards += Decimal256::from_uint256(
+        sponsor.lottery_deposit + sponsor.lock_bonus() + sponsor.weight_bonus(),
+    ) * (state.sponsor_reward_emission_index.global_reward_index
+        - sponsor.reward_index);
     sponsor.reward_index = state.sponsor_reward_emission_index.global_reward_index;
 }
 
@@ -85,11 +101,14 @@ pub fn handle_depositor_ticket_updates(
     minted_aust: Uint256,
 ) -> Result<u64, ContractError> {
     // Get combinations from encoded tickets
-    let combinations = base64_encoded_tickets_to_vec_string_tickets(encoded_tickets)?;
+    let combinations = base64_encoded_tickets_to_vec_string_tickets(
+        encoded_tickets,
+        config.ticket_length as usize,
+    )?;
 
     // Validate that all sequence combinations are valid
     for combination in combinations.clone() {
-        if !is_valid_sequence(&combination, TICKET_LENGTH) {
+        if !is_valid_sequence(&combination, config.ticket_length as usize) {
             return Err(ContractError::InvalidSequence(combination));
         }
     }
@@ -119,12 +138,15 @@ pub fn handle_depositor_ticket_updates(
 
     // Get the number of tickets the user would have post transaction (without accounting for round up)
     let mut post_transaction_num_depositor_tickets =
-        (depositor_info.tickets.len() + number_of_new_tickets as usize) as u64;
+        (depositor_info.num_tickets + number_of_new_tickets as usize) as u64;
 
     // Check if we need to round up the number of combinations based on the depositor's mixed_tax_post_transaction_lottery_deposit
+    // Generated sequences never take the depositor over max_tickets_per_depositor
     let mut new_combinations = combinations;
     for _ in 0..100 {
-        if post_transaction_max_depositor_tickets <= post_transaction_num_depositor_tickets {
+        if post_transaction_max_depositor_tickets <= post_transaction_num_depositor_tickets
+            || config.max_tickets_per_depositor <= post_transaction_num_depositor_tickets
+        {
             break;
         }
 
@@ -133,6 +155,7 @@ pub fn handle_depositor_ticket_updates(
             depositor.clone().into_string(),
             post_transaction_num_depositor_tickets,
             current_time,
+            config.ticket_length as usize,
         );
 
         // Add the randomly generated sequence to new_combinations
@@ -159,6 +182,45 @@ pub fn handle_depositor_ticket_updates(
         });
     }
 
+    add_depositor_tickets(
+        deps,
+        env,
+        config,
+        depositor,
+        depositor_info,
+        &new_combinations,
+    )?;
+
+    Ok(number_of_new_tickets)
+}
+
+/// Adds already validated combinations to the tickets of the depositor.
+/// Modifies depositor_info, but doesn't save it to storage.
+pub fn add_depositor_tickets(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    depositor: &Addr,
+    depositor_info: &mut DepositorInfo,
+    new_combinations: &[String],
+) -> Result<(), ContractError> {
+    let number_of_new_tickets = new_combinations.len() as u64;
+
+    // Tickets are acquired for the lottery currently open for deposits, unless they
+    // are acquired within ticket_eligibility_delay of its draw. Then they are pending
+    // and only take part in the next lottery
+    let state = STATE.load(deps.storage)?;
+    let acquired_lottery = match state.next_lottery_time {
+        Expiration::AtTime(next_lottery_time)
+            if config.ticket_eligibility_delay != 0
+                && env.block.time.plus_seconds(config.ticket_eligibility_delay)
+                    >= next_lottery_time =>
+        {
+            state.current_lottery + 1
+        }
+        _ => state.current_lottery,
+    };
+
     for combination in new_combinations {
         // check that the number of holders for any given ticket isn't too high
         if let Some(holders) = TICKETS
@@ -166,7 +228,7 @@ pub fn handle_depositor_ticket_updates(
             .unwrap()
         {
             if holders.len() >= config.max_holders as usize {
-                return Err(ContractError::InvalidHolderSequence(combination));
+                return Err(ContractError::InvalidHolderSequence(combination.clone()));
             }
         }
 
@@ -179,12 +241,29 @@ pub fn handle_depositor_ticket_updates(
         TICKETS
             .update(deps.storage, combination.as_bytes(), add_ticket)
             .unwrap();
+        store_ticket_acquisition(
+            deps.storage,
+            combination,
+            depositor,
+            acquired_lottery,
+            env.block.time.seconds(),
+        )?;
+    }
 
-        // add the combination to the depositor_info
-        depositor_info.tickets.push(combination);
+    if acquired_lottery > state.current_lottery {
+        store_pending_tickets(
+            deps.storage,
+            depositor,
+            acquired_lottery,
+            number_of_new_tickets,
+        )?;
     }
 
-    Ok(number_of_new_tickets)
+    // add the combinations to the depositor tickets
+    store_depositor_tickets(deps.storage, depositor, new_combinations)?;
+    depositor_info.num_tickets += number_of_new_tickets as usize;
+
+    Ok(())
 }
 
 /// Handles all changes to operator's following a deposit
@@ -246,6 +325,25 @@ pub fn handle_depositor_operator_updates(
     Ok(())
 }
 
+/// Records the operator shares and total_operator_shares after they changed
+pub fn record_operator_share_checkpoint(
+    storage: &mut dyn Storage,
+    operator: &Addr,
+    pool: &Pool,
+    time: u64,
+) -> StdResult<()> {
+    let operator_info = read_operator_info(storage, operator);
+    store_operator_share_checkpoint(
+        storage,
+        operator,
+        OperatorShareCheckpoint {
+            time,
+            shares: operator_info.shares,
+            total_operator_shares: pool.total_operator_shares,
+        },
+    )
+}
+
 /// This iterates over all mature claims for the address, and removes them, up to an optional cap.
 /// it removes the finished claims and returns the total amount of tokens to be released.
 pub fn claim_unbonded_withdrawals(
@@ -279,6 +377,71 @@ pub fn claim_unbonded_withdrawals(
     Ok(to_send)
 }
 
+/// Adds a claim to the unbonding claims of the depositor. Claims released in the same epoch
+/// are consolidated into a single claim, released at the latest of their release times.
+pub fn add_unbonding_claim(
+    depositor: &mut DepositorInfo,
+    claim: Claim,
+    epoch_interval: Duration,
+) -> Result<(), ContractError> {
+    let release_epoch = |release_at: &Expiration| match (release_at, epoch_interval) {
+        (Expiration::AtTime(time), Duration::Time(interval)) if interval != 0 => {
+            Some(time.seconds() / interval)
+        }
+        (Expiration::AtHeight(height), Duration::Height(interval)) if interval != 0 => {
+            Some(height / interval)
+        }
+        _ => None,
+    };
+
+    let claim_epoch = release_epoch(&claim.release_at);
+    if claim_epoch.is_some() {
+        if let Some(existing) = depositor
+            .unbonding_info
+            .iter_mut()
+            .find(|existing| release_epoch(&existing.release_at) == claim_epoch)
+        {
+            existing.amount += claim.amount;
+            if claim.release_at > existing.release_at {
+                existing.release_at = claim.release_at;
+            }
+            return Ok(());
+        }
+    }
+
+    // Check max unbonding_info concurrent claims is not bypassed
+    if depositor.unbonding_info.len() as u8 >= MAX_CLAIMS {
+        return Err(ContractError::MaxUnbondingClaims {
+            max_claims: MAX_CLAIMS,
+        });
+    }
+    depositor.unbonding_info.push(claim);
+    Ok(())
+}
+
+/// Weight of a ticket acquired at acquired_lottery when splitting the prize of current_lottery
+pub fn calculate_ticket_loyalty_weight(
+    loyalty_config: &LoyaltyConfig,
+    acquired_lottery: u64,
+    current_lottery: u64,
+) -> Decimal256 {
+    let rounds_held = current_lottery
+        .saturating_sub(acquired_lottery)
+        .min(loyalty_config.max_bonus_rounds);
+
+    Decimal256::one() + loyalty_config.bonus_per_round * Decimal256::from_uint256(rounds_held)
+}
+
+/// Fraction of the time weight window during which a ticket acquired at acquired_at was held
+pub fn calculate_ticket_time_weight(window: &TimeWeightWindow, acquired_at: u64) -> Decimal256 {
+    if window.end <= window.start {
+        return Decimal256::one();
+    }
+
+    let held_since = acquired_at.max(window.start).min(window.end);
+    Decimal256::from_ratio(window.end - held_since, window.end - window.start)
+}
+
 pub fn calculate_winner_prize(
     querier: &QuerierWrapper,
     config: &Config,
@@ -287,34 +450,141 @@ pub fn calculate_winner_prize(
     snapshotted_depositor_stats: &DepositorStatsInfo,
     winner_address: &Addr,
 ) -> StdResult<(Uint128, Uint128)> {
+    // Get the glow boost multiplier
+    let glow_boost_multiplier = calculate_winner_boost_multiplier(
+        querier,
+        config,
+        lottery_info,
+        snapshotted_depositor_stats,
+        winner_address,
+    )?;
+
+    Ok(calculate_boosted_winner_prize(
+        prize_info,
+        lottery_info,
+        glow_boost_multiplier,
+    ))
+}
+
+// Share of the bucket won in tier i, weighted by ticket loyalty if it applied to the lottery
+fn share_of_bucket(
+    prize_info: &PrizeInfo,
+    lottery_info: &LotteryInfo,
+    i: usize,
+    bucket: Uint256,
+) -> Uint256 {
+    match &lottery_info.loyalty_weights {
+        Some(loyalty_weights) if !loyalty_weights[i].is_zero() => {
+            bucket * (prize_info.loyalty_weights[i] / loyalty_weights[i])
+        }
+        _ => bucket.multiply_ratio(prize_info.matches[i], lottery_info.number_winners[i]),
+    }
+}
+
+/// Returns the ust and GLOW prizes of a winner given their glow boost multiplier
+pub fn calculate_boosted_winner_prize(
+    prize_info: &PrizeInfo,
+    lottery_info: &LotteryInfo,
+    glow_boost_multiplier: Decimal256,
+) -> (Uint128, Uint128) {
     let LotteryInfo {
         prize_buckets,
         number_winners,
         glow_prize_buckets,
-        block_height,
-        total_user_shares: snapshotted_total_user_shares,
         ..
     } = lottery_info;
 
-    let PrizeInfo {
-        matches: winner_matches,
-        ..
-    } = prize_info;
-
     let mut ust_to_send: Uint128 = Uint128::zero();
     let mut glow_to_send: Uint128 = Uint128::zero();
 
+    for i in 0..number_winners.len() {
+        if number_winners[i] == 0 {
+            continue;
+        }
+
+        // Handle ust calculations, up to the per winner cap
+        let mut amount = share_of_bucket(prize_info, lottery_info, i, prize_buckets[i]);
+        if let Some(cap) = winner_prize_cap(lottery_info, i) {
+            amount = std::cmp::min(amount, cap);
+        }
+
+        ust_to_send += Uint128::from(amount);
+
+        // Get the raw awarded glow
+        let glow_raw_amount = share_of_bucket(prize_info, lottery_info, i, glow_prize_buckets[i]);
+
+        // Get the GLOW to send
+        glow_to_send += Uint128::from(glow_raw_amount * glow_boost_multiplier);
+    }
+
+    (ust_to_send, glow_to_send)
+}
+
+/// Returns the ust prize of a winner over the per winner cap of each tier. Winners
+/// only exceed it when ticket loyalty made their share larger than average
+pub fn calculate_prize_cap_excess(
+    prize_info: &PrizeInfo,
+    lottery_info: &LotteryInfo,
+) -> Vec<Uint256> {
+    (0..lottery_info.number_winners.len())
+        .map(|i| {
+            if lottery_info.number_winners[i] == 0 {
+                return Uint256::zero();
+            }
+            let amount =
+                share_of_bucket(prize_info, lottery_info, i, lottery_info.prize_buckets[i]);
+            match winner_prize_cap(lottery_info, i) {
+                Some(cap) if amount > cap => amount - cap,
+                _ => Uint256::zero(),
+            }
+        })
+        .collect()
+}
+
+/// Returns the multiplier applied to the GLOW prizes of a winner, based on
+/// their deposit and voting balance snapshotted at the lottery height
+pub fn calculate_winner_boost_multiplier(
+    querier: &QuerierWrapper,
+    config: &Config,
+    lottery_info: &LotteryInfo,
+    snapshotted_depositor_stats: &DepositorStatsInfo,
+    winner_address: &Addr,
+) -> StdResult<Decimal256> {
+    Ok(snapshot_winner_boost(
+        querier,
+        config,
+        lottery_info,
+        snapshotted_depositor_stats,
+        winner_address,
+    )?
+    .multiplier)
+}
+
+/// Returns the glow boost multiplier of a winner with the balances it was computed from
+pub fn snapshot_winner_boost(
+    querier: &QuerierWrapper,
+    config: &Config,
+    lottery_info: &LotteryInfo,
+    snapshotted_depositor_stats: &DepositorStatsInfo,
+    winner_address: &Addr,
+) -> StdResult<BoostSnapshot> {
+    let LotteryInfo {
+        block_height,
+        total_user_shares: snapshotted_total_user_shares,
+        ..
+    } = lottery_info;
+
     // Get the values needed for boost calculation
 
     // User lottery deposit
 
     let snapshotted_user_shares = snapshotted_depositor_stats.shares;
 
-    // User voting balance
+    // User voting balance, including the ve balances delegated to them
 
-    let snapshotted_user_voting_balance = query_address_voting_balance_at_timestamp(
+    let snapshotted_user_voting_balance = query_address_voting_power_at_timestamp(
         querier,
-        &config.ve_contract,
+        &config.gov_contract,
         *block_height,
         winner_address,
     )?;
@@ -324,41 +594,19 @@ pub fn calculate_winner_prize(
     let snapshotted_total_voting_balance =
         query_total_voting_balance_at_timestamp(querier, &config.ve_contract, *block_height)?;
 
-    for i in 0..NUM_PRIZE_BUCKETS {
-        if number_winners[i] == 0 {
-            continue;
-        }
-
-        // Handle ust calculations
-        let prize_available: Uint256 = prize_buckets[i];
-
-        let amount: Uint128 = prize_available
-            .multiply_ratio(winner_matches[i], number_winners[i])
-            .into();
-
-        ust_to_send += amount;
-
-        // Handle glow calculations
-        let glow_prize_available = glow_prize_buckets[i];
-
-        // Get the raw awarded glow
-        let glow_raw_amount =
-            glow_prize_available.multiply_ratio(winner_matches[i], number_winners[i]);
-
-        // Get the glow boost multiplier
-        let glow_boost_multiplier = calculate_boost_multiplier(
+    Ok(BoostSnapshot {
+        multiplier: calculate_boost_multiplier(
             config.lotto_winner_boost_config.clone(),
             snapshotted_user_shares,
             *snapshotted_total_user_shares,
             snapshotted_user_voting_balance,
             snapshotted_total_voting_balance,
-        );
-
-        // Get the GLOW to send
-        glow_to_send += Uint128::from(glow_raw_amount * glow_boost_multiplier);
-    }
-
-    Ok((ust_to_send, glow_to_send))
+        ),
+        user_shares: snapshotted_user_shares,
+        total_user_shares: *snapshotted_total_user_shares,
+        user_voting_balance: snapshotted_user_voting_balance,
+        total_voting_balance: snapshotted_total_voting_balance,
+    })
 }
 
 pub fn calculate_boost_multiplier(
@@ -411,25 +659,71 @@ pub fn calculate_boost_multiplier(
 }
 
 // Get max bounds
-pub fn calculate_max_bound(min_bound: &str, minimum_matches_for_winning_ticket: usize) -> String {
+pub fn calculate_max_bound(
+    min_bound: &str,
+    minimum_matches_for_winning_ticket: usize,
+    ticket_length: usize,
+) -> String {
     format!(
         "{:f<length$}",
         min_bound[..minimum_matches_for_winning_ticket].to_string(),
-        length = TICKET_LENGTH
+        length = ticket_length
     )
 }
 
-pub fn pseudo_random_seq(sender_addr: String, tickets: u64, time: u64) -> String {
+pub fn pseudo_random_seq(sender_addr: String, tickets: u64, time: u64, length: usize) -> String {
     let mut input = sender_addr;
     input.push_str(&time.to_string());
     input.push_str(&tickets.to_string());
     let mut hasher = Keccak256::new();
     hasher.update(input.as_bytes());
     let result = hasher.finalize();
-    let pseudo_random_hash = &hex::encode(result)[2..TICKET_LENGTH + 2];
+    let pseudo_random_hash = &hex::encode(result)[2..length + 2];
     pseudo_random_hash.to_string()
 }
 
+/// Generates quick_pick pseudo random sequences for the depositor, skipping sequences
+/// that have reached the max holder limit. Returns the sequences and the next nonce.
+pub fn generate_quick_pick_sequences(
+    storage: &dyn Storage,
+    env: &Env,
+    config: &Config,
+    depositor: &Addr,
+    mut nonce: u64,
+    quick_pick: u32,
+) -> Result<(Vec<String>, u64), ContractError> {
+    let seed = format!("{}{}{}", env.block.chain_id, env.block.height, depositor);
+    let max_attempts = nonce + quick_pick as u64 * MAX_QUICK_PICK_ATTEMPTS;
+
+    let mut sequences: Vec<String> = vec![];
+    while sequences.len() < quick_pick as usize {
+        if nonce >= max_attempts {
+            return Err(ContractError::QuickPickExhausted {});
+        }
+
+        let sequence = pseudo_random_seq(
+            seed.clone(),
+            nonce,
+            env.block.time.nanos(),
+            config.ticket_length as usize,
+        );
+        nonce += 1;
+
+        // Count holders including the sequences already picked in this batch
+        let holders = TICKETS
+            .may_load(storage, sequence.as_bytes())?
+            .unwrap_or_default()
+            .len()
+            + sequences.iter().filter(|s| **s == sequence).count();
+
+        if holders < config.max_holders as usize {
+            sequences.push(sequence);
+        }
+    }
+
+    Ok((sequences, nonce))
+}
+
 pub fn is_valid_sequence(sequence: &str, len: usize) -> bool {
     sequence.len() == len
         && sequence
@@ -449,6 +743,39 @@ pub fn count_seq_matches(a: &str, b: &str) -> u8 {
     count
 }
 
+/// base raised to the power of exp, by squaring
+pub fn decimal_pow(base: Decimal256, exp: u128) -> Decimal256 {
+    let mut result = Decimal256::one();
+    let mut base = base;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp % 2 == 1 {
+            result *= base;
+        }
+        base = base * base;
+        exp /= 2;
+    }
+    result
+}
+
+/// Probability of a random ticket of ticket_length hex characters matching exactly
+/// the first `matches` characters of the winning sequence, i.e. of falling in that tier
+pub fn tier_probability(matches: usize, ticket_length: usize) -> Decimal256 {
+    let sixteenth = Decimal256::from_ratio(1u64, 16u64);
+    let all_matched = decimal_pow(sixteenth, matches as u128);
+    if matches == ticket_length {
+        all_matched
+    } else {
+        all_matched * (Decimal256::one() - sixteenth)
+    }
+}
+
+/// Probability of at least one of num_tickets random tickets falling in a tier of
+/// probability tier_probability
+pub fn probability_of_any(tier_probability: Decimal256, num_tickets: u128) -> Decimal256 {
+    Decimal256::one() - decimal_pow(Decimal256::one() - tier_probability, num_tickets)
+}
+
 #[allow(dead_code)]
 pub fn uint256_times_decimal256_ceil(a: Uint256, b: Decimal256) -> Uint256 {
     // Check for rounding error
@@ -462,8 +789,49 @@ pub fn uint256_times_decimal256_ceil(a: Uint256, b: Decimal256) -> Uint256 {
     }
 }
 
+/// Validates that prize_distribution has a fraction for each prize bucket and sums to 1
+pub fn validate_prize_distribution(
+    prize_distribution: &[Decimal256],
+    num_prize_buckets: usize,
+) -> Result<(), ContractError> {
+    if prize_distribution.len() != num_prize_buckets {
+        return Err(ContractError::InvalidPrizeDistribution {});
+    }
+
+    let mut sum = Decimal256::zero();
+    for item in prize_distribution.iter() {
+        sum += *item;
+    }
+
+    if sum != Decimal256::one() {
+        return Err(ContractError::InvalidPrizeDistribution {});
+    }
+
+    Ok(())
+}
+
+/// Validates that min_matches_for_prize is a number of matches of the prize distribution,
+/// and that no share of the prize distribution goes to fewer matches
+pub fn validate_min_matches_for_prize(
+    min_matches_for_prize: u8,
+    prize_distribution: &[Decimal256],
+) -> Result<(), ContractError> {
+    let min_matches = min_matches_for_prize as usize;
+    if min_matches >= prize_distribution.len()
+        || prize_distribution[..min_matches]
+            .iter()
+            .any(|fraction_of_prize| !fraction_of_prize.is_zero())
+    {
+        return Err(ContractError::InvalidMinMatchesForPrize(
+            min_matches_for_prize,
+        ));
+    }
+
+    Ok(())
+}
+
 pub fn get_minimum_matches_for_winning_ticket(
-    prize_distribution: [Decimal256; NUM_PRIZE_BUCKETS],
+    prize_distribution: &[Decimal256],
 ) -> StdResult<usize> {
     for (index, fraction_of_prize) in prize_distribution.iter().enumerate() {
         if *fraction_of_prize != Decimal256::zero() {
@@ -478,11 +846,14 @@ pub fn get_minimum_matches_for_winning_ticket(
     ))
 }
 
+#[derive(Default)]
 pub struct ExecuteLotteryRedeemedAustInfo {
     pub value_of_user_aust_to_be_redeemed_for_lottery: Uint256,
     pub user_aust_to_redeem: Uint256,
     pub value_of_sponsor_aust_to_be_redeemed_for_lottery: Uint256,
     pub sponsor_aust_to_redeem: Uint256,
+    pub value_of_pol_aust_to_be_redeemed_for_lottery: Uint256,
+    pub pol_aust_to_redeem: Uint256,
     pub aust_to_redeem: Uint256,
     pub aust_to_redeem_value: Uint256,
 }
@@ -494,6 +865,11 @@ pub fn calculate_value_of_aust_to_be_redeemed_for_lottery(
     contract_a_balance: Uint256,
     aust_exchange_rate: Decimal256,
 ) -> ExecuteLotteryRedeemedAustInfo {
+    // Nothing is redeemed under negative yield, see calculate_yield_deficit
+    if aust_exchange_rate < state.last_lottery_execution_aust_exchange_rate {
+        return ExecuteLotteryRedeemedAustInfo::default();
+    }
+
     // Get the aust_user_balance
     let total_user_aust = pool.total_user_aust;
 
@@ -506,8 +882,22 @@ pub fn calculate_value_of_aust_to_be_redeemed_for_lottery(
     // Get the user_aust_to_redeem
     let user_aust_to_redeem = value_of_user_aust_to_be_redeemed_for_lottery / aust_exchange_rate;
 
-    // Sponsor balance equals aust_balance - total_user_aust
-    let total_sponsor_aust = contract_a_balance - pool.total_user_aust;
+    // The whole value of the protocol owned liquidity above its principal, so liquidity
+    // deposited since the last lottery only contributes its own appreciation
+    let pol_value = pool.total_pol_aust * aust_exchange_rate;
+    let value_of_pol_aust_to_be_redeemed_for_lottery = if pol_value > pool.total_pol_principal {
+        pol_value - pool.total_pol_principal
+    } else {
+        Uint256::zero()
+    };
+
+    // Get the pol_aust_to_redeem
+    let pol_aust_to_redeem = value_of_pol_aust_to_be_redeemed_for_lottery / aust_exchange_rate;
+
+    // Sponsor balance equals aust_balance - total_user_aust - total_pol_aust - total_savings_aust,
+    // the appreciation of the savings isn't redeemed
+    let total_sponsor_aust =
+        contract_a_balance - pool.total_user_aust - pool.total_pol_aust - pool.total_savings_aust;
 
     // This should equal aust_sponsor_balance * (rate - state.last_lottery_exchange_rate) * config.split_factor;
     let value_of_sponsor_aust_to_be_redeemed_for_lottery =
@@ -518,7 +908,7 @@ pub fn calculate_value_of_aust_to_be_redeemed_for_lottery(
         value_of_sponsor_aust_to_be_redeemed_for_lottery / aust_exchange_rate;
 
     // Get the aust_to_redeem and aust_to_redeem_value
-    let aust_to_redeem = user_aust_to_redeem + sponsor_aust_to_redeem;
+    let aust_to_redeem = user_aust_to_redeem + sponsor_aust_to_redeem + pol_aust_to_redeem;
     let aust_to_redeem_value = aust_to_redeem * aust_exchange_rate;
 
     ExecuteLotteryRedeemedAustInfo {
@@ -526,11 +916,37 @@ pub fn calculate_value_of_aust_to_be_redeemed_for_lottery(
         user_aust_to_redeem,
         value_of_sponsor_aust_to_be_redeemed_for_lottery,
         sponsor_aust_to_redeem,
+        value_of_pol_aust_to_be_redeemed_for_lottery,
+        pol_aust_to_redeem,
         aust_to_redeem,
         aust_to_redeem_value,
     }
 }
 
+/// UST value the users and the protocol owned liquidity lost to an aust exchange rate
+/// below the one of the last lottery execution. The rate of the last lottery execution
+/// is kept until appreciation recoups the deficit, only then are prizes funded again
+pub fn calculate_yield_deficit(
+    state: &State,
+    pool: &Pool,
+    aust_exchange_rate: Decimal256,
+) -> Uint256 {
+    if aust_exchange_rate >= state.last_lottery_execution_aust_exchange_rate {
+        return Uint256::zero();
+    }
+
+    let user_deficit = pool.total_user_aust
+        * (state.last_lottery_execution_aust_exchange_rate - aust_exchange_rate);
+    let pol_value = pool.total_pol_aust * aust_exchange_rate;
+    let pol_deficit = if pool.total_pol_principal > pol_value {
+        pool.total_pol_principal - pol_value
+    } else {
+        Uint256::zero()
+    };
+
+    user_deficit + pol_deficit
+}
+
 #[allow(dead_code)]
 pub fn calculate_depositor_balance(
     pool: &Pool,
@@ -544,8 +960,47 @@ pub fn calculate_depositor_balance(
         * aust_exchange_rate
 }
 
+/// Number of tickets the savings appreciation of a depositor can back on top of the tickets
+/// they already hold. The depositor's part of the appreciation redeemed for prizes at the
+/// next lottery, as computed in calculate_value_of_aust_to_be_redeemed_for_lottery, is excluded
+pub fn calculate_convertible_yield_tickets(
+    state: &State,
+    pool: &Pool,
+    config: &Config,
+    depositor_info: &DepositorInfo,
+    aust_exchange_rate: Decimal256,
+) -> u64 {
+    let depositor_aust = pool.total_user_aust
+        * decimal_from_ratio_or_zero(depositor_info.shares, pool.total_user_shares);
+
+    let value_to_be_redeemed_for_lottery =
+        if aust_exchange_rate > state.last_lottery_execution_aust_exchange_rate {
+            depositor_aust
+                * (aust_exchange_rate - state.last_lottery_execution_aust_exchange_rate)
+                * config.split_factor
+        } else {
+            Uint256::zero()
+        };
+
+    let depositor_balance = depositor_aust * aust_exchange_rate - value_to_be_redeemed_for_lottery;
+
+    let max_depositor_tickets = Uint128::from(
+        depositor_balance
+            / Decimal256::from_uint256(
+                config.ticket_price
+            // Subtract 10^-5 in order to offset rounding problems
+            // relies on ticket price being at least 10^-5 UST
+                - Uint256::from(10u128),
+            ),
+    )
+    .u128() as u64;
+
+    max_depositor_tickets.saturating_sub(depositor_info.num_tickets as u64)
+}
+
 pub fn base64_encoded_tickets_to_vec_string_tickets(
     encoded_tickets: String,
+    ticket_length: usize,
 ) -> StdResult<Vec<String>> {
     // Encoded_tickets to binary
     let decoded_binary_tickets = match base64::decode(encoded_tickets) {
@@ -558,36 +1013,45 @@ pub fn base64_encoded_tickets_to_vec_string_tickets(
     };
 
     // Validate that the decoded value is the right length
-    if decoded_binary_tickets.len() % 3 != 0 {
+    let binary_ticket_length = ticket_length / 2 + ticket_length % 2;
+    if decoded_binary_tickets.len() % binary_ticket_length != 0 {
         return Err(StdError::generic_err("Decoded tickets wrong length."));
     };
 
-    // Will always return a Vec of 6 character hex strings
+    // Will always return a Vec of ticket_length character hex strings
     Ok(decoded_binary_tickets
-        .chunks(3)
-        .map(hex::encode)
+        .chunks(binary_ticket_length)
+        .map(|binary_ticket| binary_ticket_to_sequence(binary_ticket, ticket_length))
         .collect::<Vec<String>>())
 }
 
+/// Binary form of a sequence, two characters per byte. Sequences of an
+/// odd length are padded with a trailing 0
+pub fn sequence_to_binary_ticket(sequence: &str) -> StdResult<Vec<u8>> {
+    let padded_sequence = if sequence.len() % 2 == 1 {
+        format!("{}0", sequence)
+    } else {
+        sequence.to_string()
+    };
+
+    hex::decode(padded_sequence)
+        .map_err(|_| StdError::generic_err("Couldn't hex decode string ticket"))
+}
+
+/// Sequence of a binary ticket, without the padding of odd ticket lengths
+pub fn binary_ticket_to_sequence(binary_ticket: &[u8], ticket_length: usize) -> String {
+    let mut sequence = hex::encode(binary_ticket);
+    sequence.truncate(ticket_length);
+    sequence
+}
+
 pub fn vec_string_tickets_to_vec_binary_tickets(
     vec_string_tickets: Vec<String>,
-) -> StdResult<Vec<[u8; 3]>> {
+) -> StdResult<Vec<Vec<u8>>> {
     vec_string_tickets
         .iter()
-        .map(|s| {
-            let vec_ticket = match hex::decode(s) {
-                Ok(b) => b,
-                Err(_) => return Err(StdError::generic_err("Couldn't hex decode string ticket")),
-            };
-
-            match vec_ticket.try_into() {
-                Ok(b) => Ok(b),
-                Err(_) => Err(StdError::generic_err(
-                    "Couldn't convert vec ticket to [u8, 3]",
-                )),
-            }
-        })
-        .collect::<StdResult<Vec<[u8; 3]>>>()
+        .map(|s| sequence_to_binary_ticket(s))
+        .collect::<StdResult<Vec<Vec<u8>>>>()
 }
 
 pub fn vec_binary_tickets_to_vec_string_tickets(vec_binary_tickets: Vec<[u8; 3]>) -> Vec<String> {
@@ -597,6 +1061,41 @@ pub fn vec_binary_tickets_to_vec_string_tickets(vec_binary_tickets: Vec<[u8; 3]>
         .collect::<Vec<String>>()
 }
 
+/// UST value of one user share given the aust exchange rate
+pub fn calculate_share_price(pool: &Pool, aust_exchange_rate: Decimal256) -> Decimal256 {
+    decimal_from_ratio_or_one(pool.total_user_aust, pool.total_user_shares) * aust_exchange_rate
+}
+
+/// Time weighted average of the share price since the oldest checkpoint.
+/// Each checkpoint's price holds until the next one, and the last one until current_time.
+/// Falls back to current_share_price when no time has elapsed.
+pub fn calculate_share_price_twap(
+    checkpoints: &[SharePriceCheckpoint],
+    current_time: u64,
+    current_share_price: Decimal256,
+) -> Decimal256 {
+    let start_time = match checkpoints.first() {
+        Some(checkpoint) => checkpoint.time,
+        None => return current_share_price,
+    };
+
+    if current_time <= start_time {
+        return current_share_price;
+    }
+
+    let mut weighted_sum = Decimal256::zero();
+    for (index, checkpoint) in checkpoints.iter().enumerate() {
+        let end_time = checkpoints
+            .get(index + 1)
+            .map(|next| next.time)
+            .unwrap_or(current_time);
+        let elapsed = end_time.saturating_sub(checkpoint.time);
+        weighted_sum += checkpoint.share_price * Decimal256::from_uint256(elapsed);
+    }
+
+    weighted_sum / Decimal256::from_uint256(current_time - start_time)
+}
+
 pub fn decimal_from_ratio_or_one(a: Uint256, b: Uint256) -> Decimal256 {
     if a == Uint256::zero() && b == Uint256::zero() {
         return Decimal256::one();
@@ -604,3 +1103,11 @@ pub fn decimal_from_ratio_or_one(a: Uint256, b: Uint256) -> Decimal256 {
 
     Decimal256::from_ratio(a, b)
 }
+
+pub fn decimal_from_ratio_or_zero(a: Uint256, b: Uint256) -> Decimal256 {
+    if b == Uint256::zero() {
+        return Decimal256::zero();
+    }
+
+    Decimal256::from_ratio(a, b)
+}
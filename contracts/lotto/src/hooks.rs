@@ -0,0 +1,107 @@
+use cosmwasm_std::{
+    attr, to_binary, ContractResult, Deps, DepsMut, MessageInfo, Reply, Response, StdResult,
+    Storage, SubMsg, WasmMsg,
+};
+use glow_protocol::lotto::{GlowHookMsg, HookExecuteMsg, HooksResponse};
+
+use crate::error::ContractError;
+use crate::state::{CONFIG, HOOKS};
+
+pub const HOOK_REPLY_ID: u64 = 6;
+
+const MAX_HOOKS: u32 = 10;
+
+// Gas a hook can use, so a misbehaving hook can't exhaust the transaction
+const HOOK_GAS_LIMIT: u64 = 300_000;
+
+/// Add or remove hook contracts - restricted to owner or gov
+pub fn execute_update_hooks(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.owner && info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut hooks = HOOKS.may_load(deps.storage)?.unwrap_or_default();
+    for hook in remove.iter() {
+        let hook = deps.api.addr_validate(hook)?;
+        let index = hooks
+            .iter()
+            .position(|registered| *registered == hook)
+            .ok_or_else(|| ContractError::HookNotRegistered(hook.to_string()))?;
+        hooks.remove(index);
+    }
+    for hook in add.iter() {
+        let hook = deps.api.addr_validate(hook)?;
+        if hooks.contains(&hook) {
+            return Err(ContractError::HookAlreadyRegistered(hook.to_string()));
+        }
+        hooks.push(hook);
+    }
+    if hooks.len() > MAX_HOOKS as usize {
+        return Err(ContractError::TooManyHooks {
+            max_hooks: MAX_HOOKS,
+        });
+    }
+    HOOKS.save(deps.storage, &hooks)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_hooks"),
+        attr("added", add.join(",")),
+        attr("removed", remove.join(",")),
+    ]))
+}
+
+/// Submessages notifying every hook of msg. They are sent after the state
+/// changes of the operation, and their failure is ignored by the reply
+pub fn hook_submsgs(storage: &dyn Storage, msg: GlowHookMsg) -> StdResult<Vec<SubMsg>> {
+    let hooks = HOOKS.may_load(storage)?.unwrap_or_default();
+    if hooks.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let msg = to_binary(&HookExecuteMsg::GlowHook(msg))?;
+    Ok(hooks
+        .into_iter()
+        .map(|hook| {
+            let mut submsg = SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: hook.to_string(),
+                    msg: msg.clone(),
+                    funds: vec![],
+                },
+                HOOK_REPLY_ID,
+            );
+            submsg.gas_limit = Some(HOOK_GAS_LIMIT);
+            submsg
+        })
+        .collect())
+}
+
+/// Only failed hooks reply, their changes are reverted but the operation
+/// which notified them goes through
+pub fn handle_hook_reply(msg: Reply) -> Result<Response, ContractError> {
+    let error = match msg.result {
+        ContractResult::Err(error) => error,
+        ContractResult::Ok(_) => String::new(),
+    };
+
+    Ok(Response::new().add_attributes(vec![attr("action", "hook_failed"), attr("error", error)]))
+}
+
+pub fn query_hooks(deps: Deps) -> StdResult<HooksResponse> {
+    Ok(HooksResponse {
+        hooks: HOOKS
+            .may_load(deps.storage)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|hook| hook.to_string())
+            .collect(),
+    })
+}
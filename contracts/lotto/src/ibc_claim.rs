@@ -0,0 +1,159 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{
+    attr, Addr, Coin, CosmosMsg, Deps, DepsMut, Env, IbcMsg, IbcTimeout, MessageInfo, Response,
+    StdResult, Storage, Uint128,
+};
+use cw0::Expiration;
+use cw_storage_plus::U64Key;
+use glow_protocol::lotto::{Claim, ClaimDestination, IbcClaimConfig, IbcClaimResponse};
+
+use crate::error::ContractError;
+use crate::state::{
+    read_depositor_info, store_depositor_info, Config, IbcClaim, CONFIG, IBC_CLAIMS,
+    NEXT_IBC_CLAIM_ID,
+};
+
+pub fn validate_claim_destination(
+    config: &Config,
+    destination: &ClaimDestination,
+) -> Result<(), ContractError> {
+    if !config
+        .ibc_claim_config
+        .allowed_channels
+        .contains(&destination.ibc_channel)
+    {
+        return Err(ContractError::IbcChannelNotAllowed(
+            destination.ibc_channel.clone(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Records the claim and returns its id along with the ics20 transfer of the prize.
+/// On timeout the transfer module refunds the amount to this contract
+pub fn ibc_claim_transfer_msg(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    winner: &Addr,
+    destination: ClaimDestination,
+    amount: Uint128,
+) -> StdResult<(u64, CosmosMsg)> {
+    let timeout = env
+        .block
+        .time
+        .plus_seconds(config.ibc_claim_config.timeout_seconds);
+
+    let msg = CosmosMsg::Ibc(IbcMsg::Transfer {
+        channel_id: destination.ibc_channel.clone(),
+        to_address: destination.to_address.clone(),
+        amount: Coin {
+            denom: config.stable_denom.clone(),
+            amount,
+        },
+        timeout: IbcTimeout::with_timestamp(timeout),
+    });
+
+    let id = NEXT_IBC_CLAIM_ID.may_load(storage)?.unwrap_or_default();
+    IBC_CLAIMS.save(
+        storage,
+        U64Key::from(id),
+        &IbcClaim {
+            winner: winner.clone(),
+            destination,
+            amount,
+            timeout: timeout.seconds(),
+            refunded: false,
+        },
+    )?;
+    NEXT_IBC_CLAIM_ID.save(storage, &(id + 1))?;
+
+    Ok((id, msg))
+}
+
+pub fn execute_update_ibc_claim_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    ibc_claim_config: IbcClaimConfig,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !ibc_claim_config.allowed_channels.is_empty() && ibc_claim_config.timeout_seconds == 0 {
+        return Err(ContractError::InvalidIbcClaimConfig {});
+    }
+
+    config.ibc_claim_config = ibc_claim_config;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_ibc_claim_config")]))
+}
+
+/// The contract isn't notified of ics20 acknowledgements, so the owner confirms
+/// that a transfer timed out. The refunded amount becomes immediately claimable
+/// by the winner through Claim
+pub fn execute_refund_ibc_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut ibc_claim = IBC_CLAIMS.load(deps.storage, U64Key::from(id))?;
+
+    if ibc_claim.refunded {
+        return Err(ContractError::IbcClaimAlreadyRefunded(id));
+    }
+
+    if env.block.time.seconds() < ibc_claim.timeout {
+        return Err(ContractError::IbcClaimNotTimedOut {
+            timeout: ibc_claim.timeout,
+        });
+    }
+
+    let mut depositor_info = read_depositor_info(deps.storage, &ibc_claim.winner);
+    depositor_info.unbonding_info.push(Claim {
+        amount: Uint256::from(ibc_claim.amount),
+        release_at: Expiration::AtHeight(env.block.height),
+    });
+    store_depositor_info(
+        deps.storage,
+        &ibc_claim.winner,
+        depositor_info,
+        env.block.height,
+    )?;
+
+    ibc_claim.refunded = true;
+    IBC_CLAIMS.save(deps.storage, U64Key::from(id), &ibc_claim)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "refund_ibc_claim"),
+        attr("id", id.to_string()),
+        attr("winner", ibc_claim.winner.to_string()),
+        attr("amount", ibc_claim.amount),
+    ]))
+}
+
+pub fn query_ibc_claim(deps: Deps, id: u64) -> StdResult<IbcClaimResponse> {
+    let ibc_claim = IBC_CLAIMS.load(deps.storage, U64Key::from(id))?;
+
+    Ok(IbcClaimResponse {
+        id,
+        winner: ibc_claim.winner.to_string(),
+        destination: ibc_claim.destination,
+        amount: ibc_claim.amount,
+        timeout: ibc_claim.timeout,
+        refunded: ibc_claim.refunded,
+    })
+}
@@ -0,0 +1,61 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{attr, Addr, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage};
+use glow_protocol::lotto::InboxResponse;
+
+use crate::error::ContractError;
+use crate::state::{read_depositor_info, Inbox, INBOXES};
+
+pub fn read_inbox(storage: &dyn Storage, address: &Addr) -> StdResult<Inbox> {
+    Ok(INBOXES.may_load(storage, address)?.unwrap_or_default())
+}
+
+/// Counts a new prize for winner, called once per lottery the first time
+/// execute_prize records a winning ticket of winner
+pub fn record_prize_won(storage: &mut dyn Storage, winner: &Addr) -> StdResult<()> {
+    let mut inbox = read_inbox(storage, winner)?;
+    inbox.unseen_prizes += 1;
+    inbox.unclaimed_prizes += 1;
+    INBOXES.save(storage, winner, &inbox)
+}
+
+pub fn record_prize_claimed(storage: &mut dyn Storage, winner: &Addr) -> StdResult<()> {
+    let mut inbox = read_inbox(storage, winner)?;
+    // Prizes won before the inbox existed were never counted
+    inbox.unclaimed_prizes = inbox.unclaimed_prizes.saturating_sub(1);
+    INBOXES.save(storage, winner, &inbox)
+}
+
+pub fn execute_mark_seen(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut inbox = read_inbox(deps.storage, &info.sender)?;
+    inbox.unseen_prizes = 0;
+    INBOXES.save(deps.storage, &info.sender, &inbox)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "mark_seen"),
+        attr("address", info.sender.to_string()),
+    ]))
+}
+
+pub fn query_inbox(deps: Deps, env: Env, address: String) -> StdResult<InboxResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let inbox = read_inbox(deps.storage, &address)?;
+    let depositor = read_depositor_info(deps.storage, &address);
+
+    // Unbonding claims are bounded by MAX_CLAIMS
+    let mut matured_claims = 0u32;
+    let mut matured_claims_amount = Uint256::zero();
+    for claim in depositor.unbonding_info {
+        if claim.release_at.is_expired(&env.block) {
+            matured_claims += 1;
+            matured_claims_amount += claim.amount;
+        }
+    }
+
+    Ok(InboxResponse {
+        address: address.to_string(),
+        unseen_prizes: inbox.unseen_prizes,
+        unclaimed_prizes: inbox.unclaimed_prizes,
+        matured_claims,
+        matured_claims_amount,
+    })
+}
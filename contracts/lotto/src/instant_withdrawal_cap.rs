@@ -0,0 +1,110 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{attr, Deps, DepsMut, MessageInfo, Response, StdResult, Storage};
+use glow_protocol::lotto::{InstantWithdrawalCap, InstantWithdrawalCapResponse};
+
+use crate::error::ContractError;
+use crate::state::{
+    InstantWithdrawals, CONFIG, INSTANT_WITHDRAWALS, INSTANT_WITHDRAWAL_CAP, STATE,
+};
+
+/// Set the limit on the stable withdrawn instantly per epoch, or lift it
+/// if cap is None - restricted to gov
+pub fn execute_update_instant_withdrawal_cap(
+    deps: DepsMut,
+    info: MessageInfo,
+    cap: Option<InstantWithdrawalCap>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let cap_attr = match &cap {
+        Some(InstantWithdrawalCap::Amount(amount)) => amount.to_string(),
+        Some(InstantWithdrawalCap::PoolRatio(ratio)) => {
+            if *ratio > Decimal256::one() {
+                return Err(ContractError::InvalidInstantWithdrawalCap {});
+            }
+            format!("{} of the pool", ratio)
+        }
+        None => "none".to_string(),
+    };
+
+    match cap {
+        Some(cap) => INSTANT_WITHDRAWAL_CAP.save(deps.storage, &cap)?,
+        None => INSTANT_WITHDRAWAL_CAP.remove(deps.storage),
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_instant_withdrawal_cap"),
+        attr("cap", cap_attr),
+    ]))
+}
+
+/// Stable left to withdraw instantly in the epoch. None for a PoolRatio cap
+/// until the pool value of the epoch is recorded
+fn available_amount(
+    cap: &InstantWithdrawalCap,
+    instant_withdrawals: &InstantWithdrawals,
+) -> Option<Uint256> {
+    let limit = match cap {
+        InstantWithdrawalCap::Amount(amount) => *amount,
+        InstantWithdrawalCap::PoolRatio(ratio) => instant_withdrawals.epoch_pool_value? * *ratio,
+    };
+
+    if limit > instant_withdrawals.withdrawn {
+        Some(limit - instant_withdrawals.withdrawn)
+    } else {
+        Some(Uint256::zero())
+    }
+}
+
+/// Adds amount to the stable withdrawn instantly in the epoch, failing once the
+/// cap is exceeded. pool_value is the value of the user deposits before the
+/// withdrawal, recorded as the epoch's on its first instant withdrawal
+pub fn record_instant_withdrawal(
+    storage: &mut dyn Storage,
+    pool_value: Uint256,
+    amount: Uint256,
+) -> Result<(), ContractError> {
+    let cap = match INSTANT_WITHDRAWAL_CAP.may_load(storage)? {
+        Some(cap) => cap,
+        None => return Ok(()),
+    };
+
+    let mut instant_withdrawals = INSTANT_WITHDRAWALS.may_load(storage)?.unwrap_or_default();
+    if instant_withdrawals.epoch_pool_value.is_none() {
+        instant_withdrawals.epoch_pool_value = Some(pool_value);
+    }
+
+    let available = available_amount(&cap, &instant_withdrawals).unwrap_or_default();
+    if amount > available {
+        return Err(ContractError::InstantWithdrawalCapExceeded {
+            available,
+            retry_after: STATE.load(storage)?.next_epoch,
+        });
+    }
+
+    instant_withdrawals.withdrawn += amount;
+    INSTANT_WITHDRAWALS.save(storage, &instant_withdrawals)?;
+
+    Ok(())
+}
+
+pub fn query_instant_withdrawal_cap(deps: Deps) -> StdResult<InstantWithdrawalCapResponse> {
+    let cap = INSTANT_WITHDRAWAL_CAP.may_load(deps.storage)?;
+    let instant_withdrawals = INSTANT_WITHDRAWALS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+
+    Ok(InstantWithdrawalCapResponse {
+        available: cap
+            .as_ref()
+            .and_then(|cap| available_amount(cap, &instant_withdrawals)),
+        cap,
+        withdrawn_in_epoch: instant_withdrawals.withdrawn,
+        resets_at: STATE.load(deps.storage)?.next_epoch,
+    })
+}
@@ -0,0 +1,168 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    attr, coin, BankMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage,
+};
+use glow_protocol::lotto::InsuranceResponse;
+use glow_protocol::querier::deduct_tax;
+
+use crate::error::ContractError;
+use crate::state::{Insurance, CONFIG, INSURANCE, STATE};
+
+pub fn read_insurance(storage: &dyn Storage) -> StdResult<Insurance> {
+    Ok(INSURANCE.may_load(storage)?.unwrap_or_default())
+}
+
+/// Set the share of the reserve moved to the insurance fund at each
+/// epoch operation - restricted to gov
+pub fn execute_update_insurance(
+    deps: DepsMut,
+    info: MessageInfo,
+    reserve_share: Decimal256,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if reserve_share > Decimal256::one() {
+        return Err(ContractError::InvalidInsuranceShare {});
+    }
+
+    let mut insurance = read_insurance(deps.storage)?;
+    insurance.reserve_share = reserve_share;
+    INSURANCE.save(deps.storage, &insurance)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_insurance"),
+        attr("reserve_share", reserve_share.to_string()),
+    ]))
+}
+
+/// Move amount of the reserve to the insurance fund - restricted to gov
+pub fn execute_top_up_insurance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint256,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if amount > state.total_reserve {
+        return Err(ContractError::InsufficientReserve {
+            amount,
+            total_reserve: state.total_reserve,
+        });
+    }
+
+    let mut insurance = read_insurance(deps.storage)?;
+    insurance.balance += amount;
+    state.total_reserve -= amount;
+
+    INSURANCE.save(deps.storage, &insurance)?;
+    STATE.save(deps.storage, &state, env.block.height)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "top_up_insurance"),
+        attr("amount", amount.to_string()),
+        attr("insurance_balance", insurance.balance.to_string()),
+    ]))
+}
+
+/// Send amount of the insurance fund to recipient, by default
+/// the community contract - restricted to gov
+pub fn execute_withdraw_insurance(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint256,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut insurance = read_insurance(deps.storage)?;
+    if amount.is_zero() || amount > insurance.balance {
+        return Err(ContractError::InsufficientInsuranceBalance {
+            amount,
+            balance: insurance.balance,
+        });
+    }
+
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => config.community_contract.clone(),
+    };
+
+    insurance.balance -= amount;
+    INSURANCE.save(deps.storage, &insurance)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                coin(amount.into(), config.stable_denom),
+            )?],
+        })
+        .add_attributes(vec![
+            attr("action", "withdraw_insurance"),
+            attr("recipient", recipient.to_string()),
+            attr("amount", amount.to_string()),
+            attr("insurance_balance", insurance.balance.to_string()),
+        ]))
+}
+
+/// Moves the insurance share of total_reserves to the fund and
+/// returns the reserves left to distribute
+pub fn accrue_insurance(storage: &mut dyn Storage, total_reserves: Uint256) -> StdResult<Uint256> {
+    let mut insurance = read_insurance(storage)?;
+    let insurance_reserves = total_reserves * insurance.reserve_share;
+    if insurance_reserves.is_zero() {
+        return Ok(total_reserves);
+    }
+
+    insurance.balance += insurance_reserves;
+    INSURANCE.save(storage, &insurance)?;
+
+    Ok(total_reserves - insurance_reserves)
+}
+
+/// Draws up to shortfall from the insurance fund and returns the amount drawn
+pub fn draw_insurance(storage: &mut dyn Storage, shortfall: Uint256) -> StdResult<Uint256> {
+    let mut insurance = read_insurance(storage)?;
+    let drawn = if shortfall > insurance.balance {
+        insurance.balance
+    } else {
+        shortfall
+    };
+    if drawn.is_zero() {
+        return Ok(drawn);
+    }
+
+    insurance.balance -= drawn;
+    insurance.total_drawn += drawn;
+    INSURANCE.save(storage, &insurance)?;
+
+    Ok(drawn)
+}
+
+pub fn query_insurance(deps: Deps) -> StdResult<InsuranceResponse> {
+    let insurance = read_insurance(deps.storage)?;
+
+    Ok(InsuranceResponse {
+        reserve_share: insurance.reserve_share,
+        balance: insurance.balance,
+        total_drawn: insurance.total_drawn,
+    })
+}
@@ -117,9 +117,9 @@ fn instantiate_glow_lotto() {
 
     // set up glow lotto
     let lotto_id = app.store_code(contract_lotto());
-    let mut msg = crate::tests::instantiate_msg();
-
-    msg.anchor_contract = anchor_address.to_string();
+    let msg = crate::tests::instantiate_msg_builder()
+        .anchor_contract(anchor_address)
+        .build();
     println!("{:?}", msg);
 
     // throwing errors because we need to add support for Anchor
@@ -0,0 +1,89 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{Deps, Env, Order, StdResult};
+use terraswap::querier::query_token_balance;
+
+use crate::error::ContractError;
+use crate::insurance::read_insurance;
+use crate::querier::{query_balance, query_exchange_rate};
+use crate::receipt::read_total_receipt_aust;
+use crate::state::{CONFIG, POOL, STATE, TICKETS};
+
+fn check(holds: bool, invariant: &str) -> Result<(), ContractError> {
+    if holds {
+        Ok(())
+    } else {
+        Err(ContractError::InvariantViolated(invariant.to_string()))
+    }
+}
+
+/// Checks the protocol invariants after an execute, failing the transaction if any is broken.
+/// Balances are queried before the response messages run, so funds in transit
+/// (e.g. a deposit not yet sent to anchor) are still counted where they came from.
+pub fn assert_invariants(deps: Deps, env: &Env) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+    let pool = POOL.load(deps.storage)?;
+
+    // User aust is only held on behalf of shares
+    check(
+        !pool.total_user_shares.is_zero() || pool.total_user_aust.is_zero(),
+        "total_user_aust without total_user_shares",
+    )?;
+
+    // Contract worth, in stable denom
+    let aust_exchange_rate =
+        query_exchange_rate(deps, config.anchor_contract.to_string(), env.block.height)?
+            .exchange_rate;
+    let contract_a_balance = Uint256::from(query_token_balance(
+        &deps.querier,
+        config.a_terra_contract.clone(),
+        env.contract.address.clone(),
+    )?);
+    let contract_balance = query_balance(
+        deps,
+        env.contract.address.to_string(),
+        config.stable_denom.clone(),
+    )?;
+    let contract_worth = contract_a_balance * aust_exchange_rate + contract_balance;
+
+    // The pool covers what users, savings depositors, sponsors, receipt token holders
+    // and the protocol owned liquidity can withdraw
+    let total_deposits = (pool.total_user_aust
+        + pool.total_pol_aust
+        + pool.total_savings_aust
+        + read_total_receipt_aust(deps.storage)?)
+        * aust_exchange_rate
+        + pool.total_sponsor_lottery_deposits;
+    check(
+        total_deposits <= contract_worth,
+        "pool deposits exceed contract worth",
+    )?;
+
+    // Prizes, reserves and the insurance fund are backed by the contract
+    let total_prize_buckets = state
+        .prize_buckets
+        .iter()
+        .fold(Uint256::zero(), |sum, bucket| sum + *bucket);
+    check(
+        total_prize_buckets
+            + state.total_reserve
+            + state.missed_lottery_prizes
+            + read_insurance(deps.storage)?.balance
+            <= contract_worth,
+        "prize buckets and reserve exceed contract worth",
+    )?;
+
+    // Every ticket holder is counted in total_tickets
+    let total_ticket_holders = TICKETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, holders)| holders.len() as u64))
+        .collect::<StdResult<Vec<u64>>>()?
+        .into_iter()
+        .sum::<u64>();
+    check(
+        Uint256::from(total_ticket_holders) == state.total_tickets,
+        "ticket holders don't match total_tickets",
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{
+    attr, to_binary, Attribute, ContractResult, Deps, DepsMut, Env, MessageInfo, Reply, Response,
+    StdResult, Storage, SubMsg, WasmMsg,
+};
+use glow_protocol::community::ExecuteMsg as CommunityExecuteMsg;
+use glow_protocol::lotto::JackpotFloorResponse;
+
+use crate::error::ContractError;
+use crate::state::{Config, JackpotFloor, State, CONFIG, JACKPOT_FLOOR, STATE};
+
+pub const JACKPOT_TOP_UP_REPLY_ID: u64 = 7;
+
+pub fn read_jackpot_floor(storage: &dyn Storage) -> StdResult<JackpotFloor> {
+    Ok(JACKPOT_FLOOR.may_load(storage)?.unwrap_or_default())
+}
+
+/// Set the amount the jackpot bucket is topped up to at lottery executions,
+/// or stop the top-ups if None - restricted to gov
+pub fn execute_update_jackpot_floor(
+    deps: DepsMut,
+    info: MessageInfo,
+    floor: Option<Uint256>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // The outstanding subsidy is still repaid once the top-ups stop
+    let mut jackpot_floor = read_jackpot_floor(deps.storage)?;
+    jackpot_floor.floor = floor.unwrap_or_else(Uint256::zero);
+    JACKPOT_FLOOR.save(deps.storage, &jackpot_floor)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_jackpot_floor"),
+        attr("floor", jackpot_floor.floor.to_string()),
+    ]))
+}
+
+/// Credits the jackpot bucket up to the floor and requests the difference from
+/// the community contract. The credit is undone if the spend fails
+pub fn jackpot_top_up_submsgs(
+    storage: &mut dyn Storage,
+    config: &Config,
+    state: &mut State,
+) -> Result<(Vec<SubMsg>, Vec<Attribute>), ContractError> {
+    let mut jackpot_floor = read_jackpot_floor(storage)?;
+    let jackpot = match state.prize_buckets.last_mut() {
+        Some(jackpot) if *jackpot < jackpot_floor.floor => jackpot,
+        _ => return Ok((vec![], vec![])),
+    };
+
+    let top_up = jackpot_floor.floor - *jackpot;
+    *jackpot += top_up;
+
+    jackpot_floor.outstanding_subsidy += top_up;
+    jackpot_floor.total_subsidized += top_up;
+    jackpot_floor.top_up_in_flight = top_up;
+    JACKPOT_FLOOR.save(storage, &jackpot_floor)?;
+
+    let msg = SubMsg::reply_always(
+        WasmMsg::Execute {
+            contract_addr: config.community_contract.to_string(),
+            funds: vec![],
+            msg: to_binary(&CommunityExecuteMsg::WhitelistedSpend {
+                amount: top_up.into(),
+            })?,
+        },
+        JACKPOT_TOP_UP_REPLY_ID,
+    );
+
+    Ok((vec![msg], vec![attr("jackpot_top_up", top_up.to_string())]))
+}
+
+pub fn handle_jackpot_top_up_reply(
+    deps: DepsMut,
+    env: Env,
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    let mut jackpot_floor = read_jackpot_floor(deps.storage)?;
+    let top_up = jackpot_floor.top_up_in_flight;
+    jackpot_floor.top_up_in_flight = Uint256::zero();
+
+    if let ContractResult::Err(error) = msg.result {
+        // The jackpot runs without the top-up
+        let mut state = STATE.load(deps.storage)?;
+        if let Some(jackpot) = state.prize_buckets.last_mut() {
+            *jackpot -= top_up;
+        }
+        STATE.save(deps.storage, &state, env.block.height)?;
+
+        jackpot_floor.outstanding_subsidy -= top_up;
+        jackpot_floor.total_subsidized -= top_up;
+        JACKPOT_FLOOR.save(deps.storage, &jackpot_floor)?;
+
+        return Ok(Response::new().add_attributes(vec![
+            attr("action", "jackpot_top_up_failed"),
+            attr("returned_top_up", top_up.to_string()),
+            attr("error", error),
+        ]));
+    }
+
+    JACKPOT_FLOOR.save(deps.storage, &jackpot_floor)?;
+
+    Ok(Response::new())
+}
+
+/// Takes the outstanding subsidy out of the reserves being swept, up to all of them,
+/// and returns the amount repaid to the community contract
+pub fn repay_jackpot_subsidy(storage: &mut dyn Storage, reserves: Uint256) -> StdResult<Uint256> {
+    let mut jackpot_floor = read_jackpot_floor(storage)?;
+    let repaid = if jackpot_floor.outstanding_subsidy > reserves {
+        reserves
+    } else {
+        jackpot_floor.outstanding_subsidy
+    };
+    if repaid.is_zero() {
+        return Ok(repaid);
+    }
+
+    jackpot_floor.outstanding_subsidy -= repaid;
+    jackpot_floor.total_repaid += repaid;
+    JACKPOT_FLOOR.save(storage, &jackpot_floor)?;
+
+    Ok(repaid)
+}
+
+pub fn query_jackpot_floor(deps: Deps) -> StdResult<JackpotFloorResponse> {
+    let jackpot_floor = read_jackpot_floor(deps.storage)?;
+
+    Ok(JackpotFloorResponse {
+        floor: if jackpot_floor.floor.is_zero() {
+            None
+        } else {
+            Some(jackpot_floor.floor)
+        },
+        outstanding_subsidy: jackpot_floor.outstanding_subsidy,
+        total_subsidized: jackpot_floor.total_subsidized,
+        total_repaid: jackpot_floor.total_repaid,
+    })
+}
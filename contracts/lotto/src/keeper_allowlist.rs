@@ -0,0 +1,85 @@
+use cosmwasm_std::{attr, Addr, BlockInfo, Deps, DepsMut, MessageInfo, Response, StdResult};
+use cw0::Expiration;
+use glow_protocol::lotto::{KeeperAllowlist, KeeperAllowlistResponse};
+
+use crate::error::ContractError;
+use crate::state::{CONFIG, KEEPER_ALLOWLIST};
+
+/// Set the keepers with exclusive access to the lottery executions during
+/// the window after they become ready, or remove them if None - restricted to gov
+pub fn execute_update_keeper_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    allowlist: Option<KeeperAllowlist>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match allowlist {
+        Some(allowlist) => {
+            if allowlist.keepers.is_empty() {
+                return Err(ContractError::EmptyKeeperAllowlist {});
+            }
+
+            let keepers = allowlist
+                .keepers
+                .iter()
+                .map(|keeper| Ok(deps.api.addr_validate(keeper)?.to_string()))
+                .collect::<StdResult<Vec<String>>>()?;
+
+            KEEPER_ALLOWLIST.save(
+                deps.storage,
+                &KeeperAllowlist {
+                    keepers,
+                    exclusivity_window: allowlist.exclusivity_window,
+                },
+            )?;
+        }
+        None => KEEPER_ALLOWLIST.remove(deps.storage),
+    }
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_keeper_allowlist")]))
+}
+
+/// Fails if sender isn't allowlisted while the exclusivity window opened at
+/// ready_at is running. Only windows opened at a block time apply
+pub fn assert_keeper_allowed(
+    deps: Deps,
+    block: &BlockInfo,
+    sender: &Addr,
+    ready_at: Expiration,
+) -> Result<(), ContractError> {
+    let allowlist = match KEEPER_ALLOWLIST.may_load(deps.storage)? {
+        Some(allowlist) => allowlist,
+        None => return Ok(()),
+    };
+
+    if allowlist
+        .keepers
+        .iter()
+        .any(|keeper| keeper == sender.as_str())
+    {
+        return Ok(());
+    }
+
+    if let Expiration::AtTime(ready_at) = ready_at {
+        let window_end = ready_at.seconds() + allowlist.exclusivity_window;
+        if block.time.seconds() < window_end {
+            return Err(ContractError::KeeperExclusivityWindow {
+                seconds_remaining: window_end - block.time.seconds(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn query_keeper_allowlist(deps: Deps) -> StdResult<KeeperAllowlistResponse> {
+    Ok(KeeperAllowlistResponse {
+        allowlist: KEEPER_ALLOWLIST.may_load(deps.storage)?,
+    })
+}
@@ -1,19 +1,59 @@
 pub mod contract;
 pub mod state;
 
+mod anchor;
 #[cfg(test)]
 mod anchor_mock;
 #[cfg(test)]
 mod test_helpers;
 
+mod auto_claim;
+mod badges;
+mod buyback;
+mod campaigns;
+mod charity;
+mod claim_agent;
+mod claim_receipts;
+mod compliance;
+mod emission_decay;
+mod epoch_ops;
 mod error;
+mod events;
+mod exit;
+mod gift_cards;
+mod glow_reserve;
 mod helpers;
+mod hooks;
+mod ibc_claim;
+mod inbox;
+mod instant_withdrawal_cap;
+mod insurance;
 #[cfg(test)]
 mod integration_test;
+#[cfg(feature = "debug-invariants")]
+mod invariants;
+mod jackpot_floor;
+mod keeper_allowlist;
+mod migration;
 #[cfg(test)]
 mod mock_querier;
 mod oracle;
+mod payout_privacy;
+mod permit;
+mod pol;
+mod prize_caps;
+mod prize_rebalancing;
 mod prize_strategy;
+mod prize_templates;
 mod querier;
+mod rate_guard;
+mod receipt;
+mod savings;
+mod share_donation;
+mod sponsor_weight;
+mod syndicate;
 #[cfg(test)]
 mod tests;
+mod timelock;
+mod winners_root;
+mod withdrawal_queue;
@@ -0,0 +1,556 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    attr, Addr, Deps, DepsMut, Env, Order, Response, StdError, StdResult, Storage, Timestamp,
+};
+use cw_storage_plus::{Bound, U64Key};
+use glow_protocol::lotto::{
+    BoostConfig, DrawTimeline, IbcClaimConfig, LoyaltyConfig, MigrateMsg, MigrationStatusResponse,
+    MigrationStepResponse, MissedLotteryPolicy, PauseState, PrizeCursor, ReserveDestination,
+    RewardEmissionsIndex, SponsorLockConfig, TICKET_LENGTH,
+};
+use std::str::from_utf8;
+
+use crate::epoch_ops::init_epoch_operations;
+use crate::error::ContractError;
+use crate::querier::query_exchange_rate;
+use crate::state::{
+    old_read_depositors, old_read_lottery_info, old_remove_depositor_info, old_remove_lottery_info,
+    parse_length, read_lottery_info, store_depositor_info, store_depositor_tickets,
+    store_lottery_info, Config, ContractVersion, DepositorInfo, LotteryInfo, MigrationProgress,
+    Pool, State, CONFIG, CONTRACT_VERSION, LEGACY_LOTTERY_PAGES, MIGRATION_PROGRESS, OLDCONFIG,
+    OLDPOOL, OLDSTATE, OLD_PRIZES, POOL, PRIZES, STATE, TICKETS,
+};
+
+// Version of the storage layout written by this code
+pub const LATEST_CONTRACT_VERSION: u64 = 3;
+// Version of contracts deployed with the old storage layout, before versions were tracked
+pub const LEGACY_CONTRACT_VERSION: u64 = 1;
+// Version of contracts deployed with the current storage layout, before versions were tracked
+pub const UNTRACKED_CONTRACT_VERSION: u64 = 2;
+
+pub struct StepOutcome {
+    pub migrated_entries: u64,
+    pub completed: bool,
+}
+
+/// Paginated migration step, run through ExecuteMsg::MigrateStep
+/// after the migration to its version
+pub struct MigrationStep {
+    pub step_id: u64,
+    // Version whose storage layout the step completes
+    pub version: u64,
+    pub name: &'static str,
+    pub run: fn(DepsMut, &Env, Option<u32>) -> Result<StepOutcome, ContractError>,
+}
+
+// Steps must be ordered by version then step_id
+pub const MIGRATION_STEPS: &[MigrationStep] = &[MigrationStep {
+    step_id: 1,
+    version: 2,
+    name: "old_depositors",
+    run: migrate_old_depositors,
+}];
+
+pub fn read_contract_version(storage: &dyn Storage) -> StdResult<ContractVersion> {
+    if let Some(contract_version) = CONTRACT_VERSION.may_load(storage)? {
+        return Ok(contract_version);
+    }
+
+    // Without a contract version, the layout is told apart by the shape of the state
+    let version = if is_legacy_layout(storage) {
+        LEGACY_CONTRACT_VERSION
+    } else {
+        UNTRACKED_CONTRACT_VERSION
+    };
+
+    Ok(ContractVersion {
+        version,
+        target_version: version,
+    })
+}
+
+/// The legacy state only parses as OldState, which lacks the reward emission indexes of State
+fn is_legacy_layout(storage: &dyn Storage) -> bool {
+    STATE.load(storage).is_err() && OLDSTATE.load(storage).is_ok()
+}
+
+/// Steps of the migration in progress, in the order they have to be run
+fn migration_steps(
+    contract_version: &ContractVersion,
+) -> impl Iterator<Item = &'static MigrationStep> {
+    let (version, target_version) = (contract_version.version, contract_version.target_version);
+    MIGRATION_STEPS
+        .iter()
+        .filter(move |step| version < step.version && step.version <= target_version)
+}
+
+fn read_migration_progress(storage: &dyn Storage, step_id: u64) -> StdResult<MigrationProgress> {
+    Ok(MIGRATION_PROGRESS
+        .may_load(storage, U64Key::from(step_id))?
+        .unwrap_or_default())
+}
+
+pub fn has_pending_migration_steps(storage: &dyn Storage) -> StdResult<bool> {
+    let contract_version = read_contract_version(storage)?;
+    Ok(contract_version.version < contract_version.target_version)
+}
+
+/// Runs the migrations up to target_version. Storage changes that can't fit in a
+/// single transaction are registered as steps, and the contract stays paused
+/// until they are completed. Migrating again to the current target is a no-op.
+pub fn migrate_to(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let mut contract_version = read_contract_version(deps.storage)?;
+    let target_version = msg.target_version.unwrap_or(LATEST_CONTRACT_VERSION);
+
+    if target_version > LATEST_CONTRACT_VERSION || target_version < contract_version.target_version
+    {
+        return Err(ContractError::InvalidMigrationTarget(target_version));
+    }
+
+    if target_version == contract_version.target_version {
+        return Ok(Response::new().add_attributes(vec![
+            attr("action", "migrate"),
+            attr("version", contract_version.target_version.to_string()),
+        ]));
+    }
+
+    for version in contract_version.target_version + 1..=target_version {
+        if version == 2 {
+            migrate_to_v2(deps.branch(), &env, &msg)?;
+        }
+        if version == 3 {
+            migrate_to_v3(deps.branch(), &env)?;
+        }
+    }
+
+    contract_version.target_version = target_version;
+    if migration_steps(&contract_version).next().is_none() {
+        contract_version.version = target_version;
+    } else {
+        // Pause until the steps are completed
+        let mut config = CONFIG.load(deps.storage)?;
+        config.paused = true;
+        CONFIG.save(deps.storage, &config)?;
+    }
+    CONTRACT_VERSION.save(deps.storage, &contract_version)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate"),
+        attr("version", contract_version.version.to_string()),
+        attr("target_version", target_version.to_string()),
+    ]))
+}
+
+/// Runs a page of a migration step. Steps have to be completed in order and
+/// running a completed step is a no-op. The contract is unpaused once the
+/// last step is completed.
+pub fn execute_migrate_step(
+    mut deps: DepsMut,
+    env: Env,
+    step_id: u64,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let mut contract_version = read_contract_version(deps.storage)?;
+
+    let step = migration_steps(&contract_version)
+        .find(|step| step.step_id == step_id)
+        .ok_or(ContractError::MigrationStepNotPending(step_id))?;
+
+    let mut progress = read_migration_progress(deps.storage, step_id)?;
+    if progress.completed {
+        return Ok(Response::new().add_attributes(vec![
+            attr("action", "migrate_step"),
+            attr("step_id", step_id.to_string()),
+            attr("num_migrated_entries", "0"),
+            attr("completed", "true"),
+        ]));
+    }
+
+    // Earlier steps have to be completed first
+    for previous in migration_steps(&contract_version).take_while(|s| s.step_id != step_id) {
+        if !read_migration_progress(deps.storage, previous.step_id)?.completed {
+            return Err(ContractError::MigrationStepNotPending(step_id));
+        }
+    }
+
+    let outcome = (step.run)(deps.branch(), &env, limit)?;
+
+    progress.migrated_entries += outcome.migrated_entries;
+    progress.completed = outcome.completed;
+    MIGRATION_PROGRESS.save(deps.storage, U64Key::from(step_id), &progress)?;
+
+    if progress.completed
+        && migration_steps(&contract_version).last().map(|s| s.step_id) == Some(step_id)
+    {
+        contract_version.version = contract_version.target_version;
+        CONTRACT_VERSION.save(deps.storage, &contract_version)?;
+
+        let mut config = CONFIG.load(deps.storage)?;
+        config.paused = false;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate_step"),
+        attr("step_id", step_id.to_string()),
+        attr("num_migrated_entries", outcome.migrated_entries.to_string()),
+        attr("completed", progress.completed.to_string()),
+    ]))
+}
+
+pub fn query_migration_status(deps: Deps) -> StdResult<MigrationStatusResponse> {
+    let contract_version = read_contract_version(deps.storage)?;
+
+    let steps = migration_steps(&contract_version)
+        .map(|step| {
+            let progress = read_migration_progress(deps.storage, step.step_id)?;
+            Ok(MigrationStepResponse {
+                step_id: step.step_id,
+                version: step.version,
+                name: step.name.to_string(),
+                migrated_entries: progress.migrated_entries,
+                completed: progress.completed,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(MigrationStatusResponse {
+        version: contract_version.version,
+        target_version: contract_version.target_version,
+        steps,
+    })
+}
+
+fn migrate_to_v2(deps: DepsMut, env: &Env, msg: &MigrateMsg) -> Result<(), ContractError> {
+    // Migration Notes
+    // The changes to storage:
+    // - CONFIG (reuses storage key)
+    // - LOTTERIES (new storage key)
+    // - PRIZES (new storage key)
+    // - DEPOSITORS (new storage key, paginated migration)
+    // - STATE (reuses storage key)
+    // - POOL (reuses storage key)
+
+    let default_lotto_winner_boost_config: BoostConfig = BoostConfig {
+        base_multiplier: Decimal256::from_ratio(40u64, 100u64),
+        max_multiplier: Decimal256::one(),
+        total_voting_power_weight: Decimal256::percent(150),
+    };
+
+    let lotto_winner_boost_config =
+        if let Some(msg_lotto_winner_boost_config) = msg.lotto_winner_boost_config.clone() {
+            if msg_lotto_winner_boost_config.base_multiplier
+                > msg_lotto_winner_boost_config.max_multiplier
+            {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "boost config base multiplier must be less than max multiplier",
+                )));
+            }
+            msg_lotto_winner_boost_config
+        } else {
+            default_lotto_winner_boost_config
+        };
+
+    // migrate config
+    let old_config = OLDCONFIG.load(deps.as_ref().storage)?;
+    let new_config = Config {
+        owner: old_config.owner,
+        a_terra_contract: old_config.a_terra_contract,
+        gov_contract: old_config.gov_contract,
+        ve_contract: deps.api.addr_validate(msg.ve_contract.as_str())?,
+        community_contract: deps.api.addr_validate(msg.community_contract.as_str())?,
+        distributor_contract: old_config.distributor_contract,
+        oracle_contract: old_config.oracle_contract,
+        stable_denom: old_config.stable_denom,
+        anchor_contract: old_config.anchor_contract,
+        lottery_interval: old_config.lottery_interval,
+        epoch_interval: old_config.epoch_interval,
+        block_time: old_config.block_time,
+        round_delta: old_config.round_delta,
+        ticket_price: old_config.ticket_price,
+        max_holders: old_config.max_holders,
+        prize_distribution: old_config.prize_distribution,
+        target_award: old_config.target_award,
+        reserve_factor: old_config.reserve_factor,
+        split_factor: old_config.split_factor,
+        instant_withdrawal_fee: old_config.instant_withdrawal_fee,
+        unbonding_period: old_config.unbonding_period,
+        max_tickets_per_depositor: msg.max_tickets_per_depositor,
+        glow_prize_buckets: msg.glow_prize_buckets.clone(),
+        paused: true,
+        lotto_winner_boost_config,
+        reserve_destination: ReserveDestination::Community,
+        fee_distributor_contract: None,
+        lp_staking_contract: None,
+        lp_staking_reserve_share: Decimal256::zero(),
+        min_deposit_amount: Uint256::zero(),
+        loyalty_config: LoyaltyConfig::default(),
+        sponsor_lock_config: SponsorLockConfig::default(),
+        ibc_claim_config: IbcClaimConfig::default(),
+        operator_reward_vesting_epochs: 0,
+        pause_state: PauseState::default(),
+        guardians: vec![],
+        ticket_eligibility_delay: 0,
+        missed_lottery_policy: MissedLotteryPolicy::SkipToNext,
+        blacklist_dispute_window: 0,
+        time_weighted_odds: false,
+        ticket_length: TICKET_LENGTH as u8,
+        min_matches_for_prize: None,
+    };
+
+    CONFIG.save(deps.storage, &new_config)?;
+
+    // Query exchange_rate from anchor money market
+    let aust_exchange_rate: Decimal256 = query_exchange_rate(
+        deps.as_ref(),
+        deps.api
+            .addr_validate(new_config.anchor_contract.as_str())?
+            .to_string(),
+        env.block.height,
+    )?
+    .exchange_rate;
+
+    let old_state = OLDSTATE.load(deps.storage)?;
+
+    let state = State {
+        total_tickets: old_state.total_tickets,
+        total_reserve: old_state.total_reserve,
+        prize_buckets: old_state.prize_buckets,
+        current_lottery: old_state.current_lottery,
+        next_lottery_time: old_state.next_lottery_time,
+        next_lottery_exec_time: old_state.next_lottery_exec_time,
+        next_epoch: old_state.next_epoch,
+        operator_reward_emission_index: RewardEmissionsIndex {
+            global_reward_index: Decimal256::zero(),
+            glow_emission_rate: msg.operator_glow_emission_rate,
+            last_reward_updated: env.block.height,
+        },
+        sponsor_reward_emission_index: RewardEmissionsIndex {
+            global_reward_index: old_state.global_reward_index,
+            glow_emission_rate: msg.sponsor_glow_emission_rate,
+            last_reward_updated: old_state.last_reward_updated,
+        },
+        last_lottery_execution_aust_exchange_rate: aust_exchange_rate,
+        missed_lotteries: 0,
+        missed_lottery_prizes: Uint256::zero(),
+        yield_deficit: Uint256::zero(),
+    };
+
+    // The snapshot keeps the previous value in its changelog, which
+    // can't be read from the old state bytes under the same key
+    OLDSTATE.remove(deps.storage);
+    STATE.save(deps.storage, &state, env.block.height)?;
+
+    // Migrate pool
+    // Initially total_user_aust and total_user_shares are set to 0
+    // But they are updated in the migrate_old_depositors section of the loop
+    let old_pool = OLDPOOL.load(deps.as_ref().storage)?;
+    let new_pool = Pool {
+        total_user_aust: Uint256::zero(),
+        total_user_shares: Uint256::zero(),
+        total_sponsor_lottery_deposits: old_pool.total_sponsor_lottery_deposits,
+        total_operator_shares: Uint256::zero(),
+        total_sponsor_lock_bonus: Uint256::zero(),
+        total_sponsor_weight_bonus: Uint256::zero(),
+        total_pol_aust: Uint256::zero(),
+        total_pol_principal: Uint256::zero(),
+        total_savings_aust: Uint256::zero(),
+    };
+
+    OLDPOOL.remove(deps.storage);
+    POOL.save(deps.storage, &new_pool, env.block.height)?;
+
+    // Migrate prize info
+    let old_prizes = OLD_PRIZES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (mut k, v) = item?;
+
+            // https://github.com/CosmWasm/cw-plus/issues/466
+
+            // Gets the length prefix from the composite key
+            let mut tu = k.split_off(2);
+
+            // Calculate the size of the first key in the composite key
+            // using the length prefix
+            let t_len = parse_length(&k)?;
+
+            // Split tu into the first and second key.
+            // u is the second key, and tu is the first key
+            let u = tu.split_off(t_len);
+
+            // Extract address from the first key
+            let addr = Addr::unchecked(from_utf8(&tu)?);
+
+            // Extract the lottery id from the second key
+            let lottery_id = U64Key::from(u);
+
+            // Return
+            Ok((lottery_id, addr, v))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for old_prize in old_prizes {
+        let (lottery_id, addr, prize_info) = old_prize;
+        OLD_PRIZES.remove(deps.storage, (&addr, lottery_id.clone()));
+
+        PRIZES.save(deps.storage, (lottery_id, &addr), &prize_info)?;
+    }
+
+    Ok(())
+}
+
+fn migrate_to_v3(deps: DepsMut, env: &Env) -> Result<(), ContractError> {
+    // Migration Notes
+    // The changes to storage:
+    // - LOTTERIES (reuses storage key, the page string is replaced by the prize cursor)
+    // - EPOCH_OPERATIONS (new, every operation is first due at the next_epoch of the state)
+    // - POOL (total_pol_principal is new, the liquidity was valued at the last lottery rate)
+    //
+    // Only the current lottery can have a prize execution in progress.
+    // The page of awarded lotteries is ignored when they are loaded.
+
+    let state = STATE.load(deps.storage)?;
+    init_epoch_operations(deps.storage, state.next_epoch)?;
+
+    let mut pool = POOL.load(deps.storage)?;
+    if pool.total_pol_principal.is_zero() && !pool.total_pol_aust.is_zero() {
+        pool.total_pol_principal =
+            pool.total_pol_aust * state.last_lottery_execution_aust_exchange_rate;
+        POOL.save(deps.storage, &pool, env.block.height)?;
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let lottery_id = U64Key::from(state.current_lottery);
+
+    let page = match LEGACY_LOTTERY_PAGES.may_load(deps.storage, lottery_id)? {
+        Some(legacy) if !legacy.page.is_empty() => legacy.page,
+        _ => return Ok(()),
+    };
+
+    let mut lottery_info = read_lottery_info(deps.storage, state.current_lottery);
+    if lottery_info.awarded {
+        return Ok(());
+    }
+
+    // The page was the first ticket of the next page, so the tickets
+    // of the winning range before it have already been processed
+    let tier = config.min_matches_for_prize()?;
+    let processed_tickets = || {
+        TICKETS.keys(
+            deps.storage,
+            Some(Bound::Inclusive(Vec::from(&lottery_info.sequence[..tier]))),
+            Some(Bound::Exclusive(Vec::from(page.as_str()))),
+            Order::Descending,
+        )
+    };
+
+    let prize_cursor = PrizeCursor {
+        tier: tier as u8,
+        last_ticket: processed_tickets()
+            .next()
+            .map(|ticket| String::from_utf8_lossy(&ticket).to_string()),
+        processed_count: processed_tickets().count() as u64,
+    };
+    lottery_info.prize_cursor = prize_cursor;
+    store_lottery_info(deps.storage, state.current_lottery, &lottery_info)?;
+
+    Ok(())
+}
+
+/// Moves a page of depositors to the new storage layout, along with the
+/// lottery infos once no old depositor is left
+fn migrate_old_depositors(
+    deps: DepsMut,
+    env: &Env,
+    limit: Option<u32>,
+) -> Result<StepOutcome, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let aust_exchange_rate: Decimal256 = query_exchange_rate(
+        deps.as_ref(),
+        deps.api
+            .addr_validate(config.anchor_contract.as_str())?
+            .to_string(),
+        env.block.height,
+    )?
+    .exchange_rate;
+
+    let old_depositors = old_read_depositors(deps.as_ref(), None, limit)?;
+
+    let mut num_migrated_entries: u32 = 0;
+
+    let mut pool = POOL.load(deps.storage)?;
+
+    for (addr, old_depositor_info) in old_depositors {
+        // Delete old depositor
+        old_remove_depositor_info(deps.storage, &addr);
+
+        // Get the depositors balance, add the value of the savings aust with the lottery_deposit
+        // Then at the end there will be some left over aust.
+        // This will be captured by the sponsors.
+        let depositor_aust_balance = old_depositor_info.savings_aust
+            + old_depositor_info.lottery_deposit / aust_exchange_rate;
+
+        store_depositor_tickets(deps.storage, &addr, &old_depositor_info.tickets)?;
+        let new_depositor_info = DepositorInfo {
+            shares: depositor_aust_balance,
+            num_tickets: old_depositor_info.tickets.len(),
+            unbonding_info: old_depositor_info.unbonding_info,
+            operator_addr: Addr::unchecked(""),
+        };
+
+        pool.total_user_shares += depositor_aust_balance;
+        pool.total_user_aust += depositor_aust_balance;
+
+        // Store new depositor
+        store_depositor_info(deps.storage, &addr, new_depositor_info, env.block.height)?;
+
+        // Increment num_migrates_entries
+        num_migrated_entries += 1;
+    }
+
+    let completed = old_read_depositors(deps.as_ref(), None, Some(1))?.is_empty();
+    if completed {
+        // Migrate lottery info
+
+        let state = STATE.load(deps.storage)?;
+        let config = CONFIG.load(deps.storage)?;
+
+        // Don't need to include state.current_lottery
+        // because nothing has been saved with id state.current_lottery yet
+        for i in 0..state.current_lottery {
+            let old_lottery_info = old_read_lottery_info(deps.storage, i);
+
+            let new_lottery_info = LotteryInfo {
+                rand_round: old_lottery_info.rand_round,
+                sequence: old_lottery_info.sequence,
+                awarded: old_lottery_info.awarded,
+                timestamp: Timestamp::from_seconds(0),
+                prize_buckets: old_lottery_info.prize_buckets,
+                number_winners: old_lottery_info.number_winners,
+                prize_cursor: PrizeCursor::default(),
+                glow_prize_buckets: vec![Uint256::zero(); config.num_prize_buckets()],
+                block_height: old_lottery_info.timestamp,
+                total_user_shares: pool.total_user_shares,
+                draw_timeline: DrawTimeline::default(),
+                loyalty_weights: None,
+                time_weight_window: None,
+                funded_glow_prize_buckets: None,
+                glow_prize_shortfall: Uint256::zero(),
+                winners_root: None,
+                prize_caps: None,
+            };
+
+            store_lottery_info(deps.storage, i, &new_lottery_info)?;
+
+            old_remove_lottery_info(deps.storage, i);
+        }
+    }
+
+    POOL.save(deps.storage, &pool, env.block.height)?;
+
+    Ok(StepOutcome {
+        migrated_entries: num_migrated_entries as u64,
+        completed,
+    })
+}
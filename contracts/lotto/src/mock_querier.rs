@@ -1,3 +1,4 @@
+use glow_protocol::gov::VotingPowerResponse;
 use glow_protocol::ve_token::{StakerResponse, StateResponse};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -12,7 +13,9 @@ use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg};
 use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute};
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use glow_protocol::distributor::GlowEmissionRateResponse;
+use glow_protocol::distributor::{
+    ConfigResponse as DistributorConfigResponse, GlowEmissionRateResponse,
+};
 use moneymarket::market::EpochStateResponse;
 use std::collections::HashMap;
 
@@ -21,6 +24,9 @@ use crate::tests::RATE;
 use crate::oracle::OracleResponse;
 
 pub const MOCK_CONTRACT_ADDR: &str = "cosmos2contract";
+pub const MOCK_GLOW_TOKEN: &str = "glow_token";
+pub const MOCK_ANCHOR_ADDR: &str = "anchor";
+pub const MOCK_ANCHOR_BALANCE: u128 = 1_000_000_000_000_000_000;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -51,9 +57,17 @@ pub enum QueryMsg {
         timestamp: Option<u64>,
     },
 
+    VotingPower {
+        address: String,
+        timestamp: Option<u64>,
+    },
+
     GetRandomness {
         round: u64,
     },
+
+    /// Query distributor config for the GLOW token
+    Config {},
 }
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
@@ -61,8 +75,15 @@ pub enum QueryMsg {
 pub fn mock_dependencies(
     contract_balance: &[Coin],
 ) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
-    let mut custom_querier: WasmMockQuerier =
-        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+    // The mock anchor market has enough stable to redeem any withdrawal
+    let anchor_balance = [Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(MOCK_ANCHOR_BALANCE),
+    }];
+    let mut custom_querier: WasmMockQuerier = WasmMockQuerier::new(MockQuerier::new(&[
+        (MOCK_CONTRACT_ADDR, contract_balance),
+        (MOCK_ANCHOR_ADDR, &anchor_balance),
+    ]));
 
     // Mock aUST-UST exchange rate
     custom_querier.with_exchange_rate(Decimal256::permille(RATE));
@@ -102,6 +123,7 @@ pub struct WasmMockQuerier {
     tax_querier: TaxQuerier,
     exchange_rate_querier: ExchangeRateQuerier,
     emission_rate_querier: EmissionRateQuerier, //TODO: use in tests and replace _ for EmissionRateQuerier
+    distributor_glow_balance: Uint128,
 }
 
 #[derive(Clone, Default)]
@@ -258,6 +280,19 @@ impl WasmMockQuerier {
                         })))
                     }
 
+                    QueryMsg::Config {} => SystemResult::Ok(ContractResult::from(to_binary(
+                        &DistributorConfigResponse {
+                            owner: MOCK_CONTRACT_ADDR.to_string(),
+                            glow_token: MOCK_GLOW_TOKEN.to_string(),
+                            whitelist: vec![],
+                            spend_limit: Uint128::zero(),
+                            emission_cap: Decimal256::zero(),
+                            emission_floor: Decimal256::zero(),
+                            increment_multiplier: Decimal256::zero(),
+                            decrement_multiplier: Decimal256::zero(),
+                        },
+                    ))),
+
                     QueryMsg::Staker { address, .. } => {
                         let balances: &HashMap<String, Uint128> =
                             match self.token_querier.balances.get(contract_addr) {
@@ -295,6 +330,33 @@ impl WasmMockQuerier {
                         ))
                     }
 
+                    QueryMsg::VotingPower { address, .. } => {
+                        let balances: &HashMap<String, Uint128> =
+                            match self.token_querier.balances.get(contract_addr) {
+                                Some(balances) => balances,
+                                None => {
+                                    return SystemResult::Err(SystemError::InvalidRequest {
+                                        error: format!(
+                                            "No balance info exists for the contract {}",
+                                            contract_addr
+                                        ),
+                                        request: msg.as_slice().into(),
+                                    })
+                                }
+                            };
+
+                        let balance = balances.get(&address).copied().unwrap_or_default();
+
+                        SystemResult::Ok(ContractResult::Ok(
+                            to_binary(&VotingPowerResponse {
+                                balance,
+                                delegated_balance: Uint128::zero(),
+                                voting_power: balance,
+                            })
+                            .unwrap(),
+                        ))
+                    }
+
                     QueryMsg::State { .. } => {
                         let balances: &HashMap<String, Uint128> =
                             match self.token_querier.balances.get(contract_addr) {
@@ -324,6 +386,15 @@ impl WasmMockQuerier {
                     }
 
                     _ => match from_binary::<Cw20QueryMsg>(msg).unwrap() {
+                        // The only GLOW holder queried by lotto is the distributor
+                        Cw20QueryMsg::Balance { .. } if contract_addr == MOCK_GLOW_TOKEN => {
+                            SystemResult::Ok(ContractResult::Ok(
+                                to_binary(&Cw20BalanceResponse {
+                                    balance: self.distributor_glow_balance,
+                                })
+                                .unwrap(),
+                            ))
+                        }
                         Cw20QueryMsg::Balance { address } => {
                             let balances: &HashMap<String, Uint128> =
                                 match self.token_querier.balances.get(contract_addr) {
@@ -373,6 +444,8 @@ impl WasmMockQuerier {
             tax_querier: TaxQuerier::default(),
             exchange_rate_querier: ExchangeRateQuerier::default(),
             emission_rate_querier: EmissionRateQuerier::default(),
+            // Enough GLOW to fund any configured glow prize buckets
+            distributor_glow_balance: Uint128::from(u64::MAX),
         }
     }
 
@@ -428,6 +501,11 @@ impl WasmMockQuerier {
         self.exchange_rate_querier = ExchangeRateQuerier::new(rate);
     }
 
+    // configure the GLOW balance of the distributor
+    pub fn with_distributor_glow_balance(&mut self, balance: Uint128) {
+        self.distributor_glow_balance = balance;
+    }
+
     // configure glow emission rate
     #[allow(dead_code)] //TODO: Use in tests
     pub fn with_emission_rate(&mut self, rate: Decimal256) {
@@ -1,5 +1,4 @@
 use cosmwasm_std::{Addr, Binary, Env};
-use glow_protocol::lotto::TICKET_LENGTH;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -12,8 +11,8 @@ pub fn calculate_lottery_rand_round(env: Env, round_delta: u64) -> u64 {
     current_round + round_delta // make round delta as config param
 }
 
-pub fn sequence_from_hash(hash: String) -> String {
-    let seq = &hash[2..TICKET_LENGTH + 2];
+pub fn sequence_from_hash(hash: String, ticket_length: usize) -> String {
+    let seq = &hash[2..ticket_length + 2];
     seq.to_string()
 }
 
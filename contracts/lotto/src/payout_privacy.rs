@@ -0,0 +1,67 @@
+use cosmwasm_std::{attr, Addr, Deps, DepsMut, MessageInfo, Response, StdResult};
+use glow_protocol::lotto::{PayoutHashResponse, PayoutReveal};
+use sha3::{Digest, Keccak256};
+
+use crate::compliance::assert_not_blacklisted;
+use crate::error::ContractError;
+use crate::state::PAYOUT_HASHES;
+
+/// Hex encoded keccak256 hash of "{salt}:{address}", registered by a depositor
+/// so their prizes can be paid to address without linking it beforehand
+pub fn payout_hash(salt: &str, address: &str) -> String {
+    hex::encode(Keccak256::digest(
+        format!("{}:{}", salt, address).as_bytes(),
+    ))
+}
+
+pub fn execute_register_payout_hash(
+    deps: DepsMut,
+    info: MessageInfo,
+    payout_hash: String,
+) -> Result<Response, ContractError> {
+    let hash = match hex::decode(&payout_hash) {
+        Ok(hash) if hash.len() == 32 => hash,
+        _ => return Err(ContractError::InvalidPayoutHash {}),
+    };
+
+    PAYOUT_HASHES.save(deps.storage, &info.sender, &hash)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_payout_hash"),
+        attr("depositor", info.sender.to_string()),
+        attr("payout_hash", hex::encode(hash)),
+    ]))
+}
+
+/// Checks the reveal against the hash registered by winner and returns the
+/// revealed payout address. The hash is removed, so each one pays out once.
+/// Only the recipient changes: prizes, boosts and ledgers stay keyed to winner
+pub fn take_payout_address(
+    deps: DepsMut,
+    winner: &Addr,
+    reveal: &PayoutReveal,
+) -> Result<Addr, ContractError> {
+    let hash = PAYOUT_HASHES
+        .may_load(deps.storage, winner)?
+        .ok_or_else(|| ContractError::PayoutHashNotRegistered(winner.to_string()))?;
+
+    if hex::encode(hash) != payout_hash(&reveal.salt, &reveal.address) {
+        return Err(ContractError::InvalidPayoutReveal {});
+    }
+
+    let address = deps.api.addr_validate(&reveal.address)?;
+    assert_not_blacklisted(deps.storage, &address)?;
+
+    PAYOUT_HASHES.remove(deps.storage, winner);
+
+    Ok(address)
+}
+
+pub fn query_payout_hash(deps: Deps, depositor: String) -> StdResult<PayoutHashResponse> {
+    let depositor = deps.api.addr_validate(&depositor)?;
+    let payout_hash = PAYOUT_HASHES
+        .may_load(deps.storage, &depositor)?
+        .map(hex::encode);
+
+    Ok(PayoutHashResponse { payout_hash })
+}
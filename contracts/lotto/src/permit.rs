@@ -0,0 +1,265 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{
+    attr, coin, to_vec, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    StdResult, Storage,
+};
+use glow_protocol::lotto::{DepositPermit, EscrowResponse, GlowHookMsg};
+use glow_protocol::querier::deduct_tax;
+use serde::Serialize;
+use sha3::{Digest, Keccak256};
+
+use crate::anchor::deposit_stable_msg;
+use crate::compliance::assert_not_blacklisted;
+use crate::contract::process_deposit;
+use crate::error::ContractError;
+use crate::events::deposit_event;
+use crate::hooks::hook_submsgs;
+use crate::state::{read_lottery_info, Escrow, CONFIG, ESCROWS, STATE, TOTAL_ESCROWED};
+
+/// Payload signed by the depositor, binding the permit to this contract and chain
+#[derive(Serialize)]
+struct PermitPayload<'a> {
+    chain_id: &'a str,
+    contract: &'a str,
+    permit: &'a DepositPermit,
+}
+
+/// Json encoded permit payload, whose keccak256 hash is what gets signed
+pub fn permit_payload(env: &Env, permit: &DepositPermit) -> StdResult<Vec<u8>> {
+    to_vec(&PermitPayload {
+        chain_id: &env.block.chain_id,
+        contract: env.contract.address.as_str(),
+        permit,
+    })
+}
+
+pub fn read_total_escrowed(storage: &dyn Storage) -> StdResult<Uint256> {
+    Ok(TOTAL_ESCROWED.may_load(storage)?.unwrap_or_default())
+}
+
+/// Adds the sent stable to the escrow of the sender, registering the public key
+/// permits have to be signed with. Setting a new key invalidates the permits
+/// signed with the previous one.
+pub fn execute_fund_escrow(
+    deps: DepsMut,
+    info: MessageInfo,
+    pubkey: Option<Binary>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let amount = info
+        .funds
+        .iter()
+        .find(|c| c.denom == config.stable_denom)
+        .map(|c| Uint256::from(c.amount))
+        .unwrap_or_else(Uint256::zero);
+
+    // compressed or uncompressed secp256k1 public key
+    if let Some(pubkey) = &pubkey {
+        if pubkey.len() != 33 && pubkey.len() != 65 {
+            return Err(ContractError::InvalidEscrowPubkey {});
+        }
+    }
+
+    let mut escrow = match (ESCROWS.may_load(deps.storage, &info.sender)?, pubkey) {
+        (Some(mut escrow), Some(pubkey)) => {
+            escrow.pubkey = pubkey;
+            escrow
+        }
+        (None, Some(pubkey)) => Escrow {
+            pubkey,
+            balance: Uint256::zero(),
+            nonce: 0,
+        },
+        (Some(escrow), None) => escrow,
+        (None, None) => return Err(ContractError::EscrowPubkeyMissing {}),
+    };
+
+    escrow.balance += amount;
+    ESCROWS.save(deps.storage, &info.sender, &escrow)?;
+    let total_escrowed = read_total_escrowed(deps.storage)? + amount;
+    TOTAL_ESCROWED.save(deps.storage, &total_escrowed)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "fund_escrow"),
+        attr("owner", info.sender.to_string()),
+        attr("amount", amount.to_string()),
+        attr("escrow_balance", escrow.balance.to_string()),
+    ]))
+}
+
+/// Sends amount of stable, or the whole balance if not set, from the escrow of the sender
+pub fn execute_withdraw_escrow(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Option<Uint256>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut escrow = ESCROWS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or_else(|| ContractError::EscrowNotFound(info.sender.to_string()))?;
+
+    let amount = amount.unwrap_or(escrow.balance);
+    if amount.is_zero() {
+        return Err(ContractError::ZeroEscrowWithdrawal {});
+    }
+    if amount > escrow.balance {
+        return Err(ContractError::InsufficientEscrowBalance {
+            amount,
+            escrow_balance: escrow.balance,
+        });
+    }
+
+    escrow.balance -= amount;
+    ESCROWS.save(deps.storage, &info.sender, &escrow)?;
+    let total_escrowed = read_total_escrowed(deps.storage)? - amount;
+    TOTAL_ESCROWED.save(deps.storage, &total_escrowed)?;
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                coin(amount.into(), config.stable_denom),
+            )?],
+        }))
+        .add_attributes(vec![
+            attr("action", "withdraw_escrow"),
+            attr("owner", info.sender.to_string()),
+            attr("amount", amount.to_string()),
+            attr("escrow_balance", escrow.balance.to_string()),
+        ]))
+}
+
+/// Deposits from the escrow of the permit depositor, on behalf of whoever relays
+/// the signed permit. The relayer pays the fees and gets nothing from the deposit.
+pub fn execute_deposit_with_permit(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    permit: DepositPermit,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+
+    // Validate that the lottery has not already started
+    let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
+    if current_lottery.rand_round != 0 {
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
+    }
+
+    if permit.expires <= env.block.time.seconds() {
+        return Err(ContractError::PermitExpired(permit.expires));
+    }
+
+    let depositor = deps.api.addr_validate(&permit.depositor)?;
+    assert_not_blacklisted(deps.storage, &depositor)?;
+
+    let mut escrow = ESCROWS
+        .may_load(deps.storage, &depositor)?
+        .ok_or_else(|| ContractError::EscrowNotFound(depositor.to_string()))?;
+
+    if permit.nonce != escrow.nonce {
+        return Err(ContractError::InvalidPermitNonce {
+            nonce: permit.nonce,
+            expected: escrow.nonce,
+        });
+    }
+
+    let digest = Keccak256::digest(&permit_payload(&env, &permit)?);
+    let verified = deps
+        .api
+        .secp256k1_verify(&digest, &signature, &escrow.pubkey)
+        .unwrap_or(false);
+    if !verified {
+        return Err(ContractError::InvalidPermitSignature {});
+    }
+
+    let deposit_amount = permit.amount;
+    if deposit_amount.is_zero() {
+        return Err(ContractError::ZeroDepositAmount {});
+    }
+    if deposit_amount < config.min_deposit_amount {
+        return Err(ContractError::DepositBelowMinimum {
+            amount: deposit_amount,
+            min_deposit_amount: config.min_deposit_amount,
+        });
+    }
+    if deposit_amount > escrow.balance {
+        return Err(ContractError::InsufficientEscrowBalance {
+            amount: deposit_amount,
+            escrow_balance: escrow.balance,
+        });
+    }
+
+    // The permit can't be replayed once used
+    escrow.nonce += 1;
+    escrow.balance -= deposit_amount;
+    ESCROWS.save(deps.storage, &depositor, &escrow)?;
+    let total_escrowed = read_total_escrowed(deps.storage)? - deposit_amount;
+    TOTAL_ESCROWED.save(deps.storage, &total_escrowed)?;
+
+    // Deduct tx taxes when calculating the net deposited amount in anchor
+    let post_tax_deposit_amount = Uint256::from(
+        deduct_tax(
+            deps.as_ref(),
+            coin(deposit_amount.into(), config.stable_denom.clone()),
+        )?
+        .amount,
+    );
+
+    let (number_of_new_tickets, minted_aust) = process_deposit(
+        deps.branch(),
+        &env,
+        &config,
+        &depositor,
+        post_tax_deposit_amount,
+        permit.encoded_tickets,
+        permit.operator,
+    )?;
+
+    Ok(Response::new()
+        .add_message(deposit_stable_msg(&config, post_tax_deposit_amount)?)
+        .add_submessages(hook_submsgs(
+            deps.storage,
+            GlowHookMsg::OnDeposit {
+                depositor: depositor.to_string(),
+                recipient: depositor.to_string(),
+                amount: deposit_amount,
+                tickets: number_of_new_tickets,
+            },
+        )?)
+        .add_event(deposit_event(
+            &depositor,
+            &depositor,
+            deposit_amount,
+            post_tax_deposit_amount,
+            number_of_new_tickets,
+            minted_aust,
+        ))
+        .add_attributes(vec![
+            attr("action", "deposit_with_permit"),
+            attr("relayer", info.sender.to_string()),
+            attr("depositor", depositor.to_string()),
+            attr("nonce", permit.nonce.to_string()),
+            attr("deposit_amount", deposit_amount.to_string()),
+            attr("tickets", number_of_new_tickets.to_string()),
+            attr("aust_minted", minted_aust.to_string()),
+        ]))
+}
+
+pub fn query_escrow(deps: Deps, address: String) -> StdResult<EscrowResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let escrow = ESCROWS.may_load(deps.storage, &address)?;
+
+    Ok(EscrowResponse {
+        address: address.to_string(),
+        pubkey: escrow.as_ref().map(|e| e.pubkey.clone()),
+        balance: escrow.as_ref().map(|e| e.balance).unwrap_or_default(),
+        nonce: escrow.map(|e| e.nonce).unwrap_or_default(),
+    })
+}
@@ -0,0 +1,155 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{attr, coin, BankMsg, CosmosMsg, DepsMut, Env, MessageInfo, Response};
+use glow_protocol::querier::deduct_tax;
+
+use crate::anchor::{deposit_stable_msg, redeem_stable_msg};
+use crate::error::ContractError;
+use crate::querier::query_exchange_rate;
+use crate::state::{read_lottery_info, CONFIG, POOL, STATE};
+
+/// Deposit the sent stable as protocol owned liquidity - restricted to community.
+/// The deposit holds no tickets and earns no GLOW, its whole yield is redeemed for
+/// the prizes at each lottery
+pub fn execute_pol_deposit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut pool = POOL.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.community_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let deposit_amount = info
+        .funds
+        .iter()
+        .find(|c| c.denom == config.stable_denom)
+        .map(|c| Uint256::from(c.amount))
+        .unwrap_or_else(Uint256::zero);
+
+    if deposit_amount.is_zero() {
+        return Err(ContractError::ZeroDepositAmount {});
+    }
+
+    // Deduct tx taxes when calculating the net deposited amount in anchor
+    let post_tax_deposit_amount = Uint256::from(
+        deduct_tax(
+            deps.as_ref(),
+            coin(deposit_amount.into(), config.stable_denom.clone()),
+        )?
+        .amount,
+    );
+
+    let aust_exchange_rate = query_exchange_rate(
+        deps.as_ref(),
+        config.anchor_contract.to_string(),
+        env.block.height,
+    )?
+    .exchange_rate;
+    let minted_aust = post_tax_deposit_amount / aust_exchange_rate;
+
+    pool.total_pol_aust += minted_aust;
+    pool.total_pol_principal += minted_aust * aust_exchange_rate;
+    POOL.save(deps.storage, &pool, env.block.height)?;
+
+    Ok(Response::new()
+        .add_message(deposit_stable_msg(&config, post_tax_deposit_amount)?)
+        .add_attributes(vec![
+            attr("action", "pol_deposit"),
+            attr("deposit_amount", deposit_amount),
+            attr("minted_aust", minted_aust),
+        ]))
+}
+
+/// Withdraw amount of the protocol owned liquidity to the community contract, or all
+/// of it if not set - restricted to community. The liquidity is valued at its principal,
+/// the yield above it going to the prizes, or at its current value when below it
+pub fn execute_pol_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Option<Uint256>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+    let mut pool = POOL.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.community_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Validate that there isn't a lottery in progress
+    let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
+    if current_lottery.rand_round != 0 {
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
+    }
+
+    let aust_exchange_rate = query_exchange_rate(
+        deps.as_ref(),
+        config.anchor_contract.to_string(),
+        env.block.height,
+    )?
+    .exchange_rate;
+
+    // Value the liquidity at the lower of its principal and current value
+    let pol_value = pool.total_pol_aust * aust_exchange_rate;
+    let pol_balance = if pol_value < pool.total_pol_principal {
+        pol_value
+    } else {
+        pool.total_pol_principal
+    };
+
+    let amount = amount.unwrap_or(pol_balance);
+    if amount.is_zero() {
+        return Err(ContractError::ZeroPolWithdrawal {});
+    }
+    if amount > pol_balance {
+        return Err(ContractError::InsufficientPolBalance {
+            amount,
+            balance: pol_balance,
+        });
+    }
+
+    // Only the withdrawn amount is redeemed. The yield stays in the pool and is
+    // redeemed for the prizes at the next lottery, a loss below the principal is
+    // realized when withdrawing everything
+    let aust_to_redeem = amount / aust_exchange_rate;
+    let aust_to_redeem_value = aust_to_redeem * aust_exchange_rate;
+
+    pool.total_pol_aust -= aust_to_redeem;
+    pool.total_pol_principal = if amount == pol_balance {
+        Uint256::zero()
+    } else {
+        pool.total_pol_principal - amount
+    };
+    POOL.save(deps.storage, &pool, env.block.height)?;
+
+    // Discount tx taxes from Anchor to Glow
+    let coin_amount = deduct_tax(
+        deps.as_ref(),
+        coin(aust_to_redeem_value.into(), config.stable_denom.clone()),
+    )?;
+
+    // Discount tx taxes from Glow to community
+    let net_coin_amount = deduct_tax(deps.as_ref(), coin_amount)?;
+
+    let msgs: Vec<CosmosMsg> = vec![
+        redeem_stable_msg(&config, aust_to_redeem)?,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: config.community_contract.to_string(),
+            amount: vec![net_coin_amount],
+        }),
+    ];
+
+    Ok(Response::new().add_messages(msgs).add_attributes(vec![
+        attr("action", "pol_withdraw"),
+        attr("redeem_amount_anchor", aust_to_redeem),
+        attr("redeem_stable_amount", aust_to_redeem_value),
+    ]))
+}
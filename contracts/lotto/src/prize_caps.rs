@@ -0,0 +1,104 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{attr, Deps, DepsMut, MessageInfo, Response, StdResult, Storage};
+use glow_protocol::lotto::{PrizeCap, PrizeCapsResponse};
+
+use crate::error::ContractError;
+use crate::state::{LotteryInfo, CONFIG, PRIZE_CAPS, STATE};
+
+/// Set the caps on the UST awarded by each prize bucket, or remove them
+/// if None. Lotteries executed before keep their caps - restricted to gov
+pub fn execute_update_prize_caps(
+    deps: DepsMut,
+    info: MessageInfo,
+    prize_caps: Option<Vec<PrizeCap>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match prize_caps {
+        Some(prize_caps) => {
+            if prize_caps.len() != config.num_prize_buckets()
+                || prize_caps.iter().any(|cap| {
+                    cap.per_winner == Some(Uint256::zero()) || cap.per_tier == Some(Uint256::zero())
+                })
+            {
+                return Err(ContractError::InvalidPrizeCaps {});
+            }
+            PRIZE_CAPS.save(deps.storage, &prize_caps)?;
+        }
+        None => PRIZE_CAPS.remove(deps.storage),
+    }
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_prize_caps")]))
+}
+
+/// Most the bucket at index of the lottery awards to its winners
+pub fn tier_prize_cap(lottery_info: &LotteryInfo, index: usize, winners: u32) -> Option<Uint256> {
+    let cap = lottery_info.prize_caps.as_ref()?.get(index)?;
+    let per_winner_cap = cap
+        .per_winner
+        .map(|per_winner| per_winner * Uint256::from(winners as u64));
+
+    match (cap.per_tier, per_winner_cap) {
+        (Some(per_tier), Some(per_winner_cap)) => Some(std::cmp::min(per_tier, per_winner_cap)),
+        (per_tier, per_winner_cap) => per_tier.or(per_winner_cap),
+    }
+}
+
+/// Most a single winner of the bucket at index of the lottery receives
+pub fn winner_prize_cap(lottery_info: &LotteryInfo, index: usize) -> Option<Uint256> {
+    lottery_info.prize_caps.as_ref()?.get(index)?.per_winner
+}
+
+/// Splits a prize bucket into the prize awarded with the tier cap, its reserve fee
+/// and the excess rolling over to the next lottery. The reserve fee is only taken
+/// from the part of the bucket that is awarded
+pub fn split_capped_prize_bucket(
+    prize_bucket: Uint256,
+    reserve_factor: Decimal256,
+    cap: Option<Uint256>,
+) -> (Uint256, Uint256, Uint256) {
+    let reserve_fee = prize_bucket * reserve_factor;
+    let awarded_prize = prize_bucket - reserve_fee;
+
+    match cap {
+        Some(cap) if awarded_prize > cap => {
+            // The bucket share which awards cap once the reserve fee is deducted
+            let awarded_bucket = std::cmp::min(
+                prize_bucket,
+                std::cmp::max(cap, cap / (Decimal256::one() - reserve_factor)),
+            );
+            (cap, awarded_bucket - cap, prize_bucket - awarded_bucket)
+        }
+        _ => (awarded_prize, reserve_fee, Uint256::zero()),
+    }
+}
+
+/// Adds the prize excess of a winner over their cap back to the prize buckets
+pub fn roll_over_prize_excess(
+    storage: &mut dyn Storage,
+    block_height: u64,
+    excess: &[Uint256],
+) -> StdResult<()> {
+    if excess.iter().all(|excess| excess.is_zero()) {
+        return Ok(());
+    }
+
+    let mut state = STATE.load(storage)?;
+    for (index, excess) in excess.iter().enumerate() {
+        if let Some(prize_bucket) = state.prize_buckets.get_mut(index) {
+            *prize_bucket += *excess;
+        }
+    }
+    STATE.save(storage, &state, block_height)
+}
+
+pub fn query_prize_caps(deps: Deps) -> StdResult<PrizeCapsResponse> {
+    Ok(PrizeCapsResponse {
+        prize_caps: PRIZE_CAPS.may_load(deps.storage)?,
+    })
+}
@@ -0,0 +1,281 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{attr, Attribute, Deps, DepsMut, MessageInfo, Response, StdResult, Storage};
+use glow_protocol::lotto::{PrizeRebalancingConfig, PrizeRebalancingResponse};
+
+use crate::error::ContractError;
+use crate::helpers::{validate_min_matches_for_prize, validate_prize_distribution};
+use crate::state::{
+    LotteryInfo, PrizeRebalancing, PrizeTierStats, CONFIG, PRIZE_REBALANCING, PRIZE_TIER_STATS,
+    STATE,
+};
+
+fn validate_rebalancing(
+    rebalancing: &PrizeRebalancingConfig,
+    num_prize_buckets: usize,
+) -> Result<(), ContractError> {
+    if rebalancing.interval == 0 || rebalancing.max_step.is_zero() {
+        return Err(ContractError::InvalidPrizeRebalancing {});
+    }
+
+    if rebalancing.min_distribution.len() != num_prize_buckets
+        || rebalancing.max_distribution.len() != num_prize_buckets
+        || rebalancing.target_payouts.len() != num_prize_buckets
+    {
+        return Err(ContractError::InvalidPrizeRebalancing {});
+    }
+
+    let mut min_sum = Decimal256::zero();
+    let mut max_sum = Decimal256::zero();
+    for index in 0..num_prize_buckets {
+        let min = rebalancing.min_distribution[index];
+        let max = rebalancing.max_distribution[index];
+        if min > max || max > Decimal256::one() {
+            return Err(ContractError::InvalidPrizeRebalancing {});
+        }
+        min_sum += min;
+        max_sum += max;
+    }
+
+    // A distribution summing to 1 must fit within the bounds
+    if min_sum > Decimal256::one() || max_sum < Decimal256::one() {
+        return Err(ContractError::InvalidPrizeRebalancing {});
+    }
+
+    Ok(())
+}
+
+/// Set the bounds of the prize_distribution re-balancing, or disable it
+/// if None. Lotteries are counted from the current one - restricted to gov
+pub fn execute_update_prize_rebalancing(
+    deps: DepsMut,
+    info: MessageInfo,
+    rebalancing: Option<PrizeRebalancingConfig>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match rebalancing {
+        Some(rebalancing) => {
+            validate_rebalancing(&rebalancing, config.num_prize_buckets())?;
+            let state = STATE.load(deps.storage)?;
+            PRIZE_REBALANCING.save(
+                deps.storage,
+                &PrizeRebalancing {
+                    config: rebalancing,
+                    last_rebalanced_lottery: state.current_lottery,
+                },
+            )?;
+        }
+        None => PRIZE_REBALANCING.remove(deps.storage),
+    }
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_prize_rebalancing")]))
+}
+
+/// Adds the winners and prizes of an awarded lottery to the tier stats
+pub fn record_tier_stats(storage: &mut dyn Storage, lottery_info: &LotteryInfo) -> StdResult<()> {
+    let mut stats = PRIZE_TIER_STATS.may_load(storage)?.unwrap_or_default();
+
+    // Stats start out empty, with no entry per tier yet
+    let num_prize_buckets = lottery_info.number_winners.len();
+    stats.winners.resize(num_prize_buckets, 0);
+    stats
+        .awarded_prizes
+        .resize(num_prize_buckets, Uint256::zero());
+
+    stats.awarded_lotteries += 1;
+    for index in 0..num_prize_buckets {
+        let winners = lottery_info.number_winners[index];
+        if winners != 0 {
+            stats.winners[index] += winners as u64;
+            stats.awarded_prizes[index] += lottery_info.prize_buckets[index];
+        }
+    }
+
+    PRIZE_TIER_STATS.save(storage, &stats)
+}
+
+fn format_distribution(distribution: &[Decimal256]) -> String {
+    distribution
+        .iter()
+        .map(|share| share.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Moves prize_distribution towards the shares paying each tier winner its target payout,
+/// by at most max_step per tier and within the governance bounds. The residual of the
+/// clamped shares is absorbed by the highest tiers with room left, so that the
+/// distribution keeps summing to 1
+fn rebalanced_distribution(
+    rebalancing: &PrizeRebalancingConfig,
+    stats: &PrizeTierStats,
+    current: &[Decimal256],
+) -> Option<(Vec<Decimal256>, Vec<Decimal256>)> {
+    let num_prize_buckets = current.len();
+    if stats.winners.len() != num_prize_buckets {
+        return None;
+    }
+
+    let mut weights = vec![Uint256::zero(); num_prize_buckets];
+    let mut total_weight = Uint256::zero();
+    for (index, weight) in weights.iter_mut().enumerate() {
+        *weight = rebalancing.target_payouts[index] * Uint256::from(stats.winners[index]);
+        total_weight += *weight;
+    }
+
+    // Without any winner in a targeted tier there is nothing to go by
+    if total_weight.is_zero() {
+        return None;
+    }
+
+    let mut target = vec![Decimal256::zero(); num_prize_buckets];
+    let mut distribution = vec![Decimal256::zero(); num_prize_buckets];
+    for index in 0..num_prize_buckets {
+        target[index] = Decimal256::from_ratio(weights[index], total_weight);
+
+        let share = current[index];
+        let stepped = if target[index] > share {
+            share + decimal_min(target[index] - share, rebalancing.max_step)
+        } else {
+            share - decimal_min(share - target[index], rebalancing.max_step)
+        };
+
+        distribution[index] = decimal_max(
+            decimal_min(stepped, rebalancing.max_distribution[index]),
+            rebalancing.min_distribution[index],
+        );
+    }
+
+    let mut sum = Decimal256::zero();
+    for share in distribution.iter() {
+        sum += *share;
+    }
+
+    if sum < Decimal256::one() {
+        let mut missing = Decimal256::one() - sum;
+        for index in (0..num_prize_buckets).rev() {
+            let added = decimal_min(
+                rebalancing.max_distribution[index] - distribution[index],
+                missing,
+            );
+            distribution[index] += added;
+            missing -= added;
+        }
+    } else if sum > Decimal256::one() {
+        let mut excess = sum - Decimal256::one();
+        for index in (0..num_prize_buckets).rev() {
+            let removed = decimal_min(
+                distribution[index] - rebalancing.min_distribution[index],
+                excess,
+            );
+            distribution[index] -= removed;
+            excess -= removed;
+        }
+    }
+
+    Some((distribution, target))
+}
+
+fn decimal_min(a: Decimal256, b: Decimal256) -> Decimal256 {
+    if a < b {
+        a
+    } else {
+        b
+    }
+}
+
+fn decimal_max(a: Decimal256, b: Decimal256) -> Decimal256 {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+/// Re-balances prize_distribution once interval lotteries were run since the last
+/// re-balancing, returning the audit attributes. Called in the epoch operations,
+/// which only run while no lottery is in progress
+pub fn apply_prize_rebalancing(
+    storage: &mut dyn Storage,
+    current_lottery: u64,
+) -> Result<Vec<Attribute>, ContractError> {
+    let mut rebalancing = match PRIZE_REBALANCING.may_load(storage)? {
+        Some(rebalancing) => rebalancing,
+        None => return Ok(vec![]),
+    };
+    if current_lottery < rebalancing.last_rebalanced_lottery + rebalancing.config.interval {
+        return Ok(vec![]);
+    }
+
+    rebalancing.last_rebalanced_lottery = current_lottery;
+    PRIZE_REBALANCING.save(storage, &rebalancing)?;
+
+    let stats = PRIZE_TIER_STATS.may_load(storage)?.unwrap_or_default();
+    let mut config = CONFIG.load(storage)?;
+    let previous_distribution = config.prize_distribution.clone();
+
+    let (distribution, target) =
+        match rebalanced_distribution(&rebalancing.config, &stats, &previous_distribution) {
+            Some(rebalanced) => rebalanced,
+            None => return Ok(vec![attr("prize_rebalancing", "no_winners")]),
+        };
+
+    // Bounds validated on update always leave room for a valid distribution
+    validate_prize_distribution(&distribution, config.num_prize_buckets())?;
+
+    // Shares below the minimum matches would never be awarded
+    if let Some(min_matches_for_prize) = config.min_matches_for_prize {
+        if validate_min_matches_for_prize(min_matches_for_prize, &distribution).is_err() {
+            return Ok(vec![attr(
+                "prize_rebalancing",
+                "below_min_matches_for_prize",
+            )]);
+        }
+    }
+
+    config.prize_distribution = distribution.clone();
+    CONFIG.save(storage, &config)?;
+
+    Ok(vec![
+        attr("prize_rebalancing", "rebalanced"),
+        attr(
+            "previous_prize_distribution",
+            format_distribution(&previous_distribution),
+        ),
+        attr("target_prize_distribution", format_distribution(&target)),
+        attr("prize_distribution", format_distribution(&distribution)),
+        attr(
+            "tier_winners",
+            stats
+                .winners
+                .iter()
+                .map(|winners| winners.to_string())
+                .collect::<Vec<String>>()
+                .join(","),
+        ),
+        attr("awarded_lotteries", stats.awarded_lotteries.to_string()),
+    ])
+}
+
+pub fn query_prize_rebalancing(deps: Deps) -> StdResult<PrizeRebalancingResponse> {
+    let rebalancing = PRIZE_REBALANCING.may_load(deps.storage)?;
+    let stats = PRIZE_TIER_STATS.may_load(deps.storage)?.unwrap_or_default();
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(PrizeRebalancingResponse {
+        last_rebalanced_lottery: rebalancing
+            .as_ref()
+            .map(|rebalancing| rebalancing.last_rebalanced_lottery)
+            .unwrap_or_default(),
+        rebalancing: rebalancing.map(|rebalancing| rebalancing.config),
+        prize_distribution: config.prize_distribution,
+        awarded_lotteries: stats.awarded_lotteries,
+        winners: stats.winners,
+        awarded_prizes: stats.awarded_prizes,
+    })
+}
@@ -1,28 +1,45 @@
+use crate::anchor::{read_pending_anchor_operations, redeem_stable_msg};
 use crate::error::ContractError;
-use crate::querier::{query_exchange_rate, query_oracle};
+use crate::events::{lottery_executed_event, prize_awarded_event};
+use crate::querier::{
+    query_aust_balance_and_exchange_rate, query_distributor_glow_balance, query_oracle,
+};
 
+use crate::inbox::record_prize_won;
+use crate::jackpot_floor::jackpot_top_up_submsgs;
+use crate::keeper_allowlist::assert_keeper_allowed;
+use crate::prize_caps::{split_capped_prize_bucket, tier_prize_cap};
+use crate::prize_rebalancing::record_tier_stats;
+use crate::rate_guard::{excessive_rate_deviation, guard_exchange_rate, read_rate_guard};
+use crate::receipt::read_total_receipt_aust;
 use crate::state::{
-    read_lottery_info, store_lottery_info, LotteryInfo, PrizeInfo, CONFIG, POOL, PRIZES, STATE,
+    read_depositor_stats_at_height, read_lottery_info, read_ticket_acquisition,
+    read_ticket_acquisition_time, read_total_pending_tickets, store_lottery_info, Config,
+    LotteryInfo, Pool, PrizeInfo, State, CONFIG, LAST_SKIM_TIME, POOL, PRIZES, PRIZE_CAPS, STATE,
     TICKETS,
 };
-use cosmwasm_bignumber::Uint256;
+use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{
-    attr, coin, to_binary, CosmosMsg, DepsMut, Env, MessageInfo, Order, Response, StdResult,
-    WasmMsg,
+    attr, coin, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Storage,
+    Uint128,
 };
 use cw0::{Duration, Expiration};
-use cw20::Cw20ExecuteMsg::Send as Cw20Send;
 use cw_storage_plus::{Bound, U64Key};
-use glow_protocol::lotto::NUM_PRIZE_BUCKETS;
-use terraswap::querier::query_token_balance;
+use glow_protocol::lotto::{
+    CanExecuteResponse, DrawMilestone, DrawTimeline, ExecutionBlocker, MissedLotteryPolicy,
+    NextLotteryProjectionResponse, OddsResponse, PrizeCursor, PrizeExecutionProgressResponse,
+    TierOddsResponse, TimeWeightWindow,
+};
 
 use crate::helpers::{
-    calculate_max_bound, calculate_value_of_aust_to_be_redeemed_for_lottery, count_seq_matches,
-    get_minimum_matches_for_winning_ticket, ExecuteLotteryRedeemedAustInfo,
+    calculate_max_bound, calculate_ticket_loyalty_weight, calculate_ticket_time_weight,
+    calculate_value_of_aust_to_be_redeemed_for_lottery, calculate_yield_deficit, count_seq_matches,
+    probability_of_any, tier_probability, ExecuteLotteryRedeemedAustInfo,
 };
 use crate::oracle::{calculate_lottery_rand_round, sequence_from_hash};
+use crate::winners_root::record_winner_leaves;
+use crate::withdrawal_queue::read_withdrawal_queue_state;
 use glow_protocol::querier::deduct_tax;
-use moneymarket::market::Cw20HookMsg;
 use std::ops::Add;
 use std::str;
 use std::usize;
@@ -36,20 +53,14 @@ pub fn execute_lottery(
     let config = CONFIG.load(deps.storage)?;
     let mut pool = POOL.load(deps.storage)?;
 
-    // Get the contract's aust balance
-    let contract_a_balance = query_token_balance(
+    // Get the contract's aust balance and the aust exchange rate
+    let (contract_a_balance, aust_exchange_rate) = query_aust_balance_and_exchange_rate(
         &deps.querier,
-        deps.api.addr_validate(config.a_terra_contract.as_str())?,
-        env.clone().contract.address,
-    )?;
-
-    // Get the aust exchange rate
-    let aust_exchange_rate = query_exchange_rate(
-        deps.as_ref(),
-        config.anchor_contract.to_string(),
+        &config.a_terra_contract,
+        &config.anchor_contract,
+        &env.contract.address,
         env.block.height,
-    )?
-    .exchange_rate;
+    )?;
 
     // Validate that no funds are sent when executing the lottery
     if !info.funds.is_empty() {
@@ -63,9 +74,20 @@ pub fn execute_lottery(
         });
     }
 
-    // Validate that there are a non zero number of tickets
-    if state.total_tickets.is_zero() {
-        return Err(ContractError::InvalidLotteryExecutionTickets {});
+    // Only allowlisted keepers can execute right after the lottery is ready
+    assert_keeper_allowed(
+        deps.as_ref(),
+        &env.block,
+        &info.sender,
+        state.next_lottery_time,
+    )?;
+
+    // Validate that there are a non zero number of tickets taking part in this lottery
+    let pending_tickets = read_total_pending_tickets(deps.storage)?.pending(state.current_lottery);
+    if state.total_tickets <= Uint256::from(pending_tickets) {
+        return Err(ContractError::InvalidLotteryExecutionTickets {
+            lottery_id: state.current_lottery,
+        });
     }
 
     // Set the next_lottery_exec_time to the current block time plus `config.block_time`
@@ -76,30 +98,82 @@ pub fn execute_lottery(
     // Validate that the lottery hasn't already started
     let mut lottery_info = read_lottery_info(deps.storage, state.current_lottery);
     if lottery_info.rand_round != 0 {
-        return Err(ContractError::LotteryAlreadyStarted {});
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
+    }
+
+    // Pause the lottery instead of redeeming at a rate that moved too fast
+    if let Some(res) = guard_exchange_rate(deps.storage, &env, aust_exchange_rate)? {
+        return Ok(res);
     }
 
     // Get the lottery_rand_round
     let lottery_rand_round = calculate_lottery_rand_round(env.clone(), config.round_delta);
 
+    // Check that the distributor holds enough GLOW to pay out every glow prize bucket
+    // at the max boost. If not, scale the buckets down for this lottery.
+    let (funded_glow_prize_buckets, glow_prize_shortfall) =
+        calculate_funded_glow_prize_buckets(deps.as_ref(), &config)?;
+
+    // The round of time weighted odds starts at the previous lottery execution
+    let time_weight_window = if config.time_weighted_odds {
+        let end = env.block.time.seconds();
+        let start = match (state.current_lottery, config.lottery_interval) {
+            (0, Duration::Time(lottery_interval)) => end.saturating_sub(lottery_interval),
+            (0, _) => end,
+            (current_lottery, _) => read_lottery_info(deps.storage, current_lottery - 1)
+                .timestamp
+                .seconds(),
+        };
+        Some(TimeWeightWindow { start, end })
+    } else {
+        None
+    };
+
     // Populate lottery_info
     lottery_info = LotteryInfo {
         rand_round: lottery_rand_round,
         sequence: "".to_string(),
         awarded: false,
-        prize_buckets: [Uint256::zero(); NUM_PRIZE_BUCKETS],
-        number_winners: [0; NUM_PRIZE_BUCKETS],
-        page: "".to_string(),
-        glow_prize_buckets: [Uint256::zero(); NUM_PRIZE_BUCKETS],
+        prize_buckets: vec![Uint256::zero(); config.num_prize_buckets()],
+        number_winners: vec![0; config.num_prize_buckets()],
+        prize_cursor: PrizeCursor::default(),
+        glow_prize_buckets: vec![Uint256::zero(); config.num_prize_buckets()],
         block_height: env.block.height,
         timestamp: env.block.time,
         total_user_shares: pool.total_user_shares,
+        draw_timeline: DrawTimeline {
+            sales_cutoff: Some(state.next_lottery_time),
+            lottery_executed: Some(DrawMilestone {
+                height: env.block.height,
+                time: env.block.time,
+            }),
+            randomness_available: Some(state.next_lottery_exec_time),
+            ..DrawTimeline::default()
+        },
+        // Snapshot whether loyalty weighting or time weighted odds apply to this lottery
+        loyalty_weights: if config.loyalty_config.enabled || time_weight_window.is_some() {
+            Some(vec![Decimal256::zero(); config.num_prize_buckets()])
+        } else {
+            None
+        },
+        time_weight_window,
+        funded_glow_prize_buckets: Some(funded_glow_prize_buckets),
+        glow_prize_shortfall,
+        winners_root: None,
+        prize_caps: PRIZE_CAPS.may_load(deps.storage)?,
     };
 
     store_lottery_info(deps.storage, state.current_lottery, &lottery_info)?;
 
+    // Under negative yield the lottery runs on the prize buckets as they are
+    let negative_yield = aust_exchange_rate < state.last_lottery_execution_aust_exchange_rate;
+    state.yield_deficit = calculate_yield_deficit(&state, &pool, aust_exchange_rate);
+
     let ExecuteLotteryRedeemedAustInfo {
         user_aust_to_redeem,
+        pol_aust_to_redeem,
         aust_to_redeem,
         aust_to_redeem_value,
         ..
@@ -107,7 +181,10 @@ pub fn execute_lottery(
         &state,
         &pool,
         &config,
-        Uint256::from(contract_a_balance),
+        // aust held for queued withdrawals and receipt tokens is not part of the pool
+        contract_a_balance
+            - read_withdrawal_queue_state(deps.storage)?.total_aust
+            - read_total_receipt_aust(deps.storage)?,
         aust_exchange_rate,
     );
 
@@ -120,49 +197,553 @@ pub fn execute_lottery(
         .amount,
     );
 
-    if net_amount.is_zero() {
+    let prize_amount = calculate_lottery_prize_amount(&env, &mut state, &config, net_amount)?;
+
+    if prize_amount.is_zero() && !negative_yield {
         // If aust_to_redeem and award_available are zero, return error
-        return Err(ContractError::InsufficientLotteryFunds {});
+        return Err(ContractError::InsufficientLotteryFunds {
+            lottery_id: state.current_lottery,
+        });
     }
 
     for (index, fraction_of_prize) in config.prize_distribution.iter().enumerate() {
-        // Add the proportional amount of the net redeemed amount to the relevant award bucket.
-        state.prize_buckets[index] += net_amount * *fraction_of_prize
+        // Add the proportional amount of the prize amount to the relevant award bucket.
+        state.prize_buckets[index] += prize_amount * *fraction_of_prize
     }
 
-    let mut msgs: Vec<CosmosMsg> = vec![];
+    // Top up the jackpot to the floor set by governance from the community contract
+    let (top_up_msgs, top_up_attrs) = jackpot_top_up_submsgs(deps.storage, &config, &mut state)?;
 
-    // Message to redeem "aust_to_redeem" of aust from the Anchor contract
-    let redeem_msg = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: config.a_terra_contract.to_string(),
-        funds: vec![],
-        msg: to_binary(&Cw20Send {
-            contract: config.anchor_contract.to_string(),
-            amount: aust_to_redeem.into(),
-            msg: to_binary(&Cw20HookMsg::RedeemStable {})?,
-        })?,
-    });
-
-    msgs.push(redeem_msg);
+    // Message to redeem "aust_to_redeem" of aust from the Anchor contract.
+    // The prize buckets are credited with the redeemed stable, so a failed
+    // redemption fails the whole lottery execution.
+    // Catch-up lotteries may have nothing left to redeem
+    let redeem_msgs = if aust_to_redeem.is_zero() {
+        vec![]
+    } else {
+        vec![redeem_stable_msg(&config, aust_to_redeem)?]
+    };
 
-    // Update last_lottery_exchange_rate
-    state.last_lottery_execution_aust_exchange_rate = aust_exchange_rate;
+    // Update last_lottery_exchange_rate, unless the deficit still has to be recouped
+    if !negative_yield {
+        state.last_lottery_execution_aust_exchange_rate = aust_exchange_rate;
+    }
 
     // Update the user shares
     pool.total_user_aust = pool.total_user_aust - user_aust_to_redeem;
+    pool.total_pol_aust -= pol_aust_to_redeem;
 
     // Store the state
-    STATE.save(deps.storage, &state)?;
+    STATE.save(deps.storage, &state, env.block.height)?;
     // Store the pool
-    POOL.save(deps.storage, &pool)?;
+    POOL.save(deps.storage, &pool, env.block.height)?;
+
+    let mut res = Response::new()
+        .add_messages(redeem_msgs)
+        .add_submessages(top_up_msgs)
+        .add_event(lottery_executed_event(
+            state.current_lottery,
+            aust_to_redeem,
+            prize_amount,
+            state.missed_lotteries,
+        ))
+        .add_attributes(vec![
+            attr("action", "execute_lottery"),
+            attr("redeemed_amount", aust_to_redeem.to_string()),
+            attr("glow_prize_shortfall", glow_prize_shortfall.to_string()),
+            attr("missed_lotteries", state.missed_lotteries.to_string()),
+        ])
+        .add_attributes(top_up_attrs);
+
+    if negative_yield {
+        res = res.add_attributes(vec![
+            attr("yield_deficit", state.yield_deficit.to_string()),
+            attr(
+                "recoup_exchange_rate",
+                state.last_lottery_execution_aust_exchange_rate.to_string(),
+            ),
+        ]);
+    }
 
-    let res = Response::new().add_messages(msgs).add_attributes(vec![
-        attr("action", "execute_lottery"),
-        attr("redeemed_amount", aust_to_redeem.to_string()),
-    ]);
     Ok(res)
 }
 
+/// Returns the amount added to the prize buckets out of the net redeemed amount,
+/// applying the missed lottery policy to state
+fn calculate_lottery_prize_amount(
+    env: &Env,
+    state: &mut State,
+    config: &Config,
+    net_amount: Uint256,
+) -> Result<Uint256, ContractError> {
+    if state.missed_lotteries > 0 {
+        // Catch-up lottery for a missed interval: take its share of the held back yield
+        let missed_lottery_prize =
+            state.missed_lottery_prizes / Uint256::from(state.missed_lotteries);
+        state.missed_lottery_prizes -= missed_lottery_prize;
+        state.missed_lotteries -= 1;
+        Ok(net_amount + missed_lottery_prize)
+    } else if let MissedLotteryPolicy::RunAllMissed { max } = config.missed_lottery_policy {
+        // Hold back the yield of the missed intervals for their catch-up lotteries
+        let missed_lotteries =
+            std::cmp::min(calculate_missed_lottery_intervals(env, state, config)?, max);
+        let missed_lottery_prizes =
+            net_amount * Decimal256::from_ratio(missed_lotteries, missed_lotteries + 1);
+        state.missed_lotteries = missed_lotteries;
+        state.missed_lottery_prizes = missed_lottery_prizes;
+        Ok(net_amount - missed_lottery_prizes)
+    } else {
+        Ok(net_amount)
+    }
+}
+
+pub fn query_next_lottery_projection(
+    deps: Deps,
+    env: Env,
+) -> Result<NextLotteryProjectionResponse, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    let pool = POOL.load(deps.storage)?;
+
+    // Get the contract's aust balance and the aust exchange rate
+    let (contract_a_balance, aust_exchange_rate) = query_aust_balance_and_exchange_rate(
+        &deps.querier,
+        &config.a_terra_contract,
+        &config.anchor_contract,
+        &env.contract.address,
+        env.block.height,
+    )?;
+
+    let ExecuteLotteryRedeemedAustInfo {
+        value_of_user_aust_to_be_redeemed_for_lottery,
+        value_of_sponsor_aust_to_be_redeemed_for_lottery,
+        aust_to_redeem,
+        aust_to_redeem_value,
+        ..
+    } = calculate_value_of_aust_to_be_redeemed_for_lottery(
+        &state,
+        &pool,
+        &config,
+        // aust held for queued withdrawals and receipt tokens is not part of the pool
+        contract_a_balance
+            - read_withdrawal_queue_state(deps.storage)?.total_aust
+            - read_total_receipt_aust(deps.storage)?,
+        aust_exchange_rate,
+    );
+
+    // Get the amount of ust that will be received after accounting for taxes
+    let net_amount = Uint256::from(
+        deduct_tax(
+            deps,
+            coin(aust_to_redeem_value.into(), config.stable_denom.clone()),
+        )?
+        .amount,
+    );
+
+    let prize_amount = calculate_lottery_prize_amount(&env, &mut state, &config, net_amount)?;
+
+    let mut prize_bucket_additions = vec![Uint256::zero(); config.num_prize_buckets()];
+    let mut projected_prize_buckets = state.prize_buckets;
+    for (index, fraction_of_prize) in config.prize_distribution.iter().enumerate() {
+        prize_bucket_additions[index] = prize_amount * *fraction_of_prize;
+        projected_prize_buckets[index] += prize_bucket_additions[index];
+    }
+
+    Ok(NextLotteryProjectionResponse {
+        aust_to_redeem,
+        aust_to_redeem_value,
+        user_value: value_of_user_aust_to_be_redeemed_for_lottery,
+        sponsor_value: value_of_sponsor_aust_to_be_redeemed_for_lottery,
+        prize_amount,
+        prize_bucket_additions,
+        projected_prize_buckets,
+    })
+}
+
+/// Odds of a holder of num_tickets more random tickets at the next lottery, given
+/// the current total_tickets and prize buckets
+pub fn query_odds(deps: Deps, num_tickets: u64) -> StdResult<OddsResponse> {
+    let state = STATE.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let total_tickets = state.total_tickets + Uint256::from(num_tickets);
+    let ticket_length = config.ticket_length as usize;
+    let min_matches_for_prize = config.min_matches_for_prize()?;
+
+    let mut expected_value = Uint256::zero();
+    let mut tiers = vec![];
+    for (matches, prize_bucket) in state.prize_buckets.iter().enumerate() {
+        let probability = tier_probability(matches, ticket_length);
+
+        // The bucket, net of the reserve fee, is split between the tickets of the tier
+        // if any. By symmetry each ticket gets an even share of the expected payout.
+        // Tiers below the minimum matches aren't awarded
+        let expected_prize = if total_tickets.is_zero() || matches < min_matches_for_prize {
+            Uint256::zero()
+        } else {
+            *prize_bucket
+                * ((Decimal256::one() - config.reserve_factor)
+                    * probability_of_any(probability, Uint128::from(total_tickets).u128())
+                    * Decimal256::from_ratio(num_tickets, total_tickets))
+        };
+        expected_value += expected_prize;
+
+        tiers.push(TierOddsResponse {
+            matches: matches as u8,
+            probability: probability_of_any(probability, num_tickets as u128),
+            expected_prize,
+        });
+    }
+
+    Ok(OddsResponse {
+        num_tickets,
+        total_tickets: state.total_tickets,
+        tiers,
+        expected_value,
+    })
+}
+
+/// ExecutePrize progress of a lottery, estimated from the position of the
+/// last processed ticket in the key range of the winning tickets
+pub fn query_prize_execution_progress(
+    deps: Deps,
+    lottery_id: Option<u64>,
+) -> StdResult<PrizeExecutionProgressResponse> {
+    let lottery_id = match lottery_id {
+        Some(lottery_id) => lottery_id,
+        None => STATE.load(deps.storage)?.current_lottery,
+    };
+    let lottery_info = read_lottery_info(deps.storage, lottery_id);
+    let cursor = lottery_info.prize_cursor;
+
+    let progress = if lottery_info.awarded {
+        Decimal256::one()
+    } else if let Some(last_ticket) = &cursor.last_ticket {
+        let tier = cursor.tier as usize;
+        let position = u64::from_str_radix(&last_ticket[tier..], 16)
+            .map_err(|_| StdError::generic_err("invalid prize cursor"))?;
+        Decimal256::from_ratio(position + 1, 16u64.pow((last_ticket.len() - tier) as u32))
+    } else {
+        Decimal256::zero()
+    };
+
+    Ok(PrizeExecutionProgressResponse {
+        lottery_id,
+        in_progress: lottery_info.rand_round != 0 && !lottery_info.awarded,
+        awarded: lottery_info.awarded,
+        prize_cursor: cursor,
+        progress,
+    })
+}
+
+fn seconds_remaining(env: &Env, expiration: Expiration) -> Option<u64> {
+    match expiration {
+        Expiration::AtTime(time) => Some(time.seconds().saturating_sub(env.block.time.seconds())),
+        _ => None,
+    }
+}
+
+fn blocked(reason: ExecutionBlocker) -> CanExecuteResponse {
+    CanExecuteResponse {
+        ready: false,
+        reason: Some(reason),
+        seconds_remaining: None,
+        missing_funds: None,
+    }
+}
+
+fn ready() -> CanExecuteResponse {
+    CanExecuteResponse {
+        ready: true,
+        reason: None,
+        seconds_remaining: None,
+        missing_funds: None,
+    }
+}
+
+/// Runs the checks of execute_lottery without executing it, so that
+/// keepers don't have to broadcast a transaction to find out it fails
+pub fn query_can_execute_lottery(
+    deps: Deps,
+    env: Env,
+) -> Result<CanExecuteResponse, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    let pool = POOL.load(deps.storage)?;
+
+    if config.pause_state.lottery_paused {
+        return Ok(blocked(ExecutionBlocker::LotteryPaused));
+    }
+
+    if !state.next_lottery_time.is_expired(&env.block) {
+        return Ok(CanExecuteResponse {
+            seconds_remaining: seconds_remaining(&env, state.next_lottery_time),
+            ..blocked(ExecutionBlocker::LotteryNotReady)
+        });
+    }
+
+    let pending_tickets = read_total_pending_tickets(deps.storage)?.pending(state.current_lottery);
+    if state.total_tickets <= Uint256::from(pending_tickets) {
+        return Ok(blocked(ExecutionBlocker::NoTickets));
+    }
+
+    if read_lottery_info(deps.storage, state.current_lottery).rand_round != 0 {
+        return Ok(blocked(ExecutionBlocker::LotteryAlreadyStarted));
+    }
+
+    let (contract_a_balance, aust_exchange_rate) = query_aust_balance_and_exchange_rate(
+        &deps.querier,
+        &config.a_terra_contract,
+        &config.anchor_contract,
+        &env.contract.address,
+        env.block.height,
+    )?;
+
+    // The contract must hold the aust of the users, of the protocol owned liquidity, of the
+    // savings, queued withdrawals and receipt tokens, and enough aust to cover the sponsor deposits
+    let claimed_aust = pool.total_user_aust
+        + pool.total_pol_aust
+        + pool.total_savings_aust
+        + read_withdrawal_queue_state(deps.storage)?.total_aust
+        + read_total_receipt_aust(deps.storage)?;
+    let mut sponsor_aust = pool.total_sponsor_lottery_deposits / aust_exchange_rate;
+    if sponsor_aust * aust_exchange_rate < pool.total_sponsor_lottery_deposits {
+        sponsor_aust += Uint256::one();
+    }
+    let required_aust = claimed_aust + sponsor_aust;
+    if contract_a_balance < required_aust {
+        return Ok(CanExecuteResponse {
+            missing_funds: Some(required_aust - contract_a_balance),
+            ..blocked(ExecutionBlocker::InsufficientAust)
+        });
+    }
+
+    // Executing would trip the rate guard and pause the lottery
+    if excessive_rate_deviation(&read_rate_guard(deps.storage)?, &env, aust_exchange_rate).is_some()
+    {
+        return Ok(blocked(ExecutionBlocker::ExchangeRateOutOfBounds));
+    }
+
+    // Under negative yield the lottery runs without funding the prizes
+    if aust_exchange_rate >= state.last_lottery_execution_aust_exchange_rate
+        && query_next_lottery_projection(deps, env)?
+            .prize_amount
+            .is_zero()
+    {
+        return Ok(blocked(ExecutionBlocker::InsufficientLotteryFunds));
+    }
+
+    Ok(ready())
+}
+
+/// Runs the checks of execute_prize without executing it
+pub fn query_can_execute_prize(deps: Deps, env: Env) -> StdResult<CanExecuteResponse> {
+    let state = STATE.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    if config.pause_state.lottery_paused {
+        return Ok(blocked(ExecutionBlocker::LotteryPaused));
+    }
+
+    let lottery_info = read_lottery_info(deps.storage, state.current_lottery);
+    if lottery_info.rand_round == 0 {
+        return Ok(blocked(ExecutionBlocker::LotteryNotStarted));
+    }
+
+    if !state.next_lottery_exec_time.is_expired(&env.block) {
+        return Ok(CanExecuteResponse {
+            seconds_remaining: seconds_remaining(&env, state.next_lottery_exec_time),
+            ..blocked(ExecutionBlocker::RandomnessNotReady)
+        });
+    }
+
+    // The first page fetches the randomness from the oracle
+    if lottery_info.sequence.is_empty()
+        && query_oracle(
+            deps,
+            config.oracle_contract.to_string(),
+            lottery_info.rand_round,
+        )
+        .is_err()
+    {
+        return Ok(blocked(ExecutionBlocker::RandomnessUnavailable));
+    }
+
+    Ok(ready())
+}
+
+/// Returns the number of whole lottery intervals elapsed since next_lottery_time
+fn calculate_missed_lottery_intervals(
+    env: &Env,
+    state: &State,
+    config: &Config,
+) -> Result<u64, ContractError> {
+    // Get the amount of time between now and the time at which the lottery
+    // became runnable
+    let time_since_next_lottery_time =
+        if let Expiration::AtTime(next_lottery_time) = state.next_lottery_time {
+            env.block.time.minus_seconds(next_lottery_time.seconds())
+        } else {
+            return Err(ContractError::InvalidLotteryNextTime {});
+        };
+
+    // Get the lottery interval in seconds
+    let lottery_interval_seconds = if let Duration::Time(time) = config.lottery_interval {
+        time
+    } else {
+        return Err(ContractError::InvalidLotteryInterval {});
+    };
+
+    Ok(time_since_next_lottery_time.seconds() / lottery_interval_seconds)
+}
+
+/// Returns the glow prize buckets the distributor can fund and the shortfall, if any.
+/// Buckets are scaled down pro rata when the distributor balance can't cover the
+/// configured buckets at the max lotto winner boost.
+fn calculate_funded_glow_prize_buckets(
+    deps: Deps,
+    config: &Config,
+) -> StdResult<(Vec<Uint256>, Uint256)> {
+    let required = config
+        .glow_prize_buckets
+        .iter()
+        .fold(Uint256::zero(), |acc, bucket| acc + *bucket)
+        * config.lotto_winner_boost_config.max_multiplier;
+
+    if required.is_zero() {
+        return Ok((config.glow_prize_buckets.clone(), Uint256::zero()));
+    }
+
+    let available = query_distributor_glow_balance(&deps.querier, &config.distributor_contract)?;
+
+    if available >= required {
+        return Ok((config.glow_prize_buckets.clone(), Uint256::zero()));
+    }
+
+    let funded_ratio = Decimal256::from_ratio(available, required);
+    let mut funded_glow_prize_buckets = vec![Uint256::zero(); config.num_prize_buckets()];
+    for (index, bucket) in config.glow_prize_buckets.iter().enumerate() {
+        funded_glow_prize_buckets[index] = *bucket * funded_ratio;
+    }
+
+    Ok((funded_glow_prize_buckets, required - available))
+}
+
+// Minimum number of seconds between two skims
+pub const MIN_SKIM_INTERVAL: u64 = 60 * 60 * 24;
+
+/// Credits aust held by the contract but not tracked by the pool to the prize buckets.
+/// Untracked aust is the contract balance minus the user aust (which includes operator
+/// shares), the protocol owned liquidity, the aust backing sponsor deposits and aust
+/// with a pending redemption.
+/// Aust of the contract owed to users, savers, sponsors and the protocol owned liquidity,
+/// or held for receipts, queued withdrawals and pending redemptions
+pub fn calculate_tracked_aust(
+    storage: &dyn Storage,
+    state: &State,
+    pool: &Pool,
+    aust_exchange_rate: Decimal256,
+) -> StdResult<Uint256> {
+    // Like in calculate_value_of_aust_to_be_redeemed_for_lottery, the sponsor aust is worth its
+    // deposits at the last lottery rate and its appreciation since then goes to the next lottery
+    let sponsor_aust_exchange_rate = std::cmp::min(
+        aust_exchange_rate,
+        state.last_lottery_execution_aust_exchange_rate,
+    );
+    let sponsor_aust = pool.total_sponsor_lottery_deposits / sponsor_aust_exchange_rate;
+    let pending_redeem_aust = read_pending_anchor_operations(storage)?.redeem_stable;
+    let queued_aust = read_withdrawal_queue_state(storage)?.total_aust;
+    let receipt_aust = read_total_receipt_aust(storage)?;
+
+    Ok(pool.total_user_aust
+        + pool.total_pol_aust
+        + pool.total_savings_aust
+        + sponsor_aust
+        + pending_redeem_aust
+        + queued_aust
+        + receipt_aust)
+}
+
+pub fn execute_skim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    let pool = POOL.load(deps.storage)?;
+
+    if !info.funds.is_empty() {
+        return Err(ContractError::InvalidLotteryExecutionFunds {});
+    }
+
+    // Validate that the skim interval has passed
+    if let Some(last_skim_time) = LAST_SKIM_TIME.may_load(deps.storage)? {
+        let next_skim_time = last_skim_time + MIN_SKIM_INTERVAL;
+        if env.block.time.seconds() < next_skim_time {
+            return Err(ContractError::SkimNotReady { next_skim_time });
+        }
+    }
+
+    // Validate that there isn't a lottery in progress already
+    let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
+    if current_lottery.rand_round != 0 {
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
+    }
+
+    // Get the contract's aust balance and the aust exchange rate
+    let (contract_a_balance, aust_exchange_rate) = query_aust_balance_and_exchange_rate(
+        &deps.querier,
+        &config.a_terra_contract,
+        &config.anchor_contract,
+        &env.contract.address,
+        env.block.height,
+    )?;
+
+    let tracked_aust = calculate_tracked_aust(deps.storage, &state, &pool, aust_exchange_rate)?;
+    let untracked_aust = if contract_a_balance > tracked_aust {
+        contract_a_balance - tracked_aust
+    } else {
+        Uint256::zero()
+    };
+
+    // Get the amount of ust that will be received after accounting for taxes
+    let net_amount = Uint256::from(
+        deduct_tax(
+            deps.as_ref(),
+            coin(
+                (untracked_aust * aust_exchange_rate).into(),
+                config.stable_denom.clone(),
+            ),
+        )?
+        .amount,
+    );
+
+    if net_amount.is_zero() {
+        return Err(ContractError::NothingToSkim {});
+    }
+
+    let mut prize_bucket_amounts = vec![];
+    for (index, fraction_of_prize) in config.prize_distribution.iter().enumerate() {
+        let amount = net_amount * *fraction_of_prize;
+        state.prize_buckets[index] += amount;
+        prize_bucket_amounts.push(amount.to_string());
+    }
+
+    // The skimmed amount is credited to the prize buckets, so the redemption can't fail on its own
+    let redeem_msg = redeem_stable_msg(&config, untracked_aust)?;
+
+    LAST_SKIM_TIME.save(deps.storage, &env.block.time.seconds())?;
+    STATE.save(deps.storage, &state, env.block.height)?;
+
+    Ok(Response::new().add_message(redeem_msg).add_attributes(vec![
+        attr("action", "skim"),
+        attr("contract_a_balance", contract_a_balance.to_string()),
+        attr("tracked_aust", tracked_aust.to_string()),
+        attr("skimmed_aust", untracked_aust.to_string()),
+        attr("prize_amount", net_amount.to_string()),
+        attr("prize_bucket_amounts", prize_bucket_amounts.join(",")),
+    ]))
+}
+
 fn calc_limit(request: Option<u32>) -> usize {
     request.unwrap_or(DEFAULT_LIMIT) as usize
 }
@@ -188,47 +769,74 @@ pub fn execute_prize(
 
     // Validate that rand_round has been assigned
     if lottery_info.rand_round == 0 {
-        return Err(ContractError::InvalidLotteryPrizeExecution {});
+        return Err(ContractError::InvalidLotteryPrizeExecution {
+            lottery_id: current_lottery,
+        });
     }
 
     // Validate that the next_lottery_exec_time has passed
     if !state.next_lottery_exec_time.is_expired(&env.block) {
-        return Err(ContractError::InvalidLotteryPrizeExecutionExpired {});
+        return Err(ContractError::InvalidLotteryPrizeExecutionExpired {
+            lottery_id: current_lottery,
+            next_lottery_exec_time: state.next_lottery_exec_time,
+        });
     }
 
+    // Only allowlisted keepers can execute right after the randomness is ready
+    assert_keeper_allowed(
+        deps.as_ref(),
+        &env.block,
+        &info.sender,
+        state.next_lottery_exec_time,
+    )?;
+
     // If first time called in current lottery, generate the random winning sequence
     if lottery_info.sequence.is_empty() {
         let oracle_response = query_oracle(
             deps.as_ref(),
-            config.oracle_contract.into_string(),
+            config.oracle_contract.to_string(),
             lottery_info.rand_round,
         )?;
         let random_hash = hex::encode(oracle_response.randomness.as_slice());
-        lottery_info.sequence = sequence_from_hash(random_hash);
+        lottery_info.sequence = sequence_from_hash(random_hash, config.ticket_length as usize);
+        lottery_info.draw_timeline.randomness_fetched = Some(DrawMilestone {
+            height: env.block.height,
+            time: env.block.time,
+        });
     }
 
+    // Record the block of this page for draw latency tracking
+    lottery_info.draw_timeline.prize_pages.push(DrawMilestone {
+        height: env.block.height,
+        time: env.block.time,
+    });
+
     // Calculate pagination bounds
     let limit = calc_limit(limit);
-    let minimum_matches_for_winning_ticket =
-        get_minimum_matches_for_winning_ticket(config.prize_distribution)?;
 
-    // Min bound is either the string of the first two characters of the winning sequence
-    // or the page specified by lottery_info
-    let min_bound: &str = if lottery_info.page.is_empty() {
-        &lottery_info.sequence[..minimum_matches_for_winning_ticket]
-    } else {
-        &lottery_info.page
-    };
+    // The tier is fixed on the first page, so that later pages scan
+    // the same range even if the prize distribution is updated
+    if lottery_info.prize_cursor.tier == 0 {
+        lottery_info.prize_cursor.tier = config.min_matches_for_prize()? as u8;
+    }
+    let tier = lottery_info.prize_cursor.tier as usize;
+
+    // Winning tickets start with the first tier characters of the winning sequence
+    let min_bound = &lottery_info.sequence[..tier];
+    let max_bound = calculate_max_bound(min_bound, tier, config.ticket_length as usize);
 
-    // Get max bounds
-    let max_bound = calculate_max_bound(min_bound, minimum_matches_for_winning_ticket);
+    // Resume right after the last processed ticket
+    let start = match &lottery_info.prize_cursor.last_ticket {
+        Some(last_ticket) => Bound::Exclusive(Vec::from(last_ticket.as_str())),
+        None => Bound::Inclusive(Vec::from(min_bound)),
+    };
 
     // Get winning tickets
     let winning_tickets: Vec<_> = TICKETS
-        // Get tickets inclusive from the min_bound to the max_bound with a limit
+        // Get tickets from the start bound to the max_bound with a limit
         .range(
             deps.storage,
-            Some(Bound::Inclusive(Vec::from(min_bound))),
+            Some(start),
             Some(Bound::Inclusive(Vec::from(max_bound.clone()))),
             Order::Ascending,
         )
@@ -237,22 +845,26 @@ pub fn execute_prize(
         .unwrap();
 
     if !winning_tickets.is_empty() {
-        // Update pagination for next iterations, if necessary
-        if let Some(next) = TICKETS
+        let last_ticket = winning_tickets.last().unwrap().0.clone();
+
+        // The lottery is awarded once no winning ticket is left after this page
+        if TICKETS
             .range(
                 deps.storage,
-                Some(Bound::Exclusive(winning_tickets.last().unwrap().clone().0)),
+                Some(Bound::Exclusive(last_ticket.clone())),
                 Some(Bound::Inclusive(Vec::from(max_bound))),
                 Order::Ascending,
             )
             .next()
+            .is_none()
         {
-            // Set the page to the next value after the last winning_ticket from the previous limited query
-            lottery_info.page = String::from_utf8(next.unwrap().0).unwrap();
-        } else {
             lottery_info.awarded = true;
         }
 
+        // Update pagination for next iterations
+        lottery_info.prize_cursor.last_ticket = Some(String::from_utf8(last_ticket).unwrap());
+        lottery_info.prize_cursor.processed_count += winning_tickets.len() as u64;
+
         // Update holders prizes and lottery info number of winners
         winning_tickets.iter().for_each(|sequence| {
             // Get the number of matches between this winning ticket and the perfect winning ticket.
@@ -260,38 +872,102 @@ pub fn execute_prize(
                 &lottery_info.sequence.clone(),
                 str::from_utf8(&*sequence.0).unwrap(),
             );
+
+            // Pair each holder with the lottery and time at which they acquired their copy
+            // of the ticket, leaving out the pending copies acquired for the next lottery
+            let winners: Vec<_> = sequence
+                .1
+                .iter()
+                .enumerate()
+                .filter_map(|(index, winner)| {
+                    let copy = sequence.1[..index]
+                        .iter()
+                        .filter(|holder| *holder == winner)
+                        .count();
+                    let acquired_lottery =
+                        read_ticket_acquisition(deps.storage, &sequence.0, winner, copy).unwrap();
+                    let acquired_at =
+                        read_ticket_acquisition_time(deps.storage, &sequence.0, winner, copy)
+                            .unwrap();
+                    if acquired_lottery <= current_lottery {
+                        Some((winner, acquired_lottery, acquired_at))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
             // Increment the number of winners corresponding the number of matches of this ticket
             // by the number of people who hold this ticket.
-            lottery_info.number_winners[matches as usize] += sequence.1.len() as u32;
-
-            sequence.1.iter().for_each(|winner| {
-                // Get the lottery_id
-                let lottery_key: U64Key = state.current_lottery.into();
-
-                // Check if a prize already exist
-                let maybe_prize = PRIZES
-                    .may_load(deps.storage, (lottery_key.clone(), winner))
-                    .unwrap();
-
-                // Calculate updated_prize accordingly
-                let updated_prize = if let Some(mut prize) = maybe_prize {
-                    prize.matches[matches as usize] += 1;
-                    prize
-                } else {
-                    let mut winnings = [0; NUM_PRIZE_BUCKETS];
-                    winnings[matches as usize] = 1;
-
-                    PrizeInfo {
-                        claimed: false,
-                        matches: winnings,
+            lottery_info.number_winners[matches as usize] += winners.len() as u32;
+
+            winners
+                .into_iter()
+                .for_each(|(winner, acquired_lottery, acquired_at)| {
+                    // Get the lottery_id
+                    let lottery_key: U64Key = state.current_lottery.into();
+
+                    // Check if a prize already exist
+                    let maybe_prize = PRIZES
+                        .may_load(deps.storage, (lottery_key.clone(), winner))
+                        .unwrap();
+
+                    // Calculate updated_prize accordingly
+                    let mut updated_prize = if let Some(mut prize) = maybe_prize {
+                        prize.matches[matches as usize] += 1;
+                        prize
+                    } else {
+                        let mut winnings = vec![0; config.num_prize_buckets()];
+                        winnings[matches as usize] = 1;
+
+                        // First winning ticket of this winner in the lottery
+                        record_prize_won(deps.storage, winner).unwrap();
+
+                        PrizeInfo {
+                            claimed: false,
+                            matches: winnings,
+                            loyalty_weights: vec![Decimal256::zero(); config.num_prize_buckets()],
+                        }
+                    };
+
+                    // Weight this copy of the ticket by how long the winner has held it
+                    if let Some(loyalty_weights) = lottery_info.loyalty_weights.as_mut() {
+                        let mut weight = match lottery_info.time_weight_window.as_ref() {
+                            Some(_) if !config.loyalty_config.enabled => Decimal256::one(),
+                            _ => calculate_ticket_loyalty_weight(
+                                &config.loyalty_config,
+                                acquired_lottery,
+                                current_lottery,
+                            ),
+                        };
+
+                        // Time weighted odds use the shares behind each ticket of the
+                        // winner at the lottery height and the fraction of the round held
+                        if let Some(window) = lottery_info.time_weight_window.as_ref() {
+                            let stats = read_depositor_stats_at_height(
+                                deps.storage,
+                                winner,
+                                lottery_info.block_height,
+                            );
+                            let shares_per_ticket = if stats.num_tickets == 0 {
+                                Decimal256::zero()
+                            } else {
+                                Decimal256::from_ratio(stats.shares, stats.num_tickets as u64)
+                            };
+                            weight = weight
+                                * shares_per_ticket
+                                * calculate_ticket_time_weight(window, acquired_at);
+                        }
+
+                        updated_prize.loyalty_weights[matches as usize] += weight;
+                        loyalty_weights[matches as usize] += weight;
                     }
-                };
 
-                // Save the updated prize
-                PRIZES
-                    .save(deps.storage, (lottery_key, winner), &updated_prize)
-                    .unwrap();
-            });
+                    // Save the updated prize
+                    PRIZES
+                        .save(deps.storage, (lottery_key, winner), &updated_prize)
+                        .unwrap();
+                });
         });
     } else {
         // If there are no more winning tickets, then set awarded to true
@@ -300,18 +976,25 @@ pub fn execute_prize(
 
     // If all winners have been accounted, update lottery info and jump to next round
     let mut total_awarded_prize = Uint256::zero();
+    let mut total_rolled_over_prize = Uint256::zero();
+    let mut events = vec![];
     if lottery_info.awarded {
+        lottery_info.draw_timeline.finalized = Some(DrawMilestone {
+            height: env.block.height,
+            time: env.block.time,
+        });
+
         // Update the lottery prize buckets based on whether or not there is a winner in the corresponding bucket
         for (index, rank) in lottery_info.number_winners.iter().enumerate() {
             if *rank != 0 {
-                // Get the prize to be distributed for this tier
-                let mut awarded_prize_bucket = state.prize_buckets[index];
-
-                // Get the reserve fee for this tier
-                let local_reserve_fee = awarded_prize_bucket * config.reserve_factor;
-
-                // Decrease the prize to be distributed by the reserve fee
-                awarded_prize_bucket = awarded_prize_bucket - local_reserve_fee;
+                // Get the prize to be distributed for this tier, net of the reserve fee
+                // and up to the tier cap. The excess rolls over to the next lottery
+                let (awarded_prize_bucket, local_reserve_fee, rolled_over_prize) =
+                    split_capped_prize_bucket(
+                        state.prize_buckets[index],
+                        config.reserve_factor,
+                        tier_prize_cap(&lottery_info, index, *rank),
+                    );
 
                 // Increase the total reserve by the reserve fee
                 state.total_reserve += local_reserve_fee;
@@ -322,15 +1005,41 @@ pub fn execute_prize(
                 // Update the corresponding lottery prize bucket
                 lottery_info.prize_buckets[index] = awarded_prize_bucket;
 
-                // Set the corresponding award bucket to 0
-                state.prize_buckets[index] = Uint256::zero();
+                // Reset the corresponding award bucket to the rolled over excess
+                state.prize_buckets[index] = rolled_over_prize;
+                total_rolled_over_prize += rolled_over_prize;
 
                 // Update the corresponding glow lottery prize bucket
-                // In this case glow_prize_buckets is a config and we don't set it to zero afterwards
-                lottery_info.glow_prize_buckets[index] = config.glow_prize_buckets[index];
+                // In this case glow_prize_buckets is a config and we don't set it to zero afterwards.
+                // Use the buckets the distributor could fund when the lottery was executed
+                lottery_info.glow_prize_buckets[index] =
+                    match &lottery_info.funded_glow_prize_buckets {
+                        Some(funded_glow_prize_buckets) => funded_glow_prize_buckets[index],
+                        None => config.glow_prize_buckets[index],
+                    };
+
+                events.push(prize_awarded_event(
+                    current_lottery,
+                    index,
+                    *rank,
+                    awarded_prize_bucket,
+                    local_reserve_fee,
+                    lottery_info.glow_prize_buckets[index],
+                ));
             }
         }
 
+        record_tier_stats(deps.storage, &lottery_info)?;
+
+        // Commit to the prizes of the winners for claims on other chains
+        lottery_info.winners_root = Some(record_winner_leaves(
+            deps.storage,
+            &deps.querier,
+            &config,
+            current_lottery,
+            &lottery_info,
+        )?);
+
         // Increment the current_lottery_number
         state.current_lottery += 1;
 
@@ -338,29 +1047,17 @@ pub fn execute_prize(
         // We want next_lottery_time to be a time in the future so pick the smallest x such that
         // next_lottery_time = next_lottery_time + x * lottery_interval
         // but next_lottery_time + x * lottery_interval > env.block_time
-
-        // Get the amount of time between now and the time at which the lottery
-        // became runnable
-        let time_since_next_lottery_time =
-            if let Expiration::AtTime(next_lottery_time) = state.next_lottery_time {
-                env.block.time.minus_seconds(next_lottery_time.seconds())
-            } else {
-                return Err(ContractError::InvalidLotteryNextTime {});
-            };
-
-        // Get the lottery interval in seconds
-        let lottery_interval_seconds = if let Duration::Time(time) = config.lottery_interval {
-            time
-        } else {
-            return Err(ContractError::InvalidLotteryInterval {});
-        };
+        // Catch-up lotteries for missed intervals are instead runnable right away, one interval apart
 
         // Get the number of lottery intervals that have passed
         // since the lottery became runnable
         // this should be 0 everytime
         // unless somebody forgot to run the lottery for a week for example
-        let lottery_intervals_since_last_lottery =
-            time_since_next_lottery_time.seconds() / lottery_interval_seconds;
+        let lottery_intervals_since_last_lottery = if state.missed_lotteries > 0 {
+            0
+        } else {
+            calculate_missed_lottery_intervals(&env, &state, &config)?
+        };
 
         // Set the next_lottery_time to the closest time in the future that is
         // the current value of next_lottery_time plus a multiple of lottery_interval
@@ -374,14 +1071,24 @@ pub fn execute_prize(
         state.next_lottery_exec_time = Expiration::Never {};
 
         // Save the state
-        STATE.save(deps.storage, &state)?;
+        STATE.save(deps.storage, &state, env.block.height)?;
     }
 
     // Save the lottery_info
     store_lottery_info(deps.storage, current_lottery, &lottery_info)?;
 
-    Ok(Response::new().add_attributes(vec![
+    let mut attributes = vec![
         attr("action", "execute_prize"),
         attr("total_awarded_prize", total_awarded_prize.to_string()),
-    ]))
+    ];
+    if !total_rolled_over_prize.is_zero() {
+        attributes.push(attr(
+            "total_rolled_over_prize",
+            total_rolled_over_prize.to_string(),
+        ));
+    }
+
+    Ok(Response::new()
+        .add_events(events)
+        .add_attributes(attributes))
 }
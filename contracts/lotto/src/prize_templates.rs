@@ -0,0 +1,116 @@
+use cosmwasm_bignumber::Decimal256;
+use cosmwasm_std::{attr, Deps, DepsMut, MessageInfo, Order, Response, StdResult, Storage};
+use glow_protocol::common::Pagination;
+use glow_protocol::lotto::{PrizeTemplateResponse, PrizeTemplatesResponse};
+
+use crate::error::ContractError;
+use crate::helpers::validate_prize_distribution;
+use crate::state::{CONFIG, PRIZE_TEMPLATES};
+
+const MAX_TEMPLATE_NAME_LEN: usize = 32;
+/// Prize distribution of the template called name
+pub fn read_prize_template(
+    storage: &dyn Storage,
+    name: &str,
+) -> Result<Vec<Decimal256>, ContractError> {
+    PRIZE_TEMPLATES
+        .may_load(storage, name)?
+        .ok_or_else(|| ContractError::PrizeTemplateNotFound(name.to_string()))
+}
+
+/// Saves the prize distribution of a template. The template must not exist yet
+/// when creating it, and must exist when updating it - restricted to gov
+pub fn execute_save_prize_template(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+    prize_distribution: Vec<Decimal256>,
+    create: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if name.is_empty() || name.len() > MAX_TEMPLATE_NAME_LEN {
+        return Err(ContractError::InvalidPrizeTemplateName {});
+    }
+
+    let exists = PRIZE_TEMPLATES.has(deps.storage, &name);
+    if create && exists {
+        return Err(ContractError::PrizeTemplateAlreadyExists(name));
+    }
+    if !create && !exists {
+        return Err(ContractError::PrizeTemplateNotFound(name));
+    }
+
+    validate_prize_distribution(&prize_distribution, config.num_prize_buckets())?;
+
+    PRIZE_TEMPLATES.save(deps.storage, &name, &prize_distribution)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr(
+            "action",
+            if create {
+                "create_prize_template"
+            } else {
+                "update_prize_template"
+            },
+        ),
+        attr("name", name),
+    ]))
+}
+
+/// Removes a template. Sponsorships made with it keep their distribution - restricted to gov
+pub fn execute_remove_prize_template(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    read_prize_template(deps.storage, &name)?;
+    PRIZE_TEMPLATES.remove(deps.storage, &name);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_prize_template"),
+        attr("name", name),
+    ]))
+}
+
+pub fn query_prize_templates(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PrizeTemplatesResponse> {
+    let pagination = Pagination::new(start_after, limit);
+
+    let templates = PRIZE_TEMPLATES
+        .range(
+            deps.storage,
+            pagination.start_bound(),
+            None,
+            Order::Ascending,
+        )
+        .take(pagination.limit)
+        .map(|item| {
+            let (name, prize_distribution) = item?;
+            Ok(PrizeTemplateResponse {
+                name: String::from_utf8_lossy(&name).to_string(),
+                prize_distribution,
+            })
+        })
+        .collect::<StdResult<Vec<PrizeTemplateResponse>>>()?;
+
+    Ok(PrizeTemplatesResponse {
+        templates,
+        limit: pagination.limit as u32,
+    })
+}
@@ -2,29 +2,19 @@ use crate::oracle::{OracleResponse, QueryMsg as QueryOracle};
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::Uint128;
 use cosmwasm_std::{
-    to_binary, Addr, BalanceResponse as BankBalanceResponse, BankQuery, Deps, QuerierWrapper,
-    QueryRequest, StdResult, WasmQuery,
+    Addr, BalanceResponse as BankBalanceResponse, BankQuery, Deps, QuerierWrapper, QueryRequest,
+    StdResult,
+};
+use glow_protocol::distributor::{
+    ConfigResponse as DistributorConfigResponse, GlowEmissionRateResponse,
+    QueryMsg as DistributorQueryMsg,
+};
+use glow_protocol::querier::{
+    exchange_rate_query, query_batch, token_balance_query, ve_state_query, voting_power_query,
+    SmartQuery,
 };
-use glow_protocol::distributor::{GlowEmissionRateResponse, QueryMsg as DistributorQueryMsg};
-use glow_protocol::ve_token::{QueryMsg as VEQueryMessage, StakerResponse, StateResponse};
-use moneymarket::market::{EpochStateResponse, QueryMsg as AnchorMsg};
 
-pub fn query_exchange_rate(
-    deps: Deps,
-    money_market_addr: String,
-    block_height: u64,
-) -> StdResult<EpochStateResponse> {
-    let epoch_state: EpochStateResponse =
-        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: money_market_addr,
-            msg: to_binary(&AnchorMsg::EpochState {
-                block_height: Some(block_height),
-                distributed_interest: None,
-            })?,
-        }))?;
-
-    Ok(epoch_state)
-}
+pub use glow_protocol::querier::query_exchange_rate;
 
 pub fn query_balance(deps: Deps, account_addr: String, denom: String) -> StdResult<Uint256> {
     // load price form the oracle
@@ -36,6 +26,25 @@ pub fn query_balance(deps: Deps, account_addr: String, denom: String) -> StdResu
     Ok(balance.amount.amount.into())
 }
 
+/// aust balance of the contract and the aust exchange rate at block_height
+pub fn query_aust_balance_and_exchange_rate(
+    querier: &QuerierWrapper,
+    a_terra_contract: &Addr,
+    anchor_contract: &Addr,
+    contract_addr: &Addr,
+    block_height: u64,
+) -> StdResult<(Uint256, Decimal256)> {
+    let (balance, epoch_state) = query_batch(
+        querier,
+        (
+            token_balance_query(a_terra_contract, contract_addr)?,
+            exchange_rate_query(anchor_contract, block_height)?,
+        ),
+    )?;
+
+    Ok((balance.balance.into(), epoch_state.exchange_rate))
+}
+
 #[allow(dead_code)]
 pub fn query_glow_emission_rate(
     querier: &QuerierWrapper,
@@ -45,35 +54,41 @@ pub fn query_glow_emission_rate(
     current_emission_rate: Decimal256,
 ) -> StdResult<GlowEmissionRateResponse> {
     // get the amount of money in the lottery pool
+    SmartQuery::new(
+        distributor,
+        &DistributorQueryMsg::GlowEmissionRate {
+            current_award: lottery_balance,
+            target_award,
+            current_emission_rate,
+        },
+    )?
+    .query(querier)
+}
 
-    let glow_emission_rate: GlowEmissionRateResponse =
-        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: distributor.to_string(),
-            msg: to_binary(&DistributorQueryMsg::GlowEmissionRate {
-                current_award: lottery_balance,
-                target_award,
-                current_emission_rate,
-            })?,
-        }))?;
+/// GLOW held by the distributor, which funds the GLOW prizes
+pub fn query_distributor_glow_balance(
+    querier: &QuerierWrapper,
+    distributor: &Addr,
+) -> StdResult<Uint256> {
+    let distributor_config: DistributorConfigResponse =
+        SmartQuery::new(distributor, &DistributorQueryMsg::Config {})?.query(querier)?;
 
-    Ok(glow_emission_rate)
+    let balance =
+        token_balance_query(distributor_config.glow_token, distributor)?.query(querier)?;
+
+    Ok(Uint256::from(balance.balance))
 }
 
-pub fn query_address_voting_balance_at_timestamp(
+/// Voting power of address from gov, which accounts for delegated ve balances
+pub fn query_address_voting_power_at_timestamp(
     querier: &QuerierWrapper,
-    ve_addr: &Addr,
+    gov_addr: &Addr,
     timestamp: u64,
     address: &Addr,
 ) -> StdResult<Uint128> {
-    let balance: StdResult<StakerResponse> = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: ve_addr.to_string(),
-        msg: to_binary(&VEQueryMessage::Staker {
-            address: address.to_string(),
-            timestamp: Some(timestamp),
-        })?,
-    }));
+    let voting_power = voting_power_query(gov_addr, address, Some(timestamp))?.query(querier);
 
-    Ok(balance.map_or(Uint128::zero(), |s| s.balance))
+    Ok(voting_power.map_or(Uint128::zero(), |v| v.voting_power))
 }
 
 pub fn query_total_voting_balance_at_timestamp(
@@ -81,23 +96,11 @@ pub fn query_total_voting_balance_at_timestamp(
     ve_addr: &Addr,
     timestamp: u64,
 ) -> StdResult<Uint128> {
-    let total_supply: StdResult<StateResponse> =
-        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: ve_addr.to_string(),
-            msg: to_binary(&VEQueryMessage::State {
-                timestamp: Some(timestamp),
-            })?,
-        }));
+    let total_supply = ve_state_query(ve_addr, Some(timestamp))?.query(querier);
 
     Ok(total_supply.map_or(Uint128::zero(), |t| t.total_balance))
 }
 
 pub fn query_oracle(deps: Deps, oracle_addr: String, round: u64) -> StdResult<OracleResponse> {
-    let oracle_response: OracleResponse =
-        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: oracle_addr,
-            msg: to_binary(&QueryOracle::GetRandomness { round })?,
-        }))?;
-
-    Ok(oracle_response)
+    SmartQuery::new(oracle_addr, &QueryOracle::GetRandomness { round })?.query(&deps.querier)
 }
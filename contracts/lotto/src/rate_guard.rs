@@ -0,0 +1,162 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{attr, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage};
+use glow_protocol::lotto::RateGuardResponse;
+
+use crate::error::ContractError;
+use crate::state::{RateGuard, CONFIG, RATE_GUARD};
+
+const SECONDS_PER_HOUR: u64 = 3600;
+
+pub fn read_rate_guard(storage: &dyn Storage) -> StdResult<RateGuard> {
+    Ok(RATE_GUARD.may_load(storage)?.unwrap_or_default())
+}
+
+/// Set the maximum deviation of the aust exchange rate per hour since the last
+/// accepted rate. None disables the circuit breaker - restricted to gov
+pub fn execute_update_rate_guard(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_deviation_per_hour: Option<Decimal256>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(max_deviation_per_hour) = max_deviation_per_hour {
+        if max_deviation_per_hour.is_zero() || max_deviation_per_hour > Decimal256::one() {
+            return Err(ContractError::InvalidRateDeviation {});
+        }
+    }
+
+    let mut rate_guard = read_rate_guard(deps.storage)?;
+    rate_guard.max_deviation_per_hour = max_deviation_per_hour;
+    RATE_GUARD.save(deps.storage, &rate_guard)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_rate_guard"),
+        attr(
+            "max_deviation_per_hour",
+            max_deviation_per_hour
+                .map(|deviation| deviation.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        ),
+    ]))
+}
+
+/// Accept rate as the last seen exchange rate after review, resetting a tripped
+/// circuit breaker and resuming the lottery it paused - restricted to gov
+pub fn execute_accept_exchange_rate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rate: Decimal256,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut rate_guard = read_rate_guard(deps.storage)?;
+    if rate_guard.tripped {
+        config.pause_state.lottery_paused = false;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    rate_guard.last_rate = rate;
+    rate_guard.last_rate_time = env.block.time.seconds();
+    rate_guard.tripped = false;
+    rate_guard.rejected_rate = None;
+    RATE_GUARD.save(deps.storage, &rate_guard)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "accept_exchange_rate"),
+        attr("rate", rate.to_string()),
+    ]))
+}
+
+/// Returns the deviation of rate from the last accepted rate and the maximum
+/// deviation allowed, if rate is out of bounds
+pub fn excessive_rate_deviation(
+    rate_guard: &RateGuard,
+    env: &Env,
+    rate: Decimal256,
+) -> Option<(Decimal256, Decimal256)> {
+    let max_deviation_per_hour = rate_guard.max_deviation_per_hour?;
+    if rate_guard.last_rate.is_zero() {
+        return None;
+    }
+
+    let difference = if rate > rate_guard.last_rate {
+        rate - rate_guard.last_rate
+    } else {
+        rate_guard.last_rate - rate
+    };
+    let deviation = difference / rate_guard.last_rate;
+
+    // The hour in progress counts as a whole one
+    let elapsed = env
+        .block
+        .time
+        .seconds()
+        .saturating_sub(rate_guard.last_rate_time);
+    let hours = elapsed / SECONDS_PER_HOUR + 1;
+    let max_deviation = max_deviation_per_hour * Decimal256::from_uint256(Uint256::from(hours));
+
+    if deviation > max_deviation {
+        Some((deviation, max_deviation))
+    } else {
+        None
+    }
+}
+
+/// Records rate as the last seen exchange rate if it is within the allowed deviation
+/// from the previous one. Otherwise trips the circuit breaker, pausing the lottery,
+/// and returns the response to end the execution with
+pub fn guard_exchange_rate(
+    storage: &mut dyn Storage,
+    env: &Env,
+    rate: Decimal256,
+) -> StdResult<Option<Response>> {
+    let mut rate_guard = read_rate_guard(storage)?;
+
+    if let Some((deviation, max_deviation)) = excessive_rate_deviation(&rate_guard, env, rate) {
+        rate_guard.tripped = true;
+        rate_guard.rejected_rate = Some(rate);
+        RATE_GUARD.save(storage, &rate_guard)?;
+
+        let mut config = CONFIG.load(storage)?;
+        config.pause_state.lottery_paused = true;
+        CONFIG.save(storage, &config)?;
+
+        return Ok(Some(Response::new().add_attributes(vec![
+            attr("action", "rate_guard_tripped"),
+            attr("last_rate", rate_guard.last_rate.to_string()),
+            attr("rejected_rate", rate.to_string()),
+            attr("deviation", deviation.to_string()),
+            attr("max_deviation", max_deviation.to_string()),
+        ])));
+    }
+
+    rate_guard.last_rate = rate;
+    rate_guard.last_rate_time = env.block.time.seconds();
+    RATE_GUARD.save(storage, &rate_guard)?;
+
+    Ok(None)
+}
+
+pub fn query_rate_guard(deps: Deps) -> StdResult<RateGuardResponse> {
+    let rate_guard = read_rate_guard(deps.storage)?;
+
+    Ok(RateGuardResponse {
+        max_deviation_per_hour: rate_guard.max_deviation_per_hour,
+        last_rate: rate_guard.last_rate,
+        last_rate_time: rate_guard.last_rate_time,
+        tripped: rate_guard.tripped,
+        rejected_rate: rate_guard.rejected_rate,
+    })
+}
@@ -0,0 +1,261 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{
+    attr, coin, to_binary, BankMsg, ContractResult, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Reply, Response, StdResult, Storage, SubMsg, Uint128, WasmMsg,
+};
+use cw20::{Cw20Coin, Cw20ExecuteMsg, MinterResponse};
+use glow_protocol::lotto::ReceiptTokenResponse;
+use glow_protocol::querier::deduct_tax;
+use serde::Serialize;
+
+use crate::anchor::{deposit_stable_msg, redeem_stable_msg};
+use crate::error::ContractError;
+use crate::querier::query_exchange_rate;
+use crate::state::{ReceiptToken, CONFIG, RECEIPT_TOKEN};
+
+pub const RECEIPT_TOKEN_REPLY_ID: u64 = 4;
+
+const RECEIPT_TOKEN_DECIMALS: u8 = 6;
+
+/// Instantiate message of the cw20 contract used as receipt token
+#[derive(Serialize)]
+struct TokenInstantiateMsg {
+    name: String,
+    symbol: String,
+    decimals: u8,
+    initial_balances: Vec<Cw20Coin>,
+    mint: Option<MinterResponse>,
+}
+
+/// aust held on behalf of receipt token holders, which is not part of the pool
+pub fn read_total_receipt_aust(storage: &dyn Storage) -> StdResult<Uint256> {
+    Ok(RECEIPT_TOKEN
+        .may_load(storage)?
+        .map(|receipt_token| receipt_token.total_aust)
+        .unwrap_or_default())
+}
+
+/// Instantiates the cw20 receipt token from code_id, with the lotto as minter - restricted to gov
+pub fn execute_create_receipt_token(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    code_id: u64,
+    name: String,
+    symbol: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if RECEIPT_TOKEN.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::ReceiptTokenAlreadyCreated {});
+    }
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Instantiate {
+                admin: Some(config.gov_contract.to_string()),
+                code_id,
+                msg: to_binary(&TokenInstantiateMsg {
+                    name,
+                    symbol,
+                    decimals: RECEIPT_TOKEN_DECIMALS,
+                    initial_balances: vec![],
+                    mint: Some(MinterResponse {
+                        minter: env.contract.address.to_string(),
+                        cap: None,
+                    }),
+                })?,
+                funds: vec![],
+                label: "glow receipt token".to_string(),
+            }),
+            RECEIPT_TOKEN_REPLY_ID,
+        ))
+        .add_attributes(vec![
+            attr("action", "create_receipt_token"),
+            attr("code_id", code_id.to_string()),
+        ]))
+}
+
+/// Stores the address of the instantiated receipt token
+pub fn handle_receipt_token_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let events = match msg.result {
+        ContractResult::Ok(response) => response.events,
+        ContractResult::Err(_) => return Err(ContractError::InvalidReceiptTokenReply {}),
+    };
+
+    let contract_address = events
+        .iter()
+        .filter(|event| event.ty == "instantiate" || event.ty == "instantiate_contract")
+        .flat_map(|event| event.attributes.iter())
+        .find(|attr| attr.key == "_contract_address" || attr.key == "contract_address")
+        .map(|attr| attr.value.clone())
+        .ok_or(ContractError::InvalidReceiptTokenReply {})?;
+
+    let contract = deps.api.addr_validate(&contract_address)?;
+    RECEIPT_TOKEN.save(
+        deps.storage,
+        &ReceiptToken {
+            contract: contract.clone(),
+            total_aust: Uint256::zero(),
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_receipt_token"),
+        attr("receipt_token", contract.to_string()),
+    ]))
+}
+
+/// Deposits the sent stable into anchor and mints one receipt token per minted aust.
+/// The deposit gets no tickets and its yield is not used for prizes.
+pub fn execute_receipt_deposit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut receipt_token = RECEIPT_TOKEN
+        .may_load(deps.storage)?
+        .ok_or(ContractError::ReceiptTokenNotCreated {})?;
+
+    let deposit_amount = info
+        .funds
+        .iter()
+        .find(|c| c.denom == config.stable_denom)
+        .map(|c| Uint256::from(c.amount))
+        .unwrap_or_else(Uint256::zero);
+
+    if deposit_amount.is_zero() {
+        return Err(ContractError::ZeroDepositAmount {});
+    }
+    if deposit_amount < config.min_deposit_amount {
+        return Err(ContractError::DepositBelowMinimum {
+            amount: deposit_amount,
+            min_deposit_amount: config.min_deposit_amount,
+        });
+    }
+
+    // Deduct tx taxes when calculating the net deposited amount in anchor
+    let post_tax_deposit_amount = Uint256::from(
+        deduct_tax(
+            deps.as_ref(),
+            coin(deposit_amount.into(), config.stable_denom.clone()),
+        )?
+        .amount,
+    );
+
+    let aust_exchange_rate = query_exchange_rate(
+        deps.as_ref(),
+        config.anchor_contract.to_string(),
+        env.block.height,
+    )?
+    .exchange_rate;
+    let minted_aust = post_tax_deposit_amount / aust_exchange_rate;
+
+    receipt_token.total_aust += minted_aust;
+    RECEIPT_TOKEN.save(deps.storage, &receipt_token)?;
+
+    Ok(Response::new()
+        .add_message(deposit_stable_msg(&config, post_tax_deposit_amount)?)
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: receipt_token.contract.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: info.sender.to_string(),
+                amount: minted_aust.into(),
+            })?,
+            funds: vec![],
+        }))
+        .add_attributes(vec![
+            attr("action", "receipt_deposit"),
+            attr("depositor", info.sender.to_string()),
+            attr("deposit_amount", deposit_amount.to_string()),
+            attr("minted_receipt", minted_aust.to_string()),
+        ]))
+}
+
+/// Burns amount of receipt tokens sent by the receipt token contract
+/// and sends the value of the same amount of aust to owner
+pub fn execute_redeem_receipt(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut receipt_token = RECEIPT_TOKEN
+        .may_load(deps.storage)?
+        .ok_or(ContractError::ReceiptTokenNotCreated {})?;
+
+    // only the receipt token can redeem receipts
+    if info.sender != receipt_token.contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let owner = deps.api.addr_validate(&owner)?;
+    let redeemed_aust = Uint256::from(amount);
+    if redeemed_aust.is_zero() {
+        return Err(ContractError::ZeroReceiptRedemption {});
+    }
+
+    receipt_token.total_aust -= redeemed_aust;
+    RECEIPT_TOKEN.save(deps.storage, &receipt_token)?;
+
+    let aust_exchange_rate = query_exchange_rate(
+        deps.as_ref(),
+        config.anchor_contract.to_string(),
+        env.block.height,
+    )?
+    .exchange_rate;
+
+    // Anchor sends the redeemed stable net of taxes
+    let redeemed_amount = Uint256::from(
+        deduct_tax(
+            deps.as_ref(),
+            coin(
+                (redeemed_aust * aust_exchange_rate).into(),
+                config.stable_denom.clone(),
+            ),
+        )?
+        .amount,
+    );
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: receipt_token.contract.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+            funds: vec![],
+        }))
+        .add_message(redeem_stable_msg(&config, redeemed_aust)?)
+        .add_message(CosmosMsg::Bank(BankMsg::Send {
+            to_address: owner.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                coin(redeemed_amount.into(), config.stable_denom),
+            )?],
+        }))
+        .add_attributes(vec![
+            attr("action", "redeem_receipt"),
+            attr("owner", owner.to_string()),
+            attr("burned_receipt", amount.to_string()),
+            attr("redeem_stable_amount", redeemed_amount.to_string()),
+        ]))
+}
+
+pub fn query_receipt_token(deps: Deps) -> StdResult<ReceiptTokenResponse> {
+    let receipt_token = RECEIPT_TOKEN.may_load(deps.storage)?;
+
+    Ok(ReceiptTokenResponse {
+        contract: receipt_token
+            .as_ref()
+            .map(|receipt_token| receipt_token.contract.to_string()),
+        total_aust: receipt_token
+            .map(|receipt_token| receipt_token.total_aust)
+            .unwrap_or_default(),
+    })
+}
@@ -0,0 +1,254 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    attr, coin, Addr, BankMsg, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Storage,
+};
+use glow_protocol::lotto::{GlowHookMsg, SavingsResponse};
+use glow_protocol::querier::deduct_tax;
+
+use crate::anchor::{deposit_stable_msg, redeem_stable_msg};
+use crate::contract::process_deposit;
+use crate::error::ContractError;
+use crate::events::deposit_event;
+use crate::hooks::hook_submsgs;
+use crate::querier::query_exchange_rate;
+use crate::state::{read_lottery_info, CONFIG, POOL, SAVINGS, STATE};
+
+pub fn read_savings(storage: &dyn Storage, depositor: &Addr) -> StdResult<Uint256> {
+    Ok(SAVINGS.may_load(storage, depositor)?.unwrap_or_default())
+}
+
+/// Takes amount, or all of the savings if not set, out of the savings of depositor.
+/// Returns the aust taken out
+fn take_savings(
+    deps: DepsMut,
+    env: &Env,
+    depositor: &Addr,
+    amount: Option<Uint256>,
+    aust_exchange_rate: Decimal256,
+) -> Result<Uint256, ContractError> {
+    let mut pool = POOL.load(deps.storage)?;
+    let savings_aust = read_savings(deps.storage, depositor)?;
+    let balance = savings_aust * aust_exchange_rate;
+
+    let amount = amount.unwrap_or(balance);
+    if amount.is_zero() {
+        return Err(ContractError::ZeroSavingsAmount {});
+    }
+    if amount > balance {
+        return Err(ContractError::InsufficientSavings { amount, balance });
+    }
+
+    let taken_aust = if amount == balance {
+        savings_aust
+    } else {
+        amount / aust_exchange_rate
+    };
+
+    if taken_aust == savings_aust {
+        SAVINGS.remove(deps.storage, depositor);
+    } else {
+        SAVINGS.save(deps.storage, depositor, &(savings_aust - taken_aust))?;
+    }
+    pool.total_savings_aust -= taken_aust;
+    POOL.save(deps.storage, &pool, env.block.height)?;
+
+    Ok(taken_aust)
+}
+
+/// Deposits the sent stable into anchor in the name of the sender, without tickets.
+/// The savings are kept in aust, so their whole appreciation stays with the sender
+pub fn execute_deposit_savings(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut pool = POOL.load(deps.storage)?;
+
+    let deposit_amount = info
+        .funds
+        .iter()
+        .find(|c| c.denom == config.stable_denom)
+        .map(|c| Uint256::from(c.amount))
+        .unwrap_or_else(Uint256::zero);
+
+    if deposit_amount.is_zero() {
+        return Err(ContractError::ZeroDepositAmount {});
+    }
+    if deposit_amount < config.min_deposit_amount {
+        return Err(ContractError::DepositBelowMinimum {
+            amount: deposit_amount,
+            min_deposit_amount: config.min_deposit_amount,
+        });
+    }
+
+    // Deduct tx taxes when calculating the net deposited amount in anchor
+    let post_tax_deposit_amount = Uint256::from(
+        deduct_tax(
+            deps.as_ref(),
+            coin(deposit_amount.into(), config.stable_denom.clone()),
+        )?
+        .amount,
+    );
+
+    let aust_exchange_rate = query_exchange_rate(
+        deps.as_ref(),
+        config.anchor_contract.to_string(),
+        env.block.height,
+    )?
+    .exchange_rate;
+    let minted_aust = post_tax_deposit_amount / aust_exchange_rate;
+
+    let savings_aust = read_savings(deps.storage, &info.sender)?;
+    SAVINGS.save(deps.storage, &info.sender, &(savings_aust + minted_aust))?;
+    pool.total_savings_aust += minted_aust;
+    POOL.save(deps.storage, &pool, env.block.height)?;
+
+    Ok(Response::new()
+        .add_message(deposit_stable_msg(&config, post_tax_deposit_amount)?)
+        .add_attributes(vec![
+            attr("action", "deposit_savings"),
+            attr("depositor", info.sender.to_string()),
+            attr("deposit_amount", deposit_amount.to_string()),
+            attr("aust_minted", minted_aust.to_string()),
+        ]))
+}
+
+/// Withdraws amount of the sender's savings, or all of them, at the current exchange rate
+pub fn execute_withdraw_savings(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Option<Uint256>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let aust_exchange_rate = query_exchange_rate(
+        deps.as_ref(),
+        config.anchor_contract.to_string(),
+        env.block.height,
+    )?
+    .exchange_rate;
+
+    let redeemed_aust = take_savings(
+        deps.branch(),
+        &env,
+        &info.sender,
+        amount,
+        aust_exchange_rate,
+    )?;
+
+    // Anchor sends the redeemed stable net of taxes
+    let redeemed_amount = deduct_tax(
+        deps.as_ref(),
+        coin(
+            (redeemed_aust * aust_exchange_rate).into(),
+            config.stable_denom.clone(),
+        ),
+    )?;
+
+    Ok(Response::new()
+        .add_message(redeem_stable_msg(&config, redeemed_aust)?)
+        .add_message(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![deduct_tax(deps.as_ref(), redeemed_amount.clone())?],
+        }))
+        .add_attributes(vec![
+            attr("action", "withdraw_savings"),
+            attr("depositor", info.sender.to_string()),
+            attr("redeemed_aust", redeemed_aust.to_string()),
+            attr("redeem_stable_amount", redeemed_amount.amount),
+        ]))
+}
+
+/// Moves amount of the sender's savings, or all of them, into the pool as a deposit
+/// for encoded_tickets. The aust stays in the contract, nothing is sent to anchor
+pub fn execute_convert_savings_to_tickets(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    encoded_tickets: String,
+    amount: Option<Uint256>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+
+    // Validate that the lottery has not already started
+    let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
+    if current_lottery.rand_round != 0 {
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
+    }
+
+    let aust_exchange_rate = query_exchange_rate(
+        deps.as_ref(),
+        config.anchor_contract.to_string(),
+        env.block.height,
+    )?
+    .exchange_rate;
+
+    let converted_aust = take_savings(
+        deps.branch(),
+        &env,
+        &info.sender,
+        amount,
+        aust_exchange_rate,
+    )?;
+
+    // Rounding can leave a unit of the converted aust out of the pool,
+    // which is then redeemed for the prizes like the sponsor yield
+    let converted_amount = converted_aust * aust_exchange_rate;
+    let (number_of_new_tickets, minted_aust) = process_deposit(
+        deps.branch(),
+        &env,
+        &config,
+        &info.sender,
+        converted_amount,
+        encoded_tickets,
+        None,
+    )?;
+
+    Ok(Response::new()
+        .add_submessages(hook_submsgs(
+            deps.storage,
+            GlowHookMsg::OnDeposit {
+                depositor: info.sender.to_string(),
+                recipient: info.sender.to_string(),
+                amount: converted_amount,
+                tickets: number_of_new_tickets,
+            },
+        )?)
+        .add_event(deposit_event(
+            &info.sender,
+            &info.sender,
+            converted_amount,
+            converted_amount,
+            number_of_new_tickets,
+            minted_aust,
+        ))
+        .add_attributes(vec![
+            attr("action", "convert_savings_to_tickets"),
+            attr("depositor", info.sender.to_string()),
+            attr("converted_amount", converted_amount.to_string()),
+            attr("tickets", number_of_new_tickets.to_string()),
+            attr("aust_minted", minted_aust.to_string()),
+        ]))
+}
+
+pub fn query_savings(deps: Deps, env: Env, address: String) -> StdResult<SavingsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let address = deps.api.addr_validate(&address)?;
+    let savings_aust = read_savings(deps.storage, &address)?;
+
+    let aust_exchange_rate =
+        query_exchange_rate(deps, config.anchor_contract.to_string(), env.block.height)?
+            .exchange_rate;
+
+    Ok(SavingsResponse {
+        address: address.to_string(),
+        savings_aust,
+        balance: savings_aust * aust_exchange_rate,
+    })
+}
@@ -0,0 +1,175 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, StdResult, Uint128};
+
+use crate::badges::{badge_mint_submsgs, record_sponsorship};
+use crate::error::ContractError;
+use crate::events::sponsor_event;
+use crate::helpers::{
+    compute_global_operator_reward, compute_global_sponsor_reward, compute_operator_reward,
+    compute_sponsor_reward, decimal_from_ratio_or_one, record_operator_share_checkpoint,
+};
+use crate::querier::query_exchange_rate;
+use crate::state::{
+    read_depositor_info, read_lottery_info, read_operator_info, read_sponsor_info,
+    remove_depositor_tickets, remove_ticket_acquisition, store_depositor_info, store_operator_info,
+    store_sponsor_info, CONFIG, POOL, STATE, TICKETS,
+};
+
+/// Converts amount worth of the sender's shares into a sponsorship of the sender.
+/// The aust backing the shares moves from the users to the sponsors without
+/// going through anchor, and the tickets the remaining balance can't afford are burnt
+pub fn execute_donate_shares_to_sponsorship(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+    let mut pool = POOL.load(deps.storage)?;
+
+    let mut depositor_info = read_depositor_info(deps.storage, &info.sender);
+
+    // Validate that the user has shares to donate
+    if depositor_info.shares.is_zero() {
+        return Err(ContractError::NoDepositorSavingsAustToWithdraw(
+            info.sender.to_string(),
+        ));
+    }
+
+    if amount.is_zero() {
+        return Err(ContractError::ZeroSponsorshipAmount {});
+    }
+
+    // Validate that there isn't a lottery in progress already
+    let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
+    if current_lottery.rand_round != 0 {
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
+    }
+
+    let aust_exchange_rate = query_exchange_rate(
+        deps.as_ref(),
+        config.anchor_contract.to_string(),
+        env.block.height,
+    )?
+    .exchange_rate;
+
+    // Get the number of donated shares, rounded up like withdrawals
+    let donated_shares = std::cmp::max(
+        (Uint256::from(amount) / aust_exchange_rate)
+            .multiply_ratio(pool.total_user_shares, pool.total_user_aust),
+        Uint256::one(),
+    );
+    let donated_aust = donated_shares.multiply_ratio(pool.total_user_aust, pool.total_user_shares);
+    let donated_aust_value = donated_aust * aust_exchange_rate;
+
+    let depositor_balance = pool.total_user_aust
+        * Decimal256::from_ratio(depositor_info.shares, pool.total_user_shares)
+        * aust_exchange_rate;
+
+    if donated_shares > depositor_info.shares || donated_aust_value > depositor_balance {
+        return Err(ContractError::SpecifiedWithdrawAmountTooBig {
+            amount,
+            depositor_balance,
+        });
+    }
+
+    // The split factor of the appreciation since the last lottery still belongs to the
+    // prizes, it becomes sponsor yield redeemed by the next lottery
+    let prize_share = if aust_exchange_rate > state.last_lottery_execution_aust_exchange_rate {
+        donated_aust
+            * (aust_exchange_rate - state.last_lottery_execution_aust_exchange_rate)
+            * config.split_factor
+    } else {
+        Uint256::zero()
+    };
+    let sponsorship_amount = donated_aust_value - prize_share;
+
+    // Burn the tickets the remaining balance can't afford
+    let post_transaction_depositor_balance = (pool.total_user_aust - donated_aust)
+        * decimal_from_ratio_or_one(
+            depositor_info.shares - donated_shares,
+            pool.total_user_shares - donated_shares,
+        )
+        * aust_exchange_rate;
+    let post_transaction_max_depositor_tickets = Uint128::from(
+        post_transaction_depositor_balance / Decimal256::from_uint256(config.ticket_price),
+    )
+    .u128() as usize;
+    let burnt_tickets = depositor_info
+        .num_tickets
+        .saturating_sub(post_transaction_max_depositor_tickets);
+
+    let burnt_sequences = remove_depositor_tickets(deps.storage, &info.sender, burnt_tickets)?;
+    depositor_info.num_tickets -= burnt_tickets;
+    for seq in burnt_sequences {
+        TICKETS.update(deps.storage, seq.as_bytes(), |tickets| -> StdResult<_> {
+            let mut new_tickets = tickets.unwrap();
+            let index = new_tickets.iter().position(|x| *x == info.sender).unwrap();
+            let _elem = new_tickets.remove(index);
+            Ok(new_tickets)
+        })?;
+        remove_ticket_acquisition(deps.storage, &seq, &info.sender)?;
+    }
+    state.total_tickets -= Uint256::from(burnt_tickets as u64);
+
+    // Update operator information
+    if depositor_info.operator_registered() {
+        let mut operator = read_operator_info(deps.storage, &depositor_info.operator_addr);
+        compute_global_operator_reward(&mut state, &pool, env.block.height);
+        compute_operator_reward(&state, &mut operator);
+        operator.shares -= donated_shares;
+        store_operator_info(deps.storage, &depositor_info.operator_addr, operator)?;
+
+        pool.total_operator_shares -= donated_shares;
+        record_operator_share_checkpoint(
+            deps.storage,
+            &depositor_info.operator_addr,
+            &pool,
+            env.block.time.seconds(),
+        )?;
+    }
+
+    depositor_info.shares -= donated_shares;
+    pool.total_user_shares -= donated_shares;
+    pool.total_user_aust -= donated_aust;
+
+    // Credit the sponsorship, the lock and weight bonuses are recomputed with it
+    compute_global_sponsor_reward(&mut state, &pool, env.block.height);
+    let mut sponsor_info = read_sponsor_info(deps.storage, &info.sender);
+    compute_sponsor_reward(&state, &mut sponsor_info);
+
+    pool.total_sponsor_lock_bonus -= sponsor_info.lock_bonus();
+    pool.total_sponsor_weight_bonus -= sponsor_info.weight_bonus();
+    if !sponsor_info.is_locked(env.block.time.seconds()) {
+        sponsor_info.lock_end_time = 0;
+        sponsor_info.lock_bonus_rate = Decimal256::zero();
+    }
+    sponsor_info.lottery_deposit += sponsorship_amount;
+
+    pool.total_sponsor_lottery_deposits += sponsorship_amount;
+    pool.total_sponsor_lock_bonus += sponsor_info.lock_bonus();
+    pool.total_sponsor_weight_bonus += sponsor_info.weight_bonus();
+
+    store_sponsor_info(deps.storage, &info.sender, sponsor_info)?;
+    store_depositor_info(deps.storage, &info.sender, depositor_info, env.block.height)?;
+    STATE.save(deps.storage, &state, env.block.height)?;
+    POOL.save(deps.storage, &pool, env.block.height)?;
+
+    record_sponsorship(deps.storage, &info.sender, sponsorship_amount)?;
+    let msgs = badge_mint_submsgs(deps.storage, &env, &info.sender)?;
+
+    Ok(Response::new()
+        .add_submessages(msgs)
+        .add_event(sponsor_event(&info.sender, sponsorship_amount, false))
+        .add_attributes(vec![
+            attr("action", "donate_shares_to_sponsorship"),
+            attr("sponsor", info.sender.to_string()),
+            attr("donated_shares", donated_shares.to_string()),
+            attr("donated_aust", donated_aust.to_string()),
+            attr("burnt_tickets", burnt_tickets.to_string()),
+            attr("sponsorship_amount", sponsorship_amount),
+        ]))
+}
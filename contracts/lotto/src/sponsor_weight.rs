@@ -0,0 +1,59 @@
+use cosmwasm_bignumber::Decimal256;
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+use crate::error::ContractError;
+use crate::helpers::{compute_global_sponsor_reward, compute_sponsor_reward};
+use crate::state::{read_sponsor_info, store_sponsor_info, CONFIG, POOL, STATE};
+
+// Bounds of the emission weight of a sponsor, 1 being the regular emissions
+pub const MIN_SPONSOR_EMISSION_WEIGHT: u64 = 1;
+pub const MAX_SPONSOR_EMISSION_WEIGHT: u64 = 5;
+
+/// Set the emission weight negotiated with a sponsor, multiplying the GLOW
+/// emissions of its sponsorship from now on - restricted to owner and gov
+pub fn execute_set_sponsor_weight(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sponsor: String,
+    weight: Decimal256,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.owner && info.sender != config.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if weight < Decimal256::from_uint256(MIN_SPONSOR_EMISSION_WEIGHT)
+        || weight > Decimal256::from_uint256(MAX_SPONSOR_EMISSION_WEIGHT)
+    {
+        return Err(ContractError::InvalidSponsorEmissionWeight {
+            min: MIN_SPONSOR_EMISSION_WEIGHT,
+            max: MAX_SPONSOR_EMISSION_WEIGHT,
+        });
+    }
+
+    let sponsor = deps.api.addr_validate(&sponsor)?;
+    let mut sponsor_info = read_sponsor_info(deps.storage, &sponsor);
+    let mut pool = POOL.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+
+    // Rewards accrued so far are distributed at the previous weight
+    compute_global_sponsor_reward(&mut state, &pool, env.block.height);
+    compute_sponsor_reward(&state, &mut sponsor_info);
+
+    pool.total_sponsor_weight_bonus -= sponsor_info.weight_bonus();
+    sponsor_info.emission_weight = weight;
+    pool.total_sponsor_weight_bonus += sponsor_info.weight_bonus();
+
+    store_sponsor_info(deps.storage, &sponsor, sponsor_info)?;
+    POOL.save(deps.storage, &pool, env.block.height)?;
+    STATE.save(deps.storage, &state, env.block.height)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_sponsor_weight"),
+        attr("sponsor", sponsor),
+        attr("weight", weight.to_string()),
+    ]))
+}
@@ -5,15 +5,23 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::{Addr, Deps, Order, StdError, StdResult, Storage, Timestamp};
+use cosmwasm_std::{
+    Addr, Binary, Decimal, Deps, Empty, Order, StdError, StdResult, Storage, Timestamp, Uint128,
+};
 use cosmwasm_storage::{bucket, bucket_read, ReadonlyBucket};
 use cw0::{Duration, Expiration};
-use cw_storage_plus::{Bound, Item, Map, SnapshotMap, U64Key};
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, U32Key, U64Key};
+use glow_protocol::common::{Pagination, DEFAULT_LIMIT};
 use glow_protocol::lotto::{
-    BoostConfig, Claim, DepositorInfoResponse, DepositorStatsResponse, RewardEmissionsIndex,
+    BadgeTier, BoostConfig, BuybackDestination, Claim, ClaimDestination, ClaimReceiptStatus,
+    ConfigVersion, DepositorInfoResponse, DepositorStatsResponse, DrawTimeline,
+    EmissionDecaySchedule, IbcClaimConfig, InstantWithdrawalCap, KeeperAllowlist, LoyaltyConfig,
+    MissedLotteryPolicy, OperatorDepositorResponse, PauseState, PrizeCap, PrizeCursor,
+    PrizeRebalancingConfig, ProtocolCheckpoint, ReserveDestination, RewardEmissionsIndex,
+    SponsorLockConfig, TimeWeightWindow, TimelockedConfigUpdate,
 };
 
-use glow_protocol::lotto::NUM_PRIZE_BUCKETS;
+use glow_protocol::lotto::{NUM_PRIZE_BUCKETS, TICKET_LENGTH};
 
 pub const OLD_PREFIX_LOTTERY: &[u8] = b"lottery";
 pub const PREFIX_SPONSOR: &[u8] = b"sponsor";
@@ -22,15 +30,41 @@ pub const OLD_PREFIX_DEPOSIT: &[u8] = b"depositor";
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const OLDCONFIG: Item<OldConfig> = Item::new("config");
-pub const STATE: Item<State> = Item::new("state");
+pub const STATE: SnapshotItem<State> = SnapshotItem::new(
+    "state",
+    "state__checkpoint",
+    "state__changelog",
+    cw_storage_plus::Strategy::EveryBlock,
+);
 pub const OLDSTATE: Item<OldState> = Item::new("state");
-pub const POOL: Item<Pool> = Item::new("pool");
+pub const POOL: SnapshotItem<Pool> = SnapshotItem::new(
+    "pool",
+    "pool__checkpoint",
+    "pool__changelog",
+    cw_storage_plus::Strategy::EveryBlock,
+);
 pub const OLDPOOL: Item<OldPool> = Item::new("pool");
+pub const CONTRACT_VERSION: Item<ContractVersion> = Item::new("contract_version");
+// Progress of the paginated migration steps, by step id
+pub const MIGRATION_PROGRESS: Map<U64Key, MigrationProgress> = Map::new("migration_progress");
 pub const TICKETS: Map<&[u8], Vec<Addr>> = Map::new("tickets");
+// Lottery ids at which a depositor acquired each copy of a ticket
+pub const TICKET_ACQUISITIONS: Map<(&[u8], &Addr), Vec<u64>> = Map::new("ticket_acquisitions");
+// Block times in seconds at which a depositor acquired each copy of a ticket, in the same
+// order as TICKET_ACQUISITIONS. Copies acquired before times were tracked are zero-padded
+pub const TICKET_ACQUISITION_TIMES: Map<(&[u8], &Addr), Vec<u64>> =
+    Map::new("ticket_acquisition_times");
 pub const OLD_PRIZES: Map<(&Addr, U64Key), PrizeInfo> = Map::new("prizes");
 pub const PRIZES: Map<(U64Key, &Addr), PrizeInfo> = Map::new("prizes_v2");
+// Glow boost of each claimed prize, kept to audit the GLOW paid to winners
+pub const PRIZE_BOOSTS: Map<(U64Key, &Addr), PrizeBoost> = Map::new("prize_boosts");
+// Prizes committed to in the winners merkle root of each awarded lottery
+pub const WINNER_LEAVES: Map<(U64Key, &Addr), WinnerLeaf> = Map::new("winner_leaves");
 
 pub const DEPOSITOR_DATA: Map<&Addr, DepositorData> = Map::new("depositor_data");
+// Copies of each ticket held by a depositor, keyed by (depositor, binary ticket).
+// The number of tickets of a depositor is counted in DepositorStatsInfo.num_tickets
+pub const DEPOSITOR_TICKETS: Map<(&Addr, &[u8]), u32> = Map::new("depositor_tickets");
 pub const DEPOSITOR_STATS: SnapshotMap<&Addr, DepositorStatsInfo> = SnapshotMap::new(
     "depositor_stats",
     "depositor_stats__checkpoint",
@@ -39,13 +73,154 @@ pub const DEPOSITOR_STATS: SnapshotMap<&Addr, DepositorStatsInfo> = SnapshotMap:
 );
 
 pub const LOTTERIES: Map<U64Key, LotteryInfo> = Map::new("lo_v2");
+// Lottery infos written before the prize cursor replaced the page string
+pub const LEGACY_LOTTERY_PAGES: Map<U64Key, LegacyLotteryPage> = Map::new("lo_v2");
+pub const QUICK_PICK_NONCES: Map<&Addr, u64> = Map::new("quick_pick_nonces");
+pub const PENDING_ANCHOR_OPERATIONS: Item<PendingAnchorOperations> =
+    Item::new("pending_anchor_operations");
+// Aust amount of the anchor redemptions in flight, by reply id
+pub const ANCHOR_IN_FLIGHT: Map<U64Key, Uint256> = Map::new("anchor_in_flight");
+pub const NEXT_ANCHOR_OPERATION_ID: Item<u64> = Item::new("next_anchor_operation_id");
+pub const DEPOSITOR_LEDGER: Map<&Addr, DepositorLedger> = Map::new("depositor_ledger");
+pub const SHARE_PRICE_CHECKPOINTS: Item<Vec<SharePriceCheckpoint>> =
+    Item::new("share_price_checkpoints");
+// Protocol statistics of the last MAX_PROTOCOL_CHECKPOINTS epochs, by epoch
+pub const PROTOCOL_CHECKPOINTS: Map<U64Key, ProtocolCheckpoint> = Map::new("protocol_checkpoints");
+
+pub const SCHEDULED_UPDATES: Map<U64Key, ScheduledUpdate> = Map::new("scheduled_updates");
+pub const NEXT_SCHEDULED_UPDATE_ID: Item<u64> = Item::new("next_scheduled_update_id");
+// Terms before the last MAX_CONFIG_VERSIONS flagged timelocked updates, oldest first
+pub const CONFIG_VERSIONS: Item<Vec<ConfigVersion>> = Item::new("config_versions");
+pub const LAST_SKIM_TIME: Item<u64> = Item::new("last_skim_time");
+pub const IBC_CLAIMS: Map<U64Key, IbcClaim> = Map::new("ibc_claims");
+// Number of epoch operations executed so far
+pub const CURRENT_EPOCH: Item<u64> = Item::new("current_epoch");
+pub const OPERATOR_REWARD_LEDGERS: Map<&Addr, OperatorRewardLedger> =
+    Map::new("operator_reward_ledgers");
+pub const NEXT_IBC_CLAIM_ID: Item<u64> = Item::new("next_ibc_claim_id");
+pub const SYNDICATES: Map<U64Key, Syndicate> = Map::new("syndicates");
+pub const NEXT_SYNDICATE_ID: Item<u64> = Item::new("next_syndicate_id");
+// Addresses barred from depositing, gifting and claiming
+pub const BLACKLIST: Map<&Addr, BlacklistEntry> = Map::new("blacklist");
+// Owner funded glow used to pay glow prizes when the distributor can't
+pub const GLOW_PRIZE_RESERVE: Item<GlowPrizeReserve> = Item::new("glow_prize_reserve");
+// Stable donated to each prize bucket, kept apart from sponsor deposits
+pub const PRIZE_BUCKET_DONATIONS: Item<Vec<Uint256>> = Item::new("prize_bucket_donations");
+// Total stable donated to the prize buckets by each donor
+pub const PRIZE_DONORS: Map<&Addr, Uint256> = Map::new("prize_donors");
+// Withdrawals waiting for anchor redemption capacity, by id in arrival order
+pub const WITHDRAWAL_QUEUE: Map<U64Key, QueuedWithdrawal> = Map::new("withdrawal_queue");
+pub const WITHDRAWAL_QUEUE_STATE: Item<WithdrawalQueueState> = Item::new("withdrawal_queue_state");
+// Stable held for deposits signed by the escrow owner and submitted by relayers
+pub const ESCROWS: Map<&Addr, Escrow> = Map::new("escrows");
+pub const TOTAL_ESCROWED: Item<Uint256> = Item::new("total_escrowed");
+// Unredeemed gift cards by code hash, and the stable held for them
+pub const GIFT_CARDS: Map<&[u8], GiftCard> = Map::new("gift_cards");
+pub const TOTAL_GIFT_CARDS: Item<Uint256> = Item::new("total_gift_cards");
+// Block height of each gift card redemption commitment
+pub const GIFT_CARD_COMMITMENTS: Map<&[u8], u64> = Map::new("gift_card_commitments");
+// Share of the reserve swapped to GLOW during epoch operations, unset if disabled
+pub const BUYBACK: Item<Buyback> = Item::new("buyback");
+pub const BUYBACK_STATE: Item<BuybackState> = Item::new("buyback_state");
+pub const BUYBACK_IN_FLIGHT: Item<BuybackInFlight> = Item::new("buyback_in_flight");
+// Reserves sent to the fee distributor by the reserve sweep, until its reply
+pub const FEE_DISTRIBUTION_IN_FLIGHT: Item<Uint256> = Item::new("fee_distribution_in_flight");
+// Decay of the GLOW emission rates at each epoch operation, set by gov
+pub const EMISSION_DECAY: Item<EmissionDecay> = Item::new("emission_decay");
+// Named prize distributions award sponsorships can use, managed by gov
+pub const PRIZE_TEMPLATES: Map<&str, Vec<Decimal256>> = Map::new("prize_templates");
+// cw20 token minted for deposits made without tickets, unset until created by gov
+pub const RECEIPT_TOKEN: Item<ReceiptToken> = Item::new("receipt_token");
+// aust deposited in savings mode by each depositor, out of the lottery
+pub const SAVINGS: Map<&Addr, Uint256> = Map::new("savings");
+// Charities winners can donate to, kept with their totals once unregistered
+pub const CHARITIES: Map<&Addr, Charity> = Map::new("charities");
+// Agents allowed to claim prizes for a winner, with the authorization time in seconds
+pub const CLAIM_AGENTS: Map<(&Addr, &Addr), u64> = Map::new("claim_agents");
+// Hash of the alternate payout address registered by each depositor, used once
+pub const PAYOUT_HASHES: Map<&Addr, Vec<u8>> = Map::new("payout_hashes");
+// Depositors registered for auto claims, with the registration time in seconds
+pub const AUTO_CLAIMS: Map<&Addr, u64> = Map::new("auto_claims");
+// Last depositor whose auto claim was processed, unset once the registry was scanned through
+pub const AUTO_CLAIM_CURSOR: Item<Addr> = Item::new("auto_claim_cursor");
+// Share of auto claims paid to keepers, DEFAULT_AUTO_CLAIM_BOUNTY_RATE until set by gov
+pub const AUTO_CLAIM_BOUNTY_RATE: Item<Decimal256> = Item::new("auto_claim_bounty_rate");
+// Slice of the reserve kept by the contract to cover redemption shortfalls
+pub const INSURANCE: Item<Insurance> = Item::new("insurance");
+pub const JACKPOT_FLOOR: Item<JackpotFloor> = Item::new("jackpot_floor");
+// Prize claims by receipt id, recorded before their payout messages are sent
+pub const CLAIM_RECEIPTS: Map<U64Key, ClaimReceipt> = Map::new("claim_receipts");
+pub const NEXT_CLAIM_RECEIPT_ID: Item<u64> = Item::new("next_claim_receipt_id");
+// Receipt whose GLOW payout waits for its reply, no other claim can start meanwhile
+pub const PENDING_CLAIM_RECEIPT: Item<u64> = Item::new("pending_claim_receipt");
+// Last accepted aust exchange rate and the circuit breaker on its deviation
+pub const RATE_GUARD: Item<RateGuard> = Item::new("rate_guard");
+// Lottery id from which the next Exit of a depositor scans for unclaimed prizes,
+// the lotteries before it were already scanned
+pub const EXIT_CURSORS: Map<&Addr, u64> = Map::new("exit_cursors");
+// Contracts notified of deposits, withdrawals and prize claims
+pub const HOOKS: Item<Vec<Addr>> = Item::new("hooks");
+// Limit on instant withdrawals per epoch, unset when disabled
+pub const INSTANT_WITHDRAWAL_CAP: Item<InstantWithdrawalCap> = Item::new("instant_withdrawal_cap");
+// Stable withdrawn instantly in the current epoch, reset by the epoch operations
+pub const INSTANT_WITHDRAWALS: Item<InstantWithdrawals> = Item::new("instant_withdrawals");
+// Bounds of the automatic re-balancing of prize_distribution, unset when disabled
+pub const PRIZE_REBALANCING: Item<PrizeRebalancing> = Item::new("prize_rebalancing");
+// Caps on the UST awarded by each prize bucket, unset when uncapped
+pub const PRIZE_CAPS: Item<Vec<PrizeCap>> = Item::new("prize_caps");
+// Keepers with exclusive access to the lottery executions, unset when permissionless
+pub const KEEPER_ALLOWLIST: Item<KeeperAllowlist> = Item::new("keeper_allowlist");
+// Winners and prizes awarded per tier over all awarded lotteries
+pub const PRIZE_TIER_STATS: Item<PrizeTierStats> = Item::new("prize_tier_stats");
+// Sponsor badge contract and tiers, unset when badges aren't minted
+pub const BADGE_CONFIG: Item<BadgeConfig> = Item::new("badge_config");
+// Stable sponsored by each address over all its sponsorships, withdrawn or not
+pub const SPONSORSHIP_TOTALS: Map<&Addr, Uint256> = Map::new("sponsorship_totals");
+// Badge tiers minted to each sponsor, with the mint time in seconds
+pub const MINTED_BADGES: Map<(&Addr, U32Key), u64> = Map::new("minted_badges");
+// Badge mints waiting for their reply, in the order they were sent
+pub const BADGE_MINTS_IN_FLIGHT: Item<Vec<BadgeMint>> = Item::new("badge_mints_in_flight");
+// Last execution of each epoch operation, keyed by operation name
+pub const EPOCH_OPERATIONS: Map<&str, EpochOperationState> = Map::new("epoch_operations");
+// Id after which the next SweepExpired epoch operation resumes scanning ibc claims
+pub const IBC_CLAIM_SWEEP_CURSOR: Item<u64> = Item::new("ibc_claim_sweep_cursor");
+// Prize counters per winner, so wallets don't have to scan every lottery
+pub const INBOXES: Map<&Addr, Inbox> = Map::new("inboxes");
+// Shares of the syndicate deposit owned by each member, snapshotted to split prizes
+pub const SYNDICATE_MEMBERS: SnapshotMap<(U64Key, &Addr), Uint256> = SnapshotMap::new(
+    "syndicate_members",
+    "syndicate_members__checkpoint",
+    "syndicate_members__changelog",
+    cw_storage_plus::Strategy::EveryBlock,
+);
+// (syndicate_id, (lottery_id, member)) of the syndicate prizes already claimed by a member
+pub const SYNDICATE_PRIZE_CLAIMS: Map<(U64Key, (U64Key, &Addr)), Empty> =
+    Map::new("syndicate_prize_claims");
+// Secondary index of the depositors attributed to each operator
+pub const OPERATOR_DEPOSITORS: Map<(&Addr, &Addr), Empty> = Map::new("operator_depositors");
+pub const OPERATOR_SHARE_CHECKPOINTS: Map<&Addr, Vec<OperatorShareCheckpoint>> =
+    Map::new("operator_share_checkpoints");
+// Deposits attributed to each (operator, campaign_id)
+pub const OPERATOR_CAMPAIGNS: Map<(&Addr, &str), CampaignStats> = Map::new("operator_campaigns");
+// Tickets acquired within the ticket eligibility delay, per depositor and in total
+pub const PENDING_TICKETS: Map<&Addr, PendingTickets> = Map::new("pending_tickets");
+pub const TOTAL_PENDING_TICKETS: Item<PendingTickets> = Item::new("total_pending_tickets");
+
+// Number of epoch checkpoints the share price twap is computed over
+pub const MAX_SHARE_PRICE_CHECKPOINTS: usize = 24;
+// Number of epochs kept in the protocol statistics, a year of weekly epochs
+pub const MAX_PROTOCOL_CHECKPOINTS: u64 = 52;
+// Number of share changes kept in the history of each operator
+pub const MAX_OPERATOR_SHARE_CHECKPOINTS: usize = 24;
 
 use crate::helpers::{
+    binary_ticket_to_sequence, get_minimum_matches_for_winning_ticket, sequence_to_binary_ticket,
     vec_binary_tickets_to_vec_string_tickets, vec_string_tickets_to_vec_binary_tickets,
 };
 
-// settings for pagination
-const DEFAULT_LIMIT: u32 = 10;
+// settings for the pagination of depositor tickets, which are far smaller and more numerous
+const DEFAULT_TICKETS_LIMIT: u32 = 100;
+const MAX_TICKETS_LIMIT: u32 = 1000;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
@@ -64,16 +239,65 @@ pub struct Config {
     pub round_delta: u64,
     pub ticket_price: Uint256,
     pub max_holders: u8,
-    pub prize_distribution: [Decimal256; NUM_PRIZE_BUCKETS],
+    pub prize_distribution: Vec<Decimal256>,
     pub target_award: Uint256,
     pub reserve_factor: Decimal256,
     pub split_factor: Decimal256,
     pub instant_withdrawal_fee: Decimal256,
     pub unbonding_period: Duration,
     pub max_tickets_per_depositor: u64,
-    pub glow_prize_buckets: [Uint256; NUM_PRIZE_BUCKETS],
+    pub glow_prize_buckets: Vec<Uint256>,
     pub paused: bool,
     pub lotto_winner_boost_config: BoostConfig,
+    #[serde(default)]
+    pub pause_state: PauseState,
+    #[serde(default)]
+    pub reserve_destination: ReserveDestination,
+    #[serde(default)]
+    pub fee_distributor_contract: Option<Addr>,
+    // GLOW-UST LP staking contract receiving lp_staking_reserve_share of the reserve
+    #[serde(default)]
+    pub lp_staking_contract: Option<Addr>,
+    #[serde(default)]
+    pub lp_staking_reserve_share: Decimal256,
+    // deposits, gifts and sponsorships below this amount are rejected
+    #[serde(default)]
+    pub min_deposit_amount: Uint256,
+    #[serde(default)]
+    pub loyalty_config: LoyaltyConfig,
+    #[serde(default)]
+    pub sponsor_lock_config: SponsorLockConfig,
+    #[serde(default)]
+    pub ibc_claim_config: IbcClaimConfig,
+    // operator rewards unlock linearly over this many epochs, zero unlocks them instantly
+    #[serde(default)]
+    pub operator_reward_vesting_epochs: u64,
+    // any single guardian can pause the contract, but not unpause it
+    #[serde(default)]
+    pub guardians: Vec<Addr>,
+    // seconds before a draw during which acquired tickets only take part in the next one
+    #[serde(default)]
+    pub ticket_eligibility_delay: u64,
+    #[serde(default)]
+    pub missed_lottery_policy: MissedLotteryPolicy,
+    // seconds after blacklisting before the claims of an address can be redirected
+    #[serde(default)]
+    pub blacklist_dispute_window: u64,
+    // winning tickets share their prize buckets by shares per ticket times
+    // the fraction of the round they were held
+    #[serde(default)]
+    pub time_weighted_odds: bool,
+    // number of characters of the ticket sequences, set at instantiate
+    #[serde(default = "default_ticket_length")]
+    pub ticket_length: u8,
+    // matches of a winning ticket, prize_distribution being zero below it.
+    // Configs migrated without it derive it from prize_distribution
+    #[serde(default)]
+    pub min_matches_for_prize: Option<u8>,
+}
+
+fn default_ticket_length() -> u8 {
+    TICKET_LENGTH as u8
 }
 
 impl Config {
@@ -82,6 +306,20 @@ impl Config {
             && self.community_contract != Addr::unchecked("")
             && self.distributor_contract != Addr::unchecked("")
     }
+
+    /// A prize bucket for each number of matching characters, zero included
+    pub fn num_prize_buckets(&self) -> usize {
+        self.ticket_length as usize + 1
+    }
+
+    /// Minimum number of matches of a winning ticket, min_matches_for_prize
+    /// or the first non zero share of prize_distribution if not set
+    pub fn min_matches_for_prize(&self) -> StdResult<usize> {
+        match self.min_matches_for_prize {
+            Some(min_matches) => Ok(min_matches as usize),
+            None => get_minimum_matches_for_winning_ticket(&self.prize_distribution),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -99,7 +337,7 @@ pub struct OldConfig {
     pub round_delta: u64,
     pub ticket_price: Uint256,
     pub max_holders: u8,
-    pub prize_distribution: [Decimal256; NUM_PRIZE_BUCKETS],
+    pub prize_distribution: Vec<Decimal256>,
     pub target_award: Uint256,
     pub reserve_factor: Decimal256,
     pub split_factor: Decimal256,
@@ -117,7 +355,7 @@ impl OldConfig {
 pub struct State {
     pub total_tickets: Uint256,
     pub total_reserve: Uint256,
-    pub prize_buckets: [Uint256; NUM_PRIZE_BUCKETS],
+    pub prize_buckets: Vec<Uint256>,
     pub current_lottery: u64,
     pub next_lottery_time: Expiration,
     pub next_lottery_exec_time: Expiration,
@@ -125,13 +363,22 @@ pub struct State {
     pub operator_reward_emission_index: RewardEmissionsIndex,
     pub sponsor_reward_emission_index: RewardEmissionsIndex,
     pub last_lottery_execution_aust_exchange_rate: Decimal256,
+    // catch-up lotteries left to run under MissedLotteryPolicy::RunAllMissed
+    #[serde(default)]
+    pub missed_lotteries: u64,
+    // redeemed yield held back for the catch-up lotteries
+    #[serde(default)]
+    pub missed_lottery_prizes: Uint256,
+    // UST value lost to negative yield as of the last lottery execution
+    #[serde(default)]
+    pub yield_deficit: Uint256,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct OldState {
     pub total_tickets: Uint256,
     pub total_reserve: Uint256,
-    pub prize_buckets: [Uint256; NUM_PRIZE_BUCKETS],
+    pub prize_buckets: Vec<Uint256>,
     pub current_lottery: u64,
     pub next_lottery_time: Expiration,
     pub next_lottery_exec_time: Expiration,
@@ -160,6 +407,31 @@ pub struct Pool {
     // This is used for
     // - calculating the global reward index
     pub total_operator_shares: Uint256,
+    // Extra GLOW emission weight granted to locked sponsorships,
+    // the sum of lottery_deposit * lock_bonus_rate across all sponsors.
+    // This is used for:
+    // - calculating the global sponsor reward index
+    #[serde(default)]
+    pub total_sponsor_lock_bonus: Uint256,
+    // Extra GLOW emission weight granted by sponsor emission weights,
+    // the sum of (lottery_deposit + lock bonus) * (emission_weight - 1) across all sponsors.
+    // This is used for:
+    // - calculating the global sponsor reward index
+    #[serde(default)]
+    pub total_sponsor_weight_bonus: Uint256,
+    // aust deposited by the community contract as protocol owned liquidity,
+    // minus the aust redeemed for prizes when executing the lottery.
+    // Its whole appreciation goes to the prizes and it holds no tickets
+    #[serde(default)]
+    pub total_pol_aust: Uint256,
+    // UST value of the protocol owned liquidity the community can withdraw. The value
+    // of total_pol_aust above it is the yield redeemed for the prizes
+    #[serde(default)]
+    pub total_pol_principal: Uint256,
+    // aust deposited in savings mode, out of the lottery. Its whole appreciation
+    // goes to the savings depositors and it holds no tickets
+    #[serde(default)]
+    pub total_savings_aust: Uint256,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -181,9 +453,24 @@ pub struct DepositorStatsInfo {
     pub operator_addr: Addr,
 }
 
+/// Lifetime totals of a depositor's activity
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct DepositorLedger {
+    // UST deposited into the pool, after taxes
+    pub deposited: Uint256,
+    // UST returned by withdrawals, after instant withdrawal fees
+    pub withdrawn: Uint256,
+    // UST prizes claimed, whether sent or redeposited
+    pub ust_won: Uint256,
+    // GLOW prizes claimed
+    pub glow_won: Uint256,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct DepositorData {
-    // The number of tickets the user owns.
+    // Legacy storage of the tickets the user owns. Tickets are now stored in
+    // DEPOSITOR_TICKETS and these are moved there the next time the depositor is stored.
+    #[serde(default)]
     pub vec_binary_tickets: Vec<[u8; 3]>,
     // Stores information on the user's unbonding claims.
     pub unbonding_info: Vec<Claim>,
@@ -218,8 +505,8 @@ pub struct DepositorInfo {
     // shares * total_user_aust / total_user_shares gives the amount of aust
     // that a depositor owns and has available to withdraw.
     pub shares: Uint256,
-    // The number of tickets the user owns.
-    pub tickets: Vec<String>,
+    // The number of tickets the user owns. The tickets themselves are stored in DEPOSITOR_TICKETS
+    pub num_tickets: usize,
     // Stores information on the user's unbonding claims.
     pub unbonding_info: Vec<Claim>,
     // Stores information on the frontend operator or referrer used
@@ -243,6 +530,36 @@ pub struct SponsorInfo {
     pub pending_rewards: Decimal256,
     // Reward index is used for tracking and calculating the sponsor's rewards
     pub reward_index: Decimal256,
+    // Time in seconds until which the sponsorship is locked, zero if it isn't
+    #[serde(default)]
+    pub lock_end_time: u64,
+    // Extra emission weight per unit of lottery_deposit granted by the lock
+    #[serde(default)]
+    pub lock_bonus_rate: Decimal256,
+    // Multiplier of the sponsorship's GLOW emissions, set by the owner or gov
+    // for sponsors with a negotiated emission deal
+    #[serde(default = "default_sponsor_emission_weight")]
+    pub emission_weight: Decimal256,
+}
+
+fn default_sponsor_emission_weight() -> Decimal256 {
+    Decimal256::one()
+}
+
+impl SponsorInfo {
+    pub fn is_locked(&self, time: u64) -> bool {
+        time < self.lock_end_time
+    }
+
+    // Extra GLOW emission weight of the sponsorship, accounted in total_sponsor_lock_bonus
+    pub fn lock_bonus(&self) -> Uint256 {
+        self.lottery_deposit * self.lock_bonus_rate
+    }
+
+    // Extra GLOW emission weight of the emission_weight, accounted in total_sponsor_weight_bonus
+    pub fn weight_bonus(&self) -> Uint256 {
+        (self.lottery_deposit + self.lock_bonus()) * (self.emission_weight - Decimal256::one())
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -258,6 +575,401 @@ pub struct OperatorInfo {
     pub reward_index: Decimal256,
 }
 
+/// Operator rewards accrued in an epoch, unlocking linearly from that epoch on
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingTranche {
+    pub epoch: u64,
+    pub amount: Decimal256,
+    // Part of the amount already paid out
+    pub claimed: Decimal256,
+}
+
+impl VestingTranche {
+    fn vested(&self, current_epoch: u64, vesting_epochs: u64) -> Decimal256 {
+        let elapsed_epochs = current_epoch.saturating_sub(self.epoch);
+        if elapsed_epochs >= vesting_epochs {
+            return self.amount;
+        }
+        self.amount * Decimal256::from_ratio(elapsed_epochs, vesting_epochs)
+    }
+
+    fn claimable(&self, current_epoch: u64, vesting_epochs: u64) -> Decimal256 {
+        let vested = self.vested(current_epoch, vesting_epochs);
+        if vested > self.claimed {
+            vested - self.claimed
+        } else {
+            Decimal256::zero()
+        }
+    }
+}
+
+/// Operator rewards that have left OperatorInfo.pending_rewards and are vesting
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct OperatorRewardLedger {
+    // Fully vested rewards that haven't been claimed yet
+    pub unlocked: Decimal256,
+    // Rewards still vesting, at most one per epoch
+    pub tranches: Vec<VestingTranche>,
+}
+
+impl OperatorRewardLedger {
+    /// Moves pending rewards into a tranche of the current epoch and folds
+    /// the tranches that have fully vested into the unlocked balance
+    pub fn settle(&mut self, pending_rewards: Decimal256, current_epoch: u64, vesting_epochs: u64) {
+        if !pending_rewards.is_zero() {
+            match self
+                .tranches
+                .iter_mut()
+                .find(|tranche| tranche.epoch == current_epoch)
+            {
+                Some(tranche) => tranche.amount += pending_rewards,
+                None => self.tranches.push(VestingTranche {
+                    epoch: current_epoch,
+                    amount: pending_rewards,
+                    claimed: Decimal256::zero(),
+                }),
+            }
+        }
+
+        let mut unlocked = self.unlocked;
+        self.tranches.retain(|tranche| {
+            if tranche.vested(current_epoch, vesting_epochs) == tranche.amount {
+                unlocked += tranche.claimable(current_epoch, vesting_epochs);
+                false
+            } else {
+                true
+            }
+        });
+        self.unlocked = unlocked;
+    }
+
+    pub fn claimable(&self, current_epoch: u64, vesting_epochs: u64) -> Decimal256 {
+        self.tranches
+            .iter()
+            .fold(self.unlocked, |claimable, tranche| {
+                claimable + tranche.claimable(current_epoch, vesting_epochs)
+            })
+    }
+
+    pub fn locked(&self, current_epoch: u64, vesting_epochs: u64) -> Decimal256 {
+        self.tranches
+            .iter()
+            .fold(Decimal256::zero(), |locked, tranche| {
+                locked + tranche.amount
+                    - tranche.claimed
+                    - tranche.claimable(current_epoch, vesting_epochs)
+            })
+    }
+
+    /// Marks everything vested as claimed and returns the claimed amount
+    pub fn claim(&mut self, current_epoch: u64, vesting_epochs: u64) -> Decimal256 {
+        let claimable = self.claimable(current_epoch, vesting_epochs);
+        self.unlocked = Decimal256::zero();
+        for tranche in self.tranches.iter_mut() {
+            tranche.claimed += tranche.claimable(current_epoch, vesting_epochs);
+        }
+        claimable
+    }
+}
+
+/// Config update waiting in the timelock
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScheduledUpdate {
+    pub update: TimelockedConfigUpdate,
+    // Block time in seconds after which the update can be executed
+    pub eta: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BlacklistEntry {
+    // Block time in seconds at which the address was blacklisted
+    pub blacklisted_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueuedWithdrawal {
+    pub id: u64,
+    pub depositor: Addr,
+    // aust left to redeem, already removed from the pool
+    pub aust_amount: Uint256,
+    // stable left to pay out, net of the instant withdrawal fee
+    pub amount: Uint256,
+    // Instant withdrawals are paid out when fulfilled, regular ones become claims
+    pub instant: bool,
+    pub release_at: Expiration,
+    // Address paid instead of the depositor
+    #[serde(default)]
+    pub recipient: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct WithdrawalQueueState {
+    pub next_id: u64,
+    // aust held for queued withdrawals, not part of the pool
+    pub total_aust: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractVersion {
+    // Version whose storage layout is fully migrated
+    pub version: u64,
+    // Version being migrated to, equal to version once all steps are completed
+    pub target_version: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct MigrationProgress {
+    pub migrated_entries: u64,
+    pub completed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Buyback {
+    pub pair_contract: Addr,
+    pub glow_token: Addr,
+    pub reserve_share: Decimal256,
+    pub max_spread: Decimal,
+    pub destination: BuybackDestination,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct BuybackState {
+    pub total_glow_bought: Uint256,
+    pub total_stable_spent: Uint256,
+}
+
+/// Swap waiting for its reply
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BuybackInFlight {
+    // Reserve spent on the swap, before taxes
+    pub stable_amount: Uint256,
+    // Glow held by the contract before the swap
+    pub glow_balance: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Charity {
+    pub registered: bool,
+    // UST donated to the charity, after taxes
+    pub total_donated: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct EmissionDecay {
+    pub operator_decay: Option<EmissionDecaySchedule>,
+    pub sponsor_decay: Option<EmissionDecaySchedule>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReceiptToken {
+    pub contract: Addr,
+    // aust backing the receipt tokens, one token per aust.
+    // It is not part of the pool, so it gets no tickets and its yield is not used for prizes
+    pub total_aust: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Insurance {
+    // Share of the reserve moved to the fund at each epoch operation
+    pub reserve_share: Decimal256,
+    // Stable held by the contract for the fund
+    pub balance: Uint256,
+    // Stable drawn from the fund to cover shortfalls
+    pub total_drawn: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct JackpotFloor {
+    // Amount the jackpot bucket is topped up to at lottery executions, zero if disabled
+    pub floor: Uint256,
+    // Stable received from the community contract and not repaid yet
+    pub outstanding_subsidy: Uint256,
+    pub total_subsidized: Uint256,
+    pub total_repaid: Uint256,
+    // Top-up requested by the lottery execution waiting for its reply
+    pub top_up_in_flight: Uint256,
+}
+
+/// Prize claim, recorded before its payout messages are sent
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimReceipt {
+    pub winner: Addr,
+    pub recipient: Addr,
+    pub lottery_ids: Vec<u64>,
+    // UST prize paid, after donations and taxes
+    pub ust_amount: Uint128,
+    pub glow_amount: Uint128,
+    pub status: ClaimReceiptStatus,
+    // Whether the last GLOW payout was sent from the glow prize reserve
+    pub glow_from_reserve: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct RateGuard {
+    // Maximum relative change of the exchange rate per hour, None when disabled
+    pub max_deviation_per_hour: Option<Decimal256>,
+    pub last_rate: Decimal256,
+    // Timestamp of last_rate in seconds
+    pub last_rate_time: u64,
+    // Whether the lottery was paused on a rejected rate
+    pub tripped: bool,
+    pub rejected_rate: Option<Decimal256>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct InstantWithdrawals {
+    pub withdrawn: Uint256,
+    // Value of the user deposits when the epoch started, base of a PoolRatio cap.
+    // None until the first withdrawal of the epoch records it
+    pub epoch_pool_value: Option<Uint256>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BadgeConfig {
+    pub badge_contract: Addr,
+    pub tiers: Vec<BadgeTier>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BadgeMint {
+    pub sponsor: Addr,
+    pub tier: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EpochOperationState {
+    // Block time in seconds of the last execution, if any
+    pub last_executed: Option<u64>,
+    pub next_execution: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrizeRebalancing {
+    pub config: PrizeRebalancingConfig,
+    pub last_rebalanced_lottery: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PrizeTierStats {
+    pub awarded_lotteries: u64,
+    pub winners: Vec<u64>,
+    pub awarded_prizes: Vec<Uint256>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Inbox {
+    // Prizes won since the owner last marked the inbox as seen
+    pub unseen_prizes: u32,
+    // Prizes won and not claimed yet
+    pub unclaimed_prizes: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Escrow {
+    // secp256k1 public key deposit permits are signed with
+    pub pubkey: Binary,
+    pub balance: Uint256,
+    // Nonce of the next permit, incremented when a permit is used
+    pub nonce: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GiftCard {
+    pub buyer: Addr,
+    pub amount: Uint256,
+    pub num_tickets: u64,
+    // Time in seconds from which the card can't be redeemed and can be refunded
+    pub expires: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GlowPrizeReserve {
+    pub glow_token: Addr,
+    // Glow held by the contract for prizes
+    pub balance: Uint128,
+}
+
+/// Prize claim transferred over ibc
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IbcClaim {
+    pub winner: Addr,
+    pub destination: ClaimDestination,
+    // UST sent in the transfer, after taxes
+    pub amount: Uint128,
+    // Block time in seconds at which the transfer packet times out
+    pub timeout: u64,
+    // Whether the amount was credited back to the winner after a timeout
+    pub refunded: bool,
+}
+
+/// Share price recorded during epoch operations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SharePriceCheckpoint {
+    // Block time in seconds at which the checkpoint was recorded
+    pub time: u64,
+    // UST value of one user share
+    pub share_price: Decimal256,
+}
+
+/// Group of depositors sharing a deposit, held under the syndicate's holder address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Syndicate {
+    pub creator: Addr,
+    // Number of members with shares in the syndicate deposit
+    pub num_members: u32,
+}
+
+impl Syndicate {
+    // Depositor address of the syndicate. It isn't a valid account address,
+    // so nobody can act as the syndicate directly
+    pub fn holder(syndicate_id: u64) -> Addr {
+        Addr::unchecked(format!("syndicate/{}", syndicate_id))
+    }
+}
+
+/// Operator shares recorded whenever they change
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorShareCheckpoint {
+    // Block time in seconds at which the checkpoint was recorded
+    pub time: u64,
+    pub shares: Uint256,
+    pub total_operator_shares: Uint256,
+}
+
+/// Deposits attributed to a campaign of an operator
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct CampaignStats {
+    // UST deposited through the campaign, after taxes
+    pub deposited: Uint256,
+    pub tickets: u64,
+    pub deposits: u64,
+}
+
+/// Tickets acquired too close to a draw to take part in it.
+/// They become eligible once the lottery lottery_id is the current one.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PendingTickets {
+    pub lottery_id: u64,
+    pub num_tickets: u64,
+}
+
+impl PendingTickets {
+    pub fn pending(&self, current_lottery: u64) -> u64 {
+        if self.lottery_id > current_lottery {
+            self.num_tickets
+        } else {
+            0
+        }
+    }
+}
+
+/// Anchor operations whose submessages failed and need to be retried
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PendingAnchorOperations {
+    // Amount of aust that still has to be redeemed from anchor
+    pub redeem_stable: Uint256,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct LotteryInfo {
     pub rand_round: u64,
@@ -265,11 +977,42 @@ pub struct LotteryInfo {
     pub awarded: bool,
     pub timestamp: Timestamp,
     pub block_height: u64,
-    pub prize_buckets: [Uint256; NUM_PRIZE_BUCKETS],
-    pub number_winners: [u32; NUM_PRIZE_BUCKETS],
-    pub page: String,
-    pub glow_prize_buckets: [Uint256; NUM_PRIZE_BUCKETS],
+    pub prize_buckets: Vec<Uint256>,
+    pub number_winners: Vec<u32>,
+    #[serde(default)]
+    pub prize_cursor: PrizeCursor,
+    pub glow_prize_buckets: Vec<Uint256>,
     pub total_user_shares: Uint256,
+    #[serde(default)]
+    pub draw_timeline: DrawTimeline,
+    // Sum of the loyalty weights of the winning tickets of each prize bucket, only set
+    // when loyalty weighting or time weighted odds were enabled at execution
+    #[serde(default)]
+    pub loyalty_weights: Option<Vec<Decimal256>>,
+    // Round the winning tickets are weighted over, only set when
+    // time weighted odds were enabled at execution
+    #[serde(default)]
+    pub time_weight_window: Option<TimeWeightWindow>,
+    // GLOW prize buckets the distributor could fund at execution
+    #[serde(default)]
+    pub funded_glow_prize_buckets: Option<Vec<Uint256>>,
+    // Boosted GLOW missing from the distributor to fund the configured buckets
+    #[serde(default)]
+    pub glow_prize_shortfall: Uint256,
+    // Hex encoded merkle root of the winner leaves, set once the lottery is awarded
+    #[serde(default)]
+    pub winners_root: Option<String>,
+    // Prize caps in place when the lottery was executed
+    #[serde(default)]
+    pub prize_caps: Option<Vec<PrizeCap>>,
+}
+
+/// Page string of a lottery info stored before version 3. It held the first
+/// ticket of the next ExecutePrize page
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyLotteryPage {
+    #[serde(default)]
+    pub page: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -278,15 +1021,41 @@ pub struct OldLotteryInfo {
     pub sequence: String,
     pub awarded: bool,
     pub timestamp: u64,
-    pub prize_buckets: [Uint256; NUM_PRIZE_BUCKETS],
-    pub number_winners: [u32; NUM_PRIZE_BUCKETS],
+    pub prize_buckets: Vec<Uint256>,
+    pub number_winners: Vec<u32>,
     pub page: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
 pub struct PrizeInfo {
     pub claimed: bool,
-    pub matches: [u32; NUM_PRIZE_BUCKETS],
+    pub matches: Vec<u32>,
+    // Sum of the loyalty weights of the winner's tickets in each prize bucket
+    #[serde(default)]
+    pub loyalty_weights: Vec<Decimal256>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BoostSnapshot {
+    pub multiplier: Decimal256,
+    // Shares and voting balances at the lottery height
+    pub user_shares: Uint256,
+    pub total_user_shares: Uint256,
+    pub user_voting_balance: Uint128,
+    pub total_voting_balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrizeBoost {
+    pub boost: BoostSnapshot,
+    pub glow_prize: Uint128,
+    pub claimed_at_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WinnerLeaf {
+    pub ust_amount: Uint128,
+    pub glow_amount: Uint128,
 }
 
 pub fn store_lottery_info(
@@ -297,21 +1066,220 @@ pub fn store_lottery_info(
     LOTTERIES.save(storage, U64Key::from(lottery_id), lottery_info)
 }
 
+/// Returns the state at the beginning of the given block height
+pub fn read_state_at_height(storage: &dyn Storage, height: u64) -> StdResult<State> {
+    STATE
+        .may_load_at_height(storage, height)?
+        .ok_or_else(|| StdError::not_found("State"))
+}
+
+/// Returns the stored share price checkpoints, oldest first
+pub fn read_share_price_checkpoints(storage: &dyn Storage) -> StdResult<Vec<SharePriceCheckpoint>> {
+    Ok(SHARE_PRICE_CHECKPOINTS
+        .may_load(storage)?
+        .unwrap_or_default())
+}
+
+/// Appends a checkpoint, dropping the oldest ones beyond MAX_SHARE_PRICE_CHECKPOINTS
+pub fn store_share_price_checkpoint(
+    storage: &mut dyn Storage,
+    checkpoint: SharePriceCheckpoint,
+) -> StdResult<()> {
+    let mut checkpoints = read_share_price_checkpoints(storage)?;
+    checkpoints.push(checkpoint);
+    if checkpoints.len() > MAX_SHARE_PRICE_CHECKPOINTS {
+        let excess = checkpoints.len() - MAX_SHARE_PRICE_CHECKPOINTS;
+        checkpoints.drain(..excess);
+    }
+    SHARE_PRICE_CHECKPOINTS.save(storage, &checkpoints)
+}
+
+pub fn read_scheduled_updates(
+    storage: &dyn Storage,
+    pagination: &Pagination<u64>,
+) -> StdResult<Vec<(u64, ScheduledUpdate)>> {
+    SCHEDULED_UPDATES
+        .range(storage, pagination.start_bound(), None, Order::Ascending)
+        .take(pagination.limit)
+        .map(|item| {
+            let (k, v) = item?;
+            let id = u64::from_be_bytes(
+                k.try_into()
+                    .map_err(|_| StdError::generic_err("Could not read 8 byte id"))?,
+            );
+            Ok((id, v))
+        })
+        .collect()
+}
+
+/// Returns the pool at the beginning of the given block height
+pub fn read_pool_at_height(storage: &dyn Storage, height: u64) -> StdResult<Pool> {
+    POOL.may_load_at_height(storage, height)?
+        .ok_or_else(|| StdError::not_found("Pool"))
+}
+
+pub fn store_ticket_acquisition(
+    storage: &mut dyn Storage,
+    sequence: &str,
+    depositor: &Addr,
+    lottery_id: u64,
+    acquired_at: u64,
+) -> StdResult<()> {
+    let key = (sequence.as_bytes(), depositor);
+    let acquisitions =
+        TICKET_ACQUISITIONS.update(storage, key, |acquisitions| -> StdResult<_> {
+            let mut acquisitions = acquisitions.unwrap_or_default();
+            acquisitions.push(lottery_id);
+            Ok(acquisitions)
+        })?;
+    TICKET_ACQUISITION_TIMES.update(storage, key, |times| -> StdResult<_> {
+        let mut times = times.unwrap_or_default();
+        times.resize(acquisitions.len() - 1, 0);
+        times.push(acquired_at);
+        Ok(times)
+    })?;
+    Ok(())
+}
+
+/// Removes the most recently acquired copy of a ticket,
+/// and its pending ticket count if it isn't eligible yet
+pub fn remove_ticket_acquisition(
+    storage: &mut dyn Storage,
+    sequence: &str,
+    depositor: &Addr,
+) -> StdResult<()> {
+    let key = (sequence.as_bytes(), depositor);
+    let mut acquisitions = TICKET_ACQUISITIONS
+        .may_load(storage, key)?
+        .unwrap_or_default();
+    if let Some(acquired_lottery) = acquisitions.pop() {
+        remove_pending_ticket(storage, depositor, acquired_lottery)?;
+    }
+
+    if acquisitions.is_empty() {
+        TICKET_ACQUISITIONS.remove(storage, key);
+        TICKET_ACQUISITION_TIMES.remove(storage, key);
+    } else {
+        TICKET_ACQUISITIONS.save(storage, key, &acquisitions)?;
+        if let Some(mut times) = TICKET_ACQUISITION_TIMES.may_load(storage, key)? {
+            times.truncate(acquisitions.len());
+            TICKET_ACQUISITION_TIMES.save(storage, key, &times)?;
+        }
+    }
+    Ok(())
+}
+
+/// Counts num_tickets tickets of depositor as pending until lottery_id
+pub fn store_pending_tickets(
+    storage: &mut dyn Storage,
+    depositor: &Addr,
+    lottery_id: u64,
+    num_tickets: u64,
+) -> StdResult<()> {
+    let add_pending_tickets = |pending: Option<PendingTickets>| -> StdResult<_> {
+        let mut pending = pending.unwrap_or_default();
+        // Tickets pending for an earlier lottery are eligible by now
+        if pending.lottery_id != lottery_id {
+            pending = PendingTickets {
+                lottery_id,
+                num_tickets: 0,
+            };
+        }
+        pending.num_tickets += num_tickets;
+        Ok(pending)
+    };
+    PENDING_TICKETS.update(storage, depositor, add_pending_tickets)?;
+    let total_pending = add_pending_tickets(TOTAL_PENDING_TICKETS.may_load(storage)?)?;
+    TOTAL_PENDING_TICKETS.save(storage, &total_pending)
+}
+
+fn remove_pending_ticket(
+    storage: &mut dyn Storage,
+    depositor: &Addr,
+    acquired_lottery: u64,
+) -> StdResult<()> {
+    let mut pending = read_pending_tickets(storage, depositor)?;
+    if pending.lottery_id != acquired_lottery || pending.num_tickets == 0 {
+        return Ok(());
+    }
+    pending.num_tickets -= 1;
+    PENDING_TICKETS.save(storage, depositor, &pending)?;
+
+    let mut total_pending = read_total_pending_tickets(storage)?;
+    if total_pending.lottery_id == acquired_lottery {
+        total_pending.num_tickets = total_pending.num_tickets.saturating_sub(1);
+        TOTAL_PENDING_TICKETS.save(storage, &total_pending)?;
+    }
+    Ok(())
+}
+
+pub fn read_pending_tickets(storage: &dyn Storage, depositor: &Addr) -> StdResult<PendingTickets> {
+    Ok(PENDING_TICKETS
+        .may_load(storage, depositor)?
+        .unwrap_or_default())
+}
+
+pub fn read_total_pending_tickets(storage: &dyn Storage) -> StdResult<PendingTickets> {
+    Ok(TOTAL_PENDING_TICKETS.may_load(storage)?.unwrap_or_default())
+}
+
+/// Lottery id at which the nth copy of a ticket held by depositor was acquired.
+/// Tickets acquired before acquisitions were tracked count as held since the first lottery.
+pub fn read_ticket_acquisition(
+    storage: &dyn Storage,
+    sequence: &[u8],
+    depositor: &Addr,
+    copy: usize,
+) -> StdResult<u64> {
+    Ok(TICKET_ACQUISITIONS
+        .may_load(storage, (sequence, depositor))?
+        .and_then(|acquisitions| acquisitions.get(copy).copied())
+        .unwrap_or_default())
+}
+
+/// Block time at which the nth copy of a ticket held by depositor was acquired,
+/// zero for copies acquired before acquisition times were tracked
+pub fn read_ticket_acquisition_time(
+    storage: &dyn Storage,
+    sequence: &[u8],
+    depositor: &Addr,
+    copy: usize,
+) -> StdResult<u64> {
+    Ok(TICKET_ACQUISITION_TIMES
+        .may_load(storage, (sequence, depositor))?
+        .and_then(|times| times.get(copy).copied())
+        .unwrap_or_default())
+}
+
 pub fn read_lottery_info(storage: &dyn Storage, lottery_id: u64) -> LotteryInfo {
     match LOTTERIES.load(storage, U64Key::from(lottery_id)) {
         Ok(v) => v,
-        _ => LotteryInfo {
-            rand_round: 0,
-            sequence: "".to_string(),
-            awarded: false,
-            timestamp: Timestamp::from_seconds(0),
-            prize_buckets: [Uint256::zero(); NUM_PRIZE_BUCKETS],
-            number_winners: [0; NUM_PRIZE_BUCKETS],
-            page: "".to_string(),
-            glow_prize_buckets: [Uint256::zero(); NUM_PRIZE_BUCKETS],
-            block_height: 0,
-            total_user_shares: Uint256::zero(),
-        },
+        _ => {
+            // Lotteries not stored yet have a bucket per number of matches of the configured tickets
+            let num_prize_buckets = CONFIG
+                .load(storage)
+                .map(|config| config.num_prize_buckets())
+                .unwrap_or(NUM_PRIZE_BUCKETS);
+            LotteryInfo {
+                rand_round: 0,
+                sequence: "".to_string(),
+                awarded: false,
+                timestamp: Timestamp::from_seconds(0),
+                prize_buckets: vec![Uint256::zero(); num_prize_buckets],
+                number_winners: vec![0; num_prize_buckets],
+                prize_cursor: PrizeCursor::default(),
+                glow_prize_buckets: vec![Uint256::zero(); num_prize_buckets],
+                block_height: 0,
+                total_user_shares: Uint256::zero(),
+                draw_timeline: DrawTimeline::default(),
+                loyalty_weights: None,
+                time_weight_window: None,
+                funded_glow_prize_buckets: None,
+                glow_prize_shortfall: Uint256::zero(),
+                winners_root: None,
+                prize_caps: None,
+            }
+        }
     }
 }
 
@@ -323,8 +1291,8 @@ pub fn old_read_lottery_info(storage: &dyn Storage, lottery_id: u64) -> OldLotte
             sequence: "".to_string(),
             awarded: false,
             timestamp: 0,
-            prize_buckets: [Uint256::zero(); NUM_PRIZE_BUCKETS],
-            number_winners: [0; NUM_PRIZE_BUCKETS],
+            prize_buckets: vec![Uint256::zero(); NUM_PRIZE_BUCKETS],
+            number_winners: vec![0; NUM_PRIZE_BUCKETS],
             page: "".to_string(),
         },
     }
@@ -340,23 +1308,30 @@ pub fn store_depositor_info(
     depositor_info: DepositorInfo,
     height: u64,
 ) -> StdResult<()> {
-    // Get the number of tickets
-    let num_tickets = depositor_info.tickets.len();
-
-    // Get the tickets in binary form
-    let vec_binary_tickets = vec_string_tickets_to_vec_binary_tickets(depositor_info.tickets)?;
+    // Tickets still in the legacy depositor data would be overwritten below
+    migrate_legacy_depositor_tickets(storage, depositor)?;
 
     let depositor_data = DepositorData {
-        vec_binary_tickets,
+        vec_binary_tickets: vec![],
         unbonding_info: depositor_info.unbonding_info,
     };
 
     let depositor_stats_info = DepositorStatsInfo {
         shares: depositor_info.shares,
-        num_tickets,
+        num_tickets: depositor_info.num_tickets,
         operator_addr: depositor_info.operator_addr,
     };
 
+    // Index the depositor under its operator. Depositors attributed before the index
+    // existed are added the next time their info is stored
+    if depositor_stats_info.operator_addr != Addr::unchecked("") {
+        OPERATOR_DEPOSITORS.save(
+            storage,
+            (&depositor_stats_info.operator_addr, depositor),
+            &Empty {},
+        )?;
+    }
+
     DEPOSITOR_DATA.save(storage, depositor, &depositor_data)?;
 
     DEPOSITOR_STATS.save(storage, depositor, &depositor_stats_info, height)?;
@@ -364,6 +1339,169 @@ pub fn store_depositor_info(
     Ok(())
 }
 
+/// Moves the tickets stored in the legacy DepositorData.vec_binary_tickets to DEPOSITOR_TICKETS
+pub fn migrate_legacy_depositor_tickets(
+    storage: &mut dyn Storage,
+    depositor: &Addr,
+) -> StdResult<()> {
+    let mut depositor_data = match DEPOSITOR_DATA.may_load(storage, depositor)? {
+        Some(depositor_data) if !depositor_data.vec_binary_tickets.is_empty() => depositor_data,
+        _ => return Ok(()),
+    };
+
+    let legacy_tickets =
+        vec_binary_tickets_to_vec_string_tickets(depositor_data.vec_binary_tickets);
+    store_depositor_tickets(storage, depositor, &legacy_tickets)?;
+
+    depositor_data.vec_binary_tickets = vec![];
+    DEPOSITOR_DATA.save(storage, depositor, &depositor_data)
+}
+
+/// Adds one copy of each ticket to the tickets held by depositor.
+/// Does *not* update the number of tickets of the depositor.
+pub fn store_depositor_tickets(
+    storage: &mut dyn Storage,
+    depositor: &Addr,
+    tickets: &[String],
+) -> StdResult<()> {
+    for binary_ticket in vec_string_tickets_to_vec_binary_tickets(tickets.to_vec())? {
+        DEPOSITOR_TICKETS.update(
+            storage,
+            (depositor, &binary_ticket),
+            |copies| -> StdResult<_> { Ok(copies.unwrap_or_default() + 1) },
+        )?;
+    }
+    Ok(())
+}
+
+/// Removes num_tickets tickets held by depositor, in ascending sequence order,
+/// and returns the removed sequences.
+/// Does *not* update the number of tickets of the depositor.
+pub fn remove_depositor_tickets(
+    storage: &mut dyn Storage,
+    depositor: &Addr,
+    num_tickets: usize,
+) -> StdResult<Vec<String>> {
+    migrate_legacy_depositor_tickets(storage, depositor)?;
+
+    let mut held = vec![];
+    let mut held_copies: usize = 0;
+    for item in DEPOSITOR_TICKETS
+        .prefix(depositor)
+        .range(storage, None, None, Order::Ascending)
+    {
+        if held_copies >= num_tickets {
+            break;
+        }
+        let (binary_ticket, copies) = item?;
+        held_copies += copies as usize;
+        held.push((binary_ticket, copies));
+    }
+
+    let ticket_length = CONFIG.load(storage)?.ticket_length as usize;
+    let mut removed_tickets = vec![];
+    for (binary_ticket, copies) in held {
+        let removed_copies = (num_tickets - removed_tickets.len()).min(copies as usize);
+        if removed_copies == copies as usize {
+            DEPOSITOR_TICKETS.remove(storage, (depositor, &binary_ticket));
+        } else {
+            DEPOSITOR_TICKETS.save(
+                storage,
+                (depositor, &binary_ticket),
+                &(copies - removed_copies as u32),
+            )?;
+        }
+
+        let sequence = binary_ticket_to_sequence(&binary_ticket, ticket_length);
+        removed_tickets.extend(vec![sequence; removed_copies]);
+    }
+
+    if removed_tickets.len() < num_tickets {
+        return Err(StdError::generic_err(
+            "Can't remove more tickets than the depositor holds",
+        ));
+    }
+
+    Ok(removed_tickets)
+}
+
+/// Removes one copy of sequence from the tickets held by depositor.
+/// Returns false if the depositor doesn't hold the ticket.
+/// Does *not* update the number of tickets of the depositor.
+pub fn remove_depositor_ticket(
+    storage: &mut dyn Storage,
+    depositor: &Addr,
+    sequence: &str,
+) -> StdResult<bool> {
+    migrate_legacy_depositor_tickets(storage, depositor)?;
+
+    let binary_ticket = sequence_to_binary_ticket(sequence)?;
+    let key = (depositor, &binary_ticket[..]);
+    match DEPOSITOR_TICKETS.may_load(storage, key)? {
+        None => Ok(false),
+        Some(1) => {
+            DEPOSITOR_TICKETS.remove(storage, key);
+            Ok(true)
+        }
+        Some(copies) => {
+            DEPOSITOR_TICKETS.save(storage, key, &(copies - 1))?;
+            Ok(true)
+        }
+    }
+}
+
+/// All the tickets held by depositor, in ascending sequence order
+pub fn read_depositor_tickets(storage: &dyn Storage, depositor: &Addr) -> StdResult<Vec<String>> {
+    let mut tickets = vec_binary_tickets_to_vec_string_tickets(
+        read_depositor_data(storage, depositor).vec_binary_tickets,
+    );
+
+    let ticket_length = CONFIG.load(storage)?.ticket_length as usize;
+    for item in DEPOSITOR_TICKETS
+        .prefix(depositor)
+        .range(storage, None, None, Order::Ascending)
+    {
+        let (binary_ticket, copies) = item?;
+        let sequence = binary_ticket_to_sequence(&binary_ticket, ticket_length);
+        tickets.extend(vec![sequence; copies as usize]);
+    }
+
+    tickets.sort();
+    Ok(tickets)
+}
+
+/// Tickets (paginated) of depositor whose sequence is above start_after, in ascending
+/// sequence order. limit counts distinct sequences, listed once per copy held
+pub fn read_depositor_tickets_page(
+    storage: &dyn Storage,
+    depositor: &Addr,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<String>> {
+    let limit = limit
+        .unwrap_or(DEFAULT_TICKETS_LIMIT)
+        .min(MAX_TICKETS_LIMIT) as usize;
+
+    let mut tickets: Vec<String> = vec![];
+    let mut sequences = 0;
+    for ticket in read_depositor_tickets(storage, depositor)? {
+        if matches!(&start_after, Some(start_after) if ticket <= *start_after) {
+            continue;
+        }
+
+        // Copies of a sequence are never split over two pages
+        if tickets.last() != Some(&ticket) {
+            if sequences == limit {
+                break;
+            }
+            sequences += 1;
+        }
+        tickets.push(ticket);
+    }
+
+    Ok(tickets)
+}
+
 pub fn old_remove_depositor_info(storage: &mut dyn Storage, depositor: &Addr) {
     bucket::<OldDepositorInfo>(storage, OLD_PREFIX_DEPOSIT).remove(depositor.as_bytes())
 }
@@ -411,13 +1549,7 @@ pub fn old_read_depositor_info(storage: &dyn Storage, depositor: &Addr) -> OldDe
 }
 
 pub fn read_depositor_info(storage: &dyn Storage, depositor: &Addr) -> DepositorInfo {
-    let depositor_data = match DEPOSITOR_DATA.load(storage, depositor) {
-        Ok(v) => v,
-        _ => DepositorData {
-            vec_binary_tickets: vec![],
-            unbonding_info: vec![],
-        },
-    };
+    let depositor_data = read_depositor_data(storage, depositor);
 
     let depositor_stats_info = match DEPOSITOR_STATS.load(storage, depositor) {
         Ok(v) => v,
@@ -428,16 +1560,13 @@ pub fn read_depositor_info(storage: &dyn Storage, depositor: &Addr) -> Depositor
         },
     };
 
-    let vec_string_tickets =
-        vec_binary_tickets_to_vec_string_tickets(depositor_data.vec_binary_tickets);
-
     DepositorInfo {
         // DepositorData
-        tickets: vec_string_tickets,
         unbonding_info: depositor_data.unbonding_info,
 
         // DepositorStats
         shares: depositor_stats_info.shares,
+        num_tickets: depositor_stats_info.num_tickets,
         operator_addr: depositor_stats_info.operator_addr,
     }
 }
@@ -468,6 +1597,28 @@ pub fn read_depositor_stats_at_height(
     }
 }
 
+pub fn read_depositor_ledger(storage: &dyn Storage, depositor: &Addr) -> DepositorLedger {
+    DEPOSITOR_LEDGER
+        .may_load(storage, depositor)
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+}
+
+pub fn update_depositor_ledger<A>(
+    storage: &mut dyn Storage,
+    depositor: &Addr,
+    action: A,
+) -> StdResult<DepositorLedger>
+where
+    A: FnOnce(&mut DepositorLedger),
+{
+    let mut ledger = read_depositor_ledger(storage, depositor);
+    action(&mut ledger);
+    DEPOSITOR_LEDGER.save(storage, depositor, &ledger)?;
+    Ok(ledger)
+}
+
 pub fn read_depositor_data(storage: &dyn Storage, depositor: &Addr) -> DepositorData {
     match DEPOSITOR_DATA.load(storage, depositor) {
         Ok(v) => v,
@@ -493,6 +1644,9 @@ pub fn read_sponsor_info(storage: &dyn Storage, sponsor: &Addr) -> SponsorInfo {
             lottery_deposit: Uint256::zero(),
             pending_rewards: Decimal256::zero(),
             reward_index: Decimal256::zero(),
+            lock_end_time: 0,
+            lock_bonus_rate: Decimal256::zero(),
+            emission_weight: Decimal256::one(),
         },
     }
 }
@@ -516,28 +1670,89 @@ pub fn read_operator_info(storage: &dyn Storage, operator: &Addr) -> OperatorInf
     }
 }
 
+pub fn read_current_epoch(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(CURRENT_EPOCH.may_load(storage)?.unwrap_or_default())
+}
+
+pub fn read_operator_reward_ledger(
+    storage: &dyn Storage,
+    operator: &Addr,
+) -> StdResult<OperatorRewardLedger> {
+    Ok(OPERATOR_REWARD_LEDGERS
+        .may_load(storage, operator)?
+        .unwrap_or_default())
+}
+
+pub fn read_operator_share_checkpoints(
+    storage: &dyn Storage,
+    operator: &Addr,
+) -> StdResult<Vec<OperatorShareCheckpoint>> {
+    Ok(OPERATOR_SHARE_CHECKPOINTS
+        .may_load(storage, operator)?
+        .unwrap_or_default())
+}
+
+/// Appends a checkpoint, dropping the oldest ones beyond MAX_OPERATOR_SHARE_CHECKPOINTS
+pub fn store_operator_share_checkpoint(
+    storage: &mut dyn Storage,
+    operator: &Addr,
+    checkpoint: OperatorShareCheckpoint,
+) -> StdResult<()> {
+    let mut checkpoints = read_operator_share_checkpoints(storage, operator)?;
+    checkpoints.push(checkpoint);
+    if checkpoints.len() > MAX_OPERATOR_SHARE_CHECKPOINTS {
+        let excess = checkpoints.len() - MAX_OPERATOR_SHARE_CHECKPOINTS;
+        checkpoints.drain(..excess);
+    }
+    OPERATOR_SHARE_CHECKPOINTS.save(storage, operator, &checkpoints)
+}
+
+pub fn read_operator_depositors(
+    deps: Deps,
+    operator: &Addr,
+    pagination: &Pagination<Addr>,
+) -> StdResult<Vec<OperatorDepositorResponse>> {
+    OPERATOR_DEPOSITORS
+        .prefix(operator)
+        .keys(
+            deps.storage,
+            pagination.start_bound(),
+            None,
+            Order::Ascending,
+        )
+        .take(pagination.limit)
+        .map(|k| {
+            let depositor = String::from_utf8(k).unwrap();
+            let depositor_stats = read_depositor_stats(deps.storage, &Addr::unchecked(&depositor));
+            Ok(OperatorDepositorResponse {
+                depositor,
+                shares: depositor_stats.shares,
+            })
+        })
+        .collect()
+}
+
 pub fn read_depositors_info(
     deps: Deps,
-    start_after: Option<Addr>,
-    limit: Option<u32>,
+    pagination: &Pagination<Addr>,
 ) -> StdResult<Vec<DepositorInfoResponse>> {
-    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
-    let start = start_after.map(|v| Bound::Exclusive(v.as_bytes().to_vec()));
-
     DEPOSITOR_STATS
-        .range(deps.storage, start, None, Order::Ascending)
-        .take(limit)
+        .range(
+            deps.storage,
+            pagination.start_bound(),
+            None,
+            Order::Ascending,
+        )
+        .take(pagination.limit)
         .map(|elem| {
             let (k, v) = elem?;
             let depositor = String::from_utf8(k).unwrap();
             let depositor_addr = Addr::unchecked(&depositor);
             let depositor_data = read_depositor_data(deps.storage, &depositor_addr);
-            let vec_string_tickets =
-                vec_binary_tickets_to_vec_string_tickets(depositor_data.vec_binary_tickets);
             Ok(DepositorInfoResponse {
                 depositor,
                 shares: v.shares,
-                tickets: vec_string_tickets,
+                tickets: read_depositor_tickets(deps.storage, &depositor_addr)?,
                 unbonding_info: depositor_data.unbonding_info,
             })
         })
@@ -546,22 +1761,28 @@ pub fn read_depositors_info(
 
 pub fn read_depositors_stats(
     deps: Deps,
-    start_after: Option<Addr>,
-    limit: Option<u32>,
+    pagination: &Pagination<Addr>,
 ) -> StdResult<Vec<DepositorStatsResponse>> {
-    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
-    let start = start_after.map(|v| Bound::Exclusive(v.as_bytes().to_vec()));
+    let current_lottery = STATE.load(deps.storage)?.current_lottery;
 
     DEPOSITOR_STATS
-        .range(deps.storage, start, None, Order::Ascending)
-        .take(limit)
+        .range(
+            deps.storage,
+            pagination.start_bound(),
+            None,
+            Order::Ascending,
+        )
+        .take(pagination.limit)
         .map(|elem| {
             let (k, v) = elem?;
             let depositor = String::from_utf8(k).unwrap();
+            let pending_tickets = read_pending_tickets(deps.storage, &Addr::unchecked(&depositor))?
+                .pending(current_lottery);
             Ok(DepositorStatsResponse {
                 depositor,
                 shares: v.shares,
                 num_tickets: v.num_tickets,
+                pending_tickets,
             })
         })
         .collect()
@@ -617,18 +1838,19 @@ pub fn read_prize(deps: Deps, address: &Addr, lottery_id: u64) -> StdResult<Priz
 pub fn read_lottery_prizes(
     deps: Deps,
     lottery_id: u64,
-    start_after: Option<Addr>,
-    limit: Option<u32>,
+    pagination: &Pagination<Addr>,
 ) -> StdResult<Vec<(Addr, PrizeInfo)>> {
     let lottery_key = U64Key::from(lottery_id);
 
-    let start = start_after.map(|a| Bound::Exclusive(a.as_bytes().to_vec()));
-    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
-
     PRIZES
         .prefix(lottery_key)
-        .range(deps.storage, start, None, Order::Ascending)
-        .take(limit)
+        .range(
+            deps.storage,
+            pagination.start_bound(),
+            None,
+            Order::Ascending,
+        )
+        .take(pagination.limit)
         .map(|item| {
             let (k, v) = item?;
 
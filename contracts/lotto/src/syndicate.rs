@@ -0,0 +1,436 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    attr, coin, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdResult, Uint128,
+};
+use cw_storage_plus::U64Key;
+use glow_protocol::lotto::{GlowHookMsg, SyndicateMemberResponse, SyndicateResponse};
+use glow_protocol::querier::deduct_tax;
+
+use crate::anchor::deposit_stable_msg;
+use crate::contract::process_deposit;
+use crate::error::ContractError;
+use crate::events::deposit_event;
+use crate::glow_reserve::glow_prize_msg;
+use crate::helpers::{
+    calculate_prize_cap_excess, calculate_winner_prize, compute_global_operator_reward,
+    decimal_from_ratio_or_one, handle_depositor_operator_updates, record_operator_share_checkpoint,
+};
+use crate::hooks::hook_submsgs;
+use crate::prize_caps::roll_over_prize_excess;
+use crate::querier::{query_balance, query_exchange_rate};
+use crate::state::{
+    read_depositor_info, read_depositor_stats, read_depositor_stats_at_height, read_lottery_info,
+    remove_depositor_tickets, remove_ticket_acquisition, store_depositor_info,
+    update_depositor_ledger, Syndicate, CONFIG, NEXT_SYNDICATE_ID, POOL, PRIZES, STATE, SYNDICATES,
+    SYNDICATE_MEMBERS, SYNDICATE_PRIZE_CLAIMS, TICKETS,
+};
+
+fn assert_lottery_not_started(deps: Deps) -> Result<(), ContractError> {
+    let state = STATE.load(deps.storage)?;
+    let current_lottery = read_lottery_info(deps.storage, state.current_lottery);
+    if current_lottery.rand_round != 0 {
+        return Err(ContractError::LotteryAlreadyStarted {
+            lottery_id: state.current_lottery,
+        });
+    }
+
+    Ok(())
+}
+
+pub fn execute_create_syndicate(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let syndicate_id = NEXT_SYNDICATE_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    SYNDICATES.save(
+        deps.storage,
+        U64Key::from(syndicate_id),
+        &Syndicate {
+            creator: info.sender.clone(),
+            num_members: 0,
+        },
+    )?;
+    NEXT_SYNDICATE_ID.save(deps.storage, &(syndicate_id + 1))?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "create_syndicate"),
+        attr("syndicate_id", syndicate_id.to_string()),
+        attr("creator", info.sender.to_string()),
+        attr("holder", Syndicate::holder(syndicate_id).to_string()),
+    ]))
+}
+
+/// Deposits into the syndicate holder and credits the minted shares to the sender
+pub fn execute_join_syndicate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    syndicate_id: u64,
+    encoded_tickets: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut syndicate = SYNDICATES.load(deps.storage, U64Key::from(syndicate_id))?;
+    let holder = Syndicate::holder(syndicate_id);
+
+    assert_lottery_not_started(deps.as_ref())?;
+
+    // Get the amount of funds sent in the base stable denom
+    let deposit_amount = info
+        .funds
+        .iter()
+        .find(|c| c.denom == config.stable_denom)
+        .map(|c| Uint256::from(c.amount))
+        .unwrap_or_else(Uint256::zero);
+
+    if deposit_amount.is_zero() {
+        return Err(ContractError::ZeroDepositAmount {});
+    }
+
+    if deposit_amount < config.min_deposit_amount {
+        return Err(ContractError::DepositBelowMinimum {
+            amount: deposit_amount,
+            min_deposit_amount: config.min_deposit_amount,
+        });
+    }
+
+    // Deduct tx taxes when calculating the net deposited amount in anchor
+    let post_tax_deposit_amount = Uint256::from(
+        deduct_tax(
+            deps.as_ref(),
+            coin(deposit_amount.into(), config.stable_denom.clone()),
+        )?
+        .amount,
+    );
+
+    let syndicate_shares = read_depositor_stats(deps.storage, &holder).shares;
+    let (number_of_new_tickets, minted_aust) = process_deposit(
+        deps.branch(),
+        &env,
+        &config,
+        &holder,
+        post_tax_deposit_amount,
+        encoded_tickets,
+        None,
+    )?;
+    let minted_shares = read_depositor_stats(deps.storage, &holder).shares - syndicate_shares;
+
+    let member_key = (U64Key::from(syndicate_id), &info.sender);
+    let member_shares = SYNDICATE_MEMBERS
+        .may_load(deps.storage, member_key.clone())?
+        .unwrap_or_default();
+    if member_shares.is_zero() {
+        syndicate.num_members += 1;
+        SYNDICATES.save(deps.storage, U64Key::from(syndicate_id), &syndicate)?;
+    }
+    SYNDICATE_MEMBERS.save(
+        deps.storage,
+        member_key,
+        &(member_shares + minted_shares),
+        env.block.height,
+    )?;
+
+    Ok(Response::new()
+        .add_message(deposit_stable_msg(&config, post_tax_deposit_amount)?)
+        .add_submessages(hook_submsgs(
+            deps.storage,
+            GlowHookMsg::OnDeposit {
+                depositor: info.sender.to_string(),
+                recipient: holder.to_string(),
+                amount: deposit_amount,
+                tickets: number_of_new_tickets,
+            },
+        )?)
+        .add_event(deposit_event(
+            &info.sender,
+            &holder,
+            deposit_amount,
+            post_tax_deposit_amount,
+            number_of_new_tickets,
+            minted_aust,
+        ))
+        .add_attributes(vec![
+            attr("action", "join_syndicate"),
+            attr("syndicate_id", syndicate_id.to_string()),
+            attr("member", info.sender.to_string()),
+            attr("deposit_amount", deposit_amount.to_string()),
+            attr("tickets", number_of_new_tickets.to_string()),
+            attr("aust_minted", minted_aust.to_string()),
+            attr("shares_minted", minted_shares.to_string()),
+        ]))
+}
+
+/// Moves the sender's shares from the syndicate holder to the sender's own deposit.
+/// The syndicate gives up the tickets its remaining deposit no longer covers
+pub fn execute_leave_syndicate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    syndicate_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+    let mut pool = POOL.load(deps.storage)?;
+    let mut syndicate = SYNDICATES.load(deps.storage, U64Key::from(syndicate_id))?;
+    let holder = Syndicate::holder(syndicate_id);
+
+    assert_lottery_not_started(deps.as_ref())?;
+
+    let member_key = (U64Key::from(syndicate_id), &info.sender);
+    let member_shares = SYNDICATE_MEMBERS
+        .may_load(deps.storage, member_key.clone())?
+        .unwrap_or_default();
+    if member_shares.is_zero() {
+        return Err(ContractError::NotSyndicateMember(syndicate_id));
+    }
+
+    // Get the aust exchange rate
+    let aust_exchange_rate = query_exchange_rate(
+        deps.as_ref(),
+        config.anchor_contract.to_string(),
+        env.block.height,
+    )?
+    .exchange_rate;
+
+    let mut syndicate_info = read_depositor_info(deps.storage, &holder);
+    syndicate_info.shares -= member_shares;
+
+    // Give up the tickets above what the remaining syndicate deposit affords
+    let syndicate_balance = pool.total_user_aust
+        * decimal_from_ratio_or_one(syndicate_info.shares, pool.total_user_shares)
+        * aust_exchange_rate;
+    let max_syndicate_tickets = Uint128::from(
+        syndicate_balance
+            / Decimal256::from_uint256(
+                config.ticket_price
+            // Same rounding offset as when the tickets were granted on deposit
+                - Uint256::from(10u128),
+            ),
+    )
+    .u128() as usize;
+    let withdrawn_tickets = syndicate_info
+        .num_tickets
+        .saturating_sub(max_syndicate_tickets);
+
+    for seq in remove_depositor_tickets(deps.storage, &holder, withdrawn_tickets)? {
+        TICKETS.update(deps.storage, seq.as_bytes(), |tickets| -> StdResult<_> {
+            let mut new_tickets = tickets.unwrap_or_default();
+            if let Some(index) = new_tickets.iter().position(|x| *x == holder) {
+                new_tickets.remove(index);
+            }
+            Ok(new_tickets)
+        })?;
+        remove_ticket_acquisition(deps.storage, &seq, &holder)?;
+    }
+    syndicate_info.num_tickets -= withdrawn_tickets;
+    state.total_tickets -= Uint256::from(withdrawn_tickets as u64);
+
+    // Credit the shares to the member, attributing them to the member's operator
+    let mut member_info = read_depositor_info(deps.storage, &info.sender);
+    compute_global_operator_reward(&mut state, &pool, env.block.height);
+    handle_depositor_operator_updates(
+        deps.branch(),
+        &mut state,
+        &mut pool,
+        &info.sender,
+        &mut member_info,
+        member_shares,
+        None,
+    )?;
+    if member_info.operator_registered() {
+        record_operator_share_checkpoint(
+            deps.storage,
+            &member_info.operator_addr,
+            &pool,
+            env.block.time.seconds(),
+        )?;
+    }
+    member_info.shares += member_shares;
+
+    store_depositor_info(deps.storage, &holder, syndicate_info, env.block.height)?;
+    store_depositor_info(deps.storage, &info.sender, member_info, env.block.height)?;
+    SYNDICATE_MEMBERS.remove(deps.storage, member_key, env.block.height)?;
+    syndicate.num_members -= 1;
+    SYNDICATES.save(deps.storage, U64Key::from(syndicate_id), &syndicate)?;
+    STATE.save(deps.storage, &state, env.block.height)?;
+    POOL.save(deps.storage, &pool, env.block.height)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "leave_syndicate"),
+        attr("syndicate_id", syndicate_id.to_string()),
+        attr("member", info.sender.to_string()),
+        attr("shares", member_shares.to_string()),
+        attr("syndicate_tickets_removed", withdrawn_tickets.to_string()),
+    ]))
+}
+
+pub fn execute_claim_syndicate_lottery(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    syndicate_id: u64,
+    lottery_ids: Vec<u64>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let holder = Syndicate::holder(syndicate_id);
+    SYNDICATES.load(deps.storage, U64Key::from(syndicate_id))?;
+
+    assert_lottery_not_started(deps.as_ref())?;
+
+    let mut ust_to_send = Uint128::zero();
+    let mut glow_to_send = Uint128::zero();
+
+    for lottery_id in lottery_ids.clone() {
+        let lottery_info = read_lottery_info(deps.storage, lottery_id);
+        if !lottery_info.awarded {
+            return Err(ContractError::InvalidClaimLotteryNotAwarded(lottery_id));
+        }
+
+        let prize = match PRIZES.may_load(deps.storage, (U64Key::from(lottery_id), &holder))? {
+            Some(prize) => prize,
+            None => continue,
+        };
+
+        let claim_key = (
+            U64Key::from(syndicate_id),
+            (U64Key::from(lottery_id), &info.sender),
+        );
+        if SYNDICATE_PRIZE_CLAIMS.has(deps.storage, claim_key.clone()) {
+            return Err(ContractError::SyndicatePrizeAlreadyClaimed(lottery_id));
+        }
+
+        // Split the prize by the shares held when the lottery was drawn
+        let member_shares = SYNDICATE_MEMBERS
+            .may_load_at_height(
+                deps.storage,
+                (U64Key::from(syndicate_id), &info.sender),
+                lottery_info.block_height,
+            )?
+            .unwrap_or_default();
+        if member_shares.is_zero() {
+            continue;
+        }
+        let snapshotted_syndicate_stats =
+            read_depositor_stats_at_height(deps.storage, &holder, lottery_info.block_height);
+
+        let (prize_ust, prize_glow) = calculate_winner_prize(
+            &deps.querier,
+            &config,
+            &prize,
+            &lottery_info,
+            &snapshotted_syndicate_stats,
+            &holder,
+        )?;
+
+        ust_to_send += Uint128::from(
+            Uint256::from(prize_ust)
+                .multiply_ratio(member_shares, snapshotted_syndicate_stats.shares),
+        );
+        glow_to_send += Uint128::from(
+            Uint256::from(prize_glow)
+                .multiply_ratio(member_shares, snapshotted_syndicate_stats.shares),
+        );
+
+        // The member's share of the prize over the per winner cap goes back to the prize buckets
+        let excess: Vec<Uint256> = calculate_prize_cap_excess(&prize, &lottery_info)
+            .into_iter()
+            .map(|excess| excess.multiply_ratio(member_shares, snapshotted_syndicate_stats.shares))
+            .collect();
+        roll_over_prize_excess(deps.storage, env.block.height, &excess)?;
+
+        SYNDICATE_PRIZE_CLAIMS.save(deps.storage, claim_key, &Empty {})?;
+    }
+
+    if ust_to_send == Uint128::zero() {
+        return Err(ContractError::InsufficientClaimableFunds(
+            info.sender.to_string(),
+        ));
+    }
+
+    // Deduct taxes on the claim
+    let net_send = deduct_tax(
+        deps.as_ref(),
+        coin(ust_to_send.into(), config.stable_denom.clone()),
+    )?
+    .amount;
+
+    // Double-check if there is enough balance to send in the contract
+    let balance = query_balance(
+        deps.as_ref(),
+        env.contract.address.to_string(),
+        config.stable_denom.clone(),
+    )?;
+
+    if ust_to_send > balance.into() {
+        return Err(ContractError::InsufficientFunds {
+            to_send: ust_to_send,
+            available_balance: balance,
+        });
+    }
+
+    update_depositor_ledger(deps.storage, &info.sender, |ledger| {
+        ledger.ust_won += Uint256::from(net_send);
+        ledger.glow_won += Uint256::from(glow_to_send);
+    })?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![CosmosMsg::Bank(BankMsg::Send {
+        to_address: info.sender.to_string(),
+        amount: vec![Coin {
+            denom: config.stable_denom.clone(),
+            amount: net_send,
+        }],
+    })];
+
+    if glow_to_send != Uint128::zero() {
+        msgs.push(glow_prize_msg(
+            deps.storage,
+            &deps.querier,
+            &config,
+            &info.sender,
+            glow_to_send,
+        )?);
+    }
+
+    Ok(Response::new().add_messages(msgs).add_attributes(vec![
+        attr("action", "claim_syndicate_lottery"),
+        attr("syndicate_id", syndicate_id.to_string()),
+        attr("lottery_ids", format!("{:?}", lottery_ids)),
+        attr("member", info.sender.to_string()),
+        attr("redeemed_ust", net_send),
+        attr("redeemed_glow", glow_to_send),
+    ]))
+}
+
+pub fn query_syndicate(deps: Deps, syndicate_id: u64) -> StdResult<SyndicateResponse> {
+    let syndicate = SYNDICATES.load(deps.storage, U64Key::from(syndicate_id))?;
+    let holder = Syndicate::holder(syndicate_id);
+    let syndicate_stats = read_depositor_stats(deps.storage, &holder);
+
+    Ok(SyndicateResponse {
+        syndicate_id,
+        creator: syndicate.creator.to_string(),
+        holder: holder.to_string(),
+        num_members: syndicate.num_members,
+        shares: syndicate_stats.shares,
+        num_tickets: syndicate_stats.num_tickets,
+    })
+}
+
+pub fn query_syndicate_member(
+    deps: Deps,
+    syndicate_id: u64,
+    member: String,
+) -> StdResult<SyndicateMemberResponse> {
+    let member_addr = deps.api.addr_validate(&member)?;
+    let shares = SYNDICATE_MEMBERS
+        .may_load(deps.storage, (U64Key::from(syndicate_id), &member_addr))?
+        .unwrap_or_default();
+
+    Ok(SyndicateMemberResponse {
+        syndicate_id,
+        member,
+        shares,
+    })
+}
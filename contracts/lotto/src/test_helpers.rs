@@ -4,17 +4,21 @@ use crate::helpers::{
 use crate::mock_querier::MOCK_CONTRACT_ADDR;
 use crate::state::{
     OldDepositorInfo, OldLotteryInfo, CONFIG, OLD_PREFIX_DEPOSIT, OLD_PREFIX_LOTTERY, POOL, STATE,
+    WINNER_LEAVES,
 };
-use crate::tests::{A_UST, RATE};
+use crate::tests::{A_UST, FIRST_LOTTO_TIME, HOUR_TIME, RATE};
 use cosmwasm_storage::bucket;
-use glow_protocol::lotto::NUM_PRIZE_BUCKETS;
+use cw0::Expiration;
+use cw_storage_plus::U64Key;
+use glow_protocol::lotto::{DrawMilestone, DrawTimeline};
+use glow_protocol::merkle::{merkle_root, winner_leaf};
+use glow_protocol::sdk::lotto::encode_tickets;
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::{coin, Addr, Deps, StdResult, Storage};
+use cosmwasm_std::{coin, Addr, BlockInfo, Deps, Order, StdResult, Storage, Timestamp};
 use glow_protocol::querier::{deduct_tax, query_token_balance};
-use std::convert::TryInto;
 
-pub fn calculate_prize_buckets(deps: Deps) -> [Uint256; NUM_PRIZE_BUCKETS] {
+pub fn calculate_prize_buckets(deps: Deps) -> Vec<Uint256> {
     let pool = POOL.load(deps.storage).unwrap();
     let config = CONFIG.load(deps.storage).unwrap();
     let state = STATE.load(deps.storage).unwrap();
@@ -46,7 +50,7 @@ pub fn calculate_prize_buckets(deps: Deps) -> [Uint256; NUM_PRIZE_BUCKETS] {
             .amount,
     );
 
-    let mut prize_buckets = state.prize_buckets;
+    let mut prize_buckets = state.prize_buckets.clone();
 
     for index in 0..state.prize_buckets.len() {
         // Add the proportional amount of the net redeemed amount to the relevant award bucket.
@@ -58,10 +62,10 @@ pub fn calculate_prize_buckets(deps: Deps) -> [Uint256; NUM_PRIZE_BUCKETS] {
 }
 
 pub fn calculate_lottery_prize_buckets(
-    state_prize_buckets: [Uint256; NUM_PRIZE_BUCKETS],
-    number_winners: [u32; NUM_PRIZE_BUCKETS],
+    state_prize_buckets: Vec<Uint256>,
+    number_winners: Vec<u32>,
     reserve_factor: u64,
-) -> ([Uint256; NUM_PRIZE_BUCKETS], Uint256) {
+) -> (Vec<Uint256>, Uint256) {
     let mut total_reserve = Uint256::zero();
 
     (
@@ -77,24 +81,20 @@ pub fn calculate_lottery_prize_buckets(
                     *a - reserve_fee
                 }
             })
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap(),
+            .collect(),
         total_reserve,
     )
 }
 
 pub fn calculate_remaining_state_prize_buckets(
-    state_prize_buckets: [Uint256; NUM_PRIZE_BUCKETS],
-    number_winners: [u32; NUM_PRIZE_BUCKETS],
-) -> [Uint256; NUM_PRIZE_BUCKETS] {
+    state_prize_buckets: Vec<Uint256>,
+    number_winners: Vec<u32>,
+) -> Vec<Uint256> {
     state_prize_buckets
         .iter()
         .zip(&number_winners)
         .map(|(a, b)| if *b == 0 { *a } else { Uint256::zero() })
-        .collect::<Vec<_>>()
-        .try_into()
-        .unwrap()
+        .collect()
 }
 
 pub fn generate_sequential_ticket_combinations(num_combinations: u64) -> Vec<String> {
@@ -104,21 +104,7 @@ pub fn generate_sequential_ticket_combinations(num_combinations: u64) -> Vec<Str
 }
 
 pub fn vec_string_tickets_to_encoded_tickets(vec_string_tickets: Vec<String>) -> String {
-    // Convert each string to
-    // when it's a string its taking 8 bits per char
-    // but each char only holds 4 bits of information
-    // convert it to just 4 bits, but then thats u4 not u8. u8 is 256
-
-    let binary_data = vec_string_tickets
-        // Iterate over combinations
-        .iter()
-        // Take each combination and hex decode it
-        .flat_map(|s| hex::decode(s).unwrap())
-        // Then collect the flat map into a vec of u8
-        .collect::<Vec<u8>>();
-
-    // Encode the vec of u8 with base64
-    base64::encode(binary_data)
+    encode_tickets(&vec_string_tickets)
 }
 
 // Used for testing migration
@@ -140,3 +126,48 @@ pub fn old_store_depositor_info(
     bucket::<OldDepositorInfo>(storage, OLD_PREFIX_DEPOSIT)
         .save(depositor.as_bytes(), depositor_info)
 }
+
+/// The draw timeline of the first lottery when all prizes are executed in a single page
+pub fn single_page_draw_timeline(
+    execute_lottery_block: &BlockInfo,
+    execute_prize_block: &BlockInfo,
+) -> DrawTimeline {
+    let execute_prize_milestone = DrawMilestone {
+        height: execute_prize_block.height,
+        time: execute_prize_block.time,
+    };
+
+    DrawTimeline {
+        sales_cutoff: Some(Expiration::AtTime(Timestamp::from_seconds(
+            FIRST_LOTTO_TIME,
+        ))),
+        lottery_executed: Some(DrawMilestone {
+            height: execute_lottery_block.height,
+            time: execute_lottery_block.time,
+        }),
+        randomness_available: Some(Expiration::AtTime(
+            execute_lottery_block.time.plus_seconds(HOUR_TIME),
+        )),
+        randomness_fetched: Some(execute_prize_milestone.clone()),
+        prize_pages: vec![execute_prize_milestone.clone()],
+        finalized: Some(execute_prize_milestone),
+    }
+}
+
+pub fn winners_root_of(storage: &dyn Storage, lottery_id: u64) -> Option<String> {
+    let leaves = WINNER_LEAVES
+        .prefix(U64Key::from(lottery_id))
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (k, leaf) = item.unwrap();
+            winner_leaf(
+                std::str::from_utf8(&k).unwrap(),
+                lottery_id,
+                leaf.ust_amount,
+                leaf.glow_amount,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    Some(hex::encode(merkle_root(&leaves)))
+}
@@ -0,0 +1,263 @@
+use cosmwasm_bignumber::Decimal256;
+use cosmwasm_std::{attr, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage};
+use cw_storage_plus::U64Key;
+use glow_protocol::common::Pagination;
+use glow_protocol::lotto::{
+    ConfigVersion, ConfigVersionsResponse, ScheduledUpdateResponse, ScheduledUpdatesResponse,
+    TimelockedConfigUpdate,
+};
+
+use crate::error::ContractError;
+use crate::helpers::{validate_min_matches_for_prize, validate_prize_distribution};
+use crate::state::{
+    read_scheduled_updates, Config, ScheduledUpdate, CONFIG, CONFIG_VERSIONS,
+    NEXT_SCHEDULED_UPDATE_ID, SCHEDULED_UPDATES,
+};
+
+// Minimum notice in seconds given to depositors before a timelocked update applies
+pub const MIN_TIMELOCK_DELAY: u64 = 60 * 60 * 24 * 2;
+// Seconds after an update raising the instant withdrawal fee or changing the prize
+// distribution during which depositors can still withdraw under the previous fee
+pub const GRACE_WINDOW: u64 = 60 * 60 * 24 * 7;
+// Config versions kept in the history
+pub const MAX_CONFIG_VERSIONS: usize = 5;
+
+fn validate_timelocked_config_update(
+    update: &TimelockedConfigUpdate,
+    config: &Config,
+) -> Result<(), ContractError> {
+    if update.reserve_factor.is_none()
+        && update.instant_withdrawal_fee.is_none()
+        && update.prize_distribution.is_none()
+        && update.min_matches_for_prize.is_none()
+    {
+        return Err(ContractError::EmptyConfigUpdate {});
+    }
+
+    if let Some(reserve_factor) = update.reserve_factor {
+        if reserve_factor > Decimal256::one() {
+            return Err(ContractError::InvalidReserveFactor {});
+        }
+    }
+
+    if let Some(instant_withdrawal_fee) = update.instant_withdrawal_fee {
+        if instant_withdrawal_fee > Decimal256::one() {
+            return Err(ContractError::InvalidWithdrawalFee {});
+        }
+    }
+
+    if let Some(prize_distribution) = &update.prize_distribution {
+        validate_prize_distribution(prize_distribution, config.num_prize_buckets())?;
+    }
+
+    // The updated minimum matches must be consistent with the updated prize distribution
+    let prize_distribution = update
+        .prize_distribution
+        .as_ref()
+        .unwrap_or(&config.prize_distribution);
+    if let Some(min_matches_for_prize) = update
+        .min_matches_for_prize
+        .or(config.min_matches_for_prize)
+    {
+        validate_min_matches_for_prize(min_matches_for_prize, prize_distribution)?;
+    }
+
+    Ok(())
+}
+
+pub fn execute_schedule_config_update(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    update: TimelockedConfigUpdate,
+    eta: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let min_eta = env.block.time.seconds() + MIN_TIMELOCK_DELAY;
+    if eta < min_eta {
+        return Err(ContractError::InvalidTimelockEta { min_eta });
+    }
+
+    validate_timelocked_config_update(&update, &config)?;
+
+    let id = NEXT_SCHEDULED_UPDATE_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    SCHEDULED_UPDATES.save(
+        deps.storage,
+        U64Key::from(id),
+        &ScheduledUpdate { update, eta },
+    )?;
+    NEXT_SCHEDULED_UPDATE_ID.save(deps.storage, &(id + 1))?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "schedule_config_update"),
+        attr("id", id.to_string()),
+        attr("eta", eta.to_string()),
+    ]))
+}
+
+pub fn execute_scheduled_update(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    let ScheduledUpdate { update, eta } = SCHEDULED_UPDATES.load(deps.storage, U64Key::from(id))?;
+
+    if env.block.time.seconds() < eta {
+        return Err(ContractError::TimelockNotExpired { eta });
+    }
+
+    // Depositors get a grace window to exit under the previous terms
+    // if the update makes them worse off
+    let fee_raised = matches!(
+        update.instant_withdrawal_fee,
+        Some(instant_withdrawal_fee) if instant_withdrawal_fee > config.instant_withdrawal_fee
+    );
+    let prize_distribution_changed = matches!(
+        &update.prize_distribution,
+        Some(prize_distribution) if *prize_distribution != config.prize_distribution
+    );
+    if fee_raised || prize_distribution_changed {
+        record_config_version(deps.storage, &config, env.block.time.seconds())?;
+    }
+
+    if let Some(reserve_factor) = update.reserve_factor {
+        config.reserve_factor = reserve_factor;
+    }
+
+    if let Some(instant_withdrawal_fee) = update.instant_withdrawal_fee {
+        config.instant_withdrawal_fee = instant_withdrawal_fee;
+    }
+
+    if let Some(prize_distribution) = update.prize_distribution {
+        config.prize_distribution = prize_distribution;
+    }
+
+    if let Some(min_matches_for_prize) = update.min_matches_for_prize {
+        config.min_matches_for_prize = Some(min_matches_for_prize);
+    }
+
+    // The prize distribution may have been re-balanced since the update was scheduled
+    if let Some(min_matches_for_prize) = config.min_matches_for_prize {
+        validate_min_matches_for_prize(min_matches_for_prize, &config.prize_distribution)?;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+    SCHEDULED_UPDATES.remove(deps.storage, U64Key::from(id));
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "execute_scheduled_update"),
+        attr("id", id.to_string()),
+    ]))
+}
+
+pub fn execute_cancel_scheduled_update(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check permission
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let scheduled_update = SCHEDULED_UPDATES.load(deps.storage, U64Key::from(id))?;
+
+    // Once the eta has passed anyone can execute the update, so it can no longer be cancelled
+    if env.block.time.seconds() >= scheduled_update.eta {
+        return Err(ContractError::TimelockExpired {
+            eta: scheduled_update.eta,
+        });
+    }
+
+    SCHEDULED_UPDATES.remove(deps.storage, U64Key::from(id));
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "cancel_scheduled_update"),
+        attr("id", id.to_string()),
+    ]))
+}
+
+/// Appends the terms of config before an update to the history of config versions
+fn record_config_version(
+    storage: &mut dyn Storage,
+    config: &Config,
+    changed_at: u64,
+) -> StdResult<()> {
+    let mut versions = CONFIG_VERSIONS.may_load(storage)?.unwrap_or_default();
+    let version = versions.last().map_or(0, |version| version.version + 1);
+
+    versions.push(ConfigVersion {
+        version,
+        changed_at,
+        instant_withdrawal_fee: config.instant_withdrawal_fee,
+        prize_distribution: config.prize_distribution.clone(),
+    });
+    if versions.len() > MAX_CONFIG_VERSIONS {
+        versions.remove(0);
+    }
+
+    CONFIG_VERSIONS.save(storage, &versions)
+}
+
+/// Instant withdrawal fee charged at now: the lowest of the current fee and of the
+/// fees in force before the updates whose grace window is still running
+pub fn effective_instant_withdrawal_fee(
+    storage: &dyn Storage,
+    config: &Config,
+    now: u64,
+) -> StdResult<Decimal256> {
+    Ok(CONFIG_VERSIONS
+        .may_load(storage)?
+        .unwrap_or_default()
+        .iter()
+        .filter(|version| now < version.changed_at + GRACE_WINDOW)
+        .fold(config.instant_withdrawal_fee, |fee, version| {
+            std::cmp::min(fee, version.instant_withdrawal_fee)
+        }))
+}
+
+pub fn query_config_versions(deps: Deps, env: Env) -> StdResult<ConfigVersionsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigVersionsResponse {
+        versions: CONFIG_VERSIONS.may_load(deps.storage)?.unwrap_or_default(),
+        instant_withdrawal_fee: effective_instant_withdrawal_fee(
+            deps.storage,
+            &config,
+            env.block.time.seconds(),
+        )?,
+    })
+}
+
+pub fn query_scheduled_updates(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ScheduledUpdatesResponse> {
+    let pagination = Pagination::uncapped(start_after, limit);
+    let updates = read_scheduled_updates(deps.storage, &pagination)?
+        .into_iter()
+        .map(|(id, scheduled_update)| ScheduledUpdateResponse {
+            id,
+            update: scheduled_update.update,
+            eta: scheduled_update.eta,
+        })
+        .collect();
+
+    Ok(ScheduledUpdatesResponse {
+        updates,
+        limit: pagination.limit as u32,
+    })
+}
@@ -0,0 +1,109 @@
+use cosmwasm_std::{Addr, Deps, Order, QuerierWrapper, StdResult, Storage};
+use cw_storage_plus::U64Key;
+use glow_protocol::common::Pagination;
+use glow_protocol::lotto::{WinnerLeafResponse, WinnerLeavesResponse};
+use glow_protocol::merkle::{merkle_root, winner_leaf};
+use std::str::from_utf8;
+
+use crate::helpers::calculate_winner_prize;
+use crate::state::{
+    read_depositor_stats_at_height, read_lottery_info, Config, LotteryInfo, WinnerLeaf, PRIZES,
+    WINNER_LEAVES,
+};
+
+/// Records the prizes of the winners of an awarded lottery and returns the hex
+/// encoded merkle root of their leaves, taken in ascending winner address order.
+/// Claim contracts on other chains verify winnings against this root
+pub fn record_winner_leaves(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    config: &Config,
+    lottery_id: u64,
+    lottery_info: &LotteryInfo,
+) -> StdResult<String> {
+    let winners = PRIZES
+        .prefix(U64Key::from(lottery_id))
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (k, prize_info) = item?;
+            Ok((Addr::unchecked(from_utf8(&k)?), prize_info))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut leaves = vec![];
+    for (winner, prize_info) in winners {
+        let snapshotted_depositor_stats =
+            read_depositor_stats_at_height(storage, &winner, lottery_info.block_height);
+        let (ust_amount, glow_amount) = calculate_winner_prize(
+            querier,
+            config,
+            &prize_info,
+            lottery_info,
+            &snapshotted_depositor_stats,
+            &winner,
+        )?;
+
+        WINNER_LEAVES.save(
+            storage,
+            (U64Key::from(lottery_id), &winner),
+            &WinnerLeaf {
+                ust_amount,
+                glow_amount,
+            },
+        )?;
+        leaves.push(winner_leaf(
+            winner.as_str(),
+            lottery_id,
+            ust_amount,
+            glow_amount,
+        ));
+    }
+
+    Ok(hex::encode(merkle_root(&leaves)))
+}
+
+/// Leaves of the winners merkle tree of a lottery, in tree order
+pub fn query_winner_leaves(
+    deps: Deps,
+    lottery_id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<WinnerLeavesResponse> {
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let pagination = Pagination::new(start_after, limit);
+
+    let leaves = WINNER_LEAVES
+        .prefix(U64Key::from(lottery_id))
+        .range(
+            deps.storage,
+            pagination.start_bound(),
+            None,
+            Order::Ascending,
+        )
+        .take(pagination.limit)
+        .map(|item| {
+            let (k, leaf) = item?;
+            let winner = from_utf8(&k)?.to_string();
+            Ok(WinnerLeafResponse {
+                leaf: hex::encode(winner_leaf(
+                    &winner,
+                    lottery_id,
+                    leaf.ust_amount,
+                    leaf.glow_amount,
+                )),
+                winner,
+                ust_amount: leaf.ust_amount,
+                glow_amount: leaf.glow_amount,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(WinnerLeavesResponse {
+        lottery_id,
+        winners_root: read_lottery_info(deps.storage, lottery_id).winners_root,
+        leaves,
+        limit: pagination.limit as u32,
+    })
+}
@@ -0,0 +1,259 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    attr, coin, BankMsg, CosmosMsg, Deps, DepsMut, Env, Order, Response, StdResult, Storage,
+};
+use cw_storage_plus::U64Key;
+use glow_protocol::lotto::{
+    Claim, QueuedWithdrawalResponse, QueuedWithdrawalsResponse, WithdrawalQueueResponse,
+};
+use glow_protocol::querier::deduct_tax;
+
+use crate::anchor::redeem_stable_msg;
+use crate::error::ContractError;
+use crate::helpers::add_unbonding_claim;
+use crate::querier::{query_balance, query_exchange_rate};
+use crate::state::{
+    read_depositor_info, store_depositor_info, Config, QueuedWithdrawal, WithdrawalQueueState,
+    CONFIG, WITHDRAWAL_QUEUE, WITHDRAWAL_QUEUE_STATE,
+};
+
+// settings for processing the queue
+const DEFAULT_QUEUE_LIMIT: u32 = 10;
+const MAX_QUEUE_LIMIT: u32 = 30;
+
+pub fn read_withdrawal_queue_state(storage: &dyn Storage) -> StdResult<WithdrawalQueueState> {
+    Ok(WITHDRAWAL_QUEUE_STATE
+        .may_load(storage)?
+        .unwrap_or_default())
+}
+
+/// Stable anchor can currently pay out for redemptions
+pub fn query_redemption_capacity(deps: Deps, config: &Config) -> StdResult<Uint256> {
+    query_balance(
+        deps,
+        config.anchor_contract.to_string(),
+        config.stable_denom.clone(),
+    )
+}
+
+/// Withdrawals are queued while anchor can't redeem them,
+/// and behind the withdrawals already queued
+pub fn should_queue_withdrawal(
+    deps: Deps,
+    config: &Config,
+    withdrawn_aust_value: Uint256,
+) -> StdResult<bool> {
+    Ok(!read_withdrawal_queue_state(deps.storage)?
+        .total_aust
+        .is_zero()
+        || withdrawn_aust_value > query_redemption_capacity(deps, config)?)
+}
+
+/// Adds a withdrawal of aust_amount paying amount of stable at the back of the queue
+pub fn enqueue_withdrawal(
+    storage: &mut dyn Storage,
+    mut withdrawal: QueuedWithdrawal,
+) -> StdResult<u64> {
+    let mut queue_state = read_withdrawal_queue_state(storage)?;
+
+    let id = queue_state.next_id;
+    withdrawal.id = id;
+    queue_state.next_id += 1;
+    queue_state.total_aust += withdrawal.aust_amount;
+
+    WITHDRAWAL_QUEUE.save(storage, U64Key::from(id), &withdrawal)?;
+    WITHDRAWAL_QUEUE_STATE.save(storage, &queue_state)?;
+
+    Ok(id)
+}
+
+/// Fulfills queued withdrawals in order, up to the redemption capacity of anchor.
+/// The withdrawal at which the capacity runs out is fulfilled pro rata and stays
+/// at the front of the queue.
+pub fn execute_process_withdrawal_queue(
+    deps: DepsMut,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut queue_state = read_withdrawal_queue_state(deps.storage)?;
+
+    if queue_state.total_aust.is_zero() {
+        return Err(ContractError::WithdrawalQueueEmpty {});
+    }
+
+    let mut capacity = query_redemption_capacity(deps.as_ref(), &config)?;
+    if capacity.is_zero() {
+        return Err(ContractError::NoRedemptionCapacity {});
+    }
+
+    let aust_exchange_rate = query_exchange_rate(
+        deps.as_ref(),
+        config.anchor_contract.to_string(),
+        env.block.height,
+    )?
+    .exchange_rate;
+
+    let limit = limit.unwrap_or(DEFAULT_QUEUE_LIMIT).min(MAX_QUEUE_LIMIT) as usize;
+    let withdrawals = WITHDRAWAL_QUEUE
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(Vec<u8>, QueuedWithdrawal)>>>()?;
+
+    let mut redeemed_aust = Uint256::zero();
+    let mut payouts: Vec<CosmosMsg> = vec![];
+    let mut num_fulfilled: u64 = 0;
+
+    for (key, mut withdrawal) in withdrawals {
+        if capacity.is_zero() {
+            break;
+        }
+
+        let withdrawal_value = withdrawal.aust_amount * aust_exchange_rate;
+        let (fulfilled_aust, fulfilled_amount) = if withdrawal_value <= capacity {
+            (withdrawal.aust_amount, withdrawal.amount)
+        } else {
+            let ratio = Decimal256::from_ratio(capacity, withdrawal_value);
+            (withdrawal.aust_amount * ratio, withdrawal.amount * ratio)
+        };
+
+        if fulfilled_aust.is_zero() {
+            break;
+        }
+
+        capacity = capacity - std::cmp::min(capacity, fulfilled_aust * aust_exchange_rate);
+        redeemed_aust += fulfilled_aust;
+        queue_state.total_aust -= fulfilled_aust;
+
+        withdrawal.aust_amount -= fulfilled_aust;
+        withdrawal.amount -= fulfilled_amount;
+        if withdrawal.aust_amount.is_zero() {
+            WITHDRAWAL_QUEUE.remove(deps.storage, key.into());
+            num_fulfilled += 1;
+        } else {
+            WITHDRAWAL_QUEUE.save(deps.storage, key.into(), &withdrawal)?;
+        }
+
+        if fulfilled_amount.is_zero() {
+            continue;
+        }
+
+        // Regular withdrawals become unbonding claims. The amount is sent right away
+        // when the depositor has no claim slot left, as it was already unbonding.
+        if !withdrawal.instant {
+            let mut depositor_info = read_depositor_info(deps.storage, &withdrawal.depositor);
+            let added = add_unbonding_claim(
+                &mut depositor_info,
+                Claim {
+                    amount: fulfilled_amount,
+                    release_at: withdrawal.release_at,
+                },
+                config.epoch_interval,
+            );
+            if added.is_ok() {
+                store_depositor_info(
+                    deps.storage,
+                    &withdrawal.depositor,
+                    depositor_info,
+                    env.block.height,
+                )?;
+                continue;
+            }
+        }
+
+        payouts.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: withdrawal
+                .recipient
+                .as_ref()
+                .unwrap_or(&withdrawal.depositor)
+                .to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                coin(fulfilled_amount.into(), config.stable_denom.clone()),
+            )?],
+        }));
+    }
+
+    WITHDRAWAL_QUEUE_STATE.save(deps.storage, &queue_state)?;
+
+    // The payouts depend on the redeemed funds, so the redemption must succeed
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !redeemed_aust.is_zero() {
+        msgs.push(redeem_stable_msg(&config, redeemed_aust)?);
+    }
+    msgs.extend(payouts);
+
+    Ok(Response::new().add_messages(msgs).add_attributes(vec![
+        attr("action", "process_withdrawal_queue"),
+        attr("redeem_amount_anchor", redeemed_aust.to_string()),
+        attr("num_fulfilled", num_fulfilled.to_string()),
+        attr("queued_aust", queue_state.total_aust.to_string()),
+    ]))
+}
+
+/// Queued withdrawals of address, with the stable they would receive if the
+/// queue was processed now
+pub fn query_queued_withdrawals(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> StdResult<QueuedWithdrawalsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let address = deps.api.addr_validate(&address)?;
+
+    let aust_exchange_rate =
+        query_exchange_rate(deps, config.anchor_contract.to_string(), env.block.height)?
+            .exchange_rate;
+    let mut capacity = query_redemption_capacity(deps, &config)?;
+
+    let mut withdrawals: Vec<QueuedWithdrawalResponse> = vec![];
+    let mut aust_ahead = Uint256::zero();
+
+    for (position, item) in WITHDRAWAL_QUEUE
+        .range(deps.storage, None, None, Order::Ascending)
+        .enumerate()
+    {
+        let (_, withdrawal) = item?;
+
+        let withdrawal_value = withdrawal.aust_amount * aust_exchange_rate;
+        let estimated_fulfillment = if withdrawal_value <= capacity {
+            withdrawal.amount
+        } else {
+            withdrawal.amount * Decimal256::from_ratio(capacity, withdrawal_value)
+        };
+
+        if withdrawal.depositor == address {
+            withdrawals.push(QueuedWithdrawalResponse {
+                id: withdrawal.id,
+                position: position as u64,
+                aust_amount: withdrawal.aust_amount,
+                amount: withdrawal.amount,
+                instant: withdrawal.instant,
+                release_at: withdrawal.release_at,
+                aust_ahead,
+                estimated_fulfillment,
+            });
+        }
+
+        aust_ahead += withdrawal.aust_amount;
+        capacity = capacity - std::cmp::min(capacity, withdrawal_value);
+    }
+
+    Ok(QueuedWithdrawalsResponse {
+        address: address.to_string(),
+        withdrawals,
+    })
+}
+
+pub fn query_withdrawal_queue(deps: Deps) -> StdResult<WithdrawalQueueResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let queue_state = read_withdrawal_queue_state(deps.storage)?;
+
+    Ok(WithdrawalQueueResponse {
+        total_aust: queue_state.total_aust,
+        num_withdrawals: WITHDRAWAL_QUEUE
+            .keys(deps.storage, None, None, Order::Ascending)
+            .count() as u64,
+        redemption_capacity: query_redemption_capacity(deps, &config)?,
+    })
+}
@@ -2,8 +2,8 @@
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Binary, CanonicalAddr, CosmosMsg, Decimal, Deps, DepsMut, Env,
-    MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg,
+    from_binary, to_binary, Addr, BankMsg, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps,
+    DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg,
 };
 
 use crate::state::{
@@ -13,6 +13,7 @@ use crate::state::{
 
 use crate::state::read_old_config;
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use glow_protocol::querier::deduct_tax;
 use glow_protocol::staking::{
     ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
     StakerInfoResponse, StateResponse,
@@ -42,6 +43,8 @@ pub fn instantiate(
             glow_token: deps.api.addr_canonicalize(&msg.glow_token)?,
             staking_token: deps.api.addr_canonicalize(&msg.staking_token)?,
             distribution_schedule: msg.distribution_schedule,
+            reward_denom: msg.reward_denom,
+            reward_depositors: vec![],
         },
     )?;
 
@@ -51,6 +54,8 @@ pub fn instantiate(
             last_distributed: env.block.time.seconds(),
             total_bond_amount: Uint128::zero(),
             global_reward_index: Decimal::zero(),
+            global_reserve_reward_index: Decimal::zero(),
+            undistributed_reserve_reward: Uint128::zero(),
         },
     )?;
 
@@ -66,7 +71,16 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
         ExecuteMsg::UpdateConfig {
             owner,
             distribution_schedule,
-        } => update_config(deps, env, info, owner, distribution_schedule),
+            reward_depositors,
+        } => update_config(
+            deps,
+            env,
+            info,
+            owner,
+            distribution_schedule,
+            reward_depositors,
+        ),
+        ExecuteMsg::DepositReward {} => deposit_reward(deps, info),
         ExecuteMsg::MigrateStaking {
             new_staking_contract,
         } => migrate_staking(deps, env, info, new_staking_contract),
@@ -139,8 +153,11 @@ pub fn unbond(deps: DepsMut, env: Env, info: MessageInfo, amount: Uint128) -> St
     decrease_bond_amount(&mut state, &mut staker_info, amount)?;
 
     // Store or remove updated rewards info
-    // depends on the left pending reward and bond amount
-    if staker_info.pending_reward.is_zero() && staker_info.bond_amount.is_zero() {
+    // depends on the left pending rewards and bond amount
+    if staker_info.pending_reward.is_zero()
+        && staker_info.pending_reserve_reward.is_zero()
+        && staker_info.bond_amount.is_zero()
+    {
         remove_staker_info(deps.storage, &sender_addr_raw);
     } else {
         store_staker_info(deps.storage, &sender_addr_raw, &staker_info)?;
@@ -180,6 +197,9 @@ pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Respons
     let amount = staker_info.pending_reward;
     staker_info.pending_reward = Uint128::zero();
 
+    let reserve_amount = staker_info.pending_reserve_reward;
+    staker_info.pending_reserve_reward = Uint128::zero();
+
     // Store or remove updated rewards info
     // depends on the left pending reward and bond amount
     if staker_info.bond_amount.is_zero() {
@@ -191,20 +211,79 @@ pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Respons
     // Store updated state
     store_state(deps.storage, &state)?;
 
-    Ok(Response::new()
-        .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: deps.api.addr_humanize(&config.glow_token)?.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: info.sender.to_string(),
-                amount,
-            })?,
-            funds: vec![],
-        })])
-        .add_attributes(vec![
-            ("action", "withdraw"),
-            ("owner", info.sender.as_str()),
-            ("amount", amount.to_string().as_str()),
-        ]))
+    let mut messages = vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(&config.glow_token)?.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: info.sender.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    })];
+
+    if !reserve_amount.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: config.reward_denom,
+                    amount: reserve_amount,
+                },
+            )?],
+        }));
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "withdraw"),
+        ("owner", info.sender.as_str()),
+        ("amount", amount.to_string().as_str()),
+        ("reserve_amount", reserve_amount.to_string().as_str()),
+    ]))
+}
+
+/// Deposit Reward
+/// Reward depositors (lotto, fee distributor) send reward_denom funds
+/// to be distributed to stakers pro rata to their bond amount
+pub fn deposit_reward(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    let mut state: State = read_state(deps.storage)?;
+
+    let sender_addr_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if !config.reward_depositors.contains(&sender_addr_raw) {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    let amount = info
+        .funds
+        .iter()
+        .find(|c| c.denom == config.reward_denom)
+        .map(|c| c.amount)
+        .unwrap_or_else(Uint128::zero);
+
+    if amount.is_zero() {
+        return Err(StdError::generic_err(format!(
+            "No {} assets are provided to deposit",
+            config.reward_denom
+        )));
+    }
+
+    // Keep the reward for later if there are no stakers to distribute it to
+    let reward = amount + state.undistributed_reserve_reward;
+    if state.total_bond_amount.is_zero() {
+        state.undistributed_reserve_reward = reward;
+    } else {
+        state.global_reserve_reward_index = state.global_reserve_reward_index
+            + Decimal::from_ratio(reward, state.total_bond_amount);
+        state.undistributed_reserve_reward = Uint128::zero();
+    }
+
+    store_state(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "deposit_reward"),
+        ("depositor", info.sender.as_str()),
+        ("amount", amount.to_string().as_str()),
+    ]))
 }
 
 pub fn update_config(
@@ -213,6 +292,7 @@ pub fn update_config(
     info: MessageInfo,
     owner: Option<String>,
     distribution_schedule: Option<Vec<(u64, u64, Uint128)>>,
+    reward_depositors: Option<Vec<String>>,
 ) -> StdResult<Response> {
     // get gov address by querying anc token minter
     let config: Config = read_config(deps.storage)?;
@@ -237,11 +317,22 @@ pub fn update_config(
             config.distribution_schedule
         };
 
+    let reward_depositors: Vec<CanonicalAddr> = if let Some(reward_depositors) = reward_depositors {
+        reward_depositors
+            .iter()
+            .map(|depositor| deps.api.addr_canonicalize(depositor))
+            .collect::<StdResult<Vec<CanonicalAddr>>>()?
+    } else {
+        config.reward_depositors
+    };
+
     let new_config = Config {
         owner,
         glow_token: config.glow_token,
         staking_token: config.staking_token,
         distribution_schedule,
+        reward_denom: config.reward_denom,
+        reward_depositors,
     };
     store_config(deps.storage, &new_config)?;
 
@@ -360,13 +451,19 @@ fn compute_reward(config: &Config, state: &mut State, block_time: u64) {
         + Decimal::from_ratio(distributed_amount, state.total_bond_amount);
 }
 
-// withdraw reward to pending reward
+// withdraw rewards to pending rewards
 fn compute_staker_reward(state: &State, staker_info: &mut StakerInfo) -> StdResult<()> {
     let pending_reward = (staker_info.bond_amount * state.global_reward_index)
         .checked_sub(staker_info.bond_amount * staker_info.reward_index)?;
 
     staker_info.reward_index = state.global_reward_index;
     staker_info.pending_reward += pending_reward;
+
+    let pending_reserve_reward = (staker_info.bond_amount * state.global_reserve_reward_index)
+        .checked_sub(staker_info.bond_amount * staker_info.reserve_reward_index)?;
+
+    staker_info.reserve_reward_index = state.global_reserve_reward_index;
+    staker_info.pending_reserve_reward += pending_reserve_reward;
     Ok(())
 }
 
@@ -388,6 +485,12 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         glow_token: deps.api.addr_humanize(&config.glow_token)?.to_string(),
         staking_token: deps.api.addr_humanize(&config.staking_token)?.to_string(),
         distribution_schedule: config.distribution_schedule,
+        reward_denom: config.reward_denom,
+        reward_depositors: config
+            .reward_depositors
+            .iter()
+            .map(|depositor| Ok(deps.api.addr_humanize(depositor)?.to_string()))
+            .collect::<StdResult<Vec<String>>>()?,
     };
 
     Ok(resp)
@@ -415,6 +518,8 @@ pub fn query_state(deps: Deps, env: Env, block_time: Option<u64>) -> StdResult<S
         last_distributed: state.last_distributed,
         total_bond_amount: state.total_bond_amount,
         global_reward_index: state.global_reward_index,
+        global_reserve_reward_index: state.global_reserve_reward_index,
+        undistributed_reserve_reward: state.undistributed_reserve_reward,
     })
 }
 
@@ -449,6 +554,8 @@ pub fn query_staker_info(
         reward_index: staker_info.reward_index,
         bond_amount: staker_info.bond_amount,
         pending_reward: staker_info.pending_reward,
+        reserve_reward_index: staker_info.reserve_reward_index,
+        pending_reserve_reward: staker_info.pending_reserve_reward,
     })
 }
 
@@ -516,6 +623,8 @@ pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response>
         glow_token: old_config.glow_token,
         staking_token: old_config.staking_token,
         distribution_schedule: msg.distribution_schedule,
+        reward_denom: msg.reward_denom,
+        reward_depositors: vec![],
     };
 
     // store new config in contract
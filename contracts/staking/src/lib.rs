@@ -3,3 +3,6 @@ pub mod state;
 
 #[cfg(test)]
 mod testing;
+
+#[cfg(test)]
+mod mock_querier;
@@ -15,6 +15,9 @@ pub struct Config {
     pub glow_token: CanonicalAddr,
     pub staking_token: CanonicalAddr,
     pub distribution_schedule: Vec<(u64, u64, Uint128)>,
+    pub reward_denom: String,
+    #[serde(default)]
+    pub reward_depositors: Vec<CanonicalAddr>,
 }
 
 pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
@@ -41,6 +44,12 @@ pub struct State {
     pub last_distributed: u64,
     pub total_bond_amount: Uint128,
     pub global_reward_index: Decimal,
+    // Reward index of the reward_denom rewards deposited by the lotto
+    #[serde(default)]
+    pub global_reserve_reward_index: Decimal,
+    // Rewards deposited while nothing was bonded, distributed with the next deposit
+    #[serde(default)]
+    pub undistributed_reserve_reward: Uint128,
 }
 
 pub fn store_state(storage: &mut dyn Storage, state: &State) -> StdResult<()> {
@@ -56,6 +65,10 @@ pub struct StakerInfo {
     pub reward_index: Decimal,
     pub bond_amount: Uint128,
     pub pending_reward: Uint128,
+    #[serde(default)]
+    pub reserve_reward_index: Decimal,
+    #[serde(default)]
+    pub pending_reserve_reward: Uint128,
 }
 
 /// returns return staker_info of the given owner
@@ -81,6 +94,8 @@ pub fn read_staker_info(storage: &dyn Storage, owner: &CanonicalAddr) -> StdResu
             reward_index: Decimal::zero(),
             bond_amount: Uint128::zero(),
             pending_reward: Uint128::zero(),
+            reserve_reward_index: Decimal::zero(),
+            pending_reserve_reward: Uint128::zero(),
         }),
     }
 }
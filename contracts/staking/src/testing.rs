@@ -1,7 +1,9 @@
 use crate::contract::{execute, instantiate, query};
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use crate::mock_querier::mock_dependencies;
+use cosmwasm_std::testing::{mock_env, mock_info};
 use cosmwasm_std::{
-    attr, from_binary, to_binary, CosmosMsg, Decimal, StdError, SubMsg, Uint128, WasmMsg,
+    attr, from_binary, to_binary, BankMsg, Coin, CosmosMsg, Decimal, StdError, SubMsg, Uint128,
+    WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use glow_protocol::staking::ExecuteMsg::UpdateConfig;
@@ -19,6 +21,7 @@ fn proper_initialization() {
         glow_token: "reward0000".to_string(),
         staking_token: "staking0000".to_string(),
         distribution_schedule: vec![(100, 200, Uint128::from(1000000u128))],
+        reward_denom: "uusd".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -36,6 +39,8 @@ fn proper_initialization() {
             glow_token: "reward0000".to_string(),
             staking_token: "staking0000".to_string(),
             distribution_schedule: vec![(100, 200, Uint128::from(1000000u128))],
+            reward_denom: "uusd".to_string(),
+            reward_depositors: vec![],
         }
     );
 
@@ -52,6 +57,8 @@ fn proper_initialization() {
             last_distributed: mock_env().block.time.seconds(),
             total_bond_amount: Uint128::zero(),
             global_reward_index: Decimal::zero(),
+            global_reserve_reward_index: Decimal::zero(),
+            undistributed_reserve_reward: Uint128::zero(),
         }
     );
 }
@@ -76,6 +83,7 @@ fn test_bond_tokens() {
                 Uint128::from(10000000u128),
             ),
         ],
+        reward_denom: "uusd".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -109,6 +117,8 @@ fn test_bond_tokens() {
             reward_index: Decimal::zero(),
             pending_reward: Uint128::zero(),
             bond_amount: Uint128::from(100u128),
+            reserve_reward_index: Decimal::zero(),
+            pending_reserve_reward: Uint128::zero(),
         }
     );
 
@@ -126,6 +136,8 @@ fn test_bond_tokens() {
             total_bond_amount: Uint128::from(100u128),
             global_reward_index: Decimal::zero(),
             last_distributed: mock_env().block.time.seconds(),
+            global_reserve_reward_index: Decimal::zero(),
+            undistributed_reserve_reward: Uint128::zero(),
         }
     );
 
@@ -157,6 +169,8 @@ fn test_bond_tokens() {
             reward_index: Decimal::from_ratio(1000u128, 1u128),
             pending_reward: Uint128::from(100000u128),
             bond_amount: Uint128::from(200u128),
+            reserve_reward_index: Decimal::zero(),
+            pending_reserve_reward: Uint128::zero(),
         }
     );
 
@@ -174,6 +188,8 @@ fn test_bond_tokens() {
             total_bond_amount: Uint128::from(200u128),
             global_reward_index: Decimal::from_ratio(1000u128, 1u128),
             last_distributed: mock_env().block.time.seconds() + 10,
+            global_reserve_reward_index: Decimal::zero(),
+            undistributed_reserve_reward: Uint128::zero(),
         }
     );
 
@@ -212,6 +228,7 @@ fn test_unbond() {
                 Uint128::from(10000000u128),
             ),
         ],
+        reward_denom: "uusd".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -281,6 +298,7 @@ fn test_compute_reward() {
                 Uint128::from(10000000u128),
             ),
         ],
+        reward_denom: "uusd".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -326,6 +344,8 @@ fn test_compute_reward() {
             reward_index: Decimal::from_ratio(10000u128, 1u128),
             pending_reward: Uint128::from(1000000u128),
             bond_amount: Uint128::from(200u128),
+            reserve_reward_index: Decimal::zero(),
+            pending_reserve_reward: Uint128::zero(),
         }
     );
 
@@ -357,6 +377,8 @@ fn test_compute_reward() {
             reward_index: Decimal::from_ratio(15000u64, 1u64),
             pending_reward: Uint128::from(2000000u128),
             bond_amount: Uint128::from(100u128),
+            reserve_reward_index: Decimal::zero(),
+            pending_reserve_reward: Uint128::zero(),
         }
     );
 
@@ -379,6 +401,8 @@ fn test_compute_reward() {
             reward_index: Decimal::from_ratio(25000u64, 1u64),
             pending_reward: Uint128::from(3000000u128),
             bond_amount: Uint128::from(100u128),
+            reserve_reward_index: Decimal::zero(),
+            pending_reserve_reward: Uint128::zero(),
         }
     );
 }
@@ -403,6 +427,7 @@ fn test_withdraw() {
                 Uint128::from(10000000u128),
             ),
         ],
+        reward_denom: "uusd".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -440,6 +465,104 @@ fn test_withdraw() {
     );
 }
 
+#[test]
+fn test_deposit_reward() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        glow_token: "reward0000".to_string(),
+        staking_token: "staking0000".to_string(),
+        distribution_schedule: vec![(
+            mock_env().block.time.seconds(),
+            mock_env().block.time.seconds() + 100,
+            Uint128::from(1000000u128),
+        )],
+        reward_denom: "uusd".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = UpdateConfig {
+        owner: None,
+        distribution_schedule: None,
+        reward_depositors: Some(vec!["lotto0000".to_string()]),
+    };
+    let info = mock_info("owner", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // only reward depositors can deposit rewards
+    let msg = ExecuteMsg::DepositReward {};
+    let info = mock_info("addr0000", &[Coin::new(1000u128, "uusd")]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Unauthorized"),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    // rewards deposited before anyone bonded are kept for the next deposit
+    let info = mock_info("lotto0000", &[Coin::new(500u128, "uusd")]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+
+    // bond 100 tokens
+    let bond_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, bond_msg).unwrap();
+
+    let info = mock_info("lotto0000", &[Coin::new(1000u128, "uusd")]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::StakerInfo {
+            staker: "addr0000".to_string(),
+            block_time: None,
+        },
+    )
+    .unwrap();
+    let staker_info: StakerInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        staker_info.reserve_reward_index,
+        Decimal::from_ratio(15u128, 1u128)
+    );
+    assert_eq!(staker_info.pending_reserve_reward, Uint128::from(1500u128));
+
+    // both rewards are withdrawn, the reserve reward net of the tax
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(100);
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::Withdraw {}).unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "reward0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(1000000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![Coin::new(1485u128, "uusd")],
+            }))
+        ]
+    );
+}
+
 #[test]
 fn test_migrate_staking() {
     let mut deps = mock_dependencies(&[]);
@@ -460,6 +583,7 @@ fn test_migrate_staking() {
                 Uint128::from(10000000u128),
             ),
         ],
+        reward_denom: "uusd".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -557,7 +681,9 @@ fn test_migrate_staking() {
                     mock_env().block.time.seconds() + 150,
                     Uint128::from(5000000u128)
                 ), // slot was modified
-            ]
+            ],
+            reward_denom: "uusd".to_string(),
+            reward_depositors: vec![],
         }
     );
 }
@@ -582,6 +708,7 @@ fn test_update_config_owner() {
                 Uint128::from(10000000u128),
             ),
         ],
+        reward_denom: "uusd".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -591,6 +718,7 @@ fn test_update_config_owner() {
     let msg = UpdateConfig {
         owner: Some("owner1".to_string()),
         distribution_schedule: None,
+        reward_depositors: None,
     };
     let info = mock_info("not_owner", &[]);
     let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
@@ -647,6 +775,7 @@ fn test_update_config_distribution_schedules() {
                 Uint128::from(10000000u128),
             ),
         ],
+        reward_denom: "uusd".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -659,6 +788,7 @@ fn test_update_config_distribution_schedules() {
             mock_env().block.time.seconds() + 400,
             Uint128::from(10000000u128),
         )]),
+        reward_depositors: None,
     };
 
     let info = mock_info("not_owner", &[]);
@@ -728,6 +858,7 @@ fn test_update_config_distribution_schedules() {
                 Uint128::from(10000000u128),
             ),
         ]),
+        reward_depositors: None,
     };
 
     let info = mock_info("owner", &[]);
@@ -791,6 +922,7 @@ fn test_update_config_distribution_schedules() {
                 Uint128::from(10000000u128),
             ),
         ]),
+        reward_depositors: None,
     };
 
     let info = mock_info("owner", &[]);
@@ -835,6 +967,7 @@ fn test_update_config_distribution_schedules() {
                 Uint128::from(10000000u128),
             ),
         ]),
+        reward_depositors: None,
     };
 
     let info = mock_info("owner", &[]);
@@ -906,6 +1039,7 @@ fn test_update_config_distribution_schedules() {
                 Uint128::from(50000000u128),
             ),
         ]),
+        reward_depositors: None,
     };
 
     let info = mock_info("owner", &[]);
@@ -976,6 +1110,7 @@ fn test_update_config_distribution_schedules() {
                 Uint128::from(80000000u128),
             ),
         ]),
+        reward_depositors: None,
     };
 
     let info = mock_info("owner", &[]);
@@ -1051,6 +1186,7 @@ fn test_update_config_distribution_schedules() {
                 Uint128::from(60000000u128),
             ),
         ]),
+        reward_depositors: None,
     };
 
     let info = mock_info("owner", &[]);
@@ -7,6 +7,7 @@ use cosmwasm_std::Uint128;
 pub struct InstantiateMsg {
     pub owner: String,
     pub glow_token: String,
+    pub community_contract: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -14,6 +15,7 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     UpdateConfig {
         owner: Option<String>,
+        community_contract: Option<String>,
     },
     WithdrawExpiredTokens {
         recipient: String,
@@ -21,12 +23,18 @@ pub enum ExecuteMsg {
     RegisterMerkleRoot {
         merkle_root: String,
         expiry_at_seconds: u64,
+        total_amount: Uint128,
     },
     Claim {
         stage: u8,
         amount: Uint128,
         proof: Vec<String>,
     },
+    /// Sends the unclaimed GLOW of an expired stage to the community contract.
+    /// Can be called by anyone
+    SweepStage {
+        stage: u8,
+    },
 }
 
 /// We currently take no arguments for migrations
@@ -41,6 +49,7 @@ pub enum QueryMsg {
     LatestStage {},
     IsClaimed { stage: u8, address: String },
     ExpiryAtSeconds { stage: u8 },
+    Stage { stage: u8 },
 }
 
 // We define a custom struct for each query response
@@ -48,6 +57,7 @@ pub enum QueryMsg {
 pub struct ConfigResponse {
     pub owner: String,
     pub glow_token: String,
+    pub community_contract: Option<String>,
 }
 
 // We define a custom struct for each query response
@@ -74,3 +84,13 @@ pub struct IsClaimedResponse {
 pub struct ExpiryAtSecondsResponse {
     pub expiry_at_seconds: u64,
 }
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StageResponse {
+    pub stage: u8,
+    pub expiry_at_seconds: u64,
+    pub total_amount: Uint128,
+    pub claimed_amount: Uint128,
+    pub swept_amount: Option<Uint128>,
+}
@@ -1,7 +1,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Order;
+use cosmwasm_std::{Addr, CanonicalAddr, Order};
+use cw_storage_plus::Bound;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -19,3 +20,99 @@ impl From<OrderBy> for Order {
         }
     }
 }
+
+// Number of items a paginated query returns if its limit is not set, and at most
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+
+/// Keys paginated queries can start after, encoded as stored
+pub trait PaginationKey {
+    fn key_bytes(&self) -> Vec<u8>;
+}
+
+impl PaginationKey for u64 {
+    fn key_bytes(&self) -> Vec<u8> {
+        self.to_be_bytes().to_vec()
+    }
+}
+
+impl PaginationKey for String {
+    fn key_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+}
+
+impl PaginationKey for Addr {
+    fn key_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+}
+
+impl PaginationKey for CanonicalAddr {
+    fn key_bytes(&self) -> Vec<u8> {
+        self.as_slice().to_vec()
+    }
+}
+
+/// Start and clamped limit of a paginated query
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pagination<T> {
+    pub start_after: Option<T>,
+    pub limit: usize,
+}
+
+impl<T: PaginationKey> Pagination<T> {
+    /// Pagination with the DEFAULT_LIMIT and MAX_LIMIT shared by the contracts
+    pub fn new(start_after: Option<T>, limit: Option<u32>) -> Self {
+        Self::with_limits(start_after, limit, DEFAULT_LIMIT, MAX_LIMIT)
+    }
+
+    /// Pagination with the DEFAULT_LIMIT only, for the queries which never capped their limit
+    pub fn uncapped(start_after: Option<T>, limit: Option<u32>) -> Self {
+        Self::with_limits(start_after, limit, DEFAULT_LIMIT, u32::MAX)
+    }
+
+    pub fn with_limits(
+        start_after: Option<T>,
+        limit: Option<u32>,
+        default_limit: u32,
+        max_limit: u32,
+    ) -> Self {
+        Pagination {
+            start_after,
+            limit: limit.unwrap_or(default_limit).min(max_limit) as usize,
+        }
+    }
+
+    /// Exclusive bound on the keys after start_after
+    pub fn start_bound(&self) -> Option<Bound> {
+        self.start_after
+            .as_ref()
+            .map(|key| Bound::Exclusive(key.key_bytes()))
+    }
+
+    /// Min and max bounds of a range over the keys following start_after in order
+    pub fn range_bounds(&self, order: Order) -> (Option<Bound>, Option<Bound>) {
+        match order {
+            Order::Ascending => (self.start_bound(), None),
+            Order::Descending => (None, self.start_bound()),
+        }
+    }
+
+    /// Same as range_bounds for the raw ranges of buckets, whose start is
+    /// inclusive and end exclusive
+    pub fn raw_range_bounds(&self, order: Order) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+        let key = self.start_after.as_ref().map(|key| key.key_bytes());
+        match order {
+            Order::Ascending => (
+                key.map(|mut key| {
+                    // The first key after start_after
+                    key.push(0);
+                    key
+                }),
+                None,
+            ),
+            Order::Descending => (None, key),
+        }
+    }
+}
@@ -34,7 +34,7 @@ pub enum ExecuteMsg {
     SponsorLotto {
         amount: Uint128,
         award: Option<bool>,
-        prize_distribution: Option<[Decimal256; 7]>,
+        prize_distribution: Option<Vec<Decimal256>>,
     },
     WithdrawSponsor {},
     Swap {
@@ -43,6 +43,15 @@ pub enum ExecuteMsg {
     Burn {
         amount: Uint128,
     },
+    /// Set the contracts allowed to request stable spends - restricted to owner
+    UpdateSpendWhitelist {
+        whitelist: Vec<String>,
+    },
+    /// Send amount of stable to the sender, up to spend_limit - restricted to
+    /// whitelisted contracts
+    WhitelistedSpend {
+        amount: Uint128,
+    },
 }
 
 /// Migrations message
@@ -58,6 +67,7 @@ pub struct MigrateMsg {
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
+    SpendWhitelist {},
 }
 
 // We define a custom struct for each query response
@@ -71,3 +81,8 @@ pub struct ConfigResponse {
     pub terraswap_factory: String,
     pub spend_limit: Uint128,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendWhitelistResponse {
+    pub whitelist: Vec<String>,
+}
@@ -36,6 +36,22 @@ pub enum ExecuteMsg {
     RemoveDistributor {
         distributor: String,
     },
+    /// Set the emission curve bounding the cumulative spends - restricted to owner (gov)
+    UpdateEmissionCurve {
+        emission_curve: EmissionCurve,
+    },
+}
+
+/// GLOW emittable by the distributor over time. Each period starting from genesis_time
+/// unlocks (1 - decay_rate) times the GLOW of the previous one, genesis_amount for the
+/// first, until end_time
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmissionCurve {
+    pub genesis_time: u64,
+    pub genesis_amount: Uint128,
+    pub decay_rate: Decimal256,
+    pub period: u64,
+    pub end_time: u64,
 }
 
 /// We currently take no arguments for migrations
@@ -51,6 +67,7 @@ pub enum QueryMsg {
         target_award: Uint256,
         current_emission_rate: Decimal256,
     },
+    RemainingEmission {},
 }
 
 // We define a custom struct for each query response
@@ -71,3 +88,14 @@ pub struct ConfigResponse {
 pub struct GlowEmissionRateResponse {
     pub emission_rate: Decimal256,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RemainingEmissionResponse {
+    pub emission_curve: Option<EmissionCurve>,
+    // GLOW spent since the emission curve was set
+    pub total_spent: Uint128,
+    // GLOW that can be spent now
+    pub emittable: Uint128,
+    // GLOW left to spend until the end of the curve
+    pub remaining: Uint128,
+}
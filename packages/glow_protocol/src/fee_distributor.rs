@@ -1,4 +1,4 @@
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Decimal, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -21,6 +21,18 @@ pub enum ExecuteMsg {
         limit: Option<u32>,
     },
     DistributeGlow {},
+    /// Public Message
+    /// Opens a UST epoch with the UST received since the last one, shared among
+    /// the veGLOW holders pro-rata to their balances at the epoch timestamp, which is
+    /// the last week boundary before the distribution.
+    /// If nothing is staked, the amount is below the minimum epoch amount or an epoch
+    /// was already opened for that week, it rolls over to the next epoch
+    DistributeUst {},
+    /// Claims the sender's UST of the epochs in [start, end)
+    ClaimEpochs {
+        start: u64,
+        end: u64,
+    },
     UpdateConfig {
         owner: Option<String>,
     },
@@ -40,6 +52,15 @@ pub enum QueryMsg {
         fee_limit: Option<u32>,
         fee_start_after: Option<u64>,
     },
+    UstEpoch {
+        epoch: u64,
+    },
+    /// UST claimable by the address over the epochs in [start, end)
+    ClaimableEpochs {
+        address: String,
+        start: u64,
+        end: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
@@ -54,6 +75,8 @@ pub struct ConfigResponse {
 pub struct StateResponse {
     pub contract_addr: String,
     pub total_distributed_unclaimed_fees: Uint128,
+    pub total_unclaimed_ust: Uint128,
+    pub num_ust_epochs: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -63,3 +86,19 @@ pub struct StakerResponse {
     pub last_claimed_fee_timestamp: u64,
     pub claimable_fees_lower_bound: Uint128,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct UstEpochResponse {
+    pub epoch: u64,
+    pub timestamp: u64,
+    pub total_voting_balance: Uint128,
+    pub ust_per_voting_balance: Decimal,
+    pub distributed_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct ClaimableEpochsResponse {
+    pub claimable: Uint128,
+    // Epochs of the range with a claimable amount
+    pub epochs: Vec<u64>,
+}
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 use crate::common::OrderBy;
+use crate::lotto::ExecuteMsg as LottoExecuteMsg;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -41,6 +42,24 @@ pub enum ExecuteMsg {
         proposal_deposit: Option<Uint128>,
         snapshot_period: Option<u64>,
     },
+    /// Owner operation to enable polls paid with a UST deposit,
+    /// which is held in Anchor until the poll ends
+    UpdateUstDepositConfig {
+        anchor_contract: String,
+        a_terra_contract: String,
+        community_contract: String,
+        proposal_deposit: Uint128,
+    },
+    /// Create a poll paying the deposit in uusd.
+    /// The principal is refunded when quorum is reached and
+    /// the yield earned meanwhile goes to the community contract
+    CreateUstPoll {
+        title: String,
+        description: String,
+        link: Option<String>,
+        execute_msgs: Option<Vec<PollExecuteMsg>>,
+        actions: Option<Vec<PollAction>>,
+    },
     CastVote {
         poll_id: u64,
         vote: VoteOption,
@@ -57,6 +76,57 @@ pub enum ExecuteMsg {
     ExpirePoll {
         poll_id: u64,
     },
+    /// Delegate the voting power of the sender to another address
+    Delegate {
+        to: String,
+    },
+    /// Take back the voting power delegated by the sender
+    Undelegate {},
+    /// Refuse the voting power delegated to the sender by delegator
+    RemoveDelegator {
+        delegator: String,
+    },
+    /// Owner operation to add or remove contracts polls can send
+    /// arbitrary messages to with PollAction::Execute
+    UpdateExecuteWhitelist {
+        add: Option<Vec<String>>,
+        remove: Option<Vec<String>>,
+    },
+    /// Owner operation to replace the committee attesting off-chain vote results.
+    /// Members are secp256k1 public keys, min_signatures of them must sign an attestation
+    UpdateOffchainCommittee {
+        members: Vec<Binary>,
+        min_signatures: u32,
+    },
+    /// Owner operation to register an off-chain proposal by the hex encoded
+    /// keccak256 hash of its json encoded actions
+    RegisterOffchainProposal {
+        proposal_hash: String,
+    },
+    /// Public Message
+    /// Executes the actions of a registered off-chain proposal once, given the
+    /// committee attestation of a vote reaching quorum and threshold
+    ExecuteOffchainProposal {
+        actions: Vec<PollAction>,
+        attestation: OffchainVoteAttestation,
+        signatures: Vec<CommitteeSignature>,
+    },
+}
+
+/// Result of an off-chain vote. Committee members sign the keccak256 hash of
+/// the json encoded {chain_id, contract, proposal_hash, attestation}
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OffchainVoteAttestation {
+    pub yes_votes: Uint128,
+    pub no_votes: Uint128,
+    // Voting power snapshotted for the vote, the quorum is computed from it
+    pub total_voting_power: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CommitteeSignature {
+    pub public_key: Binary,
+    pub signature: Binary,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -71,6 +141,7 @@ pub enum Cw20HookMsg {
         description: String,
         link: Option<String>,
         execute_msgs: Option<Vec<PollExecuteMsg>>,
+        actions: Option<Vec<PollAction>>,
     },
 }
 
@@ -82,6 +153,36 @@ pub struct PollExecuteMsg {
     pub msg: Binary,
 }
 
+/// Typed action of a poll. All the actions of a poll are executed
+/// atomically, after the execute_msgs, in the order they were given
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PollAction {
+    /// Spend GLOW held by the community contract
+    CommunitySpend {
+        community_contract: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Update the lotto configuration. Only the lotto config messages are accepted
+    UpdateLottoConfig {
+        lotto_contract: String,
+        msg: Box<LottoExecuteMsg>,
+    },
+    /// Approve a later MigrateContract poll of contract to code_id. Gov must be
+    /// the admin of contract for the migration to go through
+    WhitelistMigration { contract: String, code_id: u64 },
+    /// Migrate a contract administrated by gov to a code id whitelisted by an earlier
+    /// poll. The approval is consumed by the migration
+    MigrateContract {
+        contract: String,
+        new_code_id: u64,
+        msg: Binary,
+    },
+    /// Execute an arbitrary message on a whitelisted contract
+    Execute { contract: String, msg: Binary },
+}
+
 /// We currently take no arguments for migrations
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {
@@ -111,6 +212,33 @@ pub enum QueryMsg {
         limit: Option<u32>,
         order_by: Option<OrderBy>,
     },
+    /// Address the voting power of address is delegated to
+    Delegation {
+        address: String,
+    },
+    /// Addresses delegating their voting power to address
+    Delegators {
+        address: String,
+    },
+    /// Voting balance of address including delegations at timestamp
+    VotingPower {
+        address: String,
+        timestamp: Option<u64>,
+    },
+    /// Contracts PollAction::Execute can target
+    ExecuteWhitelist {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Code ids contract can be migrated to with PollAction::MigrateContract
+    MigrationWhitelist {
+        contract: String,
+    },
+    /// Committee attesting off-chain vote results
+    OffchainCommittee {},
+    OffchainProposal {
+        proposal_hash: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
@@ -125,6 +253,15 @@ pub struct ConfigResponse {
     pub expiration_period: u64,
     pub proposal_deposit: Uint128,
     pub snapshot_period: u64,
+    pub ust_deposit_config: Option<UstDepositConfigResponse>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct UstDepositConfigResponse {
+    pub anchor_contract: String,
+    pub a_terra_contract: String,
+    pub community_contract: String,
+    pub proposal_deposit: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
@@ -146,15 +283,51 @@ pub struct PollResponse {
     pub link: Option<String>,
     pub deposit_amount: Uint128,
     pub execute_data: Option<Vec<PollExecuteMsg>>,
+    pub actions: Option<Vec<PollAction>>,
     pub yes_votes: Uint128, // balance
     pub no_votes: Uint128,  // balance
     pub staked_amount: Option<Uint128>,
     pub total_balance_at_end_poll: Option<Uint128>,
+    pub ust_deposit: Option<UstDepositResponse>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct ExecuteWhitelistResponse {
+    pub contracts: Vec<String>,
+    // Limit applied to this page, at most MAX_LIMIT
+    pub limit: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct OffchainCommitteeResponse {
+    pub members: Vec<Binary>,
+    pub min_signatures: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct OffchainProposalResponse {
+    pub proposal_hash: String,
+    pub registered_at: u64,
+    pub executed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct MigrationWhitelistResponse {
+    pub contract: String,
+    pub code_ids: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct UstDepositResponse {
+    pub amount: Uint128,
+    pub aust_amount: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 pub struct PollsResponse {
     pub polls: Vec<PollResponse>,
+    // Limit applied to this page, at most MAX_LIMIT
+    pub limit: u32,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
@@ -179,6 +352,25 @@ pub struct VotersResponseItem {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 pub struct VotersResponse {
     pub voters: Vec<VotersResponseItem>,
+    // Limit applied to this page, at most MAX_LIMIT
+    pub limit: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct DelegationResponse {
+    pub delegate: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct DelegatorsResponse {
+    pub delegators: Vec<String>,
+}
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct VotingPowerResponse {
+    pub balance: Uint128,           // own ve balance
+    pub delegated_balance: Uint128, // ve balance delegated to the address
+    pub voting_power: Uint128, // zero if the address delegates, balance + delegated_balance otherwise
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
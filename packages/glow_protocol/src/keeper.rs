@@ -0,0 +1,90 @@
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub owner: String,
+    pub lotto_contract: String,
+    pub bounty_denom: String,
+}
+
+/// Maintenance tasks of the lotto contract the keeper can dispatch
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum KeeperTask {
+    ExecuteLottery,
+    ExecutePrize,
+    // Sweeps the reserve and updates the emissions
+    EpochOperations,
+    SweepExpired,
+}
+
+impl KeeperTask {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeeperTask::ExecuteLottery => "execute_lottery",
+            KeeperTask::ExecutePrize => "execute_prize",
+            KeeperTask::EpochOperations => "epoch_operations",
+            KeeperTask::SweepExpired => "sweep_expired",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Owner operation to update the config
+    UpdateConfig {
+        owner: Option<String>,
+        lotto_contract: Option<String>,
+    },
+    /// Owner operation to register a task, or update its schedule and bounty.
+    /// The task runs at most once every interval seconds and pays the bounty
+    /// to whoever pokes the keeper when it runs
+    RegisterTask {
+        task: KeeperTask,
+        interval: u64,
+        bounty: Uint128,
+    },
+    /// Owner operation to remove a task from the registry
+    RemoveTask { task: KeeperTask },
+    /// Public Message
+    /// Dispatches every registered task which is due and ready on the lotto
+    /// contract, paying the sum of their bounties to the sender
+    Poke {},
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// Every registered task with its schedule, and whether a poke would run it
+    Tasks {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub lotto_contract: String,
+    pub bounty_denom: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TaskInfo {
+    pub task: KeeperTask,
+    pub interval: u64,
+    pub bounty: Uint128,
+    // Block time in seconds of the last run, None if it never ran
+    pub last_run: Option<u64>,
+    pub due: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TasksResponse {
+    pub tasks: Vec<TaskInfo>,
+}
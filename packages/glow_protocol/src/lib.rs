@@ -4,8 +4,12 @@ pub mod community;
 pub mod distributor;
 pub mod fee_distributor;
 pub mod gov;
+pub mod keeper;
 pub mod lotto;
+pub mod merkle;
 pub mod querier;
+#[cfg(any(test, feature = "sdk"))]
+pub mod sdk;
 pub mod staking;
 pub mod ve_token;
 pub mod vesting;
@@ -2,11 +2,20 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::{Addr, Timestamp, Uint128};
+use cosmwasm_std::{Addr, Binary, Decimal, Timestamp, Uint128};
 use cw0::{Duration, Expiration};
+use cw20::Cw20ReceiveMsg;
 
+// Default length of the ticket sequences, instantiate can pick
+// any length between MIN_TICKET_LENGTH and MAX_TICKET_LENGTH
 pub const TICKET_LENGTH: usize = 6;
+pub const MIN_TICKET_LENGTH: usize = 4;
+pub const MAX_TICKET_LENGTH: usize = 8;
+// Prize buckets of the default ticket length
 pub const NUM_PRIZE_BUCKETS: usize = TICKET_LENGTH + 1;
+pub const MAX_COVERAGE_PREFIX_LEN: u8 = 2;
+pub const SECONDS_PER_WEEK: u64 = 7 * 24 * 60 * 60;
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct BoostConfig {
@@ -22,20 +31,354 @@ pub struct RewardEmissionsIndex {
     pub glow_emission_rate: Decimal256,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DrawMilestone {
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+/// Timeline of a lottery draw, used to measure draw latency
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct DrawTimeline {
+    pub sales_cutoff: Option<Expiration>, // scheduled lottery time at which ticket sales were closed
+    pub lottery_executed: Option<DrawMilestone>, // block in which ExecuteLottery ran
+    pub randomness_available: Option<Expiration>, // time from which the oracle round can be used
+    pub randomness_fetched: Option<DrawMilestone>, // block in which the winning sequence was set
+    pub prize_pages: Vec<DrawMilestone>,  // block of each ExecutePrize page
+    pub finalized: Option<DrawMilestone>, // block in which the lottery was awarded
+}
+
+/// Round over which winning tickets are weighted by the time they were held,
+/// in block time seconds
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TimeWeightWindow {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Position of ExecutePrize in the tickets of a lottery. Pages resume right
+/// after last_ticket, so tickets added or removed between pages don't shift it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PrizeCursor {
+    pub tier: u8, // leading digits of the winning sequence the scanned tickets share, 0 before the first page
+    pub last_ticket: Option<String>, // last ticket processed
+    pub processed_count: u64, // tickets processed so far
+}
+
+/// Where the reserve is swept to during epoch operations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReserveDestination {
+    #[default]
+    Community,
+    FeeDistributor,
+    Split {
+        community_bps: u16,
+    }, // share of the reserve going to the community, in basis points
+}
+
+/// Pause flags for each group of execution paths. The global paused flag
+/// only stops everything while the steps of a migration are pending
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PauseState {
+    pub deposits_paused: bool, // Deposits, gifts, sponsorships, escrow and gift card funding, and the savings deposits and conversions
+    pub withdrawals_paused: bool, // Withdrawals, the withdrawal queue, receipt redemptions, escrow withdrawals, gift card refunds and dust sweeps
+    pub lottery_paused: bool,     // ExecuteLottery, ExecutePrize and Skim
+    pub claims_paused: bool, // Prize, reward and badge claims, retried claims and IBC claim refunds
+}
+
+/// Loyalty weighting of prizes. When enabled, a winning ticket's share of its prize
+/// bucket is weighted by 1 + bonus_per_round * min(rounds held, max_bonus_rounds)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct LoyaltyConfig {
+    pub enabled: bool,
+    pub bonus_per_round: Decimal256,
+    pub max_bonus_rounds: u64,
+}
+
+/// What happens to the lotteries missed while the lottery couldn't be executed,
+/// e.g. during a chain halt
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MissedLotteryPolicy {
+    /// Run a single lottery and move on to the next lottery time in the future
+    #[default]
+    SkipToNext,
+    /// Run a lottery for each missed interval, up to max extra lotteries,
+    /// splitting the redeemed yield evenly between them
+    RunAllMissed { max: u64 },
+}
+
+/// Lockup terms for sponsorships. A sponsorship locked for N weeks earns GLOW emissions on
+/// lottery_deposit * (1 + bonus_per_week * min(N, max_lock_weeks)). Withdrawing before the
+/// lock expires forfeits early_exit_penalty of the deposit to the prize buckets.
+/// Lockups are disabled while max_lock_weeks is zero
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct SponsorLockConfig {
+    pub bonus_per_week: Decimal256,
+    pub max_lock_weeks: u64,
+    pub early_exit_penalty: Decimal256,
+}
+
+/// Economic parameters that can only be changed through the timelock,
+/// giving depositors advance notice of the change
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TimelockedConfigUpdate {
+    pub reserve_factor: Option<Decimal256>,
+    pub instant_withdrawal_fee: Option<Decimal256>,
+    pub prize_distribution: Option<Vec<Decimal256>>,
+    pub min_matches_for_prize: Option<u8>,
+}
+
+/// Terms in force before a timelocked update raised the instant withdrawal fee
+/// or changed the prize distribution
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigVersion {
+    pub version: u64,
+    // Block time in seconds the update applied at
+    pub changed_at: u64,
+    pub instant_withdrawal_fee: Decimal256,
+    pub prize_distribution: Vec<Decimal256>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RedepositConfig {
+    pub operator: Option<String>, // operator to register for the redeposited funds
+}
+
+/// Channels over which prizes can be claimed to another chain, and the timeout
+/// in seconds of the ics20 transfer packets
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct IbcClaimConfig {
+    pub allowed_channels: Vec<String>,
+    pub timeout_seconds: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimDestination {
+    pub ibc_channel: String, // ics20 channel the UST prize is transferred over
+    pub to_address: String,  // recipient on the remote chain
+}
+
+/// Asset the UST prize of a claim is paid in
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutAsset {
+    Ust,
+    // The equivalent aust, keeping the prize earning yield. The claim fails when
+    // the contract doesn't hold enough aust that isn't owed to anyone
+    Aust,
+    // Deposited back into the pool, like redeposit without an operator
+    Redeposit,
+}
+
+/// Progress of the GLOW payout of a prize claim
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaimReceiptStatus {
+    // The GLOW payout is being executed
+    Pending,
+    // The UST and GLOW prizes were paid
+    Finalized,
+    // The GLOW payout failed, it can be sent again with RetryClaim
+    Failed,
+}
+
+/// Preimage of a payout hash, the hex encoded keccak256 hash of "{salt}:{address}"
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayoutReveal {
+    pub address: String,
+    pub salt: String,
+}
+
+/// Share of a UST prize donated to a charity registered by gov
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CharityDonation {
+    pub charity: String,
+    // Between zero (excluded) and one
+    pub share: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GiftBatchItem {
+    pub recipient: String,
+    pub encoded_tickets: String,
+}
+
+/// Set as the data of deposit responses, for calling contracts to read in their reply handler
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositReceipt {
+    pub recipient: String,
+    pub shares_minted: Uint256,
+    pub tickets: u64,
+    pub aust_minted: Uint256,
+}
+
+/// Where the GLOW bought back with the reserve is sent
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BuybackDestination {
+    Community,
+    FeeDistributor,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BuybackConfig {
+    // Terraswap pair of the stable denom and GLOW
+    pub pair_contract: String,
+    pub glow_token: String,
+    // Share of the reserve swapped to GLOW at each epoch operation
+    pub reserve_share: Decimal256,
+    // Maximum spread accepted by the pair, above which the swap fails
+    // and the stable goes back to the reserve
+    pub max_spread: Decimal,
+    pub destination: BuybackDestination,
+}
+
+/// Decay of a GLOW emission rate, applied at each epoch operation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmissionDecaySchedule {
+    // Emission rate set when the schedule is updated
+    pub initial_rate: Decimal256,
+    // Multiplier applied to the emission rate at each epoch, at most one
+    pub decay_factor: Decimal256,
+    // Rate the emission rate doesn't decay below, at most initial_rate
+    pub floor: Decimal256,
+}
+
+/// Bounds of the automatic re-balancing of prize_distribution. Every interval lotteries,
+/// each tier share moves by at most max_step towards the share which pays target_payout
+/// to each of its winners, given the winners realized per tier so far
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrizeRebalancingConfig {
+    // Number of lotteries between re-balancing
+    pub interval: u64,
+    // Relative prize targeted for a single winner of each tier
+    pub target_payouts: Vec<Uint256>,
+    pub min_distribution: Vec<Decimal256>,
+    pub max_distribution: Vec<Decimal256>,
+    // Maximum change of a tier share at each re-balancing
+    pub max_step: Decimal256,
+}
+
+/// Keepers with exclusive access to ExecuteLottery and ExecutePrize during the first
+/// exclusivity_window seconds after they become ready, permissionless afterwards
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KeeperAllowlist {
+    pub keepers: Vec<String>,
+    pub exclusivity_window: u64,
+}
+
+/// Caps on the UST awarded by a prize bucket. Amounts are net of the reserve fee
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PrizeCap {
+    // Most a single winner of the bucket receives
+    pub per_winner: Option<Uint256>,
+    // Most the bucket awards to all its winners
+    pub per_tier: Option<Uint256>,
+}
+
+/// Badge minted to a sponsor once their cumulative sponsorship reaches threshold
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BadgeTier {
+    pub threshold: Uint256,
+    pub token_uri: Option<String>,
+}
+
+/// Sponsor badges minted on the cw721 badge_contract, ordered by increasing threshold.
+/// Tier numbers start at 1
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BadgeConfig {
+    pub badge_contract: String,
+    pub tiers: Vec<BadgeTier>,
+}
+
+/// Independent parts of the epoch operations, each rate limited by epoch_interval
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EpochOperation {
+    // Sends the reserve to the insurance fund, buyback, lp staking, community
+    // and fee distributor
+    SweepReserve,
+    // Updates the GLOW emission rates, advances the epoch, re-balances
+    // prize_distribution and records the share price
+    UpdateEmissions,
+    // Prunes the records of ibc claims refunded after timing out
+    SweepExpired,
+}
+
+/// Notification sent to the registered hook contracts after deposits,
+/// withdrawals and prize claims. Hook failures don't revert the operation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GlowHookMsg {
+    OnDeposit {
+        depositor: String,
+        recipient: String,
+        amount: Uint256,
+        tickets: u64,
+    },
+    OnWithdraw {
+        depositor: String,
+        amount: Uint256,
+        instant: bool,
+    },
+    OnPrizeClaimed {
+        winner: String,
+        lottery_ids: Vec<u64>,
+        amount: Uint128,
+        glow_amount: Uint128,
+    },
+}
+
+/// Execute message hook contracts receive, their ExecuteMsg must have
+/// a GlowHook(GlowHookMsg) variant
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HookExecuteMsg {
+    GlowHook(GlowHookMsg),
+}
+
+/// Limit on the stable withdrawn instantly within an epoch, withdrawals past
+/// it have to go through the unbonding period
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InstantWithdrawalCap {
+    /// Absolute stable amount
+    Amount(Uint256),
+    /// Share of the value of the user deposits at the start of the epoch
+    PoolRatio(Decimal256),
+}
+
+/// Deposit intent signed off-chain by the depositor. The signature is over the
+/// keccak256 hash of the json encoding of {"chain_id", "contract", "permit"}
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositPermit {
+    pub depositor: String,
+    pub encoded_tickets: String,
+    // Stable taken from the escrow of the depositor
+    pub amount: Uint256,
+    pub operator: Option<String>,
+    // Must match the nonce of the escrow
+    pub nonce: u64,
+    // Block time in seconds after which the permit can't be used
+    pub expires: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub owner: String,
-    pub stable_denom: String,                                // uusd
-    pub anchor_contract: String,                             // anchor money market address
-    pub aterra_contract: String,                             // aterra auusd contract address
-    pub oracle_contract: String,                             // oracle address
-    pub lottery_interval: u64,                               // time between lotteries
-    pub epoch_interval: u64,   // time between executing epoch operations
-    pub block_time: u64,       // number of blocks (or time) lottery is blocked while is executed
-    pub round_delta: u64,      // number of rounds of security to get oracle rand
+    pub stable_denom: String,                            // uusd
+    pub anchor_contract: String,                         // anchor money market address
+    pub aterra_contract: String,                         // aterra auusd contract address
+    pub oracle_contract: String,                         // oracle address
+    pub lottery_interval: u64,                           // time between lotteries
+    pub epoch_interval: u64,                             // time between executing epoch operations
+    pub block_time: u64, // number of blocks (or time) lottery is blocked while is executed
+    pub round_delta: u64, // number of rounds of security to get oracle rand
     pub ticket_price: Uint256, // prize of a ticket in stable_denom
-    pub max_holders: u8,       // Max number of holders per ticket
-    pub prize_distribution: [Decimal256; NUM_PRIZE_BUCKETS], // distribution for awarding prizes to winning tickets
+    pub max_holders: u8, // Max number of holders per ticket
+    pub prize_distribution: Vec<Decimal256>, // distribution for awarding prizes to winning tickets
     pub target_award: Uint256, // target award used in deposit rewards computation
     pub reserve_factor: Decimal256, // % of the prize that goes to the reserve fund
     pub split_factor: Decimal256, // what % of interest goes to saving and which one lotto pool
@@ -45,13 +388,17 @@ pub struct InstantiateMsg {
     pub initial_sponsor_glow_emission_rate: Decimal256, // initial GLOW emission rate for sponsor rewards
     pub initial_lottery_execution: u64, // time in seconds for the first Lotto execution
     pub max_tickets_per_depositor: u64, // the maximum number of tickets that a depositor can hold
-    pub glow_prize_buckets: [Uint256; NUM_PRIZE_BUCKETS], // glow to be awarded as a bonus to lottery winners
+    pub glow_prize_buckets: Vec<Uint256>, // glow to be awarded as a bonus to lottery winners
     pub lotto_winner_boost_config: Option<BoostConfig>, // the boost config to apply to glow emissions for lotto winners
+    pub ticket_length: Option<u8>, // length of the ticket sequences, TICKET_LENGTH if not set
+    pub min_matches_for_prize: Option<u8>, // matches of a winning ticket, derived from prize_distribution if not set
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
+    /// Receives receipt tokens sent with a Cw20HookMsg
+    Receive(Cw20ReceiveMsg),
     /// Register Contracts contract address - restricted to owner
     RegisterContracts {
         /// Gov contract tracks ve balances
@@ -63,82 +410,388 @@ pub enum ExecuteMsg {
         /// veGLOW contract for calculating boost multipliers
         ve_contract: String,
     },
-    /// Update contract configuration - restricted to owner
+    /// Update contract configuration - restricted to owner. Guardians can only
+    /// set pause flags to true
     UpdateConfig {
         owner: Option<String>,
         oracle_addr: Option<String>,
-        reserve_factor: Option<Decimal256>,
-        instant_withdrawal_fee: Option<Decimal256>,
         unbonding_period: Option<u64>,
         epoch_interval: Option<u64>,
         max_holders: Option<u8>,
         max_tickets_per_depositor: Option<u64>,
-        paused: Option<bool>,
+        paused: Option<bool>, // Sets all the pause flags at once
         lotto_winner_boost_config: Option<BoostConfig>,
         operator_glow_emission_rate: Option<Decimal256>,
         sponsor_glow_emission_rate: Option<Decimal256>,
+        min_deposit_amount: Option<Uint256>,
+        operator_reward_vesting_epochs: Option<u64>,
+        deposits_paused: Option<bool>,
+        withdrawals_paused: Option<bool>,
+        lottery_paused: Option<bool>,
+        claims_paused: Option<bool>,
     },
     /// Update lottery configuration - restricted to owner
     UpdateLotteryConfig {
         lottery_interval: Option<u64>,
         block_time: Option<u64>,
         ticket_price: Option<Uint256>,
-        prize_distribution: Option<[Decimal256; NUM_PRIZE_BUCKETS]>,
         round_delta: Option<u64>,
+        loyalty_config: Option<LoyaltyConfig>,
+        sponsor_lock_config: Option<SponsorLockConfig>,
+        ticket_eligibility_delay: Option<u64>,
+        missed_lottery_policy: Option<MissedLotteryPolicy>,
+        time_weighted_odds: Option<bool>,
+    },
+    /// Schedule a change of the timelocked parameters, executable after eta - restricted to owner
+    ScheduleConfigUpdate {
+        update: TimelockedConfigUpdate,
+        eta: u64,
+    },
+    /// Apply a scheduled config update whose eta has passed
+    ExecuteScheduledUpdate { id: u64 },
+    /// Cancel a scheduled config update before its eta - restricted to owner
+    CancelScheduledUpdate { id: u64 },
+    /// Update the channels prizes can be claimed over - restricted to owner
+    UpdateIbcClaimConfig { ibc_claim_config: IbcClaimConfig },
+    /// Credit a timed out ibc prize claim, which the transfer module refunded to
+    /// this contract, to the winner's local claims - restricted to owner
+    RefundIbcClaim { id: u64 },
+    /// Update where the reserve is swept to - restricted to gov
+    UpdateReserveDestination {
+        reserve_destination: ReserveDestination,
+        fee_distributor_contract: Option<String>,
+        lp_staking_contract: Option<String>,
+        lp_staking_reserve_share: Option<Decimal256>,
+    },
+    /// Add and remove blacklisted addresses, which can't deposit, gift or claim,
+    /// and set the dispute window before their claims can be redirected - restricted to gov
+    UpdateBlacklist {
+        add: Vec<String>,
+        remove: Vec<String>,
+        dispute_window: Option<u64>,
     },
+    /// Send the released unbonding claims of a blacklisted address to the community
+    /// contract once the dispute window has passed - restricted to gov
+    RedirectBlacklistedClaims { address: String },
+    /// Register and unregister the charities winners can donate to - restricted to gov
+    UpdateCharities {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Set the share of the reserve swapped to GLOW at each epoch operation,
+    /// or disable buybacks if None - restricted to gov
+    UpdateBuybackConfig {
+        buyback_config: Option<BuybackConfig>,
+    },
+    /// Set the share of the reserve moved to the insurance fund at each epoch
+    /// operation. The fund covers redemption shortfalls - restricted to gov
+    UpdateInsurance { reserve_share: Decimal256 },
+    /// Set the amount the jackpot bucket is topped up to from the community
+    /// contract at lottery executions, or stop the top-ups if None - restricted to gov
+    UpdateJackpotFloor { floor: Option<Uint256> },
+    /// Set the maximum deviation of the aust exchange rate per hour before
+    /// the lottery is paused. None disables the check - restricted to gov
+    UpdateRateGuard {
+        max_deviation_per_hour: Option<Decimal256>,
+    },
+    /// Accept a new aust exchange rate after a review, resuming the lottery
+    /// paused by the rate guard - restricted to gov
+    AcceptExchangeRate { rate: Decimal256 },
+    /// Set the limit on instant withdrawals per epoch. None lifts
+    /// the limit - restricted to gov
+    UpdateInstantWithdrawalCap { cap: Option<InstantWithdrawalCap> },
+    /// Set the bounds within which epoch operations re-balance prize_distribution
+    /// from realized win frequencies. None disables re-balancing - restricted to gov
+    UpdatePrizeRebalancing {
+        rebalancing: Option<Box<PrizeRebalancingConfig>>,
+    },
+    /// Set the caps on the UST awarded by each prize bucket, the excess rolls
+    /// over to the next lottery. None removes the caps - restricted to gov
+    UpdatePrizeCaps { prize_caps: Option<Vec<PrizeCap>> },
+    /// Set the share of auto claims paid to the keeper processing them - restricted to gov
+    UpdateAutoClaimBounty { bounty_rate: Decimal256 },
+    /// Give the listed keepers exclusive access to the lottery executions for a
+    /// window after they become ready, or open them to anyone right away if None
+    /// - restricted to gov
+    UpdateKeeperAllowlist { allowlist: Option<KeeperAllowlist> },
+    /// Set the cw721 contract and sponsorship thresholds of sponsor badges,
+    /// or stop minting them if None - restricted to gov
+    UpdateBadgeConfig { badge_config: Option<BadgeConfig> },
+    /// Mint the badges of the thresholds crossed by the cumulative sponsorship
+    /// of the sender which weren't minted yet, e.g. after a failed mint
+    ClaimBadges {},
+    /// Move amount of the reserve to the insurance fund - restricted to gov
+    TopUpInsurance { amount: Uint256 },
+    /// Send amount of the insurance fund to recipient, by default the
+    /// community contract - restricted to gov
+    WithdrawInsurance {
+        amount: Uint256,
+        recipient: Option<String>,
+    },
+    /// Set the decay schedules of the operator and sponsor GLOW emission rates,
+    /// applied at each epoch operation. A None schedule keeps that rate constant - restricted to gov
+    UpdateEmissionDecay {
+        operator_decay: Option<EmissionDecaySchedule>,
+        sponsor_decay: Option<EmissionDecaySchedule>,
+    },
+    /// Set the emission weight of a sponsor, between 1 and 5, multiplying the GLOW
+    /// emissions of its sponsorship - restricted to owner and gov
+    SetSponsorWeight { sponsor: String, weight: Decimal256 },
+    /// Add a named prize distribution award sponsorships can use - restricted to gov
+    CreatePrizeTemplate {
+        name: String,
+        prize_distribution: Vec<Decimal256>,
+    },
+    /// Change the prize distribution of a template - restricted to gov
+    UpdatePrizeTemplate {
+        name: String,
+        prize_distribution: Vec<Decimal256>,
+    },
+    /// Remove a prize template - restricted to gov
+    RemovePrizeTemplate { name: String },
+    /// Pull amount of glow from the owner into the prize reserve, which pays glow
+    /// prizes when the distributor can't - restricted to owner
+    TopUpGlow { amount: Uint128 },
     /// Deposit amount of stable into the pool
     Deposit {
         encoded_tickets: String,
         operator: Option<String>,
+        /// Campaign of the depositor's operator the deposit is attributed to
+        campaign_id: Option<String>,
+    },
+    /// Deposit amount of stable into the pool, receiving quick_pick
+    /// pseudo-randomly generated tickets
+    QuickPickDeposit {
+        quick_pick: u32,
+        operator: Option<String>,
     },
     /// Claim tickets
     ClaimTickets { encoded_tickets: String },
+    /// Use the savings appreciation of the sender to back up to max_tickets
+    /// quick_pick generated tickets, without depositing
+    ConvertYieldToTickets { max_tickets: u32 },
     /// Deposit amount of stable into the pool in the name of the recipient
     Gift {
         encoded_tickets: String,
         recipient: String,
         operator: Option<String>,
     },
+    /// Deposit amount of stable into the pool in the name of each recipient,
+    /// paying the ticket price of every gifted ticket
+    GiftBatch { gifts: Vec<GiftBatchItem> },
+    /// Deposit the stable sent by an integrating contract in the name of the recipient.
+    /// The response data holds the DepositReceipt of the deposit
+    DepositFor {
+        recipient: String,
+        encoded_tickets: String,
+        operator: Option<String>,
+    },
     /// Sponsor the pool. If award is true, sponsor the award available directly
     Sponsor {
         award: Option<bool>,
-        prize_distribution: Option<[Decimal256; NUM_PRIZE_BUCKETS]>,
+        prize_distribution: Option<Vec<Decimal256>>,
+        // Name of a prize template to use as prize distribution of an award sponsorship,
+        // instead of prize_distribution
+        prize_template: Option<String>,
+        // Number of weeks to lock the whole sponsorship for, in exchange for a higher
+        // GLOW emission weight. Only valid for non award sponsorships
+        lock_duration: Option<u64>,
     },
+    /// Donate stable to a single prize bucket, awarded at the next lottery.
+    /// Donations aren't sponsorships and can't be withdrawn
+    DonateToPrizeBucket { bucket_index: u8 },
+    /// Fulfill queued withdrawals in order, up to the redemption capacity of anchor
+    ProcessWithdrawalQueue { limit: Option<u32> },
+    /// Instantiate the cw20 receipt token from code_id - restricted to gov
+    CreateReceiptToken {
+        code_id: u64,
+        name: String,
+        symbol: String,
+    },
+    /// Deposit the sent stable without tickets, minting receipt tokens
+    /// redeemable for the deposit and its whole yield
+    ReceiptDeposit {},
+    /// Deposit the sent stable in savings mode: the deposit gets no tickets and its
+    /// whole yield goes to the sender instead of the prizes
+    DepositSavings {},
+    /// Withdraw amount of the sender's savings, or all of them if not set
+    WithdrawSavings { amount: Option<Uint256> },
+    /// Move amount of the sender's savings, or all of them if not set, into the pool
+    /// as a deposit for encoded_tickets
+    ConvertSavingsToTickets {
+        encoded_tickets: String,
+        amount: Option<Uint256>,
+    },
+    /// Add the sent stable to the escrow of the sender, used by deposit permits.
+    /// pubkey is required to open the escrow, and replaces the previous key if set
+    FundEscrow { pubkey: Option<Binary> },
+    /// Withdraw amount of stable from the escrow of the sender, or all of it if not set
+    WithdrawEscrow { amount: Option<Uint256> },
+    /// Deposit from the escrow of the permit depositor, who signed the permit off-chain.
+    /// Sent by a relayer paying the fees
+    DepositWithPermit {
+        permit: DepositPermit,
+        signature: Binary,
+    },
+    /// Fund a gift card paying for num_tickets tickets, redeemable until expires
+    /// (in seconds) by whoever knows the code whose hex keccak256 hash is code_hash
+    FundGiftCard {
+        code_hash: String,
+        num_tickets: u64,
+        expires: u64,
+    },
+    /// Commit to redeeming a gift card, commitment being the hex keccak256 hash of
+    /// "{code}:{sender}". The code can only be revealed in a later block
+    CommitGiftCard { commitment: String },
+    /// Deposit the gift card of code in the name of the sender, who committed to it
+    /// in an earlier block, with as many tickets as the card pays for
+    RedeemGiftCard {
+        code: String,
+        encoded_tickets: String,
+    },
+    /// Send the stable of an expired gift card back to its buyer - restricted to the buyer
+    RefundGiftCard { code_hash: String },
     /// Withdraws the sponsorship of the sender
     SponsorWithdraw {},
+    /// Converts amount worth of the sender's deposit into a sponsorship without
+    /// redeeming it from anchor. Tickets the remaining deposit can't afford are burnt
+    DonateSharesToSponsorship { amount: Uint128 },
+    /// Deposit the sent stable as protocol owned liquidity, without tickets or
+    /// GLOW emissions. Its whole yield goes to the prizes - restricted to community
+    PolDeposit {},
+    /// Withdraw amount of stable of the protocol owned liquidity to the community
+    /// contract, or all of it if not set - restricted to community
+    PolWithdraw { amount: Option<Uint256> },
     /// Withdraws amount from the pool. If amount is None, it tries to withdraw all
     /// the pooled funds of the sender. If instant true, incurs on withdrawal fee.
+    /// If tickets is set, those are the tickets given up, and there must be exactly
+    /// as many as the withdrawal requires. Otherwise the lowest sequences are given up.
+    /// Instant withdrawals are paid to recipient if set, by default the sender
     Withdraw {
         amount: Option<Uint128>,
         instant: Option<bool>,
+        tickets: Option<Vec<String>>,
+        recipient: Option<String>,
     },
-    /// Claim unbonded withdrawals
-    Claim {},
-    /// Claims pending lottery prizes for a given list of lottery ids
-    ClaimLottery { lottery_ids: Vec<u64> },
+    /// Claim unbonded withdrawals, paid to recipient if set, by default the sender
+    Claim { recipient: Option<String> },
+    /// Opt in to have matured unbonding claims pushed to the sender by any keeper,
+    /// for a bounty deducted from each claim
+    RegisterAutoClaim {},
+    /// Opt out of auto claims
+    DeregisterAutoClaim {},
+    /// Pays the matured unbonding claims of up to limit registered depositors,
+    /// resuming after the last one processed. The sender receives the bounties
+    ProcessAutoClaims { limit: Option<u32> },
+    /// Claims pending lottery prizes for a given list of lottery ids.
+    /// If redeposit is set, the UST winnings are deposited back into the pool
+    /// on behalf of the winner instead of being sent to them
+    ClaimLottery {
+        lottery_ids: Vec<u64>,
+        redeposit: Option<RedepositConfig>,
+        // Sends the UST prize to another chain instead of the sender,
+        // the GLOW prize is always paid locally
+        destination: Option<ClaimDestination>,
+        // Donates a share of the UST prize to a registered charity
+        donation: Option<CharityDonation>,
+        // Reveals the payout hash registered by the sender, paying the prizes
+        // to the revealed address instead
+        payout: Option<PayoutReveal>,
+        // Asset the UST prize is paid in, UST by default
+        payout_asset: Option<PayoutAsset>,
+    },
+    /// Registers the hash of an alternate address the next prizes of the sender
+    /// can be paid to, see ClaimLottery. Replaces any previously registered hash
+    RegisterPayoutHash { payout_hash: String },
+    /// Authorize agent to claim the sender's lottery prizes with ClaimLotteryFor.
+    /// The prizes are always paid to the sender
+    AuthorizeClaimAgent { agent: String },
+    /// Revoke a claim agent of the sender
+    RevokeClaimAgent { agent: String },
+    /// Claims the prizes of winner for a given list of lottery ids, paying them
+    /// to winner - restricted to the claim agents of winner
+    ClaimLotteryFor {
+        winner: String,
+        lottery_ids: Vec<u64>,
+    },
+    /// Sends again the GLOW prize of a claim whose GLOW payout failed, see
+    /// the ClaimReceipt query - restricted to the winner of the claim
+    RetryClaim { receipt_id: u64 },
     /// Claims pending depositor rewards
     ClaimRewards {},
+    /// Resets the count of prizes won since the sender last checked their inbox
+    MarkSeen {},
+    /// Create a syndicate whose members deposit together, sharing its tickets and prizes
+    CreateSyndicate {},
+    /// Deposit amount of stable into the syndicate. The tickets are held by the syndicate
+    JoinSyndicate {
+        syndicate_id: u64,
+        encoded_tickets: String,
+    },
+    /// Moves the sender's part of the syndicate deposit to their own deposit, without tickets.
+    /// Prizes won while the sender was a member can still be claimed
+    LeaveSyndicate { syndicate_id: u64 },
+    /// Claims all unclaimed prizes of the sender, then withdraws all their shares
+    /// and claims their matured unbonding claims and GLOW rewards. Lotteries are
+    /// scanned for prizes in pages, Exit is repeated until exit_complete is true
+    Exit { instant: bool },
+    /// Claims the sender's part of the syndicate prizes for a given list of lottery ids,
+    /// pro-rata to their part of the syndicate deposit when each lottery was drawn
+    ClaimSyndicateLottery {
+        syndicate_id: u64,
+        lottery_ids: Vec<u64>,
+    },
     /// First step on the lottery execution. Sets oracle round number
     ExecuteLottery {},
     /// Second step (paginated) on the lottery execution. Sets winner sequence and
     /// stores winning sequences
     ExecutePrize { limit: Option<u32> },
-    /// Updates rewards emission rate and transfer outstanding reserve to gov
-    ExecuteEpochOps {},
-    /// Handles the migrate loop
-    MigrateOldDepositors { limit: Option<u32> },
+    /// Runs the listed epoch operations, each of them must be ready. Runs every
+    /// epoch operation when none are listed: updates rewards emission rate and
+    /// transfer outstanding reserve to gov
+    ExecuteEpochOps {
+        operations: Option<Vec<EpochOperation>>,
+    },
+    /// Re-sends anchor redemptions that previously failed
+    RetryPending {},
+    /// Withdraws every listed depositor whose balance is below the minimum
+    /// deposit amount into an unbonding claim - restricted to owner
+    DustSweep { depositors: Vec<String> },
+    /// Credits aust sent to the contract outside of deposits to the prize buckets - permissionless,
+    /// but can only be run once per skim interval
+    Skim {},
+    /// Add a guardian allowed to pause the contract - restricted to owner
+    AddGuardian { guardian: String },
+    /// Remove a guardian - restricted to owner
+    RemoveGuardian { guardian: String },
+    /// Add or remove contracts notified of deposits, withdrawals and prize
+    /// claims - restricted to owner or gov
+    UpdateHooks {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Run a page of a pending paginated migration step
+    MigrateStep { step_id: u64, limit: Option<u32> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Burn the sent receipt tokens and send their value in stable to the sender
+    RedeemReceipt {},
 }
 
 /// Migration message
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {
-    pub glow_prize_buckets: [Uint256; NUM_PRIZE_BUCKETS], // glow to be awarded as a bonus to lottery winners
-    pub max_tickets_per_depositor: u64, // the maximum number of tickets that a depositor can hold
-    pub community_contract: String,     // Glow community contract address
+    pub glow_prize_buckets: Vec<Uint256>, // glow to be awarded as a bonus to lottery winners
+    pub max_tickets_per_depositor: u64,   // the maximum number of tickets that a depositor can hold
+    pub community_contract: String,       // Glow community contract address
     pub lotto_winner_boost_config: Option<BoostConfig>, // The boost config to apply to glow emissions for lotto winners
     pub ve_contract: String,                            // Glow ve token contract address
     pub operator_glow_emission_rate: Decimal256,        // The emission rate to set for operators
     pub sponsor_glow_emission_rate: Decimal256,         // The emission rate to set for sponsors
+    pub target_version: Option<u64>, // Version to migrate to, the latest one if not set
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -146,26 +799,55 @@ pub struct MigrateMsg {
 pub enum QueryMsg {
     /// Lotto contract configuration
     Config {},
-    /// Current state
+    /// Current state. If block_height is set, the state at the
+    /// beginning of that block
     State { block_height: Option<u64> },
     /// Lotto pool current state. Savings aust and lottery deposits.
-    Pool {},
+    /// If block_height is set, the pool at the beginning of that block
+    Pool { block_height: Option<u64> },
     /// Lottery information by lottery id
     LotteryInfo { lottery_id: Option<u64> },
     /// Ticket information by sequence. Returns a list of holders (addresses)
     TicketInfo { sequence: String },
     /// Prizes for a given address on a given lottery id
     PrizeInfo { address: String, lottery_id: u64 },
+    /// Prizes for a given address on each of the given lottery ids it won, at most 100
+    PrizeInfos {
+        address: String,
+        lottery_ids: Vec<u64>,
+    },
+    /// Glow boost multiplier of a prize and the balances it was computed from,
+    /// as recorded at claim time or computed now for unclaimed prizes
+    PrizeBoostDetail { address: String, lottery_id: u64 },
     /// Prizes for a given lottery id
     LotteryPrizeInfos {
         lottery_id: u64,
         start_after: Option<String>,
         limit: Option<u32>,
     },
-    /// Depositor information by address
-    DepositorInfo { address: String },
+    /// Winners (paginated) of a given lottery id with their boosted prizes
+    LotteryWinners {
+        lottery_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Depositor information by address. Tickets are only listed if include_tickets
+    /// is set, DepositorTickets lists them by page
+    DepositorInfo {
+        address: String,
+        include_tickets: Option<bool>,
+    },
+    /// Tickets (paginated) of a depositor in ascending sequence order. limit counts
+    /// distinct sequences, each of them listed once per copy held
+    DepositorTickets {
+        address: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     /// Depositor stats by address
     DepositorStatsInfo { address: String },
+    /// Lifetime deposits, withdrawals and winnings of a depositor
+    DepositorLedger { address: String },
     /// List (paginated) of DepositorInfo
     DepositorInfos {
         start_after: Option<String>,
@@ -178,11 +860,150 @@ pub enum QueryMsg {
     },
     /// Sponsor information by address
     Sponsor { address: String },
+    /// Prize bucket donations per bucket, and the total donated by donor if set
+    PrizeDonations { donor: Option<String> },
     /// Sponsor information by address
     Operator { address: String },
+    /// List (paginated) of the depositors attributed to an operator
+    OperatorDepositors {
+        operator: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Operator shares, their part of total_operator_shares and how both changed
+    /// over the last share updates
+    OperatorStats { operator: String },
+    /// List (paginated) of the deposits attributed to each campaign of an operator
+    OperatorCampaignStats {
+        operator: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     /// Get the lottery balance. This is the amount that would be distributed in prizes if the lottery were run right
     /// now.
     LotteryBalance {},
+    /// Anchor redemptions that failed and are waiting to be retried
+    PendingAnchorOperations {},
+    /// Get the current UST value of one user share and its time weighted average
+    /// over the last epoch checkpoints
+    SharePrice {},
+    /// List (paginated) of config updates waiting in the timelock
+    ScheduledUpdates {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Recent config versions and the instant withdrawal fee charged at the current
+    /// block, the previous one during the grace window after an update
+    ConfigVersions {},
+    /// Depositor, sponsor and operator information of an address in a single response
+    Account { address: String },
+    /// Prizes won and unbonding claims ready to be claimed by an address
+    Inbox { address: String },
+    /// Number of sequences and tickets per sequence prefix of prefix_len hex chars
+    /// (at most MAX_COVERAGE_PREFIX_LEN). Prefixes without tickets are omitted
+    SequenceCoverage { prefix_len: u8 },
+    /// Prize claim sent over ibc
+    IbcClaim { id: u64 },
+    /// Prize claim by receipt id, with the status of its GLOW payout
+    ClaimReceipt { receipt_id: u64 },
+    /// Syndicate information by id
+    Syndicate { syndicate_id: u64 },
+    /// Part of a syndicate deposit owned by a member
+    SyndicateMember { syndicate_id: u64, member: String },
+    /// Unbonded amount a depositor will be able to claim at a given time
+    ClaimableAt { address: String, timestamp: u64 },
+    /// Yield the next ExecuteLottery would redeem and add to the prize buckets,
+    /// projected from the current balances and exchange rate
+    NextLotteryProjection {},
+    /// Chances of a holder of num_tickets more random tickets to win each tier of
+    /// the next lottery, and their expected prizes from the current prize buckets
+    Odds { num_tickets: u64 },
+    /// Blacklist status of an address
+    Blacklist { address: String },
+    /// Glow held by the contract to pay glow prizes when the distributor can't
+    GlowPrizeReserve {},
+    /// Queued withdrawals of an address, with their position in the queue
+    QueuedWithdrawals { address: String },
+    /// Total queued withdrawals and the current redemption capacity of anchor
+    WithdrawalQueue {},
+    /// Contract version and progress of the pending migration steps
+    MigrationStatus {},
+    /// Escrow balance, public key and next permit nonce of an address
+    Escrow { address: String },
+    /// Unredeemed gift card of a code hash
+    GiftCard { code_hash: String },
+    /// Receipt token contract and aust held for its holders
+    ReceiptToken {},
+    /// Savings mode balance of an address
+    Savings { address: String },
+    /// Buyback config and total GLOW bought back with the reserve
+    Buyback {},
+    /// Emission decay schedules, current GLOW emission rates and the rates
+    /// projected for the next epochs (default 10, at most 100)
+    EmissionSchedule { epochs: Option<u32> },
+    /// Charities (paginated) with the total donated to each, including unregistered ones
+    Charities {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Claim agents (paginated) authorized by winner
+    ClaimAgents {
+        winner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Payout hash registered by depositor, if any
+    PayoutHash { depositor: String },
+    /// Insurance fund share of the reserve, balance and total drawn to cover shortfalls
+    Insurance {},
+    /// Jackpot floor and the subsidy received from the community contract to reach it
+    JackpotFloor {},
+    /// Last accepted aust exchange rate and the state of the circuit breaker
+    RateGuard {},
+    /// Limit on instant withdrawals and what is left of it in the current epoch
+    InstantWithdrawalCap {},
+    /// Prize re-balancing bounds and the winners and prizes awarded per tier so far
+    PrizeRebalancing {},
+    /// Caps on the UST awarded by each prize bucket
+    PrizeCaps {},
+    /// Whether depositor is registered for auto claims and the bounty deducted from them
+    AutoClaim { depositor: String },
+    /// Keepers with exclusive access to the lottery executions and their window
+    KeeperAllowlist {},
+    /// Cumulative sponsorship of address and the badge tiers it minted or can mint
+    BadgeEligibility { address: String },
+    /// Prize templates award sponsorships can use, ordered by name
+    PrizeTemplates {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Prize cursor and estimated ExecutePrize progress of a lottery,
+    /// by default the current one
+    PrizeExecutionProgress { lottery_id: Option<u64> },
+    /// Whether ExecuteLottery would go through at the current block, and if not why
+    CanExecuteLottery {},
+    /// Whether ExecutePrize would go through at the current block, and if not why
+    CanExecutePrize {},
+    /// Last and next execution of each epoch operation
+    EpochOperations {},
+    /// Protocol statistics (paginated) recorded at the end of each of the
+    /// last MAX_PROTOCOL_CHECKPOINTS epochs, oldest first
+    Checkpoints {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Annualized prize yield and sponsor APY over the last YIELD_STATS_EPOCHS epochs,
+    /// computed from the protocol checkpoints
+    YieldStats {},
+    /// Contracts notified of deposits, withdrawals and prize claims
+    Hooks {},
+    /// Leaves (paginated) of the winners merkle tree of an awarded lottery,
+    /// to build the proofs of cross-chain claims
+    WinnerLeaves {
+        lottery_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 // We define a custom struct for each query response
@@ -202,7 +1023,7 @@ pub struct ConfigResponse {
     pub round_delta: u64,
     pub ticket_price: Uint256,
     pub max_holders: u8,
-    pub prize_distribution: [Decimal256; NUM_PRIZE_BUCKETS],
+    pub prize_distribution: Vec<Decimal256>,
     pub target_award: Uint256,
     pub reserve_factor: Decimal256,
     pub split_factor: Decimal256,
@@ -210,14 +1031,32 @@ pub struct ConfigResponse {
     pub unbonding_period: Duration,
     pub max_tickets_per_depositor: u64,
     pub paused: bool,
+    pub pause_state: PauseState,
+    pub reserve_destination: ReserveDestination,
+    pub fee_distributor_contract: Option<String>,
+    pub lp_staking_contract: Option<String>,
+    pub lp_staking_reserve_share: Decimal256,
+    pub min_deposit_amount: Uint256,
+    pub loyalty_config: LoyaltyConfig,
+    pub guardians: Vec<String>,
+    pub sponsor_lock_config: SponsorLockConfig,
+    pub ibc_claim_config: IbcClaimConfig,
+    pub operator_reward_vesting_epochs: u64,
+    pub ticket_eligibility_delay: u64,
+    pub missed_lottery_policy: MissedLotteryPolicy,
+    pub time_weighted_odds: bool,
+    pub ticket_length: u8,
+    pub min_matches_for_prize: u8,
 }
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct StateResponse {
     pub total_tickets: Uint256,
+    // Tickets included in total_tickets that only take part in the next lottery
+    pub pending_tickets: u64,
     pub total_reserve: Uint256,
-    pub prize_buckets: [Uint256; NUM_PRIZE_BUCKETS],
+    pub prize_buckets: Vec<Uint256>,
     pub current_lottery: u64,
     pub next_lottery_time: Expiration,
     pub next_lottery_exec_time: Expiration,
@@ -225,6 +1064,13 @@ pub struct StateResponse {
     pub operator_reward_emission_index: RewardEmissionsIndex,
     pub sponsor_reward_emission_index: RewardEmissionsIndex,
     pub last_lottery_execution_aust_exchange_rate: Decimal256,
+    // Catch-up lotteries left to run for missed lottery intervals
+    pub missed_lotteries: u64,
+    // Redeemed yield set aside for the catch-up lotteries
+    pub missed_lottery_prizes: Uint256,
+    // UST value lost to an aust exchange rate below the one of the last lottery
+    // execution, recouped by appreciation before prizes are funded again
+    pub yield_deficit: Uint256,
 }
 
 // We define a custom struct for each query response
@@ -234,6 +1080,11 @@ pub struct PoolResponse {
     pub total_user_shares: Uint256,
     pub total_sponsor_lottery_deposits: Uint256,
     pub total_operator_shares: Uint256,
+    pub total_sponsor_lock_bonus: Uint256,
+    pub total_sponsor_weight_bonus: Uint256,
+    pub total_pol_aust: Uint256,
+    pub total_pol_principal: Uint256,
+    pub total_savings_aust: Uint256,
 }
 
 // We define a custom struct for each query response
@@ -245,11 +1096,17 @@ pub struct LotteryInfoResponse {
     pub awarded: bool,
     pub timestamp: Timestamp,
     pub block_height: u64,
-    pub prize_buckets: [Uint256; NUM_PRIZE_BUCKETS],
-    pub number_winners: [u32; NUM_PRIZE_BUCKETS],
-    pub page: String,
-    pub glow_prize_buckets: [Uint256; NUM_PRIZE_BUCKETS],
+    pub prize_buckets: Vec<Uint256>,
+    pub number_winners: Vec<u32>,
+    pub prize_cursor: PrizeCursor,
+    pub glow_prize_buckets: Vec<Uint256>,
     pub total_user_shares: Uint256,
+    pub draw_timeline: DrawTimeline,
+    pub loyalty_weights: Option<Vec<Decimal256>>,
+    pub time_weight_window: Option<TimeWeightWindow>,
+    pub funded_glow_prize_buckets: Option<Vec<Uint256>>,
+    pub glow_prize_shortfall: Uint256,
+    pub winners_root: Option<String>,
 }
 
 // We define a custom struct for each query response
@@ -261,12 +1118,35 @@ pub struct DepositorInfoResponse {
     pub unbonding_info: Vec<Claim>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositorTicketsResponse {
+    pub depositor: String,
+    pub tickets: Vec<String>,
+    // Tickets held over all the pages
+    pub num_tickets: usize,
+}
+
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct DepositorStatsResponse {
     pub depositor: String,
     pub shares: Uint256,
     pub num_tickets: usize,
+    pub pending_tickets: u64,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositorLedgerResponse {
+    pub depositor: String,
+    pub lifetime_deposited: Uint256,
+    pub lifetime_withdrawn: Uint256,
+    pub lifetime_ust_won: Uint256,
+    pub lifetime_glow_won: Uint256,
+    pub current_balance: Uint256,
+    // current_balance + lifetime_withdrawn + lifetime_ust_won - lifetime_deposited, split by sign
+    pub net_gain: Uint256,
+    pub net_loss: Uint256,
 }
 
 // We define a custom struct for each query response
@@ -276,6 +1156,9 @@ pub struct SponsorInfoResponse {
     pub lottery_deposit: Uint256,
     pub reward_index: Decimal256,
     pub pending_rewards: Decimal256,
+    pub lock_end_time: u64,
+    pub lock_bonus_rate: Decimal256,
+    pub emission_weight: Decimal256,
 }
 
 // We define a custom struct for each query response
@@ -285,18 +1168,93 @@ pub struct OperatorInfoResponse {
     pub shares: Uint256,
     pub reward_index: Decimal256,
     pub pending_rewards: Decimal256,
+    // Vesting split of all accrued and unclaimed rewards, including pending_rewards
+    pub locked_rewards: Decimal256,
+    pub unlocked_rewards: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorDepositorResponse {
+    pub depositor: String,
+    // Shares of the depositor, all of which are attributed to the operator
+    pub shares: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorDepositorsResponse {
+    pub depositors: Vec<OperatorDepositorResponse>,
+    // Limit applied to this page, which is not capped
+    pub limit: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CampaignStatsResponse {
+    pub campaign_id: String,
+    // UST deposited through the campaign, after taxes
+    pub deposited: Uint256,
+    pub tickets: u64,
+    pub deposits: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorCampaignStatsResponse {
+    pub operator: String,
+    pub campaigns: Vec<CampaignStatsResponse>,
+    // Limit applied to this page, at most MAX_LIMIT
+    pub limit: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorShareCheckpointResponse {
+    pub time: u64,
+    pub shares: Uint256,
+    pub total_operator_shares: Uint256,
+    // shares / total_operator_shares, the part of operator emissions the operator accrued
+    pub share_ratio: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorStatsResponse {
+    pub operator: String,
+    pub shares: Uint256,
+    pub total_operator_shares: Uint256,
+    pub share_ratio: Decimal256,
+    // Oldest first
+    pub history: Vec<OperatorShareCheckpointResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SyndicateResponse {
+    pub syndicate_id: u64,
+    pub creator: String,
+    // Address holding the syndicate deposit and tickets
+    pub holder: String,
+    pub num_members: u32,
+    pub shares: Uint256,
+    pub num_tickets: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SyndicateMemberResponse {
+    pub syndicate_id: u64,
+    pub member: String,
+    pub shares: Uint256,
 }
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct DepositorsInfoResponse {
     pub depositors: Vec<DepositorInfoResponse>,
+    // Limit applied to this page, which is not capped
+    pub limit: u32,
 }
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct DepositorsStatsResponse {
     pub depositors: Vec<DepositorStatsResponse>,
+    // Limit applied to this page, which is not capped
+    pub limit: u32,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -310,12 +1268,25 @@ pub struct TicketInfoResponse {
     pub holders: Vec<Addr>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SequencePrefixCoverage {
+    pub prefix: String,
+    pub sequences: u32, // distinct sequences starting with prefix
+    pub tickets: u32,   // tickets (holders) of those sequences
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SequenceCoverageResponse {
+    pub prefix_len: u8,
+    pub coverage: Vec<SequencePrefixCoverage>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PrizeInfoResponse {
     pub holder: Addr,
     pub lottery_id: u64,
     pub claimed: bool,
-    pub matches: [u32; NUM_PRIZE_BUCKETS],
+    pub matches: Vec<u32>,
     pub won_ust: Uint128,
     pub won_glow: Uint128,
 }
@@ -323,6 +1294,347 @@ pub struct PrizeInfoResponse {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PrizeInfosResponse {
     pub prize_infos: Vec<PrizeInfoResponse>,
+    // Limit applied to this page, or the maximum number of lotteries of a batch query
+    pub limit: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LotteryWinnerResponse {
+    pub address: String,
+    pub matches: Vec<u32>,
+    pub ust_prize: Uint128,
+    pub glow_prize: Uint128,
+    pub glow_boost_multiplier: Decimal256,
+    pub claimed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrizeBoostDetailResponse {
+    pub address: String,
+    pub lottery_id: u64,
+    pub glow_boost_multiplier: Decimal256,
+    pub user_shares: Uint256,
+    pub total_user_shares: Uint256,
+    pub user_voting_balance: Uint128,
+    pub total_voting_balance: Uint128,
+    pub glow_prize: Uint128,
+    // Height of the claim, None if the prize wasn't claimed yet
+    pub claimed_at_height: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LotteryWinnersResponse {
+    pub lottery_id: u64,
+    pub winners: Vec<LotteryWinnerResponse>,
+    // Limit applied to this page, which is not capped
+    pub limit: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingAnchorOperationsResponse {
+    pub redeem_stable: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SharePriceResponse {
+    pub share_price: Decimal256,
+    pub aust_exchange_rate: Decimal256,
+    pub twap: Decimal256,
+    pub twap_start_time: u64,
+    pub num_checkpoints: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondingClaimResponse {
+    pub amount: Uint256,
+    pub release_at: Expiration,
+    // Seconds (or blocks for height based claims) until the claim can be withdrawn
+    pub time_remaining: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InboxResponse {
+    pub address: String,
+    // Prizes won since the last MarkSeen
+    pub unseen_prizes: u32,
+    pub unclaimed_prizes: u32,
+    // Unbonding claims released and the sum of their amounts
+    pub matured_claims: u32,
+    pub matured_claims_amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimableAtResponse {
+    pub address: String,
+    pub timestamp: u64,
+    // Sum of the claims released by timestamp
+    pub claimable: Uint256,
+    // Sum of the claims still unbonding at timestamp
+    pub unbonding: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BlacklistResponse {
+    pub address: String,
+    pub blacklisted: bool,
+    // Block time in seconds at which the address was blacklisted
+    pub blacklisted_at: Option<u64>,
+    // Block time in seconds after which its claims can be redirected
+    pub redirectable_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueuedWithdrawalResponse {
+    pub id: u64,
+    // Number of queued withdrawals ahead
+    pub position: u64,
+    pub aust_amount: Uint256,
+    pub amount: Uint256,
+    pub instant: bool,
+    pub release_at: Expiration,
+    // aust of the queued withdrawals ahead
+    pub aust_ahead: Uint256,
+    // Stable that would be fulfilled if the queue was processed now
+    pub estimated_fulfillment: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueuedWithdrawalsResponse {
+    pub address: String,
+    pub withdrawals: Vec<QueuedWithdrawalResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WithdrawalQueueResponse {
+    pub total_aust: Uint256,
+    pub num_withdrawals: u64,
+    pub redemption_capacity: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrizeDonationsResponse {
+    // Total donated to each prize bucket
+    pub bucket_donations: Vec<Uint256>,
+    pub donor: Option<String>,
+    pub donor_amount: Option<Uint256>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrationStatusResponse {
+    pub version: u64,
+    pub target_version: u64,
+    // Steps left to reach target_version, in the order they have to be run
+    pub steps: Vec<MigrationStepResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrationStepResponse {
+    pub step_id: u64,
+    pub version: u64,
+    pub name: String,
+    pub migrated_entries: u64,
+    pub completed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrizeTemplateResponse {
+    pub name: String,
+    pub prize_distribution: Vec<Decimal256>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrizeTemplatesResponse {
+    pub templates: Vec<PrizeTemplateResponse>,
+    // Limit applied to this page, at most MAX_LIMIT
+    pub limit: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CharityResponse {
+    pub address: String,
+    // Whether winners can currently donate to the charity
+    pub registered: bool,
+    pub total_donated: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CharitiesResponse {
+    pub charities: Vec<CharityResponse>,
+    // Limit applied to this page, at most MAX_LIMIT
+    pub limit: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimAgentResponse {
+    pub agent: String,
+    // Timestamp of the authorization in seconds
+    pub authorized_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimAgentsResponse {
+    pub agents: Vec<ClaimAgentResponse>,
+    // Limit applied to this page, at most MAX_LIMIT
+    pub limit: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayoutHashResponse {
+    pub payout_hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReceiptTokenResponse {
+    pub contract: Option<String>,
+    // Equals the receipt token supply
+    pub total_aust: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SavingsResponse {
+    pub address: String,
+    pub savings_aust: Uint256,
+    // Value of savings_aust at the current exchange rate
+    pub balance: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BuybackResponse {
+    pub buyback_config: Option<BuybackConfig>,
+    pub total_glow_bought: Uint256,
+    pub total_stable_spent: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProjectedEmissionRates {
+    pub epoch: u64,
+    pub operator_glow_emission_rate: Decimal256,
+    pub sponsor_glow_emission_rate: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmissionScheduleResponse {
+    pub current_epoch: u64,
+    pub operator_decay: Option<EmissionDecaySchedule>,
+    pub sponsor_decay: Option<EmissionDecaySchedule>,
+    pub operator_glow_emission_rate: Decimal256,
+    pub sponsor_glow_emission_rate: Decimal256,
+    // Rates after each of the next epoch operations
+    pub projected_rates: Vec<ProjectedEmissionRates>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EscrowResponse {
+    pub address: String,
+    pub pubkey: Option<Binary>,
+    pub balance: Uint256,
+    pub nonce: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GiftCardResponse {
+    pub code_hash: String,
+    // None if there is no unredeemed card for the code hash
+    pub buyer: Option<String>,
+    pub amount: Uint256,
+    pub num_tickets: u64,
+    pub expires: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GlowPrizeReserveResponse {
+    pub glow_token: Option<String>,
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TierOddsResponse {
+    pub matches: u8,
+    // Probability of at least one of the tickets falling in the tier
+    pub probability: Decimal256,
+    pub expected_prize: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OddsResponse {
+    pub num_tickets: u64,
+    // Tickets of the other holders
+    pub total_tickets: Uint256,
+    pub tiers: Vec<TierOddsResponse>,
+    // Sum of the expected prizes of every tier
+    pub expected_value: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NextLotteryProjectionResponse {
+    pub aust_to_redeem: Uint256,
+    // UST value of aust_to_redeem before taxes
+    pub aust_to_redeem_value: Uint256,
+    // Part of aust_to_redeem_value coming from user deposits
+    pub user_value: Uint256,
+    // Part of aust_to_redeem_value coming from sponsor deposits
+    pub sponsor_value: Uint256,
+    // Amount added to the prize buckets, net of taxes and of the
+    // yield held back for catch-up lotteries
+    pub prize_amount: Uint256,
+    pub prize_bucket_additions: Vec<Uint256>,
+    // Prize buckets after the next lottery is executed
+    pub projected_prize_buckets: Vec<Uint256>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AccountResponse {
+    pub address: String,
+    pub shares: Uint256,
+    pub tickets: Vec<String>,
+    pub unbonding_info: Vec<UnbondingClaimResponse>,
+    pub sponsor: SponsorInfoResponse,
+    pub operator: OperatorInfoResponse,
+    // Sum of the pending sponsor and operator GLOW rewards
+    pub pending_glow_rewards: Decimal256,
+    pub unclaimed_prizes: u32,
+    pub boost_multiplier: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScheduledUpdateResponse {
+    pub id: u64,
+    pub update: TimelockedConfigUpdate,
+    pub eta: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScheduledUpdatesResponse {
+    pub updates: Vec<ScheduledUpdateResponse>,
+    // Limit applied to this page, which is not capped
+    pub limit: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigVersionsResponse {
+    pub versions: Vec<ConfigVersion>,
+    pub instant_withdrawal_fee: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IbcClaimResponse {
+    pub id: u64,
+    pub winner: String,
+    pub destination: ClaimDestination,
+    pub amount: Uint128,
+    pub timeout: u64,
+    pub refunded: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimReceiptResponse {
+    pub receipt_id: u64,
+    pub winner: String,
+    pub recipient: String,
+    pub lottery_ids: Vec<u64>,
+    pub ust_amount: Uint128,
+    pub glow_amount: Uint128,
+    pub status: ClaimReceiptStatus,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -331,7 +1643,198 @@ pub struct LotteryBalanceResponse {
     pub user_aust_to_redeem: Uint256,
     pub value_of_sponsor_aust_to_be_redeemed_for_lottery: Uint256,
     pub sponsor_aust_to_redeem: Uint256,
+    pub value_of_pol_aust_to_be_redeemed_for_lottery: Uint256,
+    pub pol_aust_to_redeem: Uint256,
     pub aust_to_redeem: Uint256,
     pub aust_to_redeem_value: Uint256,
-    pub prize_buckets: [Uint256; NUM_PRIZE_BUCKETS],
+    pub prize_buckets: Vec<Uint256>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrizeExecutionProgressResponse {
+    pub lottery_id: u64,
+    pub in_progress: bool,
+    pub awarded: bool,
+    pub prize_cursor: PrizeCursor,
+    // Share of the winning tickets key range already processed
+    pub progress: Decimal256,
+}
+
+/// Check of ExecuteLottery or ExecutePrize that fails at the current block
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionBlocker {
+    LotteryPaused,
+    LotteryNotReady,
+    NoTickets,
+    LotteryAlreadyStarted,
+    InsufficientAust,
+    ExchangeRateDecreased,
+    ExchangeRateOutOfBounds,
+    InsufficientLotteryFunds,
+    LotteryNotStarted,
+    RandomnessNotReady,
+    RandomnessUnavailable,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CanExecuteResponse {
+    pub ready: bool,
+    pub reason: Option<ExecutionBlocker>,
+    // Seconds until a time based check passes, if the blocker is one
+    pub seconds_remaining: Option<u64>,
+    // aust missing from the contract balance to cover the pool, for InsufficientAust
+    pub missing_funds: Option<Uint256>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateGuardResponse {
+    pub max_deviation_per_hour: Option<Decimal256>,
+    pub last_rate: Decimal256,
+    pub last_rate_time: u64,
+    pub tripped: bool,
+    pub rejected_rate: Option<Decimal256>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HooksResponse {
+    pub hooks: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantWithdrawalCapResponse {
+    pub cap: Option<InstantWithdrawalCap>,
+    // Stable withdrawn instantly since the start of the epoch
+    pub withdrawn_in_epoch: Uint256,
+    // Stable that can still be withdrawn instantly, None without a cap or
+    // before the first instant withdrawal of the epoch under a PoolRatio cap
+    pub available: Option<Uint256>,
+    // When the epoch operations can reset withdrawn_in_epoch
+    pub resets_at: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrizeCapsResponse {
+    pub prize_caps: Option<Vec<PrizeCap>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KeeperAllowlistResponse {
+    pub allowlist: Option<KeeperAllowlist>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AutoClaimResponse {
+    pub registered: bool,
+    pub bounty_rate: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrizeRebalancingResponse {
+    pub rebalancing: Option<PrizeRebalancingConfig>,
+    // Lottery id at the last re-balancing
+    pub last_rebalanced_lottery: u64,
+    pub prize_distribution: Vec<Decimal256>,
+    // Lotteries awarded since the contract started recording tier stats
+    pub awarded_lotteries: u64,
+    pub winners: Vec<u64>,
+    pub awarded_prizes: Vec<Uint256>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EpochOperationStatus {
+    pub operation: EpochOperation,
+    // Block time in seconds of the last execution, None if it never ran
+    pub last_executed: Option<u64>,
+    pub next_execution: Expiration,
+    pub ready: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EpochOperationsResponse {
+    pub operations: Vec<EpochOperationStatus>,
+}
+
+/// Protocol statistics recorded when an epoch ends
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProtocolCheckpoint {
+    pub epoch: u64,
+    // Block time in seconds at which the checkpoint was recorded
+    pub time: u64,
+    pub total_user_shares: Uint256,
+    pub aust_balance: Uint256,
+    pub prize_buckets: Vec<Uint256>,
+    pub total_tickets: Uint256,
+    pub total_sponsor_lottery_deposits: Uint256,
+    pub aust_exchange_rate: Decimal256,
+    // Prizes awarded over all lotteries so far, None for checkpoints recorded before it was
+    #[serde(default)]
+    pub awarded_prizes: Option<Uint256>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CheckpointsResponse {
+    pub checkpoints: Vec<ProtocolCheckpoint>,
+    // Limit applied to this page, at most MAX_LIMIT
+    pub limit: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct YieldStatsResponse {
+    // Number of epochs covered, zero until two checkpoints recorded the awarded prizes
+    pub epochs: u64,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub awarded_prizes: Uint256,
+    // Average value of the aust held by the pool over the epochs
+    pub average_tvl: Uint256,
+    // Annualized prizes awarded over the average value of the pool
+    pub prize_yield: Decimal256,
+    // Annualized appreciation of aust, the yield sponsorships donate to the prizes
+    pub sponsor_apy: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BadgeEligibilityResponse {
+    pub address: String,
+    pub total_sponsored: Uint256,
+    pub minted_tiers: Vec<u32>,
+    // Tiers crossed by total_sponsored which weren't minted yet
+    pub eligible_tiers: Vec<u32>,
+    // Threshold of the next tier, None once all tiers are crossed
+    pub next_threshold: Option<Uint256>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WinnerLeafResponse {
+    pub winner: String,
+    pub ust_amount: Uint128,
+    pub glow_amount: Uint128,
+    // Hex encoded glow_protocol::merkle::winner_leaf
+    pub leaf: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WinnerLeavesResponse {
+    pub lottery_id: u64,
+    pub winners_root: Option<String>,
+    pub leaves: Vec<WinnerLeafResponse>,
+    // Limit applied to this page, at most MAX_LIMIT
+    pub limit: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InsuranceResponse {
+    pub reserve_share: Decimal256,
+    pub balance: Uint256,
+    pub total_drawn: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct JackpotFloorResponse {
+    pub floor: Option<Uint256>,
+    // Subsidy not repaid yet, taken from the reserve sweeps before they are distributed
+    pub outstanding_subsidy: Uint256,
+    pub total_subsidized: Uint256,
+    pub total_repaid: Uint256,
 }
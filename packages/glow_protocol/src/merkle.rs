@@ -0,0 +1,81 @@
+use cosmwasm_std::Uint128;
+use sha3::{Digest, Keccak256};
+
+pub type Hash = [u8; 32];
+
+fn keccak256(data: &[u8]) -> Hash {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&Keccak256::digest(data));
+    hash
+}
+
+/// Leaf of a lottery winner in the winners merkle tree: the keccak256 hash of
+/// "{winner}:{lottery_id}:{ust_amount}:{glow_amount}", the amounts in their
+/// smallest unit, e.g. "terra1...:12:2500000:1000000"
+pub fn winner_leaf(
+    winner: &str,
+    lottery_id: u64,
+    ust_amount: Uint128,
+    glow_amount: Uint128,
+) -> Hash {
+    keccak256(format!("{}:{}:{}:{}", winner, lottery_id, ust_amount, glow_amount).as_bytes())
+}
+
+/// Parent of two nodes, hashed in ascending byte order like the airdrop proofs
+pub fn hash_pair(a: &Hash, b: &Hash) -> Hash {
+    if a < b {
+        keccak256(&[*a, *b].concat())
+    } else {
+        keccak256(&[*b, *a].concat())
+    }
+}
+
+/// Pairs the nodes of a level. A node left without a pair moves up as is
+fn next_level(level: &[Hash]) -> Vec<Hash> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [a, b] => hash_pair(a, b),
+            [a] => *a,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Root of the tree built from leaves, pairing nodes level by level.
+/// The root of no leaves is all zeros
+pub fn merkle_root(leaves: &[Hash]) -> Hash {
+    if leaves.is_empty() {
+        return [0u8; 32];
+    }
+
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+/// Proof of the leaf at index, the sibling nodes from the leaf up to the root
+pub fn merkle_proof(leaves: &[Hash], index: usize) -> Vec<Hash> {
+    let mut proof = vec![];
+    let mut level = leaves.to_vec();
+    let mut index = index;
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if sibling < level.len() {
+            proof.push(level[sibling]);
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+    proof
+}
+
+/// Whether proof links leaf to root
+pub fn verify_proof(leaf: &Hash, proof: &[Hash], root: &Hash) -> bool {
+    let computed = proof
+        .iter()
+        .fold(*leaf, |hash, sibling| hash_pair(&hash, sibling));
+    computed == *root
+}
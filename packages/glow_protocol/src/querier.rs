@@ -1,11 +1,132 @@
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{
-    to_binary, Addr, AllBalanceResponse, BalanceResponse, BankQuery, Coin, Deps, QueryRequest,
-    StdResult, WasmQuery,
+    to_binary, Addr, AllBalanceResponse, BalanceResponse, BankQuery, Binary, Coin, CosmosMsg, Deps,
+    QuerierWrapper, QueryRequest, StdResult, WasmMsg, WasmQuery,
 };
-use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
+use cw20::{
+    BalanceResponse as Cw20BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, TokenInfoResponse,
+};
+use moneymarket::market::{
+    Cw20HookMsg as AnchorHookMsg, EpochStateResponse, ExecuteMsg as AnchorMsg,
+    QueryMsg as AnchorQueryMsg,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::marker::PhantomData;
 use terra_cosmwasm::TerraQuerier;
 
+use crate::gov::{QueryMsg as GovQueryMsg, VotingPowerResponse};
+use crate::ve_token::{QueryMsg as VeQueryMsg, StateResponse as VeStateResponse};
+
+/// Smart query to a contract, typed with its response
+pub struct SmartQuery<T> {
+    pub contract_addr: String,
+    pub msg: Binary,
+    response: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> SmartQuery<T> {
+    pub fn new<M: Serialize>(contract_addr: impl Into<String>, msg: &M) -> StdResult<Self> {
+        Ok(SmartQuery {
+            contract_addr: contract_addr.into(),
+            msg: to_binary(msg)?,
+            response: PhantomData,
+        })
+    }
+
+    pub fn query(&self, querier: &QuerierWrapper) -> StdResult<T> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.contract_addr.clone(),
+            msg: self.msg.clone(),
+        }))
+    }
+}
+
+/// Tuple of smart queries run by query_batch
+pub trait SmartQueryBatch {
+    type Response;
+
+    fn query(&self, querier: &QuerierWrapper) -> StdResult<Self::Response>;
+}
+
+macro_rules! impl_smart_query_batch {
+    ($($query:ident: $response:ident),+) => {
+        impl<$($response: DeserializeOwned),+> SmartQueryBatch for ($(SmartQuery<$response>,)+) {
+            type Response = ($($response,)+);
+
+            fn query(&self, querier: &QuerierWrapper) -> StdResult<Self::Response> {
+                let ($($query,)+) = self;
+                Ok(($($query.query(querier)?,)+))
+            }
+        }
+    };
+}
+
+impl_smart_query_batch!(a: A);
+impl_smart_query_batch!(a: A, b: B);
+impl_smart_query_batch!(a: A, b: B, c: C);
+impl_smart_query_batch!(a: A, b: B, c: C, d: D);
+impl_smart_query_batch!(a: A, b: B, c: C, d: D, e: E);
+
+/// Runs a tuple of smart queries and returns their typed responses in the same order.
+/// Fails on the first query that fails
+pub fn query_batch<B: SmartQueryBatch>(
+    querier: &QuerierWrapper,
+    batch: B,
+) -> StdResult<B::Response> {
+    batch.query(querier)
+}
+
+/// cw20 balance of account_addr
+pub fn token_balance_query(
+    contract_addr: impl Into<String>,
+    account_addr: impl Into<String>,
+) -> StdResult<SmartQuery<Cw20BalanceResponse>> {
+    SmartQuery::new(
+        contract_addr,
+        &Cw20QueryMsg::Balance {
+            address: account_addr.into(),
+        },
+    )
+}
+
+/// Anchor epoch state, which holds the aust exchange rate, at block_height
+pub fn exchange_rate_query(
+    money_market_addr: impl Into<String>,
+    block_height: u64,
+) -> StdResult<SmartQuery<EpochStateResponse>> {
+    SmartQuery::new(
+        money_market_addr,
+        &AnchorQueryMsg::EpochState {
+            block_height: Some(block_height),
+            distributed_interest: None,
+        },
+    )
+}
+
+/// Voting power of address in gov, at timestamp if set
+pub fn voting_power_query(
+    gov_addr: impl Into<String>,
+    address: impl Into<String>,
+    timestamp: Option<u64>,
+) -> StdResult<SmartQuery<VotingPowerResponse>> {
+    SmartQuery::new(
+        gov_addr,
+        &GovQueryMsg::VotingPower {
+            address: address.into(),
+            timestamp,
+        },
+    )
+}
+
+/// ve token totals, at timestamp if set
+pub fn ve_state_query(
+    ve_addr: impl Into<String>,
+    timestamp: Option<u64>,
+) -> StdResult<SmartQuery<VeStateResponse>> {
+    SmartQuery::new(ve_addr, &VeQueryMsg::State { timestamp })
+}
+
 pub fn query_all_balances(deps: Deps, account_addr: Addr) -> StdResult<Vec<Coin>> {
     // load price form the oracle
     let all_balances: AllBalanceResponse =
@@ -31,27 +152,59 @@ pub fn query_token_balance(
     account_addr: Addr,
 ) -> StdResult<Uint256> {
     // load balance form the token contract
-    let res: Cw20BalanceResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: contract_addr.to_string(),
-        msg: to_binary(&Cw20QueryMsg::Balance {
-            address: account_addr.to_string(),
-        })?,
-    }))?;
+    let res = token_balance_query(contract_addr, account_addr)?.query(&deps.querier)?;
 
-    // load balance form the token contract
     Ok(res.balance.into())
 }
 
 pub fn query_supply(deps: Deps, contract_addr: Addr) -> StdResult<Uint256> {
     let token_info: TokenInfoResponse =
-        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: contract_addr.to_string(),
-            msg: to_binary(&Cw20QueryMsg::TokenInfo {})?,
-        }))?;
+        SmartQuery::new(contract_addr, &Cw20QueryMsg::TokenInfo {})?.query(&deps.querier)?;
 
     Ok(Uint256::from(token_info.total_supply.u128()))
 }
 
+pub fn query_exchange_rate(
+    deps: Deps,
+    money_market_addr: String,
+    block_height: u64,
+) -> StdResult<EpochStateResponse> {
+    exchange_rate_query(money_market_addr, block_height)?.query(&deps.querier)
+}
+
+/// Message depositing amount of stable_denom into anchor
+pub fn deposit_stable_msg(
+    anchor_contract: &Addr,
+    stable_denom: &str,
+    amount: Uint256,
+) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: anchor_contract.to_string(),
+        funds: vec![Coin {
+            denom: stable_denom.to_string(),
+            amount: amount.into(),
+        }],
+        msg: to_binary(&AnchorMsg::DepositStable {})?,
+    }))
+}
+
+/// Message redeeming aust_amount of aust from anchor
+pub fn redeem_stable_msg(
+    a_terra_contract: &Addr,
+    anchor_contract: &Addr,
+    aust_amount: Uint256,
+) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: a_terra_contract.to_string(),
+        funds: vec![],
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: anchor_contract.to_string(),
+            amount: aust_amount.into(),
+            msg: to_binary(&AnchorHookMsg::RedeemStable {})?,
+        })?,
+    }))
+}
+
 pub fn query_tax_rate(deps: Deps) -> StdResult<Decimal256> {
     let terra_querier = TerraQuerier::new(&deps.querier);
     Ok(terra_querier.query_tax_rate()?.rate.into())
@@ -0,0 +1,410 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+
+use cosmwasm_std::Uint128;
+
+use crate::lotto::{
+    BoostConfig, CharityDonation, ClaimDestination, ExecuteMsg, InstantiateMsg, LoyaltyConfig,
+    MissedLotteryPolicy, PayoutAsset, PayoutReveal, RedepositConfig, SponsorLockConfig,
+};
+
+pub const DEFAULT_OWNER: &str = "creator";
+pub const DEFAULT_STABLE_DENOM: &str = "uusd";
+pub const DEFAULT_ANCHOR: &str = "anchor";
+pub const DEFAULT_ATERRA: &str = "aterra-ust";
+pub const DEFAULT_ORACLE: &str = "oracle";
+
+pub const DEFAULT_TICKET_PRICE: u64 = 10_000_000; // 10 * 10^6
+pub const DEFAULT_LOTTERY_INTERVAL: u64 = 604800; // a week, in seconds
+pub const DEFAULT_BLOCK_TIME: u64 = 3600; // an hour, in seconds
+pub const DEFAULT_ROUND_DELTA: u64 = 10;
+pub const DEFAULT_FIRST_LOTTERY_TIME: u64 = 1595961494;
+pub const DEFAULT_MAX_HOLDERS: u8 = 10;
+pub const DEFAULT_MAX_TICKETS_PER_DEPOSITOR: u64 = 12000;
+
+pub fn default_prize_distribution() -> Vec<Decimal256> {
+    vec![
+        Decimal256::zero(),
+        Decimal256::zero(),
+        Decimal256::percent(5),
+        Decimal256::percent(15),
+        Decimal256::percent(25),
+        Decimal256::percent(35),
+        Decimal256::percent(20),
+    ]
+}
+
+pub fn default_glow_prize_buckets() -> Vec<Uint256> {
+    vec![
+        Uint256::zero(),
+        Uint256::zero(),
+        Uint256::from(10 * u128::pow(10, 6)),
+        Uint256::from(50 * u128::pow(10, 6)),
+        Uint256::from(100 * u128::pow(10, 6)),
+        Uint256::from(1000 * u128::pow(10, 6)),
+        Uint256::from(100000 * u128::pow(10, 6)),
+    ]
+}
+
+/// Encodes tickets, given as hex sequences, the way Deposit and Gift expect them
+pub fn encode_tickets<S: AsRef<str>>(tickets: &[S]) -> String {
+    let binary_data: Vec<u8> = tickets
+        .iter()
+        .flat_map(|ticket| {
+            // Sequences of an odd length are padded to a whole number of bytes
+            let ticket = ticket.as_ref();
+            let padded_ticket = if ticket.len() % 2 == 1 {
+                format!("{}0", ticket)
+            } else {
+                ticket.to_string()
+            };
+            hex::decode(padded_ticket).expect("tickets must be hex sequences")
+        })
+        .collect();
+
+    base64::encode(binary_data)
+}
+
+// Builder setters taking the value of each field
+macro_rules! setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            pub fn $field(mut self, $field: impl Into<$ty>) -> Self {
+                self.$field = $field.into();
+                self
+            }
+        )*
+    };
+}
+
+/// Builds the lotto InstantiateMsg, starting from the values used in the contract tests
+#[derive(Clone, Debug)]
+pub struct InstantiateMsgBuilder {
+    owner: String,
+    stable_denom: String,
+    anchor_contract: String,
+    aterra_contract: String,
+    oracle_contract: String,
+    lottery_interval: u64,
+    epoch_interval: u64,
+    block_time: u64,
+    round_delta: u64,
+    ticket_price: Uint256,
+    max_holders: u8,
+    prize_distribution: Vec<Decimal256>,
+    target_award: Uint256,
+    reserve_factor: Decimal256,
+    split_factor: Decimal256,
+    instant_withdrawal_fee: Decimal256,
+    unbonding_period: u64,
+    initial_operator_glow_emission_rate: Decimal256,
+    initial_sponsor_glow_emission_rate: Decimal256,
+    initial_lottery_execution: u64,
+    max_tickets_per_depositor: u64,
+    glow_prize_buckets: Vec<Uint256>,
+    lotto_winner_boost_config: Option<BoostConfig>,
+    ticket_length: Option<u8>,
+    min_matches_for_prize: Option<u8>,
+}
+
+impl Default for InstantiateMsgBuilder {
+    fn default() -> Self {
+        InstantiateMsgBuilder {
+            owner: DEFAULT_OWNER.to_string(),
+            stable_denom: DEFAULT_STABLE_DENOM.to_string(),
+            anchor_contract: DEFAULT_ANCHOR.to_string(),
+            aterra_contract: DEFAULT_ATERRA.to_string(),
+            oracle_contract: DEFAULT_ORACLE.to_string(),
+            lottery_interval: DEFAULT_LOTTERY_INTERVAL,
+            epoch_interval: 3 * DEFAULT_BLOCK_TIME,
+            block_time: DEFAULT_BLOCK_TIME,
+            round_delta: DEFAULT_ROUND_DELTA,
+            ticket_price: Uint256::from(DEFAULT_TICKET_PRICE),
+            max_holders: DEFAULT_MAX_HOLDERS,
+            prize_distribution: default_prize_distribution(),
+            target_award: Uint256::zero(),
+            reserve_factor: Decimal256::percent(5),
+            split_factor: Decimal256::percent(75),
+            instant_withdrawal_fee: Decimal256::percent(10),
+            unbonding_period: DEFAULT_LOTTERY_INTERVAL,
+            initial_operator_glow_emission_rate: Decimal256::zero(),
+            initial_sponsor_glow_emission_rate: Decimal256::zero(),
+            initial_lottery_execution: DEFAULT_FIRST_LOTTERY_TIME,
+            max_tickets_per_depositor: DEFAULT_MAX_TICKETS_PER_DEPOSITOR,
+            glow_prize_buckets: default_glow_prize_buckets(),
+            lotto_winner_boost_config: None,
+            ticket_length: None,
+            min_matches_for_prize: None,
+        }
+    }
+}
+
+impl InstantiateMsgBuilder {
+    setters! {
+        owner: String,
+        stable_denom: String,
+        anchor_contract: String,
+        aterra_contract: String,
+        oracle_contract: String,
+        lottery_interval: u64,
+        epoch_interval: u64,
+        block_time: u64,
+        round_delta: u64,
+        ticket_price: Uint256,
+        max_holders: u8,
+        prize_distribution: Vec<Decimal256>,
+        target_award: Uint256,
+        reserve_factor: Decimal256,
+        split_factor: Decimal256,
+        instant_withdrawal_fee: Decimal256,
+        unbonding_period: u64,
+        initial_operator_glow_emission_rate: Decimal256,
+        initial_sponsor_glow_emission_rate: Decimal256,
+        initial_lottery_execution: u64,
+        max_tickets_per_depositor: u64,
+        glow_prize_buckets: Vec<Uint256>,
+        lotto_winner_boost_config: Option<BoostConfig>,
+        ticket_length: Option<u8>,
+        min_matches_for_prize: Option<u8>,
+    }
+
+    pub fn build(self) -> InstantiateMsg {
+        InstantiateMsg {
+            owner: self.owner,
+            stable_denom: self.stable_denom,
+            anchor_contract: self.anchor_contract,
+            aterra_contract: self.aterra_contract,
+            oracle_contract: self.oracle_contract,
+            lottery_interval: self.lottery_interval,
+            epoch_interval: self.epoch_interval,
+            block_time: self.block_time,
+            round_delta: self.round_delta,
+            ticket_price: self.ticket_price,
+            max_holders: self.max_holders,
+            prize_distribution: self.prize_distribution,
+            target_award: self.target_award,
+            reserve_factor: self.reserve_factor,
+            split_factor: self.split_factor,
+            instant_withdrawal_fee: self.instant_withdrawal_fee,
+            unbonding_period: self.unbonding_period,
+            initial_operator_glow_emission_rate: self.initial_operator_glow_emission_rate,
+            initial_sponsor_glow_emission_rate: self.initial_sponsor_glow_emission_rate,
+            initial_lottery_execution: self.initial_lottery_execution,
+            max_tickets_per_depositor: self.max_tickets_per_depositor,
+            glow_prize_buckets: self.glow_prize_buckets,
+            lotto_winner_boost_config: self.lotto_winner_boost_config,
+            ticket_length: self.ticket_length,
+            min_matches_for_prize: self.min_matches_for_prize,
+        }
+    }
+}
+
+/// Builds ExecuteMsg::UpdateConfig, leaving every field not set unchanged
+#[derive(Clone, Debug, Default)]
+pub struct UpdateConfigBuilder {
+    owner: Option<String>,
+    oracle_addr: Option<String>,
+    unbonding_period: Option<u64>,
+    epoch_interval: Option<u64>,
+    max_holders: Option<u8>,
+    max_tickets_per_depositor: Option<u64>,
+    paused: Option<bool>,
+    lotto_winner_boost_config: Option<BoostConfig>,
+    operator_glow_emission_rate: Option<Decimal256>,
+    sponsor_glow_emission_rate: Option<Decimal256>,
+    min_deposit_amount: Option<Uint256>,
+    operator_reward_vesting_epochs: Option<u64>,
+    deposits_paused: Option<bool>,
+    withdrawals_paused: Option<bool>,
+    lottery_paused: Option<bool>,
+    claims_paused: Option<bool>,
+}
+
+impl UpdateConfigBuilder {
+    setters! {
+        owner: Option<String>,
+        oracle_addr: Option<String>,
+        unbonding_period: Option<u64>,
+        epoch_interval: Option<u64>,
+        max_holders: Option<u8>,
+        max_tickets_per_depositor: Option<u64>,
+        paused: Option<bool>,
+        lotto_winner_boost_config: Option<BoostConfig>,
+        operator_glow_emission_rate: Option<Decimal256>,
+        sponsor_glow_emission_rate: Option<Decimal256>,
+        min_deposit_amount: Option<Uint256>,
+        operator_reward_vesting_epochs: Option<u64>,
+        deposits_paused: Option<bool>,
+        withdrawals_paused: Option<bool>,
+        lottery_paused: Option<bool>,
+        claims_paused: Option<bool>,
+    }
+
+    pub fn build(self) -> ExecuteMsg {
+        ExecuteMsg::UpdateConfig {
+            owner: self.owner,
+            oracle_addr: self.oracle_addr,
+            unbonding_period: self.unbonding_period,
+            epoch_interval: self.epoch_interval,
+            max_holders: self.max_holders,
+            max_tickets_per_depositor: self.max_tickets_per_depositor,
+            paused: self.paused,
+            lotto_winner_boost_config: self.lotto_winner_boost_config,
+            operator_glow_emission_rate: self.operator_glow_emission_rate,
+            sponsor_glow_emission_rate: self.sponsor_glow_emission_rate,
+            min_deposit_amount: self.min_deposit_amount,
+            operator_reward_vesting_epochs: self.operator_reward_vesting_epochs,
+            deposits_paused: self.deposits_paused,
+            withdrawals_paused: self.withdrawals_paused,
+            lottery_paused: self.lottery_paused,
+            claims_paused: self.claims_paused,
+        }
+    }
+}
+
+/// Builds ExecuteMsg::UpdateLotteryConfig, leaving every field not set unchanged
+#[derive(Clone, Debug, Default)]
+pub struct UpdateLotteryConfigBuilder {
+    lottery_interval: Option<u64>,
+    block_time: Option<u64>,
+    ticket_price: Option<Uint256>,
+    round_delta: Option<u64>,
+    loyalty_config: Option<LoyaltyConfig>,
+    sponsor_lock_config: Option<SponsorLockConfig>,
+    ticket_eligibility_delay: Option<u64>,
+    missed_lottery_policy: Option<MissedLotteryPolicy>,
+    time_weighted_odds: Option<bool>,
+}
+
+impl UpdateLotteryConfigBuilder {
+    setters! {
+        lottery_interval: Option<u64>,
+        block_time: Option<u64>,
+        ticket_price: Option<Uint256>,
+        round_delta: Option<u64>,
+        loyalty_config: Option<LoyaltyConfig>,
+        sponsor_lock_config: Option<SponsorLockConfig>,
+        ticket_eligibility_delay: Option<u64>,
+        missed_lottery_policy: Option<MissedLotteryPolicy>,
+        time_weighted_odds: Option<bool>,
+    }
+
+    pub fn build(self) -> ExecuteMsg {
+        ExecuteMsg::UpdateLotteryConfig {
+            lottery_interval: self.lottery_interval,
+            block_time: self.block_time,
+            ticket_price: self.ticket_price,
+            round_delta: self.round_delta,
+            loyalty_config: self.loyalty_config,
+            sponsor_lock_config: self.sponsor_lock_config,
+            ticket_eligibility_delay: self.ticket_eligibility_delay,
+            missed_lottery_policy: self.missed_lottery_policy,
+            time_weighted_odds: self.time_weighted_odds,
+        }
+    }
+}
+
+/// Builds ExecuteMsg::Sponsor, a non award sponsorship unless set otherwise
+#[derive(Clone, Debug, Default)]
+pub struct SponsorBuilder {
+    award: Option<bool>,
+    prize_distribution: Option<Vec<Decimal256>>,
+    prize_template: Option<String>,
+    lock_duration: Option<u64>,
+}
+
+impl SponsorBuilder {
+    setters! {
+        award: Option<bool>,
+        prize_distribution: Option<Vec<Decimal256>>,
+        prize_template: Option<String>,
+        lock_duration: Option<u64>,
+    }
+
+    pub fn build(self) -> ExecuteMsg {
+        ExecuteMsg::Sponsor {
+            award: self.award,
+            prize_distribution: self.prize_distribution,
+            prize_template: self.prize_template,
+            lock_duration: self.lock_duration,
+        }
+    }
+}
+
+/// Builds ExecuteMsg::Withdraw, of the whole deposit through unbonding unless set otherwise
+#[derive(Clone, Debug, Default)]
+pub struct WithdrawBuilder {
+    amount: Option<Uint128>,
+    instant: Option<bool>,
+    tickets: Option<Vec<String>>,
+    recipient: Option<String>,
+}
+
+impl WithdrawBuilder {
+    setters! {
+        amount: Option<Uint128>,
+        instant: Option<bool>,
+        tickets: Option<Vec<String>>,
+        recipient: Option<String>,
+    }
+
+    pub fn build(self) -> ExecuteMsg {
+        ExecuteMsg::Withdraw {
+            amount: self.amount,
+            instant: self.instant,
+            tickets: self.tickets,
+            recipient: self.recipient,
+        }
+    }
+}
+
+/// Builds ExecuteMsg::ClaimLottery, paying the prizes of lottery_ids to the sender in UST
+#[derive(Clone, Debug)]
+pub struct ClaimLotteryBuilder {
+    lottery_ids: Vec<u64>,
+    redeposit: Option<RedepositConfig>,
+    destination: Option<ClaimDestination>,
+    donation: Option<CharityDonation>,
+    payout: Option<PayoutReveal>,
+    payout_asset: Option<PayoutAsset>,
+}
+
+impl ClaimLotteryBuilder {
+    pub fn new(lottery_ids: Vec<u64>) -> Self {
+        ClaimLotteryBuilder {
+            lottery_ids,
+            redeposit: None,
+            destination: None,
+            donation: None,
+            payout: None,
+            payout_asset: None,
+        }
+    }
+
+    setters! {
+        redeposit: Option<RedepositConfig>,
+        destination: Option<ClaimDestination>,
+        donation: Option<CharityDonation>,
+        payout: Option<PayoutReveal>,
+        payout_asset: Option<PayoutAsset>,
+    }
+
+    pub fn build(self) -> ExecuteMsg {
+        ExecuteMsg::ClaimLottery {
+            lottery_ids: self.lottery_ids,
+            redeposit: self.redeposit,
+            destination: self.destination,
+            donation: self.donation,
+            payout: self.payout,
+            payout_asset: self.payout_asset,
+        }
+    }
+}
+
+/// Deposit of tickets given as hex sequences
+pub fn deposit<S: AsRef<str>>(tickets: &[S], operator: Option<String>) -> ExecuteMsg {
+    ExecuteMsg::Deposit {
+        encoded_tickets: encode_tickets(tickets),
+        operator,
+        campaign_id: None,
+    }
+}
@@ -0,0 +1,143 @@
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Binary, Coin, ContractResult, Decimal, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, StdResult, SystemError, SystemResult, Uint128, WasmQuery,
+};
+use cw20::{BalanceResponse, Cw20QueryMsg};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute};
+
+/// Handler answering the smart queries sent to a mocked contract
+pub type ContractHandler = Box<dyn Fn(&Binary) -> StdResult<Binary>>;
+
+/// Mock dependencies whose querier answers for the contracts registered on it
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, MultiContractQuerier> {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: MultiContractQuerier::new(MockQuerier::new(&[(
+            MOCK_CONTRACT_ADDR,
+            contract_balance,
+        )])),
+    }
+}
+
+/// Querier mocking the Terra treasury, cw20 balances and any number of contracts,
+/// falling back to the cosmwasm MockQuerier for bank queries
+pub struct MultiContractQuerier {
+    base: MockQuerier<TerraQueryWrapper>,
+    contracts: HashMap<String, ContractHandler>,
+    // token contract -> holder -> balance
+    token_balances: HashMap<String, HashMap<String, Uint128>>,
+    tax_rate: Decimal,
+    tax_caps: HashMap<String, Uint128>,
+}
+
+impl Querier for MultiContractQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<TerraQueryWrapper> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl MultiContractQuerier {
+    pub fn new(base: MockQuerier<TerraQueryWrapper>) -> Self {
+        MultiContractQuerier {
+            base,
+            contracts: HashMap::new(),
+            token_balances: HashMap::new(),
+            tax_rate: Decimal::zero(),
+            tax_caps: HashMap::new(),
+        }
+    }
+
+    /// Answer the smart queries sent to contract_addr with handler. Queries
+    /// are deserialized into Q and responses serialized from R
+    pub fn with_contract<Q, R, F>(&mut self, contract_addr: impl Into<String>, handler: F)
+    where
+        Q: DeserializeOwned,
+        R: Serialize,
+        F: Fn(Q) -> StdResult<R> + 'static,
+    {
+        self.contracts.insert(
+            contract_addr.into(),
+            Box::new(move |msg| to_binary(&handler(from_binary(msg)?)?)),
+        );
+    }
+
+    /// Set the cw20 balances of holders, answering Balance queries to the token contract
+    pub fn with_token_balances(&mut self, token: impl Into<String>, balances: &[(&str, Uint128)]) {
+        self.token_balances.insert(
+            token.into(),
+            balances
+                .iter()
+                .map(|(holder, balance)| (holder.to_string(), *balance))
+                .collect(),
+        );
+    }
+
+    pub fn with_tax(&mut self, rate: Decimal, caps: &[(&str, Uint128)]) {
+        self.tax_rate = rate;
+        self.tax_caps = caps
+            .iter()
+            .map(|(denom, cap)| (denom.to_string(), *cap))
+            .collect();
+    }
+
+    pub fn update_balance(&mut self, addr: impl Into<String>, balance: Vec<Coin>) {
+        self.base.update_balance(addr, balance);
+    }
+
+    pub fn handle_query(&self, request: &QueryRequest<TerraQueryWrapper>) -> QuerierResult {
+        match request {
+            QueryRequest::Custom(TerraQueryWrapper {
+                route: TerraRoute::Treasury,
+                query_data,
+            }) => match query_data {
+                TerraQuery::TaxRate {} => {
+                    SystemResult::Ok(ContractResult::from(to_binary(&TaxRateResponse {
+                        rate: self.tax_rate,
+                    })))
+                }
+                TerraQuery::TaxCap { denom } => {
+                    SystemResult::Ok(ContractResult::from(to_binary(&TaxCapResponse {
+                        cap: self.tax_caps.get(denom).copied().unwrap_or_default(),
+                    })))
+                }
+                _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "treasury".to_string(),
+                }),
+            },
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                if let Some(balances) = self.token_balances.get(contract_addr) {
+                    if let Ok(Cw20QueryMsg::Balance { address }) = from_binary(msg) {
+                        let balance = balances.get(&address).copied().unwrap_or_default();
+                        return SystemResult::Ok(ContractResult::from(to_binary(
+                            &BalanceResponse { balance },
+                        )));
+                    }
+                }
+
+                match self.contracts.get(contract_addr) {
+                    Some(handler) => SystemResult::Ok(handler(msg).into()),
+                    None => SystemResult::Err(SystemError::NoSuchContract {
+                        addr: contract_addr.clone(),
+                    }),
+                }
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! Helpers for integrators and our own tests, behind the `sdk` feature: typed
+//! message builders with the defaults of the contract tests and a mock
+//! querier that answers for several contracts at once.
+
+pub mod lotto;
+pub mod mock_querier;
@@ -10,6 +10,7 @@ pub struct InstantiateMsg {
     pub glow_token: String,
     pub staking_token: String, // lp token of GLOW-UST pair contract
     pub distribution_schedule: Vec<(u64, u64, Uint128)>,
+    pub reward_denom: String, // native denom of the lotto reserve rewards (uusd)
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -24,7 +25,11 @@ pub enum ExecuteMsg {
     UpdateConfig {
         owner: Option<String>,
         distribution_schedule: Option<Vec<(u64, u64, Uint128)>>,
+        reward_depositors: Option<Vec<String>>,
     },
+    /// Distribute the attached reward_denom funds to stakers,
+    /// restricted to reward depositors (lotto, fee distributor)
+    DepositReward {},
     /// Owner operation to stop distribution on current staking contract
     /// and send remaining tokens to the new contract
     MigrateStaking {
@@ -43,6 +48,7 @@ pub enum Cw20HookMsg {
 pub struct MigrateMsg {
     pub owner: String,
     pub distribution_schedule: Vec<(u64, u64, Uint128)>,
+    pub reward_denom: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -65,6 +71,8 @@ pub struct ConfigResponse {
     pub glow_token: String,
     pub staking_token: String,
     pub distribution_schedule: Vec<(u64, u64, Uint128)>,
+    pub reward_denom: String,
+    pub reward_depositors: Vec<String>,
 }
 
 // We define a custom struct for each query response
@@ -73,6 +81,8 @@ pub struct StateResponse {
     pub last_distributed: u64,
     pub total_bond_amount: Uint128,
     pub global_reward_index: Decimal,
+    pub global_reserve_reward_index: Decimal,
+    pub undistributed_reserve_reward: Uint128,
 }
 
 // We define a custom struct for each query response
@@ -82,4 +92,6 @@ pub struct StakerInfoResponse {
     pub reward_index: Decimal,
     pub bond_amount: Uint128,
     pub pending_reward: Uint128,
+    pub reserve_reward_index: Decimal,
+    pub pending_reserve_reward: Uint128,
 }
@@ -1,8 +1,15 @@
+use crate::common::{Pagination, DEFAULT_LIMIT, MAX_LIMIT};
+use crate::merkle::{merkle_proof, merkle_root, verify_proof, winner_leaf, Hash};
 use crate::mock_querier::mock_dependencies;
-use crate::querier::{compute_tax, deduct_tax, query_tax_rate};
+use crate::querier::{
+    compute_tax, deduct_tax, exchange_rate_query, query_batch, query_tax_rate, token_balance_query,
+};
+use crate::sdk::mock_querier::mock_dependencies as mock_sdk_dependencies;
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::{Coin, Decimal, Uint128};
+use cosmwasm_std::{Addr, Coin, Decimal, Order, QuerierWrapper, Uint128};
+use cw_storage_plus::Bound;
+use moneymarket::market::{EpochStateResponse, QueryMsg as AnchorQueryMsg};
 
 #[test]
 fn tax_rate_querier() {
@@ -64,3 +71,114 @@ fn test_deduct_tax() {
         }
     );
 }
+
+#[test]
+fn batched_smart_queries() {
+    let mut deps = mock_sdk_dependencies(&[]);
+
+    deps.querier
+        .with_token_balances("aterra", &[("lotto", Uint128::from(1000u128))]);
+    deps.querier.with_contract("anchor", |_: AnchorQueryMsg| {
+        Ok(EpochStateResponse {
+            exchange_rate: Decimal256::percent(120),
+            aterra_supply: Uint256::from(1000000u64),
+        })
+    });
+
+    let querier = QuerierWrapper::new(&deps.querier);
+    let (balance, epoch_state) = query_batch(
+        &querier,
+        (
+            token_balance_query("aterra", "lotto").unwrap(),
+            exchange_rate_query("anchor", 12345).unwrap(),
+        ),
+    )
+    .unwrap();
+    assert_eq!(balance.balance, Uint128::from(1000u128));
+    assert_eq!(epoch_state.exchange_rate, Decimal256::percent(120));
+
+    // The batch fails with its first failing query
+    assert!(query_batch(&querier, (exchange_rate_query("unknown", 12345).unwrap(),)).is_err());
+}
+
+#[test]
+fn winners_merkle_proofs() {
+    let leaves: Vec<Hash> = (0..5u64)
+        .map(|i| {
+            winner_leaf(
+                &format!("addr000{}", i),
+                3,
+                Uint128::from(1_000u128 * (i as u128 + 1)),
+                Uint128::from(10u128),
+            )
+        })
+        .collect();
+    let root = merkle_root(&leaves);
+
+    // Every leaf, including the unpaired last one, proves against the root
+    for (index, leaf) in leaves.iter().enumerate() {
+        let proof = merkle_proof(&leaves, index);
+        assert!(verify_proof(leaf, &proof, &root));
+    }
+
+    // A leaf with a different prize does not
+    let forged = winner_leaf(
+        "addr0000",
+        3,
+        Uint128::from(2_000u128),
+        Uint128::from(10u128),
+    );
+    assert!(!verify_proof(&forged, &merkle_proof(&leaves, 0), &root));
+
+    // The single leaf of a lottery with one winner is the root
+    assert_eq!(merkle_root(&leaves[..1]), leaves[0]);
+    assert!(merkle_proof(&leaves[..1], 0).is_empty());
+
+    // No winners commits to the zero hash
+    assert_eq!(merkle_root(&[]), [0u8; 32]);
+}
+
+#[test]
+fn pagination_bounds_and_limits() {
+    // The limit defaults to DEFAULT_LIMIT and is clamped to MAX_LIMIT
+    assert_eq!(
+        Pagination::<u64>::new(None, None).limit,
+        DEFAULT_LIMIT as usize
+    );
+    assert_eq!(Pagination::<u64>::new(None, Some(5)).limit, 5);
+    assert_eq!(
+        Pagination::<u64>::new(None, Some(1_000)).limit,
+        MAX_LIMIT as usize
+    );
+    assert_eq!(
+        Pagination::<u64>::with_limits(None, None, 100, 1_000).limit,
+        100
+    );
+    assert_eq!(
+        Pagination::<u64>::uncapped(None, None).limit,
+        DEFAULT_LIMIT as usize
+    );
+    assert_eq!(Pagination::<u64>::uncapped(None, Some(1_000)).limit, 1_000);
+
+    // Keys are encoded as stored, u64 big endian like U64Key
+    let pagination = Pagination::new(Some(7u64), None);
+    let seven = 7u64.to_be_bytes().to_vec();
+    assert!(matches!(pagination.start_bound(), Some(Bound::Exclusive(key)) if key == seven));
+    let (min, max) = pagination.range_bounds(Order::Descending);
+    assert!(min.is_none());
+    assert!(matches!(max, Some(Bound::Exclusive(key)) if key == seven));
+
+    // Raw ranges start at the first key after start_after when ascending
+    let pagination = Pagination::new(Some(Addr::unchecked("addr0000")), None);
+    let mut first_after = b"addr0000".to_vec();
+    first_after.push(0);
+    assert_eq!(
+        pagination.raw_range_bounds(Order::Ascending),
+        (Some(first_after), None)
+    );
+    assert_eq!(
+        pagination.raw_range_bounds(Order::Descending),
+        (None, Some(b"addr0000".to_vec()))
+    );
+    assert!(Pagination::<Addr>::new(None, None).start_bound().is_none());
+}